@@ -0,0 +1,153 @@
+//! Benchmarks of the desktop side of the data pipeline on synthetic data:
+//! parsing session JSONL (10k/100k/1M messages), native ingestion of a
+//! config dir (10k/100k messages), combining account dashboards and response
+//! cache queries (by history length).
+//!
+//! `cargo bench --features bench`; `cargo bench --features bench -- parse/10000`
+//! runs one case. The Python ingestion and aggregation are measured on the
+//! user's machine by the `run_benchmark` command.
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::{json, Value};
+
+use command_center_lib::internals::{
+    cache_key, cache_lookup, cache_store, combine_account_dashboards, config_dirs, scan, stream_reader, PriceTable,
+    RawFormat, StreamOptions, StreamStats,
+};
+
+const MESSAGE_COUNTS: [usize; 3] = [10_000, 100_000, 1_000_000];
+const SCAN_MESSAGE_COUNTS: [usize; 2] = [10_000, 100_000];
+/// Thread caps of `ingest_scan`.
+const SCAN_WORKERS: [usize; 2] = [1, 4];
+/// Messages per session file written for `ingest_scan`.
+const SESSION_MESSAGES: usize = 200;
+const HISTORY_DAYS: [usize; 3] = [90, 365, 3650];
+const MODELS: [&str; 3] = ["claude-sonnet-4-5-20250929", "claude-opus-4-5-20251101", "claude-haiku-4-5-20251001"];
+
+/// Session log of `messages` assistant messages, like those Claude Code writes.
+fn session_log(messages: usize) -> Vec<u8> {
+    let mut log = Vec::with_capacity(messages * 420);
+    for i in 0..messages {
+        let record = json!({
+            "type": "assistant",
+            "sessionId": "s1",
+            "requestId": format!("req_{}", i),
+            "timestamp": format!("2025-12-{:02}T{:02}:{:02}:00.000Z", 1 + i % 28, i % 24, i % 60),
+            "costUSD": (i % 1000) as f64 / 10_000.0,
+            "message": {
+                "id": format!("msg_{}", i),
+                "role": "assistant",
+                "model": MODELS[i % MODELS.len()],
+                "content": [{"type": "text", "text": "Synthetic benchmark response"}],
+                "usage": {"input_tokens": i % 4000, "output_tokens": i % 2000, "cache_read_input_tokens": i % 20_000},
+            },
+        });
+        serde_json::to_writer(&mut log, &record).expect("serializable record");
+        log.push(b'\n');
+    }
+    log
+}
+
+fn daily(days: usize, scale: usize) -> Vec<Value> {
+    (0..days)
+        .map(|day| {
+            json!({
+                "date": format!("{:04}-{:02}-{:02}", 2016 + day / 365, 1 + day % 365 / 31, 1 + day % 28),
+                "messages": (day * scale) % 500,
+                "tokens": (day * scale) % 500 * 1_200,
+                "cost": ((day * scale) % 500) as f64 / 40.0,
+            })
+        })
+        .collect()
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    group.sample_size(10);
+    for messages in MESSAGE_COUNTS {
+        let log = session_log(messages);
+        group.throughput(Throughput::Elements(messages as u64));
+        for (name, format) in [("json", RawFormat::Json), ("jsonl", RawFormat::Jsonl)] {
+            let options = StreamOptions { session_id: "s1", format, redact: false, chunk_bytes: 256 * 1024 };
+            group.bench_with_input(BenchmarkId::new(name, messages), &log, |b, log| {
+                b.iter(|| {
+                    let mut stats = StreamStats::default();
+                    stream_reader(log.as_slice(), "bench.jsonl", &options, &mut stats, &mut |event| {
+                        black_box(event);
+                        Ok(())
+                    })
+                    .expect("in-memory stream");
+                    stats
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_ingest_scan(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ingest_scan");
+    group.sample_size(10);
+    for messages in SCAN_MESSAGE_COUNTS {
+        let home = std::env::temp_dir().join(format!("cc-bench-ingest-{}-{}", messages, std::process::id()));
+        let log = session_log(messages);
+        for (i, session) in log.split_inclusive(|byte| *byte == b'\n').collect::<Vec<_>>().chunks(SESSION_MESSAGES).enumerate() {
+            let dir = home.join(".claude").join("projects").join(format!("-home-bench-p{}", i % 8));
+            std::fs::create_dir_all(&dir).expect("bench config dir");
+            std::fs::write(dir.join(format!("s{}.jsonl", i)), session.concat()).expect("bench session file");
+        }
+        let dirs = config_dirs(&home, None, &Value::Null);
+        let prices = PriceTable::default();
+        group.throughput(Throughput::Elements(messages as u64));
+        for workers in SCAN_WORKERS {
+            let id = BenchmarkId::new(format!("{}_threads", workers), messages);
+            group.bench_with_input(id, &dirs, |b, dirs| b.iter(|| scan(dirs, &prices, workers)));
+        }
+        let _ = std::fs::remove_dir_all(&home);
+    }
+    group.finish();
+}
+
+fn bench_combine_accounts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("combine_accounts");
+    for days in HISTORY_DAYS {
+        let accounts: Vec<Value> = (0..8)
+            .map(|i| {
+                json!({
+                    "account": {"email": format!("{}@x.com", i), "snapshots": 10},
+                    "totals": {"messages": 1000, "sessions": 50, "tokens": 1_200_000, "cost": 25.0},
+                    "daily": daily(days, i + 1),
+                })
+            })
+            .collect();
+        group.throughput(Throughput::Elements((days * accounts.len()) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(days), &accounts, |b, accounts| {
+            b.iter(|| combine_account_dashboards("2016-01-01", "2025-12-31", accounts.clone()))
+        });
+    }
+    group.finish();
+}
+
+fn bench_cache(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cache");
+    for days in HISTORY_DAYS {
+        let response = json!({"totals": {"messages": 1000}, "timeline": {"daily": daily(days, 1)}});
+        let args = ["dashboard", "--from", "2016-01-01", "--to", "2025-12-31", "--granularity", "day"];
+        let key = cache_key(&args, &[]).expect("dashboard responses are cached");
+        let (_, generation) = cache_lookup(&key);
+        cache_store(key.clone(), &response, generation);
+
+        group.bench_with_input(BenchmarkId::new("hit", days), &key, |b, key| b.iter(|| cache_lookup(key)));
+        group.bench_with_input(BenchmarkId::new("store", days), &response, |b, response| {
+            b.iter(|| {
+                let (_, generation) = cache_lookup("missing");
+                cache_store(key.clone(), response, generation)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_ingest_scan, bench_combine_accounts, bench_cache);
+criterion_main!(benches);
@@ -0,0 +1,14 @@
+CREATE TABLE cc_usage_events (
+    id INTEGER PRIMARY KEY,
+    email TEXT,
+    captured_at_local TEXT,
+    current_session_used_pct INTEGER,
+    current_week_used_pct REAL
+);
+INSERT INTO cc_usage_events (email, captured_at_local, current_session_used_pct, current_week_used_pct) VALUES
+    ('me@work.com', '2025-06-07T23:00:00+00:00', 99, 80.0),
+    ('me@work.com', '2025-06-09T11:30:00+00:00', 42, 35.5),
+    ('me@work.com', '2025-06-09T15:00:00+00:00', 91, 41.0),
+    ('me@work.com', '2025-06-10T12:15:00+00:00', 95, NULL),
+    ('me@home.com', '2025-06-12T08:00:00+00:00', NULL, 12.25),
+    ('', '2025-06-12T09:00:00+00:00', 50, 50.0);
@@ -0,0 +1,13 @@
+//! Combined account dashboards from arbitrary JSON account payloads: must
+//! never panic, whatever fields are missing, mistyped or huge.
+#![no_main]
+
+use command_center_lib::internals::combine_account_dashboards;
+use libfuzzer_sys::fuzz_target;
+use serde_json::Value;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(Value::Array(accounts)) = serde_json::from_slice::<Value>(data) else { return };
+    let combined = combine_account_dashboards("2025-01-01", "2025-01-31", accounts);
+    assert!(combined["daily"].is_array());
+});
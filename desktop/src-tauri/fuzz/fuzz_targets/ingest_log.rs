@@ -0,0 +1,38 @@
+//! Session log decoding and scanning on arbitrary log bytes: must never panic,
+//! must skip exactly the lines that are not JSON records and must count each
+//! message once, however often it is logged.
+#![no_main]
+
+use std::collections::HashSet;
+
+use command_center_lib::internals::{parse_line, parse_log, ConfigDirSummary, PriceTable, Scan};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let prices = PriceTable::default();
+    let log = parse_log(data, &prices).expect("in-memory log");
+
+    let (mut messages, mut skipped) = (0, 0);
+    for line in data.split(|byte| *byte == b'\n') {
+        let text = String::from_utf8_lossy(line);
+        let line = text.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match parse_line(line) {
+            Ok(Some(_)) => messages += 1,
+            Ok(None) => {}
+            Err(_) => skipped += 1,
+        }
+    }
+    assert_eq!((log.entries.len(), log.skipped_lines), (messages, skipped));
+
+    let keys: HashSet<_> = log.entries.iter().map(|(entry, _)| entry.key.clone()).collect();
+    let (mut scan, mut seen, mut dir_summary) = (Scan::default(), HashSet::new(), ConfigDirSummary::default());
+    scan.add("app", log, &mut seen, &mut dir_summary);
+    scan.add("copy", parse_log(data, &prices).expect("in-memory log"), &mut seen, &mut dir_summary);
+    assert_eq!(dir_summary.totals.messages, keys.len() as u64);
+    assert_eq!(scan.projects.values().map(|project| project.totals.messages).sum::<u64>(), keys.len() as u64);
+    assert!(!scan.projects.contains_key("copy"));
+    assert_eq!(scan.skipped_lines, 2 * skipped);
+});
@@ -0,0 +1,20 @@
+//! Raw session streaming on arbitrary log bytes: must never fail or panic,
+//! and must account for every byte and line.
+#![no_main]
+
+use command_center_lib::internals::{stream_reader, RawFormat, StreamOptions, StreamStats};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&flags, log)) = data.split_first() else { return };
+    let options = StreamOptions {
+        session_id: "s1",
+        format: if flags & 1 == 0 { RawFormat::Jsonl } else { RawFormat::Json },
+        redact: flags & 2 != 0,
+        chunk_bytes: usize::from(flags >> 2) + 1,
+    };
+    let mut stats = StreamStats::default();
+    stream_reader(log, "fuzz.jsonl", &options, &mut stats, &mut |_| Ok(())).expect("in-memory stream");
+    assert_eq!(stats.bytes, log.len() as u64);
+    assert!(stats.skipped_lines <= stats.lines);
+});
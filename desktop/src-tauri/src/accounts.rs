@@ -0,0 +1,228 @@
+/// Combined multi-account dashboard
+///
+/// `get_combined_dashboard` queries the `account-dashboard` payload of each
+/// usage account concurrently; this module merges them into combined totals,
+/// a combined daily series and a per-account comparison matrix shaped like
+/// the one of `compare_usage_accounts` (`{key, label, values, max_email}`).
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+
+/// Maximum number of accounts accepted by `get_combined_dashboard`.
+pub const MAX_COMBINED_ACCOUNTS: usize = 8;
+
+/// Additive message totals of each account payload.
+const SUMMED_TOTALS: &[&str] = &["messages", "sessions", "tokens", "cost"];
+
+/// Comparison matrix rows: (section of the account payload, key, label).
+const COMBINED_METRICS: &[(&str, &str, &str)] = &[
+    ("totals", "cost", "Cost (USD)"),
+    ("totals", "tokens", "Tokens"),
+    ("totals", "messages", "Messages"),
+    ("totals", "sessions", "Sessions"),
+    ("account", "snapshots", "Snapshots"),
+    ("account", "peak_session_used_pct", "Peak session usage %"),
+    ("account", "peak_week_used_pct", "Peak weekly usage %"),
+    ("account", "session_near_limit", "Snapshots near session limit"),
+];
+
+fn email(account: &Value) -> &str {
+    account["account"]["email"].as_str().unwrap_or_default()
+}
+
+/// Add `value` to `total`; integer sums that would overflow continue as floats.
+fn add(total: &mut Value, value: &Value) {
+    *total = match (total.as_i64(), value.as_i64()) {
+        (Some(a), Some(b)) if a.checked_add(b).is_some() => Value::from(a + b),
+        _ => {
+            let sum = total.as_f64().unwrap_or(0.0) + value.as_f64().unwrap_or(0.0);
+            Value::from((sum * 10_000.0).round() / 10_000.0)
+        }
+    };
+}
+
+/// Merge per-account payloads (in the requested order) into one response:
+/// range, accounts, totals (with `share` of cost per account), daily and metrics.
+pub fn combine_account_dashboards(from: &str, to: &str, accounts: Vec<Value>) -> Value {
+    let mut totals: Map<String, Value> = SUMMED_TOTALS
+        .iter()
+        .map(|key| (key.to_string(), Value::from(0)))
+        .collect();
+    let mut daily: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+
+    for account in &accounts {
+        for key in SUMMED_TOTALS {
+            add(totals.get_mut(*key).expect("summed key"), &account["totals"][*key]);
+        }
+        for day in account["daily"].as_array().into_iter().flatten() {
+            let Some(date) = day["date"].as_str() else { continue };
+            let entry = daily.entry(date.to_string()).or_insert_with(|| {
+                let mut entry = Map::new();
+                entry.insert("date".to_string(), Value::from(date));
+                entry.insert("by_account".to_string(), json!({}));
+                entry
+            });
+            for key in ["messages", "tokens", "cost"] {
+                add(entry.entry(key).or_insert(Value::from(0)), &day[key]);
+            }
+            entry["by_account"][email(account)] = day["cost"].clone();
+        }
+    }
+
+    let total_cost = totals["cost"].as_f64().unwrap_or(0.0);
+    let share: Map<String, Value> = accounts
+        .iter()
+        .map(|account| {
+            let cost = account["totals"]["cost"].as_f64().unwrap_or(0.0);
+            let share = if total_cost > 0.0 { (cost / total_cost * 1000.0).round() / 10.0 } else { 0.0 };
+            (email(account).to_string(), Value::from(share))
+        })
+        .collect();
+    totals.insert("accounts".to_string(), Value::from(accounts.len()));
+    totals.insert("cost_share_pct".to_string(), Value::Object(share));
+
+    let metrics: Vec<Value> = COMBINED_METRICS
+        .iter()
+        .map(|(section, key, label)| {
+            let values: Map<String, Value> = accounts
+                .iter()
+                .map(|account| (email(account).to_string(), account[*section][*key].clone()))
+                .collect();
+            let max_email = values
+                .iter()
+                .filter_map(|(email, value)| value.as_f64().map(|v| (email, v)))
+                .fold(None::<(&String, f64)>, |best, (email, v)| match best {
+                    Some((_, best_v)) if best_v >= v => best,
+                    _ => Some((email, v)),
+                })
+                .map(|(email, _)| email.clone());
+            json!({"key": key, "label": label, "values": values, "max_email": max_email})
+        })
+        .collect();
+
+    json!({
+        "range": {"from": from, "to": to},
+        "accounts": accounts,
+        "totals": totals,
+        "daily": daily.into_values().collect::<Vec<_>>(),
+        "metrics": metrics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn account(email: &str, cost: f64, messages: i64, daily: Value) -> Value {
+        json!({
+            "account": {"email": email, "snapshots": 3, "peak_session_used_pct": null},
+            "config_dirs": [],
+            "totals": {"messages": messages, "sessions": 1, "tokens": messages * 100, "cost": cost},
+            "daily": daily,
+        })
+    }
+
+    #[test]
+    fn test_combine_account_dashboards() {
+        let combined = combine_account_dashboards(
+            "2025-03-01",
+            "2025-03-31",
+            vec![
+                account("a@x.com", 1.5, 10, json!([{"date": "2025-03-02", "messages": 10, "tokens": 1000, "cost": 1.5}])),
+                account(
+                    "b@x.com",
+                    4.5,
+                    20,
+                    json!([
+                        {"date": "2025-03-02", "messages": 5, "tokens": 500, "cost": 0.5},
+                        {"date": "2025-03-01", "messages": 15, "tokens": 1500, "cost": 4.0}
+                    ]),
+                ),
+            ],
+        );
+
+        assert_eq!(combined["totals"]["messages"], 30);
+        assert_eq!(combined["totals"]["cost"], 6.0);
+        assert_eq!(combined["totals"]["cost_share_pct"]["b@x.com"], 75.0);
+        assert_eq!(combined["daily"][0]["date"], "2025-03-01");
+        assert_eq!(combined["daily"][1]["messages"], 15);
+        assert_eq!(combined["daily"][1]["by_account"]["a@x.com"], 1.5);
+
+        let cost = &combined["metrics"][0];
+        assert_eq!(cost["values"]["a@x.com"], 1.5);
+        assert_eq!(cost["max_email"], "b@x.com");
+        // Ties keep the first account; all-null rows have no maximum
+        assert_eq!(combined["metrics"][3]["max_email"], "a@x.com");
+        assert_eq!(combined["metrics"][5]["max_email"], Value::Null);
+    }
+
+    #[test]
+    fn test_integer_overflow_continues_as_float() {
+        let mut total = Value::from(i64::MAX);
+        add(&mut total, &Value::from(1));
+        assert_eq!(total.as_f64(), Some(i64::MAX as f64 + 1.0));
+    }
+
+    /// Daily entries of one account: dates in a short window (so accounts
+    /// overlap), possibly missing fields or dates.
+    fn day() -> impl Strategy<Value = Value> {
+        (prop::option::of(1u32..=9), 0i64..1_000_000, 0u32..100_000).prop_map(|(day, messages, cents)| match day {
+            Some(day) => json!({"date": format!("2025-03-0{}", day), "messages": messages, "tokens": messages * 7, "cost": f64::from(cents) / 100.0}),
+            None => json!({"messages": messages}),
+        })
+    }
+
+    fn accounts() -> impl Strategy<Value = Vec<Value>> {
+        prop::collection::vec((0i64..1_000_000, 0u32..100_000, prop::collection::vec(day(), 0..8)), 0..=MAX_COMBINED_ACCOUNTS).prop_map(
+            |accounts| {
+                accounts
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (messages, cents, daily))| account(&format!("{}@x.com", i), f64::from(cents) / 100.0, messages, Value::from(daily)))
+                    .collect()
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn prop_totals_are_sums(accounts in accounts()) {
+            let combined = combine_account_dashboards("2025-03-01", "2025-03-31", accounts.clone());
+            let messages: i64 = accounts.iter().map(|a| a["totals"]["messages"].as_i64().unwrap()).sum();
+            let cost: f64 = accounts.iter().map(|a| a["totals"]["cost"].as_f64().unwrap()).sum();
+            prop_assert_eq!(combined["totals"]["messages"].as_i64(), Some(messages));
+            prop_assert!((combined["totals"]["cost"].as_f64().unwrap() - cost).abs() < 0.01);
+            prop_assert_eq!(combined["totals"]["accounts"].as_u64(), Some(accounts.len() as u64));
+
+            let shares: f64 = combined["totals"]["cost_share_pct"].as_object().unwrap().values().filter_map(Value::as_f64).sum();
+            prop_assert!(shares == 0.0 || (shares - 100.0).abs() <= 0.1 * accounts.len() as f64);
+        }
+
+        #[test]
+        fn prop_daily_is_sorted_and_unique(accounts in accounts()) {
+            let combined = combine_account_dashboards("2025-03-01", "2025-03-31", accounts.clone());
+            let dates: Vec<&str> = combined["daily"].as_array().unwrap().iter().map(|d| d["date"].as_str().unwrap()).collect();
+            prop_assert!(dates.windows(2).all(|pair| pair[0] < pair[1]));
+
+            let dated_messages: i64 = accounts
+                .iter()
+                .flat_map(|a| a["daily"].as_array().unwrap())
+                .filter(|d| d["date"].is_string())
+                .map(|d| d["messages"].as_i64().unwrap())
+                .sum();
+            let combined_messages: i64 = combined["daily"].as_array().unwrap().iter().map(|d| d["messages"].as_i64().unwrap()).sum();
+            prop_assert_eq!(combined_messages, dated_messages);
+        }
+
+        #[test]
+        fn prop_large_counts_do_not_panic(a in any::<i64>(), b in any::<i64>()) {
+            let mut total = Value::from(a);
+            add(&mut total, &Value::from(b));
+            match a.checked_add(b) {
+                Some(sum) => prop_assert_eq!(total.as_i64(), Some(sum)),
+                None => prop_assert!(total.is_f64()),
+            }
+        }
+    }
+}
@@ -0,0 +1,1137 @@
+/// Native aggregation of the dashboard bundle
+///
+/// Builds the `dashboard` payload (totals, trends, daily activity, timeline,
+/// heatmap, model distribution, hourly profile and recent sessions) from the
+/// desktop's SQLite store (`store`) with the keys, ordering and rounding of the
+/// Python `get_dashboard_bundle`, so the frontend renders either one. The
+/// per-day `daily` table answers day, week and month questions; only hourly
+/// views and sessions read `messages`.
+///
+/// `backend::NativeBackend` answers `dashboard` here once the store has indexed
+/// session logs, or when no Python backend can start (never in demo data mode,
+/// whose data only the Python backend has). With `--refresh=1` the store
+/// indexes changed logs first. Costs and `api_equivalent_cost` are priced like
+/// the Python backend's (see `pricing`); subscription costs and
+/// `usage_accounts` come from the account metadata and cc_usage snapshots
+/// (`usage_accounts`), and derived metrics are evaluated by `derived_metrics`.
+/// `fixtures/parity` holds a bundle of the Python backend the native one must
+/// equal field for field.
+///
+/// `meta.source` is `"native"`. The calendar overlay, workspaces, plans,
+/// config dir bindings and project metadata are read from the same JSON files
+/// as the Python backend.
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, Local, NaiveDate, SecondsFormat, TimeZone};
+use rusqlite::{params, Connection};
+use serde_json::{json, Map, Value};
+
+use crate::backend::ApiRequest;
+use crate::derived_metrics;
+use crate::error::CommandError;
+use crate::ingest::{self, CONFIG_BINDINGS_FILE, PROJECTS_FILE};
+use crate::settings::{db_dir, home_dir};
+use crate::store::{self, Store};
+use crate::usage_accounts;
+
+/// Subcommands `answer` builds from the store.
+pub const NATIVE_SUBCOMMANDS: &[&str] = &["dashboard"];
+
+/// Dashboard sections, in the order `meta.sections` lists them.
+pub const SECTIONS: &[&str] = &["totals", "timeline", "models", "hourly", "sessions"];
+
+pub const GRANULARITIES: &[&str] = &["month", "week", "day", "hour"];
+
+pub const CALENDAR_FILE: &str = "command-center-calendar.json";
+pub const WORKSPACES_FILE: &str = "command-center-workspaces.json";
+
+/// Sessions in `recent_sessions` (most expensive first).
+const RECENT_SESSIONS: i64 = 50;
+
+/// Days of the heatmap, ending today.
+const HEATMAP_DAYS: i64 = 365;
+
+/// Project filter of the queries below: `?3` is a JSON array of project IDs, or null for all.
+const SCOPE: &str = "(?3 IS NULL OR project_id IN (SELECT value FROM json_each(?3)))";
+
+const TOKENS: &str = "input_tokens + output_tokens + cache_read_tokens + cache_write_tokens";
+
+/// Local hour of a message.
+const LOCAL_HOUR: &str = "strftime('%H', epoch_ms / 1000, 'unixepoch', 'localtime')";
+
+/// Period of a row of `granularity`: `YYYY-MM`, `YYYY-Www` (Monday weeks),
+/// `YYYY-MM-DD` or `YYYY-MM-DD HH` (of `messages` only).
+fn period(granularity: &str) -> String {
+    match granularity {
+        "hour" => format!("date || ' ' || {}", LOCAL_HOUR),
+        "month" => "substr(date, 1, 7)".to_string(),
+        "week" => "strftime('%Y-W%W', date)".to_string(),
+        _ => "date".to_string(),
+    }
+}
+
+/// Display name of a model ID like the Python `format_model_name`: without the
+/// "claude-" prefix and a release date suffix, and a family with its version
+/// before or after it as e.g. "Sonnet 4.5" ("claude-3-5-haiku-20241022" -> "Haiku 3.5").
+pub fn display_name(model: &str) -> String {
+    if model.is_empty() {
+        return "Unknown".to_string();
+    }
+    let is_number = |part: &str| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit());
+    let mut name = model.replace("claude-", "");
+    if let Some((rest, date)) = name.rsplit_once('-') {
+        if date.len() == 8 && is_number(date) {
+            name = rest.to_string();
+        }
+    }
+
+    let parts: Vec<&str> = name.split('-').collect();
+    let family = parts.iter().position(|part| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_lowercase()));
+    let Some(family) = family else {
+        return name;
+    };
+    let (before, after) = (&parts[..family], &parts[family + 1..]);
+    if before.is_empty() == after.is_empty() || !before.iter().chain(after).all(|part| is_number(part)) {
+        return name;
+    }
+    let version = if before.is_empty() { after } else { before }.join(".");
+    format!("{}{} {}", parts[family][..1].to_uppercase(), &parts[family][1..], version)
+}
+
+/// `value` rounded to `digits` decimals like Python's `round`: halves of the
+/// exact binary value go to the even digit (`round(12.25, 1)` is 12.2).
+pub(crate) fn round(value: f64, digits: i32) -> f64 {
+    match digits {
+        // Python keeps values with more digits than a double has and zeroes the others
+        _ if !value.is_finite() || digits > 323 => value,
+        _ if digits < -308 => 0.0 * value,
+        0.. => format!("{:.*}", digits as usize, value).parse().unwrap_or(value),
+        _ => {
+            let factor = 10f64.powi(-digits);
+            (value / factor).round_ties_even() * factor
+        }
+    }
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, CommandError> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| CommandError::invalid_argument(format!("Invalid date: {} (expected YYYY-MM-DD)", date)))
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Dates from `from` through `to` (empty if `from` is later).
+fn days(from: NaiveDate, to: NaiveDate) -> impl Iterator<Item = NaiveDate> {
+    from.iter_days().take_while(move |day| *day <= to)
+}
+
+/// Local ISO timestamp of epoch milliseconds, like the Python `timestamp_local`.
+fn local_time(epoch_ms: i64) -> Option<String> {
+    Local
+        .timestamp_millis_opt(epoch_ms)
+        .single()
+        .map(|time| time.to_rfc3339_opts(SecondsFormat::AutoSi, false))
+}
+
+/// Same-length range just before `from`..`to`.
+fn previous_period(from: NaiveDate, to: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let length = (to - from).num_days() + 1;
+    let end = from - Duration::days(1);
+    (end - Duration::days(length - 1), end)
+}
+
+fn trend(current: f64, previous: f64) -> f64 {
+    if previous == 0.0 {
+        return 0.0;
+    }
+    round((current - previous) / previous * 100.0, 1)
+}
+
+/// Holiday and vacation periods (`set_calendar_overlay`): `[{from, to, kind, label}]`.
+struct Calendar {
+    periods: Vec<Map<String, Value>>,
+}
+
+impl Calendar {
+    fn load(db: &Path) -> Self {
+        let loaded = ingest::read_json(&db.join(CALENDAR_FILE));
+        let periods = loaded["periods"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|period| period.as_object().cloned())
+            .filter(|period| ["from", "to"].iter().all(|key| period.get(*key).is_some_and(Value::is_string)))
+            .collect();
+        Self { periods }
+    }
+
+    fn bounds(period: &Map<String, Value>) -> (&str, &str) {
+        (period["from"].as_str().unwrap_or_default(), period["to"].as_str().unwrap_or_default())
+    }
+
+    /// Days off, optionally limited to a range.
+    fn excluded_days(&self, range: Option<(&str, &str)>) -> BTreeSet<String> {
+        let mut excluded = BTreeSet::new();
+        for period in &self.periods {
+            let (mut start, mut end) = Self::bounds(period);
+            if let Some((from, to)) = range {
+                start = start.max(from);
+                end = end.min(to);
+            }
+            if let (Ok(start), Ok(end)) = (parse_date(start), parse_date(end)) {
+                excluded.extend(days(start, end).map(format_date));
+            }
+        }
+        excluded
+    }
+
+    /// Periods overlapping a range, clipped to it.
+    fn overlay(&self, from: &str, to: &str) -> Vec<Value> {
+        self.periods
+            .iter()
+            .filter(|period| Self::bounds(period).0 <= to && Self::bounds(period).1 >= from)
+            .map(|period| {
+                let (start, end) = Self::bounds(period);
+                let mut clipped = period.clone();
+                clipped.insert("from".to_string(), Value::from(start.max(from)));
+                clipped.insert("to".to_string(), Value::from(end.min(to)));
+                Value::Object(clipped)
+            })
+            .collect()
+    }
+}
+
+/// Longest and current run of active days; days off neither extend nor break a run.
+fn streaks(activity: &BTreeMap<String, i64>, excluded: &BTreeSet<String>, today: NaiveDate) -> (i64, i64) {
+    let active: BTreeSet<NaiveDate> = activity
+        .keys()
+        .filter(|day| !excluded.contains(*day))
+        .filter_map(|day| parse_date(day).ok())
+        .collect();
+    if active.is_empty() {
+        return (0, 0);
+    }
+    let off = |day: NaiveDate| excluded.contains(&format_date(day));
+
+    let (mut longest, mut run, mut previous) = (0, 0, None::<NaiveDate>);
+    for &day in &active {
+        run = match previous {
+            Some(previous) if (1..(day - previous).num_days()).all(|gap| off(previous + Duration::days(gap))) => {
+                run + 1
+            }
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+
+    // Today may still be empty or a day off: the current run can end yesterday
+    let mut day = today;
+    while off(day) {
+        day -= Duration::days(1);
+    }
+    if !active.contains(&day) {
+        day -= Duration::days(1);
+        while off(day) {
+            day -= Duration::days(1);
+        }
+        if !active.contains(&day) {
+            return (longest, 0);
+        }
+    }
+    let mut current = 0;
+    while active.contains(&day) || off(day) {
+        if active.contains(&day) {
+            current += 1;
+        }
+        day -= Duration::days(1);
+    }
+    (longest, current)
+}
+
+/// A parsed `dashboard` request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DashboardQuery {
+    pub from: String,
+    pub to: String,
+    pub refresh: bool,
+    pub granularity: String,
+    pub project_id: Option<String>,
+    pub workspace_id: Option<String>,
+    /// Requested `SECTIONS`, in their order
+    pub sections: Vec<&'static str>,
+}
+
+impl DashboardQuery {
+    pub fn from_request(request: &ApiRequest) -> Result<Self, CommandError> {
+        let required = |name: &str| {
+            request
+                .arg(name)
+                .map(str::to_string)
+                .ok_or_else(|| CommandError::invalid_argument(format!("Missing argument --{}", name)))
+        };
+        let (from, to) = (required("from")?, required("to")?);
+        parse_date(&from)?;
+        parse_date(&to)?;
+        let granularity = request.arg("granularity").unwrap_or("month").to_string();
+        if !GRANULARITIES.contains(&granularity.as_str()) {
+            return Err(CommandError::invalid_argument(format!(
+                "Unknown granularity: {} (expected {})",
+                granularity,
+                GRANULARITIES.join(", ")
+            )));
+        }
+        let sections = match request.arg("sections").filter(|sections| !sections.is_empty()) {
+            Some(list) => {
+                let wanted: Vec<&str> = list.split(',').map(str::trim).collect();
+                if let Some(unknown) = wanted.iter().find(|name| !SECTIONS.contains(name)) {
+                    return Err(CommandError::invalid_argument(format!(
+                        "Unknown dashboard section: {} (expected {})",
+                        unknown,
+                        SECTIONS.join(", ")
+                    )));
+                }
+                SECTIONS.iter().copied().filter(|name| wanted.contains(name)).collect()
+            }
+            None => SECTIONS.to_vec(),
+        };
+        let non_empty = |name: &str| request.arg(name).filter(|value| !value.is_empty()).map(str::to_string);
+        Ok(Self {
+            from,
+            to,
+            refresh: request.arg("refresh").is_some_and(|value| value != "0"),
+            granularity,
+            project_id: non_empty("project-id"),
+            workspace_id: non_empty("workspace-id"),
+            sections,
+        })
+    }
+
+    fn wants(&self, section: &str) -> bool {
+        self.sections.contains(&section)
+    }
+}
+
+/// Project IDs of a workspace (the `workspace_id` of their project metadata).
+fn workspace_projects(db: &Path, workspace_id: &str) -> Result<Vec<String>, CommandError> {
+    if ingest::read_json(&db.join(WORKSPACES_FILE)).get(workspace_id).is_none() {
+        return Err(CommandError::invalid_argument(format!("Workspace not found: {}", workspace_id)));
+    }
+    let metadata = ingest::read_json(&db.join(PROJECTS_FILE));
+    let members: Vec<String> = metadata
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(_, meta)| meta["workspace_id"].as_str() == Some(workspace_id))
+        .map(|(project_id, _)| project_id.clone())
+        .collect();
+    if members.is_empty() {
+        return Err(CommandError::invalid_argument(format!("Workspace has no projects: {}", workspace_id)));
+    }
+    Ok(members)
+}
+
+/// Queries of one dashboard over the store, limited to a project scope.
+struct Aggregator<'a> {
+    conn: &'a Connection,
+    /// JSON array of project IDs; all projects if None
+    scope: Option<String>,
+}
+
+impl Aggregator<'_> {
+    fn rows<T>(
+        &self,
+        sql: &str,
+        from: &str,
+        to: &str,
+        map: impl FnMut(&rusqlite::Row) -> rusqlite::Result<T>,
+    ) -> Result<Vec<T>, String> {
+        let mut stmt = self.conn.prepare_cached(sql).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![from, to, self.scope], map)
+            .map_err(|e| e.to_string())?
+            .collect::<rusqlite::Result<Vec<T>>>()
+            .map_err(|e| e.to_string());
+        rows
+    }
+
+    /// Messages per day.
+    fn daily_activity(&self, from: &str, to: &str) -> Result<BTreeMap<String, i64>, String> {
+        let sql = format!(
+            "SELECT date, SUM(messages) FROM daily WHERE date >= ?1 AND date <= ?2 AND {} GROUP BY date",
+            SCOPE
+        );
+        Ok(self.rows(&sql, from, to, |row| Ok((row.get(0)?, row.get(1)?)))?.into_iter().collect())
+    }
+
+    fn totals(&self, from: &str, to: &str) -> Result<Value, String> {
+        let sql = format!(
+            "SELECT COUNT(*), COUNT(DISTINCT session_id), COALESCE(SUM({}), 0), COALESCE(SUM(input_tokens), 0),
+                    COALESCE(SUM(output_tokens), 0), COALESCE(SUM(cost_usd), 0), COALESCE(SUM(cache_read_tokens), 0),
+                    COALESCE(SUM(cache_write_tokens), 0), MIN(epoch_ms), COALESCE(SUM(list_cost_usd), 0)
+             FROM messages WHERE date >= ?1 AND date <= ?2 AND {}",
+            TOKENS, SCOPE
+        );
+        let mut rows = self.rows(&sql, from, to, |row| {
+            Ok(json!({
+                "messages": row.get::<_, i64>(0)?,
+                "sessions": row.get::<_, i64>(1)?,
+                "tokens": row.get::<_, i64>(2)?,
+                "input_tokens": row.get::<_, i64>(3)?,
+                "output_tokens": row.get::<_, i64>(4)?,
+                "cost": round(row.get(5)?, 4),
+                "cache_read": row.get::<_, i64>(6)?,
+                "cache_write": row.get::<_, i64>(7)?,
+                "first_session_date": row.get::<_, Option<i64>>(8)?.and_then(local_time),
+                "api_equivalent_cost": round(row.get(9)?, 4),
+            }))
+        })?;
+        Ok(rows.pop().unwrap_or(Value::Null))
+    }
+
+    /// Usage per period of `granularity` (see `period`).
+    fn timeline(&self, from: &str, to: &str, granularity: &str) -> Result<Vec<Value>, String> {
+        let (table, messages, cost) = match granularity {
+            "hour" => ("messages", "COUNT(*)", "SUM(cost_usd)"),
+            _ => ("daily", "SUM(messages)", "SUM(cost)"),
+        };
+        let sql = format!(
+            "SELECT {} AS period, {}, SUM({}), SUM(input_tokens), SUM(output_tokens), COALESCE({}, 0)
+             FROM {} WHERE date >= ?1 AND date <= ?2 AND {} GROUP BY period ORDER BY period",
+            period(granularity), messages, TOKENS, cost, table, SCOPE
+        );
+        self.rows(&sql, from, to, |row| {
+            Ok(json!({
+                "period": row.get::<_, String>(0)?,
+                "messages": row.get::<_, i64>(1)?,
+                "tokens": row.get::<_, i64>(2)?,
+                "input_tokens": row.get::<_, i64>(3)?,
+                "output_tokens": row.get::<_, i64>(4)?,
+                "cost": round(row.get(5)?, 4),
+            }))
+        })
+    }
+
+    /// Values of the derived metric variables (`derived_metrics::METRIC_NAMES`)
+    /// per period of `granularity`. The store has no MCP calls or latencies:
+    /// `tool_calls` and `tool_errors` are 0 and `latency_ms` is null.
+    fn metric_inputs(
+        &self,
+        from: &str,
+        to: &str,
+        granularity: &str,
+    ) -> Result<BTreeMap<String, Map<String, Value>>, String> {
+        let sql = format!(
+            "SELECT {} AS period, COUNT(*), COUNT(DISTINCT session_id), SUM({}), SUM(input_tokens),
+                    SUM(output_tokens), SUM(cache_read_tokens), SUM(cache_write_tokens), SUM(COALESCE(cost_usd, 0))
+             FROM messages WHERE date >= ?1 AND date <= ?2 AND {} GROUP BY period",
+            period(granularity), TOKENS, SCOPE
+        );
+        let rows = self.rows(&sql, from, to, |row| {
+            let mut values = Map::new();
+            for (column, name) in derived_metrics::METRIC_NAMES[..7].iter().enumerate() {
+                values.insert(name.to_string(), Value::from(row.get::<_, i64>(column + 1)?));
+            }
+            values.insert("cost".to_string(), Value::from(row.get::<_, f64>(8)?));
+            values.insert("tool_calls".to_string(), Value::from(0));
+            values.insert("tool_errors".to_string(), Value::from(0));
+            values.insert("latency_ms".to_string(), Value::Null);
+            Ok((row.get(0)?, values))
+        })?;
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Message totals per account of the config dirs bound to one in
+    /// `bindings` (`{config_dir: {account_email}}`), like the Python
+    /// `query_account_message_totals`.
+    fn account_totals(&self, from: &str, to: &str, bindings: &Value) -> Result<BTreeMap<String, Value>, String> {
+        if bindings.as_object().map_or(true, Map::is_empty) {
+            return Ok(BTreeMap::new());
+        }
+        let sql = format!(
+            "SELECT config_dir, COUNT(*), COUNT(DISTINCT session_id), SUM({}), SUM(COALESCE(cost_usd, 0))
+             FROM messages WHERE date >= ?1 AND date <= ?2 AND {} GROUP BY config_dir ORDER BY config_dir",
+            TOKENS, SCOPE
+        );
+        let rows = self.rows(&sql, from, to, |row| {
+            Ok((row.get::<_, String>(0)?, [row.get::<_, i64>(1)?, row.get(2)?, row.get(3)?], row.get::<_, f64>(4)?))
+        })?;
+        let mut totals: BTreeMap<String, ([i64; 3], f64, Vec<String>)> = BTreeMap::new();
+        for (config_dir, counts, cost) in rows {
+            let Some(email) = bindings[&config_dir]["account_email"].as_str().filter(|email| !email.is_empty()) else {
+                continue;
+            };
+            let entry = totals.entry(email.to_string()).or_default();
+            for (total, count) in entry.0.iter_mut().zip(counts) {
+                *total += count;
+            }
+            entry.1 = round(entry.1 + cost, 4);
+            entry.2.push(config_dir);
+        }
+        Ok(totals
+            .into_iter()
+            .map(|(email, ([messages, sessions, tokens], cost, config_dirs))| {
+                let usage = json!({
+                    "messages": messages,
+                    "sessions": sessions,
+                    "tokens": tokens,
+                    "cost": cost,
+                    "config_dirs": config_dirs,
+                });
+                (email, usage)
+            })
+            .collect())
+    }
+
+    /// Share of each model, by tokens.
+    fn model_distribution(&self, from: &str, to: &str) -> Result<Vec<Value>, String> {
+        let sql = format!(
+            "SELECT model, SUM({}) AS tokens, SUM(input_tokens), SUM(output_tokens), SUM(messages), SUM(cost),
+                    SUM(list_cost)
+             FROM daily WHERE date >= ?1 AND date <= ?2 AND {} AND model != ''
+             GROUP BY model ORDER BY tokens DESC",
+            TOKENS, SCOPE
+        );
+        let rows = self.rows(&sql, from, to, |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, f64>(5)?,
+                row.get::<_, f64>(6)?,
+            ))
+        })?;
+        let total = rows.iter().map(|row| row.1).sum::<i64>().max(1) as f64;
+        Ok(rows
+            .into_iter()
+            .map(|(model, tokens, input, output, messages, cost, list_cost)| {
+                json!({
+                    "display_name": display_name(&model),
+                    "model": model,
+                    "tokens": tokens,
+                    "input_tokens": input,
+                    "output_tokens": output,
+                    "messages": messages,
+                    "cost": round(cost, 4),
+                    "percent": round(tokens as f64 / total * 100.0, 1),
+                    "api_equivalent_cost": round(list_cost, 4),
+                })
+            })
+            .collect())
+    }
+
+    /// Usage per local hour of the day, all 24 hours.
+    fn hourly_profile(&self, from: &str, to: &str) -> Result<Vec<Value>, String> {
+        let sql = format!(
+            "SELECT CAST({} AS INTEGER) AS hour, COUNT(*), SUM({}), SUM(input_tokens), SUM(output_tokens)
+             FROM messages WHERE date >= ?1 AND date <= ?2 AND {} GROUP BY hour",
+            LOCAL_HOUR, TOKENS, SCOPE
+        );
+        let by_hour: BTreeMap<i64, [i64; 4]> = self
+            .rows(&sql, from, to, |row| Ok((row.get(0)?, [row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?])))?
+            .into_iter()
+            .collect();
+        Ok((0..24)
+            .map(|hour| {
+                let [messages, tokens, input, output] = by_hour.get(&hour).copied().unwrap_or_default();
+                json!({
+                    "hour": hour,
+                    "messages": messages,
+                    "tokens": tokens,
+                    "input_tokens": input,
+                    "output_tokens": output,
+                })
+            })
+            .collect())
+    }
+
+    /// The most expensive sessions of the range with their per-model breakdown.
+    fn recent_sessions(&self, from: &str, to: &str) -> Result<Vec<Value>, String> {
+        let columns = format!(
+            "COUNT(*), SUM({}) AS tokens, SUM(input_tokens), SUM(output_tokens), COALESCE(SUM(cost_usd), 0) AS cost,
+             MIN(epoch_ms), MAX(epoch_ms) AS last_ms",
+            TOKENS
+        );
+        let usage = |row: &rusqlite::Row, first: usize| -> rusqlite::Result<Map<String, Value>> {
+            let mut usage = Map::new();
+            usage.insert("messages".to_string(), Value::from(row.get::<_, i64>(first)?));
+            usage.insert("tokens".to_string(), Value::from(row.get::<_, i64>(first + 1)?));
+            usage.insert("input_tokens".to_string(), Value::from(row.get::<_, i64>(first + 2)?));
+            usage.insert("output_tokens".to_string(), Value::from(row.get::<_, i64>(first + 3)?));
+            usage.insert("cost".to_string(), Value::from(round(row.get(first + 4)?, 4)));
+            usage.insert("first_time".to_string(), Value::from(local_time(row.get(first + 5)?)));
+            usage.insert("last_time".to_string(), Value::from(local_time(row.get(first + 6)?)));
+            Ok(usage)
+        };
+
+        let sql = format!(
+            "SELECT session_id, {} FROM messages
+             WHERE date >= ?1 AND date <= ?2 AND {} AND session_id IS NOT NULL
+             GROUP BY session_id ORDER BY cost DESC, tokens DESC, last_ms DESC LIMIT {}",
+            columns, SCOPE, RECENT_SESSIONS
+        );
+        let sessions = self.rows(&sql, from, to, |row| Ok((row.get::<_, String>(0)?, usage(row, 1)?)))?;
+        if sessions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids = Value::from(sessions.iter().map(|(id, _)| id.as_str()).collect::<Vec<_>>()).to_string();
+        let sql = format!(
+            "SELECT session_id, model, {} FROM messages
+             WHERE date >= ?1 AND date <= ?2 AND {} AND session_id IN (SELECT value FROM json_each(?4))
+             GROUP BY session_id, model",
+            columns, SCOPE
+        );
+        let mut stmt = self.conn.prepare_cached(&sql).map_err(|e| e.to_string())?;
+        let mut models: BTreeMap<String, Vec<Map<String, Value>>> = BTreeMap::new();
+        let rows = stmt
+            .query_map(params![from, to, self.scope, ids], |row| {
+                let model = row.get::<_, String>(1)?;
+                let model = if model.is_empty() { "Unknown".to_string() } else { model };
+                let mut item = Map::new();
+                item.insert("display_name".to_string(), Value::from(display_name(&model)));
+                item.insert("model".to_string(), Value::from(model));
+                item.extend(usage(row, 2)?);
+                Ok((row.get::<_, String>(0)?, item))
+            })
+            .map_err(|e| e.to_string())?;
+        for row in rows {
+            let (session_id, item) = row.map_err(|e| e.to_string())?;
+            models.entry(session_id).or_default().push(item);
+        }
+
+        let number = |item: &Map<String, Value>, key: &str| item[key].as_f64().unwrap_or(0.0);
+        Ok(sessions
+            .into_iter()
+            .map(|(session_id, usage)| {
+                let mut items = models.remove(&session_id).unwrap_or_default();
+                items.sort_by(|a, b| {
+                    number(b, "cost")
+                        .total_cmp(&number(a, "cost"))
+                        .then(number(b, "tokens").total_cmp(&number(a, "tokens")))
+                        .then_with(|| a["last_time"].as_str().cmp(&b["last_time"].as_str()))
+                });
+                let mut names: Vec<&str> = Vec::new();
+                let mut display_names: Vec<&str> = Vec::new();
+                for item in &items {
+                    for (list, key) in [(&mut names, "model"), (&mut display_names, "display_name")] {
+                        let value = item[key].as_str().unwrap_or("Unknown");
+                        if !list.contains(&value) {
+                            list.push(value);
+                        }
+                    }
+                }
+                let joined = |list: &[&str]| if list.is_empty() { "Unknown".to_string() } else { list.join(", ") };
+                let mut session = Map::new();
+                session.insert("session_id".to_string(), Value::from(session_id));
+                session.insert("model".to_string(), Value::from(joined(&names)));
+                session.insert("display_name".to_string(), Value::from(joined(&display_names)));
+                session.extend(usage);
+                let items: Vec<Value> = items.into_iter().map(Value::Object).collect();
+                session.insert("models".to_string(), Value::from(items));
+                Value::Object(session)
+            })
+            .collect())
+    }
+
+    /// First and last day with data in the scope.
+    fn data_range(&self) -> Result<Value, String> {
+        let sql = format!("SELECT MIN(date), MAX(date) FROM daily WHERE {}", SCOPE);
+        let mut rows = self.rows(&sql, "", "", |row| {
+            Ok(json!({"start": row.get::<_, Option<String>>(0)?, "end": row.get::<_, Option<String>>(1)?}))
+        })?;
+        Ok(rows.pop().unwrap_or(Value::Null))
+    }
+}
+
+/// Build the dashboard bundle of `query` from `store`, as of `today`, with
+/// the settings and metadata in `data_dir` and the cc_usage snapshots of
+/// `usage_dbs`.
+pub fn dashboard(
+    store: &Store,
+    query: &DashboardQuery,
+    project_ids: Option<Vec<String>>,
+    data_dir: &Path,
+    usage_dbs: &[PathBuf],
+    today: NaiveDate,
+) -> Result<Value, String> {
+    let aggregator = Aggregator {
+        conn: store.connection(),
+        scope: project_ids.map(|ids| Value::from(ids).to_string()),
+    };
+    let calendar = Calendar::load(data_dir);
+    let (from, to) = (query.from.as_str(), query.to.as_str());
+    let mut bundle = Map::new();
+    bundle.insert("range".to_string(), json!({"from": from, "to": to}));
+
+    let daily_activity = if query.wants("totals") || query.wants("timeline") {
+        aggregator.daily_activity(from, to)?
+    } else {
+        BTreeMap::new()
+    };
+
+    if query.wants("totals") {
+        let mut totals = aggregator.totals(from, to)?;
+        let (start, end) = (parse_date(from).map_err(|e| e.to_string())?, parse_date(to).map_err(|e| e.to_string())?);
+        let (previous_from, previous_to) = previous_period(start, end);
+        let previous = aggregator.totals(&format_date(previous_from), &format_date(previous_to))?;
+        let (longest, current) = streaks(&daily_activity, &calendar.excluded_days(None), today);
+
+        // Averages over the days that have passed, without days off and their usage
+        let excluded = calendar.excluded_days(Some((from, to)));
+        let elapsed: Vec<String> = days(start, end.min(today)).map(format_date).collect();
+        let counted = elapsed.iter().filter(|day| !excluded.contains(*day)).count();
+        let included: Vec<Value> = aggregator
+            .timeline(from, to, "day")?
+            .into_iter()
+            .filter(|row| !excluded.contains(row["period"].as_str().unwrap_or_default()))
+            .collect();
+        let average = |key: &str| {
+            let sum: f64 = included.iter().map(|row| row[key].as_f64().unwrap_or(0.0)).sum();
+            if counted > 0 { sum / counted as f64 } else { 0.0 }
+        };
+
+        let number = |value: &Value, key: &str| value[key].as_f64().unwrap_or(0.0);
+        let trends = json!({
+            "messages": trend(number(&totals, "messages"), number(&previous, "messages")),
+            "sessions": trend(number(&totals, "sessions"), number(&previous, "sessions")),
+            "tokens": trend(number(&totals, "tokens"), number(&previous, "tokens")),
+            "cost": trend(number(&totals, "cost"), number(&previous, "cost")),
+        });
+        // Plans are not attributable to projects
+        let subscription_cost = match aggregator.scope {
+            Some(_) => None,
+            None => usage_accounts::subscription_cost(data_dir, start, end),
+        };
+        let savings = subscription_cost.map(|cost| round(number(&totals, "api_equivalent_cost") - cost, 2));
+        totals["subscription_cost"] = Value::from(subscription_cost);
+        totals["subscription_savings"] = Value::from(savings);
+        totals["current_streak"] = Value::from(current);
+        totals["max_streak"] = Value::from(longest);
+        totals["daily_average"] = json!({
+            "days": counted,
+            "excluded_days": elapsed.len() - counted,
+            "messages": round(average("messages"), 1),
+            "tokens": average("tokens").round() as i64,
+            "cost": round(average("cost"), 4),
+        });
+        bundle.insert("totals".to_string(), totals);
+        bundle.insert("trends".to_string(), trends);
+        let snapshots = usage_accounts::snapshot_totals(usage_dbs, data_dir, from, to);
+        let bindings = ingest::read_json(&data_dir.join(CONFIG_BINDINGS_FILE));
+        let messages = aggregator.account_totals(from, to, &bindings)?;
+        bundle.insert("usage_accounts".to_string(), json!(usage_accounts::merge_account_totals(snapshots, messages)));
+    }
+
+    if query.wants("timeline") {
+        let heatmap_to = today;
+        let heatmap_from = today - Duration::days(HEATMAP_DAYS - 1);
+        let (heatmap_from, heatmap_to) = (format_date(heatmap_from), format_date(heatmap_to));
+        let metrics = derived_metrics::load(data_dir);
+        let mut timeline = aggregator.timeline(from, to, &query.granularity)?;
+        let mut daily_derived = Map::new();
+        if !metrics.is_empty() {
+            let derived = derived_metrics::compute(&metrics, &aggregator.metric_inputs(from, to, &query.granularity)?);
+            for item in &mut timeline {
+                let period = item["period"].as_str().unwrap_or_default();
+                item["derived"] = derived.get(period).cloned().unwrap_or_else(|| json!({}));
+            }
+            daily_derived = derived_metrics::compute(&metrics, &aggregator.metric_inputs(from, to, "day")?);
+        }
+        bundle.insert("daily_activity".to_string(), json!(daily_activity));
+        bundle.insert("daily_derived".to_string(), Value::Object(daily_derived));
+        bundle.insert("derived_metrics".to_string(), Value::from(metrics));
+        bundle.insert(
+            "timeline".to_string(),
+            json!({
+                "granularity": query.granularity,
+                "data": timeline,
+                "overlay": calendar.overlay(from, to),
+            }),
+        );
+        bundle.insert(
+            "heatmap".to_string(),
+            json!({
+                "range": {"from": heatmap_from, "to": heatmap_to},
+                "daily_activity": aggregator.daily_activity(&heatmap_from, &heatmap_to)?,
+                "overlay": calendar.overlay(&heatmap_from, &heatmap_to),
+            }),
+        );
+    }
+
+    if query.wants("models") {
+        bundle.insert("model_distribution".to_string(), json!(aggregator.model_distribution(from, to)?));
+    }
+    if query.wants("hourly") {
+        bundle.insert("hourly_profile".to_string(), json!(aggregator.hourly_profile(from, to)?));
+    }
+    if query.wants("sessions") {
+        bundle.insert("recent_sessions".to_string(), json!(aggregator.recent_sessions(from, to)?));
+    }
+
+    bundle.insert(
+        "meta".to_string(),
+        json!({
+            "updated_files": 0,
+            "ingest": null,
+            "refresh_blocked": null,
+            "hooks": [],
+            "generated_at": Local::now().naive_local().format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+            "data_range": aggregator.data_range()?,
+            "app_version": env!("CARGO_PKG_VERSION"),
+            "sections": query.sections,
+            "workspace_id": query.workspace_id,
+            "source": "native",
+        }),
+    );
+    Ok(Value::Object(bundle))
+}
+
+/// Whether the store in the app data dir has indexed session logs (without
+/// creating it).
+pub fn store_indexed() -> bool {
+    let Ok(path) = db_dir().map(|db| db.join(store::STORE_FILE)) else {
+        return false;
+    };
+    path.exists() && Store::open(&path).and_then(|store| store.is_indexed()).unwrap_or(false)
+}
+
+/// Answer a request for one of `NATIVE_SUBCOMMANDS` from the store in the
+/// app data dir, indexing changed logs first when it asks for a refresh.
+pub fn answer(request: &ApiRequest) -> Result<Value, CommandError> {
+    let query = DashboardQuery::from_request(request)?;
+    let db = db_dir().map_err(CommandError::internal)?;
+    let project_ids = match (&query.workspace_id, &query.project_id) {
+        (Some(workspace_id), project_id) => {
+            let members = workspace_projects(&db, workspace_id)?;
+            match project_id {
+                Some(project_id) if !members.contains(project_id) => {
+                    return Err(CommandError::invalid_argument(format!(
+                        "Project {} is not in workspace {}",
+                        project_id, workspace_id
+                    )))
+                }
+                Some(project_id) => Some(vec![project_id.clone()]),
+                None => Some(members),
+            }
+        }
+        (None, project_id) => project_id.clone().map(|id| vec![id]),
+    };
+
+    let mut store = Store::open_default().map_err(CommandError::internal)?;
+    let stats = if query.refresh {
+        let dirs = store::user_config_dirs().map_err(CommandError::internal)?;
+        Some(store.index(&dirs, false).map_err(CommandError::internal)?)
+    } else {
+        None
+    };
+    let usage_dbs = usage_accounts::usage_db_paths(&home_dir().map_err(CommandError::internal)?);
+    let mut bundle = dashboard(&store, &query, project_ids, &db, &usage_dbs, Local::now().date_naive())
+        .map_err(CommandError::internal)?;
+    if let Some(stats) = stats {
+        log::debug!("[{}] Indexed {} changed session logs", request.request_id, stats.changed_files);
+        bundle["meta"]["updated_files"] = Value::from(stats.changed_files);
+        bundle["meta"]["ingest"] = serde_json::to_value(stats).unwrap_or(Value::Null);
+    }
+    Ok(bundle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("cc-aggregate-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn line(n: u32, session_id: &str, timestamp: &str, model: &str, cost: f64) -> String {
+        json!({
+            "type": "assistant",
+            "sessionId": session_id,
+            "requestId": format!("req_{}", n),
+            "timestamp": timestamp,
+            "costUSD": cost,
+            "message": {
+                "id": format!("msg_{}", n),
+                "model": model,
+                "usage": {
+                    "input_tokens": 10,
+                    "output_tokens": 5,
+                    "cache_read_input_tokens": 100,
+                    "cache_creation_input_tokens": 20,
+                },
+            },
+        })
+        .to_string()
+            + "\n"
+    }
+
+    /// Paths of every object key (arrays by their elements, date keys left out).
+    fn paths(value: &Value, prefix: &str, out: &mut BTreeSet<String>) {
+        match value {
+            Value::Object(obj) => {
+                for (key, value) in obj.iter().filter(|(key, _)| !key.starts_with(|c: char| c.is_ascii_digit())) {
+                    let path = format!("{}.{}", prefix, key);
+                    out.insert(path.clone());
+                    paths(value, &path, out);
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|item| paths(item, &format!("{}[]", prefix), out)),
+            _ => {}
+        }
+    }
+
+    fn query(from: &str, to: &str, granularity: &str) -> DashboardQuery {
+        let args = ["dashboard", "--from", from, "--to", to, "--refresh", "0", "--granularity", granularity];
+        DashboardQuery::from_request(&ApiRequest::new("rq-test", &args)).unwrap()
+    }
+
+    fn indexed_store(dir: &TempDir) -> Store {
+        let project = dir.0.join(".claude").join("projects").join("-home-me-app");
+        fs::create_dir_all(&project).unwrap();
+        let log = [
+            line(1, "s1", "2025-06-09T10:00:00Z", "claude-sonnet-4-5-20250929", 0.5),
+            line(2, "s1", "2025-06-09T10:30:00Z", "claude-opus-4-1-20250805", 1.5),
+            line(3, "s2", "2025-06-10T12:00:00Z", "claude-sonnet-4-5-20250929", 0.25),
+            line(4, "s3", "2025-06-01T12:00:00Z", "claude-sonnet-4-5-20250929", 1.0),
+        ]
+        .concat();
+        fs::write(project.join("s1.jsonl"), log).unwrap();
+        let mut store = Store::open(&dir.0.join("db").join(store::STORE_FILE)).unwrap();
+        store.index(&[dir.0.join(".claude")], false).unwrap();
+        store
+    }
+
+    #[test]
+    fn test_dashboard_matches_python_bundle_shape() {
+        let dir = TempDir::new("shape");
+        let store = indexed_store(&dir);
+        let today = NaiveDate::from_ymd_opt(2025, 6, 10).unwrap();
+        let bundle = dashboard(&store, &query("2025-06-08", "2025-06-14", "day"), None, &dir.0, &[], today).unwrap();
+
+        crate::response_schema::validate("dashboard", &bundle).unwrap();
+        let python: Value = serde_json::from_str(include_str!("../fixtures/demo/dashboard.json")).unwrap();
+        let (mut expected, mut native) = (BTreeSet::new(), BTreeSet::new());
+        paths(&python, "", &mut expected);
+        paths(&bundle, "", &mut native);
+        native.remove(".meta.source");
+        assert_eq!(native, expected);
+
+        let totals = &bundle["totals"];
+        assert_eq!((totals["messages"].as_i64(), totals["sessions"].as_i64()), (Some(3), Some(2)));
+        assert_eq!((totals["tokens"].as_i64(), totals["cache_read"].as_i64()), (Some(405), Some(300)));
+        assert_eq!(totals["cost"].as_f64(), Some(2.25));
+        assert_eq!((totals["current_streak"].as_i64(), totals["max_streak"].as_i64()), (Some(2), Some(2)));
+        // Previous week had one message
+        assert_eq!(bundle["trends"]["messages"].as_f64(), Some(200.0));
+        assert_eq!(totals["daily_average"]["days"].as_i64(), Some(3));
+
+        assert_eq!(bundle["timeline"]["data"].as_array().unwrap().len(), 2);
+        let models = bundle["model_distribution"].as_array().unwrap();
+        assert_eq!(models[0]["display_name"], "Sonnet 4.5");
+        assert_eq!(models[0]["percent"].as_f64(), Some(66.7));
+        let hourly = bundle["hourly_profile"].as_array().unwrap();
+        assert_eq!(hourly.len(), 24);
+        assert_eq!(hourly.iter().map(|hour| hour["messages"].as_i64().unwrap()).sum::<i64>(), 3);
+
+        let sessions = bundle["recent_sessions"].as_array().unwrap();
+        assert_eq!(sessions[0]["session_id"], "s1");
+        assert_eq!(sessions[0]["display_name"], "Opus 4.1, Sonnet 4.5");
+        assert_eq!(sessions[0]["models"].as_array().unwrap().len(), 2);
+        assert_eq!(bundle["meta"]["data_range"]["start"], "2025-06-01");
+    }
+
+    /// `bundle` without what differs between runs and installs, config dirs
+    /// by name and numbers as floats (Python writes a cost of 0 as an integer).
+    fn comparable(mut bundle: Value) -> Value {
+        fn floats(value: &mut Value) {
+            match value {
+                Value::Number(number) => *value = Value::from(number.as_f64()),
+                Value::Array(items) => items.iter_mut().for_each(floats),
+                Value::Object(obj) => obj.values_mut().for_each(floats),
+                _ => {}
+            }
+        }
+        let meta = bundle["meta"].as_object_mut().unwrap();
+        for key in ["generated_at", "updated_files", "ingest", "app_version", "source"] {
+            meta.remove(key);
+        }
+        for account in bundle["usage_accounts"].as_array_mut().unwrap() {
+            for dir in account.pointer_mut("/usage/config_dirs").and_then(Value::as_array_mut).into_iter().flatten() {
+                *dir = Value::from(Path::new(dir.as_str().unwrap()).file_name().unwrap().to_str());
+            }
+        }
+        floats(&mut bundle);
+        bundle
+    }
+
+    #[test]
+    fn test_dashboard_matches_python() {
+        // Hours and local times of the recording are UTC
+        if std::env::var("TZ").as_deref() != Ok("UTC") {
+            let name = format!("{}::test_dashboard_matches_python", module_path!().split_once("::").unwrap().1);
+            let status = std::process::Command::new(std::env::current_exe().unwrap())
+                .args(["--exact", &name, "--test-threads=1"])
+                .env("TZ", "UTC")
+                .status()
+                .unwrap();
+            assert!(status.success());
+            return;
+        }
+
+        // Session logs, plans, bindings, snapshots and metrics; the bundle the Python backend built from them
+        // (see scripts/record_parity_fixture.py)
+        let home = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join("parity").join("home");
+        let python: Value = serde_json::from_str(include_str!("../fixtures/parity/dashboard.json")).unwrap();
+        let dir = TempDir::new("parity");
+        let db = dir.0.join("db");
+        fs::create_dir_all(&db).unwrap();
+        for entry in fs::read_dir(home.join(".claude").join("db")).unwrap() {
+            let path = entry.unwrap().path();
+            fs::copy(&path, db.join(path.file_name().unwrap())).unwrap();
+        }
+        let config_dir = home.join(".claude");
+        let bindings = ingest::read_json(&db.join(CONFIG_BINDINGS_FILE))["~/.claude"].clone();
+        fs::write(db.join(CONFIG_BINDINGS_FILE), json!({config_dir.display().to_string(): bindings}).to_string())
+            .unwrap();
+        let usage_db = db.join("cc_usage.db");
+        let snapshots = Connection::open(&usage_db).unwrap();
+        snapshots.execute_batch(&fs::read_to_string(db.join("cc_usage.sql")).unwrap()).unwrap();
+        drop(snapshots);
+        let mut store = Store::open(&db.join(store::STORE_FILE)).unwrap();
+        store.index(&[config_dir], false).unwrap();
+
+        let today = parse_date(python["heatmap"]["range"]["to"].as_str().unwrap()).unwrap();
+        let bundle = dashboard(&store, &query("2025-06-08", "2025-06-14", "day"), None, &db, &[usage_db], today);
+        assert_eq!(comparable(bundle.unwrap()), comparable(python));
+    }
+
+    #[test]
+    fn test_sections_granularity_and_scope() {
+        let dir = TempDir::new("sections");
+        let store = indexed_store(&dir);
+        let today = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+        let mut models_only = query("2025-06-01", "2025-06-30", "month");
+        models_only.sections = vec!["models"];
+        let bundle = dashboard(&store, &models_only, None, &dir.0, &[], today).unwrap();
+        let keys: Vec<&String> = bundle.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["meta", "model_distribution", "range"]);
+
+        let monthly = dashboard(&store, &query("2025-06-01", "2025-06-30", "month"), None, &dir.0, &[], today).unwrap();
+        assert_eq!(monthly["timeline"]["data"][0]["period"], "2025-06");
+        assert_eq!(monthly["timeline"]["data"][0]["messages"].as_i64(), Some(4));
+        let weekly = dashboard(&store, &query("2025-06-01", "2025-06-30", "week"), None, &dir.0, &[], today).unwrap();
+        assert_eq!(weekly["timeline"]["data"][1]["period"], "2025-W23");
+
+        let other = Some(vec!["-home-me-other".to_string()]);
+        let empty = dashboard(&store, &query("2025-06-01", "2025-06-30", "day"), other, &dir.0, &[], today).unwrap();
+        assert_eq!(empty["totals"]["messages"].as_i64(), Some(0));
+        assert_eq!(empty["meta"]["data_range"]["start"], Value::Null);
+        assert_eq!(empty["model_distribution"], json!([]));
+    }
+
+    #[test]
+    fn test_streaks_skip_days_off() {
+        let activity: BTreeMap<String, i64> =
+            ["2025-06-02", "2025-06-03", "2025-06-06", "2025-06-09"].iter().map(|day| (day.to_string(), 1)).collect();
+        let today = NaiveDate::from_ymd_opt(2025, 6, 10).unwrap();
+        assert_eq!(streaks(&activity, &BTreeSet::new(), today), (2, 1));
+        let off: BTreeSet<String> = ["2025-06-04", "2025-06-05", "2025-06-07", "2025-06-08", "2025-06-10"]
+            .iter()
+            .map(|day| day.to_string())
+            .collect();
+        assert_eq!(streaks(&activity, &off, today), (4, 4));
+        assert_eq!(display_name("claude-3-5-haiku-20241022"), "Haiku 3.5");
+        assert_eq!(display_name("claude-opus-4-6-20260101"), "Opus 4.6");
+        assert_eq!(display_name("claude-3-opus-20240229"), "Opus 3");
+        assert_eq!(display_name("claude-sonnet-4-5-20250929-v2"), "sonnet-4-5-20250929-v2");
+        assert_eq!(display_name("<synthetic>"), "<synthetic>");
+        let invalid = ApiRequest::new("rq", &["dashboard", "--from=2025-13-01", "--to=2025-06-01"]);
+        let error = DashboardQuery::from_request(&invalid).unwrap_err();
+        assert_eq!(error.message(), "Invalid date: 2025-13-01 (expected YYYY-MM-DD)");
+    }
+
+    /// Messages as (message number, hours after 2025-06-07 00:00 UTC, model, session, input, output, cost in
+    /// cents); numbers repeat, so some messages are logged twice.
+    fn messages() -> impl Strategy<Value = Vec<(u32, i64, usize, usize, u64, u64, u32)>> {
+        let message = (0u32..40, 0i64..14 * 24, 0usize..3, 0usize..4, 0u64..5000, 0u64..5000, 0u32..500);
+        prop::collection::vec(message, 1..40)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_totals_are_sums_of_models_and_days(messages in messages()) {
+            const MODELS: [&str; 3] = ["claude-sonnet-4-5-20250929", "claude-opus-4-1-20250805", "claude-3-5-haiku"];
+            let dir = TempDir::new("sums");
+            let project = dir.0.join(".claude").join("projects").join("-home-me-app");
+            fs::create_dir_all(&project).unwrap();
+            let start = chrono::Utc.with_ymd_and_hms(2025, 6, 7, 0, 0, 0).unwrap();
+            let log: String = messages
+                .iter()
+                .map(|(n, hours, model, session, input, output, cents)| {
+                    let timestamp = (start + Duration::hours(*hours)).to_rfc3339_opts(SecondsFormat::Millis, true);
+                    json!({
+                        "sessionId": format!("s{}", session),
+                        "requestId": format!("req_{}", n),
+                        "timestamp": timestamp,
+                        "costUSD": f64::from(*cents) / 100.0,
+                        "message": {
+                            "id": format!("msg_{}", n),
+                            "model": MODELS[*model],
+                            "usage": {"input_tokens": input, "output_tokens": output},
+                        },
+                    })
+                    .to_string()
+                        + "\n"
+                })
+                .collect();
+            fs::write(project.join("s.jsonl"), log).unwrap();
+            let mut store = Store::open(&dir.0.join("db").join(store::STORE_FILE)).unwrap();
+            store.index(&[dir.0.join(".claude")], false).unwrap();
+
+            let today = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+            let build = |granularity| {
+                dashboard(&store, &query("2025-06-01", "2025-06-30", granularity), None, &dir.0, &[], today)
+            };
+            let (daily, hourly) = (build("day").unwrap(), build("hour").unwrap());
+            let totals = &daily["totals"];
+            let column = |rows: &Value, key: &str| -> Vec<Value> {
+                rows.as_array().unwrap().iter().map(|row| row[key].clone()).collect()
+            };
+            let sum_i64 = |rows: &Value, key: &str| column(rows, key).iter().filter_map(Value::as_i64).sum::<i64>();
+            let sum_f64 = |rows: &Value, key: &str| column(rows, key).iter().filter_map(Value::as_f64).sum::<f64>();
+            let unique: BTreeSet<u32> = messages.iter().map(|message| message.0).collect();
+            prop_assert_eq!(totals["messages"].as_i64(), Some(unique.len() as i64));
+
+            for rows in [&daily["model_distribution"], &daily["timeline"]["data"], &hourly["timeline"]["data"]] {
+                prop_assert_eq!(totals["messages"].as_i64(), Some(sum_i64(rows, "messages")));
+                prop_assert_eq!(totals["tokens"].as_i64(), Some(sum_i64(rows, "tokens")));
+                prop_assert_eq!(totals["input_tokens"].as_i64(), Some(sum_i64(rows, "input_tokens")));
+                prop_assert!((totals["cost"].as_f64().unwrap() - sum_f64(rows, "cost")).abs() < 1e-6);
+            }
+            let list_cost = sum_f64(&daily["model_distribution"], "api_equivalent_cost");
+            prop_assert!((totals["api_equivalent_cost"].as_f64().unwrap() - list_cost).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,485 @@
+/// Scoped API tokens for the remote surfaces
+///
+/// The local HTTP server (`http_server`) only answers requests that carry a
+/// bearer token created with `create_api_token`. A token grants scopes:
+///
+/// - `read` - statistics: dashboards, sessions, projects, limits and the
+///   other read-only subcommands in `READ_COMMANDS`
+/// - `export` - reports, raw session and cost allocation exports and backups
+///   (`EXPORT_COMMANDS`)
+/// - `admin` - every subcommand, including the state-changing ones
+///
+/// A subcommand in neither list needs `admin`, so a new subcommand is never
+/// exposed to a read-only token by accident.
+///
+/// Tokens are stored in `~/.claude/db/command-center-api-tokens.json` as
+/// SHA-256 hashes; the secret is only returned once, by `create_api_token`.
+/// The file is read once and kept in memory; a request's `last_used_at` is
+/// written with the next save, at most once a minute and on app exit (`flush`).
+/// Creation, revocation and every request made with a token are appended to
+/// the audit log (`command-center-audit.jsonl`, shared with the Python
+/// backend) with the token's `token_id`.
+use std::fs;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::error::CommandError;
+use crate::schema::{self, Migration, Store};
+use crate::settings::db_dir;
+
+pub const API_TOKENS_FILE: &str = "command-center-api-tokens.json";
+
+/// Audit log of the Python backend (`audit_log.py`), one JSON object per line.
+pub const AUDIT_LOG_FILE: &str = "command-center-audit.jsonl";
+
+/// Prefix of token secrets, so a leaked token is recognizable.
+const SECRET_PREFIX: &str = "cc_";
+
+/// Read-only subcommands a `read` token may call.
+pub const READ_COMMANDS: &[&str] = &[
+    "dashboard",
+    "refresh-status",
+    "quarantined-lines",
+    "day",
+    "aggregate-history",
+    "model",
+    "session",
+    "session-files",
+    "sessions",
+    "limits",
+    "limit-impact",
+    "mcp-usage",
+    "hook-stats",
+    "effort-breakdown",
+    "concurrency-stats",
+    "outcome-stats",
+    "compaction-stats",
+    "usage-accounts",
+    "compare-accounts",
+    "account-dashboard",
+    "claude-accounts",
+    "budget-history",
+    "hooks",
+    "derived-metrics",
+    "available-metrics",
+    "metric-series",
+    "calendar-overlay",
+    "working-hours",
+    "out-of-hours-usage",
+    "cost-centers",
+    "cost-allocation",
+    "price-versions",
+    "snapshots",
+    "snapshot",
+    "diff-snapshots",
+    "projects",
+    "project-timeline",
+    "stale-projects",
+    "workspaces",
+    "timestamp-shifts",
+    "mirror-status",
+    "version",
+    "schema-info",
+];
+
+/// Export subcommands an `export` token may call.
+pub const EXPORT_COMMANDS: &[&str] = &[
+    "export-png",
+    "export-raw-sessions",
+    "export-cost-allocation",
+    "create-backup",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    Read,
+    Export,
+    Admin,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 3] = [Scope::Read, Scope::Export, Scope::Admin];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Export => "export",
+            Scope::Admin => "admin",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Self::ALL.into_iter().find(|scope| scope.as_str() == name).ok_or_else(|| {
+            let names: Vec<&str> = Self::ALL.iter().map(|scope| scope.as_str()).collect();
+            format!("Unknown scope: {} (expected {})", name, names.join(", "))
+        })
+    }
+}
+
+/// Scope a token needs to call a backend subcommand.
+pub fn required_scope(subcommand: &str) -> Scope {
+    if READ_COMMANDS.contains(&subcommand) {
+        Scope::Read
+    } else if EXPORT_COMMANDS.contains(&subcommand) {
+        Scope::Export
+    } else {
+        Scope::Admin
+    }
+}
+
+fn api_tokens_v1(_obj: &mut Map<String, Value>) {}
+
+pub const API_TOKENS_MIGRATIONS: &[Migration] = &[Migration {
+    to: 1,
+    description: "Add schema_version",
+    apply: api_tokens_v1,
+}];
+
+pub const API_TOKENS_STORE: Store = Store {
+    name: "api_tokens",
+    file: API_TOKENS_FILE,
+    migrations: API_TOKENS_MIGRATIONS,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub scopes: Vec<Scope>,
+    /// SHA-256 of the secret (hex)
+    pub hash: String,
+    /// First characters of the secret, to tell tokens apart
+    pub prefix: String,
+    pub created_at: String,
+    /// Never expires if unset
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    #[serde(default)]
+    pub revoked_at: Option<String>,
+    #[serde(default)]
+    pub last_used_at: Option<String>,
+}
+
+impl ApiToken {
+    pub fn allows(&self, scope: Scope) -> bool {
+        self.scopes.contains(&Scope::Admin) || self.scopes.contains(&scope)
+    }
+
+    fn expired(&self, now: DateTime<Local>) -> bool {
+        self.expires_at
+            .as_deref()
+            .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+            .is_some_and(|at| at <= now)
+    }
+
+    /// "active", "expired" or "revoked".
+    pub fn status(&self, now: DateTime<Local>) -> &'static str {
+        if self.revoked_at.is_some() {
+            "revoked"
+        } else if self.expired(now) {
+            "expired"
+        } else {
+            "active"
+        }
+    }
+
+    /// The token as listed to the UI: without its hash, with its status.
+    pub fn summary(&self, now: DateTime<Local>) -> Value {
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "scopes": self.scopes,
+            "prefix": self.prefix,
+            "created_at": self.created_at,
+            "expires_at": self.expires_at,
+            "revoked_at": self.revoked_at,
+            "last_used_at": self.last_used_at,
+            "status": self.status(now),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenStore {
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Oldest first
+    #[serde(default)]
+    pub tokens: Vec<ApiToken>,
+}
+
+/// Last-used times are written at most this often.
+const LAST_USED_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The token file in memory, with the state of its last-used times.
+struct Tokens {
+    store: TokenStore,
+    /// A last-used time is newer than the file
+    unsaved: bool,
+    last_saved: Option<Instant>,
+}
+
+impl Tokens {
+    /// Save the store, last-used times included.
+    fn save(&mut self) -> Result<(), String> {
+        self.store.save()?;
+        self.unsaved = false;
+        self.last_saved = Some(Instant::now());
+        Ok(())
+    }
+}
+
+/// Tokens loaded on first use; every read and change goes through them.
+static TOKENS: Mutex<Option<Tokens>> = Mutex::new(None);
+
+fn with_tokens<T>(f: impl FnOnce(&mut Tokens) -> T) -> T {
+    let mut tokens = TOKENS.lock().unwrap_or_else(|e| e.into_inner());
+    f(tokens.get_or_insert_with(|| Tokens {
+        store: TokenStore::load(),
+        unsaved: false,
+        last_saved: None,
+    }))
+}
+
+fn hash_secret(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn random_hex(bytes: usize) -> Result<String, String> {
+    let mut buf = vec![0u8; bytes];
+    getrandom::getrandom(&mut buf).map_err(|e| format!("No secure random source: {}", e))?;
+    Ok(buf.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+impl TokenStore {
+    /// Load tokens; a missing or unreadable file yields no tokens.
+    pub fn load() -> Self {
+        db_dir()
+            .ok()
+            .and_then(|dir| fs::read(dir.join(API_TOKENS_FILE)).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let dir = db_dir()?;
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let mut value = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        value["schema_version"] = Value::from(API_TOKENS_STORE.latest());
+        schema::write_json_atomic(&dir.join(API_TOKENS_FILE), &value)
+    }
+
+    /// Add a token; returns it with its secret (not stored).
+    pub fn create(
+        &mut self,
+        name: Option<String>,
+        mut scopes: Vec<Scope>,
+        expires_at: Option<DateTime<Local>>,
+        now: DateTime<Local>,
+    ) -> Result<(ApiToken, String), String> {
+        scopes.sort();
+        scopes.dedup();
+        let secret = format!("{}{}", SECRET_PREFIX, random_hex(32)?);
+        let token = ApiToken {
+            id: format!("tok_{}", random_hex(6)?),
+            name,
+            scopes,
+            hash: hash_secret(&secret),
+            prefix: secret[..SECRET_PREFIX.len() + 6].to_string(),
+            created_at: now.to_rfc3339(),
+            expires_at: expires_at.map(|at| at.to_rfc3339()),
+            revoked_at: None,
+            last_used_at: None,
+        };
+        self.tokens.push(token.clone());
+        Ok((token, secret))
+    }
+
+    /// Mark a token revoked (kept in the list for the audit trail).
+    pub fn revoke(&mut self, id: &str, now: DateTime<Local>) -> Result<ApiToken, CommandError> {
+        let token = self
+            .tokens
+            .iter_mut()
+            .find(|token| token.id == id)
+            .ok_or_else(|| CommandError::not_found(format!("Unknown API token: {}", id)))?;
+        if token.revoked_at.is_none() {
+            token.revoked_at = Some(now.to_rfc3339());
+        }
+        Ok(token.clone())
+    }
+
+    /// Index of the token with this secret if it may use `scope` at `now`.
+    pub fn check(&self, secret: Option<&str>, scope: Scope, now: DateTime<Local>) -> Result<usize, CommandError> {
+        let secret = secret.ok_or_else(|| CommandError::unauthorized("Missing API token"))?;
+        let hash = hash_secret(secret);
+        let index = self
+            .tokens
+            .iter()
+            .position(|token| token.hash == hash)
+            .ok_or_else(|| CommandError::unauthorized("Unknown API token"))?;
+        let token = &self.tokens[index];
+        match token.status(now) {
+            "active" => {}
+            status => return Err(CommandError::unauthorized(format!("API token {} is {}", token.id, status))),
+        }
+        if !token.allows(scope) {
+            return Err(CommandError::forbidden(
+                scope.as_str(),
+                format!("API token {} lacks the {} scope", token.id, scope.as_str()),
+            ));
+        }
+        Ok(index)
+    }
+}
+
+/// Check that `secret` may call `subcommand`, record its use and audit the request.
+pub fn authorize(secret: Option<&str>, subcommand: &str, request_id: &str) -> Result<ApiToken, CommandError> {
+    let now = Local::now();
+    let params = serde_json::json!({ "subcommand": subcommand });
+    // The token used, by id, and whether it may make the request
+    let (token_id, checked) = with_tokens(|tokens| match tokens.store.check(secret, required_scope(subcommand), now) {
+        Ok(index) => {
+            tokens.store.tokens[index].last_used_at = Some(now.to_rfc3339());
+            tokens.unsaved = true;
+            let token = tokens.store.tokens[index].clone();
+            if tokens.last_saved.map_or(true, |at| at.elapsed() >= LAST_USED_SAVE_INTERVAL) {
+                if let Err(e) = tokens.save() {
+                    log::warn!("[{}] Failed to record API token use: {}", request_id, e);
+                }
+            }
+            (Some(token.id.clone()), Ok(token))
+        }
+        Err(e) => {
+            // Known tokens get the refusal in their trail; unknown secrets are only logged
+            let token_id = secret
+                .map(hash_secret)
+                .and_then(|hash| tokens.store.tokens.iter().find(|token| token.hash == hash))
+                .map(|token| token.id.clone());
+            (token_id, Err(e))
+        }
+    });
+    let error = checked.as_ref().err().map(CommandError::message);
+    audit("api-request", params, token_id.as_deref(), request_id, error);
+    checked.map_err(|e| e.with_request_id(request_id))
+}
+
+/// The tokens, oldest first.
+pub fn list() -> Vec<ApiToken> {
+    with_tokens(|tokens| tokens.store.tokens.clone())
+}
+
+/// Write last-used times not saved yet; called when the app exits.
+pub fn flush() {
+    let mut tokens = TOKENS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(tokens) = tokens.as_mut().filter(|tokens| tokens.unsaved) {
+        if let Err(e) = tokens.save() {
+            log::warn!("Failed to record API token use: {}", e);
+        }
+    }
+}
+
+/// Create a token under the store lock; returns it with its secret.
+pub fn create(
+    name: Option<String>,
+    scopes: Vec<Scope>,
+    expires_at: Option<DateTime<Local>>,
+    request_id: &str,
+) -> Result<(ApiToken, String), CommandError> {
+    let (token, secret) = with_tokens(|tokens| {
+        let mut store = tokens.store.clone();
+        let created = store.create(name, scopes, expires_at, Local::now())?;
+        store.save()?;
+        tokens.store = store;
+        tokens.unsaved = false;
+        Ok::<_, CommandError>(created)
+    })?;
+    let params = serde_json::json!({ "scopes": token.scopes, "expires_at": token.expires_at, "name": token.name });
+    audit("create-api-token", params, Some(&token.id), request_id, None);
+    Ok((token, secret))
+}
+
+/// Revoke a token under the store lock.
+pub fn revoke(id: &str, request_id: &str) -> Result<ApiToken, CommandError> {
+    let token = with_tokens(|tokens| {
+        let mut store = tokens.store.clone();
+        let token = store.revoke(id, Local::now())?;
+        store.save()?;
+        tokens.store = store;
+        tokens.unsaved = false;
+        Ok::<_, CommandError>(token)
+    })?;
+    audit("revoke-api-token", serde_json::json!({}), Some(&token.id), request_id, None);
+    Ok(token)
+}
+
+/// Append an event to the audit log in the Python backend's format, plus
+/// `token_id`; a failing audit log never fails the request.
+fn audit(command: &str, params: Value, token_id: Option<&str>, request_id: &str, error: Option<&str>) {
+    let mut event = serde_json::json!({
+        "timestamp": Local::now().to_rfc3339(),
+        "request_id": request_id,
+        "command": command,
+        "params": params,
+        "status": if error.is_some() { "error" } else { "ok" },
+        "token_id": token_id,
+    });
+    if let Some(error) = error {
+        event["error"] = Value::from(error);
+    }
+    let written = db_dir().and_then(|dir| {
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(AUDIT_LOG_FILE))
+            .map_err(|e| e.to_string())?;
+        writeln!(file, "{}", event).map_err(|e| e.to_string())
+    });
+    if let Err(e) = written {
+        log::warn!("[{}] Failed to write the audit log: {}", request_id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_scope_defaults_to_admin() {
+        assert_eq!(required_scope("dashboard"), Scope::Read);
+        assert_eq!(required_scope("export-png"), Scope::Export);
+        assert_eq!(required_scope("restore-backup"), Scope::Admin);
+        assert_eq!(required_scope("some-new-subcommand"), Scope::Admin);
+        assert!(Scope::parse("write").is_err());
+    }
+
+    #[test]
+    fn test_check_scopes_expiry_and_revocation() {
+        let now = Local::now();
+        let mut store = TokenStore::default();
+        let (reader, read_secret) = store.create(None, vec![Scope::Read], None, now).unwrap();
+        let (_, admin_secret) = store.create(None, vec![Scope::Admin], Some(now + chrono::Duration::hours(1)), now).unwrap();
+        assert!(read_secret.starts_with(&reader.prefix));
+        assert_ne!(reader.hash, read_secret);
+
+        assert_eq!(store.check(Some(&read_secret), Scope::Read, now).unwrap(), 0);
+        assert!(matches!(store.check(Some(&read_secret), Scope::Export, now), Err(CommandError::Forbidden { .. })));
+        assert_eq!(store.check(Some(&admin_secret), Scope::Export, now).unwrap(), 1);
+        assert!(matches!(store.check(None, Scope::Read, now), Err(CommandError::Unauthorized { .. })));
+        assert!(matches!(store.check(Some("cc_guess"), Scope::Read, now), Err(CommandError::Unauthorized { .. })));
+
+        let later = now + chrono::Duration::hours(2);
+        assert!(matches!(store.check(Some(&admin_secret), Scope::Read, later), Err(CommandError::Unauthorized { .. })));
+        store.revoke(&reader.id, now).unwrap();
+        assert_eq!(store.tokens[0].status(now), "revoked");
+        assert!(store.check(Some(&read_secret), Scope::Read, now).is_err());
+    }
+}
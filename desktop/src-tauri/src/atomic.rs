@@ -0,0 +1,118 @@
+/// Atomic file writes
+///
+/// Data goes to a temporary file next to the target, is flushed to disk and
+/// renamed over the target, so a crash mid-write leaves either the old or the
+/// new file, never a truncated one. Used for settings, the app's JSON stores
+/// and export files; `move_atomic` puts a file written elsewhere (e.g. a PNG
+/// rendered by the backend into a scratch directory) in place the same way.
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Persist the rename itself (directories cannot be synced on Windows).
+#[cfg(unix)]
+fn sync_dir(dir: &Path) {
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) {}
+
+/// Atomically replace `path` with `bytes`, creating the parent directory.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Invalid file path: {}", path.display()))?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let tmp = dir.join(format!(
+        ".{}.{}.{}.tmp",
+        name,
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let written = File::create(&tmp)
+        .and_then(|mut file| {
+            file.write_all(bytes)?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&tmp, path));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp);
+        return Err(format!("Failed to write {}: {}", path.display(), e));
+    }
+    sync_dir(dir);
+    Ok(())
+}
+
+/// Atomically move `from` over `to`, creating the parent directory. A plain
+/// rename when both are on one filesystem, else a copy next to the target
+/// that is renamed over it; the source is removed either way.
+pub fn move_atomic(from: &Path, to: &Path) -> Result<(), String> {
+    let dir = to
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let name = to
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Invalid file path: {}", to.display()))?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    if fs::rename(from, to).is_err() {
+        let tmp = dir.join(format!(
+            ".{}.{}.{}.tmp",
+            name,
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let copied = fs::copy(from, &tmp)
+            .and_then(|_| File::open(&tmp)?.sync_all())
+            .and_then(|()| fs::rename(&tmp, to));
+        if let Err(e) = copied {
+            let _ = fs::remove_file(&tmp);
+            return Err(format!("Failed to move {} to {}: {}", from.display(), to.display(), e));
+        }
+        let _ = fs::remove_file(from);
+    }
+    sync_dir(dir);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replaces_file_without_leftovers() {
+        let dir = std::env::temp_dir().join(format!("cc-atomic-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("nested").join("settings.json");
+
+        write_atomic(&path, b"{\"a\": 1}").unwrap();
+        write_atomic(&path, b"{}").unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"{}");
+        let names: Vec<_> = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec!["settings.json"]);
+
+        let scratch = dir.join("report.png");
+        write_atomic(&scratch, b"png").unwrap();
+        move_atomic(&scratch, &dir.join("exports").join("report.png")).unwrap();
+        assert!(!scratch.exists());
+        assert_eq!(fs::read(dir.join("exports").join("report.png")).unwrap(), b"png");
+    }
+}
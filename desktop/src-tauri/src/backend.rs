@@ -0,0 +1,406 @@
+/// Backend abstraction of the command layer
+///
+/// Commands reach the backend through the `ApiBackend` held in managed state
+/// (`AppState::backend`) instead of calling `call_python_api` directly.
+/// `PythonBackend` is the Python bridge; `MockBackend` answers from JSON
+/// fixtures keyed by subcommand, so commands can be exercised without a
+/// Python install. Starting the app with `--demo` uses a `MockBackend` with
+/// the fixtures embedded from `fixtures/demo/`; `--demo=<dir>` reads them
+/// from a directory instead. `NativeBackend` wraps the Python bridge and
+/// answers the subcommands `ingest` implements from the session logs and the
+/// dashboard from the native store (`aggregate`) once it has indexed logs,
+/// with the response cache, project name blurring and privacy mode applied as
+/// on the Python path.
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+
+use crate::aggregate;
+use crate::error::CommandError;
+use crate::handshake;
+use crate::ingest;
+use crate::presentation::{self, PresentationMode};
+use crate::privacy;
+use crate::response_cache;
+use crate::response_schema;
+use crate::python_bridge::{
+    self, call_python_api, call_python_api_streaming, call_python_api_with_progress, ChunkSink, ProgressSink,
+};
+
+/// Command-line flag that starts the app on the demo fixtures.
+pub const DEMO_FLAG: &str = "--demo";
+
+/// Fixtures of `--demo` mode: (subcommand, response).
+const DEMO_FIXTURES: &[(&str, &str)] = &[
+    ("dashboard", include_str!("../fixtures/demo/dashboard.json")),
+    ("limits", include_str!("../fixtures/demo/limits.json")),
+    ("projects", include_str!("../fixtures/demo/projects.json")),
+    ("refresh", include_str!("../fixtures/demo/refresh.json")),
+    ("refresh-status", include_str!("../fixtures/demo/refresh-status.json")),
+    ("usage-accounts", include_str!("../fixtures/demo/usage-accounts.json")),
+    ("version", include_str!("../fixtures/demo/version.json")),
+    ("workspaces", include_str!("../fixtures/demo/workspaces.json")),
+];
+
+/// One backend call: `tauri_api` arguments (subcommand first) and the request ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiRequest {
+    pub request_id: String,
+    pub args: Vec<String>,
+}
+
+impl ApiRequest {
+    pub fn new(request_id: &str, args: &[&str]) -> Self {
+        Self {
+            request_id: request_id.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    pub fn subcommand(&self) -> &str {
+        self.args.first().map(String::as_str).unwrap_or("")
+    }
+
+    /// Value of `--name`, given as `--name=value` or `--name value`.
+    pub fn arg(&self, name: &str) -> Option<&str> {
+        let flag = format!("--{}", name);
+        let mut args = self.args.iter().skip(1);
+        while let Some(arg) = args.next() {
+            if *arg == flag {
+                return args.next().map(String::as_str);
+            }
+            if let Some(value) = arg.strip_prefix(&flag).and_then(|rest| rest.strip_prefix('=')) {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+/// Executes backend calls for the command layer.
+pub trait ApiBackend: Send + Sync {
+    fn call(&self, request: ApiRequest) -> Result<Value, CommandError>;
+
+    /// `call`, passing the refresh progress reported while the call runs to
+    /// `on_progress`. Backends without progress reports just call.
+    fn call_with_progress(&self, request: ApiRequest, on_progress: ProgressSink) -> Result<Value, CommandError> {
+        let _ = on_progress;
+        self.call(request)
+    }
+
+    /// `call` with a streamed result: the chunks of its list go to `on_chunk`
+    /// while the call runs (see `call_python_api_streaming`). Backends that
+    /// don't stream return the whole result.
+    fn call_streaming(&self, request: ApiRequest, on_chunk: ChunkSink) -> Result<Value, CommandError> {
+        let _ = on_chunk;
+        self.call(request)
+    }
+
+    /// Check that the backend speaks the API version this app expects
+    /// (see `handshake`). Backends without a version to check pass.
+    fn handshake(&self, request_id: &str) -> Result<(), CommandError> {
+        let _ = request_id;
+        Ok(())
+    }
+
+    /// Stop the backend's work before the app exits (see
+    /// `python_bridge::shutdown`). Backends without processes do nothing.
+    fn shutdown(&self) {}
+}
+
+/// Backend of the managed state.
+pub type SharedBackend = Arc<dyn ApiBackend>;
+
+/// The Python bridge (`call_python_api`). Calls other than `version` run
+/// the version handshake first.
+pub struct PythonBackend;
+
+impl PythonBackend {
+    fn ensure_compatible(request: &ApiRequest) -> Result<(), CommandError> {
+        if request.subcommand() == handshake::VERSION_SUBCOMMAND {
+            return Ok(());
+        }
+        handshake::ensure_compatible(&request.request_id)
+    }
+
+    /// The response, if it has the shape its subcommand promises (`response_schema`).
+    fn checked(request: &ApiRequest, response: Result<Value, CommandError>) -> Result<Value, CommandError> {
+        let response = response?;
+        response_schema::validate(request.subcommand(), &response).map_err(|e| e.with_request_id(&request.request_id))?;
+        Ok(response)
+    }
+}
+
+impl ApiBackend for PythonBackend {
+    fn call(&self, request: ApiRequest) -> Result<Value, CommandError> {
+        Self::ensure_compatible(&request)?;
+        let args: Vec<&str> = request.args.iter().map(String::as_str).collect();
+        Self::checked(&request, call_python_api(&request.request_id, &args))
+    }
+
+    fn call_with_progress(&self, request: ApiRequest, on_progress: ProgressSink) -> Result<Value, CommandError> {
+        Self::ensure_compatible(&request)?;
+        let args: Vec<&str> = request.args.iter().map(String::as_str).collect();
+        Self::checked(&request, call_python_api_with_progress(&request.request_id, &args, on_progress))
+    }
+
+    fn call_streaming(&self, request: ApiRequest, on_chunk: ChunkSink) -> Result<Value, CommandError> {
+        Self::ensure_compatible(&request)?;
+        let args: Vec<&str> = request.args.iter().map(String::as_str).collect();
+        Self::checked(&request, call_python_api_streaming(&request.request_id, &args, on_chunk))
+    }
+
+    fn handshake(&self, request_id: &str) -> Result<(), CommandError> {
+        handshake::ensure_compatible(request_id)
+    }
+
+    fn shutdown(&self) {
+        let killed = python_bridge::shutdown();
+        if killed > 0 {
+            log::info!("Killed {} Python process(es) on exit", killed);
+        }
+    }
+}
+
+/// Backend answering `ingest::NATIVE_SUBCOMMANDS` from the session logs when
+/// the `native_ingestion` flag is on, and the dashboard from the native store
+/// once it has indexed logs; either also when `inner` finds no Python backend
+/// to start. Every other call goes to `inner`.
+pub struct NativeBackend {
+    inner: SharedBackend,
+}
+
+impl NativeBackend {
+    pub fn new(inner: SharedBackend) -> Self {
+        Self { inner }
+    }
+}
+
+impl NativeBackend {
+    /// Answer `request` natively when it has a native implementation and
+    /// `call_inner` finds no Python backend, or without asking `call_inner`
+    /// when the `native_ingestion` flag is on, or for the dashboard, when the
+    /// native store has indexed logs. The dashboard of demo data mode stays
+    /// with Python, which has the data.
+    fn call_native(
+        &self,
+        request: ApiRequest,
+        call_inner: impl FnOnce(ApiRequest) -> Result<Value, CommandError>,
+    ) -> Result<Value, CommandError> {
+        type Answer = fn(&ApiRequest) -> Result<Value, CommandError>;
+        let (answer, native_first): (Answer, bool) = match request.subcommand() {
+            name if ingest::NATIVE_SUBCOMMANDS.contains(&name) => (ingest::answer, ingest::enabled()),
+            name if aggregate::NATIVE_SUBCOMMANDS.contains(&name) && !PresentationMode::load().demo_data => {
+                (aggregate::answer, aggregate::store_indexed())
+            }
+            _ => return call_inner(request),
+        };
+        if !native_first {
+            match call_inner(request.clone()) {
+                Err(CommandError::PythonMissing { .. }) => log::info!(
+                    "[{}] No Python backend; answering `{}` natively",
+                    request.request_id,
+                    request.subcommand()
+                ),
+                response => return response,
+            }
+        }
+        Self::answer_cached(&request, answer).map_err(|e| e.with_request_id(&request.request_id))
+    }
+
+    /// A native answer through the response cache, with project names blurred
+    /// and content stripped like Python responses (see `call_python`).
+    fn answer_cached(
+        request: &ApiRequest,
+        answer: fn(&ApiRequest) -> Result<Value, CommandError>,
+    ) -> Result<Value, CommandError> {
+        let args: Vec<&str> = request.args.iter().map(String::as_str).collect();
+        let mode = PresentationMode::load();
+        // Kept apart from the Python responses to the same arguments
+        let cache_key = response_cache::cache_key(&args, &mode.env()).map(|key| format!("native {}", key));
+        let cached = cache_key.as_deref().map(response_cache::lookup);
+        let mut json = match cached {
+            Some((Some(json), _)) => json,
+            _ => {
+                let mut json = answer(request)?;
+                if mode.blur_project_names {
+                    presentation::blur_projects(&mut json);
+                }
+                if let (Some(key), Some((_, generation))) = (cache_key, cached) {
+                    response_cache::store(key, &json, generation);
+                }
+                json
+            }
+        };
+        if privacy::enabled() {
+            privacy::scrub(&mut json);
+        }
+        Ok(json)
+    }
+}
+
+impl ApiBackend for NativeBackend {
+    fn call(&self, request: ApiRequest) -> Result<Value, CommandError> {
+        self.call_native(request, |request| self.inner.call(request))
+    }
+
+    fn call_with_progress(&self, request: ApiRequest, on_progress: ProgressSink) -> Result<Value, CommandError> {
+        self.call_native(request, |request| self.inner.call_with_progress(request, on_progress))
+    }
+
+    fn call_streaming(&self, request: ApiRequest, on_chunk: ChunkSink) -> Result<Value, CommandError> {
+        self.inner.call_streaming(request, on_chunk)
+    }
+
+    fn handshake(&self, request_id: &str) -> Result<(), CommandError> {
+        self.inner.handshake(request_id)
+    }
+
+    fn shutdown(&self) {
+        self.inner.shutdown()
+    }
+}
+
+/// Fixture-driven backend: every call of a subcommand returns its fixture
+/// (or error) regardless of the other arguments; calls are recorded.
+#[derive(Default)]
+pub struct MockBackend {
+    fixtures: BTreeMap<String, Result<Value, CommandError>>,
+    calls: Mutex<Vec<ApiRequest>>,
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer `subcommand` with `response`.
+    pub fn with_fixture(mut self, subcommand: &str, response: Value) -> Self {
+        self.fixtures.insert(subcommand.to_string(), Ok(response));
+        self
+    }
+
+    /// Fail `subcommand` with `error`.
+    #[cfg(test)]
+    pub fn with_error(mut self, subcommand: &str, error: CommandError) -> Self {
+        self.fixtures.insert(subcommand.to_string(), Err(error));
+        self
+    }
+
+    /// Fixtures of a directory: `<subcommand>.json` per subcommand.
+    pub fn from_dir(dir: &Path) -> Result<Self, String> {
+        let entries = std::fs::read_dir(dir).map_err(|e| format!("Cannot read fixtures in {}: {}", dir.display(), e))?;
+        let mut backend = Self::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(subcommand) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let text = std::fs::read_to_string(&path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+            let response = serde_json::from_str(&text).map_err(|e| format!("Invalid fixture {}: {}", path.display(), e))?;
+            backend = backend.with_fixture(subcommand, response);
+        }
+        Ok(backend)
+    }
+
+    /// Backend of `--demo` mode.
+    pub fn demo() -> Self {
+        DEMO_FIXTURES.iter().fold(Self::new(), |backend, (subcommand, json)| {
+            backend.with_fixture(subcommand, serde_json::from_str(json).expect("valid demo fixture"))
+        })
+    }
+
+    /// Calls received so far, oldest first.
+    #[cfg(test)]
+    pub fn calls(&self) -> Vec<ApiRequest> {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl ApiBackend for MockBackend {
+    fn call(&self, request: ApiRequest) -> Result<Value, CommandError> {
+        let fixture = self.fixtures.get(request.subcommand()).cloned();
+        let request_id = request.request_id.clone();
+        let subcommand = request.subcommand().to_string();
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).push(request);
+        fixture
+            .unwrap_or_else(|| Err(CommandError::not_found(format!("No fixture for `{}`", subcommand))))
+            .map_err(|e| e.with_request_id(&request_id))
+    }
+}
+
+/// Backend for the app's command-line arguments: the demo fixtures with
+/// `--demo` or `--demo=<dir>`, else the Python bridge (with native ingestion).
+pub fn from_args(args: impl IntoIterator<Item = String>) -> SharedBackend {
+    let Some(demo) = args.into_iter().find(|arg| arg.split('=').next() == Some(DEMO_FLAG)) else {
+        return Arc::new(NativeBackend::new(Arc::new(PythonBackend)));
+    };
+    let backend = match demo.split_once('=') {
+        Some((_, dir)) => MockBackend::from_dir(Path::new(dir)).unwrap_or_else(|e| {
+            log::warn!("{}; using the built-in demo fixtures", e);
+            MockBackend::demo()
+        }),
+        None => MockBackend::demo(),
+    };
+    log::info!("Demo mode: backend calls are answered from fixtures");
+    Arc::new(backend)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_mock_backend() {
+        let backend = MockBackend::new()
+            .with_fixture("projects", json!({"projects": []}))
+            .with_error("refresh", CommandError::busy("locked"));
+
+        assert_eq!(backend.call(ApiRequest::new("rq-1", &["projects", "--sort=name"])).unwrap(), json!({"projects": []}));
+        assert!(matches!(backend.call(ApiRequest::new("rq-2", &["refresh"])), Err(CommandError::Busy { .. })));
+        let missing = backend.call(ApiRequest::new("rq-3", &["dashboard"])).unwrap_err();
+        assert!(matches!(missing, CommandError::NotFound { request_id: Some(ref id), .. } if id == "rq-3"));
+
+        let subcommands: Vec<String> = backend.calls().iter().map(|c| c.subcommand().to_string()).collect();
+        assert_eq!(subcommands, vec!["projects", "refresh", "dashboard"]);
+        assert_eq!(backend.calls()[0].args[1], "--sort=name");
+    }
+
+    #[test]
+    fn test_native_backend() {
+        let inner = MockBackend::new()
+            .with_fixture("dashboard", json!({"totals": {}}))
+            .with_error("projects", CommandError::python_missing("No Python interpreter found"));
+        let backend = NativeBackend::new(Arc::new(inner));
+
+        assert_eq!(backend.call(ApiRequest::new("rq-1", &["dashboard"])).unwrap(), json!({"totals": {}}));
+        // Without Python the project list comes from the session logs
+        let projects = backend.call(ApiRequest::new("rq-2", &["projects", "--sort=name"])).unwrap();
+        assert_eq!((projects["source"].as_str(), projects["sort"].as_str()), (Some("native"), Some("name")));
+        assert!(projects["projects"].is_array());
+    }
+
+    #[test]
+    fn test_fixture_dir() {
+        let dir = std::env::temp_dir().join(format!("cc-fixtures-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("limits.json"), r#"[{"limit_type": "session"}]"#).unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let backend = MockBackend::from_dir(&dir).unwrap();
+        assert_eq!(backend.call(ApiRequest::new("rq", &["limits"])).unwrap()[0]["limit_type"], "session");
+        std::fs::write(dir.join("broken.json"), "{").unwrap();
+        assert!(MockBackend::from_dir(&dir).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // Every embedded demo fixture parses
+        let demo = MockBackend::demo();
+        assert!(demo.call(ApiRequest::new("rq", &["dashboard"])).unwrap()["totals"].is_object());
+    }
+}
@@ -0,0 +1,108 @@
+/// In-app changelog
+///
+/// `CHANGELOG.md` at the repository root is compiled into the app and parsed
+/// into releases: a `## [version]` (optionally `- date`) heading, `### Section`
+/// groups and `- ` items. `[Unreleased]` counts as newer than any version.
+use serde::Serialize;
+
+const CHANGELOG: &str = include_str!("../../../CHANGELOG.md");
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangelogSection {
+    pub title: String,
+    pub items: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Release {
+    pub version: String,
+    pub date: Option<String>,
+    pub sections: Vec<ChangelogSection>,
+}
+
+/// Numeric components of a version ("2.5.0-beta" → [2, 5, 0]); None for "Unreleased".
+pub fn version_key(version: &str) -> Option<Vec<u64>> {
+    let core = version.trim().trim_start_matches('v').split(['-', '+']).next()?;
+    core.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// True if `version` is newer than `since` (unparsable versions are always newer).
+pub fn is_newer(version: &str, since: &str) -> bool {
+    match (version_key(version), version_key(since)) {
+        (Some(version), Some(since)) => version > since,
+        _ => true,
+    }
+}
+
+pub fn parse(text: &str) -> Vec<Release> {
+    let mut releases: Vec<Release> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end();
+        if let Some(heading) = line.strip_prefix("## ") {
+            let (version, date) = match heading.split_once(" - ") {
+                Some((version, date)) => (version, Some(date.trim().to_string())),
+                None => (heading, None),
+            };
+            releases.push(Release {
+                version: version.trim().trim_matches(['[', ']']).to_string(),
+                date,
+                sections: Vec::new(),
+            });
+        } else if let Some(title) = line.strip_prefix("### ") {
+            if let Some(release) = releases.last_mut() {
+                release.sections.push(ChangelogSection {
+                    title: title.trim().to_string(),
+                    items: Vec::new(),
+                });
+            }
+        } else if let Some(item) = line.strip_prefix("- ") {
+            let Some(release) = releases.last_mut() else {
+                continue;
+            };
+            if release.sections.is_empty() {
+                release.sections.push(ChangelogSection {
+                    title: "Changes".to_string(),
+                    items: Vec::new(),
+                });
+            }
+            if let Some(section) = release.sections.last_mut() {
+                section.items.push(item.trim().to_string());
+            }
+        }
+    }
+    releases
+}
+
+/// Releases newer than `since_version` (all if None), newest first.
+pub fn releases_since(since_version: Option<&str>) -> Vec<Release> {
+    parse(CHANGELOG)
+        .into_iter()
+        .filter(|release| since_version.map_or(true, |since| is_newer(&release.version, since)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_releases_and_filter_by_version() {
+        let text = "# Changelog\n\n## [Unreleased]\n\n### Added\n- Flags\n\n## [2.5.0] - 2025-01-02\n- Desktop app\n\n## [2.4.10]\n### Fixed\n- Crash\n";
+        let releases = parse(text);
+        assert_eq!(releases.len(), 3);
+        assert_eq!(releases[1].date.as_deref(), Some("2025-01-02"));
+        assert_eq!(releases[1].sections[0].title, "Changes");
+        assert_eq!(releases[2].sections[0].items, vec!["Crash"]);
+
+        assert!(is_newer("2.5.0", "2.4.10"));
+        assert!(!is_newer("2.4.10", "2.5.0"));
+        assert!(is_newer("Unreleased", "2.5.0"));
+        assert!(!is_newer("2.5.0", "v2.5.0"));
+    }
+
+    #[test]
+    fn test_bundled_changelog_parses() {
+        let releases = releases_since(None);
+        assert!(releases.iter().any(|r| r.version == env!("CARGO_PKG_VERSION")));
+    }
+}
@@ -1,9 +1,266 @@
 /// Tauri command handlers
 ///
 /// This module defines all Tauri commands that the frontend can invoke.
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, State, Window};
 
-use crate::python_bridge::call_python_api;
+use crate::accounts::{combine_account_dashboards, MAX_COMBINED_ACCOUNTS};
+use crate::api_tokens;
+use crate::backend::{ApiRequest, SharedBackend};
+use crate::state::AppState;
+use crate::changelog;
+use crate::error::CommandError;
+use crate::etag::conditional;
+use crate::exports;
+use crate::features;
+use crate::handshake;
+use crate::http_server;
+use crate::indexer;
+use crate::middleware;
+use crate::plugins;
+use crate::presentation::{self, PresentationMode};
+use crate::privacy;
+use crate::python_bridge::{
+    bridge_metrics, cancel_request as cancel_bridge_request, CancelOnDrop,
+    diagnose_environment as diagnose_python_environment, resolve_request_id,
+    set_backend_log_events as set_bridge_log_events,
+};
+use crate::schedule::{self, ScheduleRule};
+use crate::schema;
+use crate::store::{self, Store};
+use crate::settings::Settings;
+use crate::telemetry;
+use crate::ranges::{self, RangeContext, RangePreset, ResolvedRange};
+use crate::report_templates;
+use crate::raw_session::{stream_file, RawFormat, RawSessionEvent, StreamStats, RAW_CHUNK_BYTES};
+
+/// Event emitted after a partial refresh with the ingested delta.
+pub const REFRESH_DELTA_EVENT: &str = "refresh-delta";
+
+/// Event emitted while a refresh runs: request_id, mode, state
+/// (running/done/failed), files_done, files_total and percent.
+pub const REFRESH_PROGRESS_EVENT: &str = "refresh-progress";
+
+/// Event with one chunk of a streamed result: request_id, key (the list it
+/// belongs to), seq (from 0; starts over when the call is retried) and items.
+pub const RESULT_CHUNK_EVENT: &str = "result-chunk";
+
+/// Event after the last chunk of a streamed call: request_id, ok, chunks and
+/// the error of a failed call.
+pub const RESULT_DONE_EVENT: &str = "result-done";
+
+/// Maximum number of ranges accepted by `get_dashboard_bundles`.
+const MAX_DASHBOARD_RANGES: usize = 8;
+
+/// Date range for multi-range dashboard requests.
+///
+/// `granularity` and `project_id` fall back to the values passed to the command.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardRange {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub granularity: Option<String>,
+    #[serde(default)]
+    pub project_id: Option<String>,
+}
+
+/// Vacation or holiday period of the calendar overlay (inclusive local dates).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarPeriod {
+    pub from: String,
+    pub to: String,
+    #[serde(default = "default_period_kind")]
+    pub kind: String,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+fn default_period_kind() -> String {
+    "vacation".to_string()
+}
+
+/// Messages selected by `shift_session_timestamps`.
+///
+/// Dates are local dates as currently stored (i.e. the wrong ones). A date
+/// range or session IDs are required; project and config directory narrow
+/// the selection further.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeShiftFilter {
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub session_ids: Vec<String>,
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub config_dir: Option<String>,
+}
+
+/// Section of the dashboard bundle; see the `sections` parameter of `get_dashboard_bundle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Section {
+    /// totals, trends, usage_accounts
+    Totals,
+    /// timeline, daily_activity, derived metrics, heatmap, plugins
+    Timeline,
+    /// model_distribution
+    Models,
+    /// hourly_profile
+    Hourly,
+    /// recent_sessions
+    Sessions,
+}
+
+impl Section {
+    fn as_str(self) -> &'static str {
+        match self {
+            Section::Totals => "totals",
+            Section::Timeline => "timeline",
+            Section::Models => "models",
+            Section::Hourly => "hourly",
+            Section::Sessions => "sessions",
+        }
+    }
+}
+
+/// Whether `section` is requested (no selection means all sections).
+fn wants_section(sections: Option<&[Section]>, section: Section) -> bool {
+    sections.map_or(true, |s| s.is_empty() || s.contains(&section))
+}
+
+/// Filter for `list_sessions`.
+///
+/// `sort` is one of cost (default), tokens, messages, recent or oldest.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionFilter {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub search: Option<String>,
+    #[serde(default)]
+    pub sort: Option<String>,
+}
+
+/// Build `dashboard` subcommand arguments.
+fn dashboard_args(
+    from: String,
+    to: String,
+    refresh: bool,
+    granularity: String,
+    project_id: Option<String>,
+    workspace_id: Option<String>,
+    sections: Option<&[Section]>,
+) -> Vec<String> {
+    let refresh_str = if refresh { "1" } else { "0" };
+
+    let mut args = vec![
+        "dashboard".to_string(),
+        "--from".to_string(),
+        from,
+        "--to".to_string(),
+        to,
+        "--refresh".to_string(),
+        refresh_str.to_string(),
+        "--granularity".to_string(),
+        granularity,
+    ];
+
+    if let Some(pid) = project_id {
+        args.push(format!("--project-id={}", pid));
+    }
+    if let Some(wid) = workspace_id {
+        args.push(format!("--workspace-id={}", wid));
+    }
+    if let Some(sections) = sections.filter(|s| !s.is_empty()) {
+        let names: Vec<&str> = sections.iter().map(|s| s.as_str()).collect();
+        args.push(format!("--sections={}", names.join(",")));
+    }
+
+    args
+}
+
+/// Add the output of enabled metric plugins to a dashboard bundle as `plugins`.
+///
+/// Nothing is added when no plugin is enabled; a failed record query is
+/// reported as `plugins.error`.
+fn attach_plugin_metrics(
+    backend: &SharedBackend,
+    request_id: &str,
+    bundle: &mut Value,
+    from: &str,
+    to: &str,
+    project_id: Option<&str>,
+) {
+    let Ok(dir) = plugins::plugin_dir() else {
+        return;
+    };
+    let enabled = plugins::enabled_plugins(&dir);
+    if enabled.is_empty() {
+        return;
+    }
+
+    let mut args = vec![
+        "plugin-records".to_string(),
+        "--from".to_string(),
+        from.to_string(),
+        "--to".to_string(),
+        to.to_string(),
+    ];
+    if let Some(pid) = project_id {
+        args.push(format!("--project-id={}", pid));
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = match backend.call(ApiRequest::new(request_id, &args_refs)) {
+        Ok(records) => plugins::run_plugins(&enabled, &records),
+        Err(e) => serde_json::json!({ "error": e.to_string() }),
+    };
+    if let Some(object) = bundle.as_object_mut() {
+        object.insert("plugins".to_string(), output);
+    }
+}
+
+/// Run a command's backend work on a blocking thread.
+///
+/// Backend calls wait for Python (up to their timeout) and for a free
+/// concurrency slot; running them here keeps those waits off the async
+/// runtime's threads. If the command's future is dropped first (the app
+/// shuts down or the invoke is abandoned), the calls of `request_id` are
+/// cancelled (`CancelOnDrop`).
+async fn run_blocking<T: Send + 'static>(
+    backend: &SharedBackend,
+    request_id: &str,
+    work: impl FnOnce(&SharedBackend) -> Result<T, CommandError> + Send + 'static,
+) -> Result<T, CommandError> {
+    let cancel = CancelOnDrop::new(request_id);
+    let backend = backend.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || work(&backend))
+        .await
+        .map_err(|e| format!("Backend task failed: {}", e))?;
+    cancel.disarm();
+    result
+}
+
+/// Make one backend call from a command (see `run_blocking`).
+async fn call_backend(backend: &SharedBackend, request_id: &str, args: &[&str]) -> Result<Value, CommandError> {
+    let request = ApiRequest::new(request_id, args);
+    run_blocking(backend, request_id, move |backend| backend.call(request)).await
+}
 
 // ============================================================================
 // Tauri Commands
@@ -21,47 +278,386 @@ use crate::python_bridge::call_python_api;
 /// * `refresh` - Whether to perform incremental update
 /// * `granularity` - Timeline granularity (month/week/day)
 /// * `project_id` - Optional project filter
+/// * `workspace_id` - Optional workspace filter (all its projects, or `project_id` within it)
+/// * `sections` - Sections to compute (totals, timeline, models, hourly, sessions);
+///   default all. Keys of other sections are left out, which saves backend time
+///   for narrow views.
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// While `refresh` reindexes logs, `refresh-progress` events report files
+/// scanned and percent done.
 ///
 /// # Returns
 ///
 /// JSON object containing:
 /// - range: date range
-/// - totals: aggregate statistics
+/// - totals: aggregate statistics, including api_equivalent_cost and the
+///   prorated subscription_cost / subscription_savings comparison
 /// - daily_activity: daily message counts
 /// - timeline: time-series data grouped by granularity
 /// - model_distribution: per-model statistics
 /// - hourly_profile: 24-hour activity profile
 /// - recent_sessions: latest sessions
+/// - usage_accounts: per-account usage snapshot totals for the range
+/// - daily_derived / timeline.data[].derived: values of user-defined derived metrics
+/// - plugins: named series per enabled metric plugin (only if any is enabled;
+///   left out for whole-workspace views)
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn get_dashboard_bundle(
+    app: AppHandle,
+    state: State<'_, AppState>,
     from: String,
     to: String,
     refresh: bool,
     granularity: String,
     project_id: Option<String>,
-) -> Result<Value, String> {
+    workspace_id: Option<String>,
+    sections: Option<Vec<Section>>,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
     eprintln!("[Rust] get_dashboard_bundle received project_id: {:?}", project_id);
-    let refresh_str = if refresh { "1" } else { "0" };
-
-    let mut args = vec![
-        "dashboard".to_string(),
-        "--from".to_string(),
-        from,
-        "--to".to_string(),
-        to,
-        "--refresh".to_string(),
-        refresh_str.to_string(),
-        "--granularity".to_string(),
+    // Plugin records are filtered by one project only
+    let with_plugins = wants_section(sections.as_deref(), Section::Timeline)
+        && (workspace_id.is_none() || project_id.is_some());
+    let args = dashboard_args(
+        from.clone(),
+        to.clone(),
+        refresh,
         granularity,
-    ];
+        project_id.clone(),
+        workspace_id,
+        sections.as_deref(),
+    );
 
-    if let Some(pid) = project_id {
-        args.push(format!("--project-id={}", pid));
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    eprintln!("[Rust] get_dashboard_bundle args: {:?}", args_refs);
+    let request = ApiRequest::new(&request_id, &args_refs);
+    let result = run_blocking(&state.backend, &request_id, move |backend| {
+        let request_id = request.request_id.clone();
+        let mut bundle = call_with_refresh_progress(backend, &app, request)?;
+        if with_plugins {
+            attach_plugin_metrics(backend, &request_id, &mut bundle, &from, &to, project_id.as_deref());
+        }
+        Ok(bundle)
+    })
+    .await;
+    conditional(result, if_none_match)
+}
+
+/// Get dashboard bundles for several ranges at once (e.g. current + previous period).
+///
+/// Data is refreshed once up front, then all ranges are queried concurrently
+/// against the same database.
+///
+/// # Arguments
+///
+/// * `ranges` - Ranges to query (max 8), each with optional granularity/project override
+/// * `refresh` - Whether to perform one incremental update before querying
+/// * `granularity` - Default timeline granularity (month/week/day/hour)
+/// * `project_id` - Default project filter
+/// * `workspace_id` - Workspace filter for every range (see `get_dashboard_bundle`)
+/// * `sections` - Sections to compute for every range (default all, see `get_dashboard_bundle`)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON array of dashboard bundles in the order of `ranges`; `meta.updated_files`
+/// reports the shared refresh, whose progress is emitted as `refresh-progress` events.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_dashboard_bundles(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    ranges: Vec<DashboardRange>,
+    refresh: bool,
+    granularity: String,
+    project_id: Option<String>,
+    workspace_id: Option<String>,
+    sections: Option<Vec<Section>>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    if ranges.len() > MAX_DASHBOARD_RANGES {
+        return Err(CommandError::invalid_argument(format!(
+            "Too many ranges: {} (max {})",
+            ranges.len(),
+            MAX_DASHBOARD_RANGES
+        )));
+    }
+    // Dropped with the command: the blocking tasks below would run on
+    let cancel = CancelOnDrop::new(&request_id);
+
+    let updated_files = if refresh {
+        let refresh_id = request_id.clone();
+        let backend = state.backend.clone();
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            call_with_refresh_progress(&backend, &app, ApiRequest::new(&refresh_id, &["refresh"]))
+        })
+        .await
+        .map_err(|e| format!("Refresh task failed: {}", e))??;
+        result.get("updated_files").cloned().unwrap_or(Value::from(0))
+    } else {
+        Value::from(0)
+    };
+
+    let handles: Vec<_> = ranges
+        .into_iter()
+        .map(|range| {
+            let range_project = range.project_id.or_else(|| project_id.clone());
+            let args = dashboard_args(
+                range.from.clone(),
+                range.to.clone(),
+                false,
+                range.granularity.unwrap_or_else(|| granularity.clone()),
+                range_project.clone(),
+                workspace_id.clone(),
+                sections.as_deref(),
+            );
+            let request_id = request_id.clone();
+            let backend = state.backend.clone();
+            let with_plugins = wants_section(sections.as_deref(), Section::Timeline)
+                && (workspace_id.is_none() || range_project.is_some());
+            tauri::async_runtime::spawn_blocking(move || {
+                let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+                let mut bundle = backend.call(ApiRequest::new(&request_id, &args_refs))?;
+                if with_plugins {
+                    attach_plugin_metrics(
+                        &backend,
+                        &request_id,
+                        &mut bundle,
+                        &range.from,
+                        &range.to,
+                        range_project.as_deref(),
+                    );
+                }
+                Ok::<Value, CommandError>(bundle)
+            })
+        })
+        .collect();
+
+    let mut bundles = Vec::with_capacity(handles.len());
+    for handle in handles {
+        let mut bundle = handle
+            .await
+            .map_err(|e| format!("Dashboard task failed: {}", e))??;
+        if let Some(meta) = bundle.get_mut("meta").and_then(Value::as_object_mut) {
+            meta.insert("updated_files".to_string(), updated_files.clone());
+        }
+        bundles.push(bundle);
+    }
+
+    cancel.disarm();
+    Ok(Value::Array(bundles))
+}
+
+/// Call the backend, emitting the refresh progress it reports as
+/// `refresh-progress` events tagged with the request ID.
+fn call_with_refresh_progress(
+    backend: &SharedBackend,
+    app: &AppHandle,
+    request: ApiRequest,
+) -> Result<Value, CommandError> {
+    let request_id = request.request_id.clone();
+    let emit = |mut event: Value| {
+        if let Some(obj) = event.as_object_mut() {
+            obj.insert("request_id".to_string(), Value::from(request_id.as_str()));
+        }
+        if let Err(e) = app.emit(REFRESH_PROGRESS_EVENT, &event) {
+            log::warn!("[{}] Failed to emit {}: {}", request_id, REFRESH_PROGRESS_EVENT, e);
+        }
+    };
+    backend.call_with_progress(request, &emit)
+}
+
+/// Call the backend with a streamed result, emitting its chunks as
+/// `result-chunk` events and a `result-done` event when the call ends, all
+/// tagged with the request ID.
+fn call_with_result_chunks(
+    backend: &SharedBackend,
+    app: &AppHandle,
+    request: ApiRequest,
+) -> Result<Value, CommandError> {
+    let request_id = request.request_id.clone();
+    let chunks = std::cell::Cell::new(0usize);
+    let emit = |mut chunk: Value| {
+        if let Some(obj) = chunk.as_object_mut() {
+            obj.insert("request_id".to_string(), Value::from(request_id.as_str()));
+        }
+        chunks.set(chunks.get() + 1);
+        if let Err(e) = app.emit(RESULT_CHUNK_EVENT, &chunk) {
+            log::warn!("[{}] Failed to emit {}: {}", request_id, RESULT_CHUNK_EVENT, e);
+        }
+    };
+    let result = backend.call_streaming(request, &emit);
+    let done = serde_json::json!({
+        "request_id": request_id,
+        "ok": result.is_ok(),
+        "chunks": chunks.get(),
+        "error": result.as_ref().err(),
+    });
+    if let Err(e) = app.emit(RESULT_DONE_EVENT, &done) {
+        log::warn!("[{}] Failed to emit {}: {}", request_id, RESULT_DONE_EVENT, e);
     }
+    result
+}
+
+/// Run a partial refresh and emit its delta as a `refresh-delta` event.
+fn refresh_partial(backend: &SharedBackend, app: &AppHandle, request: ApiRequest) -> Result<Value, CommandError> {
+    let request_id = request.request_id.clone();
+    let mut result = call_with_refresh_progress(backend, app, request)?;
+    if let Some(obj) = result.as_object_mut() {
+        obj.insert("request_id".to_string(), Value::from(request_id.as_str()));
+    }
+    if let Err(e) = app.emit(REFRESH_DELTA_EVENT, &result) {
+        log::warn!("[{}] Failed to emit {}: {}", request_id, REFRESH_DELTA_EVENT, e);
+    }
+    Ok(result)
+}
+
+/// Ingest only today's changes (fast path instead of a full refresh).
+///
+/// Reads new/modified files touched since local midnight, skipping older lines
+/// of already tracked files, and patches only the affected aggregates.
+///
+/// # Arguments
+///
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object (also emitted as `refresh-delta` event) containing:
+/// - mode: "partial"
+/// - since: start of the refresh window (ISO)
+/// - updated_files: number of files read
+/// - affected_dates: dates whose aggregates were patched
+/// - delta: messages, tokens, cost added since the window start
+#[tauri::command]
+pub async fn refresh_today(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let request = ApiRequest::new(&request_id, &["refresh", "--today", "1"]);
+    run_blocking(&state.backend, &request_id, move |backend| refresh_partial(backend, &app, request)).await
+}
+
+/// Ingest only changes since a timestamp (or since the last scan watermark).
+///
+/// # Arguments
+///
+/// * `since` - ISO timestamp (naive values are local time); None uses the last scan watermark
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// Same as `refresh_today`; also emitted as `refresh-delta` event.
+#[tauri::command]
+pub async fn refresh_since(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    since: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let since_arg = match since {
+        Some(ts) => format!("--since={}", ts),
+        None => "--since-watermark=1".to_string(),
+    };
+    let request = ApiRequest::new(&request_id, &["refresh", &since_arg]);
+    run_blocking(&state.backend, &request_id, move |backend| refresh_partial(backend, &app, request)).await
+}
+
+/// Get data freshness and the progress of a running refresh.
+///
+/// Refreshes record their progress in a status file, so this can be polled
+/// while another request refreshes.
+///
+/// # Arguments
+///
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - state: idle, running, failed or stale (running without progress for 10 minutes)
+/// - current: mode, started_at, files_done, files_total of the running refresh
+/// - last_success / last_error: last finished and last failed refresh
+/// - data_current_as_of: finish time of the last successful refresh
+/// - pending_files: new or modified files not yet ingested
+/// - quarantined: counts of malformed lines set aside (lines, files; see `get_quarantined_lines`)
+/// - roots: per data root files, pending_files, tracked_files, last_scanned_at, newest_file_at
+/// - lock: holder of the ingestion lock (hostname, pid, heartbeat_age_seconds, stale) or null
+#[tauri::command]
+pub async fn get_refresh_status(
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["refresh-status"]).await
+}
 
+/// Get malformed JSONL lines that were skipped (quarantined) during ingestion.
+///
+/// # Arguments
+///
+/// * `limit` - Maximum number of lines (default: 100)
+/// * `source_file` - Only lines of this session file
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - total: number of matching quarantined lines
+/// - files: number of files they come from
+/// - lines: newest first, with source_file, line_number, error, content (truncated), quarantined_at
+#[tauri::command]
+pub async fn get_quarantined_lines(
+    state: State<'_, AppState>,
+    limit: Option<u32>,
+    source_file: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let limit_arg = format!("--limit={}", limit.unwrap_or(100));
+    let mut args = vec!["quarantined-lines".to_string(), limit_arg];
+    if let Some(file) = source_file {
+        args.push(format!("--file={}", file));
+    }
     let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-    eprintln!("[Rust] get_dashboard_bundle args: {:?}", args_refs);
-    call_python_api(&args_refs)
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Get every recorded version of a day's totals, to see when and why they changed.
+///
+/// A version is appended whenever a recomputation (ingest, reprice, merge,
+/// time shift or its undo) leaves the day's totals different from before;
+/// the history is never rewritten.
+///
+/// # Arguments
+///
+/// * `date` - Local date (YYYY-MM-DD)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - date: the queried date
+/// - current: latest messages, sessions, tokens and cost (null if never recorded)
+/// - history: versions oldest first, each with recorded_at, reason, the totals
+///   and their delta to the previous version
+#[tauri::command]
+pub async fn get_aggregate_history(
+    state: State<'_, AppState>,
+    date: String,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["aggregate-history", "--date", &date]).await
 }
 
 /// Get detailed statistics for a specific day.
@@ -70,6 +666,8 @@ pub async fn get_dashboard_bundle(
 ///
 /// * `date` - Date (YYYY-MM-DD)
 /// * `project_id` - Optional project filter
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
 ///
 /// # Returns
 ///
@@ -80,7 +678,14 @@ pub async fn get_dashboard_bundle(
 /// - models: model distribution for the day
 /// - sessions: sessions active on the day
 #[tauri::command]
-pub async fn get_day_details(date: String, project_id: Option<String>) -> Result<Value, String> {
+pub async fn get_day_details(
+    state: State<'_, AppState>,
+    date: String,
+    project_id: Option<String>,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
     let mut args = vec!["day".to_string(), "--date".to_string(), date];
 
     if let Some(pid) = project_id {
@@ -89,7 +694,7 @@ pub async fn get_day_details(date: String, project_id: Option<String>) -> Result
 
     let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
     eprintln!("[Rust] get_dashboard_bundle args: {:?}", args_refs);
-    call_python_api(&args_refs)
+    conditional(call_backend(&state.backend, &request_id, &args_refs).await, if_none_match)
 }
 
 /// Get detailed statistics for a specific model.
@@ -100,6 +705,8 @@ pub async fn get_day_details(date: String, project_id: Option<String>) -> Result
 /// * `from` - Start date (YYYY-MM-DD)
 /// * `to` - End date (YYYY-MM-DD)
 /// * `project_id` - Optional project filter
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
 ///
 /// # Returns
 ///
@@ -107,16 +714,20 @@ pub async fn get_day_details(date: String, project_id: Option<String>) -> Result
 /// - model: model identifier
 /// - display_name: formatted model name
 /// - range: date range
-/// - totals: aggregate statistics
+/// - totals: aggregate statistics, including api_equivalent_cost
 /// - daily_activity: daily breakdown
 /// - sessions: top sessions for this model
 #[tauri::command]
 pub async fn get_model_details(
+    state: State<'_, AppState>,
     model: String,
     from: String,
     to: String,
     project_id: Option<String>,
-) -> Result<Value, String> {
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
     let mut args = vec![
         "model".to_string(),
         "--model".to_string(),
@@ -133,7 +744,7 @@ pub async fn get_model_details(
 
     let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
     eprintln!("[Rust] get_dashboard_bundle args: {:?}", args_refs);
-    call_python_api(&args_refs)
+    conditional(call_backend(&state.backend, &request_id, &args_refs).await, if_none_match)
 }
 
 /// Get detailed statistics for a specific session.
@@ -142,6 +753,10 @@ pub async fn get_model_details(
 ///
 /// * `session_id` - Session identifier
 /// * `project_id` - Optional project filter
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+///   (ignored when streaming)
+/// * `stream` - Send `messages` as `result-chunk` events instead of in the response
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
 ///
 /// # Returns
 ///
@@ -152,12 +767,20 @@ pub async fn get_model_details(
 /// - date: session date
 /// - first_time/last_time: session timestamps
 /// - totals: aggregate statistics
-/// - messages: individual message breakdowns
+/// - messages: individual message breakdowns (empty when streamed)
+/// - compactions: count, auto/manual counts, tokens freed and events
+/// - streamed: key, chunks and items of a streamed list (only when streamed)
 #[tauri::command]
 pub async fn get_session_details(
+    app: AppHandle,
+    state: State<'_, AppState>,
     session_id: String,
     project_id: Option<String>,
-) -> Result<Value, String> {
+    if_none_match: Option<String>,
+    stream: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
     let mut args = vec!["session".to_string(), "--id".to_string(), session_id];
 
     if let Some(pid) = project_id {
@@ -166,125 +789,4360 @@ pub async fn get_session_details(
 
     let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
     eprintln!("[Rust] get_dashboard_bundle args: {:?}", args_refs);
-    call_python_api(&args_refs)
+    if stream.unwrap_or(false) {
+        let request = ApiRequest::new(&request_id, &args_refs);
+        return run_blocking(&state.backend, &request_id, move |backend| call_with_result_chunks(backend, &app, request))
+            .await;
+    }
+    conditional(call_backend(&state.backend, &request_id, &args_refs).await, if_none_match)
 }
 
-/// Get limit reset events for a date range.
+/// Stream the raw JSONL of a session through a channel.
+///
+/// The session's files (main file first, then subagent files) are located by
+/// the backend and read in chunks of about 256 KB ending on line boundaries.
+/// Channel messages are `started`, then `lines` (format "jsonl") or `records`
+/// (format "json"), then `finished`. In privacy mode message content is
+/// stripped from every line and record.
 ///
 /// # Arguments
 ///
-/// * `from` - Start date (YYYY-MM-DD)
-/// * `to` - End date (YYYY-MM-DD)
+/// * `session_id` - Session identifier
+/// * `format` - "jsonl" (default, lines as written) or "json" (parsed records of this session)
+/// * `on_chunk` - Channel receiving `RawSessionEvent` messages
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
 ///
 /// # Returns
 ///
-/// JSON array containing limit reset events with:
-/// - limit_type: type of limit ('5-hour', 'session', 'spending_cap', 'context')
-/// - reset_at: ISO timestamp when limit resets
-/// - reset_text: original reset text (e.g., "resets 12am")
-/// - summary: full summary text
-/// - year: year of the event
-/// - date: date of the event (YYYY-MM-DD)
+/// JSON object containing:
+/// - session_id, format
+/// - files: streamed file paths
+/// - bytes, lines: amount of data read
+/// - skipped_lines: malformed or foreign-session lines (format "json"; malformed lines in privacy mode)
 #[tauri::command]
-pub async fn get_limit_resets(from: String, to: String) -> Result<Value, String> {
-    call_python_api(&["limits", "--from", &from, "--to", &to])
+pub async fn get_raw_session(
+    state: State<'_, AppState>,
+    session_id: String,
+    format: Option<String>,
+    on_chunk: Channel<RawSessionEvent>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let format = RawFormat::parse(format.as_deref())?;
+    let located = call_backend(&state.backend, &request_id, &["session-files", "--id", &session_id]).await?;
+
+    let files: Vec<PathBuf> = located
+        .get("files")
+        .and_then(Value::as_array)
+        .map(|files| {
+            files
+                .iter()
+                .filter_map(|f| f.get("path").and_then(Value::as_str))
+                .map(PathBuf::from)
+                .collect()
+        })
+        .unwrap_or_default();
+    if files.is_empty() {
+        let error = CommandError::not_found(format!("No raw files found for session {}", session_id));
+        return Err(error.with_request_id(&request_id));
+    }
+
+    let stream_id = session_id.clone();
+    let stream_files = files.clone();
+    let redact = privacy::enabled();
+    let stats = tauri::async_runtime::spawn_blocking(move || -> Result<StreamStats, String> {
+        let mut send = |event: RawSessionEvent| on_chunk.send(event).map_err(|e| e.to_string());
+        send(RawSessionEvent::Started {
+            session_id: stream_id.clone(),
+            files: stream_files.iter().map(|p| p.display().to_string()).collect(),
+            total_bytes: stream_files
+                .iter()
+                .filter_map(|p| std::fs::metadata(p).ok())
+                .map(|m| m.len())
+                .sum(),
+        })?;
+
+        let mut stats = StreamStats::default();
+        for path in &stream_files {
+            stream_file(path, &stream_id, format, redact, RAW_CHUNK_BYTES, &mut stats, &mut send)?;
+        }
+        send(RawSessionEvent::Finished {
+            bytes: stats.bytes,
+            lines: stats.lines,
+            skipped_lines: stats.skipped_lines,
+        })?;
+        Ok(stats)
+    })
+    .await
+    .map_err(|e| {
+        CommandError::internal(format!("Raw session task failed: {}", e)).with_request_id(&request_id)
+    })?
+    .map_err(|e| CommandError::internal(e).with_request_id(&request_id))?;
+
+    Ok(serde_json::json!({
+        "session_id": session_id,
+        "format": if format == RawFormat::Json { "json" } else { "jsonl" },
+        "files": files.iter().map(|p| p.display().to_string()).collect::<Vec<_>>(),
+        "bytes": stats.bytes,
+        "lines": stats.lines,
+        "skipped_lines": stats.skipped_lines,
+    }))
 }
 
-/// Export PNG usage report for a date range.
+/// Run a backend export into `target_path` or a path picked in the save dialog.
+fn export_to_file(
+    backend: &SharedBackend,
+    app: &AppHandle,
+    request_id: &str,
+    kind: exports::ExportKind,
+    params: Value,
+    target_path: Option<String>,
+) -> Result<Value, CommandError> {
+    let suggested = exports::default_filename(kind, &params);
+    let Some(path) = exports::choose_target(app, target_path, &suggested)
+        .map_err(|e| CommandError::internal(e).with_request_id(request_id))?
+    else {
+        return Ok(serde_json::json!({ "cancelled": true }));
+    };
+    let (mut result, written) =
+        exports::run_export(backend, request_id, kind, &params, &path).map_err(|e| e.with_request_id(request_id))?;
+    if let Some(obj) = result.as_object_mut() {
+        obj.insert("path".to_string(), Value::from(written.path));
+        obj.insert("size".to_string(), Value::from(written.size));
+        obj.insert("sha256".to_string(), Value::from(written.sha256));
+    }
+    Ok(result)
+}
+
+/// Bundle the raw JSONL files of sessions into a zip archive.
+///
+/// The archive holds one folder per project plus a `manifest.json` with
+/// SHA-256 checksums, and can be imported elsewhere with
+/// `import_session_archive`.
 ///
 /// # Arguments
 ///
-/// * `from` - Start date (YYYY-MM-DD)
-/// * `to` - End date (YYYY-MM-DD)
+/// * `session_ids` - Sessions to export
+/// * `output_path` - Path of the zip file to write (relative to the export directory);
+///   the save dialog opens if omitted
+/// * `redact` - Strip conversation content (prompts, responses, tool I/O, paths); default false,
+///   always on in privacy mode
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
 ///
 /// # Returns
 ///
 /// JSON object containing:
-/// - filename: suggested filename for the PNG
-/// - data: base64-encoded PNG image data
-/// - size: size of PNG in bytes
-/// - mime_type: "image/png"
+/// - path: written archive
+/// - size, sha256: archive size and checksum
+/// - sessions, files: number of exported sessions and files
+/// - redacted: whether content was stripped
+/// - missing_files: source files that no longer exist on disk
+///
+/// or `{cancelled: true}` if the dialog was cancelled
 #[tauri::command]
-pub async fn export_png_report(from: String, to: String) -> Result<Value, String> {
-    call_python_api(&["export-png", "--from", &from, "--to", &to])
+pub async fn export_raw_sessions(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    session_ids: Vec<String>,
+    output_path: Option<String>,
+    redact: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    if session_ids.is_empty() {
+        return Err(CommandError::invalid_argument("No sessions selected for export").with_request_id(&request_id));
+    }
+    let params = serde_json::json!({ "session_ids": session_ids, "redact": redact.unwrap_or(false) });
+    let call_id = request_id.clone();
+    run_blocking(&state.backend, &request_id, move |backend| {
+        export_to_file(backend, &app, &call_id, exports::ExportKind::RawSessions, params, output_path)
+    })
+    .await
 }
 
-/// Get all projects with metadata.
+/// Import a teammate's session archive (from `export_raw_sessions`).
 ///
-/// # Returns
+/// The manifest and checksums are validated before anything is written.
+/// Messages already in the database are skipped; new ones are stored with
+/// origin `imported` in the target project, so they appear when that project
+/// is selected but are excluded from overall totals, budgets and allocation.
 ///
-/// JSON object containing:
-/// - projects: array of project objects with:
-///   - project_id: unique identifier
-///   - name: display name
-///   - description: project description
-///   - absolute_path: full filesystem path
-///   - first_seen: ISO timestamp when first discovered
-///   - last_seen: ISO timestamp when last seen
-///   - visible: boolean visibility flag
-#[tauri::command]
-pub async fn get_projects() -> Result<Value, String> {
-    call_python_api(&["projects"])
-}
-
-/// Get latest cc_usage account snapshots.
+/// # Arguments
+///
+/// * `path` - Path of the zip archive
+/// * `target_project` - Project ID to import into; created if missing (default: new project)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
 ///
 /// # Returns
 ///
 /// JSON object containing:
-/// - accounts: array of account usage rows
+/// - project_id, created_project: target project and whether it was created
+/// - sessions, files: archive contents
+/// - messages, duplicates: imported and skipped message counts
+/// - redacted: whether the archive was exported with redaction
 #[tauri::command]
-pub async fn get_usage_accounts() -> Result<Value, String> {
-    call_python_api(&["usage-accounts"])
+pub async fn import_session_archive(
+    state: State<'_, AppState>,
+    path: String,
+    target_project: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "import-session-archive".to_string(),
+        format!("--path={}", path),
+    ];
+
+    if let Some(project) = target_project.filter(|p| !p.is_empty()) {
+        args.push(format!("--project={}", project));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
 }
 
-/// Update project metadata fields.
+/// List sessions page by page for virtualized tables.
+///
+/// Pages are keyset-paginated: `next_cursor` encodes the last row's sort key,
+/// so sessions ingested while scrolling do not shift or duplicate rows. A
+/// cursor is only valid for the filter it was issued with.
 ///
 /// # Arguments
 ///
-/// * `project_id` - Project identifier (required)
-/// * `name` - New display name (optional)
-/// * `description` - New description (optional)
-/// * `visible` - Visibility flag (optional)
+/// * `filter` - Date range plus optional project, model, session ID search and sort
+/// * `cursor` - `next_cursor` of the previous page (omit for the first page)
+/// * `page_size` - Rows per page (default 100, max 500)
+/// * `stream` - Send `sessions` as `result-chunk` events instead of in the response
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
 ///
 /// # Returns
 ///
 /// JSON object containing:
-/// - project: updated project object
+/// - sessions: session summaries with per-model breakdowns (empty when streamed)
+/// - next_cursor: cursor of the next page (null on the last page)
+/// - total_count: number of sessions matching the filter
+/// - page_size, sort: effective paging parameters
+/// - streamed: key, chunks and items of a streamed list (only when streamed)
 #[tauri::command]
-pub async fn update_project(
-    project_id: String,
-    name: Option<String>,
-    description: Option<String>,
-    visible: Option<bool>,
-) -> Result<Value, String> {
-    // Build args as owned Strings to avoid lifetime issues
-    // Use --key=value format to avoid issues with project_id starting with hyphen
-    let mut args: Vec<String> = vec![
-        "update-project".to_string(),
-        format!("--project-id={}", project_id),
+pub async fn list_sessions(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    filter: SessionFilter,
+    cursor: Option<String>,
+    page_size: Option<u32>,
+    stream: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "sessions".to_string(),
+        "--from".to_string(),
+        filter.from,
+        "--to".to_string(),
+        filter.to,
+        "--sort".to_string(),
+        filter.sort.unwrap_or_else(|| "cost".to_string()),
+        "--page-size".to_string(),
+        page_size.unwrap_or(100).to_string(),
     ];
 
-    // Collect optional parameters
-    if let Some(n) = name {
-        args.push(format!("--name={}", n));
+    if let Some(pid) = filter.project_id {
+        args.push(format!("--project-id={}", pid));
     }
-
-    if let Some(d) = description {
-        args.push(format!("--description={}", d));
+    if let Some(model) = filter.model {
+        args.push(format!("--model={}", model));
     }
-
-    if let Some(v) = visible {
-        args.push(format!("--visible={}", if v { "1" } else { "0" }));
+    if let Some(search) = filter.search.filter(|s| !s.is_empty()) {
+        args.push(format!("--search={}", search));
+    }
+    if let Some(cursor) = cursor {
+        args.push(format!("--cursor={}", cursor));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    if stream.unwrap_or(false) {
+        let request = ApiRequest::new(&request_id, &args_refs);
+        return run_blocking(&state.backend, &request_id, move |backend| call_with_result_chunks(backend, &app, request))
+            .await;
+    }
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Get limit reset events for a date range.
+///
+/// # Arguments
+///
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON array containing limit reset events with:
+/// - limit_type: type of limit ('5-hour', 'session', 'spending_cap', 'context')
+/// - reset_at: ISO timestamp when limit resets
+/// - reset_text: original reset text (e.g., "resets 12am")
+/// - summary: full summary text
+/// - year: year of the event
+/// - date: date of the event (YYYY-MM-DD)
+#[tauri::command]
+pub async fn get_limit_resets(
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["limits", "--from", &from, "--to", &to]).await
+}
+
+/// Analyze limit hits and lockout time for a date range.
+///
+/// # Arguments
+///
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - totals: hits, days_with_hits, nominal and effective lockout minutes
+/// - by_type: hit counts per limit type
+/// - by_project: hits and tokens consumed in the 5h window before each hit
+/// - by_hour / by_weekday: hit distribution
+/// - events: individual hits with lockout durations
+#[tauri::command]
+pub async fn get_limit_impact(
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let result = call_backend(&state.backend, &request_id, &["limit-impact", "--from", &from, "--to", &to]).await;
+    conditional(result, if_none_match)
+}
+
+/// Calls, tokens and failures per MCP server for a date range.
+///
+/// MCP tool calls (tool names `mcp__<server>__<tool>`) are recorded during ingestion.
+///
+/// # Arguments
+///
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `project_id` - Filter by project (optional)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - range: from/to dates
+/// - totals: calls, failures, failure_rate, sessions, message_tokens, result_tokens
+/// - servers: per server calls, failures, failure_rate, pending, sessions, message_tokens
+///   (share of the calling responses), result_tokens (estimated) and per-tool breakdown
+#[tauri::command]
+pub async fn get_mcp_usage(
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    project_id: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "mcp-usage".to_string(),
+        format!("--from={}", from),
+        format!("--to={}", to),
+    ];
+    if let Some(project_id) = project_id {
+        args.push(format!("--project-id={}", project_id));
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Executions, durations and failure rates per Claude Code hook for a date range.
+///
+/// Hook runs (PreToolUse, PostToolUse, Stop, ...) are recorded from the session
+/// logs during ingestion; a hook is identified by its event and command.
+///
+/// # Arguments
+///
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `project_id` - Filter by project (optional)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - range: from/to dates
+/// - totals: executions, failures, blocked, failure_rate, total_duration_ms
+/// - by_event: executions, failures, blocked and total duration per hook event
+/// - hooks: per hook executions, failures, blocked, cancelled, failure_rate,
+///   avg/p95/max/total duration (null without timing) and sessions
+#[tauri::command]
+pub async fn get_hook_stats(
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    project_id: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "hook-stats".to_string(),
+        format!("--from={}", from),
+        format!("--to={}", to),
+    ];
+    if let Some(project_id) = project_id {
+        args.push(format!("--project-id={}", project_id));
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Cost, tokens and latency of a model's responses by effort/thinking setting.
+///
+/// The setting of each response is taken from the prompt that started its turn
+/// (`thinkingMetadata` in the session logs); responses without one are grouped
+/// as "unknown".
+///
+/// # Arguments
+///
+/// * `model` - Model name
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `project_id` - Filter by project (optional)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - model, range: the requested model and from/to dates
+/// - settings: per effort level requests, thinking share, output/total tokens,
+///   cost (total, share, per request) and avg/p50/p95 latency in ms
+#[tauri::command]
+pub async fn get_effort_breakdown(
+    state: State<'_, AppState>,
+    model: String,
+    from: String,
+    to: String,
+    project_id: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "effort-breakdown".to_string(),
+        format!("--model={}", model),
+        format!("--from={}", from),
+        format!("--to={}", to),
+    ];
+    if let Some(project_id) = project_id {
+        args.push(format!("--project-id={}", project_id));
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Periods where several sessions ran at the same time, and their token impact.
+///
+/// Sessions are split into activity segments (messages at most `idle_gap_minutes`
+/// apart), so idle terminals do not count as running.
+///
+/// # Arguments
+///
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `idle_gap_minutes` - Pause that ends an activity segment (default: 15)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - max_concurrency, peak: highest number of simultaneous sessions and when
+/// - levels: minutes, messages, tokens, cost and token share per concurrency level
+/// - concurrent: minutes, tokens, cost and token share with two or more sessions
+/// - daily: max concurrency, concurrent minutes and tokens per day
+/// - periods, period_count: overlap periods (top by tokens) with sessions and projects
+/// - limit_windows: tokens and concurrent share in the 5 hours before each 5-hour limit hit
+#[tauri::command]
+pub async fn get_concurrency_stats(
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    idle_gap_minutes: Option<u32>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "concurrency-stats".to_string(),
+        format!("--from={}", from),
+        format!("--to={}", to),
+    ];
+    if let Some(idle_gap_minutes) = idle_gap_minutes {
+        args.push(format!("--idle-gap={}", idle_gap_minutes));
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Share of sessions by outcome for a date range.
+///
+/// Sessions are labelled by how they ended (commit, test_pass, tool_error,
+/// user_interrupt, other) by a rule-based pass over their JSONL files; new and
+/// changed sessions of the range are classified on each call.
+///
+/// # Arguments
+///
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `project_id` - Filter by project (optional)
+/// * `reclassify` - Re-read every session of the range (default: false)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - sessions, classified: sessions started in the range and how many have a label
+/// - outcomes: sessions and share per outcome
+/// - shipped: sessions with a successful `git commit` and their share
+/// - by_project: per project sessions, shipped, shipped_share and outcome counts
+/// - classification: classified, unchanged and missing_files counts of the pass
+#[tauri::command]
+pub async fn get_outcome_stats(
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    project_id: Option<String>,
+    reclassify: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "outcome-stats".to_string(),
+        format!("--from={}", from),
+        format!("--to={}", to),
+    ];
+    if let Some(project_id) = project_id {
+        args.push(format!("--project-id={}", project_id));
+    }
+    if reclassify.unwrap_or(false) {
+        args.push("--reclassify".to_string());
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Frequency and token impact of context compactions for a date range.
+///
+/// Compactions (`/compact` and auto-compaction) are read from the sessions'
+/// JSONL files; new and changed sessions of the range are scanned on each call.
+///
+/// # Arguments
+///
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `project_id` - Filter by project (optional)
+/// * `rescan` - Re-read every session of the range (default: false)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - sessions, sessions_with_compactions, compactions: counts for the range
+/// - by_trigger: auto, manual and unknown (older logs) counts
+/// - per_session, per_compacted_session: compaction rates
+/// - avg_pre_tokens, avg_post_tokens, tokens_freed, avg_tokens_freed: context size impact
+/// - daily: compactions per day and trigger
+/// - top_sessions: sessions with the most compactions
+/// - by_project: per project sessions, compactions and per_session
+/// - scan: scanned, unchanged and missing_files counts of the pass
+#[tauri::command]
+pub async fn get_compaction_stats(
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    project_id: Option<String>,
+    rescan: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "compaction-stats".to_string(),
+        format!("--from={}", from),
+        format!("--to={}", to),
+    ];
+    if let Some(project_id) = project_id {
+        args.push(format!("--project-id={}", project_id));
+    }
+    if rescan.unwrap_or(false) {
+        args.push("--rescan".to_string());
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Export PNG usage report for a date range.
+///
+/// # Arguments
+///
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `scale` - Pixel density 1-3 (@2x, @3x); default: the scale of the monitor the
+///   window is on (see `get_display_scale`)
+/// * `target_path` - File to write (relative to the export directory); the save dialog
+///   opens if omitted
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - path: written file
+/// - filename: file name suggested by the backend
+/// - size: size of PNG in bytes
+/// - width, height: pixel size; scale: pixel density used
+/// - sha256: checksum of the written file
+/// - mime_type: "image/png"
+/// - alt_text, long_description: text alternatives of the image (also embedded in the
+///   PNG as its Title and Description)
+///
+/// or `{cancelled: true}` if the dialog was cancelled
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_png_report(
+    app: AppHandle,
+    window: Window,
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    scale: Option<u8>,
+    target_path: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let scale = scale.unwrap_or_else(|| suggested_export_scale(window.scale_factor().unwrap_or(1.0)));
+    let params = serde_json::json!({ "from": from, "to": to, "scale": scale });
+    let call_id = request_id.clone();
+    run_blocking(&state.backend, &request_id, move |backend| {
+        export_to_file(backend, &app, &call_id, exports::ExportKind::PngReport, params, target_path)
+    })
+    .await
+}
+
+/// Export the usage report as a social card of the platform's image size.
+///
+/// The full report is scaled down onto a card of the report background.
+///
+/// # Arguments
+///
+/// * `format` - "twitter" (1200x675, summary_large_image) or "linkedin" (1200x627)
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `target_path` - File to write (relative to the export directory); the save dialog
+///   opens if omitted
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// Same as `export_png_report`, or `{cancelled: true}` if the dialog was cancelled
+#[tauri::command]
+pub async fn export_for_social(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    format: String,
+    from: String,
+    to: String,
+    target_path: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    if !exports::SOCIAL_FORMATS.contains(&format.as_str()) {
+        return Err(CommandError::invalid_argument(format!(
+            "Unknown social format: {} (expected {})",
+            format,
+            exports::SOCIAL_FORMATS.join(", ")
+        ))
+        .with_request_id(&request_id));
+    }
+    let params = serde_json::json!({ "from": from, "to": to, "social": format });
+    let call_id = request_id.clone();
+    run_blocking(&state.backend, &request_id, move |backend| {
+        export_to_file(backend, &app, &call_id, exports::ExportKind::PngReport, params, target_path)
+    })
+    .await
+}
+
+/// Parameters of a PNG report render: a social card if `social` is set, else
+/// the report at `scale` (default: the density of the window's monitor).
+fn png_report_params(
+    window: &Window,
+    request_id: &str,
+    from: String,
+    to: String,
+    scale: Option<u8>,
+    social: Option<String>,
+) -> Result<Value, CommandError> {
+    match social {
+        Some(format) if !exports::SOCIAL_FORMATS.contains(&format.as_str()) => Err(CommandError::invalid_argument(
+            format!("Unknown social format: {} (expected {})", format, exports::SOCIAL_FORMATS.join(", ")),
+        )
+        .with_request_id(request_id)),
+        Some(format) => Ok(serde_json::json!({ "from": from, "to": to, "social": format })),
+        None => {
+            let scale = scale.unwrap_or_else(|| suggested_export_scale(window.scale_factor().unwrap_or(1.0)));
+            Ok(serde_json::json!({ "from": from, "to": to, "scale": scale }))
+        }
+    }
+}
+
+/// Render the PNG usage report to a temporary file and return its path.
+///
+/// The file is left in the export scratch directory (removed after an hour),
+/// for previews or handing to another app without copying the image through
+/// JSON.
+///
+/// # Arguments
+///
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `scale` - Pixel density 1-3; default: the scale of the monitor the window is on
+/// * `social` - Render a social card instead: "twitter" or "linkedin" (`scale` is ignored)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// Same as `export_png_report`, with `path` pointing at the temporary file
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn render_png_report(
+    window: Window,
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    scale: Option<u8>,
+    social: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let params = png_report_params(&window, &request_id, from, to, scale, social)?;
+    let call_id = request_id.clone();
+    let (mut result, rendered) = run_blocking(&state.backend, &request_id, move |backend| {
+        exports::render_png(backend, &call_id, &params).map_err(|e| e.with_request_id(&call_id))
+    })
+    .await?;
+    if let Some(obj) = result.as_object_mut() {
+        obj.insert("path".to_string(), Value::from(rendered.path));
+        obj.insert("sha256".to_string(), Value::from(rendered.sha256));
+    }
+    Ok(result)
+}
+
+/// Render the PNG usage report and return the image itself over binary IPC.
+///
+/// The frontend receives an `ArrayBuffer` (e.g. for a `Blob` preview or the
+/// clipboard) instead of a base64 string; the temporary file is removed.
+///
+/// # Arguments
+///
+/// Same as `render_png_report`
+///
+/// # Returns
+///
+/// The PNG bytes
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_png_report_bytes(
+    window: Window,
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    scale: Option<u8>,
+    social: Option<String>,
+    request_id: Option<String>,
+) -> Result<tauri::ipc::Response, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let params = png_report_params(&window, &request_id, from, to, scale, social)?;
+    let call_id = request_id.clone();
+    let (_, rendered) = run_blocking(&state.backend, &request_id, move |backend| {
+        exports::render_png(backend, &call_id, &params).map_err(|e| e.with_request_id(&call_id))
+    })
+    .await?;
+    let bytes = std::fs::read(&rendered.path);
+    let _ = std::fs::remove_file(&rendered.path);
+    let bytes = bytes.map_err(|e| {
+        CommandError::internal(format!("Failed to read {}: {}", rendered.path, e)).with_request_id(&request_id)
+    })?;
+    Ok(tauri::ipc::Response::new(bytes))
+}
+
+/// List the HTML report templates.
+///
+/// Templates are Tera files (`<name>.html`) in `~/.claude/db/report-templates/`,
+/// which is created with the starter `summary` template on first use. Edit
+/// them or add new ones there; see `render_report` for the variables.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - directory: template directory
+/// - templates: Array of {name, path, size_bytes, modified (unix seconds), error (syntax error or null)}
+#[tauri::command]
+pub async fn list_report_templates() -> Result<Value, CommandError> {
+    let dir = report_templates::template_dir()?;
+    let templates = report_templates::list_templates(&dir)?;
+    Ok(serde_json::json!({
+        "directory": dir.display().to_string(),
+        "templates": templates,
+    }))
+}
+
+/// Timeline granularity of a report: days for up to two months, else months.
+fn report_granularity(from: &str, to: &str) -> String {
+    let days = match (NaiveDate::parse_from_str(from, "%Y-%m-%d"), NaiveDate::parse_from_str(to, "%Y-%m-%d")) {
+        (Ok(from), Ok(to)) => (to - from).num_days(),
+        _ => 0,
+    };
+    if days <= 62 { "day" } else { "month" }.to_string()
+}
+
+/// Render an HTML report template for a date range.
+///
+/// The template gets `range` ({from, to, project_id}), `generated_at`,
+/// `app_version`, `dashboard` (the dashboard bundle of the range, with a
+/// daily timeline up to two months and a monthly one beyond) and
+/// `accessibility` (text summaries of the series for captions and alt text). Values are
+/// HTML-escaped. For a PDF, print the returned HTML (templates can set the
+/// page size with `@page`).
+///
+/// # Arguments
+///
+/// * `template` - Template name (file stem, see `list_report_templates`)
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `project_id` - Filter by project (optional)
+/// * `save` - Also write the HTML to a file (default false)
+/// * `target_path` - File to write when saving (relative to the export directory); the
+///   save dialog opens if omitted
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - template, filename (suggested), html
+/// - path, size, sha256 when saved
+///
+/// or `{cancelled: true}` if the save dialog was cancelled. Unknown templates and
+/// template errors (with line and column) fail with `invalid_argument`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn render_report(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    template: String,
+    from: String,
+    to: String,
+    project_id: Option<String>,
+    save: Option<bool>,
+    target_path: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let dir = report_templates::template_dir()?;
+    let source = report_templates::load_template(&dir, &template)
+        .map_err(|e| CommandError::invalid_argument(e).with_request_id(&request_id))?;
+
+    let granularity = report_granularity(&from, &to);
+    let args = dashboard_args(from.clone(), to.clone(), false, granularity, project_id.clone(), None, None);
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let dashboard = call_backend(&state.backend, &request_id, &args_refs).await?;
+
+    let context = serde_json::json!({
+        "range": { "from": from, "to": to, "project_id": project_id },
+        "generated_at": chrono::Local::now().to_rfc3339(),
+        "app_version": env!("CARGO_PKG_VERSION"),
+        "accessibility": report_templates::describe_dashboard(&from, &to, &dashboard),
+        "dashboard": dashboard,
+    });
+    let html = report_templates::render(&template, &source, &context)
+        .map_err(|e| CommandError::invalid_argument(e).with_request_id(&request_id))?;
+    let filename = format!("cc-report-{}-{}_{}.html", template, from, to);
+    let mut result = serde_json::json!({ "template": template, "filename": filename, "html": html });
+    if !save.unwrap_or(false) {
+        return Ok(result);
+    }
+
+    let Some(path) = exports::choose_target(&app, target_path, &filename)
+        .map_err(|e| CommandError::internal(e).with_request_id(&request_id))?
+    else {
+        return Ok(serde_json::json!({ "cancelled": true }));
+    };
+    let written = exports::write_export_file(&path, html.as_bytes())
+        .map_err(|e| CommandError::internal(e).with_request_id(&request_id))?;
+    result["path"] = Value::from(written.path);
+    result["size"] = Value::from(written.size);
+    result["sha256"] = Value::from(written.sha256);
+    Ok(result)
+}
+
+/// Export scale for a monitor scale factor: the next whole density, at most @3x.
+fn suggested_export_scale(scale_factor: f64) -> u8 {
+    (scale_factor.ceil() as u64).clamp(1, exports::MAX_EXPORT_SCALE) as u8
+}
+
+/// Scale factor of the monitor the window is on and the matching export density.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - scale_factor: e.g. 1.5 (Windows 150%) or 2.0 (retina)
+/// - suggested_scale: default `scale` of `export_png_report` (1-3)
+/// - scales: available export densities [1, 2, 3]
+#[tauri::command]
+pub async fn get_display_scale(window: Window) -> Result<Value, CommandError> {
+    let scale_factor = window.scale_factor().map_err(|e| CommandError::internal(e.to_string()))?;
+    Ok(serde_json::json!({
+        "scale_factor": scale_factor,
+        "suggested_scale": suggested_export_scale(scale_factor),
+        "scales": (1..=exports::MAX_EXPORT_SCALE).collect::<Vec<_>>(),
+    }))
+}
+
+/// Save an export rendered by the frontend (e.g. the dashboard PNG).
+///
+/// # Arguments
+///
+/// * `data` - Base64 file content (a `data:` URL is accepted)
+/// * `filename` - Suggested file name
+/// * `target_path` - File to write (relative to the export directory); the save dialog
+///   opens if omitted
+///
+/// # Returns
+///
+/// JSON object containing path, size and sha256, or `{cancelled: true}`
+#[tauri::command]
+pub async fn save_export(
+    app: AppHandle,
+    data: String,
+    filename: String,
+    target_path: Option<String>,
+) -> Result<Value, CommandError> {
+    let bytes = exports::decode_data(&data)?;
+    let Some(path) = exports::choose_target(&app, target_path, &filename)? else {
+        return Ok(serde_json::json!({ "cancelled": true }));
+    };
+    let written = exports::write_export_file(&path, &bytes)?;
+    Ok(serde_json::to_value(written)?)
+}
+
+/// Get the export directory settings.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - export_dir: configured directory (null: not set)
+/// - default_dir: directory exports and save dialogs start in
+#[tauri::command]
+pub async fn get_export_settings(app: AppHandle, state: State<'_, AppState>) -> Result<Value, CommandError> {
+    Ok(serde_json::json!({
+        "export_dir": state.settings.load().export_dir,
+        "default_dir": exports::default_export_dir(&app).display().to_string(),
+    }))
+}
+
+/// Set the default export directory.
+///
+/// # Arguments
+///
+/// * `path` - Absolute directory path (created if missing); omit to use Downloads
+///
+/// # Returns
+///
+/// The stored settings (see `get_export_settings`)
+#[tauri::command]
+pub async fn set_export_dir(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: Option<String>,
+) -> Result<Value, CommandError> {
+    let export_dir = match path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+        Some(path) => {
+            let dir = PathBuf::from(&path);
+            if !dir.is_absolute() {
+                return Err(CommandError::invalid_argument(format!(
+                    "Export directory must be an absolute path: {}",
+                    path
+                )));
+            }
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| CommandError::internal(format!("Failed to create {}: {}", path, e)))?;
+            Some(path)
+        }
+        None => None,
+    };
+    state.settings.update(|settings| {
+        settings.export_dir = export_dir;
+        Ok(())
+    })?;
+    get_export_settings(app, state).await
+}
+
+/// Get monthly budget history (actual vs budget per period).
+///
+/// Completed months are snapshotted on first access after they roll over,
+/// so past periods stay stable even if old data is re-ingested.
+///
+/// # Arguments
+///
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - current: running month with budget, actual, over_under, percent_used
+/// - periods: completed months (newest first) with the same fields
+/// - summary: period count, over/under budget counts, average percent used
+#[tauri::command]
+pub async fn get_budget_history(
+    state: State<'_, AppState>,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    conditional(call_backend(&state.backend, &request_id, &["budget-history"]).await, if_none_match)
+}
+
+/// Set the monthly budget.
+///
+/// # Arguments
+///
+/// * `amount` - Monthly budget in USD (None clears the budget)
+/// * `effective_from` - First month the budget applies to (YYYY-MM, default: current month)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - budget: updated budget configuration with all effective-dated changes
+#[tauri::command]
+pub async fn set_monthly_budget(
+    state: State<'_, AppState>,
+    amount: Option<f64>,
+    effective_from: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec!["set-budget".to_string()];
+
+    if let Some(a) = amount {
+        args.push(format!("--amount={}", a));
+    }
+
+    if let Some(month) = effective_from {
+        args.push(format!("--effective-from={}", month));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Get lifecycle hooks with their recent runs.
+///
+/// # Arguments
+///
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - events: supported events (refresh-completed, day-rolled-over, month-completed, budget-threshold)
+/// - hooks: configured hooks with id, event, action, command, timeout, enabled
+///   (export hooks: folder and formats instead of command and timeout)
+/// - budget_thresholds: percentages that fire budget-threshold
+/// - recent_runs: latest hook runs with status, exit_code, duration_ms and output
+#[tauri::command]
+pub async fn get_hooks(state: State<'_, AppState>, request_id: Option<String>) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["hooks"]).await
+}
+
+/// Create or replace a lifecycle hook.
+///
+/// Hooks run after refreshes. `{field}` placeholders in the command are
+/// replaced by shell-quoted event fields (e.g. `echo {date},{cost} >> ~/ledger.csv`);
+/// the whole event is also written to the command's stdin as JSON.
+///
+/// An export hook (`action` "export", event month-completed) runs no command:
+/// when a month completes it writes that month's cost allocation CSV and
+/// usage report PDF into `folder`.
+///
+/// # Arguments
+///
+/// * `event` - refresh-completed, day-rolled-over, month-completed or budget-threshold
+/// * `command` - Shell command template (empty for export hooks)
+/// * `timeout` - Seconds before the command is killed (default 30, max 600)
+/// * `hook_id` - Hook to create or replace (default: a new random id)
+/// * `enabled` - Whether the hook runs (default true)
+/// * `action` - "command" (default) or "export"
+/// * `folder` - Absolute folder an export hook writes to
+/// * `formats` - Files an export hook writes: "csv", "pdf" (default both)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - hook: the stored hook
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn set_hook(
+    state: State<'_, AppState>,
+    event: String,
+    command: Option<String>,
+    timeout: Option<u32>,
+    hook_id: Option<String>,
+    enabled: Option<bool>,
+    action: Option<String>,
+    folder: Option<String>,
+    formats: Option<Vec<String>>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "set-hook".to_string(),
+        "--event".to_string(),
+        event,
+        format!("--command={}", command.unwrap_or_default()),
+    ];
+
+    if let Some(t) = timeout {
+        args.push(format!("--timeout={}", t));
+    }
+    if let Some(id) = hook_id {
+        args.push(format!("--id={}", id));
+    }
+    if !enabled.unwrap_or(true) {
+        args.push("--disabled".to_string());
+    }
+    if let Some(action) = action {
+        args.push(format!("--action={}", action));
+    }
+    if let Some(folder) = folder {
+        args.push(format!("--folder={}", folder));
+    }
+    for format in formats.unwrap_or_default() {
+        args.push(format!("--format={}", format));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Delete a lifecycle hook.
+///
+/// # Arguments
+///
+/// * `hook_id` - Hook identifier
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - deleted: identifier of the deleted hook
+#[tauri::command]
+pub async fn delete_hook(
+    state: State<'_, AppState>,
+    hook_id: String,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["delete-hook", "--id", &hook_id]).await
+}
+
+/// Run a lifecycle hook once with a sample event.
+///
+/// An export hook writes the real files of the previous month.
+///
+/// # Arguments
+///
+/// * `hook_id` - Hook identifier
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - status: ok, error or timeout
+/// - exit_code, duration_ms, stdout, stderr
+/// - files: written paths (export hooks)
+#[tauri::command]
+pub async fn test_hook(
+    state: State<'_, AppState>,
+    hook_id: String,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["test-hook", "--id", &hook_id]).await
+}
+
+/// Get user-defined derived metrics.
+///
+/// # Arguments
+///
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - variables: names formulas may use, the registered metrics (see `list_available_metrics`)
+/// - functions: allowed functions (min, max, abs, round)
+/// - metrics: definitions with name, expression, description
+#[tauri::command]
+pub async fn get_derived_metrics(
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["derived-metrics"]).await
+}
+
+/// List the metrics that can be charted, for metric pickers.
+///
+/// # Arguments
+///
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - metrics: name, label, unit (count, tokens, usd, ms; null for derived),
+///   category (activity, tokens, cost, tools, latency, derived), description;
+///   registered metrics first, then derived metrics
+#[tauri::command]
+pub async fn list_available_metrics(
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["available-metrics"]).await
+}
+
+/// Get chosen metrics per period.
+///
+/// # Arguments
+///
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `metrics` - Names from `list_available_metrics`
+/// * `granularity` - "month", "week", "day" (default) or "hour"
+/// * `project_id` - Filter by project (optional)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - granularity, metrics: the inputs used
+/// - data: per period `{period, <metric>: value}` (null for an average without data
+///   or a derived formula dividing by zero)
+#[tauri::command]
+pub async fn get_metric_series(
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    metrics: Vec<String>,
+    granularity: Option<String>,
+    project_id: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    if metrics.is_empty() || metrics.iter().any(|name| name.is_empty() || name.contains(',')) {
+        return Err(CommandError::invalid_argument("Choose at least one metric").with_request_id(&request_id));
+    }
+    let mut args = vec![
+        "metric-series".to_string(),
+        format!("--from={}", from),
+        format!("--to={}", to),
+        format!("--metrics={}", metrics.join(",")),
+        format!("--granularity={}", granularity.as_deref().unwrap_or("day")),
+    ];
+    if let Some(project_id) = project_id {
+        args.push(format!("--project-id={}", project_id));
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Create or replace a derived metric.
+///
+/// Derived metrics are evaluated per timeline period (`timeline.data[].derived`)
+/// and per day (`daily_derived`) in the dashboard bundle.
+///
+/// # Arguments
+///
+/// * `name` - Metric name (lowercase identifier, e.g. cost_per_message)
+/// * `expression` - Formula, e.g. `cost / messages` or `cache_read / input`
+/// * `description` - Optional label
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - metric: the stored metric
+#[tauri::command]
+pub async fn set_derived_metric(
+    state: State<'_, AppState>,
+    name: String,
+    expression: String,
+    description: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "set-derived-metric".to_string(),
+        "--name".to_string(),
+        name,
+        format!("--expression={}", expression),
+    ];
+
+    if let Some(d) = description {
+        args.push(format!("--description={}", d));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Delete a derived metric.
+///
+/// # Arguments
+///
+/// * `name` - Metric name
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - deleted: name of the deleted metric
+#[tauri::command]
+pub async fn delete_derived_metric(
+    state: State<'_, AppState>,
+    name: String,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["delete-derived-metric", "--name", &name]).await
+}
+
+/// Get the vacation/holiday calendar overlay.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - periods: Array of {from, to, kind ("vacation" | "holiday"), label}
+#[tauri::command]
+pub async fn get_calendar_overlay(
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["calendar-overlay"]).await
+}
+
+/// Replace the vacation/holiday calendar overlay.
+///
+/// Overlay days are left out of `totals.daily_average` and neither extend nor
+/// break streaks; `timeline.overlay` and `heatmap.overlay` list the periods
+/// in range so charts can shade them.
+///
+/// # Arguments
+///
+/// * `periods` - All periods; an empty list clears the overlay
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - periods: the stored periods, sorted by start
+#[tauri::command]
+pub async fn set_calendar_overlay(
+    state: State<'_, AppState>,
+    periods: Vec<CalendarPeriod>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let periods = serde_json::to_string(&periods)?;
+    call_backend(&state.backend, &request_id, &["set-calendar-overlay", &format!("--periods={}", periods)]).await
+}
+
+/// Get the working hours definition.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - days: ISO weekdays counted as work days (1 = Monday ... 7 = Sunday)
+/// - start, end: local work window (HH:MM, end exclusive)
+#[tauri::command]
+pub async fn get_working_hours(
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["working-hours"]).await
+}
+
+/// Replace the working hours definition used by `get_out_of_hours_usage`.
+///
+/// # Arguments
+///
+/// * `days` - ISO weekdays counted as work days (1 = Monday ... 7 = Sunday)
+/// * `start` - Start of the work window (HH:MM, local time)
+/// * `end` - End of the work window (HH:MM, exclusive, after start)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing the stored days, start and end
+#[tauri::command]
+pub async fn set_working_hours(
+    state: State<'_, AppState>,
+    days: Vec<u8>,
+    start: String,
+    end: String,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let days = days.iter().map(|day| day.to_string()).collect::<Vec<_>>().join(",");
+    let args = [
+        "set-working-hours".to_string(),
+        format!("--days={}", days),
+        format!("--start={}", start),
+        format!("--end={}", end),
+    ];
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Split usage of a date range into work and personal time.
+///
+/// Each message is classified by its local time against the working hours;
+/// calendar overlay days (vacations, holidays) count as personal time.
+///
+/// # Arguments
+///
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `project_id` - Filter by project (optional)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - range, working_hours: the inputs used
+/// - work, personal: messages, tokens, cost and sessions (a session counts on each side it has messages in)
+/// - work_share: percentage of cost in work time (null without cost)
+/// - days: per-day work and personal messages, tokens and cost
+#[tauri::command]
+pub async fn get_out_of_hours_usage(
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    project_id: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "out-of-hours-usage".to_string(),
+        format!("--from={}", from),
+        format!("--to={}", to),
+    ];
+    if let Some(project_id) = project_id {
+        args.push(format!("--project-id={}", project_id));
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Get cost center configuration.
+///
+/// # Arguments
+///
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - default_cost_center: code and name used for unmapped projects
+/// - cost_centers: array of cost centers with code, name, projects, tags
+#[tauri::command]
+pub async fn get_cost_centers(
+    state: State<'_, AppState>,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    conditional(call_backend(&state.backend, &request_id, &["cost-centers"]).await, if_none_match)
+}
+
+/// Create or update a cost center mapping.
+///
+/// # Arguments
+///
+/// * `code` - Cost center code (e.g. "CC-100")
+/// * `name` - Display name (optional)
+/// * `projects` - Project IDs mapped explicitly (optional, replaces the list)
+/// * `tags` - Project tags mapped (optional, replaces the list)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// Updated cost center configuration
+#[tauri::command]
+pub async fn set_cost_center(
+    state: State<'_, AppState>,
+    code: String,
+    name: Option<String>,
+    projects: Option<Vec<String>>,
+    tags: Option<Vec<String>>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec!["set-cost-center".to_string(), format!("--code={}", code)];
+
+    if let Some(n) = name {
+        args.push(format!("--name={}", n));
+    }
+
+    if let Some(p) = projects {
+        args.push(format!("--projects={}", p.join(",")));
+    }
+
+    if let Some(t) = tags {
+        args.push(format!("--tags={}", t.join(",")));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Delete a cost center mapping.
+///
+/// # Arguments
+///
+/// * `code` - Cost center code
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// Updated cost center configuration
+#[tauri::command]
+pub async fn delete_cost_center(
+    state: State<'_, AppState>,
+    code: String,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let arg = format!("--code={}", code);
+    call_backend(&state.backend, &request_id, &["delete-cost-center", &arg]).await
+}
+
+/// Allocate a month's usage to cost centers.
+///
+/// # Arguments
+///
+/// * `month` - Month (YYYY-MM)
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - month, range
+/// - cost_centers: per cost center totals and share with per-project rows
+///   (including the allocation rule that matched)
+/// - totals: cost, tokens, messages, unpriced_messages
+/// - methodology: notes describing period, pricing and allocation rules
+#[tauri::command]
+pub async fn get_cost_allocation(
+    state: State<'_, AppState>,
+    month: String,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let result = call_backend(&state.backend, &request_id, &["cost-allocation", "--month", &month]).await;
+    conditional(result, if_none_match)
+}
+
+/// Export a month's cost allocation for finance as CSV or XLSX.
+///
+/// The methodology is embedded in the file (after the table in CSV, on a
+/// separate sheet in XLSX).
+///
+/// # Arguments
+///
+/// * `month` - Month (YYYY-MM)
+/// * `format` - "csv" (default) or "xlsx"
+/// * `target_path` - File to write (relative to the export directory); the save dialog
+///   opens if omitted
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - path: written file
+/// - filename: file name suggested by the backend
+/// - size: file size in bytes
+/// - sha256: checksum of the written file
+/// - mime_type: MIME type of the file
+/// - totals: allocation totals
+///
+/// or `{cancelled: true}` if the dialog was cancelled
+#[tauri::command]
+pub async fn export_cost_allocation(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    month: String,
+    format: Option<String>,
+    target_path: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let format = format.unwrap_or_else(|| "csv".to_string());
+    let params = serde_json::json!({ "month": month, "format": format });
+    let call_id = request_id.clone();
+    run_blocking(&state.backend, &request_id, move |backend| {
+        export_to_file(backend, &app, &call_id, exports::ExportKind::CostAllocation, params, target_path)
+    })
+    .await
+}
+
+/// Get effective-dated model price versions.
+///
+/// # Arguments
+///
+/// * `model` - Optional model filter
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - versions: array with model, effective_from, effective_to (exclusive, null = current),
+///   per-token rates, source (litellm/manual), recorded_at
+#[tauri::command]
+pub async fn get_price_versions(
+    state: State<'_, AppState>,
+    model: Option<String>,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec!["price-versions".to_string()];
+
+    if let Some(m) = model {
+        args.push(format!("--model={}", m));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    conditional(call_backend(&state.backend, &request_id, &args_refs).await, if_none_match)
+}
+
+/// Record current LiteLLM rates as price versions for all seen models.
+///
+/// # Arguments
+///
+/// * `effective_from` - Start date for changed rates (YYYY-MM-DD, default: today)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - models: number of models seen in messages
+/// - new_versions: number of versions recorded
+/// - unpriced: models without LiteLLM pricing
+#[tauri::command]
+pub async fn sync_price_versions(
+    state: State<'_, AppState>,
+    effective_from: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec!["sync-prices".to_string()];
+
+    if let Some(date) = effective_from {
+        args.push(format!("--effective-from={}", date));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Record a manual price version for a model.
+///
+/// # Arguments
+///
+/// * `model` - Model name as stored in messages
+/// * `effective_from` - First date the rates apply to (YYYY-MM-DD)
+/// * `input_per_mtok` - Input price in USD per million tokens
+/// * `output_per_mtok` - Output price in USD per million tokens
+/// * `cache_write_per_mtok` - Cache write price (default: 1.25x input)
+/// * `cache_read_per_mtok` - Cache read price (default: 0.1x input)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - recorded: false when the rates already applied on that date
+/// - model, effective_from
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn set_price_version(
+    state: State<'_, AppState>,
+    model: String,
+    effective_from: String,
+    input_per_mtok: f64,
+    output_per_mtok: f64,
+    cache_write_per_mtok: Option<f64>,
+    cache_read_per_mtok: Option<f64>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "set-price".to_string(),
+        format!("--model={}", model),
+        format!("--effective-from={}", effective_from),
+        format!("--input={}", input_per_mtok),
+        format!("--output={}", output_per_mtok),
+    ];
+
+    if let Some(price) = cache_write_per_mtok {
+        args.push(format!("--cache-write={}", price));
+    }
+
+    if let Some(price) = cache_read_per_mtok {
+        args.push(format!("--cache-read={}", price));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Recompute stored message costs with the rates in effect on each message date.
+///
+/// Costs taken from `costUSD` in the logs are never changed.
+///
+/// # Arguments
+///
+/// * `from` - Optional start date (YYYY-MM-DD)
+/// * `to` - Optional end date (YYYY-MM-DD)
+/// * `dry_run` - Report deltas without writing (default: false)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - dry_run, messages_checked, messages_changed
+/// - cost_before, cost_after, delta
+/// - by_model: per-model changed messages and before/after costs
+#[tauri::command]
+pub async fn reprice_history(
+    state: State<'_, AppState>,
+    from: Option<String>,
+    to: Option<String>,
+    dry_run: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec!["reprice".to_string()];
+
+    if let Some(f) = from {
+        args.push(format!("--from={}", f));
+    }
+
+    if let Some(t) = to {
+        args.push(format!("--to={}", t));
+    }
+
+    if dry_run.unwrap_or(false) {
+        args.push("--dry-run=1".to_string());
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Freeze the computed statistics for a range into a named snapshot.
+///
+/// Snapshots are immutable, so reported numbers don't shift when old data
+/// is re-ingested or re-priced later.
+///
+/// # Arguments
+///
+/// * `name` - Unique snapshot name (e.g. "2025-Q2")
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `project_id` - Optional project filter
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - snapshot: id, name, range, project_id, created_at, app_version and
+///   frozen data (totals, daily_activity, timeline, model_distribution, hourly_profile)
+#[tauri::command]
+pub async fn create_snapshot(
+    state: State<'_, AppState>,
+    name: String,
+    from: String,
+    to: String,
+    project_id: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "create-snapshot".to_string(),
+        format!("--name={}", name),
+        "--from".to_string(),
+        from,
+        "--to".to_string(),
+        to,
+    ];
+
+    if let Some(pid) = project_id {
+        args.push(format!("--project-id={}", pid));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// List saved snapshots.
+///
+/// # Arguments
+///
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - snapshots: array of snapshot metadata with frozen totals (newest first)
+#[tauri::command]
+pub async fn list_snapshots(
+    state: State<'_, AppState>,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    conditional(call_backend(&state.backend, &request_id, &["snapshots"]).await, if_none_match)
+}
+
+/// Get a snapshot with its full frozen data.
+///
+/// # Arguments
+///
+/// * `snapshot` - Snapshot id or name
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - snapshot: metadata and frozen data
+#[tauri::command]
+pub async fn get_snapshot(
+    state: State<'_, AppState>,
+    snapshot: String,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let arg = format!("--snapshot={}", snapshot);
+    conditional(call_backend(&state.backend, &request_id, &["snapshot", &arg]).await, if_none_match)
+}
+
+/// Delete a snapshot.
+///
+/// # Arguments
+///
+/// * `snapshot` - Snapshot id or name
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - deleted: id of the deleted snapshot
+/// - name: name of the deleted snapshot
+#[tauri::command]
+pub async fn delete_snapshot(
+    state: State<'_, AppState>,
+    snapshot: String,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let arg = format!("--snapshot={}", snapshot);
+    call_backend(&state.backend, &request_id, &["delete-snapshot", &arg]).await
+}
+
+/// Diff a snapshot against another snapshot or against live data.
+///
+/// # Arguments
+///
+/// * `base` - Base snapshot id or name
+/// * `target` - Target snapshot id or name (None recomputes the base range from live data)
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - base: base snapshot metadata
+/// - target: target snapshot metadata (name "live" when comparing with live data)
+/// - diff: totals/models deltas, changed days, and a changed flag
+#[tauri::command]
+pub async fn diff_snapshots(
+    state: State<'_, AppState>,
+    base: String,
+    target: Option<String>,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec!["diff-snapshots".to_string(), format!("--base={}", base)];
+
+    if let Some(t) = target {
+        args.push(format!("--target={}", t));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    conditional(call_backend(&state.backend, &request_id, &args_refs).await, if_none_match)
+}
+
+/// Get all projects with metadata.
+///
+/// # Arguments
+///
+/// * `sort` - Order: manual (default), last_activity, cost_this_month or name;
+///   pinned projects always come first
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - projects: array of project objects with:
+///   - project_id: unique identifier
+///   - name: display name
+///   - description: project description
+///   - absolute_path: full filesystem path
+///   - first_seen: ISO timestamp when first discovered
+///   - last_seen: ISO timestamp when last seen
+///   - visible: boolean visibility flag
+///   - pinned: boolean pin flag
+///   - sort_order: position in the manual order (absent if not ordered)
+///   - cost_this_month: cost of the current month (only with sort=cost_this_month)
+/// - sort: the order applied
+#[tauri::command]
+pub async fn get_projects(
+    state: State<'_, AppState>,
+    sort: Option<String>,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let sort_arg = format!("--sort={}", sort.as_deref().unwrap_or("manual"));
+    conditional(call_backend(&state.backend, &request_id, &["projects", &sort_arg]).await, if_none_match)
+}
+
+/// Pin or unpin a project; pinned projects are listed first.
+///
+/// # Arguments
+///
+/// * `project_id` - Project identifier
+/// * `pinned` - Pin flag
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - project: updated project object
+#[tauri::command]
+pub async fn set_project_pinned(
+    state: State<'_, AppState>,
+    project_id: String,
+    pinned: bool,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let project_arg = format!("--project-id={}", project_id);
+    let pinned_str = if pinned { "1" } else { "0" };
+    call_backend(&state.backend, &request_id, &["set-project-pinned", &project_arg, "--pinned", pinned_str]).await
+}
+
+/// Persist the manual project order.
+///
+/// # Arguments
+///
+/// * `project_ids` - Project IDs in display order; projects left out are listed after them
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - order: the stored order
+#[tauri::command]
+pub async fn set_project_order(
+    state: State<'_, AppState>,
+    project_ids: Vec<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let ids_arg = format!("--project-ids={}", project_ids.join(","));
+    call_backend(&state.backend, &request_id, &["set-project-order", &ids_arg]).await
+}
+
+/// Get latest cc_usage account snapshots.
+///
+/// # Arguments
+///
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - accounts: array of account usage rows
+#[tauri::command]
+pub async fn get_usage_accounts(
+    state: State<'_, AppState>,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    conditional(call_backend(&state.backend, &request_id, &["usage-accounts"]).await, if_none_match)
+}
+
+/// Update usage account metadata fields.
+///
+/// # Arguments
+///
+/// * `email` - Account email (required)
+/// * `label` - Display label (optional, empty string clears)
+/// * `color` - Hex color `#RRGGBB` (optional, empty string clears)
+/// * `plan_type` - Plan type override: pro, max_5x, max_20x, team, enterprise, api
+///   (optional, empty string falls back to the detected plan)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - account: updated metadata with email, label, color, plan_type
+#[tauri::command]
+pub async fn update_usage_account(
+    state: State<'_, AppState>,
+    email: String,
+    label: Option<String>,
+    color: Option<String>,
+    plan_type: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args: Vec<String> = vec![
+        "update-usage-account".to_string(),
+        format!("--email={}", email),
+    ];
+
+    if let Some(l) = label {
+        args.push(format!("--label={}", l));
+    }
+
+    if let Some(c) = color {
+        args.push(format!("--color={}", c));
+    }
+
+    if let Some(p) = plan_type {
+        args.push(format!("--plan-type={}", p));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Compare usage accounts side by side for a date range.
+///
+/// # Arguments
+///
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `emails` - Optional subset of account emails (default: all)
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - range: date range
+/// - accounts: per-account totals (snapshots, peak/avg session and week usage)
+/// - metrics: comparison matrix rows with per-account values
+#[tauri::command]
+pub async fn compare_usage_accounts(
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    emails: Option<Vec<String>>,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec![
+        "compare-accounts".to_string(),
+        "--from".to_string(),
+        from,
+        "--to".to_string(),
+        to,
+    ];
+
+    for email in emails.unwrap_or_default() {
+        args.push(format!("--email={}", email));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    conditional(call_backend(&state.backend, &request_id, &args_refs).await, if_none_match)
+}
+
+/// Get a combined dashboard of several usage accounts.
+///
+/// Each account is queried concurrently (usage from its bound config dirs plus
+/// its cc_usage snapshot totals), then merged into one payload.
+///
+/// # Arguments
+///
+/// * `from` - Start date (YYYY-MM-DD)
+/// * `to` - End date (YYYY-MM-DD)
+/// * `account_ids` - Usage account emails (max 8, duplicates ignored)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - range: date range
+/// - accounts: per-account payloads in the order of `account_ids` (account, config_dirs, totals, daily)
+/// - totals: combined messages, sessions, tokens and cost, plus cost_share_pct per account
+/// - daily: combined messages, tokens and cost per date, with by_account cost
+/// - metrics: comparison matrix rows (key, label, values per account, max_email)
+#[tauri::command]
+pub async fn get_combined_dashboard(
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    account_ids: Vec<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut emails: Vec<String> = Vec::with_capacity(account_ids.len());
+    for email in account_ids {
+        if !emails.contains(&email) {
+            emails.push(email);
+        }
+    }
+    if emails.is_empty() || emails.len() > MAX_COMBINED_ACCOUNTS {
+        return Err(CommandError::invalid_argument(format!(
+            "Expected 1 to {} accounts, got {}",
+            MAX_COMBINED_ACCOUNTS,
+            emails.len()
+        )));
+    }
+
+    let cancel = CancelOnDrop::new(&request_id);
+    let handles: Vec<_> = emails
+        .into_iter()
+        .map(|email| {
+            let args = [
+                "account-dashboard".to_string(),
+                "--from".to_string(),
+                from.clone(),
+                "--to".to_string(),
+                to.clone(),
+                format!("--email={}", email),
+            ];
+            let request_id = request_id.clone();
+            let backend = state.backend.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+                backend.call(ApiRequest::new(&request_id, &args_refs))
+            })
+        })
+        .collect();
+
+    let mut accounts = Vec::with_capacity(handles.len());
+    for handle in handles {
+        accounts.push(
+            handle
+                .await
+                .map_err(|e| format!("Account task failed: {}", e))??,
+        );
+    }
+
+    cancel.disarm();
+    Ok(combine_account_dashboards(&from, &to, accounts))
+}
+
+/// Detect Claude config directories on this machine.
+///
+/// Covers the default locations, `CLAUDE_CONFIG_DIR` and `~/.claude-*` variants.
+///
+/// # Arguments
+///
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - configs: array of config directory objects with:
+///   - config_dir: absolute path
+///   - is_default / from_env: how the directory was found
+///   - session_files: number of session JSONL files
+///   - last_activity: ISO timestamp of the newest session file
+///   - detected_email: account email Claude Code stored for the directory
+///   - account_email: usage account the directory is bound to
+///   - messages: ingested message count
+#[tauri::command]
+pub async fn detect_claude_accounts(
+    state: State<'_, AppState>,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    conditional(call_backend(&state.backend, &request_id, &["claude-accounts"]).await, if_none_match)
+}
+
+/// Bind a Claude config directory to a usage account.
+///
+/// # Arguments
+///
+/// * `config_dir` - Claude config directory path
+/// * `email` - Usage account email (None removes the binding)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - binding: config_dir and account_email
+#[tauri::command]
+pub async fn bind_claude_account(
+    state: State<'_, AppState>,
+    config_dir: String,
+    email: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args: Vec<String> = vec![
+        "bind-claude-account".to_string(),
+        format!("--config-dir={}", config_dir),
+    ];
+
+    if let Some(e) = email {
+        args.push(format!("--email={}", e));
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Detect subscription plans from local Claude files.
+///
+/// Reads plan hints (credentials tier, state file, statsig cache, limit
+/// messages) for every Claude config directory and stores the result on
+/// bound usage accounts. A plan set via `update_usage_account` overrides it.
+///
+/// # Arguments
+///
+/// * `apply` - Store detected plans on bound accounts (default: true)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - configs: per config directory plan_type, confidence and evidence
+/// - accounts: effective plan per account with plan_source (manual/detected)
+#[tauri::command]
+pub async fn detect_plan(
+    state: State<'_, AppState>,
+    apply: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let apply_str = if apply.unwrap_or(true) { "1" } else { "0" };
+    call_backend(&state.backend, &request_id, &["detect-plan", "--apply", apply_str]).await
+}
+
+/// Update project metadata fields.
+///
+/// Arguments go to the backend as JSON on stdin rather than argv, so long
+/// free-text descriptions are safe.
+///
+/// # Arguments
+///
+/// * `project_id` - Project identifier (required)
+/// * `name` - New display name (optional)
+/// * `description` - New description (optional)
+/// * `visible` - Visibility flag (optional)
+/// * `tags` - Tag list (optional, empty list clears tags)
+/// * `color` - Hex color "#rrggbb" used in charts and PNG exports (optional, empty clears it)
+/// * `icon` - Emoji or short icon name (optional, empty clears it)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - project: updated project object
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn update_project(
+    state: State<'_, AppState>,
+    project_id: String,
+    name: Option<String>,
+    description: Option<String>,
+    visible: Option<bool>,
+    tags: Option<Vec<String>>,
+    color: Option<String>,
+    icon: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    // Build args as owned Strings to avoid lifetime issues
+    // Use --key=value format to avoid issues with project_id starting with hyphen
+    let mut args: Vec<String> = vec![
+        "update-project".to_string(),
+        format!("--project-id={}", project_id),
+    ];
+
+    // Collect optional parameters
+    if let Some(n) = name {
+        args.push(format!("--name={}", n));
+    }
+
+    if let Some(d) = description {
+        args.push(format!("--description={}", d));
+    }
+
+    if let Some(v) = visible {
+        args.push(format!("--visible={}", if v { "1" } else { "0" }));
+    }
+
+    if let Some(t) = tags {
+        args.push(format!("--tags={}", t.join(",")));
+    }
+
+    if let Some(c) = color {
+        args.push(format!("--color={}", c));
+    }
+
+    if let Some(i) = icon {
+        args.push(format!("--icon={}", i));
+    }
+
+    // Convert to &str for the backend call
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Suggest a project description from the project's recent sessions.
+///
+/// The suggestion is only returned; apply it with `update_project`.
+///
+/// # Arguments
+///
+/// * `project_id` - Project identifier
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - project_id, current_description
+/// - suggestion: candidate description (null if the sessions had nothing to go on)
+/// - source: "local" (keyword extraction) or "llm" (see `set_description_llm`)
+/// - keywords, languages, files: topics extracted from the sessions
+/// - sessions_analyzed: number of session files read
+/// - llm_error: why the LLM call failed (only present when it did)
+#[tauri::command]
+pub async fn suggest_project_description(
+    state: State<'_, AppState>,
+    project_id: String,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let project_arg = format!("--project-id={}", project_id);
+    call_backend(&state.backend, &request_id, &["suggest-description", &project_arg]).await
+}
+
+/// Get the lifetime timeline of a project (project profile).
+///
+/// # Arguments
+///
+/// * `project_id` - Project identifier
+/// * `granularity` - Curve resolution: "day", "week", "month" or "auto" (default, by history length)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - project: project metadata with display_color
+/// - granularity: resolution used for the curve
+/// - first_session, last_session: {session_id, timestamp} (null without sessions)
+/// - totals: messages, sessions, tokens, cost, active_days, models
+/// - curve: [{period, messages, sessions, tokens, cost, cumulative_cost}]
+/// - milestones: [{type, date, ...}] with type first_session, new_model, cost_milestone,
+///   biggest_day or last_session, sorted by date
+#[tauri::command]
+pub async fn get_project_timeline(
+    state: State<'_, AppState>,
+    project_id: String,
+    granularity: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args: Vec<String> = vec![
+        "project-timeline".to_string(),
+        format!("--project-id={}", project_id),
+    ];
+    if let Some(g) = granularity {
+        args.push(format!("--granularity={}", g));
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Correct `first_seen` of all projects to their first session in the database.
+///
+/// # Arguments
+///
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - updated: [{project_id, previous, first_seen}] for projects that moved
+/// - checked: number of projects with messages
+#[tauri::command]
+pub async fn backfill_project_first_seen(
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["backfill-first-seen"]).await
+}
+
+/// List projects without activity for N days, with their storage footprint.
+///
+/// # Arguments
+///
+/// * `inactive_days` - Inactivity threshold in days (default 90)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - inactive_days: the threshold used
+/// - projects: [{project_id, name, last_activity, days_inactive, messages, sessions, cost,
+///   source_files, source_bytes, mirror_bytes, suggestion ("archive" or null), reason}],
+///   longest inactive first
+/// - totals: projects, suggested, source_bytes, mirror_bytes
+#[tauri::command]
+pub async fn get_stale_projects(
+    state: State<'_, AppState>,
+    inactive_days: Option<u32>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let days_arg = format!("--inactive-days={}", inactive_days.unwrap_or(90));
+    call_backend(&state.backend, &request_id, &["stale-projects", &days_arg]).await
+}
+
+/// Archive projects: hide them from the project selector and unpin them.
+///
+/// Usage data is kept; making a project visible again restores it.
+///
+/// # Arguments
+///
+/// * `project_ids` - Projects to archive
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - projects: the updated projects
+#[tauri::command]
+pub async fn archive_projects(
+    state: State<'_, AppState>,
+    project_ids: Vec<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let ids_arg = format!("--project-ids={}", project_ids.join(","));
+    call_backend(&state.backend, &request_id, &["archive-projects", &ids_arg]).await
+}
+
+fn description_llm_json(settings: &Settings) -> Value {
+    serde_json::json!({
+        "enabled": settings.description_llm.unwrap_or(false),
+        "model": settings.description_llm_model,
+        "api_key_set": std::env::var_os("ANTHROPIC_API_KEY").is_some_and(|key| !key.is_empty()),
+    })
+}
+
+/// Get the LLM opt-in of project description suggestions.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - enabled: suggestions are phrased with the Messages API
+/// - model: configured model (null: backend default)
+/// - api_key_set: `ANTHROPIC_API_KEY` is set (without it suggestions stay local)
+#[tauri::command]
+pub async fn get_description_llm_settings(state: State<'_, AppState>) -> Result<Value, CommandError> {
+    Ok(description_llm_json(&state.settings.load()))
+}
+
+/// Turn LLM-phrased project description suggestions on or off.
+///
+/// Only extracted summaries, keywords and file names are sent, never transcripts.
+///
+/// # Arguments
+///
+/// * `enabled` - Whether to call the Messages API
+/// * `model` - Model name (empty clears it; unchanged if omitted)
+///
+/// # Returns
+///
+/// The stored state (see `get_description_llm_settings`)
+#[tauri::command]
+pub async fn set_description_llm(
+    state: State<'_, AppState>,
+    enabled: bool,
+    model: Option<String>,
+) -> Result<Value, CommandError> {
+    let settings = state.settings.update(|settings| {
+        if let Some(model) = model {
+            let model = model.trim().to_string();
+            settings.description_llm_model = Some(model).filter(|model| !model.is_empty());
+        }
+        settings.description_llm = Some(enabled);
+        Ok(())
+    })?;
+    Ok(description_llm_json(&settings))
+}
+
+/// List workspaces (groups of projects).
+///
+/// # Arguments
+///
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - workspaces: list of {workspace_id, name, description, created_at, projects}
+#[tauri::command]
+pub async fn get_workspaces(
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["workspaces"]).await
+}
+
+/// Create a workspace.
+///
+/// # Arguments
+///
+/// * `name` - Display name (unique)
+/// * `description` - Description (optional)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - workspace: the new workspace, with its generated workspace_id
+#[tauri::command]
+pub async fn create_workspace(
+    state: State<'_, AppState>,
+    name: String,
+    description: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args: Vec<String> = vec!["create-workspace".to_string(), format!("--name={}", name)];
+    if let Some(d) = description {
+        args.push(format!("--description={}", d));
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Delete a workspace; its projects become unassigned.
+///
+/// # Arguments
+///
+/// * `workspace_id` - Workspace identifier
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - deleted: workspace_id
+/// - unassigned_projects: number of projects removed from the workspace
+#[tauri::command]
+pub async fn delete_workspace(
+    state: State<'_, AppState>,
+    workspace_id: String,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let workspace_arg = format!("--workspace-id={}", workspace_id);
+    call_backend(&state.backend, &request_id, &["delete-workspace", &workspace_arg]).await
+}
+
+/// Move a project into a workspace, or out of its workspace.
+///
+/// # Arguments
+///
+/// * `project_id` - Project identifier
+/// * `workspace_id` - Target workspace (omit to remove the project from its workspace)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - project_id, workspace_id, previous_workspace_id
+#[tauri::command]
+pub async fn assign_project_to_workspace(
+    state: State<'_, AppState>,
+    project_id: String,
+    workspace_id: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args: Vec<String> = vec![
+        "assign-project-workspace".to_string(),
+        format!("--project-id={}", project_id),
+    ];
+    if let Some(wid) = workspace_id {
+        args.push(format!("--workspace-id={}", wid));
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Get status of the zstd record mirror used for fast cold rebuilds.
+///
+/// # Arguments
+///
+/// * `if_none_match` - ETag from a previous response; returns `{not_modified, etag}` if unchanged
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - enabled: whether COMMAND_CENTER_MIRROR=1 is set
+/// - available: whether a zstd codec is installed
+/// - codec: codec module name or null
+/// - path: mirror directory
+/// - files / bytes: mirror size on disk
+#[tauri::command]
+pub async fn get_mirror_status(
+    state: State<'_, AppState>,
+    if_none_match: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    conditional(call_backend(&state.backend, &request_id, &["mirror-status"]).await, if_none_match)
+}
+
+/// Delete all zstd record mirror files.
+///
+/// # Arguments
+///
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - deleted: number of mirror files removed
+/// - bytes: disk space freed
+#[tauri::command]
+pub async fn clear_mirror(
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["clear-mirror"]).await
+}
+
+/// Get Python bridge metrics for this app session.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - calls: completed bridge calls
+/// - failures: calls that failed after all attempts
+/// - retries: extra attempts made for transient failures (locked database, truncated output)
+/// - recovered: calls that succeeded after a retry
+/// - timeouts: calls killed after exceeding their timeout
+/// - cancelled: calls stopped by `cancel_request`
+/// - in_flight: calls running now
+/// - retry_policy: max_attempts, base_delay_ms, max_delay_ms, patterns (transient stderr fragments)
+/// - timeout_policy: default_secs, long_secs (refresh, backups, exports)
+/// - worker: persistent Python worker state (enabled, pid, spawns, requests, crashes, fallbacks)
+/// - cache: response cache of dashboard, projects and limits (ttl_secs, entries, hits, misses, invalidations)
+/// - backend_log_events: whether `backend-log` events are emitted
+/// - commands: invokes and middleware rejections per command ({invokes, rejected: {validation, read_only}})
+/// - tasks: background tasks of the app by name (true while running)
+#[tauri::command]
+pub async fn get_bridge_metrics(state: State<'_, AppState>) -> Result<Value, CommandError> {
+    let mut metrics = bridge_metrics();
+    metrics["commands"] = middleware::command_metrics();
+    metrics["tasks"] = state.tasks.status();
+    Ok(metrics)
+}
+
+/// Check the Python backend setup for the troubleshooting screen.
+///
+/// Starts each backend the bridge would try (bundled sidecar or interpreter,
+/// virtualenvs, then `python`, `python3` and `uv run python` from `PATH`) outside the
+/// worker, so it also explains why calls fail with "No Python backend found".
+/// Each probe is limited to 15 s.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - ok: whether a backend can run `command_center.tauri_api`
+/// - active_backend: the first working backend (the one calls use)
+/// - diagnostics: app_version, python (executable, version), module_path, data_dir,
+///   db (path, exists, size, wal_size in bytes) reported by that backend
+/// - backends: per backend: backend, kind, path, found, interpreter_version, importable,
+///   diagnostics, error (CommandError object or message)
+/// - bundled: path and enabled state of the bundled backend
+/// - worker_enabled: whether calls use the persistent worker
+/// - path: PATH directories in search order
+#[tauri::command]
+pub async fn diagnose_environment() -> Result<Value, CommandError> {
+    // Probes run Python processes; keep them off the async runtime
+    let diagnostics = tauri::async_runtime::spawn_blocking(diagnose_python_environment)
+        .await
+        .map_err(|e| format!("Diagnostics task failed: {}", e))?;
+    Ok(diagnostics)
+}
+
+/// Benchmark this machine for performance reports.
+///
+/// The backend writes synthetic session logs into a throwaway home and times
+/// the real ingestion, a dashboard aggregation over the whole range and a
+/// refresh that finds nothing to do, once per dataset size; real data is
+/// never read. 100k messages take about a minute, 1M several minutes and
+/// ~500 MB of temporary disk space.
+///
+/// # Arguments
+///
+/// * `sizes` - Dataset sizes in messages: 10000, 100000 or 1000000 (default: 10000)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - machine: app version, platform, CPU count, Python and SQLite versions
+/// - results: per size messages, files, ingest_seconds, messages_per_second,
+///   ingest_workers, aggregate_seconds, cached_refresh_seconds, db_bytes
+/// - total_seconds
+/// - desktop: desktop app version, OS and architecture
+#[tauri::command]
+pub async fn run_benchmark(
+    state: State<'_, AppState>,
+    sizes: Option<Vec<u32>>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec!["benchmark".to_string()];
+    if let Some(sizes) = sizes.filter(|sizes| !sizes.is_empty()) {
+        let sizes: Vec<String> = sizes.iter().map(u32::to_string).collect();
+        args.push(format!("--sizes={}", sizes.join(",")));
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let mut result = call_backend(&state.backend, &request_id, &args_refs).await?;
+    if let Some(report) = result.as_object_mut() {
+        report.insert(
+            "desktop".to_string(),
+            serde_json::json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "os": std::env::consts::OS,
+                "arch": std::env::consts::ARCH,
+            }),
+        );
+    }
+    Ok(result)
+}
+
+/// Turn `backend-log` events on or off.
+///
+/// Python stderr output (warnings, tracebacks, other messages) is always
+/// written to the app log under the `python` target, with a level derived
+/// from the line. With events on, each line is also emitted as `backend-log`:
+/// request_id ("worker" for worker output outside a request), backend, level
+/// (error, warn, info, debug) and message. Events are off by default; a log
+/// viewer turns them on while it is open.
+///
+/// # Arguments
+///
+/// * `enabled` - Whether to emit the events
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - enabled: the new state
+#[tauri::command]
+pub async fn set_backend_log_events(enabled: bool) -> Result<Value, CommandError> {
+    set_bridge_log_events(enabled);
+    Ok(serde_json::json!({ "enabled": enabled }))
+}
+
+/// Cancel the running Python calls of a request.
+///
+/// The Python process is killed and the cancelled call fails with a
+/// `Cancelled:` error. The UI calls this when a query is aborted.
+///
+/// # Arguments
+///
+/// * `request_id` - Request ID the calls were started with
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - request_id: the request ID
+/// - cancelled: false if no call with this ID was running
+#[tauri::command]
+pub async fn cancel_request(request_id: String) -> Result<Value, CommandError> {
+    let cancelled = cancel_bridge_request(&request_id);
+    Ok(serde_json::json!({
+        "request_id": request_id,
+        "cancelled": cancelled,
+    }))
+}
+
+/// List installed metric plugins.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - directory: plugin directory
+/// - plugins: name, version, description, enabled, path, size_bytes and
+///   error (why the module cannot run, or null)
+#[tauri::command]
+pub async fn list_plugins() -> Result<Value, CommandError> {
+    let dir = plugins::plugin_dir()?;
+    let installed = plugins::list_plugins(&dir)?;
+    Ok(serde_json::json!({
+        "directory": dir.display().to_string(),
+        "plugins": installed,
+    }))
+}
+
+/// Install a WASM metric plugin.
+///
+/// The module is validated (no imports, `Metric` exports, dry run on an empty
+/// record set) before it is copied into the plugin directory. A `<name>.json`
+/// manifest next to the module supplies name, version and description.
+///
+/// # Arguments
+///
+/// * `path` - Path of the `.wasm` module
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// The installed plugin (see `list_plugins`)
+#[tauri::command]
+pub async fn install_plugin(path: String, request_id: Option<String>) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let dir = plugins::plugin_dir()?;
+    let installed = tauri::async_runtime::spawn_blocking(move || plugins::install_plugin(&dir, std::path::Path::new(&path)))
+        .await
+        .map_err(|e| CommandError::internal(format!("Install task failed: {}", e)))?
+        .map_err(|e| CommandError::invalid_argument(e).with_request_id(&request_id))?;
+    Ok(serde_json::to_value(installed)?)
+}
+
+/// Resolve a relative date-range preset to concrete dates.
+///
+/// Timezone and week start come from the shared settings (see
+/// `set_range_settings`), so every caller gets the same dates for a preset.
+///
+/// # Arguments
+///
+/// * `preset` - today, yesterday, "last N days", this-week, last-week, month-to-date,
+///   last-month, year-to-date, last-year, this-billing-cycle, last-billing-cycle or
+///   since-last-limit-reset (spaces, `_` and `-` are interchangeable)
+/// * `today` - Reference date (YYYY-MM-DD) instead of today in the configured timezone
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - preset: canonical preset name
+/// - from / to: inclusive dates (YYYY-MM-DD)
+/// - days: number of days in the range
+/// - today, timezone, week_start: the values the preset was resolved with
+#[tauri::command]
+pub async fn resolve_range(
+    state: State<'_, AppState>,
+    preset: String,
+    today: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let preset = RangePreset::parse(&preset).map_err(CommandError::invalid_argument)?;
+    let settings = state.settings.load();
+    let today = match today {
+        Some(day) => NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+            .map_err(|_| CommandError::invalid_argument(format!("Invalid date: {}", day)))?,
+        None => ranges::today(&settings)?,
+    };
+    let week_start = ranges::week_start(&settings)?;
+
+    let last_limit_reset = if preset == RangePreset::SinceLastLimitReset {
+        let from = (today - Duration::days(60)).format("%Y-%m-%d").to_string();
+        let to = today.format("%Y-%m-%d").to_string();
+        let events = call_backend(&state.backend, &request_id, &["limits", "--from", &from, "--to", &to]).await?;
+        ranges::last_limit_reset(&events)
+    } else {
+        None
+    };
+
+    let ctx = RangeContext {
+        today,
+        week_start,
+        billing_anchor_day: ranges::billing_anchor_day(&settings),
+        last_limit_reset,
+    };
+    let (from, to) = ranges::resolve(preset, &ctx)?;
+    let resolved = ResolvedRange {
+        preset: preset.key(),
+        from: from.format("%Y-%m-%d").to_string(),
+        to: to.format("%Y-%m-%d").to_string(),
+        days: (to - from).num_days() + 1,
+        today: today.format("%Y-%m-%d").to_string(),
+        timezone: settings.timezone.clone().unwrap_or_else(|| "local".to_string()),
+        week_start: ranges::weekday_name(week_start).to_string(),
+    };
+    Ok(serde_json::to_value(resolved)?)
+}
+
+/// Get the timezone, week start and billing anchor day used to resolve
+/// range presets.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - timezone: "local", "UTC" or a fixed offset such as "+02:00"
+/// - week_start: first day of the week (monday ... sunday)
+/// - billing_anchor_day: day of the month the subscription renews (1 = calendar months)
+#[tauri::command]
+pub async fn get_range_settings(state: State<'_, AppState>) -> Result<Value, CommandError> {
+    let settings = state.settings.load();
+    let week_start = ranges::week_start(&settings).unwrap_or(chrono::Weekday::Mon);
+    let billing_anchor_day = ranges::billing_anchor_day(&settings);
+    Ok(serde_json::json!({
+        "timezone": settings.timezone.unwrap_or_else(|| "local".to_string()),
+        "week_start": ranges::weekday_name(week_start),
+        "billing_anchor_day": billing_anchor_day,
+    }))
+}
+
+/// Update the timezone, week start and/or billing anchor day.
+///
+/// The billing anchor day is also read by the Python backend, so budgets and
+/// plan utilization use the same billing cycles as the range presets.
+///
+/// # Arguments
+///
+/// * `timezone` - "local", "UTC" or a fixed offset such as "+02:00" (empty resets to local)
+/// * `week_start` - First day of the week, e.g. monday or sunday (empty resets to monday)
+/// * `billing_anchor_day` - Day of the month the subscription renews, 1-31 (0 resets to calendar months)
+///
+/// # Returns
+///
+/// The stored settings (see `get_range_settings`)
+#[tauri::command]
+pub async fn set_range_settings(
+    state: State<'_, AppState>,
+    timezone: Option<String>,
+    week_start: Option<String>,
+    billing_anchor_day: Option<u32>,
+) -> Result<Value, CommandError> {
+    state.settings.update(|settings| {
+        if let Some(tz) = timezone {
+            settings.timezone = Some(tz.trim().to_string()).filter(|tz| !tz.is_empty());
+        }
+        if let Some(day) = week_start {
+            settings.week_start = Some(day.trim().to_lowercase()).filter(|day| !day.is_empty());
+        }
+        if let Some(day) = billing_anchor_day {
+            if day > 31 {
+                return Err(CommandError::invalid_argument(format!(
+                    "Invalid billing anchor day: {} (expected 1-31)",
+                    day
+                )));
+            }
+            settings.billing_anchor_day = Some(day).filter(|day| *day > 1);
+        }
+        ranges::today(settings)?;
+        ranges::week_start(settings)?;
+        Ok(())
+    })?;
+    get_range_settings(state).await
+}
+
+/// Get schema versions of the app's stores.
+///
+/// Desktop-owned files (settings) are migrated on startup after a backup;
+/// the database is migrated by the Python backend on connect.
+///
+/// # Arguments
+///
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - app_version: desktop app version
+/// - stores: per file name, path, version, latest, status (missing, current, outdated,
+///   migrated, newer, invalid), backup, error
+/// - database: path, version, latest (or error if the backend is unavailable)
+/// - native_store: path, version (null before the first index), latest of the
+///   desktop's own SQLite store, or error
+#[tauri::command]
+pub async fn get_schema_info(
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let database = call_backend(&state.backend, &request_id, &["schema-info"]).await
+        .unwrap_or_else(|e| serde_json::json!({ "error": e }));
+    Ok(serde_json::json!({
+        "app_version": env!("CARGO_PKG_VERSION"),
+        "stores": schema::inspect_all(),
+        "database": database,
+        "native_store": store::schema_info(),
+    }))
+}
+
+/// Index the session logs into the desktop's own SQLite store.
+///
+/// Files are read from where the previous run stopped; unchanged files are
+/// skipped. Messages, sessions, models and daily aggregates are stored in
+/// `~/.claude/db/command-center-store.db` (see `store`), independent of the
+/// Python backend. Progress is emitted as `index-progress` events (see
+/// `get_index_status`).
+///
+/// # Arguments
+///
+/// * `full` - Drop the store's data and index every file from the start (default false)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - files, changed_files, reindexed_files: log files found, read, and read again from the start
+/// - new_messages, duplicate_messages, skipped_lines, bytes_read
+/// - workers: threads that read the logs (`COMMAND_CENTER_INGEST_WORKERS`, default CPU count up to 8)
+/// - write_seconds, seconds: time spent writing to the store, duration of the run
+#[tauri::command]
+pub async fn index_native_store(
+    app: AppHandle,
+    full: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let stats = tauri::async_runtime::spawn_blocking(move || {
+        let dirs = store::user_config_dirs()?;
+        let full = full.unwrap_or(false);
+        indexer::index(&mut Store::open_default()?, &dirs, full, indexer::MANUAL, indexer::emitter(&app))
+    })
+    .await
+    .map_err(|e| CommandError::internal(format!("Index task failed: {}", e)))?
+    .map_err(|e| CommandError::internal(e).with_request_id(&request_id))?;
+    log::info!(
+        "[{}] Indexed {} new messages from {} changed files in {}s",
+        request_id,
+        stats.new_messages,
+        stats.changed_files,
+        stats.seconds
+    );
+    Ok(serde_json::to_value(stats)?)
+}
+
+/// Get the state of the desktop's own SQLite store.
+///
+/// # Arguments
+///
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - path, version, latest, size_bytes
+/// - files, messages, sessions, models: row counts
+/// - first_date, last_date: span of the indexed messages (null when empty)
+/// - last_indexed_at: when a file was last indexed
+#[tauri::command]
+pub async fn get_native_store_status(request_id: Option<String>) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    tauri::async_runtime::spawn_blocking(|| Store::open_default()?.status())
+        .await
+        .map_err(|e| CommandError::internal(format!("Store task failed: {}", e)))?
+        .map_err(|e| CommandError::internal(e).with_request_id(&request_id))
+}
+
+/// Get the state of the native store's indexer (the startup catch-up, the
+/// log watcher or `index_native_store`); changes are also emitted as
+/// `index-progress` events.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - state: "idle" or "indexing"
+/// - reason: what started the running or last run ("startup", "watcher", "manual"; null before any)
+/// - files_done, files_total, bytes_read, percent: progress of the running or last run
+/// - started_at, finished_at: times of the running or last run
+/// - last_stats: result of the last finished run (see `index_native_store`), last_error: its error
+#[tauri::command]
+pub async fn get_index_status() -> Result<Value, CommandError> {
+    Ok(serde_json::to_value(indexer::status())?)
+}
+
+/// Get the versions compared in the startup handshake with the Python API.
+///
+/// # Arguments
+///
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - app_version: desktop app version
+/// - expected_api_version: Python API version this app was built for
+/// - backend: api_version, package_version, schema_version, python_version
+///   reported by the backend (null if it predates the handshake)
+/// - compatible: whether the API versions match
+/// - error: the `incompatible` error when they don't (null otherwise)
+#[tauri::command]
+pub async fn get_backend_version(
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let response = call_backend(&state.backend, &request_id, &[handshake::VERSION_SUBCOMMAND]).await;
+    handshake::report(response, &request_id)
+}
+
+fn telemetry_settings_json(settings: &Settings) -> Value {
+    serde_json::json!({
+        "enabled": telemetry::enabled(settings),
+        "opted_in": settings.telemetry_enabled.unwrap_or(false),
+        "kill_switch": telemetry::kill_switch(),
+        "endpoint": settings.telemetry_endpoint,
+    })
+}
+
+/// Get the telemetry opt-in state.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - enabled: opted in and not disabled by the kill switch
+/// - opted_in: the stored choice
+/// - kill_switch: `COMMAND_CENTER_TELEMETRY=off` is set (overrides the choice)
+/// - endpoint: where payloads are sent (null: nothing is sent)
+#[tauri::command]
+pub async fn get_telemetry_settings(state: State<'_, AppState>) -> Result<Value, CommandError> {
+    Ok(telemetry_settings_json(&state.settings.load()))
+}
+
+/// Opt in to or out of anonymous telemetry.
+///
+/// Opting out also discards everything collected in this session.
+///
+/// # Arguments
+///
+/// * `enabled` - Whether to send telemetry
+/// * `endpoint` - https URL payloads are sent to (empty clears it)
+///
+/// # Returns
+///
+/// The stored state (see `get_telemetry_settings`)
+#[tauri::command]
+pub async fn set_telemetry(
+    state: State<'_, AppState>,
+    enabled: bool,
+    endpoint: Option<String>,
+) -> Result<Value, CommandError> {
+    if enabled && telemetry::kill_switch() {
+        return Err(CommandError::invalid_argument(format!(
+            "Telemetry is disabled by {}",
+            telemetry::TELEMETRY_ENV
+        )));
+    }
+    let settings = state.settings.update(|settings| {
+        if let Some(url) = endpoint {
+            let url = url.trim().to_string();
+            if !url.is_empty() && !url.starts_with("https://") {
+                return Err(CommandError::invalid_argument("Telemetry endpoint must be an https:// URL"));
+            }
+            settings.telemetry_endpoint = Some(url).filter(|url| !url.is_empty());
+        }
+        settings.telemetry_enabled = Some(enabled);
+        Ok(())
+    })?;
+    if !enabled {
+        telemetry::clear();
+    }
+    Ok(telemetry_settings_json(&settings))
+}
+
+/// Show exactly what telemetry would send.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - would_send: enabled and an endpoint is configured
+/// - endpoint: target URL
+/// - payload: schema, app_version, os, arch, session_minutes, features (calls per
+///   backend command), errors (count per exception type), performance (p50/p90/p99 ms
+///   per command)
+#[tauri::command]
+pub async fn preview_telemetry_payload(state: State<'_, AppState>) -> Result<Value, CommandError> {
+    let settings = state.settings.load();
+    Ok(serde_json::json!({
+        "would_send": telemetry::enabled(&settings) && settings.telemetry_endpoint.is_some(),
+        "endpoint": settings.telemetry_endpoint,
+        "payload": telemetry::payload(),
+    }))
+}
+
+/// Send the current telemetry payload and start collecting anew.
+///
+/// # Arguments
+///
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - sent: true
+/// - status: HTTP status of the endpoint
+/// - bytes: payload size
+#[tauri::command]
+pub async fn send_telemetry(
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let settings = state.settings.load();
+    if !telemetry::enabled(&settings) {
+        return Err(CommandError::invalid_argument("Telemetry is not enabled").with_request_id(&request_id));
+    }
+    let endpoint = settings
+        .telemetry_endpoint
+        .ok_or_else(|| {
+            CommandError::invalid_argument("No telemetry endpoint configured").with_request_id(&request_id)
+        })?;
+    let endpoint_arg = format!("--endpoint={}", endpoint);
+    let payload_arg = format!("--payload={}", telemetry::payload());
+    let result = call_backend(&state.backend, &request_id, &["send-telemetry", &endpoint_arg, &payload_arg]).await?;
+    telemetry::clear();
+    Ok(result)
+}
+
+/// Get changelog entries, e.g. for a "What's new" dialog after an update.
+///
+/// # Arguments
+///
+/// * `since_version` - Only releases newer than this version (e.g. the last version the
+///   user saw); all releases if omitted. `Unreleased` entries are always included.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - current_version: version of the running app
+/// - releases: [{version, date, sections: [{title, items}]}], newest first
+#[tauri::command]
+pub async fn get_changelog(since_version: Option<String>) -> Result<Value, CommandError> {
+    let releases = changelog::releases_since(since_version.as_deref());
+    Ok(serde_json::json!({
+        "current_version": env!("CARGO_PKG_VERSION"),
+        "releases": releases,
+    }))
+}
+
+/// Get the feature flags of experimental subsystems.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - flags: [{name, description, enabled, default, source}] where source is
+///   "local", "remote" or "default"
+/// - remote_url: URL of the remote flag document (null if none)
+/// - remote_fetched_at: when it was last fetched
+#[tauri::command]
+pub async fn get_feature_flags() -> Result<Value, CommandError> {
+    Ok(features::flags_json(&features::FlagStore::load()))
+}
+
+/// Enable or disable a feature flag locally.
+///
+/// # Arguments
+///
+/// * `name` - Flag name (see `get_feature_flags`)
+/// * `enabled` - New value; omit to drop the local choice (remote value or default applies)
+///
+/// # Returns
+///
+/// All flags (see `get_feature_flags`)
+#[tauri::command]
+pub async fn set_feature_flag(name: String, enabled: Option<bool>) -> Result<Value, CommandError> {
+    let flag = features::find(&name)
+        .ok_or_else(|| CommandError::invalid_argument(format!("Unknown feature flag: {}", name)))?;
+    let mut store = features::FlagStore::load();
+    match enabled {
+        Some(enabled) => store.local.insert(flag.name.to_string(), enabled),
+        None => store.local.remove(flag.name),
+    };
+    store.save()?;
+    Ok(features::flags_json(&store))
+}
+
+/// Fetch the remote flag document and store its values.
+///
+/// The document is a JSON object `{"flags": {"name": true}}` (or the flags
+/// object alone). Local choices still win over remote values.
+///
+/// # Arguments
+///
+/// * `url` - https URL of the document; remembered for later refreshes (defaults to the
+///   stored URL)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// All flags (see `get_feature_flags`)
+#[tauri::command]
+pub async fn refresh_feature_flags(
+    state: State<'_, AppState>,
+    url: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut store = features::FlagStore::load();
+    let url = url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .or_else(|| store.remote_url.clone())
+        .ok_or_else(|| {
+            CommandError::invalid_argument("No feature flag URL configured").with_request_id(&request_id)
+        })?;
+    if !url.starts_with("https://") {
+        let error = CommandError::invalid_argument("Feature flag URL must be an https:// URL");
+        return Err(error.with_request_id(&request_id));
+    }
+
+    let url_arg = format!("--url={}", url);
+    let result = call_backend(&state.backend, &request_id, &["fetch-feature-flags", &url_arg]).await?;
+    let remote: BTreeMap<String, bool> = serde_json::from_value(result["flags"].clone())
+        .map_err(|e| {
+            CommandError::parse_error(format!("Invalid feature flag document: {}", e)).with_request_id(&request_id)
+        })?;
+
+    store.remote = remote;
+    store.remote_url = Some(url);
+    store.remote_fetched_at = Some(chrono::Local::now().to_rfc3339());
+    store.save()?;
+    Ok(features::flags_json(&store))
+}
+
+/// List the API tokens of the local HTTP server (without their secrets).
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - tokens: [{id, name, scopes, prefix, created_at, expires_at, revoked_at,
+///   last_used_at, status}], oldest first; status is "active", "expired" or "revoked"
+/// - scopes: the scopes a token can have
+#[tauri::command]
+pub async fn list_api_tokens() -> Result<Value, CommandError> {
+    let now = chrono::Local::now();
+    let tokens: Vec<Value> = api_tokens::list().iter().map(|token| token.summary(now)).collect();
+    Ok(serde_json::json!({ "tokens": tokens, "scopes": api_tokens::Scope::ALL }))
+}
+
+/// Create an API token for the local HTTP server.
+///
+/// The secret is returned only once; the app keeps its hash.
+///
+/// # Arguments
+///
+/// * `scopes` - "read" (statistics), "export" (reports, exports, backups) and/or
+///   "admin" (every subcommand)
+/// * `expiry` - RFC 3339 time the token stops working; never if omitted
+/// * `name` - Label shown in the token list
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// The token (see `list_api_tokens`) with `token`: the secret to send as
+/// `Authorization: Bearer <token>`
+#[tauri::command]
+pub async fn create_api_token(
+    scopes: Vec<String>,
+    expiry: Option<String>,
+    name: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    if scopes.is_empty() {
+        return Err(CommandError::invalid_argument("An API token needs at least one scope").with_request_id(&request_id));
+    }
+    let scopes = scopes
+        .iter()
+        .map(|scope| api_tokens::Scope::parse(scope))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| CommandError::invalid_argument(e).with_request_id(&request_id))?;
+    let now = chrono::Local::now();
+    let expires_at = match expiry {
+        Some(expiry) => {
+            let at = chrono::DateTime::parse_from_rfc3339(&expiry)
+                .map_err(|e| {
+                    CommandError::invalid_argument(format!("Invalid expiry: {} ({})", expiry, e))
+                        .with_request_id(&request_id)
+                })?
+                .with_timezone(&chrono::Local);
+            if at <= now {
+                return Err(CommandError::invalid_argument(format!("Expiry {} is in the past", expiry))
+                    .with_request_id(&request_id));
+            }
+            Some(at)
+        }
+        None => None,
+    };
+    let name = name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+
+    let (token, secret) = api_tokens::create(name, scopes, expires_at, &request_id)
+        .map_err(|e| e.with_request_id(&request_id))?;
+    let mut result = token.summary(now);
+    result["token"] = Value::from(secret);
+    Ok(result)
+}
+
+/// Revoke an API token; requests with it are refused from now on.
+///
+/// # Arguments
+///
+/// * `id` - Token ID (see `list_api_tokens`)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// The revoked token (see `list_api_tokens`)
+#[tauri::command]
+pub async fn revoke_api_token(id: String, request_id: Option<String>) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let token = api_tokens::revoke(&id, &request_id).map_err(|e| e.with_request_id(&request_id))?;
+    Ok(token.summary(chrono::Local::now()))
+}
+
+fn http_server_settings_json(settings: &Settings) -> Value {
+    let config = http_server::listen_config(settings, http_server::has_active_token());
+    serde_json::json!({
+        "enabled": http_server::enabled(),
+        "port": settings.http_port.unwrap_or(http_server::DEFAULT_PORT),
+        "bind_address": settings.http_bind_address,
+        "allow_remote": settings.http_allow_remote.unwrap_or(false),
+        "tls_cert": settings.http_tls_cert,
+        "tls_key": settings.http_tls_key,
+        "tls_client_ca": settings.http_tls_client_ca,
+        "url": config.as_ref().ok().map(http_server::ListenConfig::url),
+        "mutual_tls": config.as_ref().is_ok_and(|config| config.mutual_tls),
+        "error": config.err(),
+    })
+}
+
+/// Get the listener settings of the local HTTP server.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - enabled: the `http_server` feature flag is on
+/// - port, bind_address (null: 127.0.0.1), allow_remote
+/// - tls_cert, tls_key, tls_client_ca: PEM file paths (null: plain HTTP, no client certificates)
+/// - url: where the server listens with these settings
+/// - mutual_tls: client certificates are required
+/// - error: why the server would not start (e.g. a non-loopback address without override), or null
+#[tauri::command]
+pub async fn get_http_server_settings(state: State<'_, AppState>) -> Result<Value, CommandError> {
+    Ok(http_server_settings_json(&state.settings.load()))
+}
+
+/// Change where and how the local HTTP server listens; applies when the app restarts.
+///
+/// Settings the server would refuse to start with (a non-loopback address
+/// without `allow_remote` or without an active API token, unreadable or
+/// mismatched certificates) are rejected.
+///
+/// # Arguments
+///
+/// * `port` - TCP port (unchanged if omitted)
+/// * `bind_address` - IP address or "localhost" (empty: 127.0.0.1; unchanged if omitted)
+/// * `allow_remote` - Allow a non-loopback bind address (unchanged if omitted)
+/// * `tls_cert` / `tls_key` - PEM certificate chain and key for HTTPS (empty clears; unchanged if omitted)
+/// * `tls_client_ca` - PEM CA certificates of accepted clients, for mTLS (empty clears; unchanged if omitted)
+///
+/// # Returns
+///
+/// The stored settings (see `get_http_server_settings`)
+#[tauri::command]
+pub async fn set_http_server_settings(
+    state: State<'_, AppState>,
+    port: Option<u16>,
+    bind_address: Option<String>,
+    allow_remote: Option<bool>,
+    tls_cert: Option<String>,
+    tls_key: Option<String>,
+    tls_client_ca: Option<String>,
+) -> Result<Value, CommandError> {
+    let non_empty = |value: String| Some(value.trim().to_string()).filter(|value| !value.is_empty());
+    let has_active_token = http_server::has_active_token();
+    let settings = state.settings.update(|settings| {
+        if let Some(port) = port {
+            settings.http_port = Some(port);
+        }
+        if let Some(address) = bind_address {
+            settings.http_bind_address = non_empty(address);
+        }
+        if let Some(allow_remote) = allow_remote {
+            settings.http_allow_remote = Some(allow_remote);
+        }
+        if let Some(path) = tls_cert {
+            settings.http_tls_cert = non_empty(path);
+        }
+        if let Some(path) = tls_key {
+            settings.http_tls_key = non_empty(path);
+        }
+        if let Some(path) = tls_client_ca {
+            settings.http_tls_client_ca = non_empty(path);
+        }
+        http_server::listen_config(settings, has_active_token).map_err(CommandError::invalid_argument)?;
+        Ok(())
+    })?;
+    Ok(http_server_settings_json(&settings))
+}
+
+/// Queue an export; it runs in the background and is recorded in the export history.
+///
+/// Emits `export-completed` or `export-failed` with the job when it finishes.
+///
+/// # Arguments
+///
+/// * `kind` - "png_report" (params: from, to), "cost_allocation" (params: month, format)
+///   or "raw_sessions" (params: session_ids, redact)
+/// * `params` - Export parameters (JSON object)
+/// * `output_path` - Path of the file to write, relative to the export directory
+///   (default: the default file name in the export directory)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// The queued job: id, kind, params, output_path, status ("queued"), request_id, created_at
+#[tauri::command]
+pub async fn enqueue_export(
+    app: AppHandle,
+    kind: exports::ExportKind,
+    params: Value,
+    output_path: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let output_path = exports::default_export_dir(&app)
+        .join(
+            output_path
+                .filter(|p| !p.trim().is_empty())
+                .unwrap_or_else(|| exports::default_filename(kind, &params)),
+        )
+        .display()
+        .to_string();
+    let job = exports::create(kind, params, output_path, request_id.clone(), None)
+        .map_err(|e| CommandError::invalid_argument(e).with_request_id(&request_id))?;
+    exports::submit(&app, job.id.clone())?;
+    Ok(serde_json::to_value(job)?)
+}
+
+/// List export jobs, newest first.
+///
+/// # Arguments
+///
+/// * `limit` - Maximum number of jobs (default: 50)
+/// * `status` - Only jobs with this status: "queued", "running", "succeeded" or "failed"
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - exports: jobs with id, kind, params, output_path, status, request_id, created_at,
+///   started_at, finished_at, size, error, rerun_of, result (backend summary)
+#[tauri::command]
+pub async fn list_exports(
+    limit: Option<usize>,
+    status: Option<exports::ExportStatus>,
+) -> Result<Value, CommandError> {
+    let jobs = exports::list(limit.unwrap_or(50), status);
+    Ok(serde_json::json!({ "exports": jobs }))
+}
+
+/// Queue a previous export again with the same parameters and output path.
+///
+/// # Arguments
+///
+/// * `id` - Job to re-run (see `list_exports`)
+///
+/// # Returns
+///
+/// The new queued job (its `rerun_of` is `id`)
+#[tauri::command]
+pub async fn rerun_export(app: AppHandle, id: String) -> Result<Value, CommandError> {
+    let job = exports::rerun(&id)?;
+    exports::submit(&app, job.id.clone())?;
+    Ok(serde_json::to_value(job)?)
+}
+
+/// Upcoming fire times of a schedule rule, to check it before relying on it.
+///
+/// Rules are wall-clock times: "weekly on monday at 08:00" fires at 08:00 local
+/// time before and after a DST switch. On a day the time falls into a DST gap
+/// it fires that much later ("shifted_forward"); when it occurs twice it fires
+/// once, at the first ("first_of_two").
+///
+/// # Arguments
+///
+/// * `rule` - {frequency: "daily" | "weekly" | "monthly", weekday (weekly), day: 1-31 (monthly,
+///   the last day in shorter months), time: "HH:MM", timezone: "local" | "UTC" | "+02:00"
+///   (default: the `timezone` setting)}
+/// * `count` - Number of fire times (default: 5, at most 50)
+/// * `after` - RFC 3339 timestamp to start after (default: now)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - timezone: zone the rule is evaluated in
+/// - fires: [{at (UTC), local (with the zone's offset), weekday, adjustment}]
+#[tauri::command]
+pub async fn get_schedule_preview(
+    rule: ScheduleRule,
+    count: Option<usize>,
+    after: Option<String>,
+) -> Result<Value, CommandError> {
+    let recurrence = rule.recurrence().map_err(CommandError::invalid_argument)?;
+    let zone = rule.zone(&Settings::load()).map_err(CommandError::invalid_argument)?;
+    let after = match after {
+        Some(after) => chrono::DateTime::parse_from_rfc3339(&after)
+            .map_err(|e| CommandError::invalid_argument(format!("Invalid timestamp {:?}: {}", after, e)))?
+            .with_timezone(&chrono::Utc),
+        None => chrono::Utc::now(),
+    };
+    let count = count.unwrap_or(schedule::DEFAULT_PREVIEW_COUNT).min(schedule::MAX_PREVIEW_COUNT);
+    Ok(serde_json::json!({
+        "timezone": zone.name(),
+        "fires": zone.preview(&recurrence, after, count),
+    }))
+}
+
+/// List scheduled exports with their next fire time.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - schedules: [{id, name, kind, params, rule, enabled, created_at, last_fired_at, last_job_id,
+///   next_fire (see `get_schedule_preview`, null if disabled or invalid)}]
+#[tauri::command]
+pub async fn list_export_schedules() -> Result<Value, CommandError> {
+    let settings = Settings::load();
+    let schedules: Vec<Value> = schedule::list()
+        .into_iter()
+        .map(|item| {
+            let next_fire = if item.enabled { schedule::next_fire_of(&item, &settings).ok().flatten() } else { None };
+            let mut value = serde_json::to_value(&item)?;
+            value["next_fire"] = serde_json::to_value(next_fire)?;
+            Ok(value)
+        })
+        .collect::<Result<_, serde_json::Error>>()?;
+    Ok(serde_json::json!({ "schedules": schedules }))
+}
+
+/// Add or replace a scheduled export.
+///
+/// Due schedules are queued by the export scheduler like `enqueue_export`, into the
+/// default export directory. A fire missed while the app was closed runs once on the
+/// next start.
+///
+/// # Arguments
+///
+/// * `id` - Schedule to replace (omit to add one)
+/// * `name` - Display name
+/// * `kind` - "png_report" or "cost_allocation"
+/// * `params` - Export parameters with a `range` preset instead of dates (e.g.
+///   {range: "last-month", format: "xlsx"}); resolved on the day it fires
+/// * `rule` - When it fires (see `get_schedule_preview`)
+/// * `enabled` - Default: true
+///
+/// # Returns
+///
+/// The stored schedule
+#[tauri::command]
+pub async fn set_export_schedule(
+    id: Option<String>,
+    name: Option<String>,
+    kind: exports::ExportKind,
+    params: Value,
+    rule: ScheduleRule,
+    enabled: Option<bool>,
+) -> Result<Value, CommandError> {
+    let stored = schedule::set(id, name, kind, params, rule, enabled.unwrap_or(true))
+        .map_err(CommandError::invalid_argument)?;
+    Ok(serde_json::to_value(stored)?)
+}
+
+/// Delete a scheduled export (its past export jobs stay in the history).
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - deleted: false if there was no such schedule
+#[tauri::command]
+pub async fn delete_export_schedule(id: String) -> Result<Value, CommandError> {
+    let deleted = schedule::delete(&id)?;
+    Ok(serde_json::json!({ "deleted": deleted }))
+}
+
+/// Write a signed backup of settings, project metadata and the database.
+///
+/// The archive's manifest lists every file with its SHA-256 and is signed with a
+/// key kept in the OS keychain (or a user-only key file), see `verify_backup`.
+///
+/// # Arguments
+///
+/// * `target_path` - Zip file to write (relative to the export directory); the save dialog
+///   opens if omitted
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - path, size, sha256: written archive
+/// - files: number of backed up files
+/// - components: e.g. ["cache", "projects", "settings"]
+/// - key_id, key_store: signing key identifier and where it is kept ("keychain" or "file")
+///
+/// or `{cancelled: true}` if the dialog was cancelled
+#[tauri::command]
+pub async fn create_backup(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    target_path: Option<String>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let suggested = format!("cc-backup-{}.zip", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let Some(path) = exports::choose_target(&app, target_path, &suggested)
+        .map_err(|e| CommandError::internal(e).with_request_id(&request_id))?
+    else {
+        return Ok(serde_json::json!({ "cancelled": true }));
+    };
+    let output_arg = format!("--output={}", path.display());
+    let mut result = call_backend(&state.backend, &request_id, &["create-backup", &output_arg]).await?;
+    let written = exports::file_info(&path).map_err(|e| CommandError::internal(e).with_request_id(&request_id))?;
+    if let Some(obj) = result.as_object_mut() {
+        obj.insert("size".to_string(), Value::from(written.size));
+        obj.insert("sha256".to_string(), Value::from(written.sha256));
+    }
+    Ok(result)
+}
+
+/// Verify a backup without restoring it.
+///
+/// # Arguments
+///
+/// * `path` - Backup archive
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - valid: all checksums match and the signature is valid
+/// - signature: "valid", "invalid" (manifest modified), "unknown_key" (signed by another
+///   key, e.g. on another machine) or "missing"
+/// - files_checked: number of files in the manifest
+/// - errors: checksum mismatches, missing or unlisted files
+/// - created_at, app_version, components: backup metadata
+#[tauri::command]
+pub async fn verify_backup(
+    state: State<'_, AppState>,
+    path: String,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let path_arg = format!("--path={}", path);
+    call_backend(&state.backend, &request_id, &["verify-backup", &path_arg]).await
+}
+
+/// Component of a backup; see the `components` parameter of `restore_backup`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupComponent {
+    /// Desktop settings, feature flags, budgets, hooks, cost centers, derived metrics, accounts
+    Settings,
+    /// Project names and descriptions
+    Projects,
+    /// The session database
+    Cache,
+}
+
+impl BackupComponent {
+    fn as_str(self) -> &'static str {
+        match self {
+            BackupComponent::Settings => "settings",
+            BackupComponent::Projects => "projects",
+            BackupComponent::Cache => "cache",
+        }
+    }
+}
+
+/// Restore a backup, or some of its components; it is verified first and nothing is
+/// written if verification fails.
+///
+/// # Arguments
+///
+/// * `path` - Backup archive
+/// * `force` - Accept a backup signed by another key (checksums must still match); default false
+/// * `components` - Components to restore (settings, projects, cache); default all in the backup
+/// * `dry_run` - Only report what would change (default: false)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - dry_run, components
+/// - files: per file `status` ("added", "modified", "unchanged"), with changed JSON key
+///   paths (`changes`) or, for the database, tables whose row counts differ (`tables`)
+/// - restored: restored archive members (unchanged files are skipped; empty for a dry run)
+/// - verification: result of `verify_backup`
+#[tauri::command]
+pub async fn restore_backup(
+    state: State<'_, AppState>,
+    path: String,
+    force: Option<bool>,
+    components: Option<Vec<BackupComponent>>,
+    dry_run: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec!["restore-backup".to_string(), format!("--path={}", path)];
+    if force.unwrap_or(false) {
+        args.push("--force".to_string());
+    }
+    if let Some(components) = components.filter(|c| !c.is_empty()) {
+        let names: Vec<&str> = components.iter().map(|c| c.as_str()).collect();
+        args.push(format!("--components={}", names.join(",")));
+    }
+    if dry_run.unwrap_or(false) {
+        args.push("--dry-run=1".to_string());
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Merge usage from a backup, database file or Claude data root of another machine.
+///
+/// Messages are matched on their entry hash and Anthropic message ID, so
+/// sessions that are in both sources are not counted twice; for each match
+/// the more complete record is kept. Only message entries are merged.
+///
+/// # Arguments
+///
+/// * `path` - Backup archive, `command_center.db` file or Claude config directory
+/// * `dry_run` - Only report what would change (default: false)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - source, kind ("backup", "database", "data_root"), dry_run
+/// - incoming, duplicates_in_source: rows read and collapsed within the source
+/// - added, identical, replaced, kept: outcome per message
+/// - double_counts_prevented: messages a naive union would have counted twice
+/// - tokens_added, cost_added, date_range
+/// - sessions: new and overlapping counts, details of overlapping sessions
+/// - projects, created_projects: projects that gained messages or were created
+#[tauri::command]
+pub async fn merge_data_source(
+    state: State<'_, AppState>,
+    path: String,
+    dry_run: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec!["merge-data".to_string(), format!("--path={}", path)];
+    if dry_run.unwrap_or(false) {
+        args.push("--dry-run=1".to_string());
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Move the timestamps of messages logged with a wrong system clock.
+///
+/// The selected messages get new UTC and local timestamps, dates and years,
+/// and the hourly and model aggregates of the old and new hours are rebuilt.
+/// Each applied shift is recorded and can be undone with `undo_timestamp_shift`.
+///
+/// # Arguments
+///
+/// * `filter` - Messages to move: `from`/`to` local dates, `sessionIds`, `projectId`, `configDir`
+/// * `offset` - Offset like "-1d", "+2h30m" or seconds
+/// * `preview` - Only report what would move (default: false)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - preview, shift_id (null for previews), offset_seconds
+/// - messages, sessions: counts moved
+/// - before, after: local date ranges ({from, to})
+/// - samples: up to 10 messages with local times before and after
+#[tauri::command]
+pub async fn shift_session_timestamps(
+    state: State<'_, AppState>,
+    filter: TimeShiftFilter,
+    offset: String,
+    preview: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    if filter.from.is_none() && filter.to.is_none() && filter.session_ids.is_empty() {
+        return Err(CommandError::invalid_argument("Select messages by date range or sessions")
+            .with_request_id(&request_id));
+    }
+    for day in filter.from.iter().chain(filter.to.iter()) {
+        NaiveDate::parse_from_str(day, "%Y-%m-%d").map_err(|_| {
+            CommandError::invalid_argument(format!("Invalid date: {}", day)).with_request_id(&request_id)
+        })?;
+    }
+
+    let mut args = vec!["shift-timestamps".to_string(), format!("--offset={}", offset)];
+    if let Some(from) = filter.from {
+        args.push(format!("--from={}", from));
+    }
+    if let Some(to) = filter.to {
+        args.push(format!("--to={}", to));
+    }
+    if !filter.session_ids.is_empty() {
+        args.push(format!("--session-ids={}", filter.session_ids.join(",")));
+    }
+    if let Some(project_id) = filter.project_id {
+        args.push(format!("--project-id={}", project_id));
+    }
+    if let Some(config_dir) = filter.config_dir {
+        args.push(format!("--config-dir={}", config_dir));
+    }
+    if preview.unwrap_or(false) {
+        args.push("--preview=1".to_string());
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// Undo a timestamp shift and rebuild the affected aggregates.
+///
+/// # Arguments
+///
+/// * `shift_id` - ID returned by `shift_session_timestamps`
+/// * `preview` - Only report what would move back (default: false)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// Same object as `shift_session_timestamps`
+#[tauri::command]
+pub async fn undo_timestamp_shift(
+    state: State<'_, AppState>,
+    shift_id: i64,
+    preview: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec!["undo-timestamp-shift".to_string(), format!("--shift-id={}", shift_id)];
+    if preview.unwrap_or(false) {
+        args.push("--preview=1".to_string());
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    call_backend(&state.backend, &request_id, &args_refs).await
+}
+
+/// List recorded timestamp shifts, newest first.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - shifts: Array of {shift_id, offset_seconds, filter, messages, created_at, undone_at}
+#[tauri::command]
+pub async fn list_timestamp_shifts(
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    call_backend(&state.backend, &request_id, &["timestamp-shifts"]).await
+}
+
+/// Get the presentation mode.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - read_only: state-changing commands are refused
+/// - blur_project_names: project names, descriptions and paths are hidden in responses
+/// - demo_data: seeded synthetic data is shown instead of real usage
+#[tauri::command]
+pub async fn get_presentation_mode() -> Result<Value, CommandError> {
+    Ok(serde_json::to_value(PresentationMode::load())?)
+}
+
+/// Turn presentation modes on or off, e.g. before screen sharing.
+///
+/// Emits `presentation-mode-changed` with the new mode so every window can show
+/// or hide its banner and refetch. This command stays available in read-only mode.
+/// Switching to demo data generates it with the default seed the first time.
+///
+/// # Arguments
+///
+/// * `read_only` - Refuse project edits, purges, restores and settings changes (unchanged if omitted)
+/// * `blur_project_names` - Replace project names with aliases such as "Project 3FA2" (unchanged if omitted)
+/// * `demo_data` - Show synthetic demo data instead of real usage (unchanged if omitted)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// The new mode (see `get_presentation_mode`)
+#[tauri::command]
+pub async fn set_presentation_mode(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    read_only: Option<bool>,
+    blur_project_names: Option<bool>,
+    demo_data: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let previous = state.settings.load();
+    let settings = state.settings.update(|settings| {
+        if let Some(read_only) = read_only {
+            settings.read_only = Some(read_only);
+        }
+        if let Some(blur) = blur_project_names {
+            settings.blur_project_names = Some(blur);
+        }
+        if let Some(demo) = demo_data {
+            settings.demo_data = Some(demo);
+        }
+        Ok(())
+    })?;
+    if demo_data == Some(true) && !presentation::demo_data_exists() {
+        if let Err(e) = call_backend(&state.backend, &request_id, &["generate-demo-data"]).await {
+            state.settings.update(|settings| {
+                settings.read_only = previous.read_only;
+                settings.blur_project_names = previous.blur_project_names;
+                settings.demo_data = previous.demo_data;
+                Ok(())
+            })?;
+            return Err(e);
+        }
+    }
+    let mode = PresentationMode::from_settings(&settings);
+    if let Err(e) = app.emit(presentation::MODE_CHANGED_EVENT, mode) {
+        log::warn!("[{}] Failed to emit {}: {}", request_id, presentation::MODE_CHANGED_EVENT, e);
+    }
+    Ok(serde_json::to_value(mode)?)
+}
+
+/// Get the privacy mode.
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - enabled: message content is stripped from every response, raw session stream and archive export
+#[tauri::command]
+pub async fn get_privacy_mode() -> Result<Value, CommandError> {
+    Ok(serde_json::json!({ "enabled": privacy::enabled() }))
+}
+
+/// Turn privacy mode on or off.
+///
+/// Turning it on is always allowed; turning it off is refused in read-only mode.
+///
+/// # Arguments
+///
+/// * `enabled` - Strip prompts, responses and tool I/O, leaving metadata and counts
+///
+/// # Returns
+///
+/// The new mode (see `get_privacy_mode`)
+#[tauri::command]
+pub async fn set_privacy_mode(state: State<'_, AppState>, enabled: bool) -> Result<Value, CommandError> {
+    state.settings.update(|settings| {
+        settings.privacy_mode = Some(enabled);
+        Ok(())
+    })?;
+    Ok(serde_json::json!({ "enabled": enabled }))
+}
+
+/// Regenerate the synthetic demo data (demo data mode only).
+///
+/// The same seed always produces the same sessions, so documentation
+/// screenshots can be reproduced.
+///
+/// # Arguments
+///
+/// * `seed` - Random seed (default: 42)
+/// * `months` - Months of history ending today, 1-24 (default: 6)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - seed, months, date_from, date_to
+/// - projects, sessions, messages, total_cost_usd: generated data
+#[tauri::command]
+pub async fn generate_demo_data(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    seed: Option<u64>,
+    months: Option<u32>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mode = PresentationMode::load();
+    if !mode.demo_data {
+        let error = CommandError::invalid_argument("Demo data can only be regenerated in demo data mode");
+        return Err(error.with_request_id(&request_id));
+    }
+    let mut args = vec!["generate-demo-data".to_string()];
+    if let Some(seed) = seed {
+        args.push(format!("--seed={}", seed));
+    }
+    if let Some(months) = months {
+        args.push(format!("--months={}", months));
+    }
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let result = call_backend(&state.backend, &request_id, &args_refs).await?;
+    if let Err(e) = app.emit(presentation::MODE_CHANGED_EVENT, mode) {
+        log::warn!("[{}] Failed to emit {}: {}", request_id, presentation::MODE_CHANGED_EVENT, e);
+    }
+    Ok(result)
+}
+
+/// Generate sample session logs to explore the app without real history.
+///
+/// Turns demo data mode on (the backend's temporary profile in the demo
+/// home, see `presentation.rs`), then writes synthetic Claude Code session
+/// logs into the demo home's `.claude/projects` and ingests them with the
+/// regular refresh. Unlike `generate_demo_data`, every feature reading logs
+/// (sessions, MCP calls, hooks, compactions, outcomes) has data to show.
+/// Real logs and the real database are never touched; turning demo data mode
+/// off returns to them. If generation fails, the previous mode is restored.
+///
+/// # Arguments
+///
+/// * `days` - Days of history ending today, 1-365 (default: 30)
+/// * `intensity` - Sessions per day: "light", "regular" or "heavy" (default: "regular")
+/// * `seed` - Random seed (default: 42)
+/// * `request_id` - Correlation ID for logs, audit log and errors (generated if omitted)
+///
+/// # Returns
+///
+/// JSON object containing:
+/// - seed, days, intensity, date_from, date_to
+/// - data_root: the sandbox config dir the logs were written to
+/// - projects, sessions, messages: generated logs; ingested_files: files the refresh read
+#[tauri::command]
+pub async fn generate_sample_data(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    days: Option<u32>,
+    intensity: Option<String>,
+    seed: Option<u64>,
+    request_id: Option<String>,
+) -> Result<Value, CommandError> {
+    let request_id = resolve_request_id(request_id);
+    let mut args = vec!["generate-sample-data".to_string()];
+    if let Some(days) = days {
+        if !(1..=365).contains(&days) {
+            return Err(CommandError::invalid_argument(format!("Invalid days: {} (expected 1-365)", days))
+                .with_request_id(&request_id));
+        }
+        args.push(format!("--days={}", days));
+    }
+    if let Some(intensity) = intensity {
+        if !presentation::SAMPLE_INTENSITIES.contains(&intensity.as_str()) {
+            return Err(CommandError::invalid_argument(format!(
+                "Unknown intensity: {} (expected {})",
+                intensity,
+                presentation::SAMPLE_INTENSITIES.join(", ")
+            ))
+            .with_request_id(&request_id));
+        }
+        args.push(format!("--intensity={}", intensity));
+    }
+    if let Some(seed) = seed {
+        args.push(format!("--seed={}", seed));
     }
 
-    // Convert to &str for call_python_api
+    let previous = state.settings.load().demo_data;
+    let settings = state.settings.update(|settings| {
+        settings.demo_data = Some(true);
+        Ok(())
+    })?;
     let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-    call_python_api(&args_refs)
+    let result = match call_backend(&state.backend, &request_id, &args_refs).await {
+        Ok(result) => result,
+        Err(e) => {
+            state.settings.update(|settings| {
+                settings.demo_data = previous;
+                Ok(())
+            })?;
+            return Err(e);
+        }
+    };
+    let mode = PresentationMode::from_settings(&settings);
+    if let Err(e) = app.emit(presentation::MODE_CHANGED_EVENT, mode) {
+        log::warn!("[{}] Failed to emit {}: {}", request_id, presentation::MODE_CHANGED_EVENT, e);
+    }
+    Ok(result)
 }
 
 #[cfg(test)]
 mod tests {
-    // Note: Tests removed as DashboardParams struct no longer exists
-    // Commands now use individual parameters for simpler frontend integration
+    use super::*;
+    use crate::backend::{ApiBackend, MockBackend};
+    use serde_json::json;
+    use std::sync::{mpsc, Arc, Condvar, Mutex};
+    use tauri::async_runtime::block_on;
+    use tauri::Manager;
+
+    fn mock_app(backend: SharedBackend) -> tauri::App<tauri::test::MockRuntime> {
+        tauri::test::mock_builder()
+            .manage(AppState::new(backend))
+            .build(tauri::test::mock_context(tauri::test::noop_assets()))
+            .expect("mock app")
+    }
+
+    #[test]
+    fn test_commands_on_mock_backend() {
+        let account = json!({
+            "account": {"email": "a@x.com"},
+            "totals": {"messages": 10, "sessions": 1, "tokens": 1000, "cost": 1.5},
+            "daily": [],
+        });
+        let backend = Arc::new(
+            MockBackend::new()
+                .with_fixture("projects", json!({"projects": [], "sort": "name"}))
+                .with_fixture("account-dashboard", account)
+                .with_error("workspaces", CommandError::busy("Database is locked")),
+        );
+        let app = mock_app(backend.clone());
+
+        let projects = block_on(get_projects(app.state(), Some("name".to_string()), None, None)).unwrap();
+        assert_eq!(projects["sort"], "name");
+
+        let error = block_on(get_workspaces(app.state(), Some("rq-ws".to_string()))).unwrap_err();
+        assert!(matches!(error, CommandError::Busy { request_id: Some(ref id), .. } if id == "rq-ws"));
+
+        let combined = block_on(get_combined_dashboard(
+            app.state(),
+            "2025-03-01".to_string(),
+            "2025-03-31".to_string(),
+            vec!["a@x.com".to_string(), "b@x.com".to_string(), "a@x.com".to_string()],
+            None,
+        ))
+        .unwrap();
+        assert_eq!(combined["totals"]["messages"], 20);
+
+        let calls = backend.calls();
+        assert_eq!(calls[0].args, vec!["projects", "--sort=name"]);
+        let mut emails: Vec<&str> = calls
+            .iter()
+            .filter(|call| call.subcommand() == "account-dashboard")
+            .map(|call| call.args[5].as_str())
+            .collect();
+        emails.sort();
+        assert_eq!(emails, vec!["--email=a@x.com", "--email=b@x.com"]);
+    }
+
+    /// Backend whose calls wait until released, like calls queued for a concurrency slot.
+    #[derive(Default)]
+    struct QueuedBackend {
+        waiting: Mutex<(usize, bool)>,
+        changed: Condvar,
+    }
+
+    impl ApiBackend for QueuedBackend {
+        fn call(&self, _request: ApiRequest) -> Result<Value, CommandError> {
+            let mut state = self.waiting.lock().unwrap();
+            state.0 += 1;
+            self.changed.notify_all();
+            while !state.1 {
+                state = self.changed.wait(state).unwrap();
+            }
+            Ok(json!({}))
+        }
+    }
+
+    #[test]
+    fn test_waiting_backend_calls_leave_the_runtime_free() {
+        const CALLS: usize = 32;
+        let backend = Arc::new(QueuedBackend::default());
+        let app = mock_app(backend.clone());
+        let commands: Vec<_> = (0..CALLS)
+            .map(|_| {
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move { get_hooks(handle.state(), None).await })
+            })
+            .collect();
+
+        // More calls wait than the runtime has threads, and other tasks still run
+        let (waiting, _) = backend
+            .changed
+            .wait_timeout_while(backend.waiting.lock().unwrap(), std::time::Duration::from_secs(10), |state| {
+                state.0 < CALLS
+            })
+            .unwrap();
+        assert_eq!(waiting.0, CALLS);
+        drop(waiting);
+        let (sender, receiver) = mpsc::channel();
+        tauri::async_runtime::spawn(async move { sender.send(()).unwrap() });
+        assert!(receiver.recv_timeout(std::time::Duration::from_secs(10)).is_ok());
+
+        backend.waiting.lock().unwrap().1 = true;
+        backend.changed.notify_all();
+        for command in commands {
+            assert!(block_on(command).unwrap().is_ok());
+        }
+    }
 }
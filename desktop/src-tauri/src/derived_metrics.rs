@@ -0,0 +1,417 @@
+/// User-defined derived metrics of the native dashboard
+///
+/// Evaluates the formulas of `command-center-derived-metrics.json` (written by
+/// the Python `derived_metrics`) over the metrics of each period, for the
+/// `derived` values of the timeline and `daily_derived`. Formulas are checked
+/// like the Python `parse_expression`: registered metric names, numbers,
+/// `+ - * /`, parentheses and calls of `min`, `max`, `abs` and `round`.
+/// Definitions whose formula is invalid are skipped; a division by zero, a
+/// call with the wrong number of arguments or a result that is not finite
+/// yields null. Values are rounded to 6 decimals.
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::aggregate::round;
+use crate::ingest;
+
+pub const DERIVED_METRICS_FILE: &str = "command-center-derived-metrics.json";
+
+/// Formula variables: the metrics of the Python `metrics_registry`, in registration order.
+pub const METRIC_NAMES: &[&str] = &[
+    "messages",
+    "sessions",
+    "tokens",
+    "input",
+    "output",
+    "cache_read",
+    "cache_write",
+    "cost",
+    "tool_calls",
+    "tool_errors",
+    "latency_ms",
+];
+
+const MAX_EXPRESSION_LENGTH: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Min,
+    Max,
+    Abs,
+    Round,
+}
+
+impl Function {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            "abs" => Some(Self::Abs),
+            "round" => Some(Self::Round),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Symbol(char),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Variable(String),
+    Neg(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+/// Digits with single underscores between them, from `chars[*at]`.
+fn digit_part(chars: &[char], at: &mut usize, radix: u32) -> Result<String, String> {
+    let mut digits = String::new();
+    while *at < chars.len() {
+        match chars[*at] {
+            c if c.is_digit(radix) => digits.push(c),
+            '_' if !digits.is_empty() && chars.get(*at + 1).is_some_and(|c| c.is_digit(radix)) => {}
+            _ => break,
+        }
+        *at += 1;
+    }
+    if digits.is_empty() {
+        return Err("Invalid formula: invalid number".to_string());
+    }
+    Ok(digits)
+}
+
+/// A number literal like Python's: decimal, hexadecimal, octal or binary
+/// integers (decimal ones without leading zeros) and decimal floats.
+fn number(chars: &[char], at: &mut usize) -> Result<f64, String> {
+    let radix = match (chars[*at], chars.get(*at + 1).map(char::to_ascii_lowercase)) {
+        ('0', Some('x')) => 16,
+        ('0', Some('o')) => 8,
+        ('0', Some('b')) => 2,
+        _ => 10,
+    };
+    if radix != 10 {
+        *at += 2;
+        if chars.get(*at) == Some(&'_') {
+            *at += 1;
+        }
+        let digits = digit_part(chars, at, radix)?;
+        let digits = digits.chars().filter_map(|c| c.to_digit(radix));
+        return Ok(digits.fold(0.0, |n, digit| n * f64::from(radix) + f64::from(digit)));
+    }
+
+    let mut literal = if chars[*at] == '.' { String::new() } else { digit_part(chars, at, 10)? };
+    let integer = literal.clone();
+    if chars.get(*at) == Some(&'.') {
+        *at += 1;
+        literal.push('.');
+        if chars.get(*at).is_some_and(char::is_ascii_digit) {
+            literal.push_str(&digit_part(chars, at, 10)?);
+        } else if integer.is_empty() {
+            return Err("Invalid formula: invalid syntax".to_string());
+        }
+    }
+    if matches!(chars.get(*at), Some('e' | 'E')) {
+        *at += 1;
+        literal.push('e');
+        if let Some(sign @ ('+' | '-')) = chars.get(*at) {
+            literal.push(*sign);
+            *at += 1;
+        }
+        literal.push_str(&digit_part(chars, at, 10)?);
+    }
+    if literal == integer && integer.len() > 1 && integer.starts_with('0') && integer.chars().any(|c| c != '0') {
+        return Err("Invalid formula: leading zeros in decimal integer literals are not permitted".to_string());
+    }
+    literal.parse().map_err(|_| "Invalid formula: invalid number".to_string())
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = expression.chars().collect();
+    if matches!(chars.first(), Some(' ' | '\t' | '\x0c')) {
+        return Err("Invalid formula: unexpected indent".to_string());
+    }
+    let (mut tokens, mut at, mut depth) = (Vec::new(), 0, 0usize);
+    while at < chars.len() {
+        let c = chars[at];
+        match c {
+            ' ' | '\t' | '\x0c' => at += 1,
+            // Line breaks only inside parentheses or at the end
+            '\n' | '\r' if depth > 0 || chars[at..].iter().all(|c| c.is_whitespace()) => at += 1,
+            c if c.is_ascii_digit() || (c == '.' && chars.get(at + 1).is_some_and(char::is_ascii_digit)) => {
+                tokens.push(Token::Number(number(&chars, &mut at)?));
+                if chars.get(at).is_some_and(|c| c.is_alphanumeric() || *c == '_') {
+                    return Err("Only numbers are allowed in formulas".to_string());
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = at;
+                while chars.get(at).is_some_and(|c| c.is_alphanumeric() || *c == '_') {
+                    at += 1;
+                }
+                tokens.push(Token::Name(chars[start..at].iter().collect()));
+            }
+            '*' | '/' if chars.get(at + 1) == Some(&c) => {
+                return Err(format!("Unsupported operator in formula: {}{}", c, c));
+            }
+            '+' | '-' | '*' | '/' | '(' | ')' | ',' => {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                tokens.push(Token::Symbol(c));
+                at += 1;
+            }
+            _ => return Err(format!("Unsupported syntax in formula: {:?}", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent over the tokens of a formula.
+struct Parser {
+    tokens: Vec<Token>,
+    at: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.at)
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        let found = self.peek() == Some(&Token::Symbol(symbol));
+        if found {
+            self.at += 1;
+        }
+        found
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut left = self.product()?;
+        while let Some(&Token::Symbol(op @ ('+' | '-'))) = self.peek() {
+            self.at += 1;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.product()?));
+        }
+        Ok(left)
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        while let Some(&Token::Symbol(op @ ('*' | '/'))) = self.peek() {
+            self.at += 1;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat('-') {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        if self.eat('+') {
+            return self.unary();
+        }
+        self.call()
+    }
+
+    fn call(&mut self) -> Result<Expr, String> {
+        let mut expr = self.atom()?;
+        while self.eat('(') {
+            let function = match &expr {
+                Expr::Variable(name) => Function::parse(name),
+                _ => None,
+            };
+            let function = function.ok_or("Unknown function in formula (allowed: min, max, abs, round)")?;
+            let mut args = Vec::new();
+            while !self.eat(')') {
+                args.push(self.sum()?);
+                if !self.eat(',') && self.peek() != Some(&Token::Symbol(')')) {
+                    return Err("Invalid formula: invalid syntax".to_string());
+                }
+            }
+            expr = Expr::Call(function, args);
+        }
+        Ok(expr)
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        let token = self.peek().cloned();
+        self.at += 1;
+        match token {
+            Some(Token::Number(value)) => Ok(Expr::Number(value)),
+            Some(Token::Name(name)) if METRIC_NAMES.contains(&name.as_str()) || Function::parse(&name).is_some() => {
+                Ok(Expr::Variable(name))
+            }
+            Some(Token::Name(name)) => {
+                Err(format!("Unknown variable {:?} (expected one of {})", name, METRIC_NAMES.join(", ")))
+            }
+            Some(Token::Symbol('(')) => {
+                let expr = self.sum()?;
+                if self.eat(')') {
+                    Ok(expr)
+                } else {
+                    Err("Invalid formula: '(' was never closed".to_string())
+                }
+            }
+            _ => Err("Invalid formula: invalid syntax".to_string()),
+        }
+    }
+}
+
+/// Parse and check a formula.
+fn parse(expression: &str) -> Result<Expr, String> {
+    if expression.trim().is_empty() {
+        return Err("Formula must not be empty".to_string());
+    }
+    if expression.chars().count() > MAX_EXPRESSION_LENGTH {
+        return Err(format!("Formula is longer than {} characters", MAX_EXPRESSION_LENGTH));
+    }
+    let mut parser = Parser { tokens: tokenize(expression)?, at: 0 };
+    let expr = parser.sum()?;
+    if parser.at != parser.tokens.len() {
+        return Err("Invalid formula: invalid syntax".to_string());
+    }
+    Ok(expr)
+}
+
+/// Value of a formula; None if a step divides by zero or a call is invalid.
+fn evaluate(expr: &Expr, values: &Map<String, Value>) -> Option<f64> {
+    fn visit(expr: &Expr, values: &Map<String, Value>) -> Option<f64> {
+        match expr {
+            Expr::Number(value) => Some(*value),
+            Expr::Variable(name) => Some(values.get(name).and_then(Value::as_f64).unwrap_or(0.0)),
+            Expr::Neg(operand) => visit(operand, values).map(|value| -value),
+            Expr::Binary(op, left, right) => {
+                let (left, right) = (visit(left, values)?, visit(right, values)?);
+                match op {
+                    '+' => Some(left + right),
+                    '-' => Some(left - right),
+                    '*' => Some(left * right),
+                    _ => (right != 0.0).then(|| left / right),
+                }
+            }
+            Expr::Call(function, args) => {
+                let args = args.iter().map(|arg| visit(arg, values)).collect::<Option<Vec<f64>>>()?;
+                match (function, args.as_slice()) {
+                    (Function::Min, [first, rest @ ..]) if !rest.is_empty() => {
+                        Some(rest.iter().fold(*first, |min, value| if *value < min { *value } else { min }))
+                    }
+                    (Function::Max, [first, rest @ ..]) if !rest.is_empty() => {
+                        Some(rest.iter().fold(*first, |max, value| if *value > max { *value } else { max }))
+                    }
+                    (Function::Abs, [value]) => Some(value.abs()),
+                    (Function::Round, [value]) => value.is_finite().then(|| value.round_ties_even()),
+                    (Function::Round, [value, digits]) if digits.is_finite() => {
+                        Some(round(*value, digits.trunc().clamp(i32::MIN.into(), i32::MAX.into()) as i32))
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+    visit(expr, values).filter(|value| value.is_finite()).map(|value| round(value, 6))
+}
+
+/// Definitions of `DERIVED_METRICS_FILE` in `data_dir` as stored
+/// (`[{name, expression, description}]`; empty if it is unreadable).
+pub fn load(data_dir: &Path) -> Vec<Value> {
+    match ingest::read_json(&data_dir.join(DERIVED_METRICS_FILE)).get_mut("metrics").map(Value::take) {
+        Some(Value::Array(metrics)) => metrics,
+        _ => Vec::new(),
+    }
+}
+
+/// Values of `metrics` for every period of `inputs` (metric values per
+/// period): `{period: {name: value or null}}`.
+pub fn compute(metrics: &[Value], inputs: &BTreeMap<String, Map<String, Value>>) -> Map<String, Value> {
+    let parsed: Vec<(&str, Expr)> = metrics
+        .iter()
+        .filter_map(|metric| {
+            let name = metric.get("name")?.as_str()?;
+            let expression = metric.get("expression")?.as_str()?;
+            parse(expression).ok().map(|expr| (name, expr))
+        })
+        .collect();
+    inputs
+        .iter()
+        .map(|(period, values)| {
+            let derived: Map<String, Value> =
+                parsed.iter().map(|(name, expr)| (name.to_string(), Value::from(evaluate(expr, values)))).collect();
+            (period.clone(), Value::Object(derived))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn value(expression: &str) -> Result<Option<f64>, String> {
+        let values = json!({"cost": 2.0, "messages": 4, "latency_ms": null});
+        parse(expression).map(|expr| evaluate(&expr, values.as_object().unwrap()))
+    }
+
+    #[test]
+    fn test_formulas_evaluate_like_python() {
+        // Results of the Python `parse_expression` and `evaluate` on the same values
+        let cases: &[(&str, Option<f64>)] = &[
+            ("cost / messages", Some(0.5)),
+            ("cost ", Some(2.0)),
+            ("01.5", Some(1.5)),
+            ("1_000 + 0x10 + 1e3 + .5 + 5.", Some(2021.5)),
+            ("(cost\n+1)", Some(3.0)),
+            ("min(1)", None),
+            ("min()", None),
+            ("round(2.5)", Some(2.0)),
+            ("round(1234.5,-2)", Some(1200.0)),
+            ("round(1.25, 1.9)", Some(1.2)),
+            ("min + 1", Some(1.0)),
+            ("(min)(1,2)", Some(1.0)),
+            ("max(1, 2, 3,)", Some(3.0)),
+            ("abs(-3, 1)", None),
+            ("round(1, 2, 3)", None),
+            ("1/(cost-cost)", None),
+            ("-(-cost) * +latency_ms + 2*-3", Some(-6.0)),
+            ("1e400", None),
+            ("round(1.5, 400)", Some(1.5)),
+            ("round(1.5, -400)", Some(0.0)),
+            ("cost / 3", Some(0.666667)),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(value(expression), Ok(*expected), "{:?}", expression);
+        }
+
+        let invalid = [" cost", "01", "cost\n+1", "1 +", "cost // 2", "cost ** 2", "1j", "True", "sum(1)", "cost(1)"];
+        for invalid in invalid {
+            assert!(value(invalid).is_err(), "{:?}", invalid);
+        }
+        assert!(value("").is_err() && value(&"1".repeat(201)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_definitions_are_skipped() {
+        let metrics = json!([
+            {"name": "per_message", "expression": "cost / messages"},
+            {"name": "broken", "expression": "cost ** 2"},
+            {"name": "no_formula"},
+            "not a metric",
+        ]);
+        let inputs = BTreeMap::from([
+            ("2025-06-09".to_string(), json!({"cost": 1.5, "messages": 3}).as_object().unwrap().clone()),
+            ("2025-06-10".to_string(), json!({"cost": 0.0, "messages": 0}).as_object().unwrap().clone()),
+        ]);
+        let derived = compute(metrics.as_array().unwrap(), &inputs);
+        let expected = json!({"2025-06-09": {"per_message": 0.5}, "2025-06-10": {"per_message": null}});
+        assert_eq!(Value::Object(derived), expected);
+    }
+}
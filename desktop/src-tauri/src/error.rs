@@ -0,0 +1,329 @@
+/// Structured errors returned by Tauri commands
+///
+/// Every command fails with a `CommandError`, serialized as an object with a
+/// `kind` tag, a human-readable `message` and, when known, the `request_id`:
+/// `{"kind": "timeout", "seconds": 60, "message": "...", "request_id": "ui-..."}`.
+/// The frontend branches on `kind` and shows `message`.
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CommandError {
+    /// No bundled backend or Python interpreter could be started
+    PythonMissing {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// The backend exited with an error; `exception` is the Python exception type
+    NonZeroExit {
+        code: Option<i32>,
+        stderr: String,
+        exception: Option<String>,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// The backend's output was not valid JSON
+    ParseError {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// The backend's JSON did not have the shape the command expects
+    /// (`response_schema`); `path` is the offending field, e.g. `totals.tokens`
+    UnexpectedShape {
+        subcommand: String,
+        path: String,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// The call did not finish within its timeout and was killed
+    Timeout {
+        seconds: u64,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// The call was stopped with `cancel_request`
+    Cancelled {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// The database or a data file stayed locked through all retries
+    Busy {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// A state-changing command was refused because read-only mode is on
+    ReadOnly {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// An argument was rejected before anything ran (bad date, unknown flag, ...)
+    InvalidArgument {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// Nothing to work on (e.g. no files for a session, nothing selected)
+    NotFound {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// A request to the local HTTP server had no valid API token (missing,
+    /// unknown, expired or revoked)
+    Unauthorized {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// The API token is valid but lacks `scope`
+    Forbidden {
+        scope: String,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// The Python API reports a version this app was not built for;
+    /// `found` is null when the backend is too old to report one
+    Incompatible {
+        expected: u64,
+        found: Option<u64>,
+        package_version: Option<String>,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// Desktop-side failure: file system, background task, serialization
+    Internal {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+}
+
+impl CommandError {
+    pub fn python_missing(message: impl Into<String>) -> Self {
+        CommandError::PythonMissing { message: message.into(), request_id: None }
+    }
+
+    pub fn parse_error(message: impl Into<String>) -> Self {
+        CommandError::ParseError { message: message.into(), request_id: None }
+    }
+
+    pub fn cancelled(message: impl Into<String>) -> Self {
+        CommandError::Cancelled { message: message.into(), request_id: None }
+    }
+
+    pub fn busy(message: impl Into<String>) -> Self {
+        CommandError::Busy { message: message.into(), request_id: None }
+    }
+
+    pub fn read_only(message: impl Into<String>) -> Self {
+        CommandError::ReadOnly { message: message.into(), request_id: None }
+    }
+
+    pub fn invalid_argument(message: impl Into<String>) -> Self {
+        CommandError::InvalidArgument { message: message.into(), request_id: None }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        CommandError::NotFound { message: message.into(), request_id: None }
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        CommandError::Unauthorized { message: message.into(), request_id: None }
+    }
+
+    pub fn forbidden(scope: impl Into<String>, message: impl Into<String>) -> Self {
+        CommandError::Forbidden { scope: scope.into(), message: message.into(), request_id: None }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        CommandError::Internal { message: message.into(), request_id: None }
+    }
+
+    pub fn timeout(seconds: u64, message: impl Into<String>) -> Self {
+        CommandError::Timeout { seconds, message: message.into(), request_id: None }
+    }
+
+    /// Error of a backend process that exited unsuccessfully.
+    ///
+    /// The message and exception type come from the JSON error line the
+    /// backend writes to stderr (`{"error": ..., "type": ...}`); without one,
+    /// the last stderr line is the message. `ReadOnlyModeError` becomes `ReadOnly`.
+    pub fn from_exit(code: Option<i32>, stderr: &str) -> Self {
+        let reported = stderr
+            .lines()
+            .filter_map(|line| line.find('{').map(|start| &line[start..]))
+            .filter_map(|json| serde_json::from_str::<Value>(json).ok())
+            .find(|value| value.get("error").is_some());
+        let exception = reported
+            .as_ref()
+            .and_then(|value| value.get("type").and_then(Value::as_str))
+            .filter(|name| name.len() <= 64 && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+            .map(str::to_string);
+        let message = reported
+            .as_ref()
+            .and_then(|value| value.get("error").and_then(Value::as_str))
+            .map(str::to_string)
+            .or_else(|| stderr.lines().rev().find(|line| !line.trim().is_empty()).map(|line| line.trim().to_string()))
+            .unwrap_or_else(|| format!("Backend exited with code {}", code.map_or("?".to_string(), |c| c.to_string())));
+
+        if exception.as_deref() == Some("ReadOnlyModeError") {
+            return CommandError::read_only(message);
+        }
+        CommandError::NonZeroExit {
+            code,
+            stderr: stderr.to_string(),
+            exception,
+            message,
+            request_id: None,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        self.fields().0
+    }
+
+    pub fn request_id(&self) -> Option<&str> {
+        self.fields().1.as_deref()
+    }
+
+    /// Python exception type of a `NonZeroExit`.
+    pub fn exception(&self) -> Option<&str> {
+        match self {
+            CommandError::NonZeroExit { exception, .. } => exception.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Attach the request ID; an ID that is already set is kept.
+    pub fn with_request_id(mut self, id: &str) -> Self {
+        let slot = self.fields_mut().1;
+        if slot.is_none() {
+            *slot = Some(id.to_string());
+        }
+        self
+    }
+
+    /// Append context to the message, e.g. the number of attempts.
+    pub fn with_suffix(mut self, suffix: &str) -> Self {
+        self.fields_mut().0.push_str(suffix);
+        self
+    }
+
+    fn fields(&self) -> (&String, &Option<String>) {
+        match self {
+            CommandError::PythonMissing { message, request_id }
+            | CommandError::NonZeroExit { message, request_id, .. }
+            | CommandError::ParseError { message, request_id }
+            | CommandError::UnexpectedShape { message, request_id, .. }
+            | CommandError::Timeout { message, request_id, .. }
+            | CommandError::Cancelled { message, request_id }
+            | CommandError::Busy { message, request_id }
+            | CommandError::ReadOnly { message, request_id }
+            | CommandError::InvalidArgument { message, request_id }
+            | CommandError::NotFound { message, request_id }
+            | CommandError::Unauthorized { message, request_id }
+            | CommandError::Forbidden { message, request_id, .. }
+            | CommandError::Incompatible { message, request_id, .. }
+            | CommandError::Internal { message, request_id } => (message, request_id),
+        }
+    }
+
+    fn fields_mut(&mut self) -> (&mut String, &mut Option<String>) {
+        match self {
+            CommandError::PythonMissing { message, request_id }
+            | CommandError::NonZeroExit { message, request_id, .. }
+            | CommandError::ParseError { message, request_id }
+            | CommandError::UnexpectedShape { message, request_id, .. }
+            | CommandError::Timeout { message, request_id, .. }
+            | CommandError::Cancelled { message, request_id }
+            | CommandError::Busy { message, request_id }
+            | CommandError::ReadOnly { message, request_id }
+            | CommandError::InvalidArgument { message, request_id }
+            | CommandError::NotFound { message, request_id }
+            | CommandError::Unauthorized { message, request_id }
+            | CommandError::Forbidden { message, request_id, .. }
+            | CommandError::Incompatible { message, request_id, .. }
+            | CommandError::Internal { message, request_id } => (message, request_id),
+        }
+    }
+}
+
+/// `[<request_id>] <message>`, the format of bridge log lines.
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.request_id() {
+            Some(id) => write!(f, "[{}] {}", id, self.message()),
+            None => f.write_str(self.message()),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Errors of desktop-side helpers (settings, exports, plugins) are plain
+/// strings; with `?` they become `Internal`. Helpers that validate input are
+/// mapped to `InvalidArgument` explicitly.
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::internal(message)
+    }
+}
+
+impl From<serde_json::Error> for CommandError {
+    fn from(e: serde_json::Error) -> Self {
+        CommandError::internal(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_serialized_with_kind() {
+        let error = CommandError::timeout(60, "Timeout: dashboard did not finish within 60 s").with_request_id("ui-1");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "kind": "timeout",
+                "seconds": 60,
+                "message": "Timeout: dashboard did not finish within 60 s",
+                "request_id": "ui-1",
+            })
+        );
+        assert_eq!(error.to_string(), "[ui-1] Timeout: dashboard did not finish within 60 s");
+        assert_eq!(error.with_request_id("ui-2").request_id(), Some("ui-1"));
+
+        let value = serde_json::to_value(CommandError::invalid_argument("Invalid date: x")).unwrap();
+        assert_eq!(value, json!({"kind": "invalid_argument", "message": "Invalid date: x"}));
+    }
+
+    #[test]
+    fn test_from_exit() {
+        let stderr = "Traceback noise\n{\"error\": \"No such file\", \"type\": \"FileNotFoundError\"}\n";
+        let error = CommandError::from_exit(Some(1), stderr);
+        assert!(matches!(error, CommandError::NonZeroExit { code: Some(1), .. }));
+        assert_eq!(error.message(), "No such file");
+        assert_eq!(error.exception(), Some("FileNotFoundError"));
+
+        let plain = CommandError::from_exit(Some(2), "usage: tauri_api\nerror: bad flag\n");
+        assert_eq!(plain.message(), "error: bad flag");
+        assert_eq!(plain.exception(), None);
+
+        let read_only = r#"{"error": "Read-only mode is on", "type": "ReadOnlyModeError"}"#;
+        assert!(matches!(CommandError::from_exit(Some(1), read_only), CommandError::ReadOnly { .. }));
+    }
+}
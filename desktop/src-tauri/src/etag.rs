@@ -0,0 +1,134 @@
+/// Content hashes for conditional (ETag-style) command responses
+///
+/// Read commands accept `if_none_match`; when the response content hash equals
+/// it, a tiny `{"not_modified": true, "etag": ...}` object is returned instead
+/// of the full payload.
+use serde_json::{json, Value};
+
+use crate::error::CommandError;
+
+/// Keys that change on every call without the content changing; not hashed.
+const VOLATILE_KEYS: &[&str] = &["generated_at", "refreshed_at", "request_id", "etag"];
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_value(&mut self, value: &Value) {
+        match value {
+            Value::Null => self.write(b"n"),
+            Value::Bool(b) => self.write(if *b { b"t" } else { b"f" }),
+            Value::Number(n) => {
+                self.write(b"#");
+                self.write(n.to_string().as_bytes());
+            }
+            Value::String(s) => {
+                self.write(b"\"");
+                self.write(&(s.len() as u64).to_le_bytes());
+                self.write(s.as_bytes());
+            }
+            Value::Array(items) => {
+                self.write(b"[");
+                self.write(&(items.len() as u64).to_le_bytes());
+                for item in items {
+                    self.write_value(item);
+                }
+            }
+            Value::Object(map) => {
+                self.write(b"{");
+                // serde_json keeps insertion order (`preserve_order` is on in the
+                // dependency graph); sort so equal objects hash the same
+                let mut entries: Vec<(&String, &Value)> =
+                    map.iter().filter(|(k, _)| !VOLATILE_KEYS.contains(&k.as_str())).collect();
+                entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+                for (key, item) in entries {
+                    self.write(&(key.len() as u64).to_le_bytes());
+                    self.write(key.as_bytes());
+                    self.write_value(item);
+                }
+                self.write(b"}");
+            }
+        }
+    }
+}
+
+/// Compute the content hash of a response, ignoring volatile keys.
+pub fn content_etag(value: &Value) -> String {
+    let mut hasher = Fnv1a(FNV_OFFSET);
+    hasher.write_value(value);
+    format!("\"{:016x}\"", hasher.0)
+}
+
+/// Apply `if_none_match` to a command result.
+///
+/// Object responses get an `etag` field; if it equals `if_none_match`, only
+/// `{"not_modified": true, "etag": ...}` is returned. Errors and non-object
+/// responses pass through unchanged.
+pub fn conditional(result: Result<Value, CommandError>, if_none_match: Option<String>) -> Result<Value, CommandError> {
+    let mut value = result?;
+    if !value.is_object() {
+        return Ok(value);
+    }
+
+    let etag = content_etag(&value);
+    if if_none_match.as_deref() == Some(etag.as_str()) {
+        return Ok(json!({ "not_modified": true, "etag": etag }));
+    }
+
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("etag".to_string(), Value::from(etag));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_etag_ignores_volatile_keys() {
+        let a = json!({"totals": {"cost": 1.5}, "meta": {"generated_at": "2025-06-15T10:00:00"}});
+        let b = json!({"totals": {"cost": 1.5}, "meta": {"generated_at": "2025-06-15T10:00:30"}});
+        let c = json!({"totals": {"cost": 2.0}, "meta": {"generated_at": "2025-06-15T10:00:30"}});
+        assert_eq!(content_etag(&a), content_etag(&b));
+        assert_ne!(content_etag(&a), content_etag(&c));
+    }
+
+    #[test]
+    fn test_etag_ignores_key_order() {
+        let mut forward = serde_json::Map::new();
+        let mut backward = serde_json::Map::new();
+        for (key, value) in [("cost", json!(1.5)), ("messages", json!(3)), ("model", json!("opus"))] {
+            forward.insert(key.to_string(), value);
+        }
+        for (key, value) in [("model", json!("opus")), ("messages", json!(3)), ("cost", json!(1.5))] {
+            backward.insert(key.to_string(), value);
+        }
+        let a = json!({"totals": Value::Object(forward), "days": 2});
+        let b = json!({"days": 2, "totals": Value::Object(backward)});
+        assert_eq!(content_etag(&a), content_etag(&b));
+        assert_ne!(content_etag(&a), content_etag(&json!({"days": 3, "totals": {}})));
+    }
+
+    #[test]
+    fn test_conditional_returns_not_modified() {
+        let payload = json!({"projects": [1, 2, 3]});
+        let etag = content_etag(&payload);
+
+        let full = conditional(Ok(payload.clone()), None).unwrap();
+        assert_eq!(full["etag"], Value::from(etag.clone()));
+
+        let short = conditional(Ok(payload), Some(etag)).unwrap();
+        assert_eq!(short["not_modified"], Value::Bool(true));
+        assert!(short.get("projects").is_none());
+    }
+}
@@ -0,0 +1,675 @@
+/// Export job queue with persisted history
+///
+/// Exports submitted with `enqueue_export` run one at a time on a background
+/// worker. Every job is recorded in `~/.claude/db/command-center-exports.json`
+/// with its parameters, status, output path and error, so failures stay
+/// visible and a job can be re-run. Completion and failure are announced with
+/// the `export-completed` / `export-failed` events (payload: the job).
+///
+/// Jobs still queued or running when the app exits are marked failed on the
+/// next start.
+///
+/// All export files are written here rather than by the frontend: a command
+/// either gets a target path or opens the save dialog in the default export
+/// directory (the `export_dir` setting, else Downloads), and reports the
+/// written path with its size and SHA-256.
+///
+/// PNG reports never travel as base64 JSON: the backend writes them into a
+/// scratch directory (`export-png --output`), from where they are moved to
+/// their destination, returned by path or sent to the UI as raw bytes.
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::atomic::{move_atomic, write_atomic};
+use crate::error::CommandError;
+use crate::privacy;
+use crate::backend::{ApiRequest, SharedBackend};
+use crate::python_bridge::new_request_id;
+use crate::schema::{self, Migration, Store};
+use crate::state::AppState;
+use crate::settings::{db_dir, Settings};
+
+pub const EXPORTS_FILE: &str = "command-center-exports.json";
+
+/// Event emitted when an export job succeeded.
+pub const EXPORT_COMPLETED_EVENT: &str = "export-completed";
+
+/// Event emitted when an export job failed.
+pub const EXPORT_FAILED_EVENT: &str = "export-failed";
+
+/// Finished jobs kept in the history (oldest are dropped).
+pub const MAX_HISTORY: usize = 200;
+
+fn exports_v1(_obj: &mut Map<String, Value>) {}
+
+pub const EXPORTS_MIGRATIONS: &[Migration] = &[Migration {
+    to: 1,
+    description: "Add schema_version",
+    apply: exports_v1,
+}];
+
+pub const EXPORTS_STORE: Store = Store {
+    name: "exports",
+    file: EXPORTS_FILE,
+    migrations: EXPORTS_MIGRATIONS,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportKind {
+    /// Python-rendered PNG report: params {from, to, scale: 1-3 (optional),
+    /// social: "twitter" | "linkedin" (optional, a fixed-size card)}
+    PngReport,
+    /// Finance allocation: params {month, format: "csv" | "xlsx"}
+    CostAllocation,
+    /// Zip of raw session files: params {session_ids, redact}
+    RawSessions,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportJob {
+    pub id: String,
+    pub kind: ExportKind,
+    pub params: Value,
+    pub output_path: String,
+    pub status: ExportStatus,
+    pub request_id: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub finished_at: Option<String>,
+    /// Size of the written file
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Job this one re-runs
+    #[serde(default)]
+    pub rerun_of: Option<String>,
+    /// Summary returned by the backend (session counts, totals, ...)
+    #[serde(default)]
+    pub result: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportHistory {
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Newest first
+    #[serde(default)]
+    pub jobs: Vec<ExportJob>,
+}
+
+impl ExportHistory {
+    fn load() -> Self {
+        db_dir()
+            .ok()
+            .and_then(|dir| fs::read(dir.join(EXPORTS_FILE)).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let dir = db_dir()?;
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let mut value = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        value["schema_version"] = Value::from(EXPORTS_STORE.latest());
+        schema::write_json_atomic(&dir.join(EXPORTS_FILE), &value)
+    }
+
+    /// Drop the oldest finished jobs beyond MAX_HISTORY.
+    fn prune(&mut self) {
+        let mut finished = 0;
+        self.jobs.retain(|job| {
+            if matches!(job.status, ExportStatus::Queued | ExportStatus::Running) {
+                return true;
+            }
+            finished += 1;
+            finished <= MAX_HISTORY
+        });
+    }
+
+    /// Mark jobs left queued or running by a previous app run as failed.
+    fn fail_interrupted(&mut self, now: &str) -> usize {
+        let mut count = 0;
+        for job in &mut self.jobs {
+            if matches!(job.status, ExportStatus::Queued | ExportStatus::Running) {
+                job.status = ExportStatus::Failed;
+                job.finished_at = Some(now.to_string());
+                job.error = Some("Interrupted: the app exited before the export finished".to_string());
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+/// Serializes read-modify-write of the history file.
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+static JOB_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+static WORKER: OnceLock<Mutex<Sender<String>>> = OnceLock::new();
+
+fn now() -> String {
+    chrono::Local::now().to_rfc3339()
+}
+
+fn with_history<T>(f: impl FnOnce(&mut ExportHistory) -> T) -> Result<T, String> {
+    let _guard = HISTORY_LOCK.lock().map_err(|_| "Export history lock poisoned".to_string())?;
+    let mut history = ExportHistory::load();
+    let result = f(&mut history);
+    history.save()?;
+    Ok(result)
+}
+
+/// Jobs newest first, optionally filtered by status.
+pub fn list(limit: usize, status: Option<ExportStatus>) -> Vec<ExportJob> {
+    ExportHistory::load()
+        .jobs
+        .into_iter()
+        .filter(|job| status.map_or(true, |status| job.status == status))
+        .take(limit)
+        .collect()
+}
+
+pub fn find(id: &str) -> Option<ExportJob> {
+    ExportHistory::load().jobs.into_iter().find(|job| job.id == id)
+}
+
+/// Fail jobs interrupted by the last exit (called once on startup).
+pub fn fail_interrupted() -> Result<usize, String> {
+    let pending = ExportHistory::load()
+        .jobs
+        .iter()
+        .any(|job| matches!(job.status, ExportStatus::Queued | ExportStatus::Running));
+    if !pending {
+        return Ok(0);
+    }
+    let now = now();
+    with_history(|history| history.fail_interrupted(&now))
+}
+
+/// Social card presets of `export_for_social` (see SOCIAL_FORMATS in png_generator.py).
+pub const SOCIAL_FORMATS: &[&str] = &["twitter", "linkedin"];
+
+/// Largest pixel density of a PNG report (@3x).
+pub const MAX_EXPORT_SCALE: u64 = 3;
+
+/// Rendered PNGs older than this are removed from the scratch directory.
+pub const SCRATCH_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+fn str_param<'a>(params: &'a Value, name: &str) -> Result<&'a str, String> {
+    params[name]
+        .as_str()
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("Missing export parameter: {}", name))
+}
+
+/// Backend arguments of a job.
+pub fn python_args(kind: ExportKind, params: &Value, output_path: &str) -> Result<Vec<String>, String> {
+    match kind {
+        ExportKind::PngReport => {
+            let mut args = vec![
+                "export-png".to_string(),
+                format!("--from={}", str_param(params, "from")?),
+                format!("--to={}", str_param(params, "to")?),
+            ];
+            if let Some(social) = params["social"].as_str() {
+                if !SOCIAL_FORMATS.contains(&social) {
+                    return Err(format!("Invalid social format: {}", social));
+                }
+                args.push(format!("--social={}", social));
+            } else if let Some(scale) = params.get("scale").filter(|scale| !scale.is_null()) {
+                match scale.as_u64() {
+                    Some(scale @ 1..=MAX_EXPORT_SCALE) => args.push(format!("--scale={}", scale)),
+                    _ => return Err(format!("Invalid export scale: {} (expected 1-3)", scale)),
+                }
+            }
+            args.push(format!("--output={}", output_path));
+            Ok(args)
+        }
+        ExportKind::CostAllocation => {
+            let format = params["format"].as_str().unwrap_or("csv");
+            if !matches!(format, "csv" | "xlsx") {
+                return Err(format!("Invalid allocation format: {}", format));
+            }
+            Ok(vec![
+                "export-cost-allocation".to_string(),
+                format!("--month={}", str_param(params, "month")?),
+                format!("--format={}", format),
+            ])
+        }
+        ExportKind::RawSessions => {
+            let ids: Vec<&str> = params["session_ids"]
+                .as_array()
+                .map(|ids| ids.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            if ids.is_empty() {
+                return Err("No sessions selected for export".to_string());
+            }
+            let mut args = vec!["export-raw-sessions".to_string()];
+            args.extend(ids.iter().map(|id| format!("--id={}", id)));
+            args.push(format!("--output={}", output_path));
+            if params["redact"].as_bool().unwrap_or(false) || privacy::enabled() {
+                args.push("--redact".to_string());
+            }
+            Ok(args)
+        }
+    }
+}
+
+/// File name suggested for an export (matches the backend's names).
+pub fn default_filename(kind: ExportKind, params: &Value) -> String {
+    let param = |name: &str| params[name].as_str().unwrap_or("").to_string();
+    match kind {
+        ExportKind::PngReport => match (params["social"].as_str(), params["scale"].as_u64()) {
+            (Some(social), _) => format!("cc-usage-{}-{}_{}.png", social, param("from"), param("to")),
+            (None, Some(scale)) if scale > 1 => {
+                format!("cc-usage-report-{}_{}@{}x.png", param("from"), param("to"), scale)
+            }
+            _ => format!("cc-usage-report-{}_{}.png", param("from"), param("to")),
+        },
+        ExportKind::CostAllocation => format!(
+            "cc-cost-allocation-{}.{}",
+            param("month"),
+            params["format"].as_str().unwrap_or("csv")
+        ),
+        ExportKind::RawSessions => {
+            let ids = params["session_ids"].as_array().map_or(0, Vec::len);
+            let suffix = if params["redact"].as_bool().unwrap_or(false) { "-redacted" } else { "" };
+            match params["session_ids"][0].as_str() {
+                Some(id) if ids == 1 => format!("cc-session-{}{}.zip", id.chars().take(8).collect::<String>(), suffix),
+                _ => format!("cc-sessions-{}{}.zip", ids, suffix),
+            }
+        }
+    }
+}
+
+/// Directory exports go to by default: the `export_dir` setting, else Downloads, else home.
+pub fn default_export_dir(app: &AppHandle) -> PathBuf {
+    Settings::load()
+        .export_dir
+        .map(PathBuf::from)
+        .or_else(|| app.path().download_dir().ok())
+        .or_else(|| app.path().home_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+fn dialog_filter(filename: &str) -> (&'static str, &'static [&'static str]) {
+    match Path::new(filename).extension().and_then(|e| e.to_str()) {
+        Some("png") => ("PNG Image", &["png"]),
+        Some("csv") => ("CSV File", &["csv"]),
+        Some("xlsx") => ("Excel Workbook", &["xlsx"]),
+        Some("zip") => ("Zip Archive", &["zip"]),
+        Some("html") => ("HTML Document", &["html"]),
+        _ => ("All Files", &["*"]),
+    }
+}
+
+/// Where to write an export: `target_path` (relative paths are taken from the
+/// default export directory), else the path picked in the save dialog.
+/// None if the dialog was cancelled.
+pub fn choose_target(app: &AppHandle, target_path: Option<String>, suggested: &str) -> Result<Option<PathBuf>, String> {
+    let dir = default_export_dir(app);
+    if let Some(target) = target_path.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+        return Ok(Some(dir.join(target)));
+    }
+    let (filter_name, extensions) = dialog_filter(suggested);
+    let Some(picked) = app
+        .dialog()
+        .file()
+        .set_directory(&dir)
+        .set_file_name(suggested)
+        .add_filter(filter_name, extensions)
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    picked
+        .into_path()
+        .map(Some)
+        .map_err(|e| format!("Unsupported save location: {}", e))
+}
+
+/// A written export file.
+#[derive(Debug, Clone, Serialize)]
+pub struct WrittenFile {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Size and SHA-256 of a file.
+pub fn file_info(path: &Path) -> Result<WrittenFile, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut file, &mut hasher).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(WrittenFile {
+        path: path.display().to_string(),
+        size,
+        sha256: format!("{:x}", hasher.finalize()),
+    })
+}
+
+/// Decode base64 export data (a `data:` URL prefix is accepted).
+pub fn decode_data(data: &str) -> Result<Vec<u8>, String> {
+    let encoded = data.split_once(";base64,").map_or(data, |(_, encoded)| encoded);
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("Invalid export data: {}", e))
+}
+
+/// Write export bytes atomically, creating the parent directory.
+pub fn write_export_file(path: &Path, bytes: &[u8]) -> Result<WrittenFile, String> {
+    write_atomic(path, bytes)?;
+    Ok(WrittenFile {
+        path: path.display().to_string(),
+        size: bytes.len() as u64,
+        sha256: format!("{:x}", Sha256::digest(bytes)),
+    })
+}
+
+/// Directory the backend renders PNG exports into.
+pub fn scratch_dir() -> PathBuf {
+    std::env::temp_dir().join("command-center-exports")
+}
+
+/// Remove rendered files older than `SCRATCH_MAX_AGE` (left behind by
+/// `render_png_report` or an interrupted export).
+fn prune_scratch(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let now = SystemTime::now();
+    for entry in entries.flatten() {
+        let expired = entry
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > SCRATCH_MAX_AGE);
+        if expired {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Render a PNG report into the scratch directory; returns the backend
+/// summary and the rendered file (named `<request id>-<file name>`).
+pub fn render_png(backend: &SharedBackend, request_id: &str, params: &Value) -> Result<(Value, WrittenFile), CommandError> {
+    let dir = scratch_dir();
+    prune_scratch(&dir);
+    fs::create_dir_all(&dir).map_err(|e| CommandError::internal(format!("Failed to create {}: {}", dir.display(), e)))?;
+    let id: String = request_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let path = dir.join(format!("{}-{}", id, default_filename(ExportKind::PngReport, params)));
+
+    let args = python_args(ExportKind::PngReport, params, &path.display().to_string())
+        .map_err(CommandError::invalid_argument)?;
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let result = backend.call(ApiRequest::new(request_id, &args_refs))?;
+    let written = file_info(&path).map_err(|_| CommandError::parse_error("Export wrote no PNG file"))?;
+    Ok((result, written))
+}
+
+/// Run a backend export and write its file; returns the backend summary
+/// (without the data) and the written file.
+pub fn run_export(
+    backend: &SharedBackend,
+    request_id: &str,
+    kind: ExportKind,
+    params: &Value,
+    output_path: &Path,
+) -> Result<(Value, WrittenFile), CommandError> {
+    if kind == ExportKind::PngReport {
+        let (mut result, rendered) = render_png(backend, request_id, params)?;
+        move_atomic(Path::new(&rendered.path), output_path)?;
+        let written = WrittenFile {
+            path: output_path.display().to_string(),
+            ..rendered
+        };
+        if let Some(obj) = result.as_object_mut() {
+            obj.insert("path".to_string(), Value::from(written.path.clone()));
+        }
+        return Ok((result, written));
+    }
+
+    let output = output_path.display().to_string();
+    let args = python_args(kind, params, &output).map_err(CommandError::invalid_argument)?;
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let mut result = backend.call(ApiRequest::new(request_id, &args_refs))?;
+
+    let written = if kind == ExportKind::RawSessions {
+        file_info(output_path)?
+    } else {
+        let data = result["data"]
+            .as_str()
+            .ok_or_else(|| CommandError::parse_error("Export returned no data"))?;
+        write_export_file(output_path, &decode_data(data)?)?
+    };
+    if let Some(obj) = result.as_object_mut() {
+        obj.remove("data");
+    }
+    Ok((result, written))
+}
+
+/// Record a new queued job (parameters are validated first).
+pub fn create(
+    kind: ExportKind,
+    params: Value,
+    output_path: String,
+    request_id: String,
+    rerun_of: Option<String>,
+) -> Result<ExportJob, String> {
+    python_args(kind, &params, &output_path)?;
+    if output_path.trim().is_empty() {
+        return Err("Missing export output path".to_string());
+    }
+    let id = format!(
+        "{}-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        JOB_COUNTER.fetch_add(1, Ordering::Relaxed) + 1
+    );
+    let job = ExportJob {
+        id,
+        kind,
+        params,
+        output_path,
+        status: ExportStatus::Queued,
+        request_id,
+        created_at: now(),
+        started_at: None,
+        finished_at: None,
+        size: None,
+        sha256: None,
+        error: None,
+        rerun_of,
+        result: None,
+    };
+    with_history(|history| {
+        history.jobs.insert(0, job.clone());
+        history.prune();
+    })?;
+    Ok(job)
+}
+
+/// Copy of a job with a new id and request ID.
+pub fn rerun(id: &str) -> Result<ExportJob, String> {
+    let job = find(id).ok_or_else(|| format!("Unknown export job: {}", id))?;
+    create(job.kind, job.params, job.output_path, new_request_id(), Some(job.id))
+}
+
+fn update(id: &str, f: impl FnOnce(&mut ExportJob)) -> Option<ExportJob> {
+    with_history(|history| {
+        let job = history.jobs.iter_mut().find(|job| job.id == id)?;
+        f(job);
+        Some(job.clone())
+    })
+    .ok()
+    .flatten()
+}
+
+fn run(app: &AppHandle, id: &str) {
+    let Some(job) = update(id, |job| {
+        job.status = ExportStatus::Running;
+        job.started_at = Some(now());
+    }) else {
+        return;
+    };
+
+    let state = app.state::<AppState>();
+    let outcome = run_export(&state.backend, &job.request_id, job.kind, &job.params, Path::new(&job.output_path));
+    let Some(job) = update(id, |job| {
+        job.finished_at = Some(now());
+        match outcome {
+            Ok((result, written)) => {
+                job.status = ExportStatus::Succeeded;
+                job.size = Some(written.size);
+                job.sha256 = Some(written.sha256);
+                job.result = Some(result);
+            }
+            Err(e) => {
+                job.status = ExportStatus::Failed;
+                job.error = Some(e.message().to_string());
+            }
+        }
+    }) else {
+        return;
+    };
+
+    let event = if job.status == ExportStatus::Succeeded {
+        EXPORT_COMPLETED_EVENT
+    } else {
+        log::warn!("[{}] Export {} failed: {}", job.request_id, job.id, job.error.as_deref().unwrap_or(""));
+        EXPORT_FAILED_EVENT
+    };
+    if let Err(e) = app.emit(event, &job) {
+        log::warn!("[{}] Failed to emit {}: {}", job.request_id, event, e);
+    }
+}
+
+/// Hand a queued job to the worker (started on first use).
+pub fn submit(app: &AppHandle, id: String) -> Result<(), String> {
+    let worker = WORKER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<String>();
+        let app = app.clone();
+        std::thread::spawn(move || {
+            for id in receiver {
+                run(&app, &id);
+            }
+        });
+        Mutex::new(sender)
+    });
+    worker
+        .lock()
+        .map_err(|_| "Export queue lock poisoned".to_string())?
+        .send(id)
+        .map_err(|e| format!("Export queue stopped: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn job(id: &str, status: ExportStatus) -> ExportJob {
+        ExportJob {
+            id: id.to_string(),
+            kind: ExportKind::PngReport,
+            params: json!({"from": "2026-01-01", "to": "2026-01-31"}),
+            output_path: "/tmp/report.png".to_string(),
+            status,
+            request_id: "req".to_string(),
+            created_at: "2026-01-31T10:00:00+00:00".to_string(),
+            started_at: None,
+            finished_at: None,
+            size: None,
+            sha256: None,
+            error: None,
+            rerun_of: None,
+            result: None,
+        }
+    }
+
+    #[test]
+    fn test_python_args_validate_params() {
+        let args = python_args(
+            ExportKind::RawSessions,
+            &json!({"session_ids": ["a", "b"], "redact": true}),
+            "/tmp/out.zip",
+        )
+        .unwrap();
+        assert_eq!(args, vec!["export-raw-sessions", "--id=a", "--id=b", "--output=/tmp/out.zip", "--redact"]);
+
+        assert!(python_args(ExportKind::PngReport, &json!({"from": "2026-01-01"}), "x").is_err());
+        let retina = json!({"from": "2026-01-01", "to": "2026-01-31", "scale": 2});
+        assert_eq!(python_args(ExportKind::PngReport, &retina, "x").unwrap()[3..], ["--scale=2", "--output=x"]);
+        assert_eq!(default_filename(ExportKind::PngReport, &retina), "cc-usage-report-2026-01-01_2026-01-31@2x.png");
+        let bad_scale = json!({"from": "2026-01-01", "to": "2026-01-31", "scale": 4});
+        assert!(python_args(ExportKind::PngReport, &bad_scale, "x").is_err());
+        let bad_social = json!({"from": "2026-01-01", "to": "2026-01-31", "social": "myspace"});
+        assert!(python_args(ExportKind::PngReport, &bad_social, "x").is_err());
+        assert_eq!(
+            default_filename(ExportKind::RawSessions, &json!({"session_ids": ["0123456789"], "redact": true})),
+            "cc-session-01234567-redacted.zip"
+        );
+        assert!(python_args(ExportKind::CostAllocation, &json!({"month": "2026-01", "format": "pdf"}), "x").is_err());
+    }
+
+    #[test]
+    fn test_written_file_reports_size_and_hash() {
+        let path = std::env::temp_dir()
+            .join(format!("cc-export-{}", std::process::id()))
+            .join("out.png");
+        let bytes = decode_data("data:image/png;base64,YWJj").unwrap();
+        let written = write_export_file(&path, &bytes).unwrap();
+        assert_eq!(written.size, 3);
+        assert_eq!(written.sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(file_info(&path).unwrap().sha256, written.sha256);
+    }
+
+    #[test]
+    fn test_interrupted_jobs_fail_and_history_is_pruned() {
+        let mut history = ExportHistory {
+            schema_version: 1,
+            jobs: vec![job("running", ExportStatus::Running), job("done", ExportStatus::Succeeded)],
+        };
+        assert_eq!(history.fail_interrupted("now"), 1);
+        assert_eq!(history.jobs[0].status, ExportStatus::Failed);
+        assert!(history.jobs[0].error.is_some());
+
+        history.jobs = (0..MAX_HISTORY + 5).map(|i| job(&i.to_string(), ExportStatus::Failed)).collect();
+        history.jobs.push(job("queued", ExportStatus::Queued));
+        history.prune();
+        assert_eq!(history.jobs.len(), MAX_HISTORY + 1);
+        assert_eq!(history.jobs[0].id, "0");
+        assert_eq!(history.jobs.last().unwrap().id, "queued");
+    }
+}
@@ -0,0 +1,160 @@
+/// Feature flags for experimental subsystems
+///
+/// Flags are declared in `FLAGS` with their default. Stored in
+/// `~/.claude/db/command-center-features.json`, a flag can be set locally
+/// (`set_feature_flag`) or by a remote flag document fetched with
+/// `refresh_feature_flags`; local choices win over remote values, which win
+/// over defaults. Names not declared here are kept in the file but ignored, so
+/// a remote document can announce flags for newer versions.
+use std::collections::BTreeMap;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::schema::{self, Migration, Store};
+use crate::settings::db_dir;
+
+pub const FEATURES_FILE: &str = "command-center-features.json";
+
+/// A declared flag.
+pub struct Flag {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub default: bool,
+}
+
+pub const FLAGS: &[Flag] = &[
+    Flag {
+        name: "native_ingestion",
+        description: "Parse and ingest session files in Rust instead of the Python backend",
+        default: false,
+    },
+    Flag {
+        name: "http_server",
+        description: "Serve the dashboard API over a local HTTP server",
+        default: false,
+    },
+    Flag {
+        name: "log_watcher",
+        description: "Watch the Claude log directories and index new session lines as they are written",
+        default: true,
+    },
+];
+
+fn features_v1(_obj: &mut serde_json::Map<String, Value>) {}
+
+pub const FEATURES_MIGRATIONS: &[Migration] = &[Migration {
+    to: 1,
+    description: "Add schema_version",
+    apply: features_v1,
+}];
+
+pub const FEATURES_STORE: Store = Store {
+    name: "features",
+    file: FEATURES_FILE,
+    migrations: FEATURES_MIGRATIONS,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FlagStore {
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Choices made in this installation
+    #[serde(default)]
+    pub local: BTreeMap<String, bool>,
+    /// Values from the last fetched remote document
+    #[serde(default)]
+    pub remote: BTreeMap<String, bool>,
+    /// https URL of the remote document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_fetched_at: Option<String>,
+}
+
+impl FlagStore {
+    /// Load flags; a missing or unreadable file yields the defaults.
+    pub fn load() -> Self {
+        db_dir()
+            .ok()
+            .and_then(|dir| fs::read(dir.join(FEATURES_FILE)).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save with the current schema version; refuses to overwrite a file
+    /// written by a newer app version.
+    pub fn save(&self) -> Result<(), String> {
+        let dir = db_dir()?;
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let path = dir.join(FEATURES_FILE);
+        let latest = FEATURES_STORE.latest();
+        if Self::load().schema_version > latest {
+            return Err(format!(
+                "{} was written by a newer version of Command Center; update the app",
+                path.display()
+            ));
+        }
+        let mut value = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        value["schema_version"] = Value::from(latest);
+        schema::write_json_atomic(&path, &value)
+    }
+
+    /// Effective value of a declared flag and where it comes from.
+    pub fn resolve(&self, flag: &Flag) -> (bool, &'static str) {
+        if let Some(&enabled) = self.local.get(flag.name) {
+            (enabled, "local")
+        } else if let Some(&enabled) = self.remote.get(flag.name) {
+            (enabled, "remote")
+        } else {
+            (flag.default, "default")
+        }
+    }
+}
+
+/// Look up a declared flag by name.
+pub fn find(name: &str) -> Option<&'static Flag> {
+    FLAGS.iter().find(|flag| flag.name == name)
+}
+
+/// All declared flags with their effective values, as returned by `get_feature_flags`.
+pub fn flags_json(store: &FlagStore) -> Value {
+    let flags: Vec<Value> = FLAGS
+        .iter()
+        .map(|flag| {
+            let (enabled, source) = store.resolve(flag);
+            serde_json::json!({
+                "name": flag.name,
+                "description": flag.description,
+                "enabled": enabled,
+                "default": flag.default,
+                "source": source,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "flags": flags,
+        "remote_url": store.remote_url,
+        "remote_fetched_at": store.remote_fetched_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_wins_over_remote_over_default() {
+        let mut store = FlagStore::default();
+        let flag = find("http_server").unwrap();
+        assert_eq!(store.resolve(flag), (false, "default"));
+
+        store.remote.insert("http_server".to_string(), true);
+        assert_eq!(store.resolve(flag), (true, "remote"));
+
+        store.local.insert("http_server".to_string(), false);
+        assert_eq!(store.resolve(flag), (false, "local"));
+        assert!(find("not_a_flag").is_none());
+    }
+}
@@ -0,0 +1,141 @@
+/// Version handshake between the desktop app and the Python API
+///
+/// The app is built against one version of the `tauri_api` JSON API
+/// (`EXPECTED_API_VERSION`, `API_VERSION` on the Python side). Before the
+/// first backend call, `PythonBackend` asks the backend for its versions
+/// (`version` subcommand) and compares the reported `api_version`. When they
+/// differ, or the backend is too old to know the subcommand, every call fails
+/// with `CommandError::Incompatible` instead of the parse errors an
+/// out-of-sync package would produce further down. The outcome is kept until
+/// the app exits; backends that could not be reached are asked again on the
+/// next call. `setup` runs the handshake in the background at startup.
+use std::sync::Mutex;
+
+use serde_json::{json, Value};
+
+use crate::error::CommandError;
+use crate::python_bridge::call_python_api;
+
+/// Version of the Python JSON API this app was built for.
+pub const EXPECTED_API_VERSION: u64 = 2;
+
+/// Subcommand reporting the backend's versions.
+pub const VERSION_SUBCOMMAND: &str = "version";
+
+/// Outcome of the handshake: the reported versions or `Incompatible`.
+static HANDSHAKE: Mutex<Option<Result<Value, CommandError>>> = Mutex::new(None);
+
+/// Compare the versions reported by `version` with the expected API version.
+pub fn check(reported: &Value) -> Result<(), CommandError> {
+    let found = reported.get("api_version").and_then(Value::as_u64);
+    if found == Some(EXPECTED_API_VERSION) {
+        return Ok(());
+    }
+    let package_version = reported.get("package_version").and_then(Value::as_str).map(str::to_string);
+    Err(incompatible(found, package_version))
+}
+
+fn incompatible(found: Option<u64>, package_version: Option<String>) -> CommandError {
+    let package = match &package_version {
+        Some(version) => format!("command_center {}", version),
+        None => "The installed command_center".to_string(),
+    };
+    let message = match found {
+        Some(found) => format!(
+            "{} speaks API version {}, but this app needs version {}. Install matching versions of the app and the command-center package.",
+            package, found, EXPECTED_API_VERSION
+        ),
+        None => format!(
+            "{} does not report an API version and is too old for this app (needs version {}). Upgrade the command-center package.",
+            package, EXPECTED_API_VERSION
+        ),
+    };
+    CommandError::Incompatible {
+        expected: EXPECTED_API_VERSION,
+        found,
+        package_version,
+        message,
+        request_id: None,
+    }
+}
+
+/// Outcome of a `version` call, or `None` when the backend could not be
+/// reached (missing Python, timeout, cancelled) and nothing was learned.
+///
+/// A backend that runs but rejects the subcommand or answers with something
+/// other than JSON predates the handshake and is incompatible.
+pub fn outcome(response: Result<Value, CommandError>) -> Option<Result<Value, CommandError>> {
+    match response {
+        Ok(reported) => Some(check(&reported).map(|()| reported)),
+        Err(CommandError::NonZeroExit { .. } | CommandError::ParseError { .. }) => Some(Err(incompatible(None, None))),
+        Err(_) => None,
+    }
+}
+
+/// Run the handshake unless it already has an outcome; `Incompatible` (with
+/// `request_id` attached) when the backend doesn't match.
+pub fn ensure_compatible(request_id: &str) -> Result<(), CommandError> {
+    if let Some(known) = HANDSHAKE.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return known.as_ref().map(|_| ()).map_err(|e| e.clone().with_request_id(request_id));
+    }
+    let response = call_python_api(request_id, &[VERSION_SUBCOMMAND]);
+    let Some(known) = outcome(response.clone()) else {
+        return response.map(|_| ());
+    };
+    if let Err(e) = &known {
+        log::warn!("[{}] Backend handshake failed: {}", request_id, e.message());
+    }
+    let result = known.as_ref().map(|_| ()).map_err(|e| e.clone().with_request_id(request_id));
+    *HANDSHAKE.lock().unwrap_or_else(|e| e.into_inner()) = Some(known);
+    result
+}
+
+/// `get_backend_version` payload for the response of a `version` call:
+/// the reported versions next to the expected one and, when they don't
+/// match, the `Incompatible` error. Unreachable backends fail as usual.
+pub fn report(response: Result<Value, CommandError>, request_id: &str) -> Result<Value, CommandError> {
+    let reported = response.as_ref().ok().cloned();
+    let Some(known) = outcome(response.clone()) else {
+        return response;
+    };
+    Ok(json!({
+        "app_version": env!("CARGO_PKG_VERSION"),
+        "expected_api_version": EXPECTED_API_VERSION,
+        "backend": reported,
+        "compatible": known.is_ok(),
+        "error": known.err().map(|e| e.with_request_id(request_id)),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let current = json!({"api_version": EXPECTED_API_VERSION, "package_version": "2.6.0"});
+        assert!(check(&current).is_ok());
+
+        let newer = check(&json!({"api_version": EXPECTED_API_VERSION + 1, "package_version": "3.0.0"})).unwrap_err();
+        assert!(matches!(
+            newer,
+            CommandError::Incompatible { found: Some(found), package_version: Some(ref v), .. }
+                if found == EXPECTED_API_VERSION + 1 && v == "3.0.0"
+        ));
+        assert!(newer.message().starts_with("command_center 3.0.0 speaks API version"));
+    }
+
+    #[test]
+    fn test_outcome() {
+        // A backend without the `version` subcommand exits with an argparse error
+        let old = CommandError::from_exit(Some(2), "error: argument command: invalid choice: 'version'\n");
+        assert!(matches!(outcome(Err(old)), Some(Err(CommandError::Incompatible { found: None, .. }))));
+        assert!(outcome(Err(CommandError::python_missing("No Python"))).is_none());
+
+        let payload = report(Ok(json!({"api_version": 0})), "rq-1").unwrap();
+        assert_eq!(payload["compatible"], false);
+        assert_eq!(payload["error"]["kind"], "incompatible");
+        assert_eq!(payload["error"]["request_id"], "rq-1");
+        assert!(report(Err(CommandError::timeout(5, "Timeout")), "rq-2").is_err());
+    }
+}
@@ -0,0 +1,224 @@
+/// Integration harness for the command layer
+///
+/// Runs the `#[tauri::command]` handlers on a mock app whose `AppState`
+/// backend answers from `fixtures/harness/<subcommand>.json` (recorded from
+/// the Python API on seeded demo data by `scripts/record_harness_fixtures.py`),
+/// with HOME pointing at a temporary directory so settings, caches and
+/// privacy mode start empty. Each command's backend calls and typed response
+/// (or error) are compared with its golden file in `fixtures/golden/`; a
+/// change to the bridge, argument building or response post-processing that
+/// changes what the frontend receives fails the test until the golden file is
+/// updated on purpose.
+///
+/// Missing golden files are written. `UPDATE_GOLDEN=1 cargo test harness`
+/// rewrites all of them; review the diff before committing.
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use serde_json::{json, Value};
+use tauri::async_runtime::block_on;
+use tauri::test::MockRuntime;
+use tauri::{Manager, State};
+
+use crate::backend::{MockBackend, SharedBackend};
+use crate::commands::*;
+use crate::error::CommandError;
+use crate::state::AppState;
+
+/// Request ID of every harness call, so errors are stable.
+const REQUEST_ID: &str = "rq-golden";
+
+/// Project of the recorded demo data.
+const PROJECT: &str = "-home-demo-projects-aurora-web";
+
+fn fixture_dir(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(name)
+}
+
+/// Temporary HOME shared by the harness calls, set once per test process.
+fn temp_home() -> &'static Path {
+    static HOME: OnceLock<PathBuf> = OnceLock::new();
+    HOME.get_or_init(|| {
+        let home = std::env::temp_dir().join(format!("cc-harness-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&home);
+        std::fs::create_dir_all(&home).expect("harness home");
+        std::env::set_var("HOME", &home);
+        home
+    })
+}
+
+struct Harness {
+    app: tauri::App<MockRuntime>,
+    backend: Arc<MockBackend>,
+    /// Backend calls already assigned to a command
+    seen: Cell<usize>,
+    update: bool,
+    mismatches: Vec<String>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        temp_home();
+        let backend = Arc::new(MockBackend::from_dir(&fixture_dir("harness")).expect("harness fixtures"));
+        let shared: SharedBackend = backend.clone();
+        let app = tauri::test::mock_builder()
+            .manage(AppState::new(shared))
+            .build(tauri::test::mock_context(tauri::test::noop_assets()))
+            .expect("mock app");
+        Self {
+            app,
+            backend,
+            seen: Cell::new(0),
+            update: std::env::var("UPDATE_GOLDEN").is_ok_and(|v| v == "1"),
+            mismatches: Vec::new(),
+        }
+    }
+
+    fn state(&self) -> State<'_, AppState> {
+        self.app.state()
+    }
+
+    /// Backend calls and result of `command`, with the temp HOME replaced.
+    fn record(&self, result: Result<Value, CommandError>) -> Value {
+        let calls = self.backend.calls();
+        let args: Vec<_> = calls[self.seen.get()..].iter().map(|call| call.args.clone()).collect();
+        self.seen.set(calls.len());
+        let record = match result {
+            Ok(response) => json!({"calls": args, "response": response}),
+            Err(error) => json!({"calls": args, "error": error}),
+        };
+        let home = temp_home().display().to_string();
+        let text = serde_json::to_string(&record).expect("serializable record");
+        serde_json::from_str(&text.replace(&home, "~")).expect("valid record")
+    }
+
+    fn check(&mut self, command: &str, result: Result<Value, CommandError>) {
+        let actual = self.record(result);
+        let path = fixture_dir("golden").join(format!("{}.json", command));
+        let expected = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok());
+        if expected.as_ref() == Some(&actual) {
+            return;
+        }
+        if self.update || expected.is_none() {
+            std::fs::create_dir_all(path.parent().unwrap()).expect("golden dir");
+            let text = serde_json::to_string_pretty(&actual).expect("serializable record");
+            std::fs::write(&path, text + "\n").expect("golden file");
+            return;
+        }
+        self.mismatches.push(command.to_string());
+    }
+}
+
+fn rq() -> Option<String> {
+    Some(REQUEST_ID.to_string())
+}
+
+/// Run a command on the harness and check it against its golden file.
+macro_rules! golden {
+    ($harness:ident, $command:ident($($arg:expr),* $(,)?)) => {{
+        let result = block_on($command($harness.state(), $($arg),*));
+        $harness.check(stringify!($command), result);
+    }};
+}
+
+#[test]
+fn test_command_goldens() {
+    let mut h = Harness::new();
+    let (from, to) = ("2026-03-01".to_string(), "2026-03-31".to_string());
+
+    // Ingestion and drill-downs
+    golden!(h, get_refresh_status(rq()));
+    golden!(h, get_quarantined_lines(None, None, rq()));
+    golden!(h, get_aggregate_history("2026-03-16".into(), rq()));
+    golden!(h, get_day_details("2026-03-16".into(), None, None, rq()));
+    golden!(h, get_model_details("claude-sonnet-4-5".into(), from.clone(), to.clone(), None, None, rq()));
+    golden!(h, get_limit_resets(from.clone(), to.clone(), rq()));
+    golden!(h, get_limit_impact(from.clone(), to.clone(), None, rq()));
+
+    // Analytics
+    golden!(h, get_mcp_usage(from.clone(), to.clone(), None, rq()));
+    golden!(h, get_hook_stats(from.clone(), to.clone(), None, rq()));
+    golden!(h, get_effort_breakdown("claude-sonnet-4-5".into(), from.clone(), to.clone(), None, rq()));
+    golden!(h, get_concurrency_stats(from.clone(), to.clone(), None, rq()));
+    golden!(h, get_outcome_stats(from.clone(), to.clone(), None, None, rq()));
+    golden!(h, get_compaction_stats(from.clone(), to.clone(), None, None, rq()));
+    golden!(h, get_out_of_hours_usage(from.clone(), to.clone(), None, rq()));
+    golden!(h, list_available_metrics(rq()));
+    golden!(h, get_metric_series(from.clone(), to.clone(), vec!["cost".into(), "messages".into()], Some("week".into()), None, rq()));
+
+    // Budgets, hooks and derived metrics
+    golden!(h, set_monthly_budget(Some(200.0), Some("2026-03".into()), rq()));
+    golden!(h, get_budget_history(None, rq()));
+    golden!(h, set_hook("refresh-completed".into(), Some("echo refreshed".into()), None, Some("on-refresh".into()), None, None, None, None, rq()));
+    golden!(h, get_hooks(rq()));
+    golden!(h, test_hook("on-refresh".into(), rq()));
+    golden!(h, delete_hook("on-refresh".into(), rq()));
+    golden!(h, set_derived_metric("cost_per_message".into(), "cost / messages".into(), None, rq()));
+    golden!(h, get_derived_metrics(rq()));
+    golden!(h, delete_derived_metric("cost_per_message".into(), rq()));
+
+    // Calendar and working hours
+    let periods = serde_json::from_value(json!([{"from": "2026-03-09", "to": "2026-03-13"}])).unwrap();
+    golden!(h, set_calendar_overlay(periods, rq()));
+    golden!(h, get_calendar_overlay(rq()));
+    golden!(h, set_working_hours(vec![1, 2, 3, 4, 5], "09:00".into(), "17:00".into(), rq()));
+    golden!(h, get_working_hours(rq()));
+
+    // Cost centers and pricing
+    golden!(h, set_cost_center("ENG".into(), Some("Engineering".into()), Some(vec![PROJECT.into()]), None, rq()));
+    golden!(h, get_cost_centers(None, rq()));
+    golden!(h, get_cost_allocation("2026-03".into(), None, rq()));
+    golden!(h, delete_cost_center("ENG".into(), rq()));
+    golden!(h, get_price_versions(None, None, rq()));
+    golden!(h, set_price_version("claude-sonnet-4-5".into(), "2026-03-15".into(), 3.0, 15.0, None, None, rq()));
+    golden!(h, reprice_history(Some(from.clone()), Some(to.clone()), Some(true), rq()));
+
+    // Snapshots
+    golden!(h, create_snapshot("march".into(), from.clone(), to.clone(), None, rq()));
+    golden!(h, list_snapshots(None, rq()));
+    golden!(h, get_snapshot("march".into(), None, rq()));
+    golden!(h, diff_snapshots("march".into(), None, None, rq()));
+    golden!(h, delete_snapshot("march".into(), rq()));
+
+    // Projects, accounts and workspaces
+    golden!(h, get_projects(None, None, rq()));
+    golden!(h, set_project_pinned(PROJECT.into(), true, rq()));
+    golden!(h, set_project_order(vec![PROJECT.into()], rq()));
+    golden!(h, update_project(PROJECT.into(), None, None, None, None, Some("#3b82f6".into()), None, rq()));
+    golden!(h, suggest_project_description(PROJECT.into(), rq()));
+    golden!(h, get_project_timeline(PROJECT.into(), None, rq()));
+    golden!(h, backfill_project_first_seen(rq()));
+    golden!(h, get_stale_projects(Some(14), rq()));
+    golden!(h, archive_projects(vec!["-home-demo-projects-ledger-api".into()], rq()));
+    golden!(h, get_usage_accounts(None, rq()));
+    golden!(h, compare_usage_accounts(from.clone(), to.clone(), None, None, rq()));
+    golden!(h, detect_claude_accounts(None, rq()));
+    golden!(h, detect_plan(None, rq()));
+    golden!(h, create_workspace("Client work".into(), None, rq()));
+    golden!(h, get_workspaces(rq()));
+
+    // Maintenance
+    golden!(h, get_mirror_status(None, rq()));
+    golden!(h, clear_mirror(rq()));
+    golden!(h, get_schema_info(rq()));
+    golden!(h, get_backend_version(rq()));
+    let filter = serde_json::from_value(json!({"from": "2026-03-16", "to": "2026-03-16"})).unwrap();
+    golden!(h, shift_session_timestamps(filter, "+1h".into(), Some(true), rq()));
+    golden!(h, list_timestamp_shifts(rq()));
+
+    // Desktop settings
+    golden!(h, get_description_llm_settings());
+    golden!(h, set_description_llm(true, None));
+    golden!(h, get_range_settings());
+    golden!(h, set_range_settings(Some("UTC".into()), Some("sunday".into()), Some(15)));
+    golden!(h, resolve_range("this-month".into(), Some("2026-03-16".into()), rq()));
+
+    assert!(
+        h.mismatches.is_empty(),
+        "Responses differ from their golden files: {} (UPDATE_GOLDEN=1 to accept)",
+        h.mismatches.join(", ")
+    );
+}
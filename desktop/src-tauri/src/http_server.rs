@@ -0,0 +1,625 @@
+/// Local HTTP server for scripts and other tools
+///
+/// With the `http_server` feature flag on, the app serves the backend's
+/// subcommands on `http://127.0.0.1:<http_port>` (setting, default 8765):
+///
+/// ```text
+/// GET /api/v1/dashboard?from=2025-06-01&to=2025-06-30
+/// Authorization: Bearer cc_...
+/// ```
+///
+/// The path names the subcommand and every query parameter becomes a
+/// `--name=value` argument (`?refresh` alone becomes `--refresh`). Responses
+/// are the backend's JSON, or a `CommandError` object with a matching status
+/// code. Every request needs an API token whose scopes cover the subcommand
+/// (see `api_tokens`); tokens are created in the app, never over HTTP.
+/// Authorized requests then pass the command middleware like an invoke
+/// (validation, read-only mode, metrics), and `--output` paths of exports
+/// must lie in the export directory (relative ones are taken from it).
+///
+/// The server binds `127.0.0.1` unless `http_bind_address` names another
+/// address. A non-loopback address is refused (the server does not start)
+/// unless `http_allow_remote` is set and at least one API token is active.
+/// With `http_tls_cert` and `http_tls_key` (PEM files) it serves HTTPS
+/// (rustls); `http_tls_client_ca` additionally requires client certificates
+/// signed by one of its CAs (mTLS). Settings apply when the app starts.
+///
+/// Each connection gets its own thread and is closed after one response; at
+/// most `MAX_CONNECTIONS` are answered at once, later ones wait in the
+/// listen backlog until a slot frees.
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use chrono::Local;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig, ServerConnection, StreamOwned};
+use serde_json::Value;
+
+use crate::api_tokens;
+use crate::backend::{ApiRequest, SharedBackend};
+use crate::error::CommandError;
+use crate::features::{self, FlagStore};
+use crate::middleware::{self, Invocation, Middleware};
+use crate::python_bridge::{new_request_id, resolve_request_id};
+use crate::settings::Settings;
+
+/// Feature flag of the server.
+pub const HTTP_SERVER_FLAG: &str = "http_server";
+
+/// Port used without an `http_port` setting.
+pub const DEFAULT_PORT: u16 = 8765;
+
+/// Path prefix of the subcommand routes.
+const API_PREFIX: &str = "/api/v1/";
+
+/// Largest request head (request line and headers) read.
+const MAX_HEAD_BYTES: u64 = 16 * 1024;
+
+/// How long a connection may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a client may take to read the response.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Connections answered at once.
+const MAX_CONNECTIONS: usize = 8;
+
+/// Subcommands never served over HTTP: the worker loop, and the ones that
+/// run programs on this machine (hook commands) or install code (plugins).
+const HIDDEN_SUBCOMMANDS: &[&str] = &["serve", "set-hook", "test-hook", "install-plugin"];
+
+/// Webview label of HTTP requests in the command middleware.
+const HTTP_WEBVIEW: &str = "http";
+
+/// Whether the server starts with the app.
+pub fn enabled() -> bool {
+    features::find(HTTP_SERVER_FLAG).is_some_and(|flag| FlagStore::load().resolve(flag).0)
+}
+
+/// Where and how the server listens, resolved from the settings.
+#[derive(Debug)]
+pub struct ListenConfig {
+    pub addr: SocketAddr,
+    /// HTTPS; plain HTTP if None
+    pub tls: Option<Arc<ServerConfig>>,
+    /// Client certificates are required (`http_tls_client_ca`)
+    pub mutual_tls: bool,
+}
+
+impl ListenConfig {
+    pub fn url(&self) -> String {
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+        format!("{}://{}", scheme, self.addr)
+    }
+}
+
+/// Parse `http_bind_address`: an IP address or "localhost".
+fn parse_bind_address(address: &str) -> Result<IpAddr, String> {
+    match address.trim() {
+        "localhost" => Ok(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        address => address
+            .parse()
+            .map_err(|_| format!("Invalid bind address: {} (expected an IP address)", address)),
+    }
+}
+
+/// Loopback addresses are always allowed; any other address needs the remote
+/// override and an active API token, so the server is never reachable from the
+/// network by accident or without authentication.
+fn check_bind_address(ip: IpAddr, allow_remote: bool, has_active_token: bool) -> Result<(), String> {
+    if ip.is_loopback() {
+        return Ok(());
+    }
+    if !allow_remote {
+        return Err(format!(
+            "Refusing to bind {}: not a loopback address (set http_allow_remote to override)",
+            ip
+        ));
+    }
+    if !has_active_token {
+        return Err(format!(
+            "Refusing to bind {}: remote access needs an active API token (create_api_token)",
+            ip
+        ));
+    }
+    Ok(())
+}
+
+fn read_pem_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, String> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Failed to read certificates from {}: {}", path.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("No certificates in {}", path.display()));
+    }
+    Ok(certs)
+}
+
+/// Server TLS of a PEM certificate chain and key; with `client_ca`, clients
+/// must present a certificate signed by one of its CAs.
+fn tls_config(cert: &Path, key: &Path, client_ca: Option<&Path>) -> Result<ServerConfig, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?;
+    let builder = match client_ca {
+        Some(client_ca) => {
+            let mut roots = RootCertStore::empty();
+            for ca in read_pem_certs(client_ca)? {
+                roots
+                    .add(ca)
+                    .map_err(|e| format!("Invalid CA certificate in {}: {}", client_ca.display(), e))?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+                .build()
+                .map_err(|e| format!("Invalid client CA {}: {}", client_ca.display(), e))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+    let key = PrivateKeyDer::from_pem_file(key)
+        .map_err(|e| format!("Failed to read the private key from {}: {}", key.display(), e))?;
+    builder
+        .with_single_cert(read_pem_certs(cert)?, key)
+        .map_err(|e| format!("Certificate {} does not match its key: {}", cert.display(), e))
+}
+
+/// Resolve and check the listener settings; the error says why the server
+/// must not start.
+pub fn listen_config(settings: &Settings, has_active_token: bool) -> Result<ListenConfig, String> {
+    let ip = match settings.http_bind_address.as_deref() {
+        Some(address) => parse_bind_address(address)?,
+        None => IpAddr::V4(Ipv4Addr::LOCALHOST),
+    };
+    check_bind_address(ip, settings.http_allow_remote.unwrap_or(false), has_active_token)?;
+    let tls = match (&settings.http_tls_cert, &settings.http_tls_key) {
+        (Some(cert), Some(key)) => {
+            let client_ca = settings.http_tls_client_ca.as_deref().map(Path::new);
+            Some(Arc::new(tls_config(Path::new(cert), Path::new(key), client_ca)?))
+        }
+        (None, None) if settings.http_tls_client_ca.is_some() => {
+            return Err("Client certificates (http_tls_client_ca) need http_tls_cert and http_tls_key".to_string())
+        }
+        (None, None) => None,
+        _ => return Err("http_tls_cert and http_tls_key must be set together".to_string()),
+    };
+    Ok(ListenConfig {
+        addr: SocketAddr::new(ip, settings.http_port.unwrap_or(DEFAULT_PORT)),
+        tls,
+        mutual_tls: settings.http_tls_client_ca.is_some(),
+    })
+}
+
+/// Whether any API token can currently authenticate.
+pub fn has_active_token() -> bool {
+    let now = Local::now();
+    api_tokens::list().iter().any(|token| token.status(now) == "active")
+}
+
+/// A parsed request head.
+#[derive(Debug, PartialEq)]
+struct HttpRequest {
+    method: String,
+    subcommand: String,
+    args: Vec<String>,
+    token: Option<String>,
+    request_id: Option<String>,
+}
+
+/// Decode `%XX` escapes and `+` (space) of a query component.
+fn percent_decode(text: &str) -> Result<String, CommandError> {
+    let invalid = || CommandError::invalid_argument(format!("Invalid query encoding: {}", text));
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.bytes();
+    while let Some(byte) = rest.next() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = [rest.next().ok_or_else(invalid)?, rest.next().ok_or_else(invalid)?];
+                let hex = std::str::from_utf8(&hex).map_err(|_| invalid())?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            }
+            byte => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+/// Backend arguments of a query string: `a=1&b` -> `--a=1 --b`.
+fn query_args(query: &str) -> Result<Vec<String>, CommandError> {
+    let mut args = Vec::new();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (name, value) = match pair.split_once('=') {
+            Some((name, value)) => (percent_decode(name)?, Some(percent_decode(value)?)),
+            None => (percent_decode(pair)?, None),
+        };
+        let valid_name = name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_'));
+        if !valid_name {
+            return Err(CommandError::invalid_argument(format!("Invalid parameter name: {:?}", name)));
+        }
+        let name = name.replace('_', "-");
+        args.push(match value {
+            Some(value) => format!("--{}={}", name, value),
+            None => format!("--{}", name),
+        });
+    }
+    Ok(args)
+}
+
+/// Take export `--output` paths from `export_dir`: relative ones are joined
+/// to it, absolute ones must lie inside it, and none may contain `..`.
+fn confine_output(args: &mut [String], export_dir: &Path) -> Result<(), CommandError> {
+    for arg in args.iter_mut() {
+        let Some(output) = arg.strip_prefix("--output=") else { continue };
+        let path = Path::new(output);
+        let inside = if path.is_absolute() {
+            path.starts_with(export_dir) && !path.components().any(|c| c == Component::ParentDir)
+        } else {
+            path.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        };
+        if !inside {
+            return Err(CommandError::invalid_argument(format!(
+                "Output path {} is outside the export directory {}",
+                output,
+                export_dir.display()
+            )));
+        }
+        *arg = format!("--output={}", export_dir.join(path).display());
+    }
+    Ok(())
+}
+
+/// Arguments of a request as the middleware sees them: the query parameters
+/// by name (`--from=2025-06-01` -> `"from": "2025-06-01"`, flags `true`) and
+/// `requestId`.
+fn invocation_args(args: &[String], request_id: &str) -> Value {
+    let mut object = serde_json::Map::new();
+    for arg in args {
+        let arg = arg.trim_start_matches('-');
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Value::from(value)),
+            None => (arg, Value::Bool(true)),
+        };
+        object.insert(name.to_string(), value);
+    }
+    object.insert("requestId".to_string(), Value::from(request_id));
+    Value::Object(object)
+}
+
+/// Parse the request line and headers of one request.
+fn parse_request(head: &[String]) -> Result<HttpRequest, CommandError> {
+    let mut parts = head.first().map(String::as_str).unwrap_or_default().split_whitespace();
+    let (method, target) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => (method, target),
+        _ => return Err(CommandError::invalid_argument("Malformed HTTP request line")),
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let subcommand = path
+        .strip_prefix(API_PREFIX)
+        .filter(|name| {
+            !name.is_empty()
+                && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+                && !HIDDEN_SUBCOMMANDS.contains(name)
+        })
+        .ok_or_else(|| CommandError::not_found(format!("No such endpoint: {}", path)))?;
+
+    let mut token = None;
+    let mut request_id = None;
+    for line in &head[1..] {
+        let Some((name, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("authorization") {
+            token = value
+                .split_once(' ')
+                .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+                .map(|(_, secret)| secret.trim().to_string());
+        } else if name.eq_ignore_ascii_case("x-request-id") {
+            request_id = Some(value.to_string());
+        }
+    }
+    Ok(HttpRequest {
+        method: method.to_string(),
+        subcommand: subcommand.to_string(),
+        args: query_args(query)?,
+        token,
+        request_id,
+    })
+}
+
+/// Read the request head: lines up to the first empty one.
+fn read_head(stream: &mut impl Read) -> Result<Vec<String>, CommandError> {
+    let mut reader = BufReader::new(stream.take(MAX_HEAD_BYTES));
+    let mut head = Vec::new();
+    loop {
+        let mut line = String::new();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|e| CommandError::invalid_argument(format!("Cannot read the request: {}", e)))?;
+        if read == 0 || !line.ends_with('\n') {
+            return Err(CommandError::invalid_argument("Incomplete or oversized HTTP request"));
+        }
+        let line = line.trim_end().to_string();
+        if line.is_empty() {
+            return Ok(head);
+        }
+        head.push(line);
+    }
+}
+
+/// HTTP status of a failed request.
+fn status_of(error: &CommandError) -> (u16, &'static str) {
+    match error {
+        CommandError::InvalidArgument { .. } => (400, "Bad Request"),
+        CommandError::Unauthorized { .. } => (401, "Unauthorized"),
+        CommandError::Forbidden { .. } | CommandError::ReadOnly { .. } => (403, "Forbidden"),
+        CommandError::NotFound { .. } => (404, "Not Found"),
+        CommandError::Timeout { .. } => (504, "Gateway Timeout"),
+        CommandError::Busy { .. } => (503, "Service Unavailable"),
+        _ => (500, "Internal Server Error"),
+    }
+}
+
+fn write_response(stream: &mut impl Write, status: (u16, &str), body: &Value, extra_headers: &str) {
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        status.0,
+        status.1,
+        body.len(),
+        extra_headers,
+        body
+    );
+    if let Err(e) = stream.write_all(response.as_bytes()).and_then(|()| stream.flush()) {
+        log::debug!("Failed to send an HTTP response: {}", e);
+    }
+}
+
+/// What the connections are answered with.
+struct Service {
+    backend: SharedBackend,
+    middleware: Vec<Box<dyn Middleware>>,
+    export_dir: PathBuf,
+}
+
+/// Answer one request on a plain or TLS stream.
+fn handle(stream: &mut (impl Read + Write), service: &Service) {
+    let fallback_id = new_request_id();
+    let request = read_head(stream).and_then(|head| parse_request(&head));
+    let request_id = request
+        .as_ref()
+        .ok()
+        .map(|request| resolve_request_id(request.request_id.clone()))
+        .unwrap_or(fallback_id);
+    if let Ok(request) = &request {
+        if !matches!(request.method.as_str(), "GET" | "POST") {
+            let error = CommandError::invalid_argument(format!("Method not allowed: {}", request.method));
+            let body = serde_json::to_value(error.with_request_id(&request_id)).unwrap_or(Value::Null);
+            write_response(stream, (405, "Method Not Allowed"), &body, "Allow: GET, POST\r\n");
+            return;
+        }
+    }
+    let result = request.and_then(|mut request| {
+        api_tokens::authorize(request.token.as_deref(), &request.subcommand, &request_id)?;
+        let command = middleware::http_command(&request.subcommand);
+        let invocation_args = invocation_args(&request.args, &request_id);
+        let invocation = Invocation { command: &command, webview: HTTP_WEBVIEW, args: &invocation_args };
+        middleware::run_chain(&service.middleware, &invocation)?;
+        if api_tokens::EXPORT_COMMANDS.contains(&request.subcommand.as_str()) {
+            confine_output(&mut request.args, &service.export_dir)?;
+        }
+        let mut args = vec![request.subcommand.as_str()];
+        args.extend(request.args.iter().map(String::as_str));
+        log::info!("[{}] HTTP {} {}", request_id, request.method, request.subcommand);
+        service.backend.call(ApiRequest::new(&request_id, &args))
+    });
+    match result {
+        Ok(value) => write_response(stream, (200, "OK"), &value, ""),
+        Err(e) => {
+            let e = e.with_request_id(&request_id);
+            let headers = match e {
+                CommandError::Unauthorized { .. } => "WWW-Authenticate: Bearer\r\n",
+                _ => "",
+            };
+            let body = serde_json::to_value(&e).unwrap_or(Value::Null);
+            write_response(stream, status_of(&e), &body, headers);
+        }
+    }
+}
+
+/// Connections being answered, capped at `MAX_CONNECTIONS`.
+static OPEN_CONNECTIONS: Mutex<usize> = Mutex::new(0);
+static CONNECTION_CLOSED: Condvar = Condvar::new();
+
+/// Slot of the connection limit, held while a connection is answered.
+struct ConnectionSlot;
+
+impl ConnectionSlot {
+    /// Wait for a free slot.
+    fn acquire() -> Self {
+        let mut open = OPEN_CONNECTIONS.lock().unwrap_or_else(|e| e.into_inner());
+        while *open >= MAX_CONNECTIONS {
+            open = CONNECTION_CLOSED.wait(open).unwrap_or_else(|e| e.into_inner());
+        }
+        *open += 1;
+        ConnectionSlot
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        *OPEN_CONNECTIONS.lock().unwrap_or_else(|e| e.into_inner()) -= 1;
+        CONNECTION_CLOSED.notify_one();
+    }
+}
+
+/// Answer one connection, after the TLS handshake if configured.
+fn serve_connection(stream: TcpStream, tls: Option<Arc<ServerConfig>>, service: &Service) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+    let Some(tls) = tls else {
+        let mut stream = stream;
+        handle(&mut stream, service);
+        return;
+    };
+    let peer = stream.peer_addr().map(|addr| addr.to_string()).unwrap_or_default();
+    let connection = match ServerConnection::new(tls) {
+        Ok(connection) => connection,
+        Err(e) => return log::warn!("TLS session for {} not started: {}", peer, e),
+    };
+    let mut stream = StreamOwned::new(connection, stream);
+    if let Err(e) = stream.conn.complete_io(&mut stream.sock) {
+        return log::info!("TLS handshake with {} failed: {}", peer, e);
+    }
+    handle(&mut stream, service);
+    stream.conn.send_close_notify();
+    let _ = stream.flush();
+}
+
+/// Body of the `http-server` task: accept connections until the app exits.
+/// Exports requested over HTTP are written in `export_dir`.
+pub fn run(backend: SharedBackend, export_dir: PathBuf) {
+    let config = match listen_config(&Settings::load(), has_active_token()) {
+        Ok(config) => config,
+        Err(e) => return log::warn!("HTTP server not started: {}", e),
+    };
+    let listener = match TcpListener::bind(config.addr) {
+        Ok(listener) => listener,
+        Err(e) => return log::warn!("HTTP server not started on {}: {}", config.addr, e),
+    };
+    if !config.addr.ip().is_loopback() && config.tls.is_none() {
+        log::warn!("HTTP server on {} without TLS: API tokens travel unencrypted", config.addr);
+    }
+    log::info!(
+        "HTTP server listening on {}{}",
+        config.url(),
+        if config.mutual_tls { " (client certificates required)" } else { "" }
+    );
+    let service = Arc::new(Service {
+        backend,
+        middleware: middleware::default_chain(),
+        export_dir,
+    });
+    loop {
+        // Not accepted before a slot is free: waiting clients stay in the backlog
+        let slot = ConnectionSlot::acquire();
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let service = service.clone();
+                let tls = config.tls.clone();
+                std::thread::spawn(move || {
+                    serve_connection(stream, tls, &service);
+                    drop(slot);
+                });
+            }
+            Err(e) => log::warn!("HTTP connection failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_request_maps_query_to_args() {
+        let request = parse_request(&head(&[
+            "GET /api/v1/dashboard?from=2025-06-01&to=2025-06-30&project_id=a%2Fb+c&refresh HTTP/1.1",
+            "Host: 127.0.0.1",
+            "authorization: Bearer cc_abc",
+            "X-Request-Id: script-1",
+        ]))
+        .unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.subcommand, "dashboard");
+        assert_eq!(
+            request.args,
+            vec!["--from=2025-06-01", "--to=2025-06-30", "--project-id=a/b c", "--refresh"]
+        );
+        assert_eq!(request.token.as_deref(), Some("cc_abc"));
+        assert_eq!(request.request_id.as_deref(), Some("script-1"));
+    }
+
+    #[test]
+    fn test_parse_request_rejects_unknown_paths_and_names() {
+        let not_found = |target: &str| {
+            let line = format!("GET {} HTTP/1.1", target);
+            matches!(parse_request(&head(&[&line])), Err(CommandError::NotFound { .. }))
+        };
+        assert!(not_found("/"));
+        assert!(not_found("/api/v1/serve"));
+        assert!(not_found("/api/v1/set-hook?command=sh"));
+        assert!(not_found("/api/v1/test-hook"));
+        assert!(not_found("/api/v1/../dashboard"));
+        assert!(matches!(
+            parse_request(&head(&["GET /api/v1/day?--date=x HTTP/1.1"])),
+            Err(CommandError::InvalidArgument { .. })
+        ));
+        assert!(matches!(parse_request(&head(&["GET"])), Err(CommandError::InvalidArgument { .. })));
+        assert!(percent_decode("%zz").is_err());
+    }
+
+    #[test]
+    fn test_output_paths_stay_in_the_export_dir() {
+        let dir = std::env::temp_dir().join("cc-exports");
+        let confined = |output: &str| {
+            let mut args = vec!["--id=s1".to_string(), format!("--output={}", output)];
+            confine_output(&mut args, &dir).map(|()| args[1].clone())
+        };
+        let inside = format!("--output={}", dir.join("report.png").display());
+        assert_eq!(confined("report.png").unwrap(), inside);
+        assert_eq!(confined(&dir.join("report.png").display().to_string()).unwrap(), inside);
+        assert!(confined("../report.png").is_err());
+        assert!(confined(&dir.join("..").join("report.png").display().to_string()).is_err());
+        assert!(confined(&std::env::temp_dir().join("report.png").display().to_string()).is_err());
+
+        let args = invocation_args(&["--from=2025-06-01".to_string(), "--refresh".to_string()], "rq-1");
+        assert_eq!(args, serde_json::json!({"from": "2025-06-01", "refresh": true, "requestId": "rq-1"}));
+    }
+
+    #[test]
+    fn test_connection_slots_are_capped() {
+        let slots: Vec<_> = (0..MAX_CONNECTIONS).map(|_| ConnectionSlot::acquire()).collect();
+        let (sender, acquired) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let _slot = ConnectionSlot::acquire();
+            sender.send(()).unwrap();
+        });
+        assert!(acquired.recv_timeout(Duration::from_millis(100)).is_err());
+        drop(slots);
+        assert!(acquired.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+
+    #[test]
+    fn test_non_loopback_bind_needs_override_and_token() {
+        let remote = parse_bind_address("0.0.0.0").unwrap();
+        assert!(check_bind_address(parse_bind_address("localhost").unwrap(), false, false).is_ok());
+        assert!(check_bind_address(parse_bind_address("::1").unwrap(), false, false).is_ok());
+        assert!(check_bind_address(remote, false, true).unwrap_err().contains("http_allow_remote"));
+        assert!(check_bind_address(remote, true, false).unwrap_err().contains("active API token"));
+        assert!(check_bind_address(remote, true, true).is_ok());
+        assert!(parse_bind_address("example.com").is_err());
+
+        let settings = Settings {
+            http_bind_address: Some("192.168.1.20".to_string()),
+            ..Settings::default()
+        };
+        assert!(listen_config(&settings, true).is_err());
+        let settings = Settings {
+            http_tls_client_ca: Some("/nonexistent/ca.pem".to_string()),
+            ..Settings::default()
+        };
+        assert!(listen_config(&settings, false).unwrap_err().contains("need http_tls_cert"));
+        let config = listen_config(&Settings::default(), false).unwrap();
+        assert_eq!(config.url(), "http://127.0.0.1:8765");
+    }
+}
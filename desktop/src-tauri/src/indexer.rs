@@ -0,0 +1,198 @@
+/// Background indexing of the native store with progress reporting
+///
+/// Every index of the native store (`store`) runs through `index`, which
+/// keeps one process-wide `IndexStatus` (idle or indexing, files done of the
+/// total, percent, the last result) and passes it to an emitter: when the
+/// run starts, at most every `PROGRESS_INTERVAL` while log files are read,
+/// and when it ends. `emitter` sends it to the UI as `index-progress`
+/// events; `get_index_status` returns the current one.
+///
+/// The `store-indexer` task (`run`, spawned by the setup hook) catches the
+/// store up with the logs written while the app was closed, which on the
+/// first start means reading months of session logs. The log watcher and
+/// `index_native_store` report through the same status. Runs never overlap:
+/// a second one waits for the first.
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::store::{self, IndexProgress, IndexStats, Store};
+use crate::watcher::{UsageUpdate, USAGE_UPDATED_EVENT};
+
+/// Event emitted while the store is indexed (payload: `IndexStatus`).
+pub const INDEX_PROGRESS_EVENT: &str = "index-progress";
+
+/// Shortest time between two progress events of one run.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Reasons of an index run.
+pub const STARTUP: &str = "startup";
+pub const WATCHER: &str = "watcher";
+pub const MANUAL: &str = "manual";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexState {
+    Idle,
+    Indexing,
+}
+
+/// State of the native store's indexer, as returned by `get_index_status`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IndexStatus {
+    pub state: IndexState,
+    /// What started the running (or last) run: `STARTUP`, `WATCHER` or `MANUAL`
+    pub reason: Option<&'static str>,
+    #[serde(flatten)]
+    pub progress: IndexProgress,
+    /// Log files done of all log files, 0-100
+    pub percent: f64,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    /// Outcome of the last finished run
+    pub last_stats: Option<IndexStats>,
+    pub last_error: Option<String>,
+}
+
+static STATUS: Mutex<IndexStatus> = Mutex::new(IndexStatus {
+    state: IndexState::Idle,
+    reason: None,
+    progress: IndexProgress { files_done: 0, files_total: 0, bytes_read: 0 },
+    percent: 0.0,
+    started_at: None,
+    finished_at: None,
+    last_stats: None,
+    last_error: None,
+});
+
+/// Held for the length of a run.
+static RUNNING: Mutex<()> = Mutex::new(());
+
+/// The current status.
+pub fn status() -> IndexStatus {
+    STATUS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn update(change: impl FnOnce(&mut IndexStatus)) -> IndexStatus {
+    let mut status = STATUS.lock().unwrap_or_else(|e| e.into_inner());
+    change(&mut status);
+    status.clone()
+}
+
+fn percent(progress: IndexProgress) -> f64 {
+    if progress.files_total == 0 {
+        return 100.0;
+    }
+    (progress.files_done as f64 / progress.files_total as f64 * 1000.0).round() / 10.0
+}
+
+/// Index `config_dirs` into `store` (see `Store::index`), tracking the run in
+/// the status and passing it to `emit` as it changes.
+pub fn index(
+    store: &mut Store,
+    config_dirs: &[PathBuf],
+    full: bool,
+    reason: &'static str,
+    mut emit: impl FnMut(&IndexStatus),
+) -> Result<IndexStats, String> {
+    let _running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    emit(&update(|status| {
+        status.state = IndexState::Indexing;
+        status.reason = Some(reason);
+        status.progress = IndexProgress::default();
+        status.percent = 0.0;
+        status.started_at = Some(Local::now().to_rfc3339());
+        status.finished_at = None;
+    }));
+
+    let mut last_emit = Instant::now();
+    let result = store.index_with_progress(config_dirs, full, |progress| {
+        let status = update(|status| {
+            status.progress = progress;
+            status.percent = percent(progress);
+        });
+        if last_emit.elapsed() >= PROGRESS_INTERVAL {
+            last_emit = Instant::now();
+            emit(&status);
+        }
+    });
+
+    emit(&update(|status| {
+        status.state = IndexState::Idle;
+        status.finished_at = Some(Local::now().to_rfc3339());
+        match &result {
+            Ok(stats) => {
+                status.percent = 100.0;
+                status.last_stats = Some(stats.clone());
+                status.last_error = None;
+            }
+            Err(e) => status.last_error = Some(e.clone()),
+        }
+    }));
+    result
+}
+
+/// Emitter of `index` that sends `index-progress` events.
+pub fn emitter(app: &AppHandle) -> impl FnMut(&IndexStatus) + '_ {
+    move |status| {
+        if let Err(e) = app.emit(INDEX_PROGRESS_EVENT, status) {
+            log::warn!("Failed to emit {}: {}", INDEX_PROGRESS_EVENT, e);
+        }
+    }
+}
+
+/// Body of the `store-indexer` task: index what changed while the app was
+/// closed and emit `usage-updated` if anything was read.
+pub fn run(app: AppHandle) {
+    let result = store::user_config_dirs()
+        .and_then(|dirs| index(&mut Store::open_default()?, &dirs, false, STARTUP, emitter(&app)));
+    match result {
+        Ok(stats) if stats.changed_files > 0 => {
+            log::info!(
+                "Caught up the native store: {} new messages from {} changed files in {}s",
+                stats.new_messages,
+                stats.changed_files,
+                stats.seconds
+            );
+            if let Err(e) = app.emit(USAGE_UPDATED_EVENT, UsageUpdate { files: Vec::new(), stats }) {
+                log::warn!("Failed to emit {}: {}", USAGE_UPDATED_EVENT, e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("Indexing the native store failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_index_reports_status() {
+        let dir = std::env::temp_dir().join(format!("cc-indexer-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let project = dir.join(".claude").join("projects").join("-home-me-app");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("s1.jsonl"), "not json\n").unwrap();
+        fs::write(project.join("s2.jsonl"), "").unwrap();
+        let mut store = Store::open(&dir.join("db").join(store::STORE_FILE)).unwrap();
+
+        let mut emitted = Vec::new();
+        let dirs = [dir.join(".claude")];
+        let stats = index(&mut store, &dirs, false, MANUAL, |status| emitted.push(status.clone())).unwrap();
+        assert_eq!(stats.files, 2);
+        let (first, last) = (&emitted[0], emitted.last().unwrap());
+        assert_eq!((first.state, first.percent, first.reason), (IndexState::Indexing, 0.0, Some(MANUAL)));
+        assert_eq!((last.state, last.percent), (IndexState::Idle, 100.0));
+        assert_eq!((last.progress.files_done, last.progress.files_total), (2, 2));
+        assert_eq!(last.last_stats.as_ref(), Some(&stats));
+        assert_eq!(&status(), last);
+        assert_eq!(percent(IndexProgress { files_done: 1, files_total: 3, bytes_read: 0 }), 33.3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,839 @@
+/// Native ingestion of Claude Code session logs
+///
+/// Reads the session logs of every Claude config dir
+/// (`<config dir>/projects/<project id>/**/*.jsonl`) directly instead of
+/// going through the Python backend and its database. Messages are
+/// deduplicated by `message.id:requestId` like the Python ingestion and
+/// summed per project and per config dir; unparseable lines and invalid
+/// UTF-8 are skipped. Costs are the `costUSD` of each line, else its tokens
+/// priced like the Python backend does (`pricing`), and nothing is written.
+///
+/// `answer` builds the `projects` and `usage-accounts` payloads from a scan;
+/// `backend::NativeBackend` uses it when the `native_ingestion` flag is on or
+/// no Python backend can start.
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
+use chrono::{DateTime, Local};
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::backend::ApiRequest;
+use crate::error::CommandError;
+use crate::features::{self, FlagStore};
+use crate::pricing::PriceTable;
+use crate::settings::{db_dir, home_dir};
+use crate::usage_accounts;
+
+/// Feature flag routing `NATIVE_SUBCOMMANDS` to this module.
+pub const NATIVE_FLAG: &str = "native_ingestion";
+
+/// Subcommands `answer` can build without Python.
+pub const NATIVE_SUBCOMMANDS: &[&str] = &["projects", "usage-accounts"];
+
+/// Caps the threads reading session logs (`worker_count`); 1 reads on the calling thread.
+pub const WORKERS_ENV: &str = "COMMAND_CENTER_INGEST_WORKERS";
+
+/// Threads used without `WORKERS_ENV`, at most.
+const MAX_AUTO_WORKERS: usize = 8;
+
+pub const PROJECTS_FILE: &str = "command-center-projects.json";
+pub const ACCOUNTS_FILE: &str = "command-center-accounts.json";
+pub const CONFIG_BINDINGS_FILE: &str = "command-center-claude-configs.json";
+
+/// Project list orders of `projects --sort`; pinned projects always come first.
+pub const PROJECT_SORTS: &[&str] = &["manual", "last_activity", "cost_this_month", "name"];
+
+/// Colors of projects without a custom color, picked like the Python API does
+/// (first SHA-256 byte of the project ID), so both paths agree.
+const PROJECT_PALETTE: [&str; 8] = [
+    "#d97757", "#6a9bcc", "#788c5d", "#c46686", "#b8860b", "#5e7ce2", "#4c9f8f", "#8f6bb3",
+];
+
+/// cc_usage snapshot fields of a `usage-accounts` row; logs don't have them.
+const SNAPSHOT_FIELDS: &[&str] = &[
+    "captured_at_local",
+    "current_session_used_pct",
+    "current_session_used_raw",
+    "current_session_resets_local",
+    "current_session_resets_raw",
+    "current_week_used_pct",
+    "current_week_used_raw",
+    "current_week_resets_local",
+    "current_week_resets_raw",
+];
+
+/// The fields of a log line used here; everything else is ignored.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LogLine {
+    timestamp: Option<String>,
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
+    #[serde(rename = "requestId")]
+    request_id: Option<String>,
+    #[serde(rename = "costUSD")]
+    cost_usd: Option<f64>,
+    message: Option<LogMessage>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LogMessage {
+    id: Option<String>,
+    model: Option<String>,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Usage {
+    input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+    cache_read_input_tokens: Option<u64>,
+    cache_creation_input_tokens: Option<u64>,
+}
+
+/// A message of a log line, with what ingestion keeps of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// Deduplication key `message.id:requestId`
+    pub key: String,
+    pub session_id: Option<String>,
+    /// Timestamp as logged (UTC)
+    pub timestamp: String,
+    pub local: DateTime<Local>,
+    pub model: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub cost_usd: Option<f64>,
+}
+
+impl LogEntry {
+    pub fn tokens(&self) -> u64 {
+        [self.input_tokens, self.output_tokens, self.cache_read_tokens, self.cache_write_tokens]
+            .iter()
+            .fold(0u64, |total, tokens| total.saturating_add(*tokens))
+    }
+}
+
+/// Parse one log line (without its newline). Lines that are not JSON records
+/// fail; records without a message ID, request ID or valid timestamp (user
+/// turns, summaries, ...) are `None`.
+pub fn parse_line(line: &str) -> Result<Option<LogEntry>, serde_json::Error> {
+    let record: LogLine = serde_json::from_str(line)?;
+    let Some(message) = record.message else { return Ok(None) };
+    let (Some(message_id), Some(request_id)) = (message.id, record.request_id) else {
+        return Ok(None);
+    };
+    let Some(timestamp) = record.timestamp else { return Ok(None) };
+    let Ok(at) = DateTime::parse_from_rfc3339(&timestamp) else { return Ok(None) };
+    if message_id.is_empty() || request_id.is_empty() {
+        return Ok(None);
+    }
+    let usage = message.usage.unwrap_or_default();
+    Ok(Some(LogEntry {
+        key: format!("{}:{}", message_id, request_id),
+        session_id: record.session_id,
+        timestamp,
+        local: at.with_timezone(&Local),
+        model: message.model,
+        input_tokens: usage.input_tokens.unwrap_or(0),
+        output_tokens: usage.output_tokens.unwrap_or(0),
+        cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+        cache_write_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+        cost_usd: record.cost_usd,
+    }))
+}
+
+/// Usage summed over deduplicated messages.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Totals {
+    pub messages: u64,
+    pub tokens: u64,
+    pub cost: f64,
+    pub sessions: BTreeSet<String>,
+    pub first: Option<DateTime<Local>>,
+    pub last: Option<DateTime<Local>>,
+}
+
+impl Totals {
+    fn add(&mut self, at: DateTime<Local>, session_id: Option<&str>, tokens: u64, cost: f64) {
+        self.messages += 1;
+        self.tokens = self.tokens.saturating_add(tokens);
+        self.cost += cost;
+        if let Some(session_id) = session_id.filter(|id| !self.sessions.contains(*id)) {
+            self.sessions.insert(session_id.to_string());
+        }
+        self.first = Some(self.first.map_or(at, |first| first.min(at)));
+        self.last = Some(self.last.map_or(at, |last| last.max(at)));
+    }
+}
+
+/// Usage of one project across config dirs.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ProjectSummary {
+    pub totals: Totals,
+    /// Cost per local month ("YYYY-MM")
+    pub cost_by_month: BTreeMap<String, f64>,
+}
+
+/// Usage of one config dir.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConfigDirSummary {
+    pub session_files: u64,
+    pub totals: Totals,
+}
+
+/// Result of `scan`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Scan {
+    /// By project ID (directory name under `projects/`)
+    pub projects: BTreeMap<String, ProjectSummary>,
+    /// By config dir, for every scanned dir (also those without logs)
+    pub config_dirs: BTreeMap<PathBuf, ConfigDirSummary>,
+    pub files: u64,
+    /// Lines that are not JSON records (corrupted or truncated)
+    pub skipped_lines: u64,
+}
+
+/// Whether `NATIVE_SUBCOMMANDS` are answered here instead of by Python.
+pub fn enabled() -> bool {
+    features::find(NATIVE_FLAG).is_some_and(|flag| FlagStore::load().resolve(flag).0)
+}
+
+fn normalize(home: &Path, path: &str) -> PathBuf {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => PathBuf::from(path),
+    };
+    match std::env::current_dir() {
+        Ok(cwd) if path.is_relative() => cwd.join(path),
+        _ => path,
+    }
+}
+
+/// Claude config dirs that may hold session logs, like the Python
+/// `get_claude_dirs`: `~/.claude` and `~/.config/claude` (always listed),
+/// then `env_dir` (`CLAUDE_CONFIG_DIR`), `~/.claude-*`, `~/.config/claude-*`
+/// and bound dirs that have a `projects/` directory.
+pub fn config_dirs(home: &Path, env_dir: Option<&str>, bindings: &Value) -> Vec<PathBuf> {
+    let mut dirs = vec![home.join(".claude"), home.join(".config").join("claude")];
+
+    let mut candidates: Vec<PathBuf> = env_dir.map(|dir| normalize(home, dir)).into_iter().collect();
+    for (parent, prefix) in [(home.to_path_buf(), ".claude-"), (home.join(".config"), "claude-")] {
+        let mut matches: Vec<PathBuf> = fs::read_dir(&parent)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+            .map(|entry| entry.path())
+            .collect();
+        matches.sort();
+        candidates.extend(matches);
+    }
+    if let Some(bound) = bindings.as_object() {
+        candidates.extend(bound.keys().map(|dir| normalize(home, dir)));
+    }
+
+    for candidate in candidates {
+        if !dirs.contains(&candidate) && candidate.join("projects").is_dir() {
+            dirs.push(candidate);
+        }
+    }
+    dirs
+}
+
+/// Session log files under `dir`, recursively (symlinked directories are not followed).
+pub fn jsonl_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() => jsonl_files(&path, files),
+            Ok(_) if path.extension().is_some_and(|ext| ext == "jsonl") => files.push(path),
+            _ => {}
+        }
+    }
+}
+
+/// Threads reading session logs: `WORKERS_ENV`, else (unset or 0) the CPU
+/// count up to `MAX_AUTO_WORKERS`.
+pub fn worker_count() -> usize {
+    match std::env::var(WORKERS_ENV).ok().and_then(|value| value.trim().parse::<usize>().ok()) {
+        Some(workers) if workers > 0 => workers,
+        _ => thread::available_parallelism().map_or(1, |count| count.get()).min(MAX_AUTO_WORKERS),
+    }
+}
+
+/// Run `parse` over `items` with `par_iter` on a rayon pool of up to
+/// `workers` threads and pass the results to `merge` on this thread, in item
+/// order, as they arrive.
+pub fn parse_in_order<T: Sync, R: Send>(
+    items: &[T],
+    workers: usize,
+    parse: impl Fn(&T) -> R + Sync,
+    mut merge: impl FnMut(R),
+) {
+    let workers = workers.clamp(1, items.len().max(1));
+    let pool = match workers {
+        1 => None,
+        _ => ThreadPoolBuilder::new()
+            .num_threads(workers)
+            .thread_name(|index| format!("ingest-{}", index))
+            .build()
+            .map_err(|e| log::warn!("Reading session logs on one thread: {}", e))
+            .ok(),
+    };
+    let Some(pool) = pool else {
+        items.iter().for_each(|item| merge(parse(item)));
+        return;
+    };
+    let (sender, receiver) = mpsc::channel();
+    pool.in_place_scope(|scope| {
+        scope.spawn(|_| {
+            items.par_iter().enumerate().for_each_with(sender, |sender, (index, item)| {
+                let _ = sender.send((index, parse(item)));
+            });
+        });
+        // Results finishing early wait for those before them
+        let mut pending = BTreeMap::new();
+        let mut wanted = 0;
+        for (index, result) in receiver {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&wanted) {
+                merge(result);
+                wanted += 1;
+            }
+        }
+    });
+}
+
+/// Consecutive log files of the same project, as (project ID, paths).
+pub fn project_groups(files: Vec<(String, PathBuf)>) -> Vec<(String, Vec<PathBuf>)> {
+    let mut groups: Vec<(String, Vec<PathBuf>)> = Vec::new();
+    for (project_id, path) in files {
+        match groups.last_mut() {
+            Some((last, paths)) if *last == project_id => paths.push(path),
+            _ => groups.push((project_id, vec![path])),
+        }
+    }
+    groups
+}
+
+/// Parse the session logs of `dirs` (each a config dir), pricing messages
+/// logged without a cost from `prices`. Project directories are read on up
+/// to `workers` threads and counted in path order, so the first file of a
+/// duplicated message still wins.
+pub fn scan(dirs: &[PathBuf], prices: &PriceTable, workers: usize) -> Scan {
+    let mut scan = Scan::default();
+    let mut seen: HashSet<String> = HashSet::new();
+
+    for config_dir in dirs {
+        let mut dir_summary = ConfigDirSummary::default();
+        let groups = project_groups(log_files(config_dir));
+        let read = |(project_id, paths): &(String, Vec<PathBuf>)| {
+            let logs: Vec<_> = paths.iter().map(|path| (path.clone(), read_log(path, prices))).collect();
+            (project_id.clone(), logs)
+        };
+        parse_in_order(&groups, workers, read, |(project_id, logs)| {
+            for (path, log) in logs {
+                dir_summary.session_files += 1;
+                scan.files += 1;
+                match log {
+                    Ok(log) => scan.add(&project_id, log, &mut seen, &mut dir_summary),
+                    Err(e) => log::warn!("Skipping session log {}: {}", path.display(), e),
+                }
+            }
+        });
+        scan.config_dirs.insert(config_dir.clone(), dir_summary);
+    }
+    scan
+}
+
+/// Session log files of a config dir with their project ID (the directory
+/// under `projects/`), in path order.
+pub fn log_files(config_dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut project_dirs: Vec<_> = fs::read_dir(config_dir.join("projects"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .collect();
+    project_dirs.sort_by_key(|entry| entry.file_name());
+
+    let mut files = Vec::new();
+    for project_dir in project_dirs {
+        let project_id = project_dir.file_name().to_string_lossy().into_owned();
+        let mut paths = Vec::new();
+        jsonl_files(&project_dir.path(), &mut paths);
+        files.extend(paths.into_iter().map(|path| (project_id.clone(), path)));
+    }
+    files
+}
+
+/// Messages of one log file with their costs.
+#[derive(Debug, Default)]
+pub struct ParsedLog {
+    pub entries: Vec<(LogEntry, f64)>,
+    /// Lines that are not JSON records
+    pub skipped_lines: u64,
+}
+
+fn read_log(path: &Path, prices: &PriceTable) -> std::io::Result<ParsedLog> {
+    parse_log(BufReader::new(File::open(path)?), prices)
+}
+
+/// Parse the lines of a session log, pricing its messages. Lines that are
+/// not valid UTF-8 are read lossily.
+pub fn parse_log(mut reader: impl BufRead, prices: &PriceTable) -> std::io::Result<ParsedLog> {
+    let mut parsed = ParsedLog::default();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            return Ok(parsed);
+        }
+        let text = String::from_utf8_lossy(&buf);
+        let line = text.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match parse_line(line) {
+            Ok(Some(entry)) => {
+                let cost = prices.cost(&entry).unwrap_or(0.0);
+                parsed.entries.push((entry, cost));
+            }
+            Ok(None) => {}
+            Err(_) => parsed.skipped_lines += 1,
+        }
+    }
+}
+
+impl Scan {
+    /// Count the messages of a log of `project_id` not seen before.
+    pub fn add(
+        &mut self,
+        project_id: &str,
+        log: ParsedLog,
+        seen: &mut HashSet<String>,
+        dir_summary: &mut ConfigDirSummary,
+    ) {
+        self.skipped_lines += log.skipped_lines;
+        for (entry, cost) in log.entries {
+            if seen.contains(&entry.key) {
+                continue;
+            }
+            let (at, tokens) = (entry.local, entry.tokens());
+            let session_id = entry.session_id.as_deref();
+            let project = self.projects.entry(project_id.to_string()).or_default();
+            project.totals.add(at, session_id, tokens, cost);
+            *project.cost_by_month.entry(at.format("%Y-%m").to_string()).or_default() += cost;
+            dir_summary.totals.add(at, session_id, tokens, cost);
+            seen.insert(entry.key);
+        }
+    }
+}
+
+fn round_cost(cost: f64) -> f64 {
+    (cost * 10_000.0).round() / 10_000.0
+}
+
+fn local_iso(at: Option<DateTime<Local>>) -> Value {
+    at.map_or(Value::Null, |at| Value::from(at.to_rfc3339()))
+}
+
+/// Display color of a project: its custom color, else a stable palette color.
+pub fn project_color(project_id: &str, metadata: &Value) -> String {
+    match metadata["color"].as_str() {
+        Some(color) if !color.is_empty() => color.to_string(),
+        _ => PROJECT_PALETTE[Sha256::digest(project_id.as_bytes())[0] as usize % PROJECT_PALETTE.len()].to_string(),
+    }
+}
+
+/// `/home/me/app` for `-home-me-app` (null for IDs that are not encoded paths).
+fn absolute_path(project_id: &str) -> Value {
+    match project_id.strip_prefix('-') {
+        Some(rest) if !rest.is_empty() => Value::from(format!("/{}", rest.replace('-', "/"))),
+        _ => Value::Null,
+    }
+}
+
+/// `projects` payload: the projects of `metadata` (`command-center-projects.json`)
+/// and those found in the logs, with first/last activity from the logs,
+/// sorted like the Python API. `month` ("YYYY-MM") is the month of
+/// `cost_this_month`, only added with that sort.
+pub fn projects_response(scan: &Scan, metadata: &Value, sort: &str, month: &str) -> Result<Value, String> {
+    if !PROJECT_SORTS.contains(&sort) {
+        return Err(format!("Invalid sort: {} (expected {})", sort, PROJECT_SORTS.join(", ")));
+    }
+    let stored = metadata.as_object().cloned().unwrap_or_default();
+    let ids: BTreeSet<&String> = stored.keys().chain(scan.projects.keys()).collect();
+
+    let mut projects: Vec<Map<String, Value>> = ids
+        .into_iter()
+        .map(|project_id| {
+            let meta = stored.get(project_id).cloned().unwrap_or(Value::Null);
+            let mut project = Map::new();
+            project.insert("project_id".into(), Value::from(project_id.as_str()));
+            project.insert("name".into(), Value::from(""));
+            project.insert("description".into(), Value::from(""));
+            project.insert("absolute_path".into(), absolute_path(project_id));
+            project.insert("first_seen".into(), Value::Null);
+            project.insert("last_seen".into(), Value::Null);
+            if let Some(fields) = meta.as_object() {
+                project.extend(fields.clone());
+            }
+            project.entry("visible").or_insert(Value::Bool(true));
+            if let Some(summary) = scan.projects.get(project_id) {
+                project.insert("first_seen".into(), local_iso(summary.totals.first));
+                project.insert("last_seen".into(), local_iso(summary.totals.last));
+            }
+            project.insert("display_color".into(), Value::from(project_color(project_id, &meta)));
+            if sort == "cost_this_month" {
+                let cost = scan.projects.get(project_id).and_then(|p| p.cost_by_month.get(month)).copied();
+                project.insert("cost_this_month".into(), Value::from(round_cost(cost.unwrap_or(0.0))));
+            }
+            project
+        })
+        .collect();
+
+    // Stable sorts: last_seen first, then the requested key, then pinned
+    let text = |project: &Map<String, Value>, key: &str| project.get(key).and_then(Value::as_str).unwrap_or("").to_string();
+    projects.sort_by_key(|project| std::cmp::Reverse(text(project, "last_seen")));
+    match sort {
+        "manual" => projects.sort_by_key(|project| match project.get("sort_order").and_then(Value::as_i64) {
+            Some(order) => (false, order),
+            None => (true, 0),
+        }),
+        "cost_this_month" => projects.sort_by(|a, b| {
+            let cost = |project: &Map<String, Value>| project["cost_this_month"].as_f64().unwrap_or(0.0);
+            cost(b).total_cmp(&cost(a))
+        }),
+        "name" => projects.sort_by_key(|project| {
+            let name = text(project, "name");
+            let name = if name.is_empty() { text(project, "project_id") } else { name };
+            name.to_lowercase()
+        }),
+        _ => {}
+    }
+    projects.sort_by_key(|project| !project.get("pinned").and_then(Value::as_bool).unwrap_or(false));
+
+    Ok(json!({"projects": projects, "sort": sort, "source": "native"}))
+}
+
+/// Email Claude Code stored for the login of `config_dir`
+/// (`.claude.json`, or `~/.claude.json` next to the default dir).
+pub fn logged_in_email(home: &Path, config_dir: &Path) -> Option<String> {
+    let mut candidates = vec![config_dir.join(".claude.json")];
+    if config_dir == home.join(".claude") {
+        candidates.push(home.join(".claude.json"));
+    }
+    candidates.into_iter().find_map(|path| {
+        let data: Value = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+        data["oauthAccount"]["emailAddress"].as_str().filter(|email| !email.is_empty()).map(str::to_string)
+    })
+}
+
+/// `usage-accounts` payload: one row per account email of the config dirs
+/// (bound in `bindings`, else the logged-in email) with its metadata from
+/// `metadata` (`command-center-accounts.json`) and usage summed over its
+/// dirs. The cc_usage snapshot fields are null. Sorted by email.
+pub fn usage_accounts_response(scan: &Scan, emails: &BTreeMap<PathBuf, String>, metadata: &Value) -> Value {
+    let mut accounts: BTreeMap<&str, (Vec<&Path>, u64, Totals)> = BTreeMap::new();
+    for (config_dir, summary) in &scan.config_dirs {
+        let Some(email) = emails.get(config_dir) else { continue };
+        let (dirs, session_files, totals) = accounts.entry(email.as_str()).or_default();
+        dirs.push(config_dir);
+        *session_files += summary.session_files;
+        totals.messages += summary.totals.messages;
+        totals.tokens = totals.tokens.saturating_add(summary.totals.tokens);
+        totals.cost += summary.totals.cost;
+        totals.sessions.extend(summary.totals.sessions.iter().cloned());
+        totals.first = [totals.first, summary.totals.first].into_iter().flatten().min();
+        totals.last = [totals.last, summary.totals.last].into_iter().flatten().max();
+    }
+
+    let rows: Vec<Value> = accounts
+        .into_iter()
+        .map(|(email, (dirs, session_files, totals))| {
+            let mut row = Map::new();
+            row.insert("email".into(), Value::from(email));
+            for key in SNAPSHOT_FIELDS {
+                row.insert(key.to_string(), Value::Null);
+            }
+            let mut row = usage_accounts::with_metadata(row, email, metadata);
+            row.insert("config_dirs".into(), json!(dirs.iter().map(|dir| dir.display().to_string()).collect::<Vec<_>>()));
+            row.insert("session_files".into(), Value::from(session_files));
+            row.insert("messages".into(), Value::from(totals.messages));
+            row.insert("sessions".into(), Value::from(totals.sessions.len()));
+            row.insert("tokens".into(), Value::from(totals.tokens));
+            row.insert("cost".into(), Value::from(round_cost(totals.cost)));
+            row.insert("first_activity".into(), local_iso(totals.first));
+            row.insert("last_activity".into(), local_iso(totals.last));
+            Value::Object(row)
+        })
+        .collect();
+
+    json!({"accounts": rows, "source": "native"})
+}
+
+/// Contents of a JSON file, or null if it is missing or invalid.
+pub fn read_json(path: &Path) -> Value {
+    fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or(Value::Null)
+}
+
+/// Answer a request for one of `NATIVE_SUBCOMMANDS` from the session logs
+/// of this user's config dirs.
+pub fn answer(request: &ApiRequest) -> Result<Value, CommandError> {
+    let home = home_dir().map_err(CommandError::internal)?;
+    let db = db_dir().map_err(CommandError::internal)?;
+    let bindings = read_json(&db.join(CONFIG_BINDINGS_FILE));
+    let env_dir = std::env::var("CLAUDE_CONFIG_DIR").ok().filter(|dir| !dir.is_empty());
+    let dirs = config_dirs(&home, env_dir.as_deref(), &bindings);
+    let scan = scan(&dirs, &PriceTable::load(&db), worker_count());
+    log::debug!(
+        "[{}] Scanned {} session logs in {} config dirs ({} lines skipped)",
+        request.request_id,
+        scan.files,
+        dirs.len(),
+        scan.skipped_lines
+    );
+
+    match request.subcommand() {
+        "projects" => {
+            let sort = request
+                .args
+                .iter()
+                .find_map(|arg| arg.strip_prefix("--sort="))
+                .unwrap_or("manual");
+            let month = Local::now().format("%Y-%m").to_string();
+            projects_response(&scan, &read_json(&db.join(PROJECTS_FILE)), sort, &month)
+                .map_err(CommandError::invalid_argument)
+        }
+        "usage-accounts" => {
+            let emails: BTreeMap<PathBuf, String> = dirs
+                .iter()
+                .filter_map(|dir| {
+                    let bound = bindings[dir.to_string_lossy().as_ref()]["account_email"]
+                        .as_str()
+                        .filter(|email| !email.is_empty())
+                        .map(str::to_string);
+                    bound.or_else(|| logged_in_email(&home, dir)).map(|email| (dir.clone(), email))
+                })
+                .collect();
+            Ok(usage_accounts_response(&scan, &emails, &read_json(&db.join(ACCOUNTS_FILE))))
+        }
+        other => Err(CommandError::not_found(format!("`{}` has no native implementation", other))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TempHome(PathBuf);
+
+    impl TempHome {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("cc-ingest-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn write(&self, relative: &str, content: &[u8]) -> PathBuf {
+            let path = self.0.join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempHome {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn line(message_id: &str, request_id: &str, session_id: &str, timestamp: &str, cost: f64) -> String {
+        json!({
+            "type": "assistant",
+            "sessionId": session_id,
+            "requestId": request_id,
+            "timestamp": timestamp,
+            "costUSD": cost,
+            "message": {
+                "id": message_id,
+                "model": "claude-sonnet-4-5-20250929",
+                "content": [{"type": "text", "text": "hi"}],
+                "usage": {"input_tokens": 10, "output_tokens": 20, "cache_read_input_tokens": 30, "cache_creation_input_tokens": null},
+            },
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_scan_deduplicates_and_skips_bad_lines() {
+        let home = TempHome::new("scan");
+        let log = [
+            line("m1", "r1", "s1", "2025-03-15T10:00:00.000Z", 0.5),
+            line("m1", "r1", "s1", "2025-03-15T10:00:00.000Z", 0.5),
+            line("m2", "r2", "s1", "2025-04-15T10:00:00Z", 0.25),
+            "{\"type\": \"user\", \"message\": {\"role\": \"user\", \"content\": \"hi\"}}".to_string(),
+            "{truncated".to_string(),
+        ]
+        .join("\n");
+        home.write(".claude/projects/-home-me-app/s1.jsonl", log.as_bytes());
+        // A resumed session repeats a message in another file; subagent logs sit in subdirectories
+        let mut nested = line("m3", "r3", "s2", "2025-04-16T10:00:00Z", 1.0).into_bytes();
+        nested.extend_from_slice(b"\n\xff\xfe\n");
+        nested.extend_from_slice(line("m2", "r2", "s2", "2025-04-15T10:00:00Z", 0.25).as_bytes());
+        home.write(".claude/projects/-home-me-app/s2/subagents/agent.jsonl", &nested);
+        home.write(".claude/projects/-home-me-app/notes.txt", b"not a log");
+
+        let dirs = config_dirs(&home.0, None, &Value::Null);
+        let scan = scan(&dirs, &PriceTable::default(), 1);
+
+        assert_eq!(scan.files, 2);
+        assert_eq!(scan.skipped_lines, 2);
+        let app = &scan.projects["-home-me-app"];
+        assert_eq!(app.totals.messages, 3);
+        assert_eq!(app.totals.tokens, 180);
+        assert_eq!(app.totals.sessions.len(), 2);
+        assert!((app.totals.cost - 1.75).abs() < 1e-9);
+        assert_eq!(app.cost_by_month.values().sum::<f64>(), 1.75);
+        let default_dir = &scan.config_dirs[&home.0.join(".claude")];
+        assert_eq!((default_dir.session_files, default_dir.totals.messages), (2, 3));
+        assert_eq!(scan.config_dirs[&home.0.join(".config").join("claude")], ConfigDirSummary::default());
+    }
+
+    #[test]
+    fn test_parallel_scan_matches_one_thread() {
+        let home = TempHome::new("parallel");
+        for project in 0..6 {
+            let log = (0..20)
+                .map(|n| {
+                    let (id, session) = (format!("m{}-{}", project, n), format!("s{}", project));
+                    line(&id, "r", &session, "2025-04-15T10:00:00Z", 0.5)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            home.write(&format!(".claude/projects/-p{}/s.jsonl", project), log.as_bytes());
+        }
+        // Copied into a later project: counted for the first one only
+        home.write(".claude/projects/-p5/copy.jsonl", line("m0-0", "r", "s0", "2025-04-15T10:00:00Z", 0.5).as_bytes());
+
+        let dirs = config_dirs(&home.0, None, &Value::Null);
+        let serial = scan(&dirs, &PriceTable::default(), 1);
+        assert_eq!(scan(&dirs, &PriceTable::default(), 4), serial);
+        assert_eq!((serial.projects["-p0"].totals.messages, serial.projects["-p5"].totals.messages), (20, 20));
+        assert_eq!(serial.files, 7);
+    }
+
+    #[test]
+    fn test_config_dirs() {
+        let home = TempHome::new("dirs");
+        home.write(".claude-work/projects/-p/s.jsonl", b"");
+        home.write(".claude-empty/settings.json", b"{}");
+        home.write("elsewhere/projects/-p/s.jsonl", b"");
+        home.write("env/projects/-p/s.jsonl", b"");
+        let mut bindings = json!({});
+        bindings[home.0.join("elsewhere").to_str().unwrap()] = json!({"account_email": "a@x.com"});
+
+        let dirs = config_dirs(&home.0, Some(home.0.join("env").to_str().unwrap()), &bindings);
+
+        let expected: Vec<PathBuf> = [".claude", ".config/claude", "env", ".claude-work", "elsewhere"]
+            .iter()
+            .map(|dir| home.0.join(dir))
+            .collect();
+        assert_eq!(dirs, expected);
+    }
+
+    #[test]
+    fn test_projects_response() {
+        let home = TempHome::new("projects");
+        home.write(".claude/projects/-home-me-old/s.jsonl", line("m1", "r1", "s1", "2025-01-15T12:00:00Z", 2.0).as_bytes());
+        home.write(".claude/projects/-home-me-new/s.jsonl", line("m2", "r2", "s2", "2025-03-15T12:00:00Z", 1.0).as_bytes());
+        let scan = scan(&config_dirs(&home.0, None, &Value::Null), &PriceTable::default(), 1);
+        let metadata = json!({
+            "-home-me-old": {"name": "Old", "description": "", "visible": true, "color": "#123456", "sort_order": 0},
+            "-home-me-gone": {"name": "gone", "description": "", "visible": false, "pinned": true},
+        });
+
+        let ids = |response: &Value| -> Vec<String> {
+            response["projects"].as_array().unwrap().iter().map(|p| p["project_id"].as_str().unwrap().to_string()).collect()
+        };
+        let manual = projects_response(&scan, &metadata, "manual", "2025-01").unwrap();
+        assert_eq!(ids(&manual), ["-home-me-gone", "-home-me-old", "-home-me-new"]);
+        assert_eq!(manual["source"], "native");
+        let new = &manual["projects"][2];
+        assert_eq!(new["absolute_path"], "/home/me/new");
+        assert_eq!(new["visible"], true);
+        assert!(new["last_seen"].as_str().unwrap().starts_with("2025-03"));
+        assert_eq!(manual["projects"][1]["display_color"], "#123456");
+        assert!(PROJECT_PALETTE.contains(&new["display_color"].as_str().unwrap()));
+
+        let by_activity = projects_response(&scan, &metadata, "last_activity", "2025-01").unwrap();
+        assert_eq!(ids(&by_activity), ["-home-me-gone", "-home-me-new", "-home-me-old"]);
+        let by_cost = projects_response(&scan, &metadata, "cost_this_month", "2025-01").unwrap();
+        assert_eq!(ids(&by_cost), ["-home-me-gone", "-home-me-old", "-home-me-new"]);
+        assert_eq!(by_cost["projects"][1]["cost_this_month"], 2.0);
+        assert_eq!(by_cost["projects"][2]["cost_this_month"], 0.0);
+        let by_name = projects_response(&scan, &metadata, "name", "2025-01").unwrap();
+        assert_eq!(ids(&by_name), ["-home-me-gone", "-home-me-new", "-home-me-old"]);
+        assert!(projects_response(&scan, &metadata, "size", "2025-01").unwrap_err().contains("Invalid sort: size"));
+    }
+
+    #[test]
+    fn test_project_color_matches_python_palette() {
+        // hashlib.sha256(b"-home-xai-DEV-command-center").digest()[0] % 8
+        let expected = PROJECT_PALETTE[Sha256::digest(b"-home-xai-DEV-command-center")[0] as usize % 8];
+        assert_eq!(project_color("-home-xai-DEV-command-center", &Value::Null), expected);
+        assert_eq!(project_color("-p", &json!({"color": ""})), PROJECT_PALETTE[Sha256::digest(b"-p")[0] as usize % 8]);
+    }
+
+    #[test]
+    fn test_usage_accounts_response() {
+        let home = TempHome::new("accounts");
+        home.write(".claude/projects/-a/s.jsonl", line("m1", "r1", "s1", "2025-01-15T12:00:00Z", 1.0).as_bytes());
+        home.write(".claude-work/projects/-b/s.jsonl", line("m2", "r2", "s2", "2025-02-15T12:00:00Z", 0.5).as_bytes());
+        home.write(".claude-other/projects/-c/s.jsonl", line("m3", "r3", "s3", "2025-03-15T12:00:00Z", 4.0).as_bytes());
+        home.write(".claude.json", br#"{"oauthAccount": {"emailAddress": "me@x.com"}}"#);
+        home.write(".claude-work/.claude.json", br#"{"oauthAccount": {"emailAddress": "work@x.com"}}"#);
+        let dirs = config_dirs(&home.0, None, &Value::Null);
+        let emails: BTreeMap<PathBuf, String> =
+            dirs.iter().filter_map(|dir| logged_in_email(&home.0, dir).map(|email| (dir.clone(), email))).collect();
+        assert_eq!(emails.len(), 2);
+
+        let mut emails = emails;
+        emails.insert(home.0.join(".claude-work"), "me@x.com".to_string());
+        let metadata = json!({"me@x.com": {"label": "Me", "color": "", "detected_plan_type": "max_5x"}});
+        let response = usage_accounts_response(&scan(&dirs, &PriceTable::default(), 1), &emails, &metadata);
+
+        let accounts = response["accounts"].as_array().unwrap();
+        assert_eq!(accounts.len(), 1);
+        let me = &accounts[0];
+        assert_eq!(me["email"], "me@x.com");
+        assert_eq!((me["label"].clone(), me["color"].clone()), (json!("Me"), Value::Null));
+        assert_eq!((me["plan_type"].clone(), me["plan_source"].clone()), (json!("max_5x"), json!("detected")));
+        assert_eq!((me["messages"].as_u64(), me["sessions"].as_u64(), me["cost"].as_f64()), (Some(2), Some(2), Some(1.5)));
+        assert_eq!(me["config_dirs"].as_array().unwrap().len(), 2);
+        assert!(me["last_activity"].as_str().unwrap().starts_with("2025-02"));
+        assert!(me["current_week_used_pct"].is_null());
+    }
+}
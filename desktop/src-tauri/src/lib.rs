@@ -1,22 +1,198 @@
 // Module declarations
+mod accounts;
+mod aggregate;
+mod api_tokens;
+mod atomic;
+mod backend;
+mod changelog;
 mod commands;
+mod derived_metrics;
+mod error;
+mod etag;
+mod exports;
+mod features;
+mod handshake;
+mod http_server;
+mod indexer;
+mod ingest;
+#[cfg(test)]
+mod harness;
+mod middleware;
+mod plugins;
+mod presentation;
+mod pricing;
+mod privacy;
 mod python_bridge;
+mod ranges;
+mod raw_session;
+mod report_templates;
+mod response_cache;
+mod response_schema;
+mod routes;
+mod schedule;
+mod schema;
+mod settings;
+mod state;
+mod store;
+mod telemetry;
+mod usage_accounts;
+mod watcher;
+
+/// Parsers, aggregation and caches exposed to the `cargo fuzz` targets in
+/// `fuzz/` and the criterion benchmarks in `benches/` (`--features bench`).
+#[cfg(any(fuzzing, feature = "bench"))]
+#[doc(hidden)]
+pub mod internals {
+    pub use crate::accounts::combine_account_dashboards;
+    pub use crate::ingest::{config_dirs, parse_line, parse_log, scan, ConfigDirSummary, Scan};
+    pub use crate::pricing::PriceTable;
+    pub use crate::raw_session::{stream_reader, RawFormat, StreamOptions, StreamStats};
+    pub use crate::response_cache::{cache_key, lookup as cache_lookup, store as cache_store};
+}
+
+use tauri::{Emitter, Listener, Manager};
 
 use commands::{
     get_dashboard_bundle,
+    get_dashboard_bundles,
+    refresh_today,
+    refresh_since,
+    get_refresh_status,
+    get_quarantined_lines,
+    get_schema_info,
+    index_native_store,
+    get_native_store_status,
+    get_index_status,
+    get_backend_version,
+    get_telemetry_settings,
+    set_telemetry,
+    preview_telemetry_payload,
+    send_telemetry,
+    get_changelog,
+    get_feature_flags,
+    set_feature_flag,
+    refresh_feature_flags,
+    list_api_tokens,
+    create_api_token,
+    revoke_api_token,
+    get_http_server_settings,
+    set_http_server_settings,
+    enqueue_export,
+    list_exports,
+    rerun_export,
+    get_schedule_preview,
+    list_export_schedules,
+    set_export_schedule,
+    delete_export_schedule,
+    create_backup,
+    verify_backup,
+    restore_backup,
+    merge_data_source,
+    shift_session_timestamps,
+    undo_timestamp_shift,
+    list_timestamp_shifts,
+    get_presentation_mode,
+    set_presentation_mode,
+    get_privacy_mode,
+    set_privacy_mode,
+    generate_demo_data,
+    generate_sample_data,
+    run_benchmark,
     get_day_details,
+    get_aggregate_history,
     get_model_details,
     get_session_details,
+    list_sessions,
+    get_raw_session,
+    export_raw_sessions,
+    import_session_archive,
     get_limit_resets,
+    get_limit_impact,
+    get_outcome_stats,
+    get_compaction_stats,
+    get_mcp_usage,
+    get_hook_stats,
+    get_effort_breakdown,
+    get_concurrency_stats,
     export_png_report,
+    export_for_social,
+    render_png_report,
+    get_png_report_bytes,
+    get_display_scale,
+    list_report_templates,
+    render_report,
+    save_export,
+    get_export_settings,
+    set_export_dir,
     get_projects,
     get_usage_accounts,
+    update_usage_account,
+    compare_usage_accounts,
+    get_combined_dashboard,
+    detect_claude_accounts,
+    bind_claude_account,
+    detect_plan,
     update_project,
+    suggest_project_description,
+    get_project_timeline,
+    backfill_project_first_seen,
+    get_stale_projects,
+    archive_projects,
+    get_description_llm_settings,
+    set_description_llm,
+    set_project_pinned,
+    set_project_order,
+    get_workspaces,
+    create_workspace,
+    delete_workspace,
+    assign_project_to_workspace,
+    get_budget_history,
+    set_monthly_budget,
+    get_hooks,
+    set_hook,
+    delete_hook,
+    test_hook,
+    get_derived_metrics,
+    list_available_metrics,
+    get_metric_series,
+    set_derived_metric,
+    delete_derived_metric,
+    get_calendar_overlay,
+    set_calendar_overlay,
+    get_working_hours,
+    set_working_hours,
+    get_out_of_hours_usage,
+    get_cost_centers,
+    set_cost_center,
+    delete_cost_center,
+    get_cost_allocation,
+    export_cost_allocation,
+    get_price_versions,
+    sync_price_versions,
+    set_price_version,
+    reprice_history,
+    create_snapshot,
+    list_snapshots,
+    get_snapshot,
+    delete_snapshot,
+    diff_snapshots,
+    get_mirror_status,
+    clear_mirror,
+    get_bridge_metrics,
+    diagnose_environment,
+    set_backend_log_events,
+    cancel_request,
+    list_plugins,
+    install_plugin,
+    resolve_range,
+    get_range_settings,
+    set_range_settings,
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
+    .manage(state::AppState::new(backend::from_args(std::env::args())))
     .plugin(tauri_plugin_dialog::init())
     .plugin(tauri_plugin_fs::init())
     .setup(|app| {
@@ -27,19 +203,224 @@ pub fn run() {
             .build(),
         )?;
       }
+      for store in schema::migrate_all() {
+        if store.status == schema::StoreStatus::Migrated {
+          log::info!(
+            "Migrated {} to schema {} (backup: {})",
+            store.path,
+            store.latest,
+            store.backup.as_deref().unwrap_or("-")
+          );
+        }
+        if let Some(error) = &store.error {
+          log::warn!("Schema check of {} failed: {}", store.path, error);
+        }
+      }
+      // Prefer a backend bundled with the app over the system Python
+      let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(std::path::Path::to_path_buf));
+      let resource_dir = app.path().resource_dir().ok();
+      python_bridge::configure_bundled_backend(
+        exe_dir.and_then(|dir| python_bridge::find_bundled_backend(&dir, resource_dir.as_deref())),
+      );
+      let handle = app.handle().clone();
+      python_bridge::set_log_sink(Box::new(move |event| {
+        if let Err(e) = handle.emit(python_bridge::BACKEND_LOG_EVENT, event) {
+          log::warn!("Failed to emit {}: {}", python_bridge::BACKEND_LOG_EVENT, e);
+        }
+      }));
+      app.listen_any(routes::ROUTE_CHANGED_EVENT, |event| {
+        match serde_json::from_str::<routes::RouteChange>(event.payload()) {
+          Ok(change) => {
+            let cancelled = routes::change_route(&change.webview, &change.route);
+            if !cancelled.is_empty() {
+              log::info!("Route {} cancelled {:?}", change.route, cancelled);
+            }
+          }
+          Err(e) => log::warn!("Invalid {} payload: {}", routes::ROUTE_CHANGED_EVENT, e),
+        }
+      });
+      // Version handshake with the Python API, off the main thread
+      let state = app.state::<state::AppState>();
+      let backend = state.backend.clone();
+      state.tasks.spawn("handshake", move || {
+        let request_id = python_bridge::new_request_id();
+        match backend.handshake(&request_id) {
+          Ok(()) => {}
+          Err(e @ error::CommandError::Incompatible { .. }) => log::error!("{}", e),
+          Err(e) => log::warn!("Backend handshake postponed: {}", e),
+        }
+      });
+      // Catch the native store up with the logs written while the app was closed
+      if watcher::enabled() || ingest::enabled() {
+        let handle = app.handle().clone();
+        state.tasks.spawn("store-indexer", move || indexer::run(handle));
+      }
+      // Index new session lines as Claude Code writes them
+      if watcher::enabled() {
+        let handle = app.handle().clone();
+        state.tasks.spawn("log-watcher", move || watcher::run(handle));
+      }
+      // Token-protected API for scripts on this machine
+      if http_server::enabled() {
+        let backend = state.backend.clone();
+        let export_dir = exports::default_export_dir(app.handle());
+        state.tasks.spawn("http-server", move || http_server::run(backend, export_dir));
+      }
+      match exports::fail_interrupted() {
+        Ok(0) => {}
+        Ok(count) => log::warn!("Marked {} interrupted export(s) as failed", count),
+        Err(e) => log::warn!("Failed to update the export history: {}", e),
+      }
+      // Queue scheduled exports when they are due
+      let handle = app.handle().clone();
+      state.tasks.spawn("export-scheduler", move || schedule::run(handle));
       Ok(())
     })
-    .invoke_handler(tauri::generate_handler![
+    .invoke_handler(middleware::chain(middleware::default_chain(), tauri::generate_handler![
       get_dashboard_bundle,
+      get_dashboard_bundles,
+      refresh_today,
+      refresh_since,
+      get_refresh_status,
+      get_quarantined_lines,
+      get_schema_info,
+      index_native_store,
+      get_native_store_status,
+      get_index_status,
+      get_backend_version,
+      get_telemetry_settings,
+      set_telemetry,
+      preview_telemetry_payload,
+      send_telemetry,
+      get_changelog,
+      get_feature_flags,
+      set_feature_flag,
+      refresh_feature_flags,
+      list_api_tokens,
+      create_api_token,
+      revoke_api_token,
+      get_http_server_settings,
+      set_http_server_settings,
+      enqueue_export,
+      list_exports,
+      rerun_export,
+      get_schedule_preview,
+      list_export_schedules,
+      set_export_schedule,
+      delete_export_schedule,
+      create_backup,
+      verify_backup,
+      restore_backup,
+      merge_data_source,
+      shift_session_timestamps,
+      undo_timestamp_shift,
+      list_timestamp_shifts,
+      get_presentation_mode,
+      set_presentation_mode,
+      get_privacy_mode,
+      set_privacy_mode,
+      generate_demo_data,
+      generate_sample_data,
+      run_benchmark,
       get_day_details,
+      get_aggregate_history,
       get_model_details,
       get_session_details,
+      list_sessions,
+      get_raw_session,
+      export_raw_sessions,
+      import_session_archive,
       get_limit_resets,
+      get_limit_impact,
+      get_outcome_stats,
+      get_compaction_stats,
+      get_mcp_usage,
+      get_hook_stats,
+      get_effort_breakdown,
+      get_concurrency_stats,
       export_png_report,
+      export_for_social,
+      render_png_report,
+      get_png_report_bytes,
+      get_display_scale,
+      list_report_templates,
+      render_report,
+      save_export,
+      get_export_settings,
+      set_export_dir,
       get_projects,
       get_usage_accounts,
-      update_project
-    ])
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+      update_usage_account,
+      compare_usage_accounts,
+      get_combined_dashboard,
+      detect_claude_accounts,
+      bind_claude_account,
+      detect_plan,
+      update_project,
+      suggest_project_description,
+      get_project_timeline,
+      backfill_project_first_seen,
+      get_stale_projects,
+      archive_projects,
+      get_description_llm_settings,
+      set_description_llm,
+      set_project_pinned,
+      set_project_order,
+      get_workspaces,
+      create_workspace,
+      delete_workspace,
+      assign_project_to_workspace,
+      get_budget_history,
+      set_monthly_budget,
+      get_hooks,
+      set_hook,
+      delete_hook,
+      test_hook,
+      get_derived_metrics,
+      list_available_metrics,
+      get_metric_series,
+      set_derived_metric,
+      delete_derived_metric,
+      get_calendar_overlay,
+      set_calendar_overlay,
+      get_working_hours,
+      set_working_hours,
+      get_out_of_hours_usage,
+      get_cost_centers,
+      set_cost_center,
+      delete_cost_center,
+      get_cost_allocation,
+      export_cost_allocation,
+      get_price_versions,
+      sync_price_versions,
+      set_price_version,
+      reprice_history,
+      create_snapshot,
+      list_snapshots,
+      get_snapshot,
+      delete_snapshot,
+      diff_snapshots,
+      get_mirror_status,
+      clear_mirror,
+      get_bridge_metrics,
+      diagnose_environment,
+      set_backend_log_events,
+      cancel_request,
+      list_plugins,
+      install_plugin,
+      resolve_range,
+      get_range_settings,
+      set_range_settings
+    ]))
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|app, event| {
+      // Closing the last window ends the app: don't leave Python running
+      if let tauri::RunEvent::Exit = event {
+        app.state::<state::AppState>().backend.shutdown();
+        api_tokens::flush();
+      }
+    });
 }
@@ -0,0 +1,453 @@
+/// Command middleware
+///
+/// Every invoke passes a chain of `Middleware` before the command runs, so
+/// cross-cutting checks live here instead of in each `#[tauri::command]`:
+///
+/// 1. `Validation` - common arguments are well-formed: `from`, `to` and
+///    `date` are YYYY-MM-DD dates (`from` not after `to`), `month` is YYYY-MM
+/// 2. `ReadOnlyGuard` - desktop setters are refused in read-only mode (the
+///    Python backend refuses its own state-changing subcommands)
+/// 3. `Metrics` - invokes are counted per command (`get_bridge_metrics`)
+/// 4. `routes::RouteTracking` - scoped reads are recorded for route cancellation
+///
+/// then the command executes. The first middleware that fails rejects the
+/// invoke with its `CommandError`, tagged with the caller's request ID (or a
+/// generated one), without running the command; rejections are counted per
+/// command and middleware. `chain` wraps the generated handler in `lib.rs`;
+/// the HTTP server runs the same chain on its requests, as commands named
+/// `http:<subcommand>` (`http_command`) from the `http` webview.
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::Runtime;
+
+use crate::api_tokens::{self, Scope};
+use crate::error::CommandError;
+use crate::presentation;
+use crate::python_bridge::resolve_request_id;
+use crate::routes::RouteTracking;
+
+/// One invoke as seen by the middleware.
+pub struct Invocation<'a> {
+    pub command: &'a str,
+    /// Label of the calling webview
+    pub webview: &'a str,
+    /// JSON arguments (camelCase names); `Null` for raw bodies
+    pub args: &'a Value,
+}
+
+impl Invocation<'_> {
+    pub fn arg(&self, name: &str) -> Option<&Value> {
+        self.args.get(name).filter(|value| !value.is_null())
+    }
+
+    pub fn request_id(&self) -> Option<&str> {
+        self.arg("requestId").and_then(Value::as_str)
+    }
+}
+
+/// A step of the chain; `Err` rejects the invoke.
+pub trait Middleware: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn handle(&self, invocation: &Invocation) -> Result<(), CommandError>;
+}
+
+/// Date arguments and their format.
+const DATE_ARGS: &[(&str, &str, &str)] = &[
+    ("from", "%Y-%m-%d", "YYYY-MM-DD"),
+    ("to", "%Y-%m-%d", "YYYY-MM-DD"),
+    ("date", "%Y-%m-%d", "YYYY-MM-DD"),
+    ("month", "%Y-%m-%d", "YYYY-MM"),
+];
+
+/// Checks the arguments shared by many commands.
+pub struct Validation;
+
+impl Middleware for Validation {
+    fn name(&self) -> &'static str {
+        "validation"
+    }
+
+    fn handle(&self, invocation: &Invocation) -> Result<(), CommandError> {
+        let mut dates = BTreeMap::new();
+        for &(name, format, expected) in DATE_ARGS {
+            let Some(value) = invocation.arg(name) else {
+                continue;
+            };
+            let text = value.as_str().unwrap_or_default();
+            // Months parse as the first day of the month
+            let parsed = if name == "month" {
+                NaiveDate::parse_from_str(&format!("{}-01", text), format).ok().filter(|_| text.len() == 7)
+            } else {
+                NaiveDate::parse_from_str(text, format).ok().filter(|_| text.len() == 10)
+            };
+            let Some(date) = parsed else {
+                return Err(CommandError::invalid_argument(format!(
+                    "Invalid {}: {} (expected {})",
+                    name, value, expected
+                )));
+            };
+            dates.insert(name, date);
+        }
+        if let (Some(from), Some(to)) = (dates.get("from"), dates.get("to")) {
+            if from > to {
+                return Err(CommandError::invalid_argument(format!(
+                    "Start date {} is after end date {}",
+                    from, to
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Prefix of backend subcommands called over the HTTP server.
+const HTTP_COMMAND_PREFIX: &str = "http:";
+
+/// Command name of a backend subcommand called over the HTTP server.
+pub fn http_command(subcommand: &str) -> String {
+    format!("{}{}", HTTP_COMMAND_PREFIX, subcommand)
+}
+
+/// Commands that change desktop state, refused in read-only mode.
+const WRITE_COMMANDS: &[&str] = &[
+    "set_export_dir",
+    "set_description_llm",
+    "install_plugin",
+    "set_range_settings",
+    "set_telemetry",
+    "set_feature_flag",
+    "create_api_token",
+    "revoke_api_token",
+    "set_http_server_settings",
+    "set_export_schedule",
+    "delete_export_schedule",
+    "generate_demo_data",
+    "generate_sample_data",
+    "index_native_store",
+    "enqueue_export",
+    "rerun_export",
+    "save_export",
+    "export_png_report",
+    "export_for_social",
+    "render_report",
+    "export_raw_sessions",
+    "export_cost_allocation",
+    "create_backup",
+    "refresh_feature_flags",
+    "send_telemetry",
+];
+
+/// Refuses desktop writes while read-only mode is on.
+pub struct ReadOnlyGuard;
+
+impl ReadOnlyGuard {
+    fn is_write(invocation: &Invocation) -> bool {
+        match invocation.command {
+            // Turning privacy mode on is always allowed
+            "set_privacy_mode" => invocation.arg("enabled") == Some(&Value::Bool(false)),
+            // A report is only written to a file when saved
+            "render_report" => invocation.arg("save") == Some(&Value::Bool(true)),
+            command => match command.strip_prefix(HTTP_COMMAND_PREFIX) {
+                // Over HTTP only the subcommands a read token may call are reads
+                Some(subcommand) => api_tokens::required_scope(subcommand) != Scope::Read,
+                None => WRITE_COMMANDS.contains(&command),
+            },
+        }
+    }
+}
+
+impl Middleware for ReadOnlyGuard {
+    fn name(&self) -> &'static str {
+        "read_only"
+    }
+
+    fn handle(&self, invocation: &Invocation) -> Result<(), CommandError> {
+        if Self::is_write(invocation) {
+            presentation::ensure_writable(invocation.command)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+struct CommandStats {
+    invokes: u64,
+    /// Rejections per middleware name
+    rejected: BTreeMap<&'static str, u64>,
+}
+
+static COMMAND_STATS: Mutex<BTreeMap<String, CommandStats>> = Mutex::new(BTreeMap::new());
+
+fn with_stats(command: &str, f: impl FnOnce(&mut CommandStats)) {
+    let mut stats = COMMAND_STATS.lock().unwrap_or_else(|e| e.into_inner());
+    f(stats.entry(command.to_string()).or_default());
+}
+
+/// Counts the invokes that pass the checks.
+pub struct Metrics;
+
+impl Middleware for Metrics {
+    fn name(&self) -> &'static str {
+        "metrics"
+    }
+
+    fn handle(&self, invocation: &Invocation) -> Result<(), CommandError> {
+        with_stats(invocation.command, |stats| stats.invokes += 1);
+        Ok(())
+    }
+}
+
+/// Invokes and rejections per command for this app session
+/// (`{command: {invokes, rejected: {middleware: count}}}`).
+pub fn command_metrics() -> Value {
+    let stats = COMMAND_STATS.lock().unwrap_or_else(|e| e.into_inner());
+    serde_json::to_value(&*stats).unwrap_or_default()
+}
+
+/// The chain in the order it runs.
+pub fn default_chain() -> Vec<Box<dyn Middleware>> {
+    vec![
+        Box::new(Validation),
+        Box::new(ReadOnlyGuard),
+        Box::new(Metrics),
+        Box::new(RouteTracking),
+    ]
+}
+
+/// Run `middleware` on an invoke; the error of the first one that fails,
+/// with the request ID attached.
+pub fn run_chain(middleware: &[Box<dyn Middleware>], invocation: &Invocation) -> Result<(), CommandError> {
+    for step in middleware {
+        if let Err(e) = step.handle(invocation) {
+            with_stats(invocation.command, |stats| *stats.rejected.entry(step.name()).or_default() += 1);
+            let request_id = resolve_request_id(invocation.request_id().map(str::to_string));
+            log::warn!("[{}] {} rejected by {}: {}", request_id, invocation.command, step.name(), e.message());
+            return Err(e.with_request_id(&request_id));
+        }
+    }
+    Ok(())
+}
+
+/// Wrap the command handler so every invoke passes `middleware` first.
+pub fn chain<R: Runtime>(
+    middleware: Vec<Box<dyn Middleware>>,
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let checked = {
+            let args = match invoke.message.payload() {
+                InvokeBody::Json(args) => args,
+                _ => &Value::Null,
+            };
+            let invocation = Invocation {
+                command: invoke.message.command(),
+                webview: invoke.message.webview_ref().label(),
+                args,
+            };
+            run_chain(&middleware, &invocation)
+        };
+        match checked {
+            Ok(()) => handler(invoke),
+            Err(e) => {
+                invoke.resolver.reject(e);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Commands whose writes the backend refuses in read-only mode (its audited
+    /// subcommands), so that dry runs and previews stay available.
+    const BACKEND_WRITE_COMMANDS: &[&str] = &[
+        "restore_backup",
+        "merge_data_source",
+        "shift_session_timestamps",
+        "undo_timestamp_shift",
+        "import_session_archive",
+        "update_usage_account",
+        "bind_claude_account",
+        "detect_plan",
+        "update_project",
+        "backfill_project_first_seen",
+        "archive_projects",
+        "set_project_pinned",
+        "set_project_order",
+        "create_workspace",
+        "delete_workspace",
+        "assign_project_to_workspace",
+        "set_monthly_budget",
+        "set_hook",
+        "delete_hook",
+        "test_hook",
+        "set_derived_metric",
+        "delete_derived_metric",
+        "set_calendar_overlay",
+        "set_working_hours",
+        "set_cost_center",
+        "delete_cost_center",
+        "sync_price_versions",
+        "set_price_version",
+        "reprice_history",
+        "create_snapshot",
+        "delete_snapshot",
+        "clear_mirror",
+    ];
+
+    /// Commands allowed in read-only mode: reads, refreshes of the usage data
+    /// and the presentation, privacy and cancel controls.
+    const READ_COMMANDS: &[&str] = &[
+        "get_dashboard_bundle",
+        "get_dashboard_bundles",
+        "refresh_today",
+        "refresh_since",
+        "get_refresh_status",
+        "get_quarantined_lines",
+        "get_schema_info",
+        "get_native_store_status",
+        "get_index_status",
+        "get_backend_version",
+        "get_telemetry_settings",
+        "preview_telemetry_payload",
+        "get_changelog",
+        "get_feature_flags",
+        "list_api_tokens",
+        "get_http_server_settings",
+        "list_exports",
+        "get_schedule_preview",
+        "list_export_schedules",
+        "verify_backup",
+        "list_timestamp_shifts",
+        "get_presentation_mode",
+        "set_presentation_mode",
+        "get_privacy_mode",
+        "set_privacy_mode",
+        "run_benchmark",
+        "get_day_details",
+        "get_aggregate_history",
+        "get_model_details",
+        "get_session_details",
+        "list_sessions",
+        "get_raw_session",
+        "get_limit_resets",
+        "get_limit_impact",
+        "get_outcome_stats",
+        "get_compaction_stats",
+        "get_mcp_usage",
+        "get_hook_stats",
+        "get_effort_breakdown",
+        "get_concurrency_stats",
+        "render_png_report",
+        "get_png_report_bytes",
+        "get_display_scale",
+        "list_report_templates",
+        "get_export_settings",
+        "get_projects",
+        "get_usage_accounts",
+        "compare_usage_accounts",
+        "get_combined_dashboard",
+        "detect_claude_accounts",
+        "suggest_project_description",
+        "get_project_timeline",
+        "get_stale_projects",
+        "get_description_llm_settings",
+        "get_workspaces",
+        "get_budget_history",
+        "get_hooks",
+        "get_derived_metrics",
+        "list_available_metrics",
+        "get_metric_series",
+        "get_calendar_overlay",
+        "get_working_hours",
+        "get_out_of_hours_usage",
+        "get_cost_centers",
+        "get_cost_allocation",
+        "get_price_versions",
+        "list_snapshots",
+        "get_snapshot",
+        "diff_snapshots",
+        "get_mirror_status",
+        "get_bridge_metrics",
+        "diagnose_environment",
+        "set_backend_log_events",
+        "cancel_request",
+        "list_plugins",
+        "resolve_range",
+        "get_range_settings",
+    ];
+
+    fn check(command: &str, args: Value) -> Result<(), CommandError> {
+        run_chain(
+            &[Box::new(Validation) as Box<dyn Middleware>, Box::new(Metrics)],
+            &Invocation { command, webview: "main", args: &args },
+        )
+    }
+
+    #[test]
+    fn test_validation_rejects_before_metrics() {
+        assert!(check("test_valid", json!({"from": "2026-01-01", "to": "2026-01-31", "month": "2026-01"})).is_ok());
+        assert!(check("test_valid", json!({"from": null, "requestId": "rq-1"})).is_ok());
+
+        let error = check("test_invalid", json!({"date": "2026-02-30", "requestId": "rq-2"})).unwrap_err();
+        assert!(matches!(error, CommandError::InvalidArgument { .. }));
+        assert_eq!(error.request_id(), Some("rq-2"));
+        assert!(check("test_invalid", json!({"from": "2026-02-01", "to": "2026-01-01"})).is_err());
+        assert!(check("test_invalid", json!({"month": "2026-1"})).is_err());
+
+        let metrics = command_metrics();
+        assert_eq!(metrics["test_valid"]["invokes"], 2);
+        assert_eq!(metrics["test_invalid"]["invokes"], 0);
+        assert_eq!(metrics["test_invalid"]["rejected"]["validation"], 3);
+    }
+
+    #[test]
+    fn test_read_only_guard_only_checks_writes() {
+        let args = json!({"enabled": true});
+        assert!(!ReadOnlyGuard::is_write(&Invocation { command: "set_privacy_mode", webview: "main", args: &args }));
+        assert!(ReadOnlyGuard::is_write(&Invocation { command: "set_telemetry", webview: "main", args: &args }));
+        let off = json!({"enabled": false});
+        assert!(ReadOnlyGuard::is_write(&Invocation { command: "set_privacy_mode", webview: "main", args: &off }));
+        for command in WRITE_COMMANDS.iter().filter(|command| **command != "render_report") {
+            assert!(ReadOnlyGuard::is_write(&Invocation { command, webview: "main", args: &args }), "{}", command);
+        }
+        for command in READ_COMMANDS.iter().chain(BACKEND_WRITE_COMMANDS).filter(|c| **c != "set_privacy_mode") {
+            assert!(!ReadOnlyGuard::is_write(&Invocation { command, webview: "main", args: &args }), "{}", command);
+        }
+        let preview = json!({"template": "summary", "save": false});
+        assert!(!ReadOnlyGuard::is_write(&Invocation { command: "render_report", webview: "main", args: &preview }));
+        let save = json!({"template": "summary", "save": true});
+        assert!(ReadOnlyGuard::is_write(&Invocation { command: "render_report", webview: "main", args: &save }));
+
+        let http = |subcommand: &str| {
+            let command = http_command(subcommand);
+            ReadOnlyGuard::is_write(&Invocation { command: &command, webview: "http", args: &args })
+        };
+        assert!(!http("dashboard"));
+        assert!(http("export-png"));
+        assert!(http("restore-backup"));
+    }
+
+    #[test]
+    fn test_every_command_is_classified() {
+        let source = include_str!("lib.rs");
+        let start = source.find("generate_handler![").expect("handler list") + "generate_handler![".len();
+        let end = start + source[start..].find(']').expect("end of handler list");
+        let commands: Vec<&str> = source[start..end].split(',').map(str::trim).filter(|c| !c.is_empty()).collect();
+        let lists = [WRITE_COMMANDS, BACKEND_WRITE_COMMANDS, READ_COMMANDS];
+        assert_eq!(lists.iter().map(|list| list.len()).sum::<usize>(), commands.len(), "stale command in a list");
+        for command in commands {
+            let classified = lists.iter().filter(|list| list.contains(&command)).count();
+            assert_eq!(classified, 1, "{} must be in exactly one of the read and write lists", command);
+        }
+    }
+}
@@ -0,0 +1,478 @@
+/// Sandboxed WASM plugins for custom metrics
+///
+/// A plugin is a WebAssembly module stored in `~/.claude/db/plugins/` as
+/// `<name>.wasm` next to a `<name>.json` manifest. It implements the `Metric`
+/// interface through three exports:
+///
+/// - `memory` - the module's linear memory
+/// - `alloc(len: i32) -> i32` - reserve `len` bytes for the input, return the offset
+/// - `metric(ptr: i32, len: i32) -> i64` - compute the metric from the input JSON
+///   and return the output location packed as `(offset << 32) | length`
+///
+/// Input is `{"range": {...}, "records": [...]}` with the normalized message
+/// records of the `plugin-records` subcommand. Output is
+/// `{"series": [{"name": "...", "points": [{"x": ..., "y": 1.0}]}]}`.
+///
+/// Modules run in wasmi without any imports (no WASI, no host functions), with
+/// a fuel budget and a memory cap, so a plugin can only turn its input into
+/// its output.
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::atomic::write_atomic;
+use crate::settings::db_dir;
+
+/// Instructions a plugin may execute per run (a few seconds in release builds).
+pub const MAX_FUEL: u64 = 1_000_000_000;
+
+/// Maximum linear memory of a plugin instance.
+pub const MAX_MEMORY_BYTES: usize = 256 * 1024 * 1024;
+
+/// Maximum size of a plugin module accepted by `install_plugin`.
+pub const MAX_MODULE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Maximum size of the JSON a plugin may return.
+pub const MAX_OUTPUT_BYTES: usize = 4 * 1024 * 1024;
+
+/// Maximum number of series per plugin.
+pub const MAX_SERIES: usize = 32;
+
+/// Plugin metadata stored next to the module.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Installed plugin as reported by `list_plugins`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginInfo {
+    #[serde(flatten)]
+    pub manifest: PluginManifest,
+    pub path: String,
+    pub size_bytes: u64,
+    /// Why the module cannot be used (invalid module or missing exports)
+    pub error: Option<String>,
+}
+
+/// One named series returned by a plugin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSeries {
+    pub name: String,
+    pub points: Vec<MetricPoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricPoint {
+    pub x: Value,
+    pub y: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetricOutput {
+    series: Vec<MetricSeries>,
+}
+
+/// Directory holding installed plugins.
+pub fn plugin_dir() -> Result<PathBuf, String> {
+    Ok(db_dir()?.join("plugins"))
+}
+
+/// Compiled plugin module.
+pub struct MetricPlugin {
+    name: String,
+    engine: Engine,
+    module: Module,
+}
+
+impl MetricPlugin {
+    /// Compile a module and check that it implements the `Metric` interface.
+    pub fn compile(name: &str, wasm: &[u8]) -> Result<Self, String> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, wasm).map_err(|e| format!("Invalid WASM module: {}", e))?;
+
+        if let Some(import) = module.imports().next() {
+            return Err(format!(
+                "Plugins may not import host functions (imports {}::{})",
+                import.module(),
+                import.name()
+            ));
+        }
+        for export in ["memory", "alloc", "metric"] {
+            if !module.exports().any(|e| e.name() == export) {
+                return Err(format!("Missing export: {}", export));
+            }
+        }
+
+        Ok(MetricPlugin {
+            name: name.to_string(),
+            engine,
+            module,
+        })
+    }
+
+    /// Run the metric on serialized input JSON.
+    pub fn run(&self, input: &[u8]) -> Result<Vec<MetricSeries>, String> {
+        self.run_with_fuel(input, MAX_FUEL)
+    }
+
+    fn run_with_fuel(&self, input: &[u8], fuel: u64) -> Result<Vec<MetricSeries>, String> {
+        let fail = |e: &dyn std::fmt::Display| format!("Plugin {} failed: {}", self.name, e);
+
+        let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY_BYTES).build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits: &mut StoreLimits| limits);
+        store.set_fuel(fuel).map_err(|e| fail(&e))?;
+
+        let instance = Linker::<StoreLimits>::new(&self.engine)
+            .instantiate(&mut store, &self.module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|e| fail(&e))?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| fail(&"memory export is not a memory"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(|e| fail(&e))?;
+        let metric = instance
+            .get_typed_func::<(i32, i32), i64>(&store, "metric")
+            .map_err(|e| fail(&e))?;
+
+        let len = i32::try_from(input.len()).map_err(|_| fail(&"input too large"))?;
+        let ptr = alloc.call(&mut store, len).map_err(|e| fail(&e))?;
+        memory
+            .write(&mut store, ptr as u32 as usize, input)
+            .map_err(|e| fail(&e))?;
+
+        let packed = metric.call(&mut store, (ptr, len)).map_err(|e| fail(&e))? as u64;
+        let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        if out_len > MAX_OUTPUT_BYTES {
+            return Err(fail(&format!("output exceeds {} bytes", MAX_OUTPUT_BYTES)));
+        }
+        let mut output = vec![0u8; out_len];
+        memory.read(&store, out_ptr, &mut output).map_err(|e| fail(&e))?;
+
+        let parsed: MetricOutput =
+            serde_json::from_slice(&output).map_err(|e| fail(&format!("invalid output JSON: {}", e)))?;
+        if parsed.series.len() > MAX_SERIES {
+            return Err(fail(&format!("more than {} series", MAX_SERIES)));
+        }
+        if let Some(series) = parsed.series.iter().find(|s| s.name.trim().is_empty()) {
+            return Err(fail(&format!("series without a name ({} points)", series.points.len())));
+        }
+        Ok(parsed.series)
+    }
+}
+
+/// Compiled modules by path, with the modification time and size they were
+/// compiled from, so listing and running plugins compile each module once.
+type CompiledPlugin = (Option<SystemTime>, u64, Result<Arc<MetricPlugin>, String>);
+
+static COMPILED: Mutex<Option<HashMap<PathBuf, CompiledPlugin>>> = Mutex::new(None);
+
+/// The compiled module at `path` (named `name`), from the cache while the
+/// file is unchanged.
+fn compiled(path: &Path, name: &str) -> Result<Arc<MetricPlugin>, String> {
+    let metadata = fs::metadata(path).map_err(|e| e.to_string())?;
+    let (modified, size) = (metadata.modified().ok(), metadata.len());
+    let mut cache = COMPILED.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = cache.get_or_insert_with(HashMap::new);
+    if let Some((cached_modified, cached_size, plugin)) = cache.get(path) {
+        if modified.is_some() && *cached_modified == modified && *cached_size == size {
+            return plugin.clone();
+        }
+    }
+    let plugin = fs::read(path)
+        .map_err(|e| e.to_string())
+        .and_then(|wasm| MetricPlugin::compile(name, &wasm))
+        .map(Arc::new);
+    cache.insert(path.to_path_buf(), (modified, size, plugin.clone()));
+    plugin
+}
+
+/// Plugin names become file names: lowercase letters, digits, `-` and `_`.
+pub fn sanitize_name(raw: &str) -> Result<String, String> {
+    let name: String = raw
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    let name = name.trim_matches('-').to_string();
+    if name.is_empty() || name.len() > 64 {
+        return Err(format!("Invalid plugin name: {:?}", raw));
+    }
+    Ok(name)
+}
+
+fn read_manifest(dir: &Path, name: &str) -> PluginManifest {
+    fs::read(dir.join(format!("{}.json", name)))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<PluginManifest>(&bytes).ok())
+        .map(|manifest| PluginManifest {
+            name: name.to_string(),
+            ..manifest
+        })
+        .unwrap_or_else(|| PluginManifest {
+            name: name.to_string(),
+            version: None,
+            description: None,
+            enabled: true,
+        })
+}
+
+/// List installed plugins, sorted by name.
+pub fn list_plugins(dir: &Path) -> Result<Vec<PluginInfo>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+
+    let mut plugins = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("wasm") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let error = compiled(&path, name).err();
+        plugins.push(PluginInfo {
+            manifest: read_manifest(dir, name),
+            path: path.display().to_string(),
+            size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            error,
+        });
+    }
+    plugins.sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+    Ok(plugins)
+}
+
+/// Validate a module and copy it into the plugin directory.
+///
+/// The plugin name and metadata come from a `<stem>.json` manifest next to
+/// the module if present, otherwise from the file name. The module must pass
+/// a dry run on an empty record set. Installing under an existing name
+/// replaces that plugin.
+pub fn install_plugin(dir: &Path, source: &Path) -> Result<PluginInfo, String> {
+    let size = fs::metadata(source)
+        .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?
+        .len();
+    if size > MAX_MODULE_BYTES {
+        return Err(format!("Plugin module exceeds {} bytes", MAX_MODULE_BYTES));
+    }
+    let wasm = fs::read(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+
+    let stem = source.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    let mut manifest = fs::read(source.with_extension("json"))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<PluginManifest>(&bytes).ok())
+        .unwrap_or_else(|| PluginManifest {
+            name: stem.to_string(),
+            version: None,
+            description: None,
+            enabled: true,
+        });
+    manifest.name = sanitize_name(&manifest.name)?;
+
+    let plugin = MetricPlugin::compile(&manifest.name, &wasm)?;
+    plugin.run(json!({"range": {}, "records": []}).to_string().as_bytes())?;
+
+    // The manifest goes last: a module without one still loads with defaults.
+    let path = dir.join(format!("{}.wasm", manifest.name));
+    write_atomic(&path, &wasm)?;
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    write_atomic(&dir.join(format!("{}.json", manifest.name)), &manifest_json)?;
+
+    Ok(PluginInfo {
+        manifest,
+        path: path.display().to_string(),
+        size_bytes: size,
+        error: None,
+    })
+}
+
+/// Enabled plugins that compile; broken ones are skipped.
+pub fn enabled_plugins(dir: &Path) -> Vec<Arc<MetricPlugin>> {
+    list_plugins(dir)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|info| info.manifest.enabled && info.error.is_none())
+        .filter_map(|info| compiled(Path::new(&info.path), &info.manifest.name).ok())
+        .collect()
+}
+
+/// Run plugins on `plugin-records` output.
+///
+/// Returns `{<plugin name>: {"series": [...]}}`, or `{"error": ...}` for a
+/// plugin that failed, so one broken plugin does not hide the others.
+pub fn run_plugins(plugins: &[Arc<MetricPlugin>], records: &Value) -> Value {
+    let input = records.to_string();
+    let mut outputs = Map::new();
+    for plugin in plugins {
+        let output = match plugin.run(input.as_bytes()) {
+            Ok(series) => json!({ "series": series }),
+            Err(e) => json!({ "error": e }),
+        };
+        outputs.insert(plugin.name.clone(), output);
+    }
+    Value::Object(outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uleb(mut n: u64, out: &mut Vec<u8>) {
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+            if n == 0 {
+                out.push(byte);
+                return;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn sleb(mut n: i64, out: &mut Vec<u8>) {
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+            if (n == 0 && byte & 0x40 == 0) || (n == -1 && byte & 0x40 != 0) {
+                out.push(byte);
+                return;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn section(id: u8, content: &[u8], out: &mut Vec<u8>) {
+        out.push(id);
+        uleb(content.len() as u64, out);
+        out.extend_from_slice(content);
+    }
+
+    fn name(s: &str, out: &mut Vec<u8>) {
+        uleb(s.len() as u64, out);
+        out.extend_from_slice(s.as_bytes());
+    }
+
+    /// Module whose `metric` returns `output` from a data segment at 2048,
+    /// or loops forever if `spin` is set.
+    fn metric_module(output: &[u8], spin: bool) -> Vec<u8> {
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        section(1, &[0x02, 0x60, 0x01, 0x7f, 0x01, 0x7f, 0x60, 0x02, 0x7f, 0x7f, 0x01, 0x7e], &mut wasm);
+        section(3, &[0x02, 0x00, 0x01], &mut wasm);
+        section(5, &[0x01, 0x00, 0x01], &mut wasm);
+
+        let mut exports = vec![0x03];
+        name("memory", &mut exports);
+        exports.extend_from_slice(&[0x02, 0x00]);
+        name("alloc", &mut exports);
+        exports.extend_from_slice(&[0x00, 0x00]);
+        name("metric", &mut exports);
+        exports.extend_from_slice(&[0x00, 0x01]);
+        section(7, &exports, &mut wasm);
+
+        let alloc_body = [0x00, 0x41, 0x80, 0x08, 0x0b];
+        let mut metric_body = vec![0x00];
+        if spin {
+            metric_body.extend_from_slice(&[0x03, 0x40, 0x0c, 0x00, 0x0b]);
+        }
+        metric_body.push(0x42);
+        sleb((2048i64 << 32) | output.len() as i64, &mut metric_body);
+        metric_body.push(0x0b);
+        let mut code = vec![0x02];
+        uleb(alloc_body.len() as u64, &mut code);
+        code.extend_from_slice(&alloc_body);
+        uleb(metric_body.len() as u64, &mut code);
+        code.extend_from_slice(&metric_body);
+        section(10, &code, &mut wasm);
+
+        let mut data = vec![0x01, 0x00, 0x41, 0x80, 0x10, 0x0b];
+        uleb(output.len() as u64, &mut data);
+        data.extend_from_slice(output);
+        section(11, &data, &mut wasm);
+        wasm
+    }
+
+    #[test]
+    fn test_run_returns_named_series() {
+        let output = br#"{"series":[{"name":"cost-per-session","points":[{"x":"2025-06-15","y":1.5}]}]}"#;
+        let plugin = MetricPlugin::compile("demo", &metric_module(output, false)).unwrap();
+        let series = plugin.run(br#"{"range":{},"records":[]}"#).unwrap();
+
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].name, "cost-per-session");
+        assert_eq!(series[0].points[0].y, 1.5);
+    }
+
+    #[test]
+    fn test_runaway_plugin_runs_out_of_fuel() {
+        let plugin = MetricPlugin::compile("spin", &metric_module(b"{}", true)).unwrap();
+        let error = plugin.run_with_fuel(b"{}", 100_000).unwrap_err();
+        assert!(error.starts_with("Plugin spin failed"), "{}", error);
+    }
+
+    #[test]
+    fn test_modules_with_imports_are_rejected() {
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        section(1, &[0x01, 0x60, 0x00, 0x00], &mut wasm);
+        let mut imports = vec![0x01];
+        name("wasi_snapshot_preview1", &mut imports);
+        name("fd_write", &mut imports);
+        imports.extend_from_slice(&[0x00, 0x00]);
+        section(2, &imports, &mut wasm);
+
+        let error = MetricPlugin::compile("io", &wasm).err().unwrap();
+        assert!(error.contains("wasi_snapshot_preview1::fd_write"), "{}", error);
+    }
+
+    #[test]
+    fn test_installed_plugins_are_compiled_once() {
+        let root = std::env::temp_dir().join(format!("cc-plugins-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let source = root.join("demo.wasm");
+        fs::write(&source, metric_module(br#"{"series":[]}"#, false)).unwrap();
+        let dir = root.join("plugins");
+
+        let info = install_plugin(&dir, &source).unwrap();
+        assert!(dir.join("demo.json").is_file());
+        let listed = list_plugins(&dir).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].error.is_none());
+        let first = enabled_plugins(&dir);
+        let second = enabled_plugins(&dir);
+        assert!(Arc::ptr_eq(&first[0], &second[0]));
+        assert_eq!(first[0].name, info.manifest.name);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("My Metric v2").unwrap(), "my-metric-v2");
+        assert!(sanitize_name("../").is_err());
+    }
+}
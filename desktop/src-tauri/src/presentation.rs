@@ -0,0 +1,163 @@
+/// Presentation modes for demos and screen sharing
+///
+/// While read-only mode is on, state-changing commands are refused: the
+/// `ReadOnlyGuard` middleware refuses desktop setters (`ensure_writable`), and
+/// the Python backend refuses its audited subcommands (project edits, purges,
+/// restores, budgets, prices...).
+/// Project names can additionally be blurred in every backend response: by
+/// the Python backend, and by `blur_projects` in responses built natively.
+///
+/// Demo data mode swaps the real data for seeded synthetic usage (see
+/// `demo_data.py`): the backend resolves its home directory to a separate
+/// demo home, so every database and settings path points there and real
+/// session logs are never read. Sample data (`sample_data.py`) are synthetic
+/// session logs written into the same demo home and ingested like real ones.
+///
+/// The mode is stored in the settings, so an app restart mid-presentation
+/// keeps it on, and is passed to Python as `COMMAND_CENTER_READ_ONLY`,
+/// `COMMAND_CENTER_BLUR_PROJECTS`, `COMMAND_CENTER_DEMO` and `COMMAND_CENTER_DEMO_HOME`.
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error::CommandError;
+use crate::settings::{db_dir, Settings};
+
+/// Environment variable that makes the Python backend refuse state-changing subcommands.
+pub const READ_ONLY_ENV: &str = "COMMAND_CENTER_READ_ONLY";
+
+/// Environment variable that makes the Python backend blur project names.
+pub const BLUR_PROJECTS_ENV: &str = "COMMAND_CENTER_BLUR_PROJECTS";
+
+/// Environment variable telling the Python backend it runs against the demo home.
+pub const DEMO_ENV: &str = "COMMAND_CENTER_DEMO";
+
+/// Environment variable with the demo home directory.
+pub const DEMO_HOME_ENV: &str = "COMMAND_CENTER_DEMO_HOME";
+
+/// Event emitted when the mode changes (payload: `PresentationMode`), for the banner.
+pub const MODE_CHANGED_EVENT: &str = "presentation-mode-changed";
+
+/// Fields cleared when project names are blurred: paths reveal them.
+const PATH_FIELDS: &[&str] = &["absolute_path", "cwd"];
+
+/// Intensities of `generate_sample_data` (sessions per active day, see `sample_data.py`).
+pub const SAMPLE_INTENSITIES: &[&str] = &["light", "regular", "heavy"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct PresentationMode {
+    pub read_only: bool,
+    pub blur_project_names: bool,
+    pub demo_data: bool,
+}
+
+/// Home directory of the backend in demo data mode (`~/.claude/db/demo`).
+pub fn demo_home() -> Result<PathBuf, String> {
+    Ok(db_dir()?.join("demo"))
+}
+
+/// True once demo data was generated.
+pub fn demo_data_exists() -> bool {
+    demo_home()
+        .map(|home| home.join(".claude").join("db").join("command_center.db").is_file())
+        .unwrap_or(false)
+}
+
+impl PresentationMode {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            read_only: settings.read_only.unwrap_or(false),
+            blur_project_names: settings.blur_project_names.unwrap_or(false),
+            demo_data: settings.demo_data.unwrap_or(false),
+        }
+    }
+
+    pub fn load() -> Self {
+        Self::from_settings(&Settings::load())
+    }
+
+    /// Environment of a backend process.
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        let flag = |on: bool| if on { "1" } else { "0" }.to_string();
+        let mut env = vec![
+            (READ_ONLY_ENV, flag(self.read_only)),
+            (BLUR_PROJECTS_ENV, flag(self.blur_project_names)),
+            (DEMO_ENV, flag(self.demo_data)),
+        ];
+        if self.demo_data {
+            if let Ok(home) = demo_home() {
+                env.push((DEMO_HOME_ENV, home.display().to_string()));
+            }
+        }
+        env
+    }
+}
+
+/// Refuse a state-changing command while read-only mode is on.
+pub fn ensure_writable(command: &str) -> Result<(), CommandError> {
+    if PresentationMode::load().read_only {
+        return Err(CommandError::read_only(format!("Read-only mode is on: {} is disabled", command)));
+    }
+    Ok(())
+}
+
+/// Stable display alias of a project ("Project 3FA2"), the same as the
+/// Python backend's (`presentation.project_alias`).
+pub fn project_alias(project_id: &str) -> String {
+    let digest = Sha256::digest(project_id.as_bytes());
+    format!("Project {:02X}{:02X}", digest[0], digest[1])
+}
+
+/// Blur project names in a response, as `presentation.blur_projects` does in
+/// the Python backend: every object with a `project_id` gets its alias as
+/// `name` and loses its description, and paths are cleared.
+pub fn blur_projects(value: &mut Value) {
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(blur_projects),
+        Value::Object(map) => {
+            map.values_mut().for_each(blur_projects);
+            for field in PATH_FIELDS {
+                if let Some(path) = map.get_mut(*field) {
+                    *path = Value::Null;
+                }
+            }
+            let alias = map.get("project_id").and_then(Value::as_str).filter(|id| !id.is_empty()).map(project_alias);
+            if let Some(alias) = alias {
+                map.insert("name".to_string(), Value::from(alias));
+                if let Some(description) = map.get_mut("description") {
+                    *description = Value::Null;
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_blur_projects_matches_the_python_backend() {
+        let mut response = json!({
+            "projects": [
+                {"project_id": "-home-u-app", "name": "app", "description": "x", "absolute_path": "/home/u/app"}
+            ],
+            "cwd": "/x",
+        });
+        blur_projects(&mut response);
+        // presentation.blur_projects of the same response
+        assert_eq!(
+            response,
+            json!({
+                "projects": [
+                    {"project_id": "-home-u-app", "name": "Project 52EE", "description": null, "absolute_path": null}
+                ],
+                "cwd": null,
+            })
+        );
+    }
+}
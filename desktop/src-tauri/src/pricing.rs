@@ -0,0 +1,317 @@
+/// Model prices of the native store and ingestion
+///
+/// Prices messages the way the Python backend does (`utils/pricing.py` and
+/// `pricing_history.py`), from the same files in the data dir, so native and
+/// Python costs agree:
+///
+/// - the `price_versions` of the Python database (`command_center.db`) in
+///   effect on the message's local date come first,
+/// - else the LiteLLM dataset the Python backend caches in
+///   `pricing_cache.json`: the model, its alias or a provider-prefixed name,
+///   then the first key (in dataset order) containing the model or contained
+///   in it,
+/// - each token type above 200k tokens per message is billed at its
+///   `_above_200k_tokens` rate when the model has one.
+///
+/// Nothing is fetched: a model missing from the cache stays unpriced (cost 0)
+/// until the Python backend updates the cache.
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use rusqlite::{Connection, OpenFlags};
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde_json::Value;
+
+use crate::ingest::LogEntry;
+
+/// LiteLLM pricing dataset cached by the Python backend (in the data dir).
+pub const PRICING_CACHE_FILE: &str = "pricing_cache.json";
+
+/// Python backend database with the `price_versions` table (in the data dir).
+pub const PYTHON_DB_FILE: &str = "command_center.db";
+
+/// Tokens of a type per message billed at the base rate.
+const TIERED_THRESHOLD: u64 = 200_000;
+
+/// Prefixes tried before a model name, like the Python `PROVIDER_PREFIXES`.
+const PROVIDER_PREFIXES: &[&str] =
+    &["anthropic/", "bedrock/", "vertex_ai/", "claude-3-5-", "claude-3-", "claude-", "openrouter/openai/"];
+
+/// Dataset names of short model names, like the Python `MODEL_ALIASES`.
+const MODEL_ALIASES: &[(&str, &str)] = &[
+    ("claude-3-opus", "claude-3-opus-20240229"),
+    ("claude-3-sonnet", "claude-3-sonnet-20240229"),
+    ("claude-3-haiku", "claude-3-haiku-20240307"),
+    ("claude-3.5-sonnet", "claude-3-5-sonnet-20241022"),
+    ("claude-3.5-haiku", "claude-3-5-haiku-20241022"),
+    ("claude-opus-4", "claude-opus-4-20250514"),
+    ("claude-sonnet-4", "claude-sonnet-4-20250514"),
+];
+
+/// Rates per token of a model (the Python `ModelPricing`).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub input_above_200k: Option<f64>,
+    pub cache_write: f64,
+    pub cache_write_above_200k: Option<f64>,
+    pub cache_read: f64,
+    pub cache_read_above_200k: Option<f64>,
+    pub output: f64,
+    pub output_above_200k: Option<f64>,
+}
+
+impl ModelPricing {
+    /// Rates of a LiteLLM record; missing or non-numeric base rates are 0.
+    fn from_litellm(record: &Value) -> Self {
+        let rate = |key: &str| record[key].as_f64();
+        Self {
+            input: rate("input_cost_per_token").unwrap_or(0.0),
+            input_above_200k: rate("input_cost_per_token_above_200k_tokens"),
+            cache_write: rate("cache_creation_input_token_cost").unwrap_or(0.0),
+            cache_write_above_200k: rate("cache_creation_input_token_cost_above_200k_tokens"),
+            cache_read: rate("cache_read_input_token_cost").unwrap_or(0.0),
+            cache_read_above_200k: rate("cache_read_input_token_cost_above_200k_tokens"),
+            output: rate("output_cost_per_token").unwrap_or(0.0),
+            output_above_200k: rate("output_cost_per_token_above_200k_tokens"),
+        }
+    }
+
+    /// Cost in USD of the tokens of `entry` (the Python `calculate_cost_usd`).
+    pub fn cost(&self, entry: &LogEntry) -> f64 {
+        tiered_cost(entry.input_tokens, self.input, self.input_above_200k)
+            + tiered_cost(entry.output_tokens, self.output, self.output_above_200k)
+            + tiered_cost(entry.cache_write_tokens, self.cache_write, self.cache_write_above_200k)
+            + tiered_cost(entry.cache_read_tokens, self.cache_read, self.cache_read_above_200k)
+    }
+}
+
+fn tiered_cost(tokens: u64, base: f64, above: Option<f64>) -> f64 {
+    match above {
+        Some(above) if tokens > TIERED_THRESHOLD => {
+            let cost = (tokens - TIERED_THRESHOLD) as f64 * above;
+            if base > 0.0 {
+                cost + TIERED_THRESHOLD as f64 * base
+            } else {
+                cost
+            }
+        }
+        _ if base > 0.0 => tokens as f64 * base,
+        _ => 0.0,
+    }
+}
+
+/// A price version: effective from, effective to (exclusive) and rates.
+type PriceVersion = (String, Option<String>, ModelPricing);
+
+/// Records of the LiteLLM dataset, in file order (the substring lookup
+/// depends on it).
+#[derive(Debug, Default)]
+struct Dataset(Vec<(String, Value)>);
+
+impl<'de> Deserialize<'de> for Dataset {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DatasetVisitor;
+
+        impl<'de> Visitor<'de> for DatasetVisitor {
+            type Value = Dataset;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an object of model records")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Dataset, A::Error> {
+                let mut records = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    records.push(entry);
+                }
+                Ok(Dataset(records))
+            }
+        }
+
+        deserializer.deserialize_map(DatasetVisitor)
+    }
+}
+
+/// Prices of one run (index or scan), loaded once.
+#[derive(Debug, Default)]
+pub struct PriceTable {
+    /// `price_versions` by model, by effective date
+    versions: HashMap<String, Vec<PriceVersion>>,
+    dataset: Dataset,
+    /// Index of each dataset key
+    keys: HashMap<String, usize>,
+    /// Dataset lookups by model name
+    found: Mutex<HashMap<String, Option<ModelPricing>>>,
+}
+
+impl PriceTable {
+    /// Prices from the pricing cache and Python database in `db_dir`; a
+    /// missing or unreadable file leaves its part out.
+    pub fn load(db_dir: &Path) -> Self {
+        let dataset = match fs::read(db_dir.join(PRICING_CACHE_FILE)) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable {}: {}", PRICING_CACHE_FILE, e);
+                Dataset::default()
+            }),
+            Err(_) => Dataset::default(),
+        };
+        let mut table = Self::from_dataset(dataset);
+        let db = db_dir.join(PYTHON_DB_FILE);
+        if db.exists() {
+            match price_versions(&db) {
+                Ok(versions) => table.versions = versions,
+                Err(e) => log::debug!("No price versions in {}: {}", db.display(), e),
+            }
+        }
+        table
+    }
+
+    fn from_dataset(dataset: Dataset) -> Self {
+        let mut keys = HashMap::new();
+        for (index, (key, _)) in dataset.0.iter().enumerate() {
+            keys.entry(key.clone()).or_insert(index);
+        }
+        Self { dataset, keys, ..Self::default() }
+    }
+
+    /// Rates of `model` on local date `day` (`YYYY-MM-DD`).
+    pub fn pricing_for(&self, model: &str, day: &str) -> Option<ModelPricing> {
+        let version = self.versions.get(model).and_then(|versions| {
+            versions
+                .iter()
+                .find(|(from, to, _)| from.as_str() <= day && to.as_ref().map_or(true, |to| day < to.as_str()))
+        });
+        match version {
+            Some((_, _, pricing)) => Some(*pricing),
+            None => self.find(model),
+        }
+    }
+
+    fn find(&self, model: &str) -> Option<ModelPricing> {
+        let mut found = self.found.lock().unwrap_or_else(|e| e.into_inner());
+        *found.entry(model.to_string()).or_insert_with(|| {
+            let alias = MODEL_ALIASES.iter().find(|(short, _)| *short == model).map(|(_, full)| *full);
+            let mut candidates = vec![model.to_string()];
+            candidates.extend(alias.map(String::from));
+            for prefix in PROVIDER_PREFIXES {
+                candidates.push(format!("{}{}", prefix, model));
+                candidates.extend(alias.map(|alias| format!("{}{}", prefix, alias)));
+            }
+            let model_lower = model.to_lowercase();
+            candidates
+                .iter()
+                .find_map(|candidate| self.keys.get(candidate).copied())
+                .or_else(|| {
+                    self.dataset.0.iter().position(|(key, _)| {
+                        let key_lower = key.to_lowercase();
+                        key_lower.contains(&model_lower) || model_lower.contains(&key_lower)
+                    })
+                })
+                .map(|index| ModelPricing::from_litellm(&self.dataset.0[index].1))
+        })
+    }
+
+    /// List price of the tokens of `entry` (null for unpriced models); the
+    /// `api_equivalent_cost` of the message.
+    pub fn list_price(&self, entry: &LogEntry) -> Option<f64> {
+        let model = entry.model.as_deref().filter(|model| !model.is_empty())?;
+        let day = entry.local.format("%Y-%m-%d").to_string();
+        self.pricing_for(model, &day).map(|pricing| pricing.cost(entry))
+    }
+
+    /// Cost of `entry`: the logged `costUSD`, else its list price.
+    pub fn cost(&self, entry: &LogEntry) -> Option<f64> {
+        entry.cost_usd.or_else(|| self.list_price(entry))
+    }
+}
+
+fn price_versions(db: &Path) -> rusqlite::Result<HashMap<String, Vec<PriceVersion>>> {
+    let conn = Connection::open_with_flags(db, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)?;
+    let mut stmt = conn.prepare(
+        "SELECT model, effective_from, effective_to,
+                input_cost_per_token, input_cost_per_token_above_200k,
+                cache_creation_cost_per_token, cache_creation_cost_per_token_above_200k,
+                cached_input_cost_per_token, cached_input_cost_per_token_above_200k,
+                output_cost_per_token, output_cost_per_token_above_200k
+         FROM price_versions ORDER BY model, effective_from",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let pricing = ModelPricing {
+            input: row.get::<_, Option<f64>>(3)?.unwrap_or(0.0),
+            input_above_200k: row.get(4)?,
+            cache_write: row.get::<_, Option<f64>>(5)?.unwrap_or(0.0),
+            cache_write_above_200k: row.get(6)?,
+            cache_read: row.get::<_, Option<f64>>(7)?.unwrap_or(0.0),
+            cache_read_above_200k: row.get(8)?,
+            output: row.get::<_, Option<f64>>(9)?.unwrap_or(0.0),
+            output_above_200k: row.get(10)?,
+        };
+        Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?, pricing)))
+    })?;
+    let mut versions: HashMap<String, Vec<PriceVersion>> = HashMap::new();
+    for row in rows {
+        let (model, version) = row?;
+        versions.entry(model).or_default().push(version);
+    }
+    Ok(versions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(model: &str, input: u64, output: u64, cache_read: u64, cache_write: u64) -> LogEntry {
+        let at = chrono::DateTime::parse_from_rfc3339("2025-06-10T12:00:00Z").unwrap();
+        LogEntry {
+            key: "msg_1:req_1".into(),
+            session_id: None,
+            timestamp: at.to_rfc3339(),
+            local: at.with_timezone(&chrono::Local),
+            model: Some(model.into()),
+            input_tokens: input,
+            output_tokens: output,
+            cache_read_tokens: cache_read,
+            cache_write_tokens: cache_write,
+            cost_usd: None,
+        }
+    }
+
+    #[test]
+    fn test_lookup_and_tiered_cost() {
+        let dataset = r#"{
+            "sample_spec": {"input_cost_per_token": 0.0, "litellm_provider": "one of the providers"},
+            "claude-haiku-4-5-20251001": {"input_cost_per_token": 1e-06, "output_cost_per_token": 5e-06},
+            "anthropic/claude-sonnet-4-20250514": {
+                "input_cost_per_token": 3e-06, "input_cost_per_token_above_200k_tokens": 6e-06,
+                "cache_read_input_token_cost": 3e-07
+            }
+        }"#;
+        let table = PriceTable::from_dataset(serde_json::from_str(dataset).unwrap());
+
+        // Alias with a provider prefix; input above 200k at the higher rate
+        let sonnet = entry("claude-sonnet-4", 250_000, 0, 1_000, 0);
+        let cost = table.list_price(&sonnet).unwrap();
+        assert!((cost - (200_000.0 * 3e-6 + 50_000.0 * 6e-6 + 1_000.0 * 3e-7)).abs() < 1e-12);
+        // Substring of a dataset key
+        assert_eq!(table.list_price(&entry("claude-haiku-4-5", 1_000, 100, 0, 0)), Some(1_000.0 * 1e-6 + 100.0 * 5e-6));
+        assert_eq!(table.list_price(&entry("gpt-4o", 1_000, 0, 0, 0)), None);
+
+        // The logged cost wins
+        let logged = LogEntry { cost_usd: Some(0.5), ..sonnet };
+        assert_eq!(table.cost(&logged), Some(0.5));
+
+        // Price versions come before the dataset, on their dates only
+        let mut versioned = PriceTable::from_dataset(serde_json::from_str(dataset).unwrap());
+        let manual = ModelPricing { input: 1e-5, ..ModelPricing::default() };
+        versioned.versions.insert(
+            "claude-haiku-4-5".into(),
+            vec![("2025-06-01".into(), Some("2025-06-10".into()), manual), ("2025-06-10".into(), None, manual)],
+        );
+        assert_eq!(versioned.pricing_for("claude-haiku-4-5", "2025-05-31").unwrap().input, 1e-6);
+        assert_eq!(versioned.pricing_for("claude-haiku-4-5", "2025-06-10").unwrap().input, 1e-5);
+    }
+}
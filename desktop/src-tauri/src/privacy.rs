@@ -0,0 +1,127 @@
+/// Privacy mode: no message content leaves the backend
+///
+/// While the `privacy_mode` setting is on, every backend response is scrubbed
+/// here before it reaches the UI (`call_python_api`), raw session streams are
+/// scrubbed record by record and raw session archives are always exported
+/// redacted. Prompts, responses, thinking, tool input/output and text derived
+/// from them (limit summaries, description suggestions) are replaced with
+/// `[redacted]`; ids, models, timestamps, token counts and costs stay, so
+/// every chart and table keeps working.
+///
+/// Scrubbing happens on the way out: cached responses keep their content and
+/// turning the mode off needs no refresh.
+use serde_json::Value;
+
+use crate::settings::Settings;
+
+/// Replacement of stripped text (the backend's session archive redaction uses the same).
+pub const REDACTED: &str = "[redacted]";
+
+/// Keys whose string values are message text; other values are scrubbed as usual.
+const TEXT_KEYS: &[&str] = &[
+    "text",
+    "thinking",
+    "prompt",
+    "first_prompt",
+    "last_prompt",
+    "summary",
+    "output",
+    "suggestion",
+];
+
+/// Keys whose whole value is message content (blocks, tool input, extracted topics).
+const CONTENT_KEYS: &[&str] = &["content", "input", "toolUseResult", "keywords", "summaries"];
+
+/// Keys kept inside content so its shape stays visible (block type, tool name and ids).
+const STRUCTURAL_KEYS: &[&str] = &["type", "id", "name", "tool_use_id", "role"];
+
+/// Whether privacy mode is on.
+pub fn enabled() -> bool {
+    Settings::load().privacy_mode.unwrap_or(false)
+}
+
+/// Strip message content from a JSON value in place.
+pub fn scrub(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if CONTENT_KEYS.contains(&key.as_str()) {
+                    scrub_content(value);
+                } else if value.is_string() && TEXT_KEYS.contains(&key.as_str()) {
+                    *value = Value::from(REDACTED);
+                } else {
+                    scrub(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(scrub),
+        _ => {}
+    }
+}
+
+/// Replace every string of content except its structural keys; numbers stay.
+fn scrub_content(value: &mut Value) {
+    match value {
+        Value::String(_) => *value = Value::from(REDACTED),
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if !STRUCTURAL_KEYS.contains(&key.as_str()) {
+                    scrub_content(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(scrub_content),
+        _ => {}
+    }
+}
+
+/// Scrubbed copy of a raw JSONL line, or `None` for lines that are not JSON
+/// (they may contain anything).
+pub fn scrub_line(line: &str) -> Option<String> {
+    let mut record: Value = serde_json::from_str(line.trim()).ok()?;
+    scrub(&mut record);
+    Some(record.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_scrub_keeps_metadata() {
+        let mut record = json!({
+            "sessionId": "s1",
+            "summary": "Fixing the parser",
+            "message": {
+                "role": "assistant",
+                "model": "claude-sonnet-4",
+                "usage": {"input_tokens": 12, "output_tokens": 40},
+                "content": [
+                    {"type": "text", "text": "Here is the fix"},
+                    {"type": "tool_use", "id": "t1", "name": "Bash", "input": {"command": "cargo test"}}
+                ]
+            },
+            "budget": {"summary": {"periods": 5, "over_budget": 2}}
+        });
+        scrub(&mut record);
+
+        assert_eq!(record["sessionId"], "s1");
+        assert_eq!(record["summary"], REDACTED);
+        assert_eq!(record["message"]["model"], "claude-sonnet-4");
+        assert_eq!(record["message"]["usage"]["output_tokens"], 40);
+        assert_eq!(record["message"]["content"][0], json!({"type": "text", "text": REDACTED}));
+        assert_eq!(record["message"]["content"][1]["name"], "Bash");
+        assert_eq!(record["message"]["content"][1]["input"]["command"], REDACTED);
+        assert_eq!(record["budget"]["summary"]["periods"], 5);
+    }
+
+    #[test]
+    fn test_scrub_line() {
+        let line = r#"{"type":"user","message":{"role":"user","content":"secret plan"}}"#;
+        let scrubbed: Value = serde_json::from_str(&scrub_line(line).unwrap()).unwrap();
+        assert_eq!(scrubbed["message"]["content"], REDACTED);
+        assert_eq!(scrubbed["message"]["role"], "user");
+        assert_eq!(scrub_line("not json: secret plan"), None);
+    }
+}
@@ -1,94 +1,2567 @@
 /// Python CLI bridge for Tauri commands
 ///
 /// This module handles executing the Python tauri_api module and parsing JSON responses.
-use serde_json::Value;
-use std::process::Command;
+/// Calls go to a long-lived `tauri_api serve` worker when it is free, and to a
+/// one-shot Python process otherwise. Every call has a timeout and can be
+/// cancelled by request ID; the Python process doing the work is then killed.
+/// At most `COMMAND_CENTER_MAX_CONCURRENT_CALLS` calls run at once, and
+/// identical read calls made while one is running share its result.
+/// A backend bundled with the app (frozen sidecar binary or embedded
+/// interpreter) is preferred over virtualenvs found near the data directory,
+/// which are preferred over the system Python; once a backend has worked it
+/// is tried first. Refresh progress the
+/// backend reports while a call runs is passed to the caller's progress sink,
+/// and streamed calls pass the chunks of their result to a chunk sink as the
+/// backend writes them (newline-delimited JSON) instead of buffering the list.
+/// Everything else the backend writes to stderr is logged under the `python`
+/// target and, when enabled, emitted as `backend-log` events.
+/// In privacy mode message content is stripped from every response (`privacy`).
+/// One-shot processes of `STDIN_JSON_COMMANDS`, and of calls with long
+/// arguments, get their arguments as JSON on stdin (`--stdin-json`) instead
+/// of argv; the worker gets every request that way.
+/// Every Python process the bridge starts is tracked by PID, and `shutdown`
+/// kills the ones still running when the app exits.
+use serde_json::{json, Value};
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hasher};
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, TryLockError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::error::CommandError;
+use crate::presentation::PresentationMode;
+use crate::privacy;
+use crate::response_cache;
+use crate::settings::{db_dir, Settings};
+use crate::telemetry;
+
+/// Environment variable carrying the request ID into the Python process.
+pub const REQUEST_ID_ENV: &str = "COMMAND_CENTER_REQUEST_ID";
+
+/// Set to `0` to run every call in a fresh Python process instead of the worker.
+pub const WORKER_ENV: &str = "COMMAND_CENTER_PYTHON_WORKER";
+
+/// Log target of forwarded backend stderr lines.
+pub const BACKEND_LOG_TARGET: &str = "python";
+
+/// Event carrying a forwarded backend stderr line (see `set_backend_log_events`).
+pub const BACKEND_LOG_EVENT: &str = "backend-log";
+
+/// Set to `1` for one-shot processes, which then write refresh progress as
+/// `{"progress": {...}}` lines to stderr; the worker sends it on its stdout.
+const PROGRESS_ENV: &str = "COMMAND_CENTER_PROGRESS";
+
+/// Receives the refresh progress events of a call
+/// (`{"mode", "state", "files_done", "files_total", "percent"}`).
+pub type ProgressSink<'a> = &'a dyn Fn(Value);
+
+/// Set to `1` for one-shot processes of streamed calls, which then write the
+/// chunks of their result as `{"chunk": {...}}` lines to stdout before it.
+const STREAM_ENV: &str = "COMMAND_CENTER_STREAM";
+
+/// Receives the result chunks of a streamed call (`{"key", "seq", "items"}`).
+pub type ChunkSink<'a> = &'a dyn Fn(Value);
+
+/// What the backend reports while a call runs.
+enum BackendEvent {
+    Progress(Value),
+    Chunk(Value),
+}
+
+type EventSink<'a> = &'a dyn Fn(BackendEvent);
+
+/// Maximum length of a caller-supplied request ID.
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+/// Set to `0` to ignore the bundled backend and use the system Python.
+pub const SIDECAR_ENV: &str = "COMMAND_CENTER_SIDECAR";
+
+/// File name (without `.exe`) of the frozen backend sidecar, installed next
+/// to the app executable (`bundle.externalBin` in `tauri.sidecar.conf.json`).
+pub const SIDECAR_NAME: &str = "command-center-backend";
+
+/// Directory of an embedded Python interpreter under the app's resource
+/// directory, with `command_center` installed into its site-packages.
+pub const EMBEDDED_PYTHON_DIR: &str = "python";
+
+/// Directory of a `command_center` checkout whose virtualenv is tried first.
+pub const PROJECT_DIR_ENV: &str = "COMMAND_CENTER_PROJECT_DIR";
+
+/// Virtualenv directories looked for in project directories.
+const VENV_DIRS: &[&str] = &[".venv", "venv"];
+
+/// Environment of the package under `uv tool dir` (`uv tool install`).
+const UV_TOOL_NAME: &str = "command-center";
+
+/// System Python interpreters tried in turn after the bundled backend and virtualenvs.
+#[cfg(not(windows))]
+const PYTHON_COMMANDS: &[&str] = &["python", "python3", "uv run python"];
+
+/// On Windows the `py` launcher comes first: `python` may be the Microsoft
+/// Store alias, which only offers to install Python.
+#[cfg(windows)]
+const PYTHON_COMMANDS: &[&str] = &["py -3", "python", "python3", "uv run python"];
+
+/// Exit code of the Microsoft Store `python`/`python3` alias when Python is not installed.
+const STORE_STUB_EXIT_CODE: i32 = 9009;
+
+/// A process without a console window: on Windows every child of the GUI app
+/// would otherwise flash one.
+fn new_command(program: impl AsRef<std::ffi::OsStr>) -> Command {
+    #[allow(unused_mut)]
+    let mut command = Command::new(program);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command
+}
+
+/// A way to start the `tauri_api` backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Backend {
+    /// Frozen backend binary shipped as a Tauri sidecar; takes `tauri_api` arguments directly
+    Sidecar(PathBuf),
+    /// Python interpreter bundled in the app resources
+    Embedded(PathBuf),
+    /// Interpreter of a virtualenv (`.venv`, Poetry or uv tool environment)
+    Virtualenv(PathBuf),
+    /// Interpreter command from `PYTHON_COMMANDS`
+    System(&'static str),
+}
+
+impl Backend {
+    /// Command running `tauri_api`, without its arguments.
+    fn command(&self) -> Command {
+        let mut command = self.program();
+        if !matches!(self, Backend::Sidecar(_)) {
+            command.arg("-m").arg("command_center.tauri_api");
+        }
+        command
+    }
+
+    /// The program itself: the sidecar binary or the Python interpreter.
+    fn program(&self) -> Command {
+        match self {
+            Backend::Sidecar(path) | Backend::Embedded(path) | Backend::Virtualenv(path) => new_command(path),
+            Backend::System(python_cmd) => {
+                let mut parts = python_cmd.split_whitespace();
+                let mut command = new_command(parts.next().unwrap_or("python"));
+                command.args(parts);
+                command
+            }
+        }
+    }
+
+    /// Where the program is: its path, or for a system command the first
+    /// match on `PATH` (like `which`). None if it does not exist.
+    fn locate(&self) -> Option<PathBuf> {
+        match self {
+            Backend::Sidecar(path) | Backend::Embedded(path) | Backend::Virtualenv(path) => {
+                path.is_file().then(|| path.clone())
+            }
+            Backend::System(python_cmd) => {
+                let program = python_cmd.split_whitespace().next()?;
+                let path = std::env::var_os("PATH")?;
+                std::env::split_paths(&path)
+                    .map(|dir| dir.join(format!("{}{}", program, std::env::consts::EXE_SUFFIX)))
+                    .find(|candidate| candidate.is_file())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Backend::Sidecar(_) => write!(f, "sidecar"),
+            Backend::Embedded(_) => write!(f, "embedded python"),
+            Backend::Virtualenv(path) => write!(f, "virtualenv {}", path.display()),
+            Backend::System(python_cmd) => write!(f, "{}", python_cmd),
+        }
+    }
+}
+
+/// Backend bundled with the app, found at startup by `configure_bundled_backend`.
+static BUNDLED_BACKEND: OnceLock<Option<Backend>> = OnceLock::new();
+
+/// Find the backend bundled with the app: the sidecar next to the executable,
+/// else an interpreter under `<resource_dir>/python`.
+pub fn find_bundled_backend(exe_dir: &Path, resource_dir: Option<&Path>) -> Option<Backend> {
+    let sidecar = exe_dir.join(format!("{}{}", SIDECAR_NAME, std::env::consts::EXE_SUFFIX));
+    if sidecar.is_file() {
+        return Some(Backend::Sidecar(sidecar));
+    }
+    let python_dir = resource_dir?.join(EMBEDDED_PYTHON_DIR);
+    let interpreter = if cfg!(windows) {
+        python_dir.join("python.exe")
+    } else {
+        python_dir.join("bin").join("python3")
+    };
+    interpreter.is_file().then_some(Backend::Embedded(interpreter))
+}
+
+/// Record the bundled backend (None if the app ships without one). Only the
+/// first call has an effect.
+pub fn configure_bundled_backend(backend: Option<Backend>) {
+    match &backend {
+        Some(Backend::Sidecar(path) | Backend::Embedded(path)) => {
+            log::info!("Bundled Python backend: {}", path.display())
+        }
+        _ => log::info!("No bundled Python backend; using the system Python"),
+    }
+    let _ = BUNDLED_BACKEND.set(backend);
+}
+
+/// The bundled backend, unless disabled with `COMMAND_CENTER_SIDECAR=0`.
+fn bundled_backend() -> Option<&'static Backend> {
+    let enabled = std::env::var(SIDECAR_ENV).map(|v| v.trim() != "0").unwrap_or(true);
+    BUNDLED_BACKEND.get()?.as_ref().filter(|_| enabled)
+}
+
+/// Interpreter of a virtualenv directory.
+fn venv_interpreter(venv: &Path) -> PathBuf {
+    if cfg!(windows) {
+        venv.join("Scripts").join("python.exe")
+    } else {
+        venv.join("bin").join("python")
+    }
+}
+
+/// Trimmed stdout of a successful discovery command, optionally run in `dir`.
+fn discovery_output(program: &str, args: &[&str], dir: Option<&Path>) -> Option<String> {
+    let mut command = new_command(program);
+    command.args(args);
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let output = run_probe(command, program).ok()?;
+    let stdout = output.stdout.trim();
+    (output.success && !stdout.is_empty()).then(|| stdout.to_string())
+}
+
+/// Find virtualenv interpreters: `.venv` or `venv` in each project
+/// directory, the Poetry environment of directories with a `pyproject.toml`
+/// (`poetry env info --executable`) and the `uv tool install` environment.
+pub fn find_virtualenvs(project_dirs: &[PathBuf], uv_tool_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut found: Vec<PathBuf> = Vec::new();
+    for dir in project_dirs {
+        let mut candidates: Vec<PathBuf> = VENV_DIRS.iter().map(|name| venv_interpreter(&dir.join(name))).collect();
+        if dir.join("pyproject.toml").is_file() {
+            candidates.extend(discovery_output("poetry", &["env", "info", "--executable"], Some(dir)).map(PathBuf::from));
+        }
+        for interpreter in candidates {
+            if interpreter.is_file() && !found.contains(&interpreter) {
+                found.push(interpreter);
+            }
+        }
+    }
+    if let Some(interpreter) = uv_tool_dir.map(|dir| venv_interpreter(&dir.join(UV_TOOL_NAME))) {
+        if interpreter.is_file() && !found.contains(&interpreter) {
+            found.push(interpreter);
+        }
+    }
+    found
+}
+
+/// A directory and its parents up to its project root, the first of them
+/// with a `pyproject.toml` or `.git`; just the directory outside a project.
+fn project_ancestors(dir: &Path) -> Vec<PathBuf> {
+    let root = dir
+        .ancestors()
+        .position(|ancestor| ancestor.join("pyproject.toml").is_file() || ancestor.join(".git").exists());
+    dir.ancestors().take(root.map_or(1, |i| i + 1)).map(Path::to_path_buf).collect()
+}
+
+/// Directories searched for virtualenvs: `COMMAND_CENTER_PROJECT_DIR`, the
+/// working directory and its parents up to the project root, then the data
+/// directory up to the home directory (`~/.claude/db`, `~/.claude`, `~`).
+fn project_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os(PROJECT_DIR_ENV).map(PathBuf::from).into_iter().collect();
+    if let Ok(cwd) = std::env::current_dir() {
+        dirs.extend(project_ancestors(&cwd));
+    }
+    if let Ok(data_dir) = db_dir() {
+        dirs.extend(data_dir.ancestors().take(3).map(Path::to_path_buf));
+    }
+    let mut unique = Vec::new();
+    for dir in dirs {
+        if !unique.contains(&dir) {
+            unique.push(dir);
+        }
+    }
+    unique
+}
+
+/// Cache of the virtualenv search in the data directory, so app starts skip
+/// the `poetry` and `uv` probes (delete it to search again).
+const VIRTUALENV_CACHE_FILE: &str = "command-center-virtualenvs.json";
+
+/// Age after which the cached search is redone.
+const VIRTUALENV_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Interpreters cached for the same searched directories, unless the cache
+/// is stale or one of them no longer exists.
+fn cached_virtualenvs(cache: &Path, searched: &[PathBuf]) -> Option<Vec<PathBuf>> {
+    let age = std::fs::metadata(cache).ok()?.modified().ok()?.elapsed().ok()?;
+    if age > VIRTUALENV_CACHE_TTL {
+        return None;
+    }
+    let cached: Value = serde_json::from_slice(&std::fs::read(cache).ok()?).ok()?;
+    let paths = |key: &str| -> Option<Vec<PathBuf>> {
+        cached[key].as_array()?.iter().map(|path| path.as_str().map(PathBuf::from)).collect()
+    };
+    let found = paths("found")?;
+    (paths("searched")? == searched && found.iter().all(|interpreter| interpreter.is_file())).then_some(found)
+}
+
+/// Virtualenvs found on first use, from the cache if it is fresh (restart the
+/// app to pick up new ones).
+static VIRTUALENVS: OnceLock<Vec<PathBuf>> = OnceLock::new();
+
+fn virtualenvs() -> &'static [PathBuf] {
+    VIRTUALENVS.get_or_init(|| {
+        let searched = project_dirs();
+        let cache = db_dir().map(|dir| dir.join(VIRTUALENV_CACHE_FILE)).ok();
+        if let Some(found) = cache.as_deref().and_then(|cache| cached_virtualenvs(cache, &searched)) {
+            return found;
+        }
+        let uv_tool_dir = discovery_output("uv", &["tool", "dir"], None).map(PathBuf::from);
+        let found = find_virtualenvs(&searched, uv_tool_dir.as_deref());
+        for interpreter in &found {
+            log::info!("Found virtualenv Python: {}", interpreter.display());
+        }
+        if let Some(cache) = cache {
+            let entry = json!({"searched": searched, "found": found});
+            if let Err(e) = crate::atomic::write_atomic(&cache, entry.to_string().as_bytes()) {
+                log::warn!("Failed to cache the virtualenv search: {}", e);
+            }
+        }
+        found
+    })
+}
+
+/// The backend that last ran `tauri_api`; tried first by later calls.
+static PREFERRED_BACKEND: Mutex<Option<Backend>> = Mutex::new(None);
+
+fn remember_backend(backend: &Backend) {
+    let mut preferred = PREFERRED_BACKEND.lock().unwrap_or_else(|e| e.into_inner());
+    if preferred.as_ref() != Some(backend) {
+        log::info!("Using Python backend: {}", backend);
+        *preferred = Some(backend.clone());
+    }
+}
+
+fn preferred_backend() -> Option<Backend> {
+    PREFERRED_BACKEND.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Backends tried in turn: the bundled one, virtualenvs, then the system
+/// Python; the one that last worked is moved to the front.
+fn backends() -> Vec<Backend> {
+    let mut backends: Vec<Backend> = bundled_backend()
+        .cloned()
+        .into_iter()
+        .chain(virtualenvs().iter().cloned().map(Backend::Virtualenv))
+        .chain(PYTHON_COMMANDS.iter().map(|&python_cmd| Backend::System(python_cmd)))
+        .collect();
+    if let Some(index) = preferred_backend().and_then(|preferred| backends.iter().position(|b| *b == preferred)) {
+        let preferred = backends.remove(index);
+        backends.insert(0, preferred);
+    }
+    backends
+}
+
+static REQUEST_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Generate a new request ID (`rq-<unix millis hex>-<counter hex>`).
+pub fn new_request_id() -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let counter = REQUEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("rq-{:x}-{:x}", millis, counter)
+}
+
+/// Use the caller's request ID if it is valid, otherwise generate one.
+///
+/// Valid IDs are 1-64 characters of ASCII letters, digits, `-`, `_`, `.` or `:`,
+/// so they can be logged and passed through the environment unchanged.
+pub fn resolve_request_id(request_id: Option<String>) -> String {
+    match request_id {
+        Some(id)
+            if !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LEN
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')) =>
+        {
+            id
+        }
+        _ => new_request_id(),
+    }
+}
+
+/// Subcommands whose repeated execution is not safe (e.g. a second delete
+/// reports "not found" after the first one succeeded).
+///
+/// They are only retried when the backend was busy, i.e. the failure happened
+/// before any change was committed; truncated output is never retried.
+const NON_IDEMPOTENT_COMMANDS: &[&str] = &[
+    "create-snapshot",
+    "delete-snapshot",
+    "delete-cost-center",
+    "test-hook",
+    "shift-timestamps",
+    "undo-timestamp-shift",
+];
+
+/// Flag telling a one-shot process to read `{"args": [...], "request_id": ...}` from stdin.
+pub const STDIN_JSON_FLAG: &str = "--stdin-json";
+
+/// Subcommands with free-text arguments that are always passed on stdin,
+/// away from argv length limits and quoting (e.g. Windows command lines).
+const STDIN_JSON_COMMANDS: &[&str] = &["update-project"];
+
+/// Calls whose arguments are longer than this are passed on stdin whatever
+/// the subcommand (Linux caps one argument at 128 KiB, Windows a command line at 32 KiB).
+const MAX_ARGV_BYTES: usize = 16 * 1024;
+
+/// JSON request for a one-shot process's stdin, if the call passes its arguments there.
+fn stdin_request(request_id: &str, args: &[&str]) -> Option<String> {
+    let subcommand = args.first().copied().unwrap_or("");
+    let argv_bytes: usize = args.iter().map(|arg| arg.len() + 1).sum();
+    (STDIN_JSON_COMMANDS.contains(&subcommand) || argv_bytes > MAX_ARGV_BYTES)
+        .then(|| json!({"args": args, "request_id": request_id}).to_string())
+}
+
+/// Subcommands that scan or rewrite a lot of data; they get the long timeout,
+/// as does `dashboard --refresh 1`.
+const LONG_RUNNING_COMMANDS: &[&str] = &[
+    "refresh",
+    "export-raw-sessions",
+    "create-backup",
+    "verify-backup",
+    "restore-backup",
+    "import-session-archive",
+    "merge-data",
+    "shift-timestamps",
+    "undo-timestamp-shift",
+    "reprice",
+    "generate-demo-data",
+    "generate-sample-data",
+    "benchmark",
+];
+
+/// Read-only subcommands whose identical in-flight calls are coalesced.
+///
+/// Writes are never coalesced: sharing the result of an earlier identical
+/// write would reorder it against a different write queued in between.
+const COALESCED_COMMANDS: &[&str] = &[
+    "dashboard",
+    "refresh-status",
+    "day",
+    "model",
+    "session",
+    "sessions",
+    "limits",
+    "limit-impact",
+    "mcp-usage",
+    "hook-stats",
+    "effort-breakdown",
+    "concurrency-stats",
+    "outcome-stats",
+    "compaction-stats",
+    "usage-accounts",
+    "compare-accounts",
+    "account-dashboard",
+    "budget-history",
+    "derived-metrics",
+    "available-metrics",
+    "metric-series",
+    "cost-allocation",
+    "snapshots",
+    "snapshot",
+    "diff-snapshots",
+    "projects",
+    "project-timeline",
+    "stale-projects",
+    "workspaces",
+    "mirror-status",
+    "schema-info",
+];
+
+/// Default number of Python calls running at once (`COMMAND_CENTER_MAX_CONCURRENT_CALLS`).
+const DEFAULT_MAX_CONCURRENT_CALLS: usize = 4;
+
+/// How often a waiting call checks its deadline and cancellation flag.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// stderr fragments of failures that are worth retrying; more can be added
+/// with `COMMAND_CENTER_RETRY_PATTERNS` (separated by `;`).
+const TRANSIENT_STDERR: &[&str] = &[
+    "database is locked",
+    "database is busy",
+    "database table is locked",
+    "Resource temporarily unavailable",
+    "Text file busy",
+    "being used by another process",
+];
+
+/// Retry policy for transient bridge failures.
+///
+/// Opt-in: every call runs once unless `COMMAND_CENTER_RETRY_ATTEMPTS` or the
+/// `retry_attempts` setting asks for more attempts. Delays are configured with
+/// `COMMAND_CENTER_RETRY_BASE_MS` and `COMMAND_CENTER_RETRY_MAX_MS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            base_delay_ms: 100,
+            max_delay_ms: 2_000,
+        }
+    }
+}
+
+/// Numeric setting from the environment (None if unset or invalid).
+fn env_var<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().and_then(|v| v.trim().parse().ok())
+}
+
+impl RetryPolicy {
+    /// Read the policy from the environment (attempts also from the settings),
+    /// falling back to defaults.
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            max_attempts: env_var("COMMAND_CENTER_RETRY_ATTEMPTS")
+                .or_else(|| Settings::load().retry_attempts)
+                .unwrap_or(default.max_attempts)
+                .clamp(1, 10),
+            base_delay_ms: env_var("COMMAND_CENTER_RETRY_BASE_MS").unwrap_or(default.base_delay_ms),
+            max_delay_ms: env_var("COMMAND_CENTER_RETRY_MAX_MS").unwrap_or(default.max_delay_ms),
+        }
+    }
+
+    /// Delay before retry number `retry` (1-based): full jitter over an
+    /// exponentially growing window capped at `max_delay_ms`.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let window = self
+            .base_delay_ms
+            .saturating_mul(1u64 << retry.min(16))
+            .min(self.max_delay_ms);
+        Duration::from_millis(jitter(window))
+    }
+}
+
+/// Time limits of bridge calls, covering all attempts of a call.
+///
+/// Configured with `COMMAND_CENTER_TIMEOUT_SECS` (most commands) and
+/// `COMMAND_CENTER_LONG_TIMEOUT_SECS` (refreshes and `LONG_RUNNING_COMMANDS`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutPolicy {
+    pub default_secs: u64,
+    pub long_secs: u64,
+}
+
+impl Default for TimeoutPolicy {
+    fn default() -> Self {
+        Self {
+            default_secs: 60,
+            long_secs: 900,
+        }
+    }
+}
+
+impl TimeoutPolicy {
+    /// Read the policy from the environment, falling back to defaults.
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            default_secs: env_var("COMMAND_CENTER_TIMEOUT_SECS")
+                .unwrap_or(default.default_secs)
+                .max(1),
+            long_secs: env_var("COMMAND_CENTER_LONG_TIMEOUT_SECS")
+                .unwrap_or(default.long_secs)
+                .max(1),
+        }
+    }
+
+    /// Timeout of a call with these arguments.
+    pub fn timeout(&self, args: &[&str]) -> Duration {
+        let secs = if is_long_running(args) {
+            self.long_secs
+        } else {
+            self.default_secs
+        };
+        Duration::from_secs(secs)
+    }
+}
+
+fn is_long_running(args: &[&str]) -> bool {
+    match args.first().copied() {
+        Some("dashboard") => {
+            args.contains(&"--refresh=1") || args.windows(2).any(|pair| pair == ["--refresh", "1"])
+        }
+        Some(subcommand) => LONG_RUNNING_COMMANDS.contains(&subcommand),
+        None => false,
+    }
+}
+
+/// Random value in `0..=max`, seeded from the std hasher's random keys.
+fn jitter(max: u64) -> u64 {
+    if max == 0 {
+        return 0;
+    }
+    RandomState::new().build_hasher().finish() % (max + 1)
+}
+
+/// Why a bridge call failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FailureKind {
+    /// Backend busy (locked database or file); nothing was committed
+    Busy,
+    /// Incomplete JSON on stdout, e.g. from a race with the writer
+    TruncatedOutput,
+    /// Did not finish within its timeout; the Python process was killed
+    Timeout,
+    /// Cancelled with `cancel_request`; the Python process was killed
+    Cancelled,
+    /// Anything else; not retried
+    Permanent,
+}
+
+impl FailureKind {
+    fn is_retryable(self, subcommand: &str) -> bool {
+        match self {
+            FailureKind::Busy => true,
+            FailureKind::TruncatedOutput => !NON_IDEMPOTENT_COMMANDS.contains(&subcommand),
+            FailureKind::Timeout | FailureKind::Cancelled | FailureKind::Permanent => false,
+        }
+    }
+}
+
+/// The built-in transient fragments plus the `;`-separated ones in `extra`.
+fn parse_patterns(extra: &str) -> Vec<String> {
+    TRANSIENT_STDERR
+        .iter()
+        .map(|fragment| fragment.to_string())
+        .chain(extra.split(';').map(str::trim).filter(|p| !p.is_empty()).map(str::to_string))
+        .collect()
+}
+
+/// Transient stderr fragments: the built-in ones and `COMMAND_CENTER_RETRY_PATTERNS`
+/// (read once per process).
+fn transient_patterns() -> &'static [String] {
+    static PATTERNS: OnceLock<Vec<String>> = OnceLock::new();
+    PATTERNS.get_or_init(|| parse_patterns(&std::env::var("COMMAND_CENTER_RETRY_PATTERNS").unwrap_or_default()))
+}
+
+fn classify_with(patterns: &[String], stderr: &str) -> FailureKind {
+    if patterns.iter().any(|fragment| stderr.contains(fragment.as_str())) {
+        FailureKind::Busy
+    } else {
+        FailureKind::Permanent
+    }
+}
+
+fn classify_stderr(stderr: &str) -> FailureKind {
+    classify_with(transient_patterns(), stderr)
+}
+
+/// A failed attempt: its class (for retries) and the error for the caller.
+type Failure = (FailureKind, CommandError);
+
+/// Error kind for telemetry: the Python exception type (never the message),
+/// otherwise the failure class.
+fn error_kind(kind: FailureKind, error: &CommandError) -> String {
+    error.exception().map(str::to_string).unwrap_or_else(|| match kind {
+        FailureKind::Busy => "busy".to_string(),
+        FailureKind::TruncatedOutput => "truncated_output".to_string(),
+        FailureKind::Timeout => "timeout".to_string(),
+        FailureKind::Cancelled => "cancelled".to_string(),
+        FailureKind::Permanent => "other".to_string(),
+    })
+}
+
+/// Deadline and cancellation flag of one `call_python_api` invocation.
+struct CallControl {
+    subcommand: String,
+    timeout: Duration,
+    deadline: Instant,
+    cancelled: Arc<AtomicBool>,
+    /// The result is streamed in chunks (`call_python_api_streaming`)
+    stream: bool,
+}
+
+impl CallControl {
+    /// Why the call has to stop now, if it has to.
+    fn stop_reason(&self) -> Option<FailureKind> {
+        if self.cancelled.load(Ordering::Relaxed) || SHUTTING_DOWN.load(Ordering::Relaxed) {
+            Some(FailureKind::Cancelled)
+        } else if Instant::now() >= self.deadline {
+            Some(FailureKind::Timeout)
+        } else {
+            None
+        }
+    }
+
+    /// Error of a stopped call (`Timeout` or `Cancelled`).
+    fn stop_error(&self, kind: FailureKind) -> Failure {
+        let error = if kind == FailureKind::Cancelled {
+            CommandError::cancelled(format!("{} was cancelled", self.subcommand))
+        } else {
+            CommandError::timeout(
+                self.timeout.as_secs(),
+                format!("{} did not finish within {} s", self.subcommand, self.timeout.as_secs()),
+            )
+        };
+        (kind, error)
+    }
+}
+
+/// Cancellation flags of in-flight calls by request ID, with the number of
+/// calls sharing the ID (e.g. the ranges of `get_dashboard_bundles`).
+static IN_FLIGHT: Mutex<BTreeMap<String, (Arc<AtomicBool>, usize)>> = Mutex::new(BTreeMap::new());
+
+/// Registration of a call in `IN_FLIGHT`, removed when dropped.
+struct InFlight {
+    request_id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl InFlight {
+    fn register(request_id: &str) -> Self {
+        let mut calls = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = calls
+            .entry(request_id.to_string())
+            .or_insert_with(|| (Arc::new(AtomicBool::new(false)), 0));
+        entry.1 += 1;
+        Self {
+            request_id: request_id.to_string(),
+            cancelled: entry.0.clone(),
+        }
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        let mut calls = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(entry) = calls.get_mut(&self.request_id) {
+            entry.1 -= 1;
+            if entry.1 == 0 {
+                calls.remove(&self.request_id);
+            }
+        }
+    }
+}
+
+/// Cancel the in-flight calls of a request.
+///
+/// Their Python work is killed and they fail with a `Cancelled` error.
+/// Returns false if no call with this request ID is running.
+pub fn cancel_request(request_id: &str) -> bool {
+    let calls = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+    match calls.get(request_id) {
+        Some((cancelled, _)) => {
+            cancelled.store(true, Ordering::Relaxed);
+            log::info!("[{}] Cancellation requested", request_id);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Cancels the calls of a request when dropped, unless `disarm`ed first.
+///
+/// Held by async commands across an `.await` on backend work (every command
+/// runs its backend calls on a blocking thread through `run_blocking` in
+/// `commands`): when the command's future is dropped (the app shuts down or
+/// the invoke is abandoned) the Python process working for it is killed
+/// instead of running on in the blocking thread.
+pub struct CancelOnDrop {
+    request_id: String,
+    armed: bool,
+}
+
+impl CancelOnDrop {
+    pub fn new(request_id: &str) -> Self {
+        Self {
+            request_id: request_id.to_string(),
+            armed: true,
+        }
+    }
+
+    /// The command finished; nothing to cancel.
+    pub fn disarm(mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if self.armed && cancel_request(&self.request_id) {
+            log::info!("[{}] Command ended before its backend work finished", self.request_id);
+        }
+    }
+}
+
+/// Start a child in its own process group, so killing it also kills the
+/// processes it started.
+fn spawn_tracked(command: &mut Command) -> std::io::Result<Child> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    command.spawn()
+}
+
+/// A child process together with the processes it starts (pool workers,
+/// the interpreter under `uv run`): on Unix the child leads its own process
+/// group (`spawn_tracked`), on Windows it is assigned to a job object.
+/// Processes the child starts before the assignment escape the job.
+struct ProcessTree {
+    child: Child,
+    /// The child was reaped: its PID, and with it the group ID, may now
+    /// belong to another process
+    exited: bool,
+    #[cfg(windows)]
+    job: Option<job_object::Job>,
+}
+
+impl ProcessTree {
+    fn new(child: Child) -> Self {
+        Self {
+            #[cfg(windows)]
+            job: job_object::Job::assign(&child),
+            child,
+            exited: false,
+        }
+    }
+
+    fn try_wait(&mut self) -> std::io::Result<Option<std::process::ExitStatus>> {
+        let status = self.child.try_wait()?;
+        self.exited |= status.is_some();
+        Ok(status)
+    }
+
+    fn kill(&mut self) {
+        #[cfg(unix)]
+        if !self.exited {
+            // SAFETY: signals the group the child leads (an unreaped child keeps
+            // its PID reserved); a child started outside `spawn_tracked` has no
+            // such group and the call fails harmlessly
+            unsafe {
+                libc::killpg(self.child.id() as libc::pid_t, libc::SIGKILL);
+            }
+        }
+        #[cfg(windows)]
+        if let Some(job) = &self.job {
+            job.terminate();
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        self.exited = true;
+    }
+}
+
+#[cfg(windows)]
+mod job_object {
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation, SetInformationJobObject,
+        TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    /// Job object holding a child; closing it kills what is left in it.
+    pub struct Job(HANDLE);
+
+    // SAFETY: a job handle can be used and closed from any thread
+    unsafe impl Send for Job {}
+
+    impl Job {
+        pub fn assign(child: &Child) -> Option<Self> {
+            // SAFETY: the handles are valid for the calls; the job is closed on drop
+            unsafe {
+                let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+                if handle.is_null() {
+                    return None;
+                }
+                let job = Job(handle);
+                let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+                info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                let limited = SetInformationJobObject(
+                    handle,
+                    JobObjectExtendedLimitInformation,
+                    &info as *const JOBOBJECT_EXTENDED_LIMIT_INFORMATION as *const std::ffi::c_void,
+                    std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                ) != 0;
+                (limited && AssignProcessToJobObject(handle, child.as_raw_handle() as HANDLE) != 0).then_some(job)
+            }
+        }
+
+        pub fn terminate(&self) {
+            // SAFETY: the handle is open until drop
+            unsafe {
+                TerminateJobObject(self.0, 1);
+            }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            // SAFETY: closed once, here
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
+
+/// Python processes started by the bridge (the worker and one-shot calls)
+/// by PID, so `shutdown` can kill the ones still running.
+static CHILDREN: Mutex<BTreeMap<u32, Arc<Mutex<ProcessTree>>>> = Mutex::new(BTreeMap::new());
+
+/// Set by `shutdown`: calls stop and no new process is left running.
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// A child process registered in `CHILDREN` until dropped.
+struct TrackedChild {
+    pid: u32,
+    child: Arc<Mutex<ProcessTree>>,
+}
+
+impl TrackedChild {
+    fn new(child: Child) -> Self {
+        let pid = child.id();
+        let child = Arc::new(Mutex::new(ProcessTree::new(child)));
+        CHILDREN
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(pid, child.clone());
+        let tracked = Self { pid, child };
+        // Started while `shutdown` ran: it would outlive the app
+        if SHUTTING_DOWN.load(Ordering::Relaxed) {
+            tracked.kill();
+        }
+        tracked
+    }
+
+    fn id(&self) -> u32 {
+        self.pid
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ProcessTree> {
+        self.child.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn try_wait(&self) -> std::io::Result<Option<std::process::ExitStatus>> {
+        self.lock().try_wait()
+    }
+
+    /// Kill the child and the processes it started.
+    fn kill(&self) {
+        self.lock().kill();
+    }
+}
+
+impl Drop for TrackedChild {
+    fn drop(&mut self) {
+        CHILDREN.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.pid);
+    }
+}
+
+/// PIDs of the Python processes running now.
+fn child_pids() -> Vec<u32> {
+    CHILDREN.lock().unwrap_or_else(|e| e.into_inner()).keys().copied().collect()
+}
+
+/// Stop all Python work before the app exits.
+///
+/// Every in-flight call is cancelled and every process the bridge started
+/// is killed, so a refresh interrupted by closing the app does not keep
+/// running and holding the database lock. Returns the number of processes
+/// killed. Calls made afterwards fail as `Cancelled`.
+pub fn shutdown() -> usize {
+    SHUTTING_DOWN.store(true, Ordering::Relaxed);
+    let children: Vec<_> = CHILDREN
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|(&pid, child)| (pid, child.clone()))
+        .collect();
+    let mut killed = 0;
+    for (pid, child) in children {
+        let mut tree = child.lock().unwrap_or_else(|e| e.into_inner());
+        if matches!(tree.try_wait(), Ok(None)) {
+            log::info!("Killing Python process {} on exit", pid);
+            tree.kill();
+            killed += 1;
+        }
+    }
+    killed
+}
+
+/// Maximum number of Python calls running at once.
+fn max_concurrent_calls() -> usize {
+    env_var("COMMAND_CENTER_MAX_CONCURRENT_CALLS")
+        .unwrap_or(DEFAULT_MAX_CONCURRENT_CALLS)
+        .clamp(1, 64)
+}
+
+/// Number of attempts holding a `Permit`.
+static RUNNING_CALLS: Mutex<usize> = Mutex::new(0);
+static PERMIT_RELEASED: Condvar = Condvar::new();
+
+/// Slot of the concurrency limit, held while an attempt runs Python.
+struct Permit;
+
+impl Permit {
+    /// Wait for a free slot; gives up when the call times out or is cancelled.
+    ///
+    /// Blocks the calling thread, so callers must not be async runtime threads:
+    /// commands reach the bridge through `run_blocking` (`commands`), the HTTP
+    /// server and background tasks from their own threads.
+    fn acquire(control: &CallControl) -> Result<Self, Failure> {
+        let max = max_concurrent_calls();
+        let mut running = RUNNING_CALLS.lock().unwrap_or_else(|e| e.into_inner());
+        if *running >= max {
+            METRICS.queued.fetch_add(1, Ordering::Relaxed);
+        }
+        while *running >= max {
+            if let Some(kind) = control.stop_reason() {
+                return Err(control.stop_error(kind));
+            }
+            running = PERMIT_RELEASED
+                .wait_timeout(running, POLL_INTERVAL)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        *running += 1;
+        Ok(Permit)
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut running = RUNNING_CALLS.lock().unwrap_or_else(|e| e.into_inner());
+        *running -= 1;
+        PERMIT_RELEASED.notify_one();
+    }
+}
+
+/// Result of the attempts of a call and the number of attempts made.
+type Outcome = (Result<Value, Failure>, u32);
+
+/// A running coalesced call; identical calls wait for its outcome.
+#[derive(Default)]
+struct SharedCall {
+    outcome: Mutex<Option<Outcome>>,
+    done: Condvar,
+}
+
+impl SharedCall {
+    /// Wait for the outcome of the running call. None if that call was
+    /// cancelled, so this one has to run by itself.
+    fn wait(&self, control: &CallControl) -> Option<Outcome> {
+        let mut outcome = self.outcome.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            match outcome.as_ref() {
+                Some((Err((FailureKind::Cancelled, _)), _)) => return None,
+                Some(outcome) => return Some(outcome.clone()),
+                None => {}
+            }
+            if let Some(kind) = control.stop_reason() {
+                return Some((Err(control.stop_error(kind)), 1));
+            }
+            outcome = self
+                .done
+                .wait_timeout(outcome, POLL_INTERVAL)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+}
+
+/// Coalesced calls running now, by their arguments and environment.
+static COALESCING: Mutex<BTreeMap<String, Arc<SharedCall>>> = Mutex::new(BTreeMap::new());
+
+/// The call that does the work for a coalescing key. Dropping it
+/// unregisters the key and wakes the waiting calls.
+struct Leader<'a> {
+    key: &'a str,
+    shared: Arc<SharedCall>,
+}
+
+impl Leader<'_> {
+    fn unregister(&self) {
+        COALESCING
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(self.key);
+    }
+
+    /// Publish the outcome; calls arriving from now on run by themselves.
+    fn finish(self, outcome: Outcome) {
+        self.unregister();
+        *self.shared.outcome.lock().unwrap_or_else(|e| e.into_inner()) = Some(outcome);
+    }
+}
+
+impl Drop for Leader<'_> {
+    fn drop(&mut self) {
+        self.unregister();
+        let mut outcome = self.shared.outcome.lock().unwrap_or_else(|e| e.into_inner());
+        if outcome.is_none() {
+            let error = CommandError::internal("Coalesced call did not complete");
+            *outcome = Some((Err((FailureKind::Permanent, error)), 1));
+        }
+        self.shared.done.notify_all();
+    }
+}
+
+/// Coalescing key of a call, or None if it must run by itself.
+fn coalescing_key(args: &[&str], env: &[(&'static str, String)]) -> Option<String> {
+    let subcommand = args.first()?;
+    if !COALESCED_COMMANDS.contains(subcommand) {
+        return None;
+    }
+    Some(format!("{:?}{:?}", args, env))
+}
+
+/// Run `run` unless an identical call is already running, in which case
+/// its outcome is shared.
+fn run_coalesced(key: &str, control: &CallControl, run: impl FnOnce() -> Outcome) -> Outcome {
+    loop {
+        let shared = {
+            let mut calls = COALESCING.lock().unwrap_or_else(|e| e.into_inner());
+            match calls.get(key) {
+                Some(shared) => shared.clone(),
+                None => {
+                    let shared = Arc::new(SharedCall::default());
+                    calls.insert(key.to_string(), shared.clone());
+                    drop(calls);
+                    let leader = Leader { key, shared };
+                    let outcome = run();
+                    leader.finish(outcome.clone());
+                    return outcome;
+                }
+            }
+        };
+        if let Some(outcome) = shared.wait(control) {
+            if control.stop_reason().is_none() {
+                METRICS.coalesced.fetch_add(1, Ordering::Relaxed);
+            }
+            return outcome;
+        }
+    }
+}
+
+/// Bridge call counters, exposed through `get_bridge_metrics`.
+struct BridgeMetrics {
+    calls: AtomicU64,
+    failures: AtomicU64,
+    retries: AtomicU64,
+    recovered: AtomicU64,
+    timeouts: AtomicU64,
+    cancelled: AtomicU64,
+    queued: AtomicU64,
+    coalesced: AtomicU64,
+    worker_spawns: AtomicU64,
+    worker_requests: AtomicU64,
+    worker_crashes: AtomicU64,
+    worker_fallbacks: AtomicU64,
+}
+
+static METRICS: BridgeMetrics = BridgeMetrics {
+    calls: AtomicU64::new(0),
+    failures: AtomicU64::new(0),
+    retries: AtomicU64::new(0),
+    recovered: AtomicU64::new(0),
+    timeouts: AtomicU64::new(0),
+    cancelled: AtomicU64::new(0),
+    queued: AtomicU64::new(0),
+    coalesced: AtomicU64::new(0),
+    worker_spawns: AtomicU64::new(0),
+    worker_requests: AtomicU64::new(0),
+    worker_crashes: AtomicU64::new(0),
+    worker_fallbacks: AtomicU64::new(0),
+};
+
+/// Snapshot of bridge metrics and the active retry policy.
+///
+/// * `calls` - completed `call_python_api` invocations
+/// * `failures` - calls that failed after all attempts
+/// * `retries` - extra attempts made for transient failures
+/// * `recovered` - calls that succeeded after at least one retry
+/// * `retry_policy` - attempts, delays and the stderr `patterns` treated as transient
+/// * `timeouts` / `cancelled` - calls stopped by their timeout or `cancel_request`
+/// * `in_flight` - calls running now
+/// * `concurrency` - `max_concurrent` and `running` Python calls, `queued`
+///   (calls that waited for a free slot) and `coalesced` (calls that shared
+///   the result of an identical running call)
+/// * `backend` - `bundled` path of the sidecar or embedded interpreter (null
+///   if the app ships without one) and whether it is `enabled`
+/// * `worker` - persistent worker state: `enabled`, `pid` (null if not running),
+///   `spawns`, `requests`, `crashes` and `fallbacks` (calls run in a one-shot
+///   process because the worker was busy or could not start)
+/// * `processes` - PIDs of the Python processes running now (worker and one-shot)
+/// * `cache` - response cache `ttl_secs`, `entries`, `hits`, `misses` and `invalidations`
+pub fn bridge_metrics() -> Value {
+    let policy = RetryPolicy::from_env();
+    let timeouts = TimeoutPolicy::from_env();
+    let in_flight: usize = IN_FLIGHT
+        .lock()
+        .map(|calls| calls.values().map(|(_, count)| count).sum())
+        .unwrap_or(0);
+    let running = *RUNNING_CALLS.lock().unwrap_or_else(|e| e.into_inner());
+    let pid = match WORKER.try_lock() {
+        Ok(worker) => worker.as_ref().map(|w| w.child.id()),
+        Err(TryLockError::Poisoned(worker)) => worker.into_inner().as_ref().map(|w| w.child.id()),
+        // Busy with a request, so it is running
+        Err(TryLockError::WouldBlock) => None,
+    };
+    json!({
+        "calls": METRICS.calls.load(Ordering::Relaxed),
+        "failures": METRICS.failures.load(Ordering::Relaxed),
+        "retries": METRICS.retries.load(Ordering::Relaxed),
+        "recovered": METRICS.recovered.load(Ordering::Relaxed),
+        "timeouts": METRICS.timeouts.load(Ordering::Relaxed),
+        "cancelled": METRICS.cancelled.load(Ordering::Relaxed),
+        "in_flight": in_flight,
+        "concurrency": {
+            "max_concurrent": max_concurrent_calls(),
+            "running": running,
+            "queued": METRICS.queued.load(Ordering::Relaxed),
+            "coalesced": METRICS.coalesced.load(Ordering::Relaxed),
+        },
+        "retry_policy": {
+            "max_attempts": policy.max_attempts,
+            "base_delay_ms": policy.base_delay_ms,
+            "max_delay_ms": policy.max_delay_ms,
+            "patterns": transient_patterns(),
+        },
+        "timeout_policy": {
+            "default_secs": timeouts.default_secs,
+            "long_secs": timeouts.long_secs,
+        },
+        "backend": {
+            "bundled": match BUNDLED_BACKEND.get() {
+                Some(Some(Backend::Sidecar(path) | Backend::Embedded(path))) => Some(path.display().to_string()),
+                _ => None,
+            },
+            "enabled": bundled_backend().is_some(),
+            "virtualenvs": virtualenvs().iter().map(|p| p.display().to_string()).collect::<Vec<_>>(),
+            "preferred": preferred_backend().map(|b| b.to_string()),
+        },
+        "worker": {
+            "enabled": worker_enabled(),
+            "pid": pid,
+            "spawns": METRICS.worker_spawns.load(Ordering::Relaxed),
+            "requests": METRICS.worker_requests.load(Ordering::Relaxed),
+            "crashes": METRICS.worker_crashes.load(Ordering::Relaxed),
+            "fallbacks": METRICS.worker_fallbacks.load(Ordering::Relaxed),
+        },
+        "processes": child_pids(),
+        "cache": response_cache::cache_metrics(),
+        "backend_log_events": backend_log_events(),
+    })
+}
+
+/// Whether calls go through the persistent worker (`COMMAND_CENTER_PYTHON_WORKER`, default on).
+fn worker_enabled() -> bool {
+    std::env::var(WORKER_ENV).map(|v| v.trim() != "0").unwrap_or(true)
+}
+
+/// Output of one command run, from a one-shot process or the worker.
+struct RunOutput {
+    success: bool,
+    code: Option<i32>,
+    stdout: String,
+    stderr: String,
+}
+
+/// Receives `backend-log` events: `{"request_id", "backend", "level", "message"}`.
+pub type LogSink = Box<dyn Fn(Value) + Send + Sync>;
+
+static LOG_SINK: OnceLock<LogSink> = OnceLock::new();
+
+static LOG_EVENTS: AtomicBool = AtomicBool::new(false);
+
+/// Install the sink of `backend-log` events (once, at startup).
+pub fn set_log_sink(sink: LogSink) {
+    let _ = LOG_SINK.set(sink);
+}
+
+/// Turn `backend-log` events on or off (off by default); a log viewer turns
+/// them on while it is open. Lines are logged either way.
+pub fn set_backend_log_events(enabled: bool) {
+    LOG_EVENTS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn backend_log_events() -> bool {
+    LOG_EVENTS.load(Ordering::Relaxed)
+}
+
+/// Log level of a backend stderr line: Python logging prefixes
+/// (`WARNING:root:...`), warnings (`file.py:3: DeprecationWarning: ...`),
+/// tracebacks, exception lines and the `{"error": ...}` response are
+/// recognized; other output is info.
+fn stderr_level(line: &str) -> log::Level {
+    let line = line.trim_start();
+    let exception_line = line
+        .split_once(": ")
+        .is_some_and(|(name, _)| name.ends_with("Error") || name.ends_with("Exception"));
+    if line.starts_with("ERROR")
+        || line.starts_with("CRITICAL")
+        || line.starts_with("Traceback (most recent call last)")
+        || line.starts_with("{\"error\"")
+        || exception_line
+    {
+        log::Level::Error
+    } else if line.starts_with("WARNING") || line.contains("Warning: ") {
+        log::Level::Warn
+    } else if line.starts_with("DEBUG") {
+        log::Level::Debug
+    } else {
+        log::Level::Info
+    }
+}
+
+/// Forward the stderr of a run to the log and, if enabled, as `backend-log`
+/// events. Indented lines (traceback frames) keep the level of the line before.
+fn forward_stderr(request_id: &str, backend: &str, stderr: &str) {
+    let mut level = log::Level::Info;
+    for line in stderr.lines().filter(|line| !line.trim().is_empty()) {
+        if !line.starts_with([' ', '\t']) {
+            level = stderr_level(line);
+        }
+        log::log!(target: BACKEND_LOG_TARGET, level, "[{}] {}", request_id, line);
+        if let Some(sink) = LOG_SINK.get().filter(|_| backend_log_events()) {
+            sink(json!({
+                "request_id": request_id,
+                "backend": backend,
+                "level": level.as_str().to_lowercase(),
+                "message": line,
+            }));
+        }
+    }
+}
+
+/// The JSON document in `stdout`, parsed, and the text printed before it.
+///
+/// Packages imported by the backend can print to stdout (deprecation notices,
+/// banners) ahead of the response, which the API prints starting on a line of
+/// its own. The document is the first value that parses from a line beginning
+/// with `{` or `[`. Each candidate is read by one streaming pass that stops at
+/// the end of its first value or at its first error; a candidate cut off by
+/// the end of the output ends the search (later lines are inside it), so a
+/// truncated response is reported as one without re-parsing its tail.
+fn split_json(stdout: &str) -> (&str, Result<Value, serde_json::Error>) {
+    let line_starts = std::iter::once(0).chain(stdout.match_indices('\n').map(|(i, _)| i + 1));
+    let mut first_error = None;
+    for start in line_starts {
+        let rest = &stdout[start..];
+        if !rest.trim_start().starts_with(['{', '[']) {
+            continue;
+        }
+        match serde_json::Deserializer::from_str(rest).into_iter::<Value>().next() {
+            Some(Ok(value)) => return (&stdout[..start], Ok(value)),
+            Some(Err(e)) if e.is_eof() => return (&stdout[..start], Err(e)),
+            Some(Err(e)) => {
+                first_error.get_or_insert((start, e));
+            }
+            None => {}
+        }
+    }
+    match first_error {
+        Some((start, e)) => (&stdout[..start], Err(e)),
+        None => ("", serde_json::from_str(stdout)),
+    }
+}
+
+/// Parse a run's output into the call result.
+///
+/// Busy and truncated-output failures are final for the interpreter that
+/// produced them; `Permanent` failures let `run_python` try the next one.
+/// Text printed to stdout before the JSON document is logged like stderr.
+fn parse_output(request_id: &str, python_cmd: &str, output: &RunOutput) -> Result<Value, Failure> {
+    use log::{debug, info};
+
+    forward_stderr(request_id, python_cmd, &output.stderr);
+
+    if !output.success {
+        debug!("[{}] Failed with {}: {}", request_id, python_cmd, output.stderr);
+        let kind = classify_stderr(&output.stderr);
+        let error = if kind == FailureKind::Busy {
+            CommandError::busy(format!("Backend busy: {}", output.stderr.trim()))
+        } else {
+            CommandError::from_exit(output.code, &output.stderr)
+        };
+        return Err((kind, error));
+    }
+
+    let (preamble, parsed) = split_json(&output.stdout);
+    if !preamble.trim().is_empty() {
+        debug!("[{}] Skipped {} bytes of stdout before the JSON response", request_id, preamble.len());
+        forward_stderr(request_id, python_cmd, preamble);
+    }
+
+    match parsed {
+        Ok(json) => {
+            info!("[{}] API call successful ({} bytes)", request_id, output.stdout.len());
+            Ok(json)
+        }
+        Err(e) => {
+            debug!("[{}] JSON parse error: {}", request_id, e);
+            let kind = if e.is_eof() {
+                FailureKind::TruncatedOutput
+            } else {
+                FailureKind::Permanent
+            };
+            Err((kind, CommandError::parse_error(format!("JSON parse error: {} | stdout: {}", e, output.stdout))))
+        }
+    }
+}
+
+/// Long-lived `tauri_api serve` process exchanging line-delimited JSON
+/// (see `command_center.api_worker`).
+///
+/// The worker is bound to the presentation-mode environment it was started
+/// with (demo mode changes HOME at import), so it is restarted when that
+/// changes. It exits on its own when the app closes its stdin. A reader
+/// thread forwards its stdout lines, so waiting for a response can time out.
+struct PythonWorker {
+    child: TrackedChild,
+    stdin: ChildStdin,
+    responses: Receiver<String>,
+    backend: String,
+    env: Vec<(&'static str, String)>,
+    next_id: u64,
+}
+
+/// Why a worker request failed.
+enum WorkerError {
+    /// The request could not be written; the worker did not receive it
+    Send(String),
+    /// No valid response; the worker may have run the command
+    Receive(String),
+    /// Timed out or cancelled while waiting; the worker has to be killed
+    Stopped(FailureKind),
+}
+
+static WORKER: Mutex<Option<PythonWorker>> = Mutex::new(None);
+
+impl PythonWorker {
+    /// Start a worker with the first backend that answers the ready line.
+    fn spawn(env: &[(&'static str, String)], control: &CallControl) -> Result<Self, String> {
+        let mut last_error = String::from("no Python interpreter");
+        for backend in backends() {
+            let python_cmd = backend.to_string();
+            let mut command = backend.command();
+            command
+                .arg("serve")
+                .envs(env.iter().cloned())
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+            let mut child = match spawn_tracked(&mut command) {
+                Ok(child) => child,
+                Err(e) => {
+                    last_error = format!("Failed to execute {}: {}", python_cmd, e);
+                    continue;
+                }
+            };
+            let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+                let _ = child.kill();
+                let _ = child.wait();
+                continue;
+            };
+            // Output of requests comes with their responses; this is the
+            // worker's own (startup, import warnings)
+            if let Some(stderr) = child.stderr.take() {
+                let backend = python_cmd.clone();
+                std::thread::spawn(move || {
+                    for line in BufReader::new(stderr).lines() {
+                        let Ok(line) = line else { break };
+                        forward_stderr("worker", &backend, &line);
+                    }
+                });
+            }
+            let (sender, responses) = mpsc::channel();
+            std::thread::spawn(move || {
+                for line in BufReader::new(stdout).lines() {
+                    let Ok(line) = line else { break };
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+            let worker = Self {
+                child: TrackedChild::new(child),
+                stdin,
+                responses,
+                backend: python_cmd.clone(),
+                env: env.to_vec(),
+                next_id: 0,
+            };
+            match worker.read_message(control) {
+                Ok(ready) if ready.get("ready").and_then(Value::as_bool) == Some(true) => {
+                    METRICS.worker_spawns.fetch_add(1, Ordering::Relaxed);
+                    remember_backend(&backend);
+                    log::info!("Python worker started ({}, pid {})", worker.backend, worker.child.id());
+                    return Ok(worker);
+                }
+                Ok(other) => last_error = format!("Unexpected worker greeting from {}: {}", python_cmd, other),
+                // Out of time: another interpreter would not get any further
+                Err(WorkerError::Stopped(_)) => return Err(format!("Worker {} did not start in time", python_cmd)),
+                Err(WorkerError::Send(e) | WorkerError::Receive(e)) => {
+                    last_error = format!("Worker {} did not start: {}", python_cmd, e)
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Next message from the worker, waiting until the call's deadline or
+    /// cancellation. Lines that are not JSON objects (printed to stdout
+    /// before `serve` took it over) are logged and skipped.
+    fn read_message(&self, control: &CallControl) -> Result<Value, WorkerError> {
+        loop {
+            if let Some(kind) = control.stop_reason() {
+                return Err(WorkerError::Stopped(kind));
+            }
+            match self.responses.recv_timeout(POLL_INTERVAL) {
+                Ok(line) if !line.trim_start().starts_with('{') => forward_stderr("worker", &self.backend, &line),
+                Ok(line) => {
+                    return serde_json::from_str(&line)
+                        .map_err(|e| WorkerError::Receive(format!("invalid worker response: {}", e)))
+                }
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => return Err(WorkerError::Receive("worker exited".to_string())),
+            }
+        }
+    }
+
+    /// Send one command and wait for its response, passing progress events
+    /// and result chunks on.
+    fn request(
+        &mut self,
+        request_id: &str,
+        args: &[&str],
+        control: &CallControl,
+        on_event: EventSink,
+    ) -> Result<RunOutput, WorkerError> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let line = json!({"id": id, "args": args, "request_id": request_id, "stream": control.stream}).to_string();
+        writeln!(self.stdin, "{}", line)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| WorkerError::Send(e.to_string()))?;
+
+        let response = loop {
+            let mut message = self.read_message(control)?;
+            if message.get("id").and_then(Value::as_u64) != Some(id) {
+                return Err(WorkerError::Receive(format!("response for another request: {}", message)));
+            }
+            if let Some(event) = message.get_mut("progress") {
+                on_event(BackendEvent::Progress(event.take()));
+            } else if let Some(chunk) = message.get_mut("chunk") {
+                on_event(BackendEvent::Chunk(chunk.take()));
+            } else {
+                break message;
+            }
+        };
+        let text = |key: &str| response.get(key).and_then(Value::as_str).unwrap_or("").to_string();
+        let code = response.get("exit_code").and_then(Value::as_i64).map(|code| code as i32);
+        Ok(RunOutput {
+            success: code == Some(0),
+            code,
+            stdout: text("stdout"),
+            stderr: text("stderr"),
+        })
+    }
+}
+
+impl Drop for PythonWorker {
+    fn drop(&mut self) {
+        self.child.kill();
+    }
+}
+
+/// Run one attempt on the persistent worker.
+///
+/// Returns `None` if the worker is busy with another call or cannot be
+/// started; the caller then runs a one-shot process. A worker that died is
+/// restarted, and a request it never received is sent again.
+fn call_worker(
+    request_id: &str,
+    args: &[&str],
+    env: &[(&'static str, String)],
+    control: &CallControl,
+    on_event: EventSink,
+) -> Option<Result<Value, Failure>> {
+    use log::{debug, warn};
+
+    let mut slot = match WORKER.try_lock() {
+        Ok(slot) => slot,
+        Err(TryLockError::Poisoned(slot)) => slot.into_inner(),
+        Err(TryLockError::WouldBlock) => {
+            METRICS.worker_fallbacks.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+    };
+
+    for _ in 0..2 {
+        let stale = match slot.as_mut() {
+            Some(worker) => !worker.is_alive() || worker.env != env,
+            None => false,
+        };
+        if stale {
+            debug!("[{}] Restarting Python worker", request_id);
+            *slot = None;
+        }
+        if slot.is_none() {
+            match PythonWorker::spawn(env, control) {
+                Ok(worker) => *slot = Some(worker),
+                Err(e) => {
+                    if let Some(kind) = control.stop_reason() {
+                        return Some(Err(control.stop_error(kind)));
+                    }
+                    warn!("[{}] Python worker unavailable: {}", request_id, e);
+                    METRICS.worker_fallbacks.fetch_add(1, Ordering::Relaxed);
+                    return None;
+                }
+            }
+        }
+
+        let worker = slot.as_mut()?;
+        METRICS.worker_requests.fetch_add(1, Ordering::Relaxed);
+        match worker.request(request_id, args, control, on_event) {
+            Ok(output) => return Some(parse_output(request_id, &worker.backend, &output)),
+            Err(WorkerError::Stopped(kind)) => {
+                // Killing the worker is the only way to stop the running command
+                warn!("[{}] Stopping the Python worker ({:?})", request_id, kind);
+                *slot = None;
+                return Some(Err(control.stop_error(kind)));
+            }
+            Err(WorkerError::Send(e)) => {
+                warn!("[{}] Python worker did not take the request: {}", request_id, e);
+                METRICS.worker_crashes.fetch_add(1, Ordering::Relaxed);
+                *slot = None;
+            }
+            Err(WorkerError::Receive(e)) => {
+                // The command may have run: report like truncated output, so
+                // only idempotent commands are retried
+                warn!("[{}] Python worker failed during the request: {}", request_id, e);
+                METRICS.worker_crashes.fetch_add(1, Ordering::Relaxed);
+                *slot = None;
+                let error = CommandError::parse_error(format!("Python worker error: {}", e));
+                return Some(Err((FailureKind::TruncatedOutput, error)));
+            }
+        }
+    }
+
+    METRICS.worker_fallbacks.fetch_add(1, Ordering::Relaxed);
+    None
+}
 
 /// Execute Python tauri_api module and return JSON result.
 ///
+/// The request ID is passed to Python via `COMMAND_CENTER_REQUEST_ID`,
+/// prefixed to bridge log lines, and set as the `request_id` of errors.
+/// Transient failures are retried according to `RetryPolicy::from_env()`.
+/// A call that outlives its `TimeoutPolicy` timeout or is cancelled with
+/// `cancel_request` kills its Python process and fails with a `Timeout` or
+/// `Cancelled` error. Attempts wait for a free slot of the concurrency
+/// limit, and a read call identical to a running one (`COALESCED_COMMANDS`)
+/// returns that call's result instead of running again. Responses of
+/// `response_cache` commands are served from memory within its TTL.
+///
 /// # Arguments
 ///
+/// * `request_id` - Correlation ID of the command invocation
 /// * `args` - Command-line arguments to pass to Python module
 ///
 /// # Returns
 ///
 /// * `Ok(Value)` - Parsed JSON response from Python
-/// * `Err(String)` - Error message if execution or parsing fails
+/// * `Err(CommandError)` - Structured error if execution or parsing fails, tagged with the request ID
 ///
 /// # Example
 ///
 /// ```ignore
-/// let result = call_python_api(&["dashboard", "--from", "2025-01-01", "--to", "2025-12-27"]);
+/// let result = call_python_api("rq-1", &["dashboard", "--from", "2025-01-01", "--to", "2025-12-27"]);
 /// ```
-pub fn call_python_api(args: &[&str]) -> Result<Value, String> {
-    use log::{info, debug};
-
-    // Execute Python module - try multiple Python commands
-    let python_commands = vec!["python", "python3", "uv run python"];
-    let mut last_error = String::new();
-
-    for python_cmd in &python_commands {
-        let cmd_parts: Vec<&str> = python_cmd.split_whitespace().collect();
-        let mut command = if cmd_parts.len() > 1 {
-            // For "uv run python"
-            let mut cmd = Command::new(cmd_parts[0]);
-            for part in &cmd_parts[1..] {
-                cmd.arg(part);
-            }
-            cmd
-        } else {
-            // For "python" or "python3"
-            Command::new(cmd_parts[0])
-        };
+pub fn call_python_api(request_id: &str, args: &[&str]) -> Result<Value, CommandError> {
+    call_python_api_with_progress(request_id, args, &|_| {})
+}
 
-        command.arg("-m")
-               .arg("command_center.tauri_api")
-               .args(args);
-
-        match command.output() {
-            Ok(output) => {
-                // Check exit status
-                if !output.status.success() {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    last_error = format!("Python error ({}): {}", python_cmd, stderr);
-                    debug!("Failed with {}: {}", python_cmd, stderr);
-                    continue;
-                }
+/// `call_python_api`, passing the refresh progress the backend reports while
+/// the call runs to `on_progress` (on the calling thread). Calls that share
+/// the result of an identical running call get no progress events.
+pub fn call_python_api_with_progress(
+    request_id: &str,
+    args: &[&str],
+    on_progress: ProgressSink,
+) -> Result<Value, CommandError> {
+    call_python(request_id, args, false, &|event| {
+        if let BackendEvent::Progress(event) = event {
+            on_progress(event);
+        }
+    })
+}
+
+/// `call_python_api` with a streamed result: the list of a subcommand in
+/// `STREAMED_RESULTS` (`result_stream.py`) is passed to `on_chunk` chunk by
+/// chunk while the call runs, and the returned result holds it emptied with
+/// a `streamed` summary. Other subcommands return their result unchanged.
+/// Streamed calls are neither cached nor coalesced; a retried attempt sends
+/// its chunks again from `seq` 0. In privacy mode chunks are stripped too.
+pub fn call_python_api_streaming(
+    request_id: &str,
+    args: &[&str],
+    on_chunk: ChunkSink,
+) -> Result<Value, CommandError> {
+    call_python(request_id, args, true, &|event| {
+        if let BackendEvent::Chunk(mut chunk) = event {
+            if privacy::enabled() {
+                privacy::scrub(&mut chunk);
+            }
+            on_chunk(chunk);
+        }
+    })
+}
+
+fn call_python(request_id: &str, args: &[&str], stream: bool, on_event: EventSink) -> Result<Value, CommandError> {
+    use log::debug;
+
+    let subcommand = args.first().copied().unwrap_or("");
+    let started = Instant::now();
+    debug!("[{}] tauri_api {}", request_id, subcommand);
+
+    let in_flight = InFlight::register(request_id);
+    let timeout = TimeoutPolicy::from_env().timeout(args);
+    let control = CallControl {
+        subcommand: subcommand.to_string(),
+        timeout,
+        deadline: started + timeout,
+        cancelled: in_flight.cancelled.clone(),
+        stream,
+    };
+
+    let env = PresentationMode::load().env();
+    // Cached responses keep their content; it is stripped on the way out
+    let scrub = |mut json: Value| {
+        if privacy::enabled() {
+            privacy::scrub(&mut json);
+        }
+        json
+    };
+    // Chunks go to this caller only
+    let cache_key = response_cache::cache_key(args, &env).filter(|_| !stream);
+    let mut generation = 0;
+    if let Some(key) = &cache_key {
+        let (cached, current) = response_cache::lookup(key);
+        if let Some(json) = cached {
+            debug!("[{}] {} served from cache", request_id, subcommand);
+            return Ok(scrub(json));
+        }
+        generation = current;
+    }
 
-                // Parse JSON from stdout
-                let stdout = String::from_utf8_lossy(&output.stdout);
+    let (result, attempts) = match coalescing_key(args, &env).filter(|_| !stream) {
+        Some(key) => run_coalesced(&key, &control, || run_attempts(request_id, args, &control, on_event)),
+        None => run_attempts(request_id, args, &control, on_event),
+    };
 
-                match serde_json::from_str(&stdout) {
+    let result = match result {
+        Ok(json) => {
+            if attempts > 1 {
+                METRICS.recovered.fetch_add(1, Ordering::Relaxed);
+            }
+            telemetry::record_call(subcommand, started.elapsed(), None);
+            if let Some(key) = cache_key {
+                response_cache::store(key, &json, generation);
+            }
+            Ok(scrub(json))
+        }
+        Err((kind, error)) => {
+            match kind {
+                FailureKind::Timeout => METRICS.timeouts.fetch_add(1, Ordering::Relaxed),
+                FailureKind::Cancelled => METRICS.cancelled.fetch_add(1, Ordering::Relaxed),
+                _ => METRICS.failures.fetch_add(1, Ordering::Relaxed),
+            };
+            telemetry::record_call(subcommand, started.elapsed(), Some(&error_kind(kind, &error)));
+            let retried = if attempts > 1 {
+                format!(" (after {} attempts)", attempts)
+            } else {
+                String::new()
+            };
+            Err(error.with_suffix(&retried).with_request_id(request_id))
+        }
+    };
+
+    // Also after failures: a failed refresh may have ingested part of the data
+    if response_cache::invalidates(args) {
+        response_cache::invalidate();
+    }
+    METRICS.calls.fetch_add(1, Ordering::Relaxed);
+    result
+}
+
+/// Run a call's attempts, retrying transient failures, each attempt
+/// holding a concurrency `Permit`.
+fn run_attempts(request_id: &str, args: &[&str], control: &CallControl, on_event: EventSink) -> Outcome {
+    use log::{debug, warn};
+
+    let subcommand = args.first().copied().unwrap_or("");
+    let policy = RetryPolicy::from_env();
+    let mut attempt = 1;
+    loop {
+        let result = Permit::acquire(control).and_then(|_permit| run_python(request_id, args, control, on_event));
+        match result {
+            Err((kind, error)) if attempt < policy.max_attempts && kind.is_retryable(subcommand) => {
+                let delay = policy.delay(attempt);
+                warn!(
+                    "[{}] {} failed ({:?}), retry {}/{} in {} ms",
+                    request_id,
+                    subcommand,
+                    kind,
+                    attempt,
+                    policy.max_attempts - 1,
+                    delay.as_millis()
+                );
+                debug!("[{}] {}", request_id, error);
+                METRICS.retries.fetch_add(1, Ordering::Relaxed);
+                std::thread::sleep(delay);
+                attempt += 1;
+            }
+            result => return (result, attempt),
+        }
+    }
+}
+
+/// Run one attempt on the worker, or in one-shot processes trying the
+/// available Python interpreters in turn.
+fn run_python(
+    request_id: &str,
+    args: &[&str],
+    control: &CallControl,
+    on_event: EventSink,
+) -> Result<Value, Failure> {
+    use log::debug;
+
+    if let Some(kind) = control.stop_reason() {
+        return Err(control.stop_error(kind));
+    }
+    let mode = PresentationMode::load();
+    let env = mode.env();
+    if worker_enabled() {
+        if let Some(result) = call_worker(request_id, args, &env, control, on_event) {
+            return result;
+        }
+    }
+
+    // A backend that ran and failed explains more than one that is missing
+    let mut last_error: Option<Failure> = None;
+    let mut missing = Vec::new();
+    let stdin = stdin_request(request_id, args);
+
+    for backend in backends() {
+        if let Some(kind) = control.stop_reason() {
+            return Err(control.stop_error(kind));
+        }
+        let python_cmd = backend.to_string();
+        let mut command = backend.command();
+        match &stdin {
+            Some(_) => command.arg(STDIN_JSON_FLAG),
+            None => command.args(args),
+        };
+        command.env(REQUEST_ID_ENV, request_id)
+               .env(PROGRESS_ENV, "1")
+               .envs(env.iter().cloned());
+        if control.stream {
+            command.env(STREAM_ENV, "1");
+        }
+
+        match run_one_shot(command, stdin.as_deref(), control, on_event) {
+            Ok(Err(kind)) => {
+                debug!("[{}] Killed {} ({:?})", request_id, python_cmd, kind);
+                return Err(control.stop_error(kind));
+            }
+            // Not an interpreter: report it as missing, not as a failed call
+            Ok(Ok(output)) if is_store_stub(&output) => {
+                debug!("[{}] {} is the Microsoft Store alias", request_id, python_cmd);
+                missing.push(format!("{} (Microsoft Store alias)", python_cmd));
+                continue;
+            }
+            Ok(Ok(output)) => {
+                match parse_output(request_id, &python_cmd, &output) {
                     Ok(json) => {
-                        info!("API call successful ({} bytes)", stdout.len());
+                        remember_backend(&backend);
                         return Ok(json);
                     }
-                    Err(e) => {
-                        last_error = format!("JSON parse error: {} | stdout: {}", e, stdout);
-                        debug!("JSON parse error: {}", e);
+                    // The interpreter works; retrying another one will not help
+                    Err((kind, error)) if kind != FailureKind::Permanent => {
+                        remember_backend(&backend);
+                        return Err((kind, error));
+                    }
+                    Err(failure) => {
+                        last_error = Some(failure);
                         continue;
                     }
                 }
             }
             Err(e) => {
-                last_error = format!("Failed to execute {}: {}", python_cmd, e);
-                debug!("Failed to execute {}: {}", python_cmd, e);
+                debug!("[{}] Failed to execute {}: {}", request_id, python_cmd, e);
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    missing.push(python_cmd);
+                } else if last_error.is_none() {
+                    let error = CommandError::internal(format!("Failed to execute {}: {}", python_cmd, e));
+                    last_error = Some((FailureKind::Permanent, error));
+                }
                 continue;
             }
         }
     }
 
-    Err(last_error)
+    Err(last_error.unwrap_or_else(|| {
+        let error = CommandError::python_missing(format!("No Python backend found (tried {})", missing.join(", ")));
+        (FailureKind::Permanent, error)
+    }))
+}
+
+/// Whether a run came from the Microsoft Store alias stub rather than an interpreter.
+fn is_store_stub(output: &RunOutput) -> bool {
+    !output.success
+        && (output.code == Some(STORE_STUB_EXIT_CODE) || output.stderr.trim_start().starts_with("Python was not found"))
+}
+
+/// Progress event of a `{"progress": {...}}` stderr line.
+fn progress_event(line: &[u8]) -> Option<Value> {
+    tagged_line(line, "progress")
+}
+
+/// Result chunk of a `{"chunk": {...}}` stdout line of a streamed call.
+fn chunk_event(line: &[u8]) -> Option<Value> {
+    tagged_line(line, "chunk")
+}
+
+fn tagged_line(line: &[u8], tag: &str) -> Option<Value> {
+    if !line.starts_with(format!("{{\"{}\"", tag).as_bytes()) {
+        return None;
+    }
+    serde_json::from_slice::<Value>(line).ok()?.get_mut(tag).map(Value::take)
+}
+
+/// Run a one-shot process to completion, or kill it when the call times out
+/// or is cancelled (`Ok(Err(kind))`). `Err` means it could not be started.
+///
+/// `stdin` is written to the process (then closed) while it runs. stderr is
+/// read line by line: progress lines go to `on_event`, everything else into
+/// the output. So is stdout of a streamed call, whose chunk lines go to
+/// `on_event` as they arrive.
+fn run_one_shot(
+    mut command: Command,
+    stdin: Option<&str>,
+    control: &CallControl,
+    on_event: EventSink,
+) -> std::io::Result<Result<RunOutput, FailureKind>> {
+    fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> std::thread::JoinHandle<Vec<u8>> {
+        std::thread::spawn(move || {
+            let mut buffer = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buffer);
+            }
+            buffer
+        })
+    }
+
+    /// Read `pipe` line by line, sending the lines `event` recognizes and
+    /// collecting the others.
+    fn split<R: Read + Send + 'static>(
+        pipe: Option<R>,
+        sender: mpsc::Sender<BackendEvent>,
+        event: fn(&[u8]) -> Option<BackendEvent>,
+    ) -> std::thread::JoinHandle<Vec<u8>> {
+        std::thread::spawn(move || {
+            let mut buffer = Vec::new();
+            if let Some(pipe) = pipe {
+                for line in BufReader::new(pipe).split(b'\n') {
+                    let Ok(line) = line else { break };
+                    match event(&line) {
+                        Some(event) => {
+                            let _ = sender.send(event);
+                        }
+                        None => {
+                            buffer.extend_from_slice(&line);
+                            buffer.push(b'\n');
+                        }
+                    }
+                }
+            }
+            buffer
+        })
+    }
+
+    command
+        .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = spawn_tracked(&mut command)?;
+    // Written on its own thread: a large request must not block on a full
+    // pipe while the process waits for its own output to be read
+    if let (Some(mut pipe), Some(request)) = (child.stdin.take(), stdin) {
+        let request = request.to_string();
+        std::thread::spawn(move || {
+            let _ = pipe.write_all(request.as_bytes());
+        });
+    }
+    let (sender, events) = mpsc::channel();
+    let stdout = if control.stream {
+        split(child.stdout.take(), sender.clone(), |line| chunk_event(line).map(BackendEvent::Chunk))
+    } else {
+        drain(child.stdout.take())
+    };
+    let stderr = split(child.stderr.take(), sender, |line| progress_event(line).map(BackendEvent::Progress));
+    let child = TrackedChild::new(child);
+
+    loop {
+        for event in events.try_iter() {
+            on_event(event);
+        }
+        if let Some(status) = child.try_wait()? {
+            let text = |handle: std::thread::JoinHandle<Vec<u8>>| {
+                String::from_utf8_lossy(&handle.join().unwrap_or_default()).into_owned()
+            };
+            let stdout = text(stdout);
+            let stderr = text(stderr);
+            for event in events.try_iter() {
+                on_event(event);
+            }
+            return Ok(Ok(RunOutput {
+                success: status.success(),
+                code: status.code(),
+                stdout,
+                stderr,
+            }));
+        }
+        if let Some(kind) = control.stop_reason() {
+            child.kill();
+            // The pipe readers are not joined: a grandchild (e.g. under
+            // `uv run`) may keep the pipes open after the kill
+            return Ok(Err(kind));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Time allowed for each process started by `diagnose_environment`.
+const DIAGNOSTIC_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Run a diagnostic process to completion (or its timeout).
+fn run_probe(command: Command, name: &str) -> Result<RunOutput, String> {
+    let control = CallControl {
+        subcommand: name.to_string(),
+        timeout: DIAGNOSTIC_TIMEOUT,
+        deadline: Instant::now() + DIAGNOSTIC_TIMEOUT,
+        cancelled: Arc::new(AtomicBool::new(false)),
+        stream: false,
+    };
+    match run_one_shot(command, None, &control, &|_| {}) {
+        Ok(Ok(output)) => Ok(output),
+        Ok(Err(kind)) => Err(control.stop_error(kind).1.message().to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Probe one backend: where it is, the interpreter version and whether it
+/// imports and runs `command_center.tauri_api`.
+fn diagnose_backend(backend: &Backend) -> Value {
+    let path = backend.locate();
+    let mut report = json!({
+        "backend": backend.to_string(),
+        "kind": match backend {
+            Backend::Sidecar(_) => "sidecar",
+            Backend::Embedded(_) => "embedded",
+            Backend::Virtualenv(_) => "virtualenv",
+            Backend::System(_) => "system",
+        },
+        "path": path.as_ref().map(|p| p.display().to_string()),
+        "found": path.is_some(),
+        "interpreter_version": null,
+        "importable": false,
+        "diagnostics": null,
+        "error": null,
+    });
+    if path.is_none() {
+        report["error"] = json!(format!("{} not found", backend));
+        return report;
+    }
+
+    if !matches!(backend, Backend::Sidecar(_)) {
+        let mut command = backend.program();
+        command.arg("--version");
+        if let Ok(output) = run_probe(command, "python --version") {
+            // Python 2 printed its version to stderr
+            let version = if output.stdout.trim().is_empty() { &output.stderr } else { &output.stdout };
+            report["interpreter_version"] = json!(version.trim());
+        }
+    }
+
+    let mut command = backend.command();
+    command.arg("diagnostics");
+    let output = match run_probe(command, "diagnostics") {
+        Ok(output) => output,
+        Err(error) => {
+            report["error"] = json!(error);
+            return report;
+        }
+    };
+    if is_store_stub(&output) {
+        report["error"] = json!(format!(
+            "{} is the Microsoft Store alias, not an installed Python; install Python or turn the alias off \
+             in Settings > Apps > App execution aliases",
+            backend
+        ));
+        return report;
+    }
+    match parse_output("diagnose", &backend.to_string(), &output) {
+        Ok(diagnostics) => {
+            report["importable"] = json!(true);
+            report["diagnostics"] = diagnostics;
+        }
+        Err((_, error)) => {
+            // Anything but an import error means the module loaded and failed later
+            let import_failed = ["ModuleNotFoundError", "ImportError", "No module named"]
+                .iter()
+                .any(|marker| output.stderr.contains(marker));
+            report["importable"] = json!(!import_failed);
+            report["error"] = serde_json::to_value(&error).unwrap_or(Value::Null);
+        }
+    }
+    report
+}
+
+/// Troubleshooting report of the Python backends the bridge would try.
+///
+/// * `ok` - whether a backend runs `command_center.tauri_api`
+/// * `active_backend` - the first working backend (the one calls use)
+/// * `diagnostics` - its `diagnostics` subcommand output: app_version,
+///   python (executable, version), module_path, data_dir and db (path,
+///   exists, size, wal_size)
+/// * `backends` - every backend in the order tried: backend, kind
+///   (sidecar, embedded, virtualenv, system), path, found, interpreter_version,
+///   importable, diagnostics and error (a `CommandError` or a message)
+/// * `bundled` - the bundled sidecar or interpreter and whether it is `enabled`
+/// * `worker_enabled` - whether calls go through the persistent worker
+/// * `path` - the directories of `PATH`, in order
+pub fn diagnose_environment() -> Value {
+    let reports: Vec<Value> = backends().iter().map(diagnose_backend).collect();
+    let active = reports.iter().find(|report| !report["diagnostics"].is_null());
+    let path: Vec<String> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).map(|dir| dir.display().to_string()).collect())
+        .unwrap_or_default();
+    json!({
+        "ok": active.is_some(),
+        "active_backend": active.map(|report| report["backend"].clone()),
+        "diagnostics": active.map(|report| report["diagnostics"].clone()),
+        "backends": reports,
+        "bundled": {
+            "path": match BUNDLED_BACKEND.get() {
+                Some(Some(Backend::Sidecar(path) | Backend::Embedded(path))) => Some(path.display().to_string()),
+                _ => None,
+            },
+            "enabled": bundled_backend().is_some(),
+        },
+        "worker_enabled": worker_enabled(),
+        "path": path,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_resolve_request_id() {
+        assert_eq!(resolve_request_id(Some("ui-42".to_string())), "ui-42");
+        assert!(resolve_request_id(None).starts_with("rq-"));
+        assert!(resolve_request_id(Some("bad id\n".to_string())).starts_with("rq-"));
+        assert_ne!(new_request_id(), new_request_id());
+    }
+
+    #[test]
+    fn test_retry_classification() {
+        assert_eq!(
+            classify_stderr("sqlite3.OperationalError: database is locked"),
+            FailureKind::Busy
+        );
+        assert_eq!(classify_stderr("ValueError: bad date"), FailureKind::Permanent);
+        let patterns = parse_patterns("disk I/O error; ;");
+        assert_eq!(classify_with(&patterns, "sqlite3.OperationalError: disk I/O error"), FailureKind::Busy);
+        assert_eq!(classify_with(&patterns, "database is busy"), FailureKind::Busy);
+        assert!(!patterns.contains(&String::new()));
+        assert_eq!(patterns.len(), TRANSIENT_STDERR.len() + 1);
+        assert!(FailureKind::Busy.is_retryable("create-snapshot"));
+        assert!(FailureKind::TruncatedOutput.is_retryable("dashboard"));
+        assert!(!FailureKind::TruncatedOutput.is_retryable("delete-snapshot"));
+    }
+
+    #[test]
+    fn test_error_kind_uses_exception_type_only() {
+        let stderr = "{\"error\": \"No such file: /home/me/x\", \"type\": \"FileNotFoundError\"}";
+        let error = CommandError::from_exit(Some(1), stderr);
+        assert_eq!(error_kind(FailureKind::Permanent, &error), "FileNotFoundError");
+        assert_eq!(error_kind(FailureKind::Busy, &CommandError::busy("database is locked")), "busy");
+    }
+
+    #[test]
+    fn test_parse_output_classification() {
+        let output = |success: bool, stdout: &str, stderr: &str| RunOutput {
+            success,
+            code: Some(if success { 0 } else { 1 }),
+            stdout: stdout.to_string(),
+            stderr: stderr.to_string(),
+        };
+        assert_eq!(parse_output("t", "python", &output(true, "{\"a\": 1}", "")).unwrap()["a"], 1);
+        assert_eq!(
+            parse_output("t", "python", &output(true, "{\"a\": ", "")).unwrap_err().0,
+            FailureKind::TruncatedOutput
+        );
+        assert_eq!(
+            parse_output("t", "python", &output(false, "", "database is locked")).unwrap_err().0,
+            FailureKind::Busy
+        );
+        let (kind, error) = parse_output("t", "python", &output(false, "", "ValueError")).unwrap_err();
+        assert_eq!(kind, FailureKind::Permanent);
+        assert!(matches!(error, CommandError::NonZeroExit { .. }));
+    }
+
+    #[test]
+    fn test_parse_output_skips_stdout_preamble() {
+        let output = |stdout: &str| RunOutput {
+            success: true,
+            code: Some(0),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        };
+        let noisy = "/x/pkg.py:3: DeprecationWarning: {old} API\n  import pkg\n{\"a\": [1]}\n";
+        assert_eq!(parse_output("t", "python", &output(noisy)).unwrap()["a"][0], 1);
+        assert_eq!(parse_output("t", "python", &output("[banner]\n[1, 2]")).unwrap()[1], 2);
+        // A truncated document after a preamble is still reported as truncated
+        assert_eq!(
+            parse_output("t", "python", &output("Loading...\n{\"a\": ")).unwrap_err().0,
+            FailureKind::TruncatedOutput
+        );
+        assert_eq!(split_json("{\"a\": 1}").0, "");
+        assert!(split_json("no json here").1.is_err());
+        let (preamble, parsed) = split_json("{not json\n{\"a\": {\n\"b\": 1}}\n");
+        assert_eq!((preamble, parsed.unwrap()["a"]["b"].as_i64()), ("{not json\n", Some(1)));
+    }
+
+    #[test]
+    fn test_is_store_stub() {
+        let output = |code: i32, stderr: &str| RunOutput {
+            success: code == 0,
+            code: Some(code),
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+        };
+        assert!(is_store_stub(&output(STORE_STUB_EXIT_CODE, "")));
+        assert!(is_store_stub(&output(1, "Python was not found; run without arguments to install from the Microsoft Store")));
+        assert!(!is_store_stub(&output(1, "ModuleNotFoundError: No module named 'command_center'")));
+        assert!(!is_store_stub(&output(0, "")));
+    }
+
+    #[test]
+    fn test_stderr_level() {
+        assert_eq!(stderr_level("WARNING:root:pricing table is stale"), log::Level::Warn);
+        assert_eq!(stderr_level("/x/a.py:3: DeprecationWarning: old API"), log::Level::Warn);
+        assert_eq!(stderr_level("Traceback (most recent call last):"), log::Level::Error);
+        assert_eq!(stderr_level("sqlite3.OperationalError: database is locked"), log::Level::Error);
+        assert_eq!(stderr_level("{\"error\": \"bad date\", \"type\": \"ValueError\"}"), log::Level::Error);
+        assert_eq!(stderr_level("DEBUG:command_center:cache hit"), log::Level::Debug);
+        assert_eq!(stderr_level("Updated 3 files"), log::Level::Info);
+    }
+
+    #[test]
+    fn test_stdin_request() {
+        assert_eq!(stdin_request("t", &["day", "--date", "2025-06-15"]), None);
+        let request = stdin_request("t", &["update-project", "--project-id=p", "--description=a \"b\""]).unwrap();
+        let request: Value = serde_json::from_str(&request).unwrap();
+        assert_eq!(request["args"][2], "--description=a \"b\"");
+        assert_eq!(request["request_id"], "t");
+        let long = format!("--query={}", "x".repeat(MAX_ARGV_BYTES));
+        assert!(stdin_request("t", &["sessions", &long]).is_some());
+    }
+
+    #[test]
+    fn test_progress_event() {
+        let line = br#"{"progress": {"mode": "full", "state": "running", "percent": 40.0}}"#;
+        assert_eq!(progress_event(line).unwrap()["percent"], 40.0);
+        assert_eq!(progress_event(br#"{"error": "bad", "type": "ValueError"}"#), None);
+        assert_eq!(progress_event(b"Traceback (most recent call last):"), None);
+
+        let chunk = br#"{"chunk": {"key": "messages", "seq": 2, "items": [{"model": "m"}]}}"#;
+        assert_eq!(chunk_event(chunk).unwrap()["seq"], 2);
+        assert_eq!(chunk_event(line), None);
+        assert_eq!(progress_event(chunk), None);
+    }
+
+    #[test]
+    fn test_retry_delay_is_capped() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.max_attempts, 1);
+        for retry in 1..20 {
+            assert!(policy.delay(retry) <= Duration::from_millis(policy.max_delay_ms));
+        }
+    }
+
+    #[test]
+    fn test_timeout_policy() {
+        let policy = TimeoutPolicy::default();
+        let long = Duration::from_secs(policy.long_secs);
+        let short = Duration::from_secs(policy.default_secs);
+        assert_eq!(policy.timeout(&["dashboard", "--refresh", "1"]), long);
+        assert_eq!(policy.timeout(&["dashboard", "--refresh=1"]), long);
+        assert_eq!(policy.timeout(&["dashboard", "--refresh", "0"]), short);
+        assert_eq!(policy.timeout(&["create-backup"]), long);
+        assert_eq!(policy.timeout(&["list-projects"]), short);
+        assert!(!FailureKind::Timeout.is_retryable("dashboard"));
+        assert!(!FailureKind::Cancelled.is_retryable("dashboard"));
+    }
+
+    #[test]
+    fn test_cancel_request() {
+        assert!(!cancel_request("test-cancel"));
+        let first = InFlight::register("test-cancel");
+        let second = InFlight::register("test-cancel");
+        drop(first);
+        assert!(cancel_request("test-cancel"));
+        assert!(second.cancelled.load(Ordering::Relaxed));
+        drop(second);
+        assert!(!cancel_request("test-cancel"));
+    }
+
+    #[test]
+    fn test_cancel_on_drop() {
+        let call = InFlight::register("test-drop");
+        CancelOnDrop::new("test-drop").disarm();
+        assert!(!call.cancelled.load(Ordering::Relaxed));
+        drop(CancelOnDrop::new("test-drop"));
+        assert!(call.cancelled.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_tracked_child_is_registered_until_dropped() {
+        let child = TrackedChild::new(Command::new("sleep").arg("30").spawn().unwrap());
+        let pid = child.id();
+        assert!(child_pids().contains(&pid));
+        assert!(child.try_wait().unwrap().is_none());
+        child.kill();
+        assert!(child.try_wait().unwrap().is_some());
+        drop(child);
+        assert!(!child_pids().contains(&pid));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_reaped_child_group_is_left_alone() {
+        let child = TrackedChild::new(spawn_tracked(&mut Command::new("true")).unwrap());
+        while child.try_wait().unwrap().is_none() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(child.lock().exited);
+        child.kill();
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_tracked_child_kill_takes_its_children() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "sleep 30 & echo $!; wait"]).stdout(Stdio::piped());
+        let mut child = spawn_tracked(&mut command).unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap()).read_line(&mut line).unwrap();
+        let grandchild = format!("/proc/{}/stat", line.trim());
+        let child = TrackedChild::new(child);
+        child.kill();
+        // Killed, maybe not reaped yet by the init process
+        let gone = (0..50).any(|_| {
+            let stat = std::fs::read_to_string(&grandchild).unwrap_or_default();
+            let stopped = stat.is_empty() || stat.rsplit(") ").next().is_some_and(|rest| rest.starts_with('Z'));
+            if !stopped {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            stopped
+        });
+        assert!(gone);
+    }
+
+    fn test_control() -> CallControl {
+        CallControl {
+            subcommand: "day".to_string(),
+            timeout: Duration::from_secs(5),
+            deadline: Instant::now() + Duration::from_secs(5),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            stream: false,
+        }
+    }
+
+    #[test]
+    fn test_coalescing_key() {
+        let env = [("COMMAND_CENTER_READ_ONLY", "0".to_string())];
+        assert!(coalescing_key(&["update-project", "--project-id=a"], &env).is_none());
+        let day = coalescing_key(&["day", "--date", "2025-01-01"], &env);
+        assert!(day.is_some());
+        assert_ne!(day, coalescing_key(&["day", "--date", "2025-01-02"], &env));
+        assert_ne!(day, coalescing_key(&["day", "--date", "2025-01-01"], &[]));
+    }
+
+    #[test]
+    fn test_identical_calls_share_one_run() {
+        let runs = Arc::new(AtomicU64::new(0));
+        let calls: Vec<_> = (0..4)
+            .map(|_| {
+                let runs = runs.clone();
+                std::thread::spawn(move || {
+                    run_coalesced("test-shared", &test_control(), || {
+                        runs.fetch_add(1, Ordering::Relaxed);
+                        std::thread::sleep(Duration::from_millis(300));
+                        (Ok(json!({"day": 1})), 1)
+                    })
+                })
+            })
+            .collect();
+        for call in calls {
+            assert_eq!(call.join().unwrap().0.unwrap(), json!({"day": 1}));
+        }
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_cancelled_leader_is_not_shared() {
+        let leader = std::thread::spawn(|| {
+            run_coalesced("test-cancelled", &test_control(), || {
+                std::thread::sleep(Duration::from_millis(200));
+                (Err((FailureKind::Cancelled, CommandError::cancelled("Cancelled"))), 1)
+            })
+        });
+        std::thread::sleep(Duration::from_millis(50));
+        let follower = run_coalesced("test-cancelled", &test_control(), || (Ok(json!("own run")), 1));
+        assert_eq!(follower.0.unwrap(), json!("own run"));
+        assert!(leader.join().unwrap().0.is_err());
+    }
+
+    #[test]
+    fn test_find_bundled_backend() {
+        let root = std::env::temp_dir().join(format!("cc-bundled-{}", new_request_id()));
+        let exe_dir = root.join("bin");
+        let resource_dir = root.join("resources");
+        std::fs::create_dir_all(&exe_dir).unwrap();
+        assert_eq!(find_bundled_backend(&exe_dir, Some(&resource_dir)), None);
+
+        let interpreter = if cfg!(windows) {
+            resource_dir.join("python").join("python.exe")
+        } else {
+            resource_dir.join("python").join("bin").join("python3")
+        };
+        std::fs::create_dir_all(interpreter.parent().unwrap()).unwrap();
+        std::fs::write(&interpreter, "").unwrap();
+        assert_eq!(
+            find_bundled_backend(&exe_dir, Some(&resource_dir)),
+            Some(Backend::Embedded(interpreter))
+        );
+
+        let sidecar = exe_dir.join(format!("{}{}", SIDECAR_NAME, std::env::consts::EXE_SUFFIX));
+        std::fs::write(&sidecar, "").unwrap();
+        assert_eq!(
+            find_bundled_backend(&exe_dir, Some(&resource_dir)),
+            Some(Backend::Sidecar(sidecar))
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_find_virtualenvs() {
+        let root = std::env::temp_dir().join(format!("cc-venvs-{}", new_request_id()));
+        let project = root.join("project");
+        let other = root.join("other");
+        let tool_dir = root.join("uv-tools");
+        assert!(find_virtualenvs(std::slice::from_ref(&project), Some(&tool_dir)).is_empty());
+
+        let interpreters = [
+            venv_interpreter(&project.join(".venv")),
+            venv_interpreter(&other.join("venv")),
+            venv_interpreter(&tool_dir.join(UV_TOOL_NAME)),
+        ];
+        for interpreter in &interpreters {
+            std::fs::create_dir_all(interpreter.parent().unwrap()).unwrap();
+            std::fs::write(interpreter, "").unwrap();
+        }
+        assert_eq!(
+            find_virtualenvs(&[project.clone(), other, project], Some(&tool_dir)),
+            interpreters.to_vec()
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_virtualenv_search_stops_at_project_root() {
+        let root = std::env::temp_dir().join(format!("cc-ancestors-{}", new_request_id()));
+        let nested = root.join("repo").join("desktop").join("src-tauri");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(project_ancestors(&nested), vec![nested.clone()]);
+
+        std::fs::write(root.join("repo").join("pyproject.toml"), "").unwrap();
+        assert_eq!(
+            project_ancestors(&nested),
+            vec![nested.clone(), root.join("repo").join("desktop"), root.join("repo")]
+        );
+
+        let cache = root.join(VIRTUALENV_CACHE_FILE);
+        let interpreter = venv_interpreter(&root.join("repo").join(".venv"));
+        let searched = project_ancestors(&nested);
+        std::fs::write(&cache, json!({"searched": searched, "found": [interpreter]}).to_string()).unwrap();
+        assert_eq!(cached_virtualenvs(&cache, &searched), None);
+        std::fs::create_dir_all(interpreter.parent().unwrap()).unwrap();
+        std::fs::write(&interpreter, "").unwrap();
+        assert_eq!(cached_virtualenvs(&cache, &searched), Some(vec![interpreter]));
+        assert_eq!(cached_virtualenvs(&cache, &searched[..1]), None);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
     #[test]
     #[ignore] // Requires Python environment
     fn test_call_python_api() {
-        let result = call_python_api(&[
+        let result = call_python_api("test", &[
             "dashboard",
             "--from",
             "2025-01-01",
@@ -0,0 +1,382 @@
+/// Relative date-range presets
+///
+/// Presets such as "last 7 days" or "month to date" are resolved here, with
+/// the timezone and week start from the shared settings, so the UI, the CLI
+/// and scheduled jobs all turn a preset into the same `from`/`to` dates.
+///
+/// "this ..." presets end today; "last ..." presets cover the whole
+/// previous period.
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, Utc, Weekday};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::settings::Settings;
+
+/// Longest accepted "last N days" preset.
+pub const MAX_LAST_DAYS: i64 = 3660;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangePreset {
+    Today,
+    Yesterday,
+    LastDays(i64),
+    ThisWeek,
+    LastWeek,
+    MonthToDate,
+    LastMonth,
+    YearToDate,
+    LastYear,
+    ThisBillingCycle,
+    LastBillingCycle,
+    SinceLastLimitReset,
+}
+
+impl RangePreset {
+    /// Parse a preset; case, spaces, `_` and `-` are interchangeable
+    /// ("Last 7 days", "last_7_days" and "last-7-days" are the same).
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let key = raw
+            .to_lowercase()
+            .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+
+        let preset = match key.as_str() {
+            "today" => RangePreset::Today,
+            "yesterday" => RangePreset::Yesterday,
+            "this-week" | "week-to-date" => RangePreset::ThisWeek,
+            "last-week" | "previous-week" => RangePreset::LastWeek,
+            "this-month" | "month-to-date" | "mtd" => RangePreset::MonthToDate,
+            "last-month" | "previous-month" => RangePreset::LastMonth,
+            "this-year" | "year-to-date" | "ytd" => RangePreset::YearToDate,
+            "last-year" | "previous-year" => RangePreset::LastYear,
+            "this-billing-cycle" | "billing-cycle" => RangePreset::ThisBillingCycle,
+            "last-billing-cycle" | "previous-billing-cycle" => RangePreset::LastBillingCycle,
+            "since-last-limit-reset" | "since-limit-reset" => RangePreset::SinceLastLimitReset,
+            other => {
+                let days = other
+                    .strip_prefix("last-")
+                    .and_then(|rest| rest.strip_suffix("-days").or_else(|| rest.strip_suffix("-day")))
+                    .and_then(|n| n.parse::<i64>().ok())
+                    .filter(|n| (1..=MAX_LAST_DAYS).contains(n))
+                    .ok_or_else(|| format!("Unknown range preset: {:?}", raw))?;
+                RangePreset::LastDays(days)
+            }
+        };
+        Ok(preset)
+    }
+
+    /// Canonical name, e.g. "last-7-days".
+    pub fn key(self) -> String {
+        match self {
+            RangePreset::Today => "today".to_string(),
+            RangePreset::Yesterday => "yesterday".to_string(),
+            RangePreset::LastDays(n) => format!("last-{}-days", n),
+            RangePreset::ThisWeek => "this-week".to_string(),
+            RangePreset::LastWeek => "last-week".to_string(),
+            RangePreset::MonthToDate => "month-to-date".to_string(),
+            RangePreset::LastMonth => "last-month".to_string(),
+            RangePreset::YearToDate => "year-to-date".to_string(),
+            RangePreset::LastYear => "last-year".to_string(),
+            RangePreset::ThisBillingCycle => "this-billing-cycle".to_string(),
+            RangePreset::LastBillingCycle => "last-billing-cycle".to_string(),
+            RangePreset::SinceLastLimitReset => "since-last-limit-reset".to_string(),
+        }
+    }
+}
+
+/// Inputs a preset is resolved against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeContext {
+    pub today: NaiveDate,
+    pub week_start: Weekday,
+    /// Day of the month billing cycles start on
+    pub billing_anchor_day: u32,
+    /// Date of the most recent limit reset that already happened
+    pub last_limit_reset: Option<NaiveDate>,
+}
+
+/// Resolved preset as returned by `resolve_range`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolvedRange {
+    pub preset: String,
+    pub from: String,
+    pub to: String,
+    pub days: i64,
+    pub today: String,
+    pub timezone: String,
+    pub week_start: String,
+}
+
+/// "today" in the configured timezone.
+pub fn today(settings: &Settings) -> Result<NaiveDate, String> {
+    match settings.timezone.as_deref().map(str::trim) {
+        None | Some("") | Some("local") => Ok(Local::now().date_naive()),
+        Some(tz) if tz.eq_ignore_ascii_case("utc") => Ok(Utc::now().date_naive()),
+        Some(tz) => {
+            let offset = tz
+                .parse::<FixedOffset>()
+                .map_err(|_| format!("Invalid timezone: {:?} (expected local, UTC or an offset like +02:00)", tz))?;
+            Ok(Utc::now().with_timezone(&offset).date_naive())
+        }
+    }
+}
+
+/// Configured first day of the week (default Monday).
+pub fn week_start(settings: &Settings) -> Result<Weekday, String> {
+    match settings.week_start.as_deref().map(str::trim) {
+        None | Some("") => Ok(Weekday::Mon),
+        Some(day) => day
+            .parse::<Weekday>()
+            .map_err(|_| format!("Invalid week start: {:?} (expected a weekday such as monday)", day)),
+    }
+}
+
+/// Configured billing anchor day; out-of-range values fall back to 1
+/// (calendar months), as in the Python backend.
+pub fn billing_anchor_day(settings: &Settings) -> u32 {
+    settings
+        .billing_anchor_day
+        .filter(|day| (1..=31).contains(day))
+        .unwrap_or(1)
+}
+
+/// Lowercase weekday name ("monday").
+pub fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "monday",
+        Weekday::Tue => "tuesday",
+        Weekday::Wed => "wednesday",
+        Weekday::Thu => "thursday",
+        Weekday::Fri => "friday",
+        Weekday::Sat => "saturday",
+        Weekday::Sun => "sunday",
+    }
+}
+
+/// Date of the latest reset in `limits` output that is not in the future.
+pub fn last_limit_reset(events: &Value) -> Option<NaiveDate> {
+    let now = Local::now();
+    events
+        .as_array()?
+        .iter()
+        .filter_map(|event| event.get("reset_at").and_then(Value::as_str))
+        .filter_map(|reset_at| {
+            if let Ok(at) = DateTime::parse_from_rfc3339(reset_at) {
+                return (at <= now).then(|| at.date_naive());
+            }
+            let at = NaiveDateTime::parse_from_str(reset_at.get(..19)?, "%Y-%m-%dT%H:%M:%S").ok()?;
+            (at <= now.naive_local()).then(|| at.date())
+        })
+        .max()
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn previous_month(date: NaiveDate) -> NaiveDate {
+    first_of_month(first_of_month(date) - Duration::days(1))
+}
+
+fn days_in_month(first: NaiveDate) -> u32 {
+    let next = if first.month() == 12 {
+        NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+    };
+    next.map_or(31, |n| (n - first).num_days() as u32)
+}
+
+/// Start of the billing cycle containing `date`; anchors past the end of a
+/// short month start on its last day.
+pub fn billing_cycle_start(date: NaiveDate, anchor_day: u32) -> NaiveDate {
+    let in_month = |first: NaiveDate| {
+        first
+            .with_day(anchor_day.clamp(1, days_in_month(first)))
+            .unwrap_or(first)
+    };
+    let candidate = in_month(first_of_month(date));
+    if candidate <= date {
+        candidate
+    } else {
+        in_month(previous_month(date))
+    }
+}
+
+/// Resolve a preset to inclusive `(from, to)` dates.
+pub fn resolve(preset: RangePreset, ctx: &RangeContext) -> Result<(NaiveDate, NaiveDate), String> {
+    let today = ctx.today;
+    let range = match preset {
+        RangePreset::Today => (today, today),
+        RangePreset::Yesterday => {
+            let day = today - Duration::days(1);
+            (day, day)
+        }
+        RangePreset::LastDays(n) => (today - Duration::days(n - 1), today),
+        RangePreset::ThisWeek | RangePreset::LastWeek => {
+            let offset = (7 + today.weekday().num_days_from_monday() - ctx.week_start.num_days_from_monday()) % 7;
+            let start = today - Duration::days(i64::from(offset));
+            if preset == RangePreset::ThisWeek {
+                (start, today)
+            } else {
+                (start - Duration::days(7), start - Duration::days(1))
+            }
+        }
+        RangePreset::MonthToDate => (first_of_month(today), today),
+        RangePreset::LastMonth => (previous_month(today), first_of_month(today) - Duration::days(1)),
+        RangePreset::YearToDate => (today.with_ordinal(1).unwrap_or(today), today),
+        RangePreset::LastYear => {
+            let start = NaiveDate::from_ymd_opt(today.year() - 1, 1, 1).ok_or("Date out of range")?;
+            (start, today.with_ordinal(1).unwrap_or(today) - Duration::days(1))
+        }
+        RangePreset::ThisBillingCycle => (billing_cycle_start(today, ctx.billing_anchor_day), today),
+        RangePreset::LastBillingCycle => {
+            let current = billing_cycle_start(today, ctx.billing_anchor_day);
+            let previous = billing_cycle_start(current - Duration::days(1), ctx.billing_anchor_day);
+            (previous, current - Duration::days(1))
+        }
+        RangePreset::SinceLastLimitReset => {
+            let reset = ctx
+                .last_limit_reset
+                .ok_or("No limit reset found in the last 60 days")?;
+            (reset.min(today), today)
+        }
+    };
+    Ok(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn ctx(today: &str) -> RangeContext {
+        RangeContext {
+            today: date(today),
+            week_start: Weekday::Mon,
+            billing_anchor_day: 1,
+            last_limit_reset: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_accepts_spelling_variants() {
+        assert_eq!(RangePreset::parse("Last 7 days").unwrap(), RangePreset::LastDays(7));
+        assert_eq!(RangePreset::parse("last_30_days").unwrap(), RangePreset::LastDays(30));
+        assert_eq!(RangePreset::parse("month to date").unwrap(), RangePreset::MonthToDate);
+        assert!(RangePreset::parse("last 0 days").is_err());
+        assert!(RangePreset::parse("next week").is_err());
+    }
+
+    #[test]
+    fn test_week_presets_follow_week_start() {
+        // 2025-06-18 is a Wednesday
+        let mut c = ctx("2025-06-18");
+        assert_eq!(resolve(RangePreset::ThisWeek, &c).unwrap(), (date("2025-06-16"), date("2025-06-18")));
+        c.week_start = Weekday::Sun;
+        assert_eq!(resolve(RangePreset::LastWeek, &c).unwrap(), (date("2025-06-08"), date("2025-06-14")));
+    }
+
+    #[test]
+    fn test_month_and_year_boundaries() {
+        let c = ctx("2025-03-01");
+        assert_eq!(resolve(RangePreset::LastMonth, &c).unwrap(), (date("2025-02-01"), date("2025-02-28")));
+        assert_eq!(resolve(RangePreset::LastDays(7), &c).unwrap(), (date("2025-02-23"), date("2025-03-01")));
+        assert_eq!(resolve(RangePreset::LastYear, &c).unwrap(), (date("2024-01-01"), date("2024-12-31")));
+    }
+
+    #[test]
+    fn test_billing_cycle_anchor_clamps_to_short_months() {
+        assert_eq!(billing_cycle_start(date("2025-06-20"), 17), date("2025-06-17"));
+        assert_eq!(billing_cycle_start(date("2025-06-16"), 17), date("2025-05-17"));
+        assert_eq!(billing_cycle_start(date("2025-03-05"), 31), date("2025-02-28"));
+    }
+
+    fn any_date() -> impl Strategy<Value = NaiveDate> {
+        // 1970-01-01 to 2199-12-31
+        (0i64..83_950).prop_map(|days| date("1970-01-01") + Duration::days(days))
+    }
+
+    fn any_weekday() -> impl Strategy<Value = Weekday> {
+        (0u8..7).prop_map(|n| Weekday::try_from(n).unwrap())
+    }
+
+    fn any_preset() -> impl Strategy<Value = RangePreset> {
+        prop_oneof![
+            Just(RangePreset::Today),
+            Just(RangePreset::Yesterday),
+            (1..=MAX_LAST_DAYS).prop_map(RangePreset::LastDays),
+            Just(RangePreset::ThisWeek),
+            Just(RangePreset::LastWeek),
+            Just(RangePreset::MonthToDate),
+            Just(RangePreset::LastMonth),
+            Just(RangePreset::YearToDate),
+            Just(RangePreset::LastYear),
+            Just(RangePreset::ThisBillingCycle),
+            Just(RangePreset::LastBillingCycle),
+            Just(RangePreset::SinceLastLimitReset),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_resolved_ranges_end_by_today(
+            preset in any_preset(),
+            today in any_date(),
+            week_start in any_weekday(),
+            anchor in 1u32..=31,
+            reset_offset in prop::option::of(-400i64..400),
+        ) {
+            let c = RangeContext {
+                today,
+                week_start,
+                billing_anchor_day: anchor,
+                last_limit_reset: reset_offset.map(|days| today + Duration::days(days)),
+            };
+            match resolve(preset, &c) {
+                Ok((from, to)) => {
+                    prop_assert!(from <= to, "{:?}: {} > {}", preset, from, to);
+                    prop_assert!(to <= today, "{:?} ends after today: {}", preset, to);
+                    if matches!(preset, RangePreset::ThisWeek | RangePreset::LastWeek) {
+                        prop_assert_eq!(from.weekday(), week_start);
+                    }
+                    if preset == RangePreset::LastBillingCycle {
+                        prop_assert!((to - from).num_days() < 31);
+                    }
+                }
+                Err(_) => prop_assert!(preset == RangePreset::SinceLastLimitReset && c.last_limit_reset.is_none()),
+            }
+        }
+
+        #[test]
+        fn prop_billing_cycle_start_is_within_a_month(day in any_date(), anchor in 0u32..=40) {
+            let start = billing_cycle_start(day, anchor);
+            prop_assert!(start <= day);
+            prop_assert!((day - start).num_days() < 31);
+            prop_assert_eq!(billing_cycle_start(start, anchor), start);
+        }
+
+        #[test]
+        fn prop_offset_timezones_are_within_a_day_of_utc(hours in -23i32..=23, minutes in 0i32..60, sign in any::<bool>()) {
+            let settings = Settings {
+                timezone: Some(format!("{}{:02}:{:02}", if sign { '+' } else { '-' }, hours.abs(), minutes)),
+                ..Settings::default()
+            };
+            let local = today(&settings).unwrap();
+            let utc = Utc::now().date_naive();
+            prop_assert!((local - utc).num_days().abs() <= 1);
+        }
+
+        #[test]
+        fn prop_timezone_settings_never_panic(raw in "\\PC{0,12}") {
+            let settings = Settings { timezone: Some(raw), ..Settings::default() };
+            let _ = today(&settings);
+        }
+    }
+}
@@ -0,0 +1,336 @@
+/// Chunked streaming of raw session JSONL files
+///
+/// The Python backend only locates the files of a session (`session-files`);
+/// the content is read here and sent to the frontend through a Tauri channel
+/// so large sessions never pass through the JSON bridge in one piece.
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::privacy;
+
+/// Target size of one streamed chunk (chunks end on line boundaries).
+pub const RAW_CHUNK_BYTES: usize = 256 * 1024;
+
+/// Output format of `get_raw_session`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawFormat {
+    /// JSONL lines exactly as written by Claude Code
+    Jsonl,
+    /// Parsed records of the session; malformed lines are skipped
+    Json,
+}
+
+impl RawFormat {
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.unwrap_or("jsonl") {
+            "jsonl" => Ok(RawFormat::Jsonl),
+            "json" => Ok(RawFormat::Json),
+            other => Err(format!("Unknown raw session format: {} (expected jsonl or json)", other)),
+        }
+    }
+}
+
+/// Message sent through the `get_raw_session` channel.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "camelCase")]
+pub enum RawSessionEvent {
+    #[serde(rename_all = "camelCase")]
+    Started {
+        session_id: String,
+        files: Vec<String>,
+        total_bytes: u64,
+    },
+    /// Consecutive raw lines of one file (format "jsonl")
+    Lines { file: String, text: String },
+    /// Parsed records of one file (format "json")
+    Records { file: String, records: Vec<Value> },
+    #[serde(rename_all = "camelCase")]
+    Finished {
+        bytes: u64,
+        lines: u64,
+        skipped_lines: u64,
+    },
+}
+
+/// Counters reported when streaming finishes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StreamStats {
+    pub bytes: u64,
+    pub lines: u64,
+    pub skipped_lines: u64,
+}
+
+/// Stream one session file in chunks of about `chunk_bytes`.
+///
+/// In `Json` mode records belonging to another session (a `sessionId` that
+/// differs from `session_id`) and unparseable lines count as skipped.
+/// With `redact` (privacy mode) message content is stripped from every
+/// record and unparseable lines are skipped in both modes.
+pub fn stream_file<F>(
+    path: &Path,
+    session_id: &str,
+    format: RawFormat,
+    redact: bool,
+    chunk_bytes: usize,
+    stats: &mut StreamStats,
+    send: &mut F,
+) -> Result<(), String>
+where
+    F: FnMut(RawSessionEvent) -> Result<(), String>,
+{
+    let file_name = path.display().to_string();
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", file_name, e))?;
+    let options = StreamOptions { session_id, format, redact, chunk_bytes };
+    stream_reader(BufReader::new(file), &file_name, &options, stats, send)
+}
+
+/// What `stream_reader` sends and how.
+pub struct StreamOptions<'a> {
+    pub session_id: &'a str,
+    pub format: RawFormat,
+    pub redact: bool,
+    pub chunk_bytes: usize,
+}
+
+/// `stream_file` on any reader; `file_name` labels the events.
+///
+/// Logs are untrusted and sometimes corrupted (a crash mid-write, a bad
+/// disk): bytes that are not UTF-8 are replaced instead of failing the
+/// stream, so such lines are skipped in `Json` mode and passed on lossily
+/// in `Jsonl` mode.
+pub fn stream_reader<R, F>(
+    mut reader: R,
+    file_name: &str,
+    options: &StreamOptions,
+    stats: &mut StreamStats,
+    send: &mut F,
+) -> Result<(), String>
+where
+    R: BufRead,
+    F: FnMut(RawSessionEvent) -> Result<(), String>,
+{
+    let StreamOptions { session_id, format, redact, chunk_bytes } = *options;
+    let mut text = String::new();
+    let mut records = Vec::new();
+    let mut pending_bytes = 0usize;
+    let mut bytes = Vec::new();
+
+    loop {
+        bytes.clear();
+        let read = reader
+            .read_until(b'\n', &mut bytes)
+            .map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
+        if read == 0 {
+            break;
+        }
+        stats.bytes += read as u64;
+        stats.lines += 1;
+        pending_bytes += read;
+        let line = String::from_utf8_lossy(&bytes);
+
+        match format {
+            RawFormat::Jsonl if redact => match privacy::scrub_line(&line) {
+                Some(scrubbed) => {
+                    text.push_str(&scrubbed);
+                    text.push('\n');
+                }
+                None => stats.skipped_lines += 1,
+            },
+            RawFormat::Jsonl => text.push_str(&line),
+            RawFormat::Json => match serde_json::from_str::<Value>(line.trim()) {
+                Ok(mut record)
+                    if record
+                        .get("sessionId")
+                        .and_then(Value::as_str)
+                        .map_or(true, |id| id == session_id) =>
+                {
+                    if redact {
+                        privacy::scrub(&mut record);
+                    }
+                    records.push(record)
+                }
+                _ => stats.skipped_lines += 1,
+            },
+        }
+
+        if pending_bytes >= chunk_bytes {
+            flush(file_name, &mut text, &mut records, send)?;
+            pending_bytes = 0;
+        }
+    }
+
+    flush(file_name, &mut text, &mut records, send)
+}
+
+fn flush<F>(file: &str, text: &mut String, records: &mut Vec<Value>, send: &mut F) -> Result<(), String>
+where
+    F: FnMut(RawSessionEvent) -> Result<(), String>,
+{
+    if !text.is_empty() {
+        send(RawSessionEvent::Lines {
+            file: file.to_string(),
+            text: std::mem::take(text),
+        })?;
+    }
+    if !records.is_empty() {
+        send(RawSessionEvent::Records {
+            file: file.to_string(),
+            records: std::mem::take(records),
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use serde_json::json;
+    use std::io::Write;
+
+    fn write_session(name: &str, lines: &[&str]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("cc-raw-{}-{}.jsonl", name, std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        for line in lines {
+            writeln!(file, "{}", line).unwrap();
+        }
+        path
+    }
+
+    #[test]
+    fn test_jsonl_chunks_end_on_line_boundaries() {
+        let path = write_session("chunks", &[r#"{"sessionId":"s1","n":1}"#, r#"{"sessionId":"s1","n":2}"#]);
+        let mut chunks = Vec::new();
+        let mut stats = StreamStats::default();
+        stream_file(&path, "s1", RawFormat::Jsonl, false, 1, &mut stats, &mut |event| {
+            if let RawSessionEvent::Lines { text, .. } = event {
+                chunks.push(text);
+            }
+            Ok(())
+        })
+        .unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().all(|c| c.ends_with('\n')));
+        assert_eq!(stats.lines, 2);
+    }
+
+    #[test]
+    fn test_json_skips_other_sessions_and_malformed_lines() {
+        let path = write_session("json", &[r#"{"sessionId":"s1"}"#, r#"{"sessionId":"s2"}"#, "{broken", r#"{"type":"summary"}"#]);
+        let mut count = 0;
+        let mut stats = StreamStats::default();
+        stream_file(&path, "s1", RawFormat::Json, false, RAW_CHUNK_BYTES, &mut stats, &mut |event| {
+            if let RawSessionEvent::Records { records, .. } = event {
+                count += records.len();
+            }
+            Ok(())
+        })
+        .unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(count, 2);
+        assert_eq!(stats.skipped_lines, 2);
+    }
+
+    #[test]
+    fn test_redacted_lines_keep_metadata_only() {
+        let path = write_session(
+            "redact",
+            &[r#"{"sessionId":"s1","message":{"role":"user","content":"secret plan"}}"#, "secret plan, unparsed"],
+        );
+        let mut text = String::new();
+        let mut stats = StreamStats::default();
+        stream_file(&path, "s1", RawFormat::Jsonl, true, RAW_CHUNK_BYTES, &mut stats, &mut |event| {
+            if let RawSessionEvent::Lines { text: chunk, .. } = event {
+                text.push_str(&chunk);
+            }
+            Ok(())
+        })
+        .unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(!text.contains("secret"));
+        assert!(text.contains(r#""role":"user""#));
+        assert_eq!(stats.skipped_lines, 1);
+    }
+
+    /// Stream `bytes` in memory, returning the events and counters.
+    fn stream_bytes(bytes: &[u8], format: RawFormat, redact: bool, chunk_bytes: usize) -> (Vec<RawSessionEvent>, StreamStats) {
+        let mut events = Vec::new();
+        let mut stats = StreamStats::default();
+        let options = StreamOptions { session_id: "s1", format, redact, chunk_bytes };
+        stream_reader(bytes, "mem.jsonl", &options, &mut stats, &mut |event| {
+            events.push(event);
+            Ok(())
+        })
+        .unwrap();
+        (events, stats)
+    }
+
+    #[test]
+    fn test_invalid_utf8_does_not_abort_the_stream() {
+        let bytes = b"{\"sessionId\":\"s1\"}\n\xff\xfe garbage\n{\"sessionId\":\"s1\",\"n\":2}\n";
+        let (events, stats) = stream_bytes(bytes, RawFormat::Json, false, RAW_CHUNK_BYTES);
+        let RawSessionEvent::Records { records, .. } = &events[0] else { panic!("expected records") };
+        assert_eq!(records.len(), 2);
+        assert_eq!(stats.skipped_lines, 1);
+        assert_eq!(stats.bytes, bytes.len() as u64);
+    }
+
+    /// Session log lines: records of this or another session, malformed JSON
+    /// and blank lines.
+    fn log_line() -> impl Strategy<Value = String> {
+        prop_oneof![
+            ("s[12]", any::<u32>(), "[a-z ]{0,40}").prop_map(|(session, n, text)| {
+                json!({"sessionId": session, "n": n, "message": {"role": "user", "content": text}}).to_string()
+            }),
+            "[{}\\[\\]\":,a-z0-9 ]{0,40}",
+            Just(String::new()),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_jsonl_reproduces_input(lines in prop::collection::vec(log_line(), 0..40), chunk_bytes in 1usize..512) {
+            let input: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+            let (events, stats) = stream_bytes(input.as_bytes(), RawFormat::Jsonl, false, chunk_bytes);
+            let mut output = String::new();
+            for event in events {
+                let RawSessionEvent::Lines { text, .. } = event else { panic!("unexpected event") };
+                prop_assert!(text.ends_with('\n'), "chunk does not end on a line boundary");
+                output.push_str(&text);
+            }
+            prop_assert_eq!(output, input);
+            prop_assert_eq!(stats.lines, lines.len() as u64);
+        }
+
+        #[test]
+        fn prop_json_accounts_for_every_line(lines in prop::collection::vec(log_line(), 0..40), redact in any::<bool>(), chunk_bytes in 1usize..512) {
+            let input = lines.join("\n");
+            let (events, stats) = stream_bytes(input.as_bytes(), RawFormat::Json, redact, chunk_bytes);
+            let records: usize = events
+                .iter()
+                .map(|event| match event {
+                    RawSessionEvent::Records { records, .. } => records.len(),
+                    _ => 0,
+                })
+                .sum();
+            prop_assert_eq!(records as u64 + stats.skipped_lines, stats.lines);
+            prop_assert_eq!(stats.bytes, input.len() as u64);
+        }
+
+        #[test]
+        fn prop_arbitrary_bytes_never_fail(bytes in prop::collection::vec(any::<u8>(), 0..2048), json in any::<bool>(), redact in any::<bool>()) {
+            let format = if json { RawFormat::Json } else { RawFormat::Jsonl };
+            let (_, stats) = stream_bytes(&bytes, format, redact, 64);
+            prop_assert_eq!(stats.bytes, bytes.len() as u64);
+            prop_assert_eq!(stats.lines, bytes.split_inclusive(|b| *b == b'\n').count() as u64);
+        }
+    }
+}
@@ -0,0 +1,311 @@
+/// User-editable HTML report templates
+///
+/// A template is a Tera file (Jinja2-like syntax) stored in
+/// `~/.claude/db/report-templates/` as `<name>.html`. The directory is
+/// created with the bundled starter templates the first time it is listed
+/// or used; teams edit those or add their own, e.g. the exact layout finance
+/// expects. Output is autoescaped HTML; PDF is produced by printing it.
+///
+/// Templates render a context of `range` ({from, to, project_id}),
+/// `generated_at`, `app_version`, `dashboard` (the dashboard bundle of the
+/// range: totals, model_distribution, timeline, hourly_profile,
+/// recent_sessions, ...) and `accessibility`: text alternatives of the
+/// dashboard's series (`describe_dashboard`) for captions, `alt` attributes
+/// and `aria-describedby` targets, so a report with charts still reads well
+/// with a screen reader.
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::Serialize;
+use serde_json::Value;
+use tera::{Context, Tera};
+
+use crate::atomic::write_atomic;
+use crate::settings::db_dir;
+
+/// File extension of templates.
+pub const TEMPLATE_EXTENSION: &str = "html";
+
+/// Largest template file accepted.
+pub const MAX_TEMPLATE_BYTES: u64 = 512 * 1024;
+
+/// Starter templates written into a new template directory.
+const STARTER_TEMPLATES: &[(&str, &str)] = &[("summary", include_str!("../report-templates/summary.html"))];
+
+/// Template as reported by `list_report_templates`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateInfo {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    /// Last modification (seconds since the Unix epoch)
+    pub modified: Option<u64>,
+    /// Why the template cannot be rendered (syntax error, too large)
+    pub error: Option<String>,
+}
+
+pub fn template_dir() -> Result<PathBuf, String> {
+    Ok(db_dir()?.join("report-templates"))
+}
+
+/// Create the template directory with the starter templates if it does not exist.
+fn ensure_dir(dir: &Path) -> Result<(), String> {
+    if dir.is_dir() {
+        return Ok(());
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    for (name, source) in STARTER_TEMPLATES {
+        let path = dir.join(format!("{}.{}", name, TEMPLATE_EXTENSION));
+        write_atomic(&path, source.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Template names are file stems: letters, digits, `-` and `_`.
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid template name: {:?} (letters, digits, - and _)", name))
+    }
+}
+
+/// Tera error with its causes (the line and column are in a cause).
+fn describe(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// Check that a template parses.
+fn check(name: &str, source: &str) -> Result<(), String> {
+    Tera::default()
+        .add_raw_template(name, source)
+        .map_err(|e| describe(&e))
+}
+
+/// Templates in the directory, sorted by name.
+pub fn list_templates(dir: &Path) -> Result<Vec<TemplateInfo>, String> {
+    ensure_dir(dir)?;
+    let entries = fs::read_dir(dir).map_err(|e| format!("Cannot read {}: {}", dir.display(), e))?;
+    let mut templates = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().and_then(|e| e.to_str()) != Some(TEMPLATE_EXTENSION) {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+            continue;
+        };
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+        let error = if let Err(e) = validate_name(&name) {
+            Some(e)
+        } else if metadata.len() > MAX_TEMPLATE_BYTES {
+            Some(format!("Template is larger than {} KiB", MAX_TEMPLATE_BYTES / 1024))
+        } else {
+            match fs::read_to_string(&path) {
+                Ok(source) => check(&name, &source).err(),
+                Err(e) => Some(e.to_string()),
+            }
+        };
+        templates.push(TemplateInfo {
+            name,
+            path: path.display().to_string(),
+            size_bytes: metadata.len(),
+            modified: metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs()),
+            error,
+        });
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+/// Source of a template.
+pub fn load_template(dir: &Path, name: &str) -> Result<String, String> {
+    validate_name(name)?;
+    ensure_dir(dir)?;
+    let path = dir.join(format!("{}.{}", name, TEMPLATE_EXTENSION));
+    let metadata = fs::metadata(&path).map_err(|_| format!("Unknown report template: {}", name))?;
+    if metadata.len() > MAX_TEMPLATE_BYTES {
+        return Err(format!("Template {} is larger than {} KiB", name, MAX_TEMPLATE_BYTES / 1024));
+    }
+    fs::read_to_string(&path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))
+}
+
+/// Render a template with a JSON object as its context.
+///
+/// Templates are registered under their file name, so Tera's default
+/// autoescaping of `.html` templates applies.
+pub fn render(name: &str, source: &str, context: &Value) -> Result<String, String> {
+    let context = Context::from_value(context.clone()).map_err(|e| describe(&e))?;
+    let file_name = format!("{}.{}", name, TEMPLATE_EXTENSION);
+    let mut tera = Tera::default();
+    tera.add_raw_template(&file_name, source).map_err(|e| describe(&e))?;
+    tera.render(&file_name, &context).map_err(|e| describe(&e))
+}
+
+/// Integer with thousands separators ("1,234,567").
+fn grouped(value: u64) -> String {
+    let digits = value.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
+
+fn count(value: &Value) -> u64 {
+    value.as_u64().unwrap_or_else(|| value.as_f64().map_or(0, |v| v.max(0.0).round() as u64))
+}
+
+fn dollars(value: &Value) -> String {
+    format!("${:.2}", value.as_f64().unwrap_or(0.0))
+}
+
+/// Text alternatives of a dashboard bundle for the `accessibility` context
+/// variable: `summary` (one sentence), `models` (the model distribution) and
+/// `timeline` (the cost and message series, with their peaks).
+pub fn describe_dashboard(from: &str, to: &str, dashboard: &Value) -> Value {
+    let totals = &dashboard["totals"];
+    let summary = format!(
+        "Claude Code usage from {} to {}: {} messages in {} sessions, {} tokens, {} cost.",
+        from,
+        to,
+        grouped(count(&totals["messages"])),
+        grouped(count(&totals["sessions"])),
+        grouped(count(&totals["tokens"])),
+        dollars(&totals["cost"])
+    );
+
+    let models: Vec<&Value> =
+        dashboard["model_distribution"].as_array().map(|m| m.iter().collect()).unwrap_or_default();
+    let models = if models.is_empty() {
+        "No model usage in this range.".to_string()
+    } else {
+        let shares: Vec<String> = models
+            .iter()
+            .map(|model| {
+                let name = model["display_name"].as_str().or(model["model"].as_str()).unwrap_or("Unknown model");
+                let percent = model["percent"].as_f64().unwrap_or(0.0);
+                format!("{} {:.1}% of tokens ({} cost)", name, percent, dollars(&model["cost"]))
+            })
+            .collect();
+        let noun = if models.len() == 1 { "model" } else { "models" };
+        format!("{} {} by token share: {}.", models.len(), noun, shares.join(", "))
+    };
+
+    let granularity = dashboard["timeline"]["granularity"].as_str().unwrap_or("period");
+    let points: Vec<&Value> =
+        dashboard["timeline"]["data"].as_array().map(|d| d.iter().collect()).unwrap_or_default();
+    let peak = |field: &str| {
+        points
+            .iter()
+            .copied()
+            .filter(|point| point[field].as_f64().unwrap_or(0.0) > 0.0)
+            .max_by(|a, b| a[field].as_f64().unwrap_or(0.0).total_cmp(&b[field].as_f64().unwrap_or(0.0)))
+    };
+    let timeline = match (points.first(), points.last(), peak("cost"), peak("messages")) {
+        (Some(first), Some(last), Some(cost_peak), Some(message_peak)) => {
+            let active = points.iter().filter(|point| count(&point["messages"]) > 0).count();
+            format!(
+                "Usage by {} from {} to {}, {} periods with activity of {}. Cost peaked at {} in {}; \
+                 messages peaked at {} in {}.",
+                granularity,
+                first["period"].as_str().unwrap_or(from),
+                last["period"].as_str().unwrap_or(to),
+                active,
+                points.len(),
+                dollars(&cost_peak["cost"]),
+                cost_peak["period"].as_str().unwrap_or_default(),
+                grouped(count(&message_peak["messages"])),
+                message_peak["period"].as_str().unwrap_or_default()
+            )
+        }
+        _ => format!("No activity by {} in this range.", granularity),
+    };
+
+    serde_json::json!({ "summary": summary, "models": models, "timeline": timeline })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_starter_templates_render() {
+        let dir = std::env::temp_dir().join(format!("cc-report-templates-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let templates = list_templates(&dir).unwrap();
+        assert_eq!(templates.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["summary"]);
+        assert!(templates[0].error.is_none());
+
+        let dashboard = json!({
+            "totals": {"messages": 10, "sessions": 2, "tokens": 5000, "cost": 1.234},
+            "model_distribution": [{"display_name": "Sonnet <4>", "messages": 10, "tokens": 5000,
+                                    "cost": 1.234, "percent": 100.0}],
+            "timeline": {"granularity": "day", "data": []},
+        });
+        let context = json!({
+            "range": {"from": "2026-01-01", "to": "2026-01-31", "project_id": null},
+            "generated_at": "2026-02-01T09:00:00+01:00",
+            "app_version": "0.0.0",
+            "accessibility": describe_dashboard("2026-01-01", "2026-01-31", &dashboard),
+            "dashboard": dashboard,
+        });
+        let html = render("summary", &load_template(&dir, "summary").unwrap(), &context).unwrap();
+        assert!(html.contains("Sonnet &lt;4&gt;"));
+        assert!(html.contains("1.23"));
+        assert!(html.contains("<caption>1 model by token share: Sonnet &lt;4&gt; 100.0% of tokens ($1.23 cost)."));
+        assert!(html.contains("10 messages in 2 sessions, 5,000 tokens, $1.23 cost."));
+
+        fs::write(dir.join("broken.html"), "{% for x in %}").unwrap();
+        let broken = list_templates(&dir).unwrap().into_iter().find(|t| t.name == "broken").unwrap();
+        assert!(broken.error.is_some());
+        assert!(load_template(&dir, "../summary").is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_describe_dashboard_timeline_peaks() {
+        let dashboard = json!({
+            "totals": {"messages": 1234567, "sessions": 3, "tokens": 999, "cost": 12.0},
+            "timeline": {"granularity": "month", "data": [
+                {"period": "2026-01", "messages": 5, "cost": 9.5},
+                {"period": "2026-02", "messages": 0, "cost": 0.0},
+                {"period": "2026-03", "messages": 40, "cost": 2.5},
+            ]},
+        });
+        let text = describe_dashboard("2026-01-01", "2026-03-31", &dashboard);
+        assert_eq!(
+            text["summary"],
+            "Claude Code usage from 2026-01-01 to 2026-03-31: 1,234,567 messages in 3 sessions, 999 tokens, \
+             $12.00 cost."
+        );
+        assert_eq!(
+            text["timeline"],
+            "Usage by month from 2026-01 to 2026-03, 2 periods with activity of 3. Cost peaked at $9.50 in 2026-01; \
+             messages peaked at 40 in 2026-03."
+        );
+        assert_eq!(text["models"], "No model usage in this range.");
+        let empty = describe_dashboard("2026-01-01", "2026-01-31", &json!({}));
+        assert_eq!(empty["timeline"], "No activity by period in this range.");
+    }
+}
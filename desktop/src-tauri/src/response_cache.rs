@@ -0,0 +1,189 @@
+/// In-memory TTL cache of read-only command responses
+///
+/// The dashboard bundle, the project list and limit resets are requested
+/// again on every navigation with the same parameters. Identical calls of
+/// `CACHED_COMMANDS` within the TTL are answered from memory without
+/// starting Python.
+///
+/// A refresh or any write that changes what these responses contain
+/// (`INVALIDATING_COMMANDS`) clears the cache when it finishes. Data ingested
+/// outside the app (e.g. the CLI) shows up once the TTL expires.
+/// `COMMAND_CENTER_CACHE_TTL_SECS` sets the TTL (default 30, 0 disables).
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+/// Read-only subcommands whose responses are cached.
+const CACHED_COMMANDS: &[&str] = &["dashboard", "projects", "limits"];
+
+/// Subcommands after which cached responses may be stale.
+const INVALIDATING_COMMANDS: &[&str] = &[
+    "refresh",
+    "generate-demo-data",
+    "generate-sample-data",
+    "restore-backup",
+    "merge-data",
+    "shift-timestamps",
+    "undo-timestamp-shift",
+    "import-session-archive",
+    "reprice",
+    "set-price",
+    "sync-prices",
+    "set-budget",
+    "detect-plan",
+    "update-usage-account",
+    "bind-claude-account",
+    "update-project",
+    "set-project-pinned",
+    "set-project-order",
+    "archive-projects",
+    "backfill-first-seen",
+    "create-workspace",
+    "delete-workspace",
+    "assign-project-workspace",
+    "set-derived-metric",
+    "delete-derived-metric",
+    "set-calendar-overlay",
+    "clear-mirror",
+];
+
+const DEFAULT_TTL_SECS: u64 = 30;
+
+/// Entries kept at most; expired and then oldest entries are dropped first.
+const MAX_ENTRIES: usize = 64;
+
+struct Cache {
+    entries: BTreeMap<String, (Instant, Value)>,
+    /// Bumped on every invalidation, so a call that started before a write
+    /// does not store its (possibly stale) response after it.
+    generation: u64,
+}
+
+static CACHE: Mutex<Cache> = Mutex::new(Cache {
+    entries: BTreeMap::new(),
+    generation: 0,
+});
+
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+static INVALIDATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// TTL from `COMMAND_CENTER_CACHE_TTL_SECS`; zero disables the cache.
+pub fn ttl() -> Duration {
+    let secs = std::env::var("COMMAND_CENTER_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_TTL_SECS);
+    Duration::from_secs(secs)
+}
+
+/// Whether the call refreshes data (`refresh`, or a dashboard with `--refresh 1`).
+fn is_refresh(args: &[&str]) -> bool {
+    match args.first() {
+        Some(&"refresh") => true,
+        Some(&"dashboard") => args.windows(2).any(|pair| pair == ["--refresh", "1"]),
+        _ => false,
+    }
+}
+
+/// Cache key of a call, or None if its response is not cached.
+pub fn cache_key(args: &[&str], env: &[(&'static str, String)]) -> Option<String> {
+    let subcommand = args.first()?;
+    if !CACHED_COMMANDS.contains(subcommand) || is_refresh(args) || ttl().is_zero() {
+        return None;
+    }
+    Some(format!("{:?}{:?}", args, env))
+}
+
+/// A fresh cached response and the current generation (pass it to `store`).
+pub fn lookup(key: &str) -> (Option<Value>, u64) {
+    let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let hit = cache
+        .entries
+        .get(key)
+        .filter(|(stored, _)| stored.elapsed() < ttl())
+        .map(|(_, value)| value.clone());
+    match hit {
+        Some(_) => HITS.fetch_add(1, Ordering::Relaxed),
+        None => MISSES.fetch_add(1, Ordering::Relaxed),
+    };
+    (hit, cache.generation)
+}
+
+/// Cache a response unless the cache was invalidated since `generation`.
+pub fn store(key: String, value: &Value, generation: u64) {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if cache.generation != generation {
+        return;
+    }
+    let ttl = ttl();
+    cache.entries.retain(|_, (stored, _)| stored.elapsed() < ttl);
+    while cache.entries.len() >= MAX_ENTRIES {
+        let oldest = cache
+            .entries
+            .iter()
+            .min_by_key(|(_, (stored, _))| *stored)
+            .map(|(key, _)| key.clone());
+        match oldest {
+            Some(oldest) => cache.entries.remove(&oldest),
+            None => break,
+        };
+    }
+    cache.entries.insert(key, (Instant::now(), value.clone()));
+}
+
+/// Drop all cached responses.
+pub fn invalidate() {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.entries.clear();
+    cache.generation += 1;
+    INVALIDATIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Whether finishing this call makes cached responses stale.
+pub fn invalidates(args: &[&str]) -> bool {
+    is_refresh(args) || args.first().is_some_and(|s| INVALIDATING_COMMANDS.contains(s))
+}
+
+/// Cache state for `get_bridge_metrics`: ttl_secs, entries, hits, misses, invalidations.
+pub fn cache_metrics() -> Value {
+    let entries = CACHE.lock().unwrap_or_else(|e| e.into_inner()).entries.len();
+    json!({
+        "ttl_secs": ttl().as_secs(),
+        "entries": entries,
+        "hits": HITS.load(Ordering::Relaxed),
+        "misses": MISSES.load(Ordering::Relaxed),
+        "invalidations": INVALIDATIONS.load(Ordering::Relaxed),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_keys_and_invalidation() {
+        let read = ["dashboard", "--from", "2025-01-01", "--to", "2025-01-31", "--refresh", "0"];
+        let refresh = ["dashboard", "--from", "2025-01-01", "--to", "2025-01-31", "--refresh", "1"];
+        assert!(cache_key(&refresh, &[]).is_none());
+        assert!(cache_key(&["sessions"], &[]).is_none());
+        assert!(invalidates(&refresh) && invalidates(&["update-project", "--project-id=p"]));
+        assert!(!invalidates(&read));
+
+        let key = cache_key(&read, &[]).unwrap();
+        let (cached, generation) = lookup(&key);
+        assert!(cached.is_none());
+        store(key.clone(), &json!({"totals": 1}), generation);
+        assert_eq!(lookup(&key).0, Some(json!({"totals": 1})));
+
+        // A response computed before an invalidation is not stored after it
+        let (_, stale_generation) = lookup("other");
+        invalidate();
+        assert!(lookup(&key).0.is_none());
+        store("other".to_string(), &json!(1), stale_generation);
+        assert!(lookup("other").0.is_none());
+    }
+}
@@ -0,0 +1,359 @@
+/// Response shapes of backend subcommands
+///
+/// The Python API's JSON reaches charts and tables that assume a shape; a
+/// missing or mistyped field used to pass through and break them silently.
+/// `PythonBackend` checks every response of a subcommand listed in
+/// `SCHEMAS` against its `Shape` and fails the call with an
+/// `unexpected_shape` error naming the first offending field instead:
+/// "Backend returned unexpected shape for `dashboard`: missing field totals.tokens".
+///
+/// Shapes only list the fields the frontend relies on; other fields are
+/// allowed, so the backend can add fields without a desktop release.
+/// Subcommands without a schema pass unchecked.
+use serde_json::Value;
+
+use crate::error::CommandError;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Shape {
+    Any,
+    Bool,
+    Number,
+    String,
+    /// The inner shape or null
+    Nullable(&'static Shape),
+    Array(&'static Shape),
+    /// Object with arbitrary keys (dates, hours) and values of one shape
+    Map(&'static Shape),
+    /// Object with these fields
+    Object(&'static [Field]),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Field {
+    name: &'static str,
+    shape: Shape,
+    /// Missing is fine, e.g. dashboard sections that were not requested
+    optional: bool,
+}
+
+const fn field(name: &'static str, shape: Shape) -> Field {
+    Field { name, shape, optional: false }
+}
+
+const fn optional(name: &'static str, shape: Shape) -> Field {
+    Field { name, shape, optional: true }
+}
+
+const NUMBER: Shape = Shape::Number;
+const STRING: Shape = Shape::String;
+const NULLABLE_NUMBER: Shape = Shape::Nullable(&NUMBER);
+const NULLABLE_STRING: Shape = Shape::Nullable(&STRING);
+
+const RANGE: Shape = Shape::Object(&[field("from", STRING), field("to", STRING)]);
+
+const TOKENS: [Field; 4] = [
+    field("messages", NUMBER),
+    field("tokens", NUMBER),
+    field("input_tokens", NUMBER),
+    field("output_tokens", NUMBER),
+];
+
+const TOTALS: Shape = Shape::Object(&[
+    field("messages", NUMBER),
+    field("sessions", NUMBER),
+    field("tokens", NUMBER),
+    field("input_tokens", NUMBER),
+    field("output_tokens", NUMBER),
+    field("cost", NUMBER),
+]);
+
+const PERIOD: Shape = Shape::Object(&[
+    field("period", STRING),
+    TOKENS[0],
+    TOKENS[1],
+    field("cost", NUMBER),
+]);
+
+const MODEL_SHARE: Shape = Shape::Object(&[
+    field("model", NULLABLE_STRING),
+    field("display_name", NULLABLE_STRING),
+    TOKENS[0],
+    TOKENS[1],
+    TOKENS[2],
+    TOKENS[3],
+    field("cost", NUMBER),
+]);
+
+const SESSION_SUMMARY: Shape = Shape::Object(&[
+    field("session_id", STRING),
+    field("model", NULLABLE_STRING),
+    field("display_name", NULLABLE_STRING),
+    TOKENS[0],
+    TOKENS[1],
+    field("cost", NUMBER),
+    field("first_time", NULLABLE_STRING),
+    field("last_time", NULLABLE_STRING),
+]);
+
+const HOUR: Shape = Shape::Object(&[field("hour", NUMBER), TOKENS[0], TOKENS[1]]);
+
+const DASHBOARD: Shape = Shape::Object(&[
+    field("range", RANGE),
+    optional("totals", TOTALS),
+    optional("daily_activity", Shape::Map(&NUMBER)),
+    optional(
+        "timeline",
+        Shape::Object(&[field("granularity", STRING), field("data", Shape::Array(&PERIOD))]),
+    ),
+    optional("heatmap", Shape::Object(&[field("daily_activity", Shape::Map(&NUMBER))])),
+    optional("model_distribution", Shape::Array(&MODEL_SHARE)),
+    optional("hourly_profile", Shape::Array(&HOUR)),
+    optional("recent_sessions", Shape::Array(&SESSION_SUMMARY)),
+    field("meta", Shape::Object(&[])),
+]);
+
+const DAY: Shape = Shape::Object(&[
+    field("date", STRING),
+    field("totals", TOTALS),
+    field("hourly", Shape::Array(&HOUR)),
+    field("models", Shape::Array(&MODEL_SHARE)),
+    field("sessions", Shape::Array(&SESSION_SUMMARY)),
+]);
+
+const MODEL: Shape = Shape::Object(&[
+    field("model", STRING),
+    field("range", RANGE),
+    field("totals", TOTALS),
+    field("daily_activity", Shape::Map(&Shape::Any)),
+    field("sessions", Shape::Array(&SESSION_SUMMARY)),
+]);
+
+const SESSIONS: Shape = Shape::Object(&[
+    field("sessions", Shape::Array(&SESSION_SUMMARY)),
+    field("next_cursor", NULLABLE_STRING),
+    field("total_count", NUMBER),
+]);
+
+const SESSION: Shape = Shape::Object(&[
+    field("session_id", STRING),
+    field(
+        "totals",
+        Shape::Object(&[TOKENS[0], TOKENS[1], TOKENS[2], TOKENS[3], field("cost", NUMBER)]),
+    ),
+    field(
+        "messages",
+        Shape::Array(&Shape::Object(&[
+            field("timestamp", STRING),
+            field("model", NULLABLE_STRING),
+            field("input_tokens", NUMBER),
+            field("output_tokens", NUMBER),
+            field("cost", NUMBER),
+        ])),
+    ),
+]);
+
+const PROJECTS: Shape = Shape::Object(&[field(
+    "projects",
+    Shape::Array(&Shape::Object(&[
+        field("project_id", STRING),
+        field("name", STRING),
+        field("visible", Shape::Bool),
+    ])),
+)]);
+
+const LIMITS: Shape = Shape::Array(&Shape::Object(&[
+    field("limit_type", STRING),
+    field("reset_at", NULLABLE_STRING),
+    field("date", STRING),
+]));
+
+const METRIC_SERIES: Shape = Shape::Object(&[
+    field("granularity", STRING),
+    field("metrics", Shape::Array(&STRING)),
+    field("data", Shape::Array(&Shape::Object(&[field("period", STRING)]))),
+]);
+
+const PROJECT_TIMELINE: Shape = Shape::Object(&[
+    field("project_id", STRING),
+    field(
+        "curve",
+        Shape::Array(&Shape::Object(&[
+            field("period", STRING),
+            field("cost", NUMBER),
+            field("cumulative_cost", NUMBER),
+        ])),
+    ),
+    field("milestones", Shape::Array(&Shape::Object(&[field("type", STRING), field("date", STRING)]))),
+]);
+
+const USAGE_SPLIT: Shape = Shape::Object(&[
+    field("messages", NUMBER),
+    field("tokens", NUMBER),
+    field("cost", NUMBER),
+]);
+
+const OUT_OF_HOURS: Shape = Shape::Object(&[
+    field("work", USAGE_SPLIT),
+    field("personal", USAGE_SPLIT),
+    field("work_share", NULLABLE_NUMBER),
+    field(
+        "days",
+        Shape::Array(&Shape::Object(&[
+            field("date", STRING),
+            field("work", USAGE_SPLIT),
+            field("personal", USAGE_SPLIT),
+        ])),
+    ),
+]);
+
+const CONCURRENCY: Shape = Shape::Object(&[
+    field("max_concurrency", NUMBER),
+    field(
+        "levels",
+        Shape::Array(&Shape::Object(&[field("concurrency", NUMBER), field("minutes", NUMBER)])),
+    ),
+    field(
+        "daily",
+        Shape::Array(&Shape::Object(&[field("date", STRING), field("max_concurrency", NUMBER)])),
+    ),
+]);
+
+/// Subcommand and the shape of its response.
+pub const SCHEMAS: &[(&str, Shape)] = &[
+    ("dashboard", DASHBOARD),
+    ("day", DAY),
+    ("model", MODEL),
+    ("sessions", SESSIONS),
+    ("session", SESSION),
+    ("projects", PROJECTS),
+    ("limits", LIMITS),
+    ("metric-series", METRIC_SERIES),
+    ("project-timeline", PROJECT_TIMELINE),
+    ("out-of-hours-usage", OUT_OF_HOURS),
+    ("concurrency-stats", CONCURRENCY),
+];
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn expected_name(shape: &Shape) -> &'static str {
+    match shape {
+        Shape::Any => "any value",
+        Shape::Bool => "boolean",
+        Shape::Number => "number",
+        Shape::String => "string",
+        Shape::Nullable(inner) => expected_name(inner),
+        Shape::Array(_) => "array",
+        Shape::Map(_) | Shape::Object(_) => "object",
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Check `value` against `shape`; `Err((path, problem))` for the first mismatch.
+fn check_shape(shape: &Shape, value: &Value, path: &str) -> Result<(), (String, String)> {
+    let mismatch = || {
+        let at = if path.is_empty() { String::new() } else { format!("{}: ", path) };
+        Err((path.to_string(), format!("{}expected {}, got {}", at, expected_name(shape), type_name(value))))
+    };
+    match (shape, value) {
+        (Shape::Any, _) | (Shape::Bool, Value::Bool(_)) | (Shape::Number, Value::Number(_)) => Ok(()),
+        (Shape::String, Value::String(_)) => Ok(()),
+        (Shape::Nullable(_), Value::Null) => Ok(()),
+        (Shape::Nullable(inner), _) => check_shape(inner, value, path),
+        (Shape::Array(item), Value::Array(items)) => items
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, value)| check_shape(item, value, &format!("{}[{}]", path, i))),
+        (Shape::Map(item), Value::Object(map)) => map
+            .iter()
+            .try_for_each(|(key, value)| check_shape(item, value, &join(path, key))),
+        (Shape::Object(fields), Value::Object(map)) => fields.iter().try_for_each(|field| {
+            let path = join(path, field.name);
+            match map.get(field.name) {
+                Some(value) => check_shape(&field.shape, value, &path),
+                None if field.optional => Ok(()),
+                None => Err((path.clone(), format!("missing field {}", path))),
+            }
+        }),
+        _ => mismatch(),
+    }
+}
+
+/// Check a response of `subcommand` against its schema, if it has one.
+pub fn validate(subcommand: &str, response: &Value) -> Result<(), CommandError> {
+    let Some((_, shape)) = SCHEMAS.iter().find(|(name, _)| *name == subcommand) else {
+        return Ok(());
+    };
+    check_shape(shape, response, "").map_err(|(path, problem)| {
+        log::warn!("Unexpected {} response: {}", subcommand, problem);
+        CommandError::UnexpectedShape {
+            subcommand: subcommand.to_string(),
+            path,
+            message: format!("Backend returned unexpected shape for `{}`: {}", subcommand, problem),
+            request_id: None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    fn test_reports_first_offending_field() {
+        let mut dashboard: Value =
+            serde_json::from_str(include_str!("../fixtures/demo/dashboard.json")).unwrap();
+        assert!(validate("dashboard", &dashboard).is_ok());
+        // Sections that were not requested are left out
+        dashboard.as_object_mut().unwrap().remove("timeline");
+        assert!(validate("dashboard", &dashboard).is_ok());
+
+        dashboard["totals"].as_object_mut().unwrap().remove("tokens");
+        let error = validate("dashboard", &dashboard).unwrap_err();
+        assert_eq!(error.message(), "Backend returned unexpected shape for `dashboard`: missing field totals.tokens");
+        assert!(matches!(error, CommandError::UnexpectedShape { ref path, .. } if path == "totals.tokens"));
+
+        dashboard["totals"]["tokens"] = json!(1);
+        dashboard["hourly_profile"][2]["messages"] = json!("12");
+        let error = validate("dashboard", &dashboard).unwrap_err();
+        assert!(error.message().ends_with("hourly_profile[2].messages: expected number, got string"));
+
+        assert!(validate("limits", &json!({"error": "x"})).unwrap_err().message().ends_with("expected array, got object"));
+        assert!(validate("hooks", &json!("anything")).is_ok());
+    }
+
+    #[test]
+    fn test_recorded_fixtures_match_their_schema() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+        for dir in ["demo", "harness"] {
+            for (subcommand, _) in SCHEMAS {
+                let path = fixtures.join(dir).join(format!("{}.json", subcommand));
+                let Ok(text) = std::fs::read_to_string(&path) else {
+                    continue;
+                };
+                let response: Value = serde_json::from_str(&text).unwrap();
+                if let Err(e) = validate(subcommand, &response) {
+                    panic!("{}: {}", path.display(), e.message());
+                }
+            }
+        }
+    }
+}
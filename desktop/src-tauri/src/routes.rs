@@ -0,0 +1,154 @@
+/// Route-scoped cancellation of long read commands
+///
+/// The UI sends the route it is on with every invoke (`route`, next to
+/// `requestId`) and emits `route-changed` ({webview, route}) when the user
+/// navigates. Routes are `/`-separated paths such as
+/// `dashboard/2025-01-01_2025-12-31/all/day/2025-03-04`. The `RouteTracking`
+/// middleware records the request IDs of `ROUTE_SCOPED_COMMANDS` per webview; when a
+/// webview moves to another route, its calls recorded under routes that are
+/// neither the new route nor one of its parents are cancelled with
+/// `cancel_request`, so rapid navigation does not stack up backend work.
+/// Writes are never scoped.
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::CommandError;
+use crate::middleware::{Invocation, Middleware};
+use crate::python_bridge::cancel_request;
+
+/// Event the UI emits after navigating.
+pub const ROUTE_CHANGED_EVENT: &str = "route-changed";
+
+/// Read commands cancelled when the route they were made on is left.
+const ROUTE_SCOPED_COMMANDS: &[&str] = &[
+    "get_dashboard_bundle",
+    "get_dashboard_bundles",
+    "get_day_details",
+    "get_model_details",
+    "get_session_details",
+    "get_limit_impact",
+    "get_outcome_stats",
+    "get_compaction_stats",
+    "get_mcp_usage",
+    "get_hook_stats",
+    "get_effort_breakdown",
+    "get_concurrency_stats",
+    "compare_usage_accounts",
+    "get_combined_dashboard",
+    "get_project_timeline",
+    "get_budget_history",
+    "get_out_of_hours_usage",
+    "get_cost_allocation",
+    "diff_snapshots",
+];
+
+/// Calls remembered per webview; the oldest are forgotten first.
+const MAX_TRACKED_CALLS: usize = 256;
+
+/// Payload of `route-changed`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RouteChange {
+    /// Label of the webview that navigated
+    pub webview: String,
+    pub route: String,
+}
+
+#[derive(Default)]
+struct WebviewRoute {
+    route: Option<String>,
+    /// (request ID, route) of scoped calls
+    calls: Vec<(String, String)>,
+}
+
+static ROUTES: Mutex<BTreeMap<String, WebviewRoute>> = Mutex::new(BTreeMap::new());
+
+/// Whether `route` is `current` or one of its parents.
+fn is_within(route: &str, current: &str) -> bool {
+    let mut current = current.split('/');
+    route.split('/').all(|segment| current.next() == Some(segment))
+}
+
+/// Record a scoped invoke with the route from its arguments, else the
+/// webview's current route. Calls without a `requestId` get an ID in the
+/// command and cannot be cancelled, so they are not recorded.
+pub fn track_invoke(webview: &str, command: &str, args: &Value) {
+    if !ROUTE_SCOPED_COMMANDS.contains(&command) {
+        return;
+    }
+    let Some(request_id) = args.get("requestId").and_then(Value::as_str) else {
+        return;
+    };
+    let mut routes = ROUTES.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = routes.entry(webview.to_string()).or_default();
+    let route = args.get("route").and_then(Value::as_str).map(str::to_string);
+    let Some(route) = route.or_else(|| entry.route.clone()) else {
+        return;
+    };
+    entry.calls.push((request_id.to_string(), route));
+    if entry.calls.len() > MAX_TRACKED_CALLS {
+        entry.calls.remove(0);
+    }
+}
+
+/// A webview moved to `route`: cancel its calls made on routes it has left.
+///
+/// Returns the request IDs whose calls were still running.
+pub fn change_route(webview: &str, route: &str) -> Vec<String> {
+    let left: Vec<(String, String)> = {
+        let mut routes = ROUTES.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = routes.entry(webview.to_string()).or_default();
+        entry.route = Some(route.to_string());
+        let (kept, left) = std::mem::take(&mut entry.calls)
+            .into_iter()
+            .partition(|(_, call_route)| is_within(call_route, route));
+        entry.calls = kept;
+        left
+    };
+    left.into_iter()
+        .filter(|(request_id, _)| cancel_request(request_id))
+        .map(|(request_id, _)| request_id)
+        .collect()
+}
+
+/// Records scoped invokes before they run; never rejects.
+pub struct RouteTracking;
+
+impl Middleware for RouteTracking {
+    fn name(&self) -> &'static str {
+        "routes"
+    }
+
+    fn handle(&self, invocation: &Invocation) -> Result<(), CommandError> {
+        track_invoke(invocation.webview, invocation.command, invocation.args);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_change_route_keeps_parent_routes() {
+        let range = "dashboard/2025-01-01_2025-12-31/all";
+        assert!(is_within(range, &format!("{}/day/2025-03-04", range)));
+        assert!(!is_within(&format!("{}/day/2025-03-04", range), range));
+        assert!(!is_within("dashboard/2025-01-01_2025-06-30/all", range));
+
+        let webview = "test-routes";
+        change_route(webview, range);
+        track_invoke(webview, "get_dashboard_bundle", &json!({"requestId": "rq-dash"}));
+        track_invoke(webview, "get_day_details", &json!({"requestId": "rq-day", "route": format!("{}/day/2025-03-04", range)}));
+        track_invoke(webview, "update_project", &json!({"requestId": "rq-write"}));
+
+        // Nothing is running, so nothing is reported cancelled; left calls are forgotten
+        assert!(change_route(webview, &format!("{}/day/2025-03-05", range)).is_empty());
+        let routes = ROUTES.lock().unwrap();
+        let calls: Vec<&str> = routes[webview].calls.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(calls, vec!["rq-dash"]);
+    }
+}
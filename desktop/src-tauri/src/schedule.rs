@@ -0,0 +1,631 @@
+/// Scheduled exports and their timezone-aware fire times
+///
+/// A schedule pairs an export (`ExportKind` and its parameters, with a range
+/// preset instead of fixed dates) with a wall-clock rule such as "every
+/// Monday 08:00" or "monthly on the 1st at 07:30". Rules are kept in local
+/// time and turned into instants only when the next fire time is needed, so
+/// "08:00" stays 08:00 after a DST switch or a change of the system timezone.
+///
+/// Days where the wall-clock time does not name exactly one instant:
+/// - in a DST gap (02:30 on a spring-forward night) the schedule fires with
+///   the offset in force before the gap, i.e. shifted forward by its length;
+/// - in a DST overlap (02:30 on a fall-back night) it fires once, at the
+///   first of the two instants.
+///
+/// `get_schedule_preview` lists the upcoming fire times of a rule, with any
+/// such adjustment, so users can check a schedule before relying on it.
+///
+/// Schedules are stored in `~/.claude/db/command-center-export-schedules.json`.
+/// The `export-scheduler` task wakes at least every `POLL_INTERVAL`, queues
+/// due exports through `exports` and records the fire. Fire times missed
+/// while the app was closed are caught up with one export.
+use std::fs;
+use std::sync::Mutex;
+use std::time::Duration as StdDuration;
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+    TimeZone, Utc, Weekday,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+
+use crate::exports::{self, ExportKind};
+use crate::python_bridge::new_request_id;
+use crate::ranges::{self, RangeContext, RangePreset};
+use crate::schema::{self, Migration, Store};
+use crate::settings::{db_dir, Settings};
+
+pub const SCHEDULES_FILE: &str = "command-center-export-schedules.json";
+
+/// Longest the scheduler sleeps before looking at the schedules again.
+const POLL_INTERVAL: StdDuration = StdDuration::from_secs(60);
+
+/// Days searched for the next fire time (covers every monthly rule).
+const SEARCH_DAYS: usize = 400;
+
+/// Fire times returned by a preview without a `count`.
+pub const DEFAULT_PREVIEW_COUNT: usize = 5;
+
+/// Most fire times returned by one preview.
+pub const MAX_PREVIEW_COUNT: usize = 50;
+
+fn schedules_v1(_obj: &mut Map<String, Value>) {}
+
+pub const SCHEDULES_MIGRATIONS: &[Migration] = &[Migration {
+    to: 1,
+    description: "Add schema_version",
+    apply: schedules_v1,
+}];
+
+pub const SCHEDULES_STORE: Store = Store {
+    name: "export_schedules",
+    file: SCHEDULES_FILE,
+    migrations: SCHEDULES_MIGRATIONS,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// When a schedule fires, in wall-clock time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleRule {
+    pub frequency: Frequency,
+    /// Weekly rules: day of the week ("monday")
+    #[serde(default)]
+    pub weekday: Option<String>,
+    /// Monthly rules: day of the month, 1-31 (the last day in shorter months)
+    #[serde(default)]
+    pub day: Option<u32>,
+    /// "HH:MM"
+    pub time: String,
+    /// "local" (the system timezone, with its DST), "UTC" or a fixed offset
+    /// like "+02:00"; default: the `timezone` setting
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+/// A validated `ScheduleRule`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub weekday: Weekday,
+    pub day: u32,
+    pub time: NaiveTime,
+}
+
+impl ScheduleRule {
+    pub fn recurrence(&self) -> Result<Recurrence, String> {
+        let time = NaiveTime::parse_from_str(self.time.trim(), "%H:%M")
+            .map_err(|_| format!("Invalid schedule time: {:?} (expected HH:MM)", self.time))?;
+        let weekday = match (self.frequency, self.weekday.as_deref().map(str::trim)) {
+            (Frequency::Weekly, None | Some("")) => return Err("Weekly schedules need a weekday".to_string()),
+            (_, None | Some("")) => Weekday::Mon,
+            (_, Some(day)) => day
+                .parse::<Weekday>()
+                .map_err(|_| format!("Invalid weekday: {:?} (expected a weekday such as monday)", day))?,
+        };
+        let day = match (self.frequency, self.day) {
+            (Frequency::Monthly, None) => return Err("Monthly schedules need a day of the month".to_string()),
+            (_, Some(day)) if !(1..=31).contains(&day) => {
+                return Err(format!("Invalid day of the month: {} (expected 1-31)", day))
+            }
+            (_, day) => day.unwrap_or(1),
+        };
+        Ok(Recurrence { frequency: self.frequency, weekday, day, time })
+    }
+
+    /// Timezone of the rule, falling back to the `timezone` setting.
+    pub fn zone(&self, settings: &Settings) -> Result<Zone, String> {
+        Zone::parse(self.timezone.as_deref().or(settings.timezone.as_deref()))
+    }
+}
+
+impl Recurrence {
+    fn matches(&self, date: NaiveDate) -> bool {
+        match self.frequency {
+            Frequency::Daily => true,
+            Frequency::Weekly => date.weekday() == self.weekday,
+            Frequency::Monthly => date.day() == self.day.min(days_in_month(date)),
+        }
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 { (date.year() + 1, 1) } else { (date.year(), date.month() + 1) };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|next| next.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+/// Timezones a rule can use (the vocabulary of the `timezone` setting).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl Zone {
+    pub fn parse(name: Option<&str>) -> Result<Self, String> {
+        match name.map(str::trim) {
+            None | Some("") | Some("local") => Ok(Zone::Local),
+            Some(tz) if tz.eq_ignore_ascii_case("utc") => Ok(Zone::Fixed(Utc.fix())),
+            Some(tz) => tz
+                .parse::<FixedOffset>()
+                .map(Zone::Fixed)
+                .map_err(|_| format!("Invalid timezone: {:?} (expected local, UTC or an offset like +02:00)", tz)),
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Zone::Local => "local".to_string(),
+            Zone::Fixed(offset) if offset.local_minus_utc() == 0 => "UTC".to_string(),
+            Zone::Fixed(offset) => offset.to_string(),
+        }
+    }
+
+    /// `next_fire` in this zone.
+    pub fn next_fire(self, recurrence: &Recurrence, after: DateTime<Utc>) -> Option<Fire> {
+        match self {
+            Zone::Local => next_fire(recurrence, &Local, after),
+            Zone::Fixed(offset) => next_fire(recurrence, &offset, after),
+        }
+    }
+
+    /// The next `count` fire times after `after`.
+    pub fn preview(self, recurrence: &Recurrence, after: DateTime<Utc>, count: usize) -> Vec<Fire> {
+        let mut fires: Vec<Fire> = Vec::with_capacity(count);
+        while fires.len() < count {
+            let from = fires.last().map_or(after, |fire| fire.at);
+            match self.next_fire(recurrence, from) {
+                Some(fire) => fires.push(fire),
+                None => break,
+            }
+        }
+        fires
+    }
+}
+
+/// How a fire time was placed on a day where its wall-clock time is not
+/// exactly one instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DstAdjustment {
+    /// The time falls into a DST gap; it fires that much later
+    ShiftedForward,
+    /// The time occurs twice (DST ends); it fires at the first one
+    FirstOfTwo,
+}
+
+/// One fire time of a schedule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Fire {
+    #[serde(serialize_with = "rfc3339")]
+    pub at: DateTime<Utc>,
+    /// The same instant in the rule's timezone
+    #[serde(serialize_with = "rfc3339")]
+    pub local: DateTime<FixedOffset>,
+    pub weekday: &'static str,
+    pub adjustment: Option<DstAdjustment>,
+}
+
+fn rfc3339<Tz: TimeZone, S: serde::Serializer>(time: &DateTime<Tz>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    serializer.serialize_str(&time.to_rfc3339())
+}
+
+/// Instant of a wall-clock time, applying the DST rules above.
+fn resolve_local<Tz: TimeZone>(tz: &Tz, local: NaiveDateTime) -> Option<(DateTime<Tz>, Option<DstAdjustment>)> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(time) => Some((time, None)),
+        LocalResult::Ambiguous(first, _) => Some((first, Some(DstAdjustment::FirstOfTwo))),
+        LocalResult::None => {
+            // Gaps are at most a few hours: the offset a day earlier is the one before the gap.
+            let before = tz.offset_from_local_datetime(&(local - Duration::days(1))).earliest()?.fix();
+            let utc = local - Duration::seconds(i64::from(before.local_minus_utc()));
+            Some((tz.from_utc_datetime(&utc), Some(DstAdjustment::ShiftedForward)))
+        }
+    }
+}
+
+/// First fire time of `recurrence` in `tz` strictly after `after`.
+pub fn next_fire<Tz: TimeZone>(recurrence: &Recurrence, tz: &Tz, after: DateTime<Utc>) -> Option<Fire> {
+    // Start a day early: a shifted fire of the previous local day can still be ahead.
+    let start = after.with_timezone(tz).date_naive().pred_opt()?;
+    for date in start.iter_days().take(SEARCH_DAYS) {
+        if !recurrence.matches(date) {
+            continue;
+        }
+        let Some((time, adjustment)) = resolve_local(tz, date.and_time(recurrence.time)) else { continue };
+        let at = time.with_timezone(&Utc);
+        if at > after {
+            let weekday = ranges::weekday_name(date.weekday());
+            return Some(Fire { at, local: time.fixed_offset(), weekday, adjustment });
+        }
+    }
+    None
+}
+
+/// An export run on a schedule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportSchedule {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub kind: ExportKind,
+    /// Export parameters with a `range` preset in place of dates: png_report
+    /// gets `from`/`to`, cost_allocation the `month` the range starts in
+    pub params: Value,
+    pub rule: ScheduleRule,
+    pub enabled: bool,
+    pub created_at: String,
+    #[serde(default)]
+    pub last_fired_at: Option<String>,
+    /// Export job queued by the last fire
+    #[serde(default)]
+    pub last_job_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleList {
+    #[serde(default)]
+    pub schema_version: u32,
+    #[serde(default)]
+    pub schedules: Vec<ExportSchedule>,
+}
+
+impl ScheduleList {
+    fn load() -> Self {
+        db_dir()
+            .ok()
+            .and_then(|dir| fs::read(dir.join(SCHEDULES_FILE)).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let dir = db_dir()?;
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let mut value = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        value["schema_version"] = Value::from(SCHEDULES_STORE.latest());
+        schema::write_json_atomic(&dir.join(SCHEDULES_FILE), &value)
+    }
+}
+
+/// Serializes read-modify-write of the schedules file.
+static SCHEDULES_LOCK: Mutex<()> = Mutex::new(());
+
+fn with_schedules<T>(f: impl FnOnce(&mut ScheduleList) -> Result<T, String>) -> Result<T, String> {
+    let _guard = SCHEDULES_LOCK.lock().map_err(|_| "Export schedules lock poisoned".to_string())?;
+    let mut list = ScheduleList::load();
+    let result = f(&mut list)?;
+    list.save()?;
+    Ok(result)
+}
+
+/// All schedules, oldest first.
+pub fn list() -> Vec<ExportSchedule> {
+    ScheduleList::load().schedules
+}
+
+/// Export parameters of a fire on `today`: the `range` preset resolved to
+/// the dates `kind` takes.
+pub fn job_params(kind: ExportKind, params: &Value, today: NaiveDate, settings: &Settings) -> Result<Value, String> {
+    let raw = params["range"].as_str().ok_or("Scheduled exports need a range preset")?;
+    let preset = RangePreset::parse(raw)?;
+    if preset == RangePreset::SinceLastLimitReset {
+        return Err("Scheduled exports cannot use the since-last-limit-reset range".to_string());
+    }
+    let ctx = RangeContext {
+        today,
+        week_start: ranges::week_start(settings)?,
+        billing_anchor_day: ranges::billing_anchor_day(settings),
+        last_limit_reset: None,
+    };
+    let (from, to) = ranges::resolve(preset, &ctx)?;
+    let mut job = params.as_object().cloned().unwrap_or_default();
+    job.remove("range");
+    match kind {
+        ExportKind::PngReport => {
+            job.insert("from".to_string(), Value::from(from.format("%Y-%m-%d").to_string()));
+            job.insert("to".to_string(), Value::from(to.format("%Y-%m-%d").to_string()));
+        }
+        ExportKind::CostAllocation => {
+            job.insert("month".to_string(), Value::from(from.format("%Y-%m").to_string()));
+        }
+        ExportKind::RawSessions => return Err("Raw session exports cannot be scheduled".to_string()),
+    }
+    let job = Value::Object(job);
+    exports::python_args(kind, &job, "scheduled")?;
+    Ok(job)
+}
+
+/// Add a schedule (`id` None) or replace one, after validating it.
+pub fn set(
+    id: Option<String>,
+    name: Option<String>,
+    kind: ExportKind,
+    params: Value,
+    rule: ScheduleRule,
+    enabled: bool,
+) -> Result<ExportSchedule, String> {
+    let settings = Settings::load();
+    rule.recurrence()?;
+    rule.zone(&settings)?;
+    job_params(kind, &params, ranges::today(&settings)?, &settings)?;
+    let name = name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+    with_schedules(|list| match id {
+        Some(id) => {
+            let schedule = list
+                .schedules
+                .iter_mut()
+                .find(|schedule| schedule.id == id)
+                .ok_or_else(|| format!("Unknown export schedule: {}", id))?;
+            if schedule.rule != rule {
+                // Fire times are counted from the change, not from a past fire under the old rule.
+                schedule.created_at = Local::now().to_rfc3339();
+                schedule.last_fired_at = None;
+            }
+            schedule.name = name;
+            schedule.kind = kind;
+            schedule.params = params;
+            schedule.rule = rule;
+            schedule.enabled = enabled;
+            Ok(schedule.clone())
+        }
+        None => {
+            let schedule = ExportSchedule {
+                id: format!("sched-{}", new_request_id()),
+                name,
+                kind,
+                params,
+                rule,
+                enabled,
+                created_at: Local::now().to_rfc3339(),
+                last_fired_at: None,
+                last_job_id: None,
+            };
+            list.schedules.push(schedule.clone());
+            Ok(schedule)
+        }
+    })
+}
+
+/// Remove a schedule; false if there was none with this id.
+pub fn delete(id: &str) -> Result<bool, String> {
+    with_schedules(|list| {
+        let before = list.schedules.len();
+        list.schedules.retain(|schedule| schedule.id != id);
+        Ok(list.schedules.len() != before)
+    })
+}
+
+/// Next fire time of a schedule: the first one after its last fire (or its
+/// creation), which is in the past when a fire was missed.
+pub fn next_fire_of(schedule: &ExportSchedule, settings: &Settings) -> Result<Option<Fire>, String> {
+    let recurrence = schedule.rule.recurrence()?;
+    let zone = schedule.rule.zone(settings)?;
+    let since = schedule.last_fired_at.as_deref().unwrap_or(&schedule.created_at);
+    let since = DateTime::parse_from_rfc3339(since)
+        .map_err(|e| format!("Invalid timestamp in schedule {}: {}", schedule.id, e))?
+        .with_timezone(&Utc);
+    Ok(zone.next_fire(&recurrence, since))
+}
+
+/// Queue the export of a due schedule and record the fire.
+fn fire(app: &AppHandle, schedule: &ExportSchedule, fire: &Fire, settings: &Settings) -> Result<String, String> {
+    let params = job_params(schedule.kind, &schedule.params, fire.local.date_naive(), settings)?;
+    let output = exports::default_export_dir(app).join(exports::default_filename(schedule.kind, &params));
+    let job = exports::create(schedule.kind, params, output.to_string_lossy().to_string(), new_request_id(), None)?;
+    exports::submit(app, job.id.clone())?;
+    Ok(job.id)
+}
+
+/// Fire the due schedules; returns how long to sleep until the next check.
+fn tick(app: &AppHandle, now: DateTime<Utc>) -> StdDuration {
+    let settings = Settings::load();
+    let mut sleep = POLL_INTERVAL;
+    for schedule in list().into_iter().filter(|schedule| schedule.enabled) {
+        let next = match next_fire_of(&schedule, &settings) {
+            Ok(Some(next)) => next,
+            Ok(None) => continue,
+            Err(e) => {
+                log::warn!("Skipping export schedule {}: {}", schedule.id, e);
+                continue;
+            }
+        };
+        if next.at > now {
+            sleep = sleep.min((next.at - now).to_std().unwrap_or_default());
+            continue;
+        }
+        let outcome = fire(app, &schedule, &next, &settings);
+        match &outcome {
+            Ok(job_id) => log::info!("Export schedule {} queued job {} (due {})", schedule.id, job_id, next.local),
+            Err(e) => log::warn!("Export schedule {} failed: {}", schedule.id, e),
+        }
+        let recorded = with_schedules(|list| {
+            if let Some(stored) = list.schedules.iter_mut().find(|stored| stored.id == schedule.id) {
+                stored.last_fired_at = Some(now.with_timezone(&Local).to_rfc3339());
+                stored.last_job_id = outcome.ok();
+            }
+            Ok(())
+        });
+        if let Err(e) = recorded {
+            log::warn!("Failed to record the fire of export schedule {}: {}", schedule.id, e);
+        }
+    }
+    sleep
+}
+
+/// Body of the `export-scheduler` task. Fire times are recomputed from the
+/// rules on every wake, so DST switches and timezone changes take effect
+/// within `POLL_INTERVAL`.
+pub fn run(app: AppHandle) {
+    loop {
+        let sleep = tick(&app, Utc::now());
+        std::thread::sleep(sleep.max(StdDuration::from_secs(1)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Central European time with the 2026 switches (last Sundays of March
+    /// and October, 01:00 UTC).
+    #[derive(Debug, Clone, Copy)]
+    struct Cet;
+
+    impl Cet {
+        fn offset_at(utc: &NaiveDateTime) -> FixedOffset {
+            let summer = utc_time("2026-03-29 01:00")..utc_time("2026-10-25 01:00");
+            FixedOffset::east_opt(if summer.contains(utc) { 7200 } else { 3600 }).unwrap()
+        }
+    }
+
+    impl TimeZone for Cet {
+        type Offset = FixedOffset;
+
+        fn from_offset(_offset: &FixedOffset) -> Self {
+            Cet
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let valid: Vec<FixedOffset> = [7200, 3600]
+                .iter()
+                .map(|secs| FixedOffset::east_opt(*secs).unwrap())
+                .filter(|offset| {
+                    let utc = *local - Duration::seconds(i64::from(offset.local_minus_utc()));
+                    Cet::offset_at(&utc) == *offset
+                })
+                .collect();
+            match valid[..] {
+                [offset] => LocalResult::Single(offset),
+                [first, second] => LocalResult::Ambiguous(first, second),
+                _ => LocalResult::None,
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            Cet::offset_at(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            Cet::offset_at(utc)
+        }
+    }
+
+    fn utc_time(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        utc_time(s).and_utc()
+    }
+
+    fn rule(frequency: Frequency, weekday: Option<&str>, day: Option<u32>, time: &str) -> Recurrence {
+        ScheduleRule { frequency, weekday: weekday.map(String::from), day, time: time.to_string(), timezone: None }
+            .recurrence()
+            .unwrap()
+    }
+
+    fn fires(recurrence: &Recurrence, after: &str, count: usize) -> Vec<(String, Option<DstAdjustment>)> {
+        let mut after = utc(after);
+        let mut fires = Vec::new();
+        for _ in 0..count {
+            let fire = next_fire(recurrence, &Cet, after).unwrap();
+            after = fire.at;
+            fires.push((fire.local.to_rfc3339(), fire.adjustment));
+        }
+        fires
+    }
+
+    #[test]
+    fn test_weekly_rule_keeps_wall_clock_time_across_dst() {
+        let monday = rule(Frequency::Weekly, Some("monday"), None, "08:00");
+        let local: Vec<String> = fires(&monday, "2026-03-20 00:00", 3).into_iter().map(|(time, _)| time).collect();
+        assert_eq!(local, ["2026-03-23T08:00:00+01:00", "2026-03-30T08:00:00+02:00", "2026-04-06T08:00:00+02:00"]);
+        let local: Vec<String> = fires(&monday, "2026-10-20 00:00", 2).into_iter().map(|(time, _)| time).collect();
+        assert_eq!(local, ["2026-10-26T08:00:00+01:00", "2026-11-02T08:00:00+01:00"]);
+        // Exactly at a fire time, the next one is a week later.
+        let at = next_fire(&monday, &Cet, utc("2026-03-30 06:00")).unwrap();
+        assert_eq!(at.at, utc("2026-04-06 06:00"));
+        assert_eq!(at.weekday, "monday");
+    }
+
+    #[test]
+    fn test_daily_rule_in_dst_gap_and_overlap() {
+        let daily = rule(Frequency::Daily, None, None, "02:30");
+        assert_eq!(
+            fires(&daily, "2026-03-28 12:00", 2),
+            [
+                ("2026-03-29T03:30:00+02:00".to_string(), Some(DstAdjustment::ShiftedForward)),
+                ("2026-03-30T02:30:00+02:00".to_string(), None),
+            ]
+        );
+        assert_eq!(
+            fires(&daily, "2026-10-24 12:00", 2),
+            [
+                ("2026-10-25T02:30:00+02:00".to_string(), Some(DstAdjustment::FirstOfTwo)),
+                ("2026-10-26T02:30:00+01:00".to_string(), None),
+            ]
+        );
+        // The second 02:30 of the overlap does not fire again.
+        let after_first = next_fire(&daily, &Cet, utc("2026-10-25 00:30")).unwrap();
+        assert_eq!(after_first.at, utc("2026-10-26 01:30"));
+    }
+
+    #[test]
+    fn test_monthly_rule_clamps_to_month_end() {
+        let last = rule(Frequency::Monthly, None, Some(31), "07:00");
+        let local: Vec<String> = fires(&last, "2026-01-31 12:00", 3).into_iter().map(|(time, _)| time).collect();
+        assert_eq!(local, ["2026-02-28T07:00:00+01:00", "2026-03-31T07:00:00+02:00", "2026-04-30T07:00:00+02:00"]);
+
+        let fixed = Zone::parse(Some("-05:00")).unwrap();
+        let preview = fixed.preview(&rule(Frequency::Monthly, None, Some(1), "00:15"), utc("2026-01-01 00:00"), 2);
+        assert_eq!(preview[0].at, utc("2026-01-01 05:15"));
+        assert_eq!(preview[1].local.to_rfc3339(), "2026-02-01T00:15:00-05:00");
+        assert_eq!(Zone::parse(Some("utc")).unwrap().name(), "UTC");
+    }
+
+    #[test]
+    fn test_invalid_rules_and_params_are_rejected() {
+        let base = ScheduleRule {
+            frequency: Frequency::Weekly,
+            weekday: None,
+            day: None,
+            time: "08:00".to_string(),
+            timezone: None,
+        };
+        assert!(base.recurrence().is_err());
+        assert!(ScheduleRule { weekday: Some("mon".to_string()), time: "8am".to_string(), ..base.clone() }
+            .recurrence()
+            .is_err());
+        assert!(ScheduleRule { frequency: Frequency::Monthly, day: Some(32), ..base.clone() }.recurrence().is_err());
+        assert!(Zone::parse(Some("Europe/Warsaw")).is_err());
+
+        let settings = Settings::default();
+        let today = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let png = job_params(ExportKind::PngReport, &json!({"range": "last-week", "scale": 2}), today, &settings);
+        assert_eq!(png.unwrap(), json!({"from": "2026-02-23", "to": "2026-03-01", "scale": 2}));
+        let allocation = job_params(ExportKind::CostAllocation, &json!({"range": "last-month"}), today, &settings);
+        assert_eq!(allocation.unwrap(), json!({"month": "2026-02"}));
+        assert!(job_params(ExportKind::PngReport, &json!({"from": "2026-01-01"}), today, &settings).is_err());
+        assert!(job_params(ExportKind::PngReport, &json!({"range": "since-limit-reset"}), today, &settings).is_err());
+        assert!(job_params(ExportKind::RawSessions, &json!({"range": "today"}), today, &settings).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+/// Schema versions and migrations of the files the desktop app owns
+///
+/// Every versioned JSON file stores a `schema_version`. On startup files
+/// older than the app are migrated in order, one step per version, after a
+/// copy of the original is saved as `<file>.v<old>.<timestamp>.bak`. Files
+/// written by a newer app version are left untouched (and not overwritten),
+/// so downgrading never destroys data. Files without `schema_version`
+/// predate versioning and count as version 0.
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::api_tokens::API_TOKENS_STORE;
+use crate::atomic::write_atomic;
+use crate::exports::EXPORTS_STORE;
+use crate::features::FEATURES_STORE;
+use crate::schedule::SCHEDULES_STORE;
+use crate::settings::{db_dir, SETTINGS_FILE};
+
+/// One step from `to - 1` to `to`.
+pub struct Migration {
+    pub to: u32,
+    pub description: &'static str,
+    pub apply: fn(&mut Map<String, Value>),
+}
+
+/// A versioned file and its migrations (ordered by `to`).
+pub struct Store {
+    pub name: &'static str,
+    pub file: &'static str,
+    pub migrations: &'static [Migration],
+}
+
+impl Store {
+    pub fn latest(&self) -> u32 {
+        self.migrations.last().map_or(0, |m| m.to)
+    }
+
+    pub fn path(&self) -> Result<PathBuf, String> {
+        Ok(db_dir()?.join(self.file))
+    }
+}
+
+/// Version 1: stamp the version and normalize hand-edited values the app
+/// would reject (mixed-case week start, padded timezone, anchor outside 1-31).
+fn settings_v1(obj: &mut Map<String, Value>) {
+    if let Some(Value::String(day)) = obj.get_mut("week_start") {
+        *day = day.trim().to_lowercase();
+    }
+    if let Some(Value::String(tz)) = obj.get_mut("timezone") {
+        *tz = tz.trim().to_string();
+    }
+    let anchor_valid = match obj.get("billing_anchor_day") {
+        None => true,
+        Some(day) => day.as_u64().is_some_and(|day| (1..=31).contains(&day)),
+    };
+    if !anchor_valid {
+        obj.remove("billing_anchor_day");
+    }
+}
+
+pub const SETTINGS_MIGRATIONS: &[Migration] = &[Migration {
+    to: 1,
+    description: "Add schema_version; normalize week_start/timezone, drop invalid billing_anchor_day",
+    apply: settings_v1,
+}];
+
+pub const SETTINGS_STORE: Store = Store {
+    name: "settings",
+    file: SETTINGS_FILE,
+    migrations: SETTINGS_MIGRATIONS,
+};
+
+/// All versioned stores, as listed by `get_schema_info`.
+pub const STORES: &[Store] = &[SETTINGS_STORE, FEATURES_STORE, EXPORTS_STORE, API_TOKENS_STORE, SCHEDULES_STORE];
+
+/// Version stored in a file's object (0 if absent).
+pub fn schema_version(obj: &Map<String, Value>) -> u32 {
+    obj.get("schema_version")
+        .and_then(Value::as_u64)
+        .map_or(0, |v| u32::try_from(v).unwrap_or(u32::MAX))
+}
+
+/// Apply the migrations after `from`; returns the descriptions of the steps run.
+pub fn migrate_object(obj: &mut Map<String, Value>, migrations: &[Migration]) -> Vec<&'static str> {
+    let from = schema_version(obj);
+    let mut applied = Vec::new();
+    for migration in migrations.iter().filter(|m| m.to > from) {
+        (migration.apply)(obj);
+        obj.insert("schema_version".to_string(), Value::from(migration.to));
+        applied.push(migration.description);
+    }
+    applied
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StoreStatus {
+    /// File does not exist yet (defaults are used)
+    Missing,
+    Current,
+    /// Older than the app; migrated on the next start
+    Outdated,
+    Migrated,
+    /// Written by a newer app version; left untouched
+    Newer,
+    /// Unreadable or not a JSON object
+    Invalid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StoreInfo {
+    pub name: String,
+    pub path: String,
+    pub version: Option<u32>,
+    pub latest: u32,
+    pub status: StoreStatus,
+    /// Backup written before migrating
+    pub backup: Option<String>,
+    pub applied: Vec<String>,
+    pub error: Option<String>,
+}
+
+fn read_object(path: &Path) -> Result<Option<Map<String, Value>>, String> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(Value::Object(obj)) => Ok(Some(obj)),
+        Ok(_) => Err(format!("{} is not a JSON object", path.display())),
+        Err(e) => Err(format!("Failed to parse {}: {}", path.display(), e)),
+    }
+}
+
+/// Write pretty JSON atomically (see `atomic`).
+pub fn write_json_atomic(path: &Path, value: &Value) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    write_atomic(path, &json)
+}
+
+/// Inspect (and with `apply`, migrate) one store file.
+pub fn check_store(name: &str, path: &Path, migrations: &[Migration], apply: bool) -> StoreInfo {
+    let latest = migrations.last().map_or(0, |m| m.to);
+    let mut info = StoreInfo {
+        name: name.to_string(),
+        path: path.display().to_string(),
+        version: None,
+        latest,
+        status: StoreStatus::Missing,
+        backup: None,
+        applied: Vec::new(),
+        error: None,
+    };
+    let mut obj = match read_object(path) {
+        Ok(Some(obj)) => obj,
+        Ok(None) => return info,
+        Err(e) => {
+            info.status = StoreStatus::Invalid;
+            info.error = Some(e);
+            return info;
+        }
+    };
+
+    let version = schema_version(&obj);
+    info.version = Some(version);
+    info.status = match version.cmp(&latest) {
+        std::cmp::Ordering::Equal => StoreStatus::Current,
+        std::cmp::Ordering::Greater => StoreStatus::Newer,
+        std::cmp::Ordering::Less => StoreStatus::Outdated,
+    };
+    if !apply || info.status != StoreStatus::Outdated {
+        return info;
+    }
+
+    let stamp = chrono::Local::now().format("%Y%m%d%H%M%S");
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("store");
+    let backup = path.with_file_name(format!("{}.v{}.{}.bak", file_name, version, stamp));
+    if let Err(e) = fs::copy(path, &backup) {
+        info.error = Some(format!("Backup to {} failed, not migrating: {}", backup.display(), e));
+        return info;
+    }
+    info.backup = Some(backup.display().to_string());
+
+    info.applied = migrate_object(&mut obj, migrations)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    match write_json_atomic(path, &Value::Object(obj)) {
+        Ok(()) => {
+            info.version = Some(latest);
+            info.status = StoreStatus::Migrated;
+        }
+        Err(e) => info.error = Some(e),
+    }
+    info
+}
+
+/// Migrate all stores (called once on startup).
+pub fn migrate_all() -> Vec<StoreInfo> {
+    STORES
+        .iter()
+        .filter_map(|store| {
+            let path = store.path().ok()?;
+            Some(check_store(store.name, &path, store.migrations, true))
+        })
+        .collect()
+}
+
+/// Versions of all stores without changing anything.
+pub fn inspect_all() -> Vec<StoreInfo> {
+    STORES
+        .iter()
+        .filter_map(|store| {
+            let path = store.path().ok()?;
+            Some(check_store(store.name, &path, store.migrations, false))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cc-schema-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.json");
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_unversioned_file_is_backed_up_and_migrated() {
+        let path = temp_file("migrate", r#"{"week_start": " Sunday", "billing_anchor_day": 40, "x": 1}"#);
+        let info = check_store("settings", &path, SETTINGS_MIGRATIONS, true);
+
+        assert_eq!(info.status, StoreStatus::Migrated);
+        assert_eq!(info.applied.len(), 1);
+        let backup = fs::read_to_string(info.backup.unwrap()).unwrap();
+        assert!(backup.contains("Sunday"));
+
+        let migrated: Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(migrated["schema_version"], 1);
+        assert_eq!(migrated["week_start"], "sunday");
+        assert!(migrated.get("billing_anchor_day").is_none());
+        assert_eq!(migrated["x"], 1);
+    }
+
+    #[test]
+    fn test_newer_file_is_left_untouched() {
+        let content = r#"{"schema_version": 99, "week_start": "Sunday"}"#;
+        let path = temp_file("newer", content);
+        let info = check_store("settings", &path, SETTINGS_MIGRATIONS, true);
+
+        assert_eq!(info.status, StoreStatus::Newer);
+        assert!(info.backup.is_none());
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    }
+}
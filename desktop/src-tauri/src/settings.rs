@@ -0,0 +1,125 @@
+/// App settings shared by the desktop app and the Python backend
+///
+/// Stored as `~/.claude/db/command-center-settings.json`. Keys this module
+/// does not know (e.g. written by a newer version) are kept when saving. The
+/// file is versioned and migrated on startup (see `schema`).
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::schema::{self, SETTINGS_STORE};
+
+pub const SETTINGS_FILE: &str = "command-center-settings.json";
+
+/// The user's home directory (`HOME`, or `USERPROFILE` on Windows).
+pub fn home_dir() -> Result<PathBuf, String> {
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .ok_or("Cannot locate the home directory")?;
+    Ok(PathBuf::from(home))
+}
+
+/// Directory holding the database and app data (`~/.claude/db`).
+pub fn db_dir() -> Result<PathBuf, String> {
+    Ok(home_dir()?.join(".claude").join("db"))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Timezone for "today": "local" (default), "UTC" or a fixed offset such as "+02:00"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// First day of the week: "monday" (default) through "sunday"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub week_start: Option<String>,
+    /// Day of the month the subscription renews (1-31); unset means calendar months
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub billing_anchor_day: Option<u32>,
+    /// Opt-in for anonymous telemetry (default off; see `telemetry`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub telemetry_enabled: Option<bool>,
+    /// Where telemetry is sent (https only); nothing is sent without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub telemetry_endpoint: Option<String>,
+    /// Default directory of export files (default: Downloads)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_dir: Option<String>,
+    /// Presentation mode: refuse state-changing commands (see `presentation`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+    /// Presentation mode: replace project names with aliases in backend responses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blur_project_names: Option<bool>,
+    /// Presentation mode: show seeded synthetic data instead of real usage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub demo_data: Option<bool>,
+    /// Privacy mode: strip message content from every response and export (see `privacy`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub privacy_mode: Option<bool>,
+    /// Project description suggestions: phrase them with the Messages API
+    /// (needs `ANTHROPIC_API_KEY`; default off, local keyword extraction only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_llm: Option<bool>,
+    /// Model used for LLM description suggestions (backend default if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_llm_model: Option<String>,
+    /// Attempts of backend calls failing transiently (1-10; default 1, no retries)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_attempts: Option<u32>,
+    /// Port of the local HTTP server (default 8765, see `http_server`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_port: Option<u16>,
+    /// Address the local HTTP server binds (default 127.0.0.1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_bind_address: Option<String>,
+    /// Allow a non-loopback `http_bind_address` (still needs an active API token)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_allow_remote: Option<bool>,
+    /// PEM certificate chain of the local HTTP server: serve HTTPS (needs `http_tls_key`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_tls_cert: Option<String>,
+    /// PEM private key of `http_tls_cert`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_tls_key: Option<String>,
+    /// PEM CA certificates of accepted clients: require client certificates (mTLS)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_tls_client_ca: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Settings {
+    /// Load settings; a missing or unreadable file yields the defaults.
+    pub fn load() -> Self {
+        db_dir()
+            .ok()
+            .and_then(|dir| fs::read(dir.join(SETTINGS_FILE)).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save with the current schema version; refuses to overwrite a file
+    /// written by a newer app version.
+    pub fn save(&self) -> Result<(), String> {
+        let dir = db_dir()?;
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let path = dir.join(SETTINGS_FILE);
+        let latest = SETTINGS_STORE.latest();
+        let on_disk = Self::load().schema_version;
+        if on_disk > latest {
+            return Err(format!(
+                "{} was written by a newer version of Command Center (schema {} > {}); update the app",
+                path.display(),
+                on_disk,
+                latest
+            ));
+        }
+        let mut value = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        value["schema_version"] = Value::from(latest);
+        schema::write_json_atomic(&path, &value)
+    }
+}
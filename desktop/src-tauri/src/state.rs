@@ -0,0 +1,124 @@
+/// App state managed by Tauri
+///
+/// Commands get what they work with from one `AppState` in managed state
+/// (`State<'_, AppState>`, registered with `app.manage` in `lib.rs`) instead
+/// of reaching for globals:
+///
+/// - `backend` - the `ApiBackend` commands call (Python bridge, or fixtures
+///   with `--demo` and in tests)
+/// - `settings` - the settings file, with read-modify-write updates
+///   serialized so concurrent setters don't drop each other's changes
+/// - `tasks` - long-lived background threads started by the app, by name
+///
+/// The bridge's process-wide resources (interpreter selection, the worker
+/// process, concurrency permits and response cache) stay in `python_bridge`
+/// behind `PythonBackend`; a command built on a `MockBackend` never touches
+/// them, so `AppState::new(MockBackend)` is enough to test it in isolation.
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+
+use serde_json::Value;
+
+use crate::backend::SharedBackend;
+use crate::error::CommandError;
+use crate::settings::Settings;
+
+pub struct AppState {
+    pub backend: SharedBackend,
+    pub settings: SettingsStore,
+    pub tasks: TaskRegistry,
+}
+
+impl AppState {
+    pub fn new(backend: SharedBackend) -> Self {
+        Self {
+            backend,
+            settings: SettingsStore::default(),
+            tasks: TaskRegistry::default(),
+        }
+    }
+}
+
+/// Access to `command-center-settings.json`. The file stays the source of
+/// truth (the Python backend reads it too), so `load` always reads it.
+#[derive(Default)]
+pub struct SettingsStore {
+    write: Mutex<()>,
+}
+
+impl SettingsStore {
+    pub fn load(&self) -> Settings {
+        Settings::load()
+    }
+
+    /// Load, change and save the settings while holding the store lock;
+    /// nothing is saved if `change` fails. Returns the saved settings.
+    pub fn update(&self, change: impl FnOnce(&mut Settings) -> Result<(), CommandError>) -> Result<Settings, CommandError> {
+        let _guard = self.write.lock().unwrap_or_else(|e| e.into_inner());
+        let mut settings = Settings::load();
+        change(&mut settings)?;
+        settings.save()?;
+        Ok(settings)
+    }
+}
+
+/// Named background threads (handshake, watchers, schedulers).
+#[derive(Default)]
+pub struct TaskRegistry {
+    tasks: Mutex<BTreeMap<String, JoinHandle<()>>>,
+}
+
+impl TaskRegistry {
+    /// Start `task` on a thread named `name`. A task of the same name that is
+    /// still running is kept and `task` is not started; returns whether it was.
+    pub fn spawn(&self, name: &str, task: impl FnOnce() + Send + 'static) -> bool {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        if tasks.get(name).is_some_and(|handle| !handle.is_finished()) {
+            return false;
+        }
+        match std::thread::Builder::new().name(name.to_string()).spawn(task) {
+            Ok(handle) => {
+                tasks.insert(name.to_string(), handle);
+                true
+            }
+            Err(e) => {
+                log::warn!("Failed to start {}: {}", name, e);
+                false
+            }
+        }
+    }
+
+    /// `{name: running}` of the tasks started so far.
+    pub fn status(&self) -> Value {
+        let tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        tasks
+            .iter()
+            .map(|(name, handle)| (name.clone(), Value::from(!handle.is_finished())))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_task_registry_runs_one_task_per_name() {
+        let tasks = TaskRegistry::default();
+        let (release, wait) = mpsc::channel::<()>();
+        assert!(tasks.spawn("watcher", move || {
+            let _ = wait.recv();
+        }));
+        assert!(!tasks.spawn("watcher", || {}));
+        assert_eq!(tasks.status()["watcher"], true);
+
+        release.send(()).unwrap();
+        while tasks.status()["watcher"] == true {
+            std::thread::yield_now();
+        }
+        assert!(tasks.spawn("watcher", || {}));
+    }
+}
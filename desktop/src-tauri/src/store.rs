@@ -0,0 +1,646 @@
+/// Embedded SQLite store of the desktop app
+///
+/// The Rust side keeps its own database (`~/.claude/db/command-center-store.db`,
+/// separate from the Python backend's `command_center.db`) of messages,
+/// sessions, models and daily aggregates, filled from the session logs by an
+/// incremental indexer:
+///
+/// - each log file is read from where the last run stopped (`files.indexed_bytes`);
+///   unchanged files are skipped and a trailing line still being written is
+///   left for the next run,
+/// - a file that shrank or was rewritten is indexed again from the start,
+/// - messages are deduplicated by `message.id:requestId` (the first file wins),
+/// - sessions, models and daily rows are rebuilt for the keys that received
+///   messages, in the same transaction.
+///
+/// The schema is versioned with `PRAGMA user_version` and migrated in order
+/// on open. Dates are local (like the Python backend's `date` column). Each
+/// message keeps its cost (the logged `costUSD`, else its tokens priced by
+/// `pricing`) and its list price, the `api_equivalent_cost` of the dashboard.
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::ingest::{self, LogEntry};
+use crate::pricing::PriceTable;
+use crate::settings::{db_dir, home_dir};
+
+pub const STORE_FILE: &str = "command-center-store.db";
+
+/// Schema steps; `PRAGMA user_version` is the number applied.
+const MIGRATIONS: &[&str] = &[
+    // 1: messages, their files and the aggregates built from them
+    "CREATE TABLE files (
+        path TEXT PRIMARY KEY,
+        config_dir TEXT NOT NULL,
+        project_id TEXT NOT NULL,
+        size INTEGER NOT NULL,
+        modified_ns INTEGER NOT NULL,
+        indexed_bytes INTEGER NOT NULL,
+        indexed_at TEXT NOT NULL
+    );
+    CREATE TABLE messages (
+        entry_key TEXT PRIMARY KEY,
+        session_id TEXT,
+        project_id TEXT NOT NULL,
+        config_dir TEXT NOT NULL,
+        file_path TEXT NOT NULL,
+        timestamp TEXT NOT NULL,
+        epoch_ms INTEGER NOT NULL,
+        date TEXT NOT NULL,
+        model TEXT NOT NULL,
+        input_tokens INTEGER NOT NULL,
+        output_tokens INTEGER NOT NULL,
+        cache_read_tokens INTEGER NOT NULL,
+        cache_write_tokens INTEGER NOT NULL,
+        cost_usd REAL
+    );
+    CREATE INDEX idx_messages_date ON messages(date);
+    CREATE INDEX idx_messages_session ON messages(session_id);
+    CREATE INDEX idx_messages_model ON messages(model);
+    CREATE INDEX idx_messages_file ON messages(file_path);
+    CREATE TABLE sessions (
+        session_id TEXT PRIMARY KEY,
+        project_id TEXT NOT NULL,
+        config_dir TEXT NOT NULL,
+        first_ms INTEGER NOT NULL,
+        last_ms INTEGER NOT NULL,
+        messages INTEGER NOT NULL,
+        tokens INTEGER NOT NULL,
+        cost REAL NOT NULL
+    );
+    CREATE TABLE models (
+        model TEXT PRIMARY KEY,
+        first_date TEXT NOT NULL,
+        last_date TEXT NOT NULL,
+        messages INTEGER NOT NULL,
+        tokens INTEGER NOT NULL,
+        cost REAL NOT NULL
+    );
+    CREATE TABLE daily (
+        date TEXT NOT NULL,
+        project_id TEXT NOT NULL,
+        model TEXT NOT NULL,
+        messages INTEGER NOT NULL,
+        input_tokens INTEGER NOT NULL,
+        output_tokens INTEGER NOT NULL,
+        cache_read_tokens INTEGER NOT NULL,
+        cache_write_tokens INTEGER NOT NULL,
+        cost REAL NOT NULL,
+        PRIMARY KEY (date, project_id, model)
+    );",
+    // 2: list prices; messages indexed without prices are read again
+    "ALTER TABLE messages ADD COLUMN list_cost_usd REAL;
+    ALTER TABLE daily ADD COLUMN list_cost REAL NOT NULL DEFAULT 0;
+    DELETE FROM files; DELETE FROM messages; DELETE FROM sessions; DELETE FROM models; DELETE FROM daily;",
+];
+
+/// Aggregates rebuilt for the keys in the `affected_*` temp tables.
+const REBUILD_AGGREGATES: &str = "
+    DELETE FROM sessions WHERE session_id IN (SELECT key FROM temp.affected_sessions);
+    INSERT INTO sessions
+        SELECT session_id, MIN(project_id), MIN(config_dir), MIN(epoch_ms), MAX(epoch_ms), COUNT(*),
+               SUM(input_tokens + output_tokens + cache_read_tokens + cache_write_tokens),
+               COALESCE(SUM(cost_usd), 0)
+        FROM messages WHERE session_id IN (SELECT key FROM temp.affected_sessions)
+        GROUP BY session_id;
+    DELETE FROM models WHERE model IN (SELECT key FROM temp.affected_models);
+    INSERT INTO models
+        SELECT model, MIN(date), MAX(date), COUNT(*),
+               SUM(input_tokens + output_tokens + cache_read_tokens + cache_write_tokens),
+               COALESCE(SUM(cost_usd), 0)
+        FROM messages WHERE model IN (SELECT key FROM temp.affected_models)
+        GROUP BY model;
+    DELETE FROM daily WHERE date IN (SELECT key FROM temp.affected_dates);
+    INSERT INTO daily
+        SELECT date, project_id, model, COUNT(*), SUM(input_tokens), SUM(output_tokens),
+               SUM(cache_read_tokens), SUM(cache_write_tokens), COALESCE(SUM(cost_usd), 0),
+               COALESCE(SUM(list_cost_usd), 0)
+        FROM messages WHERE date IN (SELECT key FROM temp.affected_dates)
+        GROUP BY date, project_id, model;
+    DELETE FROM temp.affected_sessions;
+    DELETE FROM temp.affected_models;
+    DELETE FROM temp.affected_dates;";
+
+/// One indexing run at a time per process; other processes wait on SQLite's lock.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// Outcome of `Store::index`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct IndexStats {
+    /// Log files found
+    pub files: u64,
+    /// Files read (new, grown or rewritten)
+    pub changed_files: u64,
+    /// Files indexed again from the start because they shrank or were rewritten
+    pub reindexed_files: u64,
+    pub new_messages: u64,
+    /// Messages already stored from another file or an earlier run
+    pub duplicate_messages: u64,
+    /// Lines that are not JSON records
+    pub skipped_lines: u64,
+    pub bytes_read: u64,
+    /// Threads that read the logs
+    pub workers: u64,
+    /// Time spent writing to the store (the rest is reading and parsing)
+    pub write_seconds: f64,
+    pub seconds: f64,
+}
+
+/// Files seen so far by a running `Store::index_with_progress`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct IndexProgress {
+    pub files_done: u64,
+    pub files_total: u64,
+    pub bytes_read: u64,
+}
+
+pub struct Store {
+    conn: Connection,
+    path: PathBuf,
+}
+
+impl Store {
+    /// Open (creating and migrating as needed) the store at `path`.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let conn = Connection::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        conn.busy_timeout(Duration::from_secs(10)).map_err(|e| e.to_string())?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")
+            .map_err(|e| e.to_string())?;
+        let mut store = Self { conn, path: path.to_path_buf() };
+        store.migrate()?;
+        Ok(store)
+    }
+
+    /// The store in the app data dir.
+    pub fn open_default() -> Result<Self, String> {
+        Self::open(&db_dir()?.join(STORE_FILE))
+    }
+
+    /// The open database, for queries over the indexed tables (see `aggregate`).
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Whether any session log has been indexed.
+    pub fn is_indexed(&self) -> Result<bool, String> {
+        self.conn
+            .query_row("SELECT EXISTS(SELECT 1 FROM files)", [], |row| row.get(0))
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn version(&self) -> Result<u32, String> {
+        self.conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn latest_version() -> u32 {
+        MIGRATIONS.len() as u32
+    }
+
+    fn migrate(&mut self) -> Result<(), String> {
+        let version = self.version()?;
+        if version > Self::latest_version() {
+            return Err(format!(
+                "{} was written by a newer version of Command Center (schema {}); update the app",
+                self.path.display(),
+                version
+            ));
+        }
+        for (index, sql) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            let tx = self.conn.transaction().map_err(|e| e.to_string())?;
+            tx.execute_batch(sql)
+                .and_then(|_| tx.pragma_update(None, "user_version", index as u32 + 1))
+                .and_then(|_| tx.commit())
+                .map_err(|e| format!("Store migration to version {} failed: {}", index + 1, e))?;
+            log::info!("Migrated {} to schema version {}", self.path.display(), index + 1);
+        }
+        Ok(())
+    }
+
+    /// Index the session logs of `config_dirs`; with `full`, everything is
+    /// dropped and read again.
+    pub fn index(&mut self, config_dirs: &[PathBuf], full: bool) -> Result<IndexStats, String> {
+        self.index_with_progress(config_dirs, full, |_| {})
+    }
+
+    /// `index`, calling `on_progress` after each log file. Messages are priced
+    /// from the price files next to the store.
+    pub fn index_with_progress(
+        &mut self,
+        config_dirs: &[PathBuf],
+        full: bool,
+        mut on_progress: impl FnMut(IndexProgress),
+    ) -> Result<IndexStats, String> {
+        let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let started = Instant::now();
+        let mut stats = IndexStats::default();
+
+        let tx = self.conn.transaction().map_err(|e| e.to_string())?;
+        tx.execute_batch(
+            "CREATE TEMP TABLE IF NOT EXISTS affected_sessions (key TEXT PRIMARY KEY);
+             CREATE TEMP TABLE IF NOT EXISTS affected_models (key TEXT PRIMARY KEY);
+             CREATE TEMP TABLE IF NOT EXISTS affected_dates (key TEXT PRIMARY KEY);",
+        )
+        .map_err(|e| e.to_string())?;
+        if full {
+            tx.execute_batch("DELETE FROM files; DELETE FROM messages; DELETE FROM sessions; DELETE FROM models; DELETE FROM daily;")
+                .map_err(|e| e.to_string())?;
+        }
+
+        // Reads are planned in path order, then log files are parsed on up to
+        // `workers` threads (a project directory each) and written back in order
+        let prices = PriceTable::load(self.path.parent().unwrap_or(Path::new(".")));
+        let workers = ingest::worker_count();
+        let mut groups: Vec<Vec<PlannedRead>> = Vec::new();
+        let mut files_total = 0;
+        for config_dir in config_dirs {
+            for (project_id, paths) in ingest::project_groups(ingest::log_files(config_dir)) {
+                let mut group = Vec::new();
+                for path in paths {
+                    files_total += 1;
+                    match plan_read(&tx, config_dir, &project_id, &path, &mut stats) {
+                        Ok(Some(read)) => group.push(read),
+                        Ok(None) => {}
+                        Err(e) => log::warn!("Skipping session log {}: {}", path.display(), e),
+                    }
+                }
+                if !group.is_empty() {
+                    groups.push(group);
+                }
+            }
+        }
+        stats.files = files_total;
+        stats.workers = workers.clamp(1, groups.len().max(1)) as u64;
+
+        let planned = groups.iter().map(|group| group.len() as u64).sum::<u64>();
+        let mut progress = IndexProgress { files_done: files_total - planned, files_total, bytes_read: 0 };
+        let mut write_seconds = 0.0;
+        let read = |group: &Vec<PlannedRead>| {
+            group.iter().map(|planned| read_log(planned, &prices)).collect::<Vec<_>>()
+        };
+        let mut planned_groups = groups.iter();
+        ingest::parse_in_order(&groups, workers, read, |logs| {
+            // Results arrive in group order
+            let group = planned_groups.next().map_or(&[][..], Vec::as_slice);
+            let written = Instant::now();
+            for (planned, log) in group.iter().zip(logs) {
+                stats.bytes_read += log.as_ref().map_or(0, |log| log.bytes_read);
+                if let Err(e) = log.and_then(|log| store_log(&tx, planned, log, &mut stats)) {
+                    log::warn!("Skipping session log {}: {}", planned.path.display(), e);
+                }
+                progress.files_done += 1;
+                progress.bytes_read = stats.bytes_read;
+                on_progress(progress);
+            }
+            write_seconds += written.elapsed().as_secs_f64();
+        });
+        stats.write_seconds = (write_seconds * 1000.0).round() / 1000.0;
+
+        tx.execute_batch(REBUILD_AGGREGATES).map_err(|e| e.to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+        stats.seconds = (started.elapsed().as_secs_f64() * 1000.0).round() / 1000.0;
+        Ok(stats)
+    }
+
+    /// Row counts, date span and size, as returned by `get_native_store_status`.
+    pub fn status(&self) -> Result<Value, String> {
+        let count = |table: &str| -> Result<i64, String> {
+            self.conn
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+                .map_err(|e| e.to_string())
+        };
+        let (first_date, last_date): (Option<String>, Option<String>) = self
+            .conn
+            .query_row("SELECT MIN(date), MAX(date) FROM daily", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?;
+        let last_indexed_at: Option<String> = self
+            .conn
+            .query_row("SELECT MAX(indexed_at) FROM files", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        Ok(json!({
+            "path": self.path.display().to_string(),
+            "version": self.version()?,
+            "latest": Self::latest_version(),
+            "files": count("files")?,
+            "messages": count("messages")?,
+            "sessions": count("sessions")?,
+            "models": count("models")?,
+            "first_date": first_date,
+            "last_date": last_date,
+            "last_indexed_at": last_indexed_at,
+            "size_bytes": fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0),
+        }))
+    }
+}
+
+/// Config dirs of this user, as found by `ingest::config_dirs`.
+pub fn user_config_dirs() -> Result<Vec<PathBuf>, String> {
+    let bindings = fs::read(db_dir()?.join(ingest::CONFIG_BINDINGS_FILE))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or(Value::Null);
+    let env_dir = std::env::var("CLAUDE_CONFIG_DIR").ok().filter(|dir| !dir.is_empty());
+    Ok(ingest::config_dirs(&home_dir()?, env_dir.as_deref(), &bindings))
+}
+
+fn modified_ns(metadata: &fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |since| since.as_nanos() as i64)
+}
+
+/// A log file to read: from `start`, with what its `files` row records.
+struct PlannedRead {
+    config_dir: String,
+    project_id: String,
+    path: PathBuf,
+    size: i64,
+    modified: i64,
+    start: i64,
+}
+
+/// Messages of a log file from its planned start, with their cost and list price.
+struct LogRead {
+    entries: Vec<(LogEntry, Option<f64>, Option<f64>)>,
+    /// Offset after the last complete line
+    offset: i64,
+    bytes_read: u64,
+    skipped_lines: u64,
+}
+
+/// Where to read `path` from, or None if it did not change. The messages of
+/// a file that shrank or was rewritten are dropped, to be read again.
+fn plan_read(
+    tx: &Transaction,
+    config_dir: &Path,
+    project_id: &str,
+    path: &Path,
+    stats: &mut IndexStats,
+) -> Result<Option<PlannedRead>, String> {
+    let file_path = path.display().to_string();
+    let metadata = fs::metadata(path).map_err(|e| e.to_string())?;
+    let (size, modified) = (metadata.len() as i64, modified_ns(&metadata));
+    let known: Option<(i64, i64, i64)> = tx
+        .query_row(
+            "SELECT size, modified_ns, indexed_bytes FROM files WHERE path = ?1",
+            [&file_path],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let start = match known {
+        Some((known_size, known_modified, _)) if known_size == size && known_modified == modified => return Ok(None),
+        Some((_, _, indexed)) if size >= indexed => indexed,
+        Some(_) => {
+            // Shrunk or rewritten: its messages are read again
+            for sql in [
+                "INSERT OR IGNORE INTO temp.affected_sessions
+                    SELECT session_id FROM messages WHERE file_path = ?1 AND session_id IS NOT NULL",
+                "INSERT OR IGNORE INTO temp.affected_models SELECT model FROM messages WHERE file_path = ?1",
+                "INSERT OR IGNORE INTO temp.affected_dates SELECT date FROM messages WHERE file_path = ?1",
+                "DELETE FROM messages WHERE file_path = ?1",
+            ] {
+                tx.execute(sql, [&file_path]).map_err(|e| e.to_string())?;
+            }
+            stats.reindexed_files += 1;
+            0
+        }
+        None => 0,
+    };
+    stats.changed_files += 1;
+    Ok(Some(PlannedRead {
+        config_dir: config_dir.display().to_string(),
+        project_id: project_id.to_string(),
+        path: path.to_path_buf(),
+        size,
+        modified,
+        start,
+    }))
+}
+
+/// Parse the complete lines of a planned read (on an indexing thread).
+fn read_log(planned: &PlannedRead, prices: &PriceTable) -> Result<LogRead, String> {
+    let mut file = File::open(&planned.path).map_err(|e| e.to_string())?;
+    file.seek(SeekFrom::Start(planned.start as u64)).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(file);
+    let mut log = LogRead { entries: Vec::new(), offset: planned.start, bytes_read: 0, skipped_lines: 0 };
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        let read = reader.read_until(b'\n', &mut buf).map_err(|e| e.to_string())?;
+        if read == 0 || buf.last() != Some(&b'\n') {
+            // End of file, or a line still being written
+            return Ok(log);
+        }
+        log.offset += read as i64;
+        log.bytes_read += read as u64;
+        let text = String::from_utf8_lossy(&buf);
+        let line = text.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match ingest::parse_line(line) {
+            Ok(Some(entry)) => {
+                let (cost, list_cost) = (prices.cost(&entry), prices.list_price(&entry));
+                log.entries.push((entry, cost, list_cost));
+            }
+            Ok(None) => {}
+            Err(_) => log.skipped_lines += 1,
+        }
+    }
+}
+
+/// Insert the messages of a read and record how far the file was indexed.
+fn store_log(tx: &Transaction, planned: &PlannedRead, log: LogRead, stats: &mut IndexStats) -> Result<(), String> {
+    let file_path = planned.path.display().to_string();
+    stats.skipped_lines += log.skipped_lines;
+    let mut insert = tx
+        .prepare_cached(
+            "INSERT OR IGNORE INTO messages (entry_key, session_id, project_id, config_dir, file_path, timestamp,
+                epoch_ms, date, model, input_tokens, output_tokens, cache_read_tokens, cache_write_tokens, cost_usd,
+                list_cost_usd)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        )
+        .map_err(|e| e.to_string())?;
+    for (entry, cost, list_cost) in log.entries {
+        let date = entry.local.format("%Y-%m-%d").to_string();
+        let model = entry.model.clone().unwrap_or_default();
+        let inserted = insert
+            .execute(params![
+                entry.key,
+                entry.session_id,
+                planned.project_id,
+                planned.config_dir,
+                file_path,
+                entry.timestamp,
+                entry.local.timestamp_millis(),
+                date,
+                model,
+                entry.input_tokens as i64,
+                entry.output_tokens as i64,
+                entry.cache_read_tokens as i64,
+                entry.cache_write_tokens as i64,
+                cost,
+                list_cost,
+            ])
+            .map_err(|e| e.to_string())?;
+        if inserted == 0 {
+            stats.duplicate_messages += 1;
+            continue;
+        }
+        stats.new_messages += 1;
+        mark_affected(tx, &entry, &model, &date)?;
+    }
+
+    tx.execute(
+        "INSERT OR REPLACE INTO files (path, config_dir, project_id, size, modified_ns, indexed_bytes, indexed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            file_path,
+            planned.config_dir,
+            planned.project_id,
+            planned.size,
+            planned.modified,
+            log.offset,
+            chrono::Local::now().to_rfc3339()
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn mark_affected(tx: &Transaction, entry: &LogEntry, model: &str, date: &str) -> Result<(), String> {
+    let mark = |table: &str, key: &str| {
+        tx.prepare_cached(&format!("INSERT OR IGNORE INTO temp.{} (key) VALUES (?1)", table))
+            .and_then(|mut stmt| stmt.execute([key]))
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    };
+    if let Some(session_id) = &entry.session_id {
+        mark("affected_sessions", session_id)?;
+    }
+    mark("affected_models", model)?;
+    mark("affected_dates", date)
+}
+
+/// `database` entry of `get_schema_info` for the store.
+pub fn schema_info() -> Value {
+    match db_dir().map(|dir| dir.join(STORE_FILE)) {
+        Ok(path) if !path.exists() => json!({"path": path.display().to_string(), "version": null, "latest": Store::latest_version()}),
+        Ok(_) => match Store::open_default().and_then(|store| store.version().map(|version| (store, version))) {
+            Ok((store, version)) => json!({"path": store.path.display().to_string(), "version": version, "latest": Store::latest_version()}),
+            Err(e) => json!({"error": e}),
+        },
+        Err(e) => json!({"error": e}),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("cc-store-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn line(n: u32, session_id: &str, timestamp: &str, model: &str) -> String {
+        json!({
+            "type": "assistant",
+            "sessionId": session_id,
+            "requestId": format!("req_{}", n),
+            "timestamp": timestamp,
+            "costUSD": 0.25,
+            "message": {"id": format!("msg_{}", n), "model": model, "usage": {"input_tokens": 10, "output_tokens": 5}},
+        })
+        .to_string()
+            + "\n"
+    }
+
+    fn scalar(store: &Store, sql: &str) -> i64 {
+        store.conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_incremental_index() {
+        let dir = TempDir::new("incremental");
+        let config_dir = dir.0.join(".claude");
+        let log = config_dir.join("projects").join("-home-me-app").join("s1.jsonl");
+        fs::create_dir_all(log.parent().unwrap()).unwrap();
+        fs::write(&log, line(1, "s1", "2025-06-10T12:00:00Z", "claude-sonnet-4-5") + &line(2, "s1", "2025-06-10T12:05:00Z", "claude-opus-4-5")).unwrap();
+        let dirs = vec![config_dir];
+        let mut store = Store::open(&dir.0.join("db").join(STORE_FILE)).unwrap();
+        assert_eq!(store.version().unwrap(), Store::latest_version());
+
+        let stats = store.index(&dirs, false).unwrap();
+        assert_eq!((stats.files, stats.changed_files, stats.new_messages), (1, 1, 2));
+        assert_eq!(scalar(&store, "SELECT messages FROM sessions WHERE session_id = 's1'"), 2);
+        assert_eq!(scalar(&store, "SELECT tokens FROM sessions WHERE session_id = 's1'"), 30);
+        assert_eq!(scalar(&store, "SELECT COUNT(*) FROM models"), 2);
+
+        // Unchanged files are skipped
+        assert_eq!(store.index(&dirs, false).unwrap().changed_files, 0);
+
+        // Appended lines are read from the last offset; a partial line waits for its newline
+        let mut file = fs::OpenOptions::new().append(true).open(&log).unwrap();
+        let partial = line(4, "s1", "2025-06-11T12:00:00Z", "claude-sonnet-4-5");
+        write!(file, "{}{}not json\n{}", line(3, "s1", "2025-06-11T09:00:00Z", "claude-sonnet-4-5"), line(1, "s1", "2025-06-10T12:00:00Z", "claude-sonnet-4-5"), &partial[..20]).unwrap();
+        let stats = store.index(&dirs, false).unwrap();
+        assert_eq!((stats.new_messages, stats.duplicate_messages, stats.skipped_lines), (1, 1, 1));
+        assert_eq!(scalar(&store, "SELECT messages FROM sessions WHERE session_id = 's1'"), 3);
+        assert_eq!(scalar(&store, "SELECT SUM(messages) FROM daily"), 3);
+        write!(file, "{}", &partial[20..]).unwrap();
+        assert_eq!(store.index(&dirs, false).unwrap().new_messages, 1);
+        assert_eq!(scalar(&store, "SELECT SUM(messages) FROM daily"), 4);
+
+        // A rewritten (shorter) file is indexed again and the aggregates follow
+        fs::write(&log, line(9, "s2", "2025-06-12T12:00:00Z", "claude-haiku-4-5")).unwrap();
+        let stats = store.index(&dirs, false).unwrap();
+        assert_eq!((stats.reindexed_files, stats.new_messages), (1, 1));
+        assert_eq!(scalar(&store, "SELECT COUNT(*) FROM sessions"), 1);
+        assert_eq!(scalar(&store, "SELECT COUNT(*) FROM models"), 1);
+        assert_eq!(scalar(&store, "SELECT COUNT(*) FROM daily"), 1);
+
+        let status = store.status().unwrap();
+        assert_eq!((status["messages"].as_i64(), status["files"].as_i64()), (Some(1), Some(1)));
+        assert!(status["last_date"].as_str().unwrap().starts_with("2025-06-1"));
+
+        let stats = store.index(&dirs, true).unwrap();
+        assert_eq!((stats.changed_files, stats.new_messages), (1, 1));
+    }
+
+    #[test]
+    fn test_refuses_newer_schema() {
+        let dir = TempDir::new("newer");
+        let path = dir.0.join(STORE_FILE);
+        Connection::open(&path).unwrap().pragma_update(None, "user_version", 99).unwrap();
+        assert!(Store::open(&path).err().unwrap().contains("newer version"));
+    }
+}
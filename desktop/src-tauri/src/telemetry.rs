@@ -0,0 +1,149 @@
+/// Opt-in anonymous usage telemetry
+///
+/// Only aggregate numbers about the app itself are collected, in memory, for
+/// the current app session: how often each backend command ran, which kinds
+/// of errors occurred (exception type names, never messages) and command
+/// duration percentiles. No dates, paths, project names, session content or
+/// identifiers are included; `preview_telemetry_payload` shows the exact
+/// payload.
+///
+/// Nothing is sent unless the user enabled telemetry and configured an
+/// endpoint. Setting `COMMAND_CENTER_TELEMETRY=off` is a kill switch that
+/// overrides the setting.
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::settings::Settings;
+
+/// Environment variable that disables telemetry regardless of settings.
+pub const TELEMETRY_ENV: &str = "COMMAND_CENTER_TELEMETRY";
+
+/// Version of the payload layout.
+pub const PAYLOAD_SCHEMA: u32 = 1;
+
+/// Duration samples kept per command (oldest are dropped).
+const MAX_SAMPLES_PER_COMMAND: usize = 512;
+
+struct Collector {
+    started: Option<Instant>,
+    features: BTreeMap<String, u64>,
+    errors: BTreeMap<String, u64>,
+    durations_ms: BTreeMap<String, Vec<u64>>,
+}
+
+static COLLECTOR: Mutex<Collector> = Mutex::new(Collector {
+    started: None,
+    features: BTreeMap::new(),
+    errors: BTreeMap::new(),
+    durations_ms: BTreeMap::new(),
+});
+
+/// True if the kill switch environment variable is set to off/0/false.
+pub fn kill_switch() -> bool {
+    std::env::var(TELEMETRY_ENV)
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "off" | "0" | "false" | "no"))
+        .unwrap_or(false)
+}
+
+/// Opted in and not killed.
+pub fn enabled(settings: &Settings) -> bool {
+    !kill_switch() && settings.telemetry_enabled.unwrap_or(false)
+}
+
+/// Record one backend call: the subcommand name, its duration and the error kind if it failed.
+pub fn record_call(subcommand: &str, elapsed: Duration, error_kind: Option<&str>) {
+    let Ok(mut collector) = COLLECTOR.lock() else {
+        return;
+    };
+    collector.started.get_or_insert_with(Instant::now);
+    *collector.features.entry(subcommand.to_string()).or_insert(0) += 1;
+    if let Some(kind) = error_kind {
+        *collector.errors.entry(kind.to_string()).or_insert(0) += 1;
+    }
+    let samples = collector.durations_ms.entry(subcommand.to_string()).or_default();
+    if samples.len() >= MAX_SAMPLES_PER_COMMAND {
+        samples.remove(0);
+    }
+    samples.push(elapsed.as_millis() as u64);
+}
+
+/// Forget everything collected so far.
+pub fn clear() {
+    if let Ok(mut collector) = COLLECTOR.lock() {
+        collector.started = None;
+        collector.features.clear();
+        collector.errors.clear();
+        collector.durations_ms.clear();
+    }
+}
+
+/// Nearest-rank percentile of sorted samples.
+pub fn percentile(sorted: &[u64], p: f64) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// The payload that would be sent now.
+pub fn payload() -> Value {
+    let Ok(collector) = COLLECTOR.lock() else {
+        return Value::Null;
+    };
+    let performance: BTreeMap<&String, Value> = collector
+        .durations_ms
+        .iter()
+        .map(|(command, samples)| {
+            let mut sorted = samples.clone();
+            sorted.sort_unstable();
+            let stats = json!({
+                "samples": sorted.len(),
+                "p50_ms": percentile(&sorted, 50.0),
+                "p90_ms": percentile(&sorted, 90.0),
+                "p99_ms": percentile(&sorted, 99.0),
+            });
+            (command, stats)
+        })
+        .collect();
+    json!({
+        "schema": PAYLOAD_SCHEMA,
+        "app_version": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "session_minutes": collector.started.map_or(0, |t| t.elapsed().as_secs() / 60),
+        "features": collector.features,
+        "errors": collector.errors,
+        "performance": performance,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let samples: Vec<u64> = (1..=100).collect();
+        assert_eq!(percentile(&samples, 50.0), Some(50));
+        assert_eq!(percentile(&samples, 99.0), Some(99));
+        assert_eq!(percentile(&[7], 90.0), Some(7));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn test_payload_has_counts_but_no_arguments() {
+        clear();
+        record_call("dashboard", Duration::from_millis(120), None);
+        record_call("dashboard", Duration::from_millis(80), Some("ValueError"));
+        let payload = payload();
+
+        assert_eq!(payload["features"]["dashboard"], 2);
+        assert_eq!(payload["errors"]["ValueError"], 1);
+        assert_eq!(payload["performance"]["dashboard"]["p50_ms"], 80);
+        assert_eq!(payload["schema"], PAYLOAD_SCHEMA);
+    }
+}
@@ -0,0 +1,243 @@
+/// cc_usage snapshots and subscription costs of the usage accounts
+///
+/// Native counterparts of the Python `usage_accounts` functions the dashboard
+/// bundle uses: snapshot totals per account over a range, read from the
+/// cc_usage databases (`query_usage_account_totals`), and the subscription
+/// cost of the accounts with a known plan, prorated to billing cycles
+/// (`subscription_cost_for_range`). Labels, colors and plans come from the
+/// same `command-center-accounts.json` as in the Python backend.
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use rusqlite::{Connection, OpenFlags};
+use serde_json::{Map, Value};
+
+use crate::aggregate::round;
+use crate::ingest::{self, ACCOUNTS_FILE};
+use crate::ranges;
+use crate::settings::SETTINGS_FILE;
+
+/// Read only this cc_usage database instead of the default locations.
+pub const USAGE_DB_ENV: &str = "CC_USAGE_DB_PATH";
+
+/// Monthly list price per seat (USD) of each plan; None = negotiated.
+const PLAN_MONTHLY_PRICES: &[(&str, Option<f64>)] = &[
+    ("pro", Some(20.0)),
+    ("max_5x", Some(100.0)),
+    ("max_20x", Some(200.0)),
+    ("team", Some(30.0)),
+    ("enterprise", None),
+    ("api", Some(0.0)),
+];
+
+/// Session usage from which a snapshot counts as near the limit (percent).
+const NEAR_LIMIT_PCT: f64 = 90.0;
+
+/// cc_usage databases in the order the Python backend reads them.
+pub fn usage_db_paths(home: &Path) -> Vec<PathBuf> {
+    if let Some(path) = std::env::var(USAGE_DB_ENV).ok().filter(|path| !path.is_empty()) {
+        return vec![match path.strip_prefix("~/") {
+            Some(rest) => home.join(rest),
+            None => PathBuf::from(path),
+        }];
+    }
+    let (claude, config) = (home.join(".claude").join("db"), home.join(".config").join("claude").join("db"));
+    vec![
+        claude.join("cc_usage.db"),
+        config.join("cc_usage.db"),
+        claude.join("command_center.db"),
+        config.join("command_center.db"),
+    ]
+}
+
+/// Label, color and plan of `email` from the account metadata, added to `row`.
+/// A manually set plan overrides the detected one.
+pub fn with_metadata(mut row: Map<String, Value>, email: &str, metadata: &Value) -> Map<String, Value> {
+    let meta = &metadata[email];
+    let field = |key: &str| meta[key].as_str().filter(|value| !value.is_empty());
+    let (plan_type, plan_source) = match (field("plan_type"), field("detected_plan_type")) {
+        (Some(plan), _) => (Some(plan), Some("manual")),
+        (None, Some(plan)) => (Some(plan), Some("detected")),
+        (None, None) => (None, None),
+    };
+    row.insert("label".into(), Value::from(field("label")));
+    row.insert("color".into(), Value::from(field("color")));
+    row.insert("plan_type".into(), Value::from(plan_type));
+    row.insert("plan_source".into(), Value::from(plan_source));
+    row
+}
+
+/// A cc_usage snapshot: email, capture time and session and week usage.
+type Snapshot = (String, String, Option<Value>, Option<Value>);
+
+fn percent(value: rusqlite::types::Value) -> Option<Value> {
+    match value {
+        rusqlite::types::Value::Integer(value) => Some(Value::from(value)),
+        rusqlite::types::Value::Real(value) => Some(Value::from(value)),
+        _ => None,
+    }
+}
+
+/// Snapshots captured from `from` through `to` in the database at `path`
+/// (none if it is unreadable or has no `cc_usage_events`).
+fn snapshots(path: &Path, from: &str, to: &str) -> Vec<Snapshot> {
+    let read = || -> rusqlite::Result<Vec<Snapshot>> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let mut columns = conn.prepare("SELECT name FROM pragma_table_info('cc_usage_events')")?;
+        let columns: BTreeSet<String> = columns.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?;
+        if columns.is_empty() {
+            return Ok(Vec::new());
+        }
+        let column = |name: &str| if columns.contains(name) { name.to_string() } else { format!("NULL AS {}", name) };
+        let sql = format!(
+            "SELECT email, captured_at_local, {}, {} FROM cc_usage_events
+             WHERE email IS NOT NULL AND email != ''
+             AND SUBSTR(captured_at_local, 1, 10) >= ?1 AND SUBSTR(captured_at_local, 1, 10) <= ?2",
+            column("current_session_used_pct"),
+            column("current_week_used_pct")
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([from, to], |row| {
+            Ok((row.get(0)?, row.get(1)?, percent(row.get(2)?), percent(row.get(3)?)))
+        })?;
+        rows.collect()
+    };
+    if !path.exists() {
+        return Vec::new();
+    }
+    read().unwrap_or_default()
+}
+
+/// Snapshot totals per account from `from` through `to`, sorted by email:
+/// snapshots, active days, peak and average session and week usage and
+/// snapshots near the session limit, with the account metadata.
+pub fn snapshot_totals(usage_dbs: &[PathBuf], data_dir: &Path, from: &str, to: &str) -> Vec<Map<String, Value>> {
+    let mut seen = BTreeSet::new();
+    let mut by_email: BTreeMap<String, Vec<Snapshot>> = BTreeMap::new();
+    for path in usage_dbs {
+        for snapshot in snapshots(path, from, to) {
+            if seen.insert((snapshot.0.clone(), snapshot.1.clone())) {
+                by_email.entry(snapshot.0.clone()).or_default().push(snapshot);
+            }
+        }
+    }
+
+    // The first of equal peaks, like Python's `max`
+    let peak = |values: &[&Value]| {
+        let values = values.iter().copied();
+        values.reduce(|peak, value| if value.as_f64() > peak.as_f64() { value } else { peak }).cloned()
+    };
+    let average = |values: &[&Value]| {
+        let sum: f64 = values.iter().filter_map(|value| value.as_f64()).sum();
+        (!values.is_empty()).then(|| round(sum / values.len() as f64, 1))
+    };
+    let metadata = ingest::read_json(&data_dir.join(ACCOUNTS_FILE));
+    by_email
+        .into_iter()
+        .map(|(email, rows)| {
+            let session: Vec<&Value> = rows.iter().filter_map(|row| row.2.as_ref()).collect();
+            let week: Vec<&Value> = rows.iter().filter_map(|row| row.3.as_ref()).collect();
+            let days: BTreeSet<&str> = rows.iter().map(|row| row.1.get(..10).unwrap_or(&row.1)).collect();
+            let near_limit = session.iter().filter(|pct| pct.as_f64().is_some_and(|pct| pct >= NEAR_LIMIT_PCT));
+            let mut row = Map::new();
+            row.insert("email".into(), Value::from(email.as_str()));
+            row.insert("snapshots".into(), Value::from(rows.len()));
+            row.insert("active_days".into(), Value::from(days.len()));
+            row.insert("peak_session_used_pct".into(), Value::from(peak(&session)));
+            row.insert("avg_session_used_pct".into(), Value::from(average(&session)));
+            row.insert("peak_week_used_pct".into(), Value::from(peak(&week)));
+            row.insert("avg_week_used_pct".into(), Value::from(average(&week)));
+            row.insert("session_near_limit".into(), Value::from(near_limit.count()));
+            with_metadata(row, &email, &metadata)
+        })
+        .collect()
+}
+
+/// Start of the billing cycle after the one starting on `first`.
+fn next_cycle_start(first: NaiveDate, anchor_day: u32) -> NaiveDate {
+    // The next cycle starts on or before the last day of the next month
+    let next_month_end = first
+        .with_day(1)
+        .and_then(|day| day.checked_add_months(Months::new(2)))
+        .map_or(first + Duration::days(31), |day| day - Duration::days(1));
+    ranges::billing_cycle_start(next_month_end, anchor_day)
+}
+
+/// Billing cycles (fractional) covered by `from` through `to`.
+fn prorated_months(from: NaiveDate, to: NaiveDate, anchor_day: u32) -> f64 {
+    let mut months = 0.0;
+    let mut first = ranges::billing_cycle_start(from, anchor_day);
+    while first <= to {
+        let next = next_cycle_start(first, anchor_day);
+        let last = next - Duration::days(1);
+        let overlap = (last.min(to) - first.max(from)).num_days() + 1;
+        months += overlap as f64 / ((last - first).num_days() + 1) as f64;
+        first = next;
+    }
+    months
+}
+
+/// Subscription cost (USD) of the accounts with a priced plan, prorated to
+/// `from` through `to` in billing cycles of the configured anchor day; None
+/// if no account has one.
+pub fn subscription_cost(data_dir: &Path, from: NaiveDate, to: NaiveDate) -> Option<f64> {
+    let anchor_day = ingest::read_json(&data_dir.join(SETTINGS_FILE))["billing_anchor_day"]
+        .as_u64()
+        .filter(|day| (1..=31).contains(day))
+        .map_or(1, |day| day as u32);
+    let months = prorated_months(from, to, anchor_day);
+    let metadata = ingest::read_json(&data_dir.join(ACCOUNTS_FILE));
+    let costs: Vec<f64> = metadata
+        .as_object()
+        .into_iter()
+        .flat_map(|accounts| accounts.keys())
+        .filter_map(|email| with_metadata(Map::new(), email, &metadata)["plan_type"].as_str().map(str::to_string))
+        .filter_map(|plan| PLAN_MONTHLY_PRICES.iter().find(|(name, _)| *name == plan).and_then(|(_, price)| *price))
+        .map(|price| round(price * months, 2))
+        .collect();
+    (!costs.is_empty()).then(|| round(costs.iter().sum(), 2))
+}
+
+/// Snapshot totals merged with the message totals of the accounts' bound
+/// config dirs (`usage`, null without any); accounts with only one of the
+/// two are included. Sorted by email.
+pub fn merge_account_totals(snapshots: Vec<Map<String, Value>>, messages: BTreeMap<String, Value>) -> Vec<Value> {
+    let mut merged: BTreeMap<String, Map<String, Value>> = snapshots
+        .into_iter()
+        .map(|row| (row["email"].as_str().unwrap_or_default().to_string(), row))
+        .collect();
+    for (email, totals) in messages {
+        let row = merged.entry(email.clone()).or_insert_with(|| Map::from_iter([("email".into(), Value::from(email))]));
+        row.insert("usage".into(), totals);
+    }
+    merged
+        .into_values()
+        .map(|mut row| {
+            row.entry("usage").or_insert(Value::Null);
+            Value::Object(row)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_prorated_months_follow_billing_cycles() {
+        assert_eq!(prorated_months(date("2025-06-01"), date("2025-06-30"), 1), 1.0);
+        assert_eq!(prorated_months(date("2025-01-01"), date("2025-12-31"), 1), 12.0);
+        // Cycles of anchor day 10: May 10 - June 9 (31 days) and June 10 - July 9 (30 days)
+        let months = prorated_months(date("2025-06-08"), date("2025-06-14"), 10);
+        assert!((months - (2.0 / 31.0 + 5.0 / 30.0)).abs() < 1e-12);
+        // Anchors past the end of a short month start on its last day
+        assert_eq!(next_cycle_start(date("2025-01-31"), 31), date("2025-02-28"));
+        assert_eq!(next_cycle_start(date("2025-02-28"), 31), date("2025-03-31"));
+        assert_eq!(prorated_months(date("2025-02-28"), date("2025-03-30"), 31), 1.0);
+    }
+}
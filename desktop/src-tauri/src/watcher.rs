@@ -0,0 +1,196 @@
+/// Live updates from the Claude log directories
+///
+/// The `log-watcher` task watches the `projects/` tree of every config dir
+/// (`store::user_config_dirs`) with `notify`. Session files that are created
+/// or appended to are collected until the directories are quiet for
+/// `DEBOUNCE` (Claude Code writes a response as several lines in quick
+/// succession, at most `MAX_DELAY` while a session keeps writing), then the
+/// native store indexes them (`Store::index` only reads changed files from
+/// where it stopped, reporting through `indexer`) and `usage-updated` is
+/// emitted, so open views refresh without the user pressing refresh.
+///
+/// A config dir without a `projects/` dir yet is watched itself until the
+/// dir appears. The `log_watcher` feature flag turns the task off (read when
+/// the app starts).
+use std::collections::BTreeSet;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::features::{self, FlagStore};
+use crate::indexer;
+use crate::store::{self, IndexStats, Store};
+
+/// Feature flag of the watcher.
+pub const WATCHER_FLAG: &str = "log_watcher";
+
+/// Event emitted after new session lines were indexed (payload: `UsageUpdate`).
+pub const USAGE_UPDATED_EVENT: &str = "usage-updated";
+
+/// Quiet period after the last change before a batch is indexed.
+const DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// Longest wait for a batch while files keep changing.
+const MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// Payload of `usage-updated`.
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageUpdate {
+    /// Session files that changed
+    pub files: Vec<String>,
+    #[serde(flatten)]
+    pub stats: IndexStats,
+}
+
+/// Whether the watcher starts with the app.
+pub fn enabled() -> bool {
+    features::find(WATCHER_FLAG).is_some_and(|flag| FlagStore::load().resolve(flag).0)
+}
+
+fn is_session_log(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "jsonl")
+}
+
+/// Session files created, written or renamed into place by `event`.
+fn changed_logs(event: &Event) -> impl Iterator<Item = &PathBuf> {
+    let relevant = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_));
+    event.paths.iter().filter(move |path| relevant && is_session_log(path))
+}
+
+/// Watch the `projects/` trees of `config_dirs` and call `on_batch` with the
+/// session files changed in each batch, until it breaks.
+///
+/// A batch ends after `debounce` without changes or `max_delay` after its
+/// first change. A `projects/` dir created while watching is added with an
+/// empty batch, so files written into it before its watch started are indexed.
+pub fn watch(
+    config_dirs: &[PathBuf],
+    debounce: Duration,
+    max_delay: Duration,
+    mut on_batch: impl FnMut(Vec<PathBuf>) -> ControlFlow<()>,
+) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(|e| format!("Cannot start the log watcher: {}", e))?;
+    let mut missing = Vec::new();
+    for dir in config_dirs {
+        let projects = dir.join("projects");
+        let watched = if projects.is_dir() {
+            watcher.watch(&projects, RecursiveMode::Recursive)
+        } else if dir.is_dir() {
+            missing.push(projects);
+            watcher.watch(dir, RecursiveMode::NonRecursive)
+        } else {
+            continue;
+        };
+        if let Err(e) = watched {
+            log::warn!("Cannot watch {}: {}", dir.display(), e);
+        }
+    }
+
+    let mut changed = BTreeSet::new();
+    let mut batch_started: Option<Instant> = None;
+    loop {
+        let received = match batch_started {
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            Some(started) => rx.recv_timeout(debounce.min(max_delay.saturating_sub(started.elapsed()))),
+        };
+        match received {
+            Ok(Ok(event)) => {
+                let before = changed.len();
+                changed.extend(changed_logs(&event).cloned());
+                if let Some(index) = missing.iter().position(|dir| event.paths.contains(dir) && dir.is_dir()) {
+                    let projects = missing.remove(index);
+                    if let Err(e) = watcher.watch(&projects, RecursiveMode::Recursive) {
+                        log::warn!("Cannot watch {}: {}", projects.display(), e);
+                    }
+                    batch_started.get_or_insert_with(Instant::now);
+                }
+                if changed.len() > before {
+                    batch_started.get_or_insert_with(Instant::now);
+                }
+            }
+            Ok(Err(e)) => log::warn!("Log watcher error: {}", e),
+            Err(RecvTimeoutError::Timeout) => {
+                batch_started = None;
+                if on_batch(std::mem::take(&mut changed).into_iter().collect()).is_break() {
+                    return Ok(());
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return Err("Log watcher stopped".to_string()),
+        }
+    }
+}
+
+/// Body of the `log-watcher` task: index each batch and emit `usage-updated`
+/// when it added or re-read anything.
+pub fn run(app: AppHandle) {
+    let dirs = match store::user_config_dirs() {
+        Ok(dirs) => dirs,
+        Err(e) => {
+            log::warn!("Log watcher not started: {}", e);
+            return;
+        }
+    };
+    let result = watch(&dirs, DEBOUNCE, MAX_DELAY, |files| {
+        let indexed = Store::open_default()
+            .and_then(|mut store| indexer::index(&mut store, &dirs, false, indexer::WATCHER, indexer::emitter(&app)));
+        match indexed {
+            Ok(stats) if stats.changed_files > 0 => {
+                log::info!("Indexed {} new messages from {} changed files", stats.new_messages, stats.changed_files);
+                let files = files.iter().map(|path| path.display().to_string()).collect();
+                if let Err(e) = app.emit(USAGE_UPDATED_EVENT, UsageUpdate { files, stats }) {
+                    log::warn!("Failed to emit {}: {}", USAGE_UPDATED_EVENT, e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Indexing changed session logs failed: {}", e),
+        }
+        ControlFlow::Continue(())
+    });
+    if let Err(e) = result {
+        log::warn!("{}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watch_batches_session_log_changes() {
+        let config_dir = std::env::temp_dir().join(format!("cc-watcher-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&config_dir);
+        std::fs::create_dir_all(&config_dir).unwrap();
+        let dirs = vec![config_dir.clone()];
+        let log = config_dir.join("projects").join("-home-a").join("s1.jsonl");
+
+        let (batches, received) = mpsc::channel();
+        let watching = std::thread::spawn(move || {
+            watch(&dirs, Duration::from_millis(200), Duration::from_secs(2), |files| {
+                let done = files.iter().any(|path| path.ends_with("s1.jsonl"));
+                batches.send(files).unwrap();
+                if done {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            })
+        });
+        std::thread::sleep(Duration::from_millis(200));
+        // projects/ appears after the watch started: it is picked up with an empty batch
+        std::fs::create_dir_all(log.parent().unwrap()).unwrap();
+        assert!(received.recv_timeout(Duration::from_secs(5)).unwrap().is_empty());
+
+        std::fs::write(config_dir.join("projects").join("notes.txt"), "ignored").unwrap();
+        std::fs::write(&log, "{}\n").unwrap();
+        let batch = received.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(batch, vec![log.clone()]);
+        watching.join().unwrap().unwrap();
+        let _ = std::fs::remove_dir_all(&config_dir);
+    }
+}
@@ -1,11 +1,15 @@
 import { QueryProvider } from './providers/QueryProvider';
 import { ThemeSync } from './theme/ThemeSync';
+import { RouteSync } from './routing/RouteSync';
 import { DashboardPage } from '../pages/dashboard/DashboardPage';
+import { PresentationBanner } from '../components/feedback/PresentationBanner';
 
 export default function App() {
   return (
     <QueryProvider>
       <ThemeSync />
+      <RouteSync />
+      <PresentationBanner />
       <DashboardPage />
     </QueryProvider>
   );
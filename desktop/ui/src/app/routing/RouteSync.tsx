@@ -0,0 +1,24 @@
+import { useEffect } from 'react';
+import { useAppStore } from '../../state/store';
+import { routeChanged } from '../../state/queries';
+
+type NavigationState = ReturnType<typeof useAppStore.getState>;
+
+// Dashboard route: range and project, then the open drill-downs
+export function dashboardRoute(state: NavigationState): string {
+  const segments = ['dashboard', `${state.dateFrom}_${state.dateTo}`, state.selectedProjectId ?? 'all'];
+  if (state.selectedDay) segments.push('day', state.selectedDay);
+  if (state.selectedModel) segments.push('model', state.selectedModel);
+  if (state.selectedSession) segments.push('session', state.selectedSession);
+  return segments.map(encodeURIComponent).join('/');
+}
+
+export function RouteSync() {
+  useEffect(() => {
+    routeChanged(dashboardRoute(useAppStore.getState()));
+    // Subscribe outside React so the route changes before the next queries start
+    return useAppStore.subscribe((state) => routeChanged(dashboardRoute(state)));
+  }, []);
+
+  return null;
+}
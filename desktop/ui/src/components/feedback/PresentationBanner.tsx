@@ -0,0 +1,52 @@
+import { usePresentationMode, useSetPresentationMode } from '../../state/queries';
+import { tokens } from '../../styles/tokens';
+
+export function PresentationBanner() {
+  const { data: mode } = usePresentationMode();
+  const setMode = useSetPresentationMode();
+
+  if (!mode || (!mode.read_only && !mode.blur_project_names && !mode.demo_data)) {
+    return null;
+  }
+
+  const parts = [
+    mode.demo_data && 'demo data',
+    mode.read_only && 'read-only',
+    mode.blur_project_names && 'project names hidden',
+  ].filter(Boolean);
+
+  return (
+    <div
+      role="status"
+      style={{
+        display: 'flex',
+        alignItems: 'center',
+        justifyContent: 'center',
+        gap: '12px',
+        padding: '6px 12px',
+        fontSize: '12px',
+        color: tokens.colors.textPrimary,
+        background: tokens.colors.surface,
+        borderBottom: `2px solid ${tokens.colors.semanticWarning}`,
+      }}
+    >
+      <span>Presentation mode: {parts.join(', ')}</span>
+      <button
+        type="button"
+        disabled={setMode.isPending}
+        onClick={() => setMode.mutate({ readOnly: false, blurProjectNames: false, demoData: false })}
+        style={{
+          fontSize: '12px',
+          padding: '2px 8px',
+          borderRadius: '6px',
+          border: `1px solid ${tokens.colors.surfaceBorder}`,
+          background: tokens.colors.background,
+          color: tokens.colors.textSecondary,
+          cursor: 'pointer',
+        }}
+      >
+        Exit
+      </button>
+    </div>
+  );
+}
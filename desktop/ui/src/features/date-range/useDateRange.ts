@@ -1,5 +1,6 @@
 import { useState } from 'react';
 import { formatDate, normalizeDateString } from '../../lib/date';
+import { resolveRange } from '../../state/queries';
 import { useAppStore } from '../../state/store';
 import type { DashboardDataRange } from '../../types/dashboard';
 
@@ -47,6 +48,12 @@ export function useDateRange({ defaultFrom, dataRange, firstSessionDate }: UseDa
     setRange(formatDate(start), formatDate(end));
   };
 
+  // Presets such as "month to date" or "this billing cycle" are resolved by the backend
+  const setRangePreset = async (preset: string) => {
+    const range = await resolveRange(preset);
+    setRange(range.from, range.to);
+  };
+
   const setRangeAll = () => {
     const fallbackStart = firstSessionDate
       ? normalizeDateString(firstSessionDate)
@@ -67,6 +74,7 @@ export function useDateRange({ defaultFrom, dataRange, firstSessionDate }: UseDa
     applyRange,
     setRangeLast24Hours,
     setRangeLastDays,
+    setRangePreset,
     setRangeAll,
   };
 }
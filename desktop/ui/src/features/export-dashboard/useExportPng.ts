@@ -1,5 +1,6 @@
 import { useState } from 'react';
 import type { RefObject } from 'react';
+import type { SavedExport } from '../../types/api';
 
 const dataUrlToBytes = (dataUrl: string): Uint8Array => {
   const base64 = dataUrl.split(',')[1];
@@ -44,6 +45,8 @@ export function useExportPng({ dashboardRef }: UseExportPngOptions) {
       setIsExporting(true);
       await new Promise<void>((resolve) => requestAnimationFrame(() => resolve()));
 
+      // At least @2x, @3x on denser displays
+      const pixelRatio = Math.min(3, Math.max(2, Math.ceil(window.devicePixelRatio || 1)));
       const backgroundColor = getComputedStyle(document.documentElement)
         .getPropertyValue('--color-background')
         .trim() || '#ffffff';
@@ -51,7 +54,7 @@ export function useExportPng({ dashboardRef }: UseExportPngOptions) {
       try {
         dataUrl = await toPng(dashboardRef.current, {
           cacheBust: true,
-          pixelRatio: 2,
+          pixelRatio,
           backgroundColor,
           filter: (node) => !(node instanceof Element && node.hasAttribute('data-export-exclude')),
         });
@@ -69,25 +72,19 @@ export function useExportPng({ dashboardRef }: UseExportPngOptions) {
       const filename = `cc-dashboard-${timestamp}.png`;
 
       if (isTauriAvailable()) {
-        const { save } = await import('@tauri-apps/plugin-dialog');
-        const { writeFile } = await import('@tauri-apps/plugin-fs');
+        const { invoke } = await import('@tauri-apps/api/core');
 
-        const filePath = await save({
-          defaultPath: filename,
-          filters: [{
-            name: 'PNG Image',
-            extensions: ['png'],
-          }],
+        // Rust opens the save dialog in the default export directory and writes the file
+        const saved = await invoke<SavedExport>('save_export', {
+          data: dataUrl,
+          filename,
         });
 
-        if (!filePath) {
+        if ('cancelled' in saved) {
           return;
         }
 
-        const byteArray = dataUrlToBytes(dataUrl);
-        await writeFile(filePath, byteArray);
-
-        alert(`PNG report saved to:\n${filePath}`);
+        alert(`PNG report saved to:\n${saved.path}`);
         return;
       }
 
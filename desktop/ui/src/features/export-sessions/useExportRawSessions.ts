@@ -0,0 +1,36 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { newRequestId } from '../../state/queries';
+import type { RawSessionExport } from '../../types/api';
+
+const isTauriAvailable = () =>
+  typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+
+export function useExportRawSessions() {
+  const [isExporting, setIsExporting] = useState(false);
+
+  // Returns the export summary, or null if the dialog was cancelled
+  const exportRawSessions = async (
+    sessionIds: string[],
+    redact: boolean = false
+  ): Promise<RawSessionExport | null> => {
+    if (!isTauriAvailable()) {
+      throw new Error('Raw session export is only available in the desktop app');
+    }
+
+    setIsExporting(true);
+    try {
+      // Rust opens the save dialog in the default export directory
+      const result = await invoke<RawSessionExport | { cancelled: true }>('export_raw_sessions', {
+        sessionIds,
+        redact,
+        requestId: newRequestId(),
+      });
+      return 'cancelled' in result ? null : result;
+    } finally {
+      setIsExporting(false);
+    }
+  };
+
+  return { isExporting, exportRawSessions };
+}
@@ -13,7 +13,8 @@ import { useExportPng } from '../../features/export-dashboard/useExportPng';
 import { formatCurrency, formatNumber, getProjectDisplayName } from '../../lib/format';
 import { formatDateTimeForDisplay } from '../../lib/date';
 import { calculateGranularity } from '../../lib/time';
-import { useDashboard, useLimitResets, useProjects, useUsageAccounts } from '../../state/queries';
+import { useDashboard, useLimitResets, useProjects, useUsageAccounts, useUsageUpdates } from '../../state/queries';
+import { useStartupWarmup } from '../../state/warmup';
 import { useAppStore } from '../../state/store';
 import { tokens } from '../../styles/tokens';
 import { DashboardHeader } from './components/DashboardHeader';
@@ -59,6 +60,9 @@ export function DashboardPage() {
     error: usageAccountsError,
   } = useUsageAccounts();
 
+  useStartupWarmup(!!apiData);
+  useUsageUpdates();
+
   const viewModel = useDashboardViewModel(apiData);
   const { isExporting, exportPng } = useExportPng({ dashboardRef });
 
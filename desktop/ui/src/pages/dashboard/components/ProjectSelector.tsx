@@ -59,7 +59,11 @@ export function ProjectSelector() {
           event.currentTarget.style.background = tokens.colors.background;
         }}
       >
-        <Folder size={16} color={tokens.colors.accentPrimary} />
+        {selectedProject?.icon ? (
+          <span style={{ fontSize: '14px' }}>{selectedProject.icon}</span>
+        ) : (
+          <Folder size={16} color={selectedProject?.display_color ?? tokens.colors.accentPrimary} />
+        )}
         <span
           style={{
             fontSize: '14px',
@@ -140,6 +144,9 @@ export function ProjectSelector() {
                   : tokens.colors.textSecondary,
                 fontWeight: selectedProjectId === project.project_id ? '600' : '400',
                 transition: 'all 0.2s ease',
+                display: 'flex',
+                alignItems: 'center',
+                gap: '8px',
               }}
               onMouseEnter={(event) => {
                 event.currentTarget.style.background = tokens.colors.background;
@@ -148,6 +155,16 @@ export function ProjectSelector() {
                 event.currentTarget.style.background = 'transparent';
               }}
             >
+              <span
+                style={{
+                  width: '8px',
+                  height: '8px',
+                  borderRadius: '50%',
+                  background: project.display_color ?? tokens.colors.accentPrimary,
+                  flexShrink: 0,
+                }}
+              />
+              {project.icon && <span>{project.icon}</span>}
               {getProjectDisplayName(project)}
             </div>
           ))}
@@ -1,5 +1,5 @@
 /**
- * Display Settings - theme and date formatting controls
+ * Display Settings - theme, date formatting and startup performance controls
  */
 import { Moon, Sun, ChevronDown } from 'lucide-react';
 import { useAppStore } from '../../../../state/store';
@@ -25,6 +25,8 @@ export function DisplaySettings() {
     setDateFormat,
     dateTimeFormat,
     setDateTimeFormat,
+    warmCacheOnStartup,
+    setWarmCacheOnStartup,
   } = useAppStore();
   const userLocale = getUserLocale();
   const showLocalSample = !isEnglishLocale(userLocale);
@@ -290,6 +292,70 @@ export function DisplaySettings() {
           </div>
         </div>
       </div>
+
+      <div>
+        <h3
+          style={{
+            fontSize: 'var(--font-size-md)',
+            fontWeight: 'var(--font-weight-semibold)',
+            color: 'var(--color-text-primary)',
+            marginBottom: 'var(--spacing-md)',
+          }}
+        >
+          Performance
+        </h3>
+
+        <div
+          style={{
+            display: 'flex',
+            alignItems: 'center',
+            justifyContent: 'space-between',
+            padding: 'var(--spacing-md)',
+            backgroundColor: 'var(--color-surface)',
+            borderRadius: 'var(--radius-md)',
+            border: '1px solid var(--color-border)',
+          }}
+        >
+          <div>
+            <div
+              style={{
+                fontSize: 'var(--font-size-base)',
+                fontWeight: 'var(--font-weight-medium)',
+                color: 'var(--color-text-primary)',
+              }}
+            >
+              Warm cache on startup
+            </div>
+            <div
+              style={{
+                fontSize: 'var(--font-size-xs)',
+                color: 'var(--color-text-muted)',
+              }}
+            >
+              Preload this month, projects and today in the background (skipped on low battery)
+            </div>
+          </div>
+
+          <button
+            onClick={() => setWarmCacheOnStartup(!warmCacheOnStartup)}
+            style={{
+              padding: 'var(--spacing-sm) var(--spacing-md)',
+              borderRadius: 'var(--radius-md)',
+              border: '1px solid var(--color-border)',
+              backgroundColor: warmCacheOnStartup ? 'var(--color-accent-primary)' : 'var(--color-surface)',
+              color: warmCacheOnStartup ? 'white' : 'var(--color-text-primary)',
+              fontSize: 'var(--font-size-sm)',
+              fontWeight: 'var(--font-weight-medium)',
+              cursor: 'pointer',
+              transition: 'all var(--transition-fast)',
+            }}
+            aria-label="Toggle startup cache warming"
+            aria-pressed={warmCacheOnStartup}
+          >
+            {warmCacheOnStartup ? 'Disable' : 'Enable'}
+          </button>
+        </div>
+      </div>
     </div>
   );
 }
@@ -2,17 +2,29 @@
  * Project Settings - manage project visibility and metadata
  */
 import { useMemo, useState } from 'react';
-import { useProjects, useUpdateProject } from '../../../../state/queries';
+import {
+  useDescriptionLlmSettings,
+  useProjects,
+  useSetDescriptionLlm,
+  useSuggestProjectDescription,
+  useUpdateProject,
+} from '../../../../state/queries';
 import type { Project } from '../../../../types/api';
-import { Edit2, Check, X } from 'lucide-react';
+import { Edit2, Check, X, Sparkles } from 'lucide-react';
+import { StaleProjects } from './StaleProjects';
 
 export function ProjectSettings() {
   const { data, isLoading, error } = useProjects();
   const updateMutation = useUpdateProject();
+  const suggestMutation = useSuggestProjectDescription();
+  const { data: llmSettings } = useDescriptionLlmSettings();
+  const setLlmMutation = useSetDescriptionLlm();
 
   const [editingProject, setEditingProject] = useState<string | null>(null);
   const [editedName, setEditedName] = useState('');
   const [editedDescription, setEditedDescription] = useState('');
+  const [editedColor, setEditedColor] = useState('');
+  const [editedIcon, setEditedIcon] = useState('');
   const [lastError, setLastError] = useState<string | null>(null);
 
   const handleVisibleToggle = (projectId: string, visible: boolean) => {
@@ -37,6 +49,8 @@ export function ProjectSettings() {
     setEditingProject(project.project_id);
     setEditedName(project.name);
     setEditedDescription(project.description);
+    setEditedColor(project.color ?? '');
+    setEditedIcon(project.icon ?? '');
   };
 
   const saveEditing = (projectId: string) => {
@@ -44,7 +58,9 @@ export function ProjectSettings() {
       {
         projectId,
         name: editedName,
-        description: editedDescription
+        description: editedDescription,
+        color: editedColor,
+        icon: editedIcon,
       },
       {
         onSuccess: () => {
@@ -59,10 +75,31 @@ export function ProjectSettings() {
     );
   };
 
+  // Fill the description input with a suggestion; nothing is saved until Save
+  const suggestDescription = (projectId: string) => {
+    suggestMutation.mutate(projectId, {
+      onSuccess: (result) => {
+        if (result.suggestion) {
+          setEditedDescription(result.suggestion);
+        } else {
+          setLastError('No session content found to suggest a description from');
+          setTimeout(() => setLastError(null), 5000);
+        }
+      },
+      onError: (err: any) => {
+        const errorMsg = err?.message || err?.toString() || 'Failed to suggest a description';
+        setLastError(errorMsg);
+        setTimeout(() => setLastError(null), 5000);
+      },
+    });
+  };
+
   const cancelEditing = () => {
     setEditingProject(null);
     setEditedName('');
     setEditedDescription('');
+    setEditedColor('');
+    setEditedIcon('');
   };
 
   if (isLoading) {
@@ -156,7 +193,13 @@ export function ProjectSettings() {
             editedDescription={editedDescription}
             onEditedNameChange={setEditedName}
             onEditedDescriptionChange={setEditedDescription}
+            editedColor={editedColor}
+            editedIcon={editedIcon}
+            onEditedColorChange={setEditedColor}
+            onEditedIconChange={setEditedIcon}
             onStartEdit={() => startEditing(project)}
+            onSuggestDescription={() => suggestDescription(project.project_id)}
+            isSuggesting={suggestMutation.isPending}
             onSave={() => saveEditing(project.project_id)}
             onCancel={cancelEditing}
             onVisibleToggle={(visible) => handleVisibleToggle(project.project_id, visible)}
@@ -173,6 +216,27 @@ export function ProjectSettings() {
       }}>
         Click on project name or edit icon to modify. Use checkbox to toggle visibility.
       </p>
+
+      {/* LLM phrasing of description suggestions */}
+      <label style={{
+        display: 'flex',
+        alignItems: 'center',
+        gap: 'var(--spacing-sm)',
+        fontSize: 'var(--font-size-xs)',
+        color: 'var(--color-text-secondary)',
+      }}>
+        <input
+          type="checkbox"
+          className="limit-checkbox size-md"
+          checked={llmSettings?.enabled ?? false}
+          disabled={setLlmMutation.isPending || !llmSettings?.api_key_set}
+          onChange={(e) => setLlmMutation.mutate({ enabled: e.target.checked })}
+        />
+        Phrase description suggestions with the Anthropic API
+        {llmSettings && !llmSettings.api_key_set && ' (requires ANTHROPIC_API_KEY)'}
+      </label>
+
+      <StaleProjects />
     </div>
   );
 }
@@ -184,7 +248,13 @@ interface ProjectRowProps {
   editedDescription: string;
   onEditedNameChange: (value: string) => void;
   onEditedDescriptionChange: (value: string) => void;
+  editedColor: string;
+  editedIcon: string;
+  onEditedColorChange: (value: string) => void;
+  onEditedIconChange: (value: string) => void;
   onStartEdit: () => void;
+  onSuggestDescription: () => void;
+  isSuggesting: boolean;
   onSave: () => void;
   onCancel: () => void;
   onVisibleToggle: (visible: boolean) => void;
@@ -199,7 +269,13 @@ function ProjectRow({
   editedDescription,
   onEditedNameChange,
   onEditedDescriptionChange,
+  editedColor,
+  editedIcon,
+  onEditedColorChange,
+  onEditedIconChange,
   onStartEdit,
+  onSuggestDescription,
+  isSuggesting,
   onSave,
   onCancel,
   onVisibleToggle,
@@ -247,22 +323,97 @@ function ProjectRow({
             }}
           />
 
-          {/* Description input */}
-          <input
-            type="text"
-            value={editedDescription}
-            onChange={(e) => onEditedDescriptionChange(e.target.value)}
-            placeholder="Description..."
-            maxLength={500}
-            style={{
-              padding: 'var(--spacing-sm)',
-              border: '1px solid var(--color-border)',
-              borderRadius: 'var(--radius-sm)',
-              fontSize: 'var(--font-size-xs)',
-              backgroundColor: 'var(--color-background)',
-              color: 'var(--color-text-secondary)',
-            }}
-          />
+          {/* Description input, with a suggestion from recent sessions */}
+          <div style={{ display: 'flex', gap: 'var(--spacing-sm)' }}>
+            <input
+              type="text"
+              value={editedDescription}
+              onChange={(e) => onEditedDescriptionChange(e.target.value)}
+              placeholder="Description..."
+              maxLength={500}
+              style={{
+                flex: 1,
+                padding: 'var(--spacing-sm)',
+                border: '1px solid var(--color-border)',
+                borderRadius: 'var(--radius-sm)',
+                fontSize: 'var(--font-size-xs)',
+                backgroundColor: 'var(--color-background)',
+                color: 'var(--color-text-secondary)',
+              }}
+            />
+            <button
+              onClick={onSuggestDescription}
+              disabled={isSaving || isSuggesting}
+              title="Suggest a description from recent sessions"
+              style={{
+                padding: 'var(--spacing-xs) var(--spacing-sm)',
+                border: '1px solid var(--color-border)',
+                borderRadius: 'var(--radius-sm)',
+                backgroundColor: 'transparent',
+                color: 'var(--color-text-muted)',
+                fontSize: 'var(--font-size-xs)',
+                cursor: isSuggesting ? 'wait' : 'pointer',
+                display: 'flex',
+                alignItems: 'center',
+                gap: '4px',
+              }}
+            >
+              <Sparkles size={14} />
+              {isSuggesting ? 'Suggesting...' : 'Suggest'}
+            </button>
+          </div>
+
+          {/* Color and icon (used in project lists, charts and PNG exports) */}
+          <div style={{ display: 'flex', gap: 'var(--spacing-sm)', alignItems: 'center' }}>
+            <input
+              type="color"
+              value={editedColor || project.display_color || '#d97757'}
+              onChange={(e) => onEditedColorChange(e.target.value)}
+              aria-label="Project color"
+              style={{
+                width: '32px',
+                height: '28px',
+                padding: 0,
+                border: '1px solid var(--color-border)',
+                borderRadius: 'var(--radius-sm)',
+                backgroundColor: 'var(--color-background)',
+                cursor: 'pointer',
+              }}
+            />
+            {editedColor && (
+              <button
+                onClick={() => onEditedColorChange('')}
+                disabled={isSaving}
+                style={{
+                  padding: 'var(--spacing-xs) var(--spacing-sm)',
+                  border: '1px solid var(--color-border)',
+                  borderRadius: 'var(--radius-sm)',
+                  backgroundColor: 'transparent',
+                  color: 'var(--color-text-muted)',
+                  fontSize: 'var(--font-size-xs)',
+                  cursor: 'pointer',
+                }}
+              >
+                Default color
+              </button>
+            )}
+            <input
+              type="text"
+              value={editedIcon}
+              onChange={(e) => onEditedIconChange(e.target.value)}
+              placeholder="Icon (emoji)..."
+              maxLength={16}
+              style={{
+                width: '120px',
+                padding: 'var(--spacing-sm)',
+                border: '1px solid var(--color-border)',
+                borderRadius: 'var(--radius-sm)',
+                fontSize: 'var(--font-size-xs)',
+                backgroundColor: 'var(--color-background)',
+                color: 'var(--color-text-secondary)',
+              }}
+            />
+          </div>
 
           {/* Action buttons */}
           <div style={{ display: 'flex', gap: 'var(--spacing-sm)', justifyContent: 'flex-end' }}>
@@ -357,7 +508,12 @@ function ProjectRow({
           fontSize: 'var(--font-size-sm)',
           fontWeight: 'var(--font-weight-medium)',
           color: 'var(--color-text-primary)',
+          display: 'flex',
+          alignItems: 'center',
+          gap: '6px',
         }}>
+          <ProjectColorDot color={project.display_color} />
+          {project.icon && <span>{project.icon}</span>}
           {project.name || <span style={{ fontStyle: 'italic', color: 'var(--color-text-muted)' }}>Unnamed project</span>}
         </div>
         {project.description && (
@@ -405,6 +561,20 @@ function ProjectRow({
   );
 }
 
+function ProjectColorDot({ color }: { color?: string }) {
+  return (
+    <span
+      style={{
+        width: '10px',
+        height: '10px',
+        borderRadius: '50%',
+        backgroundColor: color || 'var(--color-accent-primary)',
+        flexShrink: 0,
+      }}
+    />
+  );
+}
+
 function LoadingSkeleton() {
   return (
     <div style={{ display: 'flex', flexDirection: 'column', gap: 'var(--spacing-sm)' }}>
@@ -0,0 +1,108 @@
+/**
+ * Stale Projects - inactive projects with their footprint and one-click archiving
+ */
+import { useState } from 'react';
+import { useArchiveProjects, useStaleProjects } from '../../../../state/queries';
+import { Archive } from 'lucide-react';
+
+const THRESHOLDS = [30, 90, 180, 365];
+
+function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+export function StaleProjects() {
+  const [inactiveDays, setInactiveDays] = useState(90);
+  const { data, isLoading } = useStaleProjects(inactiveDays);
+  const archiveMutation = useArchiveProjects();
+
+  const suggested = data?.projects.filter((project) => project.suggestion === 'archive') ?? [];
+
+  return (
+    <div style={{ display: 'flex', flexDirection: 'column', gap: 'var(--spacing-sm)' }}>
+      <div style={{ display: 'flex', alignItems: 'center', gap: 'var(--spacing-sm)' }}>
+        <span style={{ fontSize: 'var(--font-size-sm)', fontWeight: 600 }}>Inactive projects</span>
+        <select
+          value={inactiveDays}
+          onChange={(e) => setInactiveDays(Number(e.target.value))}
+          aria-label="Inactivity threshold"
+          style={{ fontSize: 'var(--font-size-xs)' }}
+        >
+          {THRESHOLDS.map((days) => (
+            <option key={days} value={days}>
+              {days}+ days
+            </option>
+          ))}
+        </select>
+        {suggested.length > 0 && (
+          <button
+            onClick={() => archiveMutation.mutate(suggested.map((project) => project.project_id))}
+            disabled={archiveMutation.isPending}
+            title="Hide from the project selector; usage stays in all totals"
+            style={{
+              marginLeft: 'auto',
+              display: 'flex',
+              alignItems: 'center',
+              gap: 'var(--spacing-xs)',
+              fontSize: 'var(--font-size-xs)',
+            }}
+          >
+            <Archive size={14} />
+            Archive {suggested.length}
+          </button>
+        )}
+      </div>
+
+      {isLoading && (
+        <div style={{ fontSize: 'var(--font-size-xs)', color: 'var(--color-text-muted)' }}>Loading…</div>
+      )}
+
+      {data && data.projects.length === 0 && (
+        <div style={{ fontSize: 'var(--font-size-xs)', color: 'var(--color-text-muted)' }}>
+          No projects inactive for {inactiveDays} days.
+        </div>
+      )}
+
+      {data && data.projects.length > 0 && (
+        <>
+          {data.projects.map((project) => (
+            <div
+              key={project.project_id}
+              style={{
+                display: 'flex',
+                alignItems: 'center',
+                gap: 'var(--spacing-sm)',
+                fontSize: 'var(--font-size-xs)',
+                color: project.visible ? 'var(--color-text-secondary)' : 'var(--color-text-muted)',
+              }}
+            >
+              <span style={{ flex: 1, overflow: 'hidden', textOverflow: 'ellipsis', whiteSpace: 'nowrap' }}>
+                {project.name}
+                {!project.visible && ' (hidden)'}
+                {project.pinned && ' (pinned)'}
+              </span>
+              <span>{project.days_inactive} days</span>
+              <span>{formatBytes(project.source_bytes + project.mirror_bytes)}</span>
+              {project.suggestion === 'archive' && (
+                <button
+                  onClick={() => archiveMutation.mutate([project.project_id])}
+                  disabled={archiveMutation.isPending}
+                  aria-label={`Archive ${project.name}`}
+                  title={project.reason}
+                >
+                  <Archive size={12} />
+                </button>
+              )}
+            </div>
+          ))}
+          <div style={{ fontSize: 'var(--font-size-xs)', color: 'var(--color-text-muted)', fontStyle: 'italic' }}>
+            {formatBytes(data.totals.source_bytes)} of session files and {formatBytes(data.totals.mirror_bytes)} of
+            cache. Archiving only hides a project; nothing is deleted.
+          </div>
+        </>
+      )}
+    </div>
+  );
+}
@@ -1,19 +1,109 @@
 /**
  * React Query hooks for Tauri API calls
  */
-import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
-import { invoke } from '@tauri-apps/api/core';
+import { useQuery, useInfiniteQuery, useMutation, useQueryClient } from '@tanstack/react-query';
+import { Channel, invoke } from '@tauri-apps/api/core';
+import { emit, listen } from '@tauri-apps/api/event';
+import { getCurrentWebview } from '@tauri-apps/api/webview';
+import { useEffect, useState } from 'react';
 import type {
   DashboardBundle,
+  DashboardRangeRequest,
+  DashboardSection,
+  PartialDashboardBundle,
   DayDetails,
+  AggregateHistory,
   ModelDetails,
   SessionDetails,
+  SessionFilter,
+  SessionPage,
+  RawSessionEvent,
+  RawSessionSummary,
+  SessionArchiveImport,
+  BackupComponent,
+  BackupRestore,
+  MergeReport,
+  CalendarPeriod,
+  EnvironmentDiagnosis,
+  BenchmarkReport,
+  NativeIndexStats,
+  UsageUpdate,
+  NativeStoreStatus,
+  IndexStatus,
+  BackendVersion,
+  BackendLogLine,
+  DisplayScale,
+  ReportTemplate,
+  RenderedReport,
+  PngReport,
+  PngReportExport,
+  SocialFormat,
+  WorkingHours,
+  OutOfHoursUsage,
+  TimeShiftFilter,
+  TimeShiftResult,
+  TimestampShift,
+  BackupResult,
+  BackupVerification,
+  DerivedMetric,
+  DerivedMetricsResponse,
+  AvailableMetric,
+  MetricSeries,
+  PluginInfo,
+  PluginList,
+  RangeSettings,
+  ResolvedRange,
   Granularity,
   LimitEvent,
+  OutcomeStats,
+  CompactionStats,
+  McpUsage,
+  HookStats,
+  EffortBreakdown,
+  ConcurrencyStats,
   ProjectsResponse,
+  ProjectSort,
+  RefreshDelta,
+  RefreshStatus,
+  RefreshProgress,
+  ResultChunk,
+  StreamedResponse,
+  QuarantinedLines,
+  SchemaInfo,
+  ExportJob,
+  ExportKind,
+  ExportStatus,
+  ExportSchedule,
+  ScheduleRule,
+  SchedulePreview,
+  Changelog,
+  FeatureFlags,
+  ApiToken,
+  ApiTokens,
+  ApiTokenScope,
+  CreatedApiToken,
+  HttpServerSettings,
+  PresentationMode,
+  PrivacyMode,
+  DemoDataResult,
+  SampleDataResult,
+  SampleIntensity,
+  TelemetryPreview,
+  TelemetrySettings,
   UpdateProjectParams,
   UpdateProjectResponse,
+  ProjectDescriptionSuggestion,
+  ProjectTimeline,
+  ProjectTimelineGranularity,
+  FirstSeenBackfill,
+  StaleProjectsResponse,
+  Project,
+  DescriptionLlmSettings,
   UsageAccountsResponse,
+  CombinedDashboard,
+  Workspace,
+  WorkspaceAssignment,
+  WorkspacesResponse,
 } from '../types/api';
 
 // Check if running in Tauri
@@ -28,18 +118,52 @@ const ENDPOINT_MAP: Record<string, string> = {
   get_limit_resets: 'limits',
   get_projects: 'projects',
   get_usage_accounts: 'usage-accounts',
+  get_refresh_status: 'refresh-status',
   update_project: 'update-project',
 };
 
-// API adapter - uses Tauri invoke in desktop, fetch in browser
-async function apiCall<T>(endpoint: string, params: Record<string, any>): Promise<T> {
+// Request IDs correlate UI calls with bridge logs, the audit log and errors
+let requestCounter = 0;
+export function newRequestId(): string {
+  requestCounter += 1;
+  return `ui-${Date.now().toString(36)}-${requestCounter.toString(36)}`;
+}
+
+// Route the UI is on, sent with every call. Leaving a route cancels the
+// dashboard and drill-down calls made on it, unless the new route is nested
+// under it ('dashboard/<range>/all' stays alive for 'dashboard/<range>/all/day/<date>').
+let currentRoute: string | null = null;
+export function routeChanged(route: string) {
+  if (route === currentRoute) return;
+  currentRoute = route;
+  if (isTauri) {
+    emit('route-changed', { webview: getCurrentWebview().label, route }).catch(() => {});
+  }
+}
+
+// API adapter - uses Tauri invoke in desktop, fetch in browser.
+// Aborting the signal (e.g. React Query cancelling a superseded query) kills the backend call.
+async function apiCall<T>(
+  endpoint: string,
+  params: Record<string, any>,
+  signal?: AbortSignal,
+  requestId: string = newRequestId()
+): Promise<T> {
   // Filter out null/undefined values
   const filteredParams = Object.fromEntries(
     Object.entries(params).filter(([_, v]) => v !== null && v !== undefined)
   );
 
   if (isTauri) {
-    return invoke<T>(endpoint, filteredParams);
+    const cancel = () => {
+      invoke('cancel_request', { requestId }).catch(() => {});
+    };
+    signal?.addEventListener('abort', cancel, { once: true });
+    try {
+      return await invoke<T>(endpoint, { requestId, ...(currentRoute ? { route: currentRoute } : {}), ...filteredParams });
+    } finally {
+      signal?.removeEventListener('abort', cancel);
+    }
   } else {
     // Browser mode - use Vite dev server API
     const apiPath = ENDPOINT_MAP[endpoint] || endpoint;
@@ -66,7 +190,7 @@ async function apiCall<T>(endpoint: string, params: Record<string, any>): Promis
       const queryParams = new URLSearchParams(
         Object.entries(filteredParams).map(([k, v]) => [k, String(v)])
       ).toString();
-      const response = await fetch(`/api/${apiPath}?${queryParams}`);
+      const response = await fetch(`/api/${apiPath}?${queryParams}`, { signal });
       if (!response.ok) {
         throw new Error(`API error: ${response.statusText}`);
       }
@@ -75,35 +199,199 @@ async function apiCall<T>(endpoint: string, params: Record<string, any>): Promis
   }
 }
 
-// Dashboard bundle query
-export function useDashboard(
+// Call of a streaming command (list_sessions, get_session_details): the big list
+// arrives as 'result-chunk' events before the result and is merged back into it.
+// onChunk sees the items so far (a retried call starts again at seq 0).
+async function streamedApiCall<T>(
+  endpoint: string,
+  params: Record<string, any>,
+  onChunk?: (key: string, items: unknown[]) => void,
+  signal?: AbortSignal
+): Promise<T> {
+  if (!isTauri) {
+    return apiCall<T>(endpoint, params, signal);
+  }
+
+  const requestId = newRequestId();
+  let items: unknown[] = [];
+  const unlisten = await listen<ResultChunk>('result-chunk', (event) => {
+    const chunk = event.payload;
+    if (chunk.request_id !== requestId) return;
+    items = chunk.seq === 0 ? [...chunk.items] : [...items, ...chunk.items];
+    onChunk?.(chunk.key, items);
+  });
+  try {
+    const result = await apiCall<T & StreamedResponse>(endpoint, { ...params, stream: true }, signal, requestId);
+    if (result.streamed) {
+      const key = result.streamed.key as keyof typeof result;
+      return { ...result, [key]: [...items, ...((result[key] as unknown[]) ?? [])] } as T;
+    }
+    return result;
+  } finally {
+    unlisten();
+  }
+}
+
+// Last response per command + params, revalidated with ifNoneMatch (ETag) on polls
+const ETAG_CACHE_SIZE = 20;
+const etagCache = new Map<string, { etag: string; data: unknown }>();
+
+type ConditionalResponse = { etag?: string; not_modified?: boolean };
+
+// Read command call that returns the cached payload when the backend reports not_modified
+async function conditionalApiCall<T>(
+  endpoint: string,
+  params: Record<string, any>,
+  signal?: AbortSignal
+): Promise<T> {
+  if (!isTauri) {
+    return apiCall<T>(endpoint, params, signal);
+  }
+
+  const key = `${endpoint}:${JSON.stringify(params)}`;
+  const cached = etagCache.get(key);
+  let result = await apiCall<T & ConditionalResponse>(endpoint, { ...params, ifNoneMatch: cached?.etag }, signal);
+
+  if (result.not_modified) {
+    if (cached) {
+      // Refresh LRU position
+      etagCache.delete(key);
+      etagCache.set(key, cached);
+      return cached.data as T;
+    }
+    result = await apiCall<T & ConditionalResponse>(endpoint, params, signal);
+  }
+
+  if (result.etag) {
+    etagCache.delete(key);
+    etagCache.set(key, { etag: result.etag, data: result });
+    if (etagCache.size > ETAG_CACHE_SIZE) {
+      etagCache.delete(etagCache.keys().next().value as string);
+    }
+  }
+  return result;
+}
+
+// Dashboard bundle query options (shared with startup cache warming)
+export function dashboardQueryOptions(
   from: string,
   to: string,
   refresh: boolean,
   granularity: Granularity,
-  projectId: string | null = null
+  projectId: string | null = null,
+  workspaceId: string | null = null
 ) {
-  return useQuery({
-    queryKey: ['dashboard', from, to, refresh, granularity, projectId],
-    queryFn: async () => {
-      const params = { from, to, refresh, granularity, projectId: projectId };
+  return {
+    queryKey: ['dashboard', from, to, refresh, granularity, projectId, workspaceId],
+    queryFn: async ({ signal }: { signal?: AbortSignal }) => {
+      const params = { from, to, refresh, granularity, projectId: projectId, workspaceId };
       console.log('[Dashboard] Calling API with params:', JSON.stringify(params));
-      const result = await apiCall<DashboardBundle>('get_dashboard_bundle', params);
+      const result = await conditionalApiCall<DashboardBundle>('get_dashboard_bundle', params, signal);
       console.log('[Dashboard] Got result, totals.messages:', result.totals.messages);
       return result;
     },
     staleTime: 30_000, // 30 seconds
+  };
+}
+
+// Dashboard bundle query
+export function useDashboard(
+  from: string,
+  to: string,
+  refresh: boolean,
+  granularity: Granularity,
+  projectId: string | null = null,
+  workspaceId: string | null = null
+) {
+  return useQuery({
+    ...dashboardQueryOptions(from, to, refresh, granularity, projectId, workspaceId),
+    retry: 2,
+  });
+}
+
+// Dashboard bundle limited to some sections (e.g. only the timeline), cheaper for narrow views
+export function useDashboardSections(
+  from: string,
+  to: string,
+  granularity: Granularity,
+  sections: DashboardSection[],
+  projectId: string | null = null,
+  workspaceId: string | null = null
+) {
+  return useQuery({
+    queryKey: ['dashboard', from, to, false, granularity, projectId, workspaceId, sections],
+    queryFn: ({ signal }) =>
+      conditionalApiCall<PartialDashboardBundle>(
+        'get_dashboard_bundle',
+        {
+          from,
+          to,
+          refresh: false,
+          granularity,
+          projectId: projectId,
+          workspaceId,
+          sections,
+        },
+        signal
+      ),
+    enabled: sections.length > 0,
+    staleTime: 30_000, // 30 seconds
+    retry: 2,
+  });
+}
+
+// Multiple dashboard bundles (e.g. current + previous period), fetched concurrently
+export function useDashboardBundles(
+  ranges: DashboardRangeRequest[],
+  refresh: boolean,
+  granularity: Granularity,
+  projectId: string | null = null,
+  workspaceId: string | null = null
+) {
+  return useQuery({
+    queryKey: ['dashboards', ranges, refresh, granularity, projectId, workspaceId],
+    queryFn: ({ signal }) =>
+      apiCall<DashboardBundle[]>(
+        'get_dashboard_bundles',
+        {
+          ranges,
+          refresh,
+          granularity,
+          projectId: projectId,
+          workspaceId,
+        },
+        signal
+      ),
+    enabled: ranges.length > 0,
+    staleTime: 30_000, // 30 seconds
     retry: 2,
   });
 }
 
+// Day details query options (shared with startup cache warming)
+export function dayDetailsQueryOptions(date: string, projectId: string | null = null) {
+  return {
+    queryKey: ['day', date, projectId],
+    queryFn: () => apiCall<DayDetails>('get_day_details', { date, projectId: projectId }),
+    staleTime: 60_000, // 1 minute
+  };
+}
+
 // Day details query
 export function useDayDetails(date: string | null, projectId: string | null = null) {
   return useQuery({
-    queryKey: ['day', date, projectId],
-    queryFn: () => apiCall<DayDetails>('get_day_details', { date: date!, projectId: projectId }),
+    ...dayDetailsQueryOptions(date ?? '', projectId),
+    enabled: !!date,
+  });
+}
+
+// Recorded versions of a day's totals (why a day changed after reprices, re-ingests...)
+export function useAggregateHistory(date: string | null) {
+  return useQuery({
+    queryKey: ['aggregate-history', date],
+    queryFn: ({ signal }) =>
+      apiCall<AggregateHistory>('get_aggregate_history', { date: date! }, signal),
     enabled: !!date,
-    staleTime: 60_000, // 1 minute
   });
 }
 
@@ -116,32 +404,112 @@ export function useModelDetails(
 ) {
   return useQuery({
     queryKey: ['model', model, from, to, projectId],
-    queryFn: () =>
-      apiCall<ModelDetails>('get_model_details', {
-        model: model!,
-        from,
-        to,
-        projectId: projectId,
-      }),
+    queryFn: ({ signal }) =>
+      apiCall<ModelDetails>(
+        'get_model_details',
+        {
+          model: model!,
+          from,
+          to,
+          projectId: projectId,
+        },
+        signal
+      ),
     enabled: !!model,
     staleTime: 60_000, // 1 minute
   });
 }
 
 // Session details query
-export function useSessionDetails(sessionId: string | null, projectId: string | null = null) {
+// Long transcripts are streamed; onMessages (if given) gets the messages received so far
+export function useSessionDetails(
+  sessionId: string | null,
+  projectId: string | null = null,
+  onMessages?: (messages: SessionDetails['messages']) => void
+) {
   return useQuery({
     queryKey: ['session', sessionId, projectId],
-    queryFn: () =>
-      apiCall<SessionDetails>('get_session_details', {
-        sessionId: sessionId!,
-        projectId: projectId,
-      }),
+    queryFn: ({ signal }) =>
+      streamedApiCall<SessionDetails>(
+        'get_session_details',
+        {
+          sessionId: sessionId!,
+          projectId: projectId,
+        },
+        onMessages && ((_key, items) => onMessages(items as SessionDetails['messages'])),
+        signal
+      ),
     enabled: !!sessionId,
     staleTime: 300_000, // 5 minutes
   });
 }
 
+// Cursor-paginated session list for virtualized tables
+export function useSessionsInfinite(filter: SessionFilter, pageSize: number = 100) {
+  return useInfiniteQuery({
+    queryKey: ['sessions', filter, pageSize],
+    queryFn: ({ pageParam, signal }) =>
+      streamedApiCall<SessionPage>(
+        'list_sessions',
+        {
+          filter,
+          cursor: pageParam,
+          pageSize,
+        },
+        undefined,
+        signal
+      ),
+    initialPageParam: null as string | null,
+    getNextPageParam: (lastPage) => lastPage.next_cursor,
+    staleTime: 60_000,
+  });
+}
+
+// Resolve a range preset ("last 7 days", "month to date", ...) with the shared timezone/week settings
+export function resolveRange(preset: string): Promise<ResolvedRange> {
+  return apiCall<ResolvedRange>('resolve_range', { preset });
+}
+
+export function useRangeSettings() {
+  return useQuery({
+    queryKey: ['range-settings'],
+    queryFn: () => apiCall<RangeSettings>('get_range_settings', {}),
+    staleTime: Infinity,
+  });
+}
+
+export function useSetRangeSettings() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: Partial<RangeSettings>) =>
+      apiCall<RangeSettings>('set_range_settings', {
+        timezone: params.timezone,
+        weekStart: params.week_start,
+        billingAnchorDay: params.billing_anchor_day,
+      }),
+    onSuccess: (settings) => {
+      queryClient.setQueryData(['range-settings'], settings);
+      // Budgets and plan costs are computed per billing cycle
+      queryClient.invalidateQueries({ queryKey: ['dashboard'] });
+    },
+  });
+}
+
+// Stream a session's raw JSONL (format 'jsonl') or parsed records (format 'json'); desktop only
+export async function streamRawSession(
+  sessionId: string,
+  onEvent: (event: RawSessionEvent) => void,
+  format: 'jsonl' | 'json' = 'jsonl'
+): Promise<RawSessionSummary> {
+  if (!isTauri) {
+    throw new Error('Raw session streaming is only available in the desktop app');
+  }
+  const onChunk = new Channel<RawSessionEvent>();
+  onChunk.onmessage = onEvent;
+  return apiCall<RawSessionSummary>('get_raw_session', { sessionId, format, onChunk });
+}
+
 // Limit resets query
 export function useLimitResets(from: string, to: string, enabled: boolean = true) {
   return useQuery({
@@ -156,40 +524,1057 @@ export function useLimitResets(from: string, to: string, enabled: boolean = true
   });
 }
 
-// Projects query
-export function useProjects() {
+// Projects query options (shared with startup cache warming)
+export const projectsQueryOptions = {
+  queryKey: ['projects'],
+  queryFn: () => conditionalApiCall<ProjectsResponse>('get_projects', {}),
+  staleTime: 300_000, // 5 minutes - data rarely changes
+};
+
+// Session outcomes; new and changed sessions of the range are classified on fetch
+export function useOutcomeStats(from: string, to: string, projectId: string | null = null) {
+  return useQuery({
+    queryKey: ['outcome-stats', from, to, projectId],
+    queryFn: () => apiCall<OutcomeStats>('get_outcome_stats', { from, to, projectId }),
+    staleTime: 5 * 60_000, // 5 minutes
+  });
+}
+
+export function useCompactionStats(from: string, to: string, projectId: string | null = null) {
+  return useQuery({
+    queryKey: ['compaction-stats', from, to, projectId],
+    queryFn: () => apiCall<CompactionStats>('get_compaction_stats', { from, to, projectId }),
+    staleTime: 5 * 60_000, // 5 minutes
+  });
+}
+
+export function useMcpUsage(from: string, to: string, projectId: string | null = null) {
   return useQuery({
-    queryKey: ['projects'],
-    queryFn: () => apiCall<ProjectsResponse>('get_projects', {}),
-    staleTime: 300_000, // 5 minutes - data rarely changes
+    queryKey: ['mcp-usage', from, to, projectId],
+    queryFn: () => apiCall<McpUsage>('get_mcp_usage', { from, to, projectId }),
+    staleTime: 5 * 60_000, // 5 minutes
   });
 }
 
+export function useHookStats(from: string, to: string, projectId: string | null = null) {
+  return useQuery({
+    queryKey: ['hook-stats', from, to, projectId],
+    queryFn: () => apiCall<HookStats>('get_hook_stats', { from, to, projectId }),
+    staleTime: 5 * 60_000, // 5 minutes
+  });
+}
+
+export function useEffortBreakdown(model: string, from: string, to: string, projectId: string | null = null) {
+  return useQuery({
+    queryKey: ['effort-breakdown', model, from, to, projectId],
+    queryFn: () => apiCall<EffortBreakdown>('get_effort_breakdown', { model, from, to, projectId }),
+    staleTime: 5 * 60_000, // 5 minutes
+    enabled: !!model,
+  });
+}
+
+export function useConcurrencyStats(from: string, to: string, idleGapMinutes?: number) {
+  return useQuery({
+    queryKey: ['concurrency-stats', from, to, idleGapMinutes],
+    queryFn: () => apiCall<ConcurrencyStats>('get_concurrency_stats', { from, to, idleGapMinutes }),
+    staleTime: 5 * 60_000, // 5 minutes
+  });
+}
+
+// Projects query (default: manual order)
+export function useProjects(sort: ProjectSort | null = null) {
+  return useQuery(
+    sort
+      ? {
+          queryKey: ['projects', sort],
+          queryFn: () => conditionalApiCall<ProjectsResponse>('get_projects', { sort }),
+          staleTime: 300_000, // 5 minutes - data rarely changes
+        }
+      : projectsQueryOptions
+  );
+}
+
 // Usage accounts query
 export function useUsageAccounts() {
   return useQuery({
     queryKey: ['usage-accounts'],
-    queryFn: () => apiCall<UsageAccountsResponse>('get_usage_accounts', {}),
+    queryFn: () => conditionalApiCall<UsageAccountsResponse>('get_usage_accounts', {}),
     staleTime: 60_000, // 1 minute
   });
 }
 
-// Update project mutation
-export function useUpdateProject() {
+// Combined dashboard of several usage accounts (queried concurrently by the backend)
+export function useCombinedDashboard(from: string, to: string, accountIds: string[]) {
+  return useQuery({
+    queryKey: ['combined-dashboard', from, to, accountIds],
+    queryFn: ({ signal }) =>
+      apiCall<CombinedDashboard>('get_combined_dashboard', { from, to, accountIds }, signal),
+    enabled: accountIds.length > 0,
+    staleTime: 60_000, // 1 minute
+  });
+}
+
+// Data freshness; polled every 2s while a refresh is running
+export function useRefreshStatus() {
+  return useQuery({
+    queryKey: ['refresh-status'],
+    queryFn: () => apiCall<RefreshStatus>('get_refresh_status', {}),
+    refetchInterval: (query) => (query.state.data?.state === 'running' ? 2_000 : 60_000),
+  });
+}
+
+// Latest 'refresh-progress' event (null before the first refresh); drives the progress bar
+export function useRefreshProgress(): RefreshProgress | null {
+  const [progress, setProgress] = useState<RefreshProgress | null>(null);
+
+  useEffect(() => {
+    if (!isTauri) {
+      return;
+    }
+    const unlisten = listen<RefreshProgress>('refresh-progress', (event) => setProgress(event.payload));
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  return progress;
+}
+
+// Schema versions of settings and database (About / diagnostics)
+export function useSchemaInfo() {
+  return useQuery({
+    queryKey: ['schema-info'],
+    queryFn: () => apiCall<SchemaInfo>('get_schema_info', {}),
+    staleTime: Infinity,
+  });
+}
+
+// Desktop's own SQLite store of the session logs
+export function useNativeStoreStatus() {
+  return useQuery({
+    queryKey: ['native-store-status'],
+    queryFn: () => apiCall<NativeStoreStatus>('get_native_store_status', {}),
+  });
+}
+
+// Indexer state, kept current by 'index-progress' events (e.g. the first build at startup)
+export function useIndexStatus() {
   const queryClient = useQueryClient();
+  const query = useQuery({
+    queryKey: ['index-status'],
+    queryFn: () => apiCall<IndexStatus>('get_index_status', {}),
+    staleTime: Infinity,
+  });
+
+  useEffect(() => {
+    if (!isTauri) {
+      return;
+    }
+    const unlisten = listen<IndexStatus>('index-progress', (event) => {
+      queryClient.setQueryData(['index-status'], event.payload);
+      if (event.payload.state === 'idle') {
+        queryClient.invalidateQueries({ queryKey: ['native-store-status'] });
+      }
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, [queryClient]);
 
+  return query;
+}
+
+// Index new log lines into the store (full: drop and index everything again)
+export function useIndexNativeStore() {
+  const queryClient = useQueryClient();
   return useMutation({
-    mutationFn: async (params: UpdateProjectParams) => {
-      return apiCall<UpdateProjectResponse>('update_project', {
-        projectId: params.projectId,
-        name: params.name,
-        description: params.description,
-        visible: params.visible,
-      });
+    mutationFn: (full?: boolean) => apiCall<NativeIndexStats>('index_native_store', { full }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['native-store-status'] });
+    },
+  });
+}
+
+// New session lines indexed by the log watcher ('usage-updated'): refetch native-backed
+// data and run the fast refresh of today, which refetches dashboards if anything changed
+export function useUsageUpdates() {
+  const queryClient = useQueryClient();
+  const { mutate: refreshToday } = useRefreshToday();
+
+  useEffect(() => {
+    if (!isTauri) {
+      return;
+    }
+    const unlisten = listen<UsageUpdate>('usage-updated', () => {
+      queryClient.invalidateQueries({ queryKey: ['native-store-status'] });
+      queryClient.invalidateQueries({ queryKey: ['projects'] });
+      queryClient.invalidateQueries({ queryKey: ['usage-accounts'] });
+      refreshToday();
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, [queryClient, refreshToday]);
+}
+
+// App and Python API versions; `compatible` is false when the installed package doesn't match
+export function useBackendVersion() {
+  return useQuery({
+    queryKey: ['backend-version'],
+    queryFn: () => apiCall<BackendVersion>('get_backend_version', {}),
+    staleTime: Infinity,
+  });
+}
+
+// Troubleshooting report; starts every backend, so only run when the screen is open
+export function useEnvironmentDiagnosis() {
+  return useQuery({
+    queryKey: ['environment-diagnosis'],
+    queryFn: () => apiCall<EnvironmentDiagnosis>('diagnose_environment', {}),
+    staleTime: Infinity,
+    retry: false,
+  });
+}
+
+// Machine benchmark for performance reports; 1M messages take several minutes
+export function useRunBenchmark() {
+  return useMutation({
+    mutationFn: (sizes?: (10000 | 100000 | 1000000)[]) => apiCall<BenchmarkReport>('run_benchmark', { sizes }),
+  });
+}
+
+// Python stderr lines received while the component is mounted (newest last);
+// turns 'backend-log' events on for that time
+export function useBackendLog(limit = 500): BackendLogLine[] {
+  const [lines, setLines] = useState<BackendLogLine[]>([]);
+
+  useEffect(() => {
+    if (!isTauri) {
+      return;
+    }
+    const unlisten = listen<BackendLogLine>('backend-log', (event) =>
+      setLines((previous) => [...previous, event.payload].slice(-limit))
+    );
+    apiCall('set_backend_log_events', { enabled: true }).catch(() => {});
+    return () => {
+      apiCall('set_backend_log_events', { enabled: false }).catch(() => {});
+      unlisten.then((stop) => stop());
+    };
+  }, [limit]);
+
+  return lines;
+}
+
+// Telemetry opt-in state and the exact payload it would send
+// Presentation (read-only) mode; kept in sync across windows by presentation-mode-changed
+export function usePresentationMode() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    if (!isTauri) {
+      return;
+    }
+    const unlisten = listen<PresentationMode>('presentation-mode-changed', (event) => {
+      queryClient.setQueryData(['presentation-mode'], event.payload);
+      queryClient.invalidateQueries({ predicate: (query) => query.queryKey[0] !== 'presentation-mode' });
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: ['presentation-mode'],
+    queryFn: () => apiCall<PresentationMode>('get_presentation_mode', {}),
+    staleTime: Infinity,
+    enabled: isTauri,
+  });
+}
+
+export function useSetPresentationMode() {
+  return useMutation({
+    mutationFn: (params: { readOnly?: boolean; blurProjectNames?: boolean; demoData?: boolean }) =>
+      apiCall<PresentationMode>('set_presentation_mode', params),
+  });
+}
+
+// Privacy mode: message content is stripped from every response by the backend
+export function usePrivacyMode() {
+  return useQuery({
+    queryKey: ['privacy-mode'],
+    queryFn: () => apiCall<PrivacyMode>('get_privacy_mode', {}),
+    staleTime: Infinity,
+    enabled: isTauri,
+  });
+}
+
+export function useSetPrivacyMode() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (enabled: boolean) => apiCall<PrivacyMode>('set_privacy_mode', { enabled }),
+    onSuccess: (mode) => {
+      queryClient.setQueryData(['privacy-mode'], mode);
+      queryClient.invalidateQueries({ predicate: (query) => query.queryKey[0] !== 'privacy-mode' });
     },
+  });
+}
+
+// Regenerate demo data (demo data mode only); the same seed gives the same data
+export function useGenerateDemoData() {
+  return useMutation({
+    mutationFn: (params: { seed?: number; months?: number }) =>
+      apiCall<DemoDataResult>('generate_demo_data', params),
+  });
+}
+
+// Write sample session logs into the demo home and switch to it (turns demo data mode on)
+export function useGenerateSampleData() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (params: { days?: number; intensity?: SampleIntensity; seed?: number }) =>
+      apiCall<SampleDataResult>('generate_sample_data', params),
     onSuccess: () => {
-      // Invalidate projects query to refresh the list
+      queryClient.invalidateQueries();
+    },
+  });
+}
+
+export function useTelemetrySettings() {
+  return useQuery({
+    queryKey: ['telemetry-settings'],
+    queryFn: () => apiCall<TelemetrySettings>('get_telemetry_settings', {}),
+    staleTime: Infinity,
+  });
+}
+
+export function useTelemetryPreview() {
+  return useQuery({
+    queryKey: ['telemetry-preview'],
+    queryFn: () => apiCall<TelemetryPreview>('preview_telemetry_payload', {}),
+    staleTime: 0,
+  });
+}
+
+export function useSetTelemetry() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { enabled: boolean; endpoint?: string }) =>
+      apiCall<TelemetrySettings>('set_telemetry', params),
+    onSuccess: (settings) => {
+      queryClient.setQueryData(['telemetry-settings'], settings);
+      queryClient.invalidateQueries({ queryKey: ['telemetry-preview'] });
+    },
+  });
+}
+
+// Releases newer than sinceVersion (e.g. the last version the user saw)
+export function useChangelog(sinceVersion?: string) {
+  return useQuery({
+    queryKey: ['changelog', sinceVersion],
+    queryFn: () => apiCall<Changelog>('get_changelog', { sinceVersion }),
+    staleTime: Infinity,
+  });
+}
+
+// Feature flags of experimental subsystems
+export function useFeatureFlags() {
+  return useQuery({
+    queryKey: ['feature-flags'],
+    queryFn: () => apiCall<FeatureFlags>('get_feature_flags', {}),
+    staleTime: Infinity,
+  });
+}
+
+// enabled: undefined drops the local choice
+export function useSetFeatureFlag() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { name: string; enabled?: boolean }) =>
+      apiCall<FeatureFlags>('set_feature_flag', params),
+    onSuccess: (flags) => queryClient.setQueryData(['feature-flags'], flags),
+  });
+}
+
+export function useRefreshFeatureFlags() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (url?: string) => apiCall<FeatureFlags>('refresh_feature_flags', { url }),
+    onSuccess: (flags) => queryClient.setQueryData(['feature-flags'], flags),
+  });
+}
+
+// API tokens of the local HTTP server
+export function useApiTokens() {
+  return useQuery({
+    queryKey: ['api-tokens'],
+    queryFn: () => apiCall<ApiTokens>('list_api_tokens', {}),
+    staleTime: 30_000,
+  });
+}
+
+// expiry: RFC 3339 time; the returned secret is not shown again
+export function useCreateApiToken() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { scopes: ApiTokenScope[]; expiry?: string; name?: string }) =>
+      apiCall<CreatedApiToken>('create_api_token', params),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['api-tokens'] });
+      queryClient.invalidateQueries({ queryKey: ['http-server-settings'] });
+    },
+  });
+}
+
+export function useRevokeApiToken() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (id: string) => apiCall<ApiToken>('revoke_api_token', { id }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['api-tokens'] });
+      queryClient.invalidateQueries({ queryKey: ['http-server-settings'] });
+    },
+  });
+}
+
+export function useHttpServerSettings() {
+  return useQuery({
+    queryKey: ['http-server-settings'],
+    queryFn: () => apiCall<HttpServerSettings>('get_http_server_settings', {}),
+    staleTime: 30_000,
+  });
+}
+
+// Omitted fields stay unchanged, empty strings clear them; rejected if the server could not start
+export function useSetHttpServerSettings() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: {
+      port?: number;
+      bindAddress?: string;
+      allowRemote?: boolean;
+      tlsCert?: string;
+      tlsKey?: string;
+      tlsClientCa?: string;
+    }) => apiCall<HttpServerSettings>('set_http_server_settings', params),
+    onSuccess: (settings) => queryClient.setQueryData(['http-server-settings'], settings),
+  });
+}
+
+// Malformed JSONL lines skipped during ingestion
+export function useQuarantinedLines(sourceFile?: string, limit = 100) {
+  return useQuery({
+    queryKey: ['quarantined-lines', sourceFile, limit],
+    queryFn: () => apiCall<QuarantinedLines>('get_quarantined_lines', { sourceFile, limit }),
+    staleTime: 60_000,
+  });
+}
+
+// Fast-path refresh of today's changes; refetches dashboards only when something changed
+export function useRefreshToday() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => apiCall<RefreshDelta>('refresh_today', {}),
+    onSuccess: (result) => {
+      queryClient.invalidateQueries({ queryKey: ['refresh-status'] });
+      if (result.delta.messages !== 0) {
+        queryClient.invalidateQueries({ queryKey: ['dashboard'] });
+        queryClient.invalidateQueries({ queryKey: ['dashboards'] });
+      }
+    },
+  });
+}
+
+// Export history, refetched when a queued export finishes
+export function useExports(status?: ExportStatus, limit = 50) {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    if (!isTauri) {
+      return;
+    }
+    const unlisten = ['export-completed', 'export-failed'].map((event) =>
+      listen<ExportJob>(event, () => queryClient.invalidateQueries({ queryKey: ['exports'] }))
+    );
+    return () => {
+      unlisten.forEach((promise) => promise.then((stop) => stop()));
+    };
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: ['exports', status, limit],
+    queryFn: () => apiCall<{ exports: ExportJob[] }>('list_exports', { status, limit }),
+  });
+}
+
+export function useEnqueueExport() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { kind: ExportKind; params: Record<string, unknown>; outputPath: string }) =>
+      apiCall<ExportJob>('enqueue_export', params),
+    onSuccess: () => queryClient.invalidateQueries({ queryKey: ['exports'] }),
+  });
+}
+
+export function useDisplayScale() {
+  return useQuery({
+    queryKey: ['display-scale'],
+    queryFn: () => apiCall<DisplayScale>('get_display_scale', {}),
+    staleTime: 60_000, // the window may move to another monitor
+  });
+}
+
+// Render the PNG report at a pixel density (default: the monitor's) and save it
+export function useExportPngReport() {
+  return useMutation({
+    mutationFn: (params: { from: string; to: string; scale?: number; targetPath?: string }) =>
+      apiCall<PngReportExport>('export_png_report', params),
+  });
+}
+
+// Render the PNG report as a Twitter/LinkedIn card and save it
+export function useExportForSocial() {
+  return useMutation({
+    mutationFn: (params: { format: SocialFormat; from: string; to: string; targetPath?: string }) =>
+      apiCall<PngReportExport>('export_for_social', params),
+  });
+}
+
+type PngReportParams = { from: string; to: string; scale?: number; social?: SocialFormat };
+
+// Render the PNG report to a temporary file (kept for an hour) without saving it
+export function useRenderPngReport() {
+  return useMutation({
+    mutationFn: (params: PngReportParams) => apiCall<PngReport>('render_png_report', params),
+  });
+}
+
+// PNG report as a Blob, sent over binary IPC (previews, copying to the clipboard)
+export async function fetchPngReport(params: PngReportParams, signal?: AbortSignal): Promise<Blob> {
+  const bytes = await apiCall<ArrayBuffer>('get_png_report_bytes', params, signal);
+  return new Blob([bytes], { type: 'image/png' });
+}
+
+// Report templates in ~/.claude/db/report-templates (edited outside the app)
+export function useReportTemplates() {
+  return useQuery({
+    queryKey: ['report-templates'],
+    queryFn: () => apiCall<{ directory: string; templates: ReportTemplate[] }>('list_report_templates', {}),
+  });
+}
+
+export function useRenderReport() {
+  return useMutation({
+    mutationFn: (params: {
+      template: string;
+      from: string;
+      to: string;
+      projectId?: string;
+      save?: boolean;
+      targetPath?: string;
+    }) => apiCall<RenderedReport>('render_report', params),
+  });
+}
+
+export function useRerunExport() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (id: string) => apiCall<ExportJob>('rerun_export', { id }),
+    onSuccess: () => queryClient.invalidateQueries({ queryKey: ['exports'] }),
+  });
+}
+
+// Upcoming fire times of a rule, with DST adjustments, shown before a schedule is saved
+export function useSchedulePreview(rule: ScheduleRule | null, count = 5) {
+  return useQuery({
+    queryKey: ['schedule-preview', rule, count],
+    queryFn: () => apiCall<SchedulePreview>('get_schedule_preview', { rule, count }),
+    enabled: rule !== null,
+  });
+}
+
+export function useExportSchedules() {
+  return useQuery({
+    queryKey: ['export-schedules'],
+    queryFn: () => apiCall<{ schedules: ExportSchedule[] }>('list_export_schedules', {}),
+    staleTime: 60_000,
+  });
+}
+
+// Adds a schedule without `id`, replaces the one with it
+export function useSetExportSchedule() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: {
+      id?: string;
+      name?: string;
+      kind: ExportSchedule['kind'];
+      params: Record<string, unknown>;
+      rule: ScheduleRule;
+      enabled?: boolean;
+    }) => apiCall<ExportSchedule>('set_export_schedule', params),
+    onSuccess: () => queryClient.invalidateQueries({ queryKey: ['export-schedules'] }),
+  });
+}
+
+export function useDeleteExportSchedule() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (id: string) => apiCall<{ deleted: boolean }>('delete_export_schedule', { id }),
+    onSuccess: () => queryClient.invalidateQueries({ queryKey: ['export-schedules'] }),
+  });
+}
+
+// Default export directory (save dialogs start there; queued exports are written there)
+export function useExportSettings() {
+  return useQuery({
+    queryKey: ['export-settings'],
+    queryFn: () => apiCall<{ export_dir: string | null; default_dir: string }>('get_export_settings', {}),
+    staleTime: Infinity,
+  });
+}
+
+export function useSetExportDir() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (path: string | null) =>
+      apiCall<{ export_dir: string | null; default_dir: string }>('set_export_dir', { path }),
+    onSuccess: (settings) => queryClient.setQueryData(['export-settings'], settings),
+  });
+}
+
+// Signed backup of settings, project metadata and the database (save dialog if no target)
+export function useCreateBackup() {
+  return useMutation({
+    mutationFn: (targetPath?: string) => apiCall<BackupResult>('create_backup', { targetPath }),
+  });
+}
+
+// Check a backup's checksums and signature without restoring it
+export function useVerifyBackup() {
+  return useMutation({
+    mutationFn: (path: string) => apiCall<BackupVerification>('verify_backup', { path }),
+  });
+}
+
+// Restore a backup (verified first); force accepts a backup signed by another key.
+// dryRun only reports per-file changes; components limits the restore (default all)
+export function useRestoreBackup() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: {
+      path: string;
+      force?: boolean;
+      components?: BackupComponent[];
+      dryRun?: boolean;
+    }) =>
+      apiCall<BackupRestore>('restore_backup', {
+        path: params.path,
+        force: params.force,
+        components: params.components,
+        dryRun: params.dryRun,
+      }),
+    onSuccess: (result) => {
+      if (!result.dry_run) {
+        queryClient.invalidateQueries();
+      }
+    },
+  });
+}
+
+// Merge a backup, database file or data root from another machine without double counting;
+// dryRun only returns the merge report
+export function useMergeDataSource() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { path: string; dryRun?: boolean }) =>
+      apiCall<MergeReport>('merge_data_source', { path: params.path, dryRun: params.dryRun }),
+    onSuccess: (result) => {
+      if (!result.dry_run && (result.added > 0 || result.replaced > 0)) {
+        queryClient.invalidateQueries();
+      }
+    },
+  });
+}
+
+// Recorded timestamp shifts, newest first
+export function useTimestampShifts() {
+  return useQuery({
+    queryKey: ['timestamp-shifts'],
+    queryFn: () => apiCall<{ shifts: TimestampShift[] }>('list_timestamp_shifts', {}),
+  });
+}
+
+// Move messages logged with a wrong clock (offset like "-1d" or "+2h30m");
+// preview only returns what would move
+export function useShiftSessionTimestamps() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { filter: TimeShiftFilter; offset: string; preview?: boolean }) =>
+      apiCall<TimeShiftResult>('shift_session_timestamps', params),
+    onSuccess: (result) => {
+      if (!result.preview && result.messages > 0) {
+        queryClient.invalidateQueries();
+      }
+    },
+  });
+}
+
+export function useUndoTimestampShift() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { shiftId: number; preview?: boolean }) =>
+      apiCall<TimeShiftResult>('undo_timestamp_shift', params),
+    onSuccess: (result) => {
+      if (!result.preview) {
+        queryClient.invalidateQueries();
+      }
+    },
+  });
+}
+
+// Import a session archive (path picked with the open dialog by the caller)
+export function useImportSessionArchive() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { path: string; targetProject?: string | null }) =>
+      apiCall<SessionArchiveImport>('import_session_archive', {
+        path: params.path,
+        targetProject: params.targetProject,
+      }),
+    onSuccess: (result) => {
+      queryClient.invalidateQueries({ queryKey: ['projects'] });
+      if (result.messages > 0) {
+        queryClient.invalidateQueries({ queryKey: ['dashboard'] });
+        queryClient.invalidateQueries({ queryKey: ['sessions'] });
+      }
+    },
+  });
+}
+
+// User-defined derived metrics
+export function useDerivedMetrics() {
+  return useQuery({
+    queryKey: ['derived-metrics'],
+    queryFn: () => apiCall<DerivedMetricsResponse>('get_derived_metrics', {}),
+    staleTime: 60_000, // 1 minute
+  });
+}
+
+// Create/replace or delete a derived metric; dashboards are refetched with the new values
+export function useSetDerivedMetric() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { name: string; expression: string; description?: string | null }) =>
+      apiCall<{ metric: DerivedMetric }>('set_derived_metric', params),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['derived-metrics'] });
+      queryClient.invalidateQueries({ queryKey: ['available-metrics'] });
+      queryClient.invalidateQueries({ queryKey: ['metric-series'] });
+      queryClient.invalidateQueries({ queryKey: ['dashboard'] });
+    },
+  });
+}
+
+export function useDeleteDerivedMetric() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (name: string) => apiCall<{ deleted: string }>('delete_derived_metric', { name }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['derived-metrics'] });
+      queryClient.invalidateQueries({ queryKey: ['available-metrics'] });
+      queryClient.invalidateQueries({ queryKey: ['metric-series'] });
+      queryClient.invalidateQueries({ queryKey: ['dashboard'] });
+    },
+  });
+}
+
+// Metrics for metric pickers: registered (messages, tokens by kind, cost, tool calls, latency) and derived
+export function useAvailableMetrics() {
+  return useQuery({
+    queryKey: ['available-metrics'],
+    queryFn: () => apiCall<{ metrics: AvailableMetric[] }>('list_available_metrics', {}),
+    staleTime: 60_000, // 1 minute
+  });
+}
+
+// Values of the picked metrics per period; disabled until a metric is picked
+export function useMetricSeries(
+  from: string,
+  to: string,
+  metrics: string[],
+  granularity: Granularity = 'day',
+  projectId: string | null = null
+) {
+  return useQuery({
+    queryKey: ['metric-series', from, to, metrics, granularity, projectId],
+    queryFn: ({ signal }) =>
+      apiCall<MetricSeries>('get_metric_series', { from, to, metrics, granularity, projectId }, signal),
+    enabled: metrics.length > 0,
+    staleTime: 5 * 60_000, // 5 minutes
+  });
+}
+
+// Vacation/holiday periods excluded from averages and streaks
+export function useCalendarOverlay() {
+  return useQuery({
+    queryKey: ['calendar-overlay'],
+    queryFn: () => apiCall<{ periods: CalendarPeriod[] }>('get_calendar_overlay', {}),
+    staleTime: 60_000, // 1 minute
+  });
+}
+
+// Replace the overlay; dashboards are refetched with the new averages, streaks and shading
+export function useSetCalendarOverlay() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (periods: CalendarPeriod[]) =>
+      apiCall<{ periods: CalendarPeriod[] }>('set_calendar_overlay', { periods }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['calendar-overlay'] });
+      queryClient.invalidateQueries({ queryKey: ['dashboard'] });
+      queryClient.invalidateQueries({ queryKey: ['dashboards'] });
+    },
+  });
+}
+
+export function useWorkingHours() {
+  return useQuery({
+    queryKey: ['working-hours'],
+    queryFn: () => apiCall<WorkingHours>('get_working_hours', {}),
+    staleTime: 60_000, // 1 minute
+  });
+}
+
+export function useSetWorkingHours() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (hours: WorkingHours) => apiCall<WorkingHours>('set_working_hours', { ...hours }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['working-hours'] });
+      queryClient.invalidateQueries({ queryKey: ['out-of-hours-usage'] });
+    },
+  });
+}
+
+// Work vs personal split of a range, e.g. for expensing work-hours usage
+export function useOutOfHoursUsage(from: string, to: string, projectId: string | null = null) {
+  return useQuery({
+    queryKey: ['out-of-hours-usage', from, to, projectId],
+    queryFn: () => apiCall<OutOfHoursUsage>('get_out_of_hours_usage', { from, to, projectId }),
+    staleTime: 5 * 60_000, // 5 minutes
+  });
+}
+
+// Installed WASM metric plugins
+export function usePlugins() {
+  return useQuery({
+    queryKey: ['plugins'],
+    queryFn: () => apiCall<PluginList>('list_plugins', {}),
+    staleTime: 60_000, // 1 minute
+  });
+}
+
+// Install a WASM metric plugin; the dashboard is refetched to pick up its series
+export function useInstallPlugin() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (path: string) => apiCall<PluginInfo>('install_plugin', { path }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['plugins'] });
+      queryClient.invalidateQueries({ queryKey: ['dashboard'] });
+    },
+  });
+}
+
+// Update project mutation
+export function useUpdateProject() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: UpdateProjectParams) => {
+      return apiCall<UpdateProjectResponse>('update_project', {
+        projectId: params.projectId,
+        name: params.name,
+        description: params.description,
+        visible: params.visible,
+        tags: params.tags,
+        color: params.color,
+        icon: params.icon,
+      });
+    },
+    onSuccess: () => {
+      // Invalidate projects query to refresh the list
+      queryClient.invalidateQueries({ queryKey: ['projects'] });
+    },
+  });
+}
+
+// Fetch a description suggestion on demand; the caller applies it with useUpdateProject
+export function useSuggestProjectDescription() {
+  return useMutation({
+    mutationFn: (projectId: string) =>
+      apiCall<ProjectDescriptionSuggestion>('suggest_project_description', { projectId }),
+  });
+}
+
+export function useProjectTimeline(projectId: string | null, granularity: ProjectTimelineGranularity = 'auto') {
+  return useQuery({
+    queryKey: ['project-timeline', projectId, granularity],
+    queryFn: () => apiCall<ProjectTimeline>('get_project_timeline', { projectId, granularity }),
+    enabled: !!projectId,
+    staleTime: 60_000, // 1 minute
+  });
+}
+
+// Move first_seen of all projects back to their first session
+export function useBackfillProjectFirstSeen() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => apiCall<FirstSeenBackfill>('backfill_project_first_seen', {}),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['projects'] });
+      queryClient.invalidateQueries({ queryKey: ['project-timeline'] });
+    },
+  });
+}
+
+// Projects without activity for N days, with archive suggestions
+export function useStaleProjects(inactiveDays: number = 90) {
+  return useQuery({
+    queryKey: ['stale-projects', inactiveDays],
+    queryFn: () => apiCall<StaleProjectsResponse>('get_stale_projects', { inactiveDays }),
+    staleTime: 300_000, // 5 minutes
+  });
+}
+
+// Hide projects from the project selector (usage is kept)
+export function useArchiveProjects() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (projectIds: string[]) =>
+      apiCall<{ projects: Project[] }>('archive_projects', { projectIds }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['projects'] });
+      queryClient.invalidateQueries({ queryKey: ['stale-projects'] });
+    },
+  });
+}
+
+export function useDescriptionLlmSettings() {
+  return useQuery({
+    queryKey: ['description-llm-settings'],
+    queryFn: () => apiCall<DescriptionLlmSettings>('get_description_llm_settings', {}),
+    staleTime: Infinity,
+  });
+}
+
+export function useSetDescriptionLlm() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { enabled: boolean; model?: string }) =>
+      apiCall<DescriptionLlmSettings>('set_description_llm', params),
+    onSuccess: (settings) => {
+      queryClient.setQueryData(['description-llm-settings'], settings);
+    },
+  });
+}
+
+// Pin or unpin a project (pinned projects are listed first)
+export function useSetProjectPinned() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { projectId: string; pinned: boolean }) =>
+      apiCall<UpdateProjectResponse>('set_project_pinned', params),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['projects'] });
+    },
+  });
+}
+
+// Persist the manual project order
+export function useSetProjectOrder() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (projectIds: string[]) => apiCall<{ order: string[] }>('set_project_order', { projectIds }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['projects'] });
+    },
+  });
+}
+
+// Workspaces (groups of projects)
+export function useWorkspaces() {
+  return useQuery({
+    queryKey: ['workspaces'],
+    queryFn: () => apiCall<WorkspacesResponse>('get_workspaces', {}),
+    staleTime: 5 * 60_000, // 5 minutes
+  });
+}
+
+export function useCreateWorkspace() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { name: string; description?: string }) =>
+      apiCall<{ workspace: Workspace }>('create_workspace', params),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['workspaces'] });
+    },
+  });
+}
+
+export function useDeleteWorkspace() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (workspaceId: string) =>
+      apiCall<{ deleted: string; unassigned_projects: number }>('delete_workspace', { workspaceId }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['workspaces'] });
+      queryClient.invalidateQueries({ queryKey: ['projects'] });
+      queryClient.invalidateQueries({ queryKey: ['dashboard'] });
+    },
+  });
+}
+
+// Move a project into a workspace (workspaceId null removes it from its workspace)
+export function useAssignProjectToWorkspace() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { projectId: string; workspaceId: string | null }) =>
+      apiCall<WorkspaceAssignment>('assign_project_to_workspace', params),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['workspaces'] });
       queryClient.invalidateQueries({ queryKey: ['projects'] });
+      queryClient.invalidateQueries({ queryKey: ['dashboard'] });
     },
   });
 }
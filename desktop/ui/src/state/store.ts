@@ -64,6 +64,10 @@ interface AppState {
   settingsOpen: boolean;
   toggleSettings: () => void;
 
+  // Startup cache warming
+  warmCacheOnStartup: boolean;
+  setWarmCacheOnStartup: (enabled: boolean) => void;
+
   // Privacy settings
   visibleUsageAccounts: string[];
   toggleUsageAccount: (email: string) => void;
@@ -142,6 +146,10 @@ export const useAppStore = create<AppState>()(
       settingsOpen: false,
       toggleSettings: () => set((s) => ({ settingsOpen: !s.settingsOpen })),
 
+      // Startup cache warming
+      warmCacheOnStartup: true,
+      setWarmCacheOnStartup: (warmCacheOnStartup) => set({ warmCacheOnStartup }),
+
       // Privacy settings
       visibleUsageAccounts: [],
       toggleUsageAccount: (email) =>
@@ -170,6 +178,7 @@ export const useAppStore = create<AppState>()(
         dateTo: state.dateTo,
         selectedProjectId: state.selectedProjectId,
         visibleUsageAccounts: state.visibleUsageAccounts,
+        warmCacheOnStartup: state.warmCacheOnStartup,
       }),
     }
   )
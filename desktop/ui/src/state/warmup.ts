@@ -0,0 +1,84 @@
+/**
+ * Startup cache warming
+ *
+ * After the first dashboard load, prefetches the queries users most often open
+ * next (current month bundle, projects list, today's details) at idle priority,
+ * so switching to them renders from cache. Skipped when disabled in settings or
+ * when running on a low battery.
+ */
+import { useEffect, useRef } from 'react';
+import { useQueryClient } from '@tanstack/react-query';
+import type { QueryClient } from '@tanstack/react-query';
+import { formatDate } from '../lib/date';
+import { calculateGranularity } from '../lib/time';
+import { useAppStore } from './store';
+import { dashboardQueryOptions, dayDetailsQueryOptions, projectsQueryOptions } from './queries';
+
+// Skip warming when discharging below this battery level (0-1)
+const MIN_BATTERY_LEVEL = 0.2;
+
+// Fallback delay when requestIdleCallback is unavailable (WebKit)
+const IDLE_FALLBACK_MS = 1_500;
+
+interface BatteryManagerLike {
+  charging: boolean;
+  level: number;
+}
+
+async function batteryAllowsWarming(): Promise<boolean> {
+  const nav = navigator as Navigator & { getBattery?: () => Promise<BatteryManagerLike> };
+  if (!nav.getBattery) return true;
+  try {
+    const battery = await nav.getBattery();
+    return battery.charging || battery.level >= MIN_BATTERY_LEVEL;
+  } catch {
+    return true;
+  }
+}
+
+function whenIdle(callback: () => void): () => void {
+  if (typeof window.requestIdleCallback === 'function') {
+    const handle = window.requestIdleCallback(callback, { timeout: 10_000 });
+    return () => window.cancelIdleCallback(handle);
+  }
+  const handle = window.setTimeout(callback, IDLE_FALLBACK_MS);
+  return () => window.clearTimeout(handle);
+}
+
+async function warmQueries(queryClient: QueryClient, projectId: string | null) {
+  const now = new Date();
+  const today = formatDate(now);
+  const monthStart = `${today.slice(0, 7)}-01`;
+
+  // Sequential on purpose: one backend call at a time keeps warming low priority
+  await queryClient.prefetchQuery(
+    dashboardQueryOptions(monthStart, today, false, calculateGranularity(monthStart, today), projectId)
+  );
+  await queryClient.prefetchQuery(projectsQueryOptions);
+  await queryClient.prefetchQuery(dayDetailsQueryOptions(today, projectId));
+}
+
+// Warm likely queries once per app start, after `ready` (first paint) turns true
+export function useStartupWarmup(ready: boolean) {
+  const queryClient = useQueryClient();
+  const warmCacheOnStartup = useAppStore((s) => s.warmCacheOnStartup);
+  const selectedProjectId = useAppStore((s) => s.selectedProjectId);
+  const started = useRef(false);
+
+  useEffect(() => {
+    if (!ready || !warmCacheOnStartup || started.current) return;
+
+    return whenIdle(() => {
+      started.current = true;
+      batteryAllowsWarming().then((allowed) => {
+        if (!allowed) {
+          console.log('[Warmup] Skipped: low battery');
+          return;
+        }
+        warmQueries(queryClient, selectedProjectId).catch((error) =>
+          console.warn('[Warmup] Failed:', error)
+        );
+      });
+    });
+  }, [ready, warmCacheOnStartup, queryClient, selectedProjectId]);
+}
@@ -6,11 +6,44 @@
 
 export type Granularity = 'month' | 'week' | 'day' | 'hour';
 
+// Rejection value of every Tauri command (desktop/src-tauri/src/error.rs)
+export type CommandError = { message: string; request_id?: string } & (
+  | { kind: 'python_missing' }
+  | { kind: 'non_zero_exit'; code: number | null; stderr: string; exception: string | null }
+  | { kind: 'parse_error' }
+  | { kind: 'unexpected_shape'; subcommand: string; path: string }
+  | { kind: 'timeout'; seconds: number }
+  | { kind: 'cancelled' }
+  | { kind: 'busy' }
+  | { kind: 'read_only' }
+  | { kind: 'invalid_argument' }
+  | { kind: 'not_found' }
+  | { kind: 'unauthorized' }
+  | { kind: 'forbidden'; scope: ApiTokenScope }
+  | { kind: 'incompatible'; expected: number; found: number | null; package_version: string | null }
+  | { kind: 'internal' }
+);
+
 export interface DateRange {
   from: string;  // YYYY-MM-DD
   to: string;    // YYYY-MM-DD
 }
 
+// Preset resolved by resolve_range, e.g. "last 7 days" or "month to date"
+export interface ResolvedRange extends DateRange {
+  preset: string;  // canonical name, e.g. "last-7-days"
+  days: number;
+  today: string;
+  timezone: string;
+  week_start: string;
+}
+
+export interface RangeSettings {
+  timezone: string;    // "local", "UTC" or an offset such as "+02:00"
+  week_start: string;  // monday ... sunday
+  billing_anchor_day: number;  // day of the month the subscription renews (1 = calendar months)
+}
+
 export interface Totals {
   messages: number;
   sessions: number;
@@ -18,11 +51,55 @@ export interface Totals {
   input_tokens: number;
   output_tokens: number;
   cost: number;
+  api_equivalent_cost: number;      // API list-price equivalent of the usage
+  subscription_cost: number | null; // prorated plan prices (null: unknown or project filter)
+  subscription_savings: number | null;
   cache_read: number;
   cache_write: number;
   current_streak: number;
-  max_streak: number;
+  max_streak: number;          // calendar overlay days neither extend nor break streaks
   first_session_date: string | null;
+  daily_average: DailyAverage;
+}
+
+// Usage per elapsed day of the range, without calendar overlay days and their usage
+export interface DailyAverage {
+  days: number;
+  excluded_days: number;
+  messages: number;
+  tokens: number;
+  cost: number;
+}
+
+// Vacation/holiday period (inclusive local dates); shaded in timelines
+export interface CalendarPeriod {
+  from: string;  // YYYY-MM-DD
+  to: string;    // YYYY-MM-DD
+  kind: 'vacation' | 'holiday';
+  label: string | null;
+}
+
+// Weekdays and local time window counted as work time
+export interface WorkingHours {
+  days: number[];  // ISO weekdays, 1 = Monday ... 7 = Sunday
+  start: string;   // HH:MM
+  end: string;     // HH:MM, exclusive
+}
+
+export interface UsageShare {
+  messages: number;
+  tokens: number;
+  cost: number;
+}
+
+// Usage split into work and personal time; calendar overlay days are personal
+export interface OutOfHoursUsage {
+  range: { from: string; to: string };
+  working_hours: WorkingHours;
+  work: UsageShare & { sessions: number };
+  personal: UsageShare & { sessions: number };
+  work_share: number | null;  // percentage of cost in work time
+  days: { date: string; work: UsageShare; personal: UsageShare }[];
 }
 
 export interface TimelineDataPoint {
@@ -32,11 +109,45 @@ export interface TimelineDataPoint {
   input_tokens: number;
   output_tokens: number;
   cost: number;
+  derived?: DerivedValues;  // only present if derived metrics are defined
+}
+
+// User-defined formula, e.g. cost_per_message = cost / messages
+export interface DerivedMetric {
+  name: string;
+  expression: string;
+  description: string | null;
+}
+
+// Metric name -> value (null when the formula divides by zero)
+export type DerivedValues = Record<string, number | null>;
+
+export interface DerivedMetricsResponse {
+  variables: string[];  // registered metric names
+  functions: string[];
+  metrics: DerivedMetric[];
+}
+
+// Metric picker entry (list_available_metrics); registered metrics first, then derived ones
+export interface AvailableMetric {
+  name: string;
+  label: string;
+  unit: 'count' | 'tokens' | 'usd' | 'ms' | null;  // null for derived metrics
+  category: 'activity' | 'tokens' | 'cost' | 'tools' | 'latency' | 'derived';
+  description: string;  // formula for derived metrics
+}
+
+// Chosen metrics per period (get_metric_series)
+export interface MetricSeries {
+  granularity: Granularity;
+  metrics: string[];
+  data: ({ period: string } & Record<string, number | null>)[];
 }
 
 export interface Timeline {
   granularity: Granularity;
   data: TimelineDataPoint[];
+  overlay: CalendarPeriod[];  // periods in range, clipped to it
 }
 
 export interface ModelDistributionItem {
@@ -47,6 +158,7 @@ export interface ModelDistributionItem {
   output_tokens: number;
   messages: number;
   cost: number;
+  api_equivalent_cost: number;
   percent: number;
 }
 
@@ -82,6 +194,232 @@ export interface SessionSummary {
   }>;
 }
 
+export type SessionSort = 'cost' | 'tokens' | 'messages' | 'recent' | 'oldest';
+
+export interface SessionFilter {
+  from: string;
+  to: string;
+  projectId?: string | null;
+  model?: string | null;
+  search?: string | null;
+  sort?: SessionSort;
+}
+
+// One page of list_sessions (keyset pagination)
+export interface SessionPage extends StreamedResponse {
+  sessions: SessionSummary[];
+  next_cursor: string | null;
+  total_count: number;
+  page_size: number;
+  sort: SessionSort;
+}
+
+// Messages of the get_raw_session channel
+export type RawSessionEvent =
+  | { event: 'started'; data: { sessionId: string; files: string[]; totalBytes: number } }
+  | { event: 'lines'; data: { file: string; text: string } }
+  | { event: 'records'; data: { file: string; records: Record<string, unknown>[] } }
+  | { event: 'finished'; data: { bytes: number; lines: number; skippedLines: number } };
+
+export interface RawSessionSummary {
+  session_id: string;
+  format: 'jsonl' | 'json';
+  files: string[];
+  bytes: number;
+  lines: number;
+  skipped_lines: number;
+}
+
+export interface RawSessionExport {
+  path: string;
+  size: number;
+  sha256: string;
+  sessions: number;
+  files: number;
+  bytes: number;
+  redacted: boolean;
+  missing_files: string[];
+}
+
+// File written by save_export (or { cancelled: true } if the save dialog was cancelled)
+export type SavedExport = { path: string; size: number; sha256: string } | { cancelled: true };
+
+// PNG report written by export_png_report / export_for_social, or to a temporary file by render_png_report
+export interface PngReport {
+  path: string;
+  filename: string;
+  size: number;
+  width: number;
+  height: number;
+  scale: number;  // pixel density (1 = 1500x1400, 2 = @2x, 3 = @3x); 1 for social cards
+  sha256: string;
+  mime_type: 'image/png';
+  alt_text: string;          // one-sentence text alternative for an <img alt>
+  long_description: string;  // one line per report panel, for aria-describedby or a caption
+}
+
+export type PngReportExport = PngReport | { cancelled: true };
+
+export type SocialFormat = 'twitter' | 'linkedin';
+
+// Scale of the monitor the window is on, for choosing the @2x/@3x export variant
+export interface DisplayScale {
+  scale_factor: number;
+  suggested_scale: number;
+  scales: number[];
+}
+
+export type BackupComponent = 'settings' | 'projects' | 'cache';
+
+export type BackupResult =
+  | {
+      path: string;
+      size: number;
+      sha256: string;
+      files: number;
+      components: BackupComponent[];
+      key_id: string;
+      key_store: 'keychain' | 'file';
+    }
+  | { cancelled: true };
+
+export interface BackupVerification {
+  valid: boolean;
+  signature: 'valid' | 'invalid' | 'unknown_key' | 'missing';
+  files_checked: number;
+  errors: string[];
+  created_at: string | null;
+  app_version: string | null;
+  components: BackupComponent[];
+}
+
+export interface BackupFileChange {
+  name: string;
+  component: BackupComponent;
+  target: string;
+  status: 'added' | 'modified' | 'unchanged';
+  // JSON files: changed key paths (e.g. "timezone", "p1.name")
+  changes?: { path: string; change: 'added' | 'removed' | 'modified' }[];
+  changes_truncated?: boolean;
+  // Database: tables whose row counts differ
+  tables?: { table: string; current_rows: number | null; backup_rows: number | null }[];
+}
+
+export interface BackupRestore {
+  dry_run: boolean;
+  components: BackupComponent[];
+  files: BackupFileChange[];
+  restored: string[];
+  verification: BackupVerification;
+}
+
+// Overlapping session in a merge: messages already local vs. read from the source
+export interface MergeSessionOverlap {
+  session_id: string;
+  project_id: string;
+  local_messages: number;
+  incoming_messages: number;
+  added: number;
+  replaced: number;
+  kept: number;
+}
+
+export interface MergeReport {
+  source: string;
+  kind: 'backup' | 'database' | 'data_root';
+  files?: number;       // data roots
+  signature?: string;   // backups
+  dry_run: boolean;
+  incoming: number;
+  duplicates_in_source: number;
+  added: number;
+  identical: number;
+  replaced: number;
+  kept: number;
+  double_counts_prevented: number;
+  tokens_added: number;
+  cost_added: number;
+  date_range: { from: string; to: string } | null;
+  sessions: { new: number; overlapping: number; details: MergeSessionOverlap[] };
+  projects: string[];
+  created_projects?: string[];  // not set for a dry run
+}
+
+// Messages selected for a timestamp shift; dates are local dates as currently stored
+export interface TimeShiftFilter {
+  from?: string;  // YYYY-MM-DD
+  to?: string;    // YYYY-MM-DD
+  sessionIds?: string[];
+  projectId?: string;
+  configDir?: string;
+}
+
+export interface TimeShiftResult {
+  preview: boolean;
+  shift_id: number | null;  // null for previews
+  offset_seconds: number;
+  messages: number;
+  sessions: number;
+  before: { from: string; to: string } | null;
+  after: { from: string; to: string } | null;
+  // Local times of up to 10 moved messages
+  samples: { entry_hash: string; session_id: string; before: string; after: string }[];
+}
+
+export interface TimestampShift {
+  shift_id: number;
+  offset_seconds: number;
+  filter: { date_from?: string; date_to?: string; session_ids?: string[]; project_id?: string; config_dir?: string };
+  messages: number;
+  created_at: string;
+  undone_at: string | null;
+}
+
+export interface SessionArchiveImport {
+  project_id: string;
+  created_project: boolean;
+  sessions: number;
+  files: number;
+  messages: number;
+  duplicates: number;
+  redacted: boolean;
+}
+
+// Range for get_dashboard_bundles; granularity/projectId override the command defaults
+export interface DashboardRangeRequest {
+  from: string;  // YYYY-MM-DD
+  to: string;    // YYYY-MM-DD
+  granularity?: Granularity;
+  projectId?: string | null;
+}
+
+// WASM metric plugins (list_plugins / install_plugin)
+export interface MetricSeries {
+  name: string;
+  points: { x: string | number; y: number }[];
+}
+
+// Output of one plugin in DashboardBundle.plugins
+export type PluginMetricOutput = { series: MetricSeries[] } | { error: string };
+
+export interface PluginInfo {
+  name: string;
+  version: string | null;
+  description: string | null;
+  enabled: boolean;
+  path: string;
+  size_bytes: number;
+  error: string | null;  // why the module cannot run
+}
+
+export interface PluginList {
+  directory: string;
+  plugins: PluginInfo[];
+}
+
+// Sections that can be requested separately from get_dashboard_bundle
+export type DashboardSection = 'totals' | 'timeline' | 'models' | 'hourly' | 'sessions';
+
 export interface DashboardBundle {
   range: DateRange;
   totals: Totals;
@@ -92,25 +430,38 @@ export interface DashboardBundle {
     cost: number;
   };
   daily_activity: Record<string, number>;
+  daily_derived?: Record<string, DerivedValues>;
+  derived_metrics?: DerivedMetric[];
   timeline: Timeline;
   model_distribution: ModelDistributionItem[];
   hourly_profile: HourlyDataPoint[];
   recent_sessions: SessionSummary[];
+  usage_accounts?: UsageAccountTotals[];
   heatmap: {
     range: DateRange;
     daily_activity: Record<string, number>;
+    overlay: CalendarPeriod[];
   };
+  plugins?: Record<string, PluginMetricOutput>;  // only present if a plugin is enabled
+  etag?: string;  // content hash for ifNoneMatch revalidation
   meta?: {
     updated_files: number;
+    ingest?: IngestStats | null;
+    refresh_blocked?: string | null;  // why refresh was skipped (another writer holds the lock)
     generated_at: string;
     data_range?: {
       start: string | null;
       end: string | null;
     };
     app_version?: string;
+    sections?: DashboardSection[];  // sections that were computed
+    workspace_id?: string | null;  // workspace filter of the bundle
   };
 }
 
+// Bundle computed for a subset of sections; keys of other sections are absent
+export type PartialDashboardBundle = Partial<DashboardBundle> & Pick<DashboardBundle, 'range'>;
+
 // Drill-down types
 
 export interface DayDetails {
@@ -141,6 +492,25 @@ export interface DayDetails {
   sessions: SessionSummary[];
 }
 
+// Totals of one day as recorded by a recomputation
+export interface DayTotalsVersion {
+  recorded_at: string;
+  // ingest, reprice, merge, time-shift, time-shift-undo, demo
+  reason: string;
+  messages: number;
+  sessions: number;
+  tokens: number;
+  cost: number;
+  // Change to the previous version (to zero for the first)
+  delta: { messages: number; sessions: number; tokens: number; cost: number };
+}
+
+export interface AggregateHistory {
+  date: string;
+  current: { messages: number; sessions: number; tokens: number; cost: number } | null;
+  history: DayTotalsVersion[];
+}
+
 export interface ModelDetails {
   model: string;
   display_name: string;
@@ -154,6 +524,7 @@ export interface ModelDetails {
     cache_read: number;
     cache_write: number;
     cost: number;
+    api_equivalent_cost: number;
   };
   daily_activity: Record<string, {
     messages: number;
@@ -169,7 +540,7 @@ export interface ModelDetails {
   }>;
 }
 
-export interface SessionDetails {
+export interface SessionDetails extends StreamedResponse {
   session_id: string;
   model: string;
   display_name: string;
@@ -194,7 +565,10 @@ export interface SessionDetails {
     cache_read: number;
     cache_write: number;
     cost: number;
+    // Stable message identity across machines and re-ingestion
+    content_hash: string | null;
   }>;
+  compactions: SessionCompactions;
 }
 
 // Limit events
@@ -219,16 +593,701 @@ export interface UsageAccount {
   current_week_used_raw: string | null;
   current_week_resets_local: string | null;
   current_week_resets_raw: string | null;
+  label: string | null;
+  color: string | null;
+  plan_type: UsagePlanType | null;
+  plan_source: 'manual' | 'detected' | null;
+  // Only from native ingestion (snapshot fields are then null)
+  config_dirs?: string[];
+  session_files?: number;
+  messages?: number;
+  sessions?: number;
+  tokens?: number;
+  cost?: number;
+  first_activity?: string | null;
+  last_activity?: string | null;
+}
+
+export type UsagePlanType = 'pro' | 'max_5x' | 'max_20x' | 'team' | 'enterprise' | 'api';
+
+// Per-account snapshot summary for a date range
+export interface UsageAccountTotals {
+  email: string;
+  label: string | null;
+  color: string | null;
+  plan_type: UsagePlanType | null;
+  plan_source: 'manual' | 'detected' | null;
+  snapshots: number;
+  active_days: number;
+  peak_session_used_pct: number | null;
+  avg_session_used_pct: number | null;
+  peak_week_used_pct: number | null;
+  avg_week_used_pct: number | null;
+  session_near_limit: number;
+  usage?: {
+    messages: number;
+    sessions: number;
+    tokens: number;
+    cost: number;
+    config_dirs: string[];
+  } | null;
+}
+
+// get_combined_dashboard: accounts queried concurrently and merged
+export interface AccountUsageTotals {
+  messages: number;
+  sessions: number;
+  tokens: number;
+  cost: number;
+}
+
+export interface AccountDashboard {
+  account: UsageAccountTotals;
+  config_dirs: string[];
+  totals: AccountUsageTotals;
+  daily: Array<{ date: string; messages: number; tokens: number; cost: number }>;
+}
+
+export interface CombinedAccountMetric {
+  key: string;
+  label: string;
+  values: Record<string, number | null>;
+  max_email: string | null;
+}
+
+export interface CombinedDashboard {
+  range: { from: string; to: string };
+  accounts: AccountDashboard[];
+  totals: AccountUsageTotals & { accounts: number; cost_share_pct: Record<string, number> };
+  daily: Array<{ date: string; messages: number; tokens: number; cost: number; by_account: Record<string, number> }>;
+  metrics: CombinedAccountMetric[];
 }
 
 export interface UsageAccountsResponse {
   accounts: UsageAccount[];
+  source?: 'native';  // Read from the session logs without Python
+}
+
+// Timing of a full refresh (dashboard meta.ingest and refresh output)
+export interface IngestStats {
+  files: number;
+  entries: number;
+  mirror_hits: number;
+  quarantined_lines: number;
+  parse_seconds: number;
+  write_seconds: number;
+  total_seconds: number;
+  files_per_second: number | null;
+}
+
+// Refresh freshness and progress (get_refresh_status)
+export interface RefreshRun {
+  mode: 'full' | 'partial';
+  started_at: string | null;
+}
+
+export interface RefreshRootStatus {
+  path: string;
+  files: number;
+  pending_files: number;
+  tracked_files: number;
+  entries: number;
+  last_scanned_at: string | null;  // UTC
+  newest_file_at: string | null;
+}
+
+export interface RefreshStatus {
+  state: 'idle' | 'running' | 'failed' | 'stale';
+  current: (RefreshRun & { pid: number; files_done: number; files_total: number | null }) | null;
+  last_success: (RefreshRun & { finished_at: string; updated_files: number }) | null;
+  last_error: { mode: 'full' | 'partial'; failed_at: string; error: string } | null;
+  data_current_as_of: string | null;
+  pending_files: number;
+  quarantined: { lines: number; files: number };
+  roots: RefreshRootStatus[];
+  lock: IngestLockHolder | null;
+}
+
+export interface IngestLockHolder {
+  token?: string;
+  hostname?: string;
+  pid?: number;
+  acquired_at?: number;   // unix seconds
+  heartbeat_at?: number;  // unix seconds
+  heartbeat_age_seconds: number | null;
+  stale: boolean;
+}
+
+// Schema versions (get_schema_info)
+export interface StoreSchema {
+  name: string;
+  path: string;
+  version: number | null;
+  latest: number;
+  status: 'missing' | 'current' | 'outdated' | 'migrated' | 'newer' | 'invalid';
+  backup: string | null;
+  applied: string[];
+  error: string | null;
+}
+
+export interface SchemaInfo {
+  app_version: string;
+  stores: StoreSchema[];
+  database: { path: string; version: number; latest: number; app_version: string } | { error: string };
+  native_store: { path: string; version: number | null; latest: number } | { error: string };
+}
+
+// Desktop's own SQLite store (index_native_store / get_native_store_status)
+export interface NativeIndexStats {
+  files: number;
+  changed_files: number;
+  reindexed_files: number;
+  new_messages: number;
+  duplicate_messages: number;
+  skipped_lines: number;
+  bytes_read: number;
+  workers: number;  // threads that read the logs
+  write_seconds: number;
+  seconds: number;
+}
+
+// Payload of the 'usage-updated' event
+export interface UsageUpdate extends NativeIndexStats {
+  files: string[];
+}
+
+// Indexer of the native store (get_index_status, 'index-progress' event)
+export interface IndexStatus {
+  state: 'idle' | 'indexing';
+  reason: 'startup' | 'watcher' | 'manual' | null;
+  files_done: number;
+  files_total: number;
+  bytes_read: number;
+  percent: number;
+  started_at: string | null;
+  finished_at: string | null;
+  last_stats: NativeIndexStats | null;
+  last_error: string | null;
+}
+
+export interface NativeStoreStatus {
+  path: string;
+  version: number;
+  latest: number;
+  files: number;
+  messages: number;
+  sessions: number;
+  models: number;
+  first_date: string | null;
+  last_date: string | null;
+  last_indexed_at: string | null;
+  size_bytes: number;
+}
+
+// Version handshake with the Python API (get_backend_version)
+export interface BackendVersion {
+  app_version: string;
+  expected_api_version: number;
+  backend: {
+    api_version: number;
+    package_version: string;
+    schema_version: number;  // latest database schema of the package
+    python_version: string;
+  } | null;  // null: the package predates the handshake
+  compatible: boolean;
+  error: CommandError | null;  // kind 'incompatible' when not compatible
+}
+
+// Backend report of the `diagnostics` subcommand
+export interface BackendDiagnostics {
+  app_version: string;
+  python: { executable: string; version: string };
+  module_path: string;
+  data_dir: string;
+  db: { path: string; exists: boolean; size: number | null; wal_size: number | null };
+}
+
+// Troubleshooting report of diagnose_environment; backends in the order the bridge tries them
+export interface EnvironmentDiagnosis {
+  ok: boolean;
+  active_backend: string | null;
+  diagnostics: BackendDiagnostics | null;
+  backends: {
+    backend: string;  // "sidecar", "embedded python", "virtualenv <path>", "python", "python3", "uv run python"
+    kind: 'sidecar' | 'embedded' | 'virtualenv' | 'system';
+    path: string | null;
+    found: boolean;
+    interpreter_version: string | null;
+    importable: boolean;
+    diagnostics: BackendDiagnostics | null;
+    error: CommandError | string | null;
+  }[];
+  bundled: { path: string | null; enabled: boolean };
+  worker_enabled: boolean;
+  path: string[];
+}
+
+// Machine numbers of run_benchmark, for performance reports
+export interface BenchmarkReport {
+  machine: {
+    app_version: string;
+    platform: string;
+    machine: string;
+    cpu_count: number | null;
+    python_version: string;
+    sqlite_version: string;
+  };
+  results: {
+    messages: number;
+    files: number;
+    generate_seconds: number;
+    ingest_seconds: number;
+    messages_per_second: number | null;
+    aggregate_seconds: number;
+    cached_refresh_seconds: number;
+    db_bytes: number;
+  }[];
+  total_seconds: number;
+  desktop: { version: string; os: string; arch: string };
+}
+
+// Opt-in anonymous telemetry (get_telemetry_settings / set_telemetry)
+export type SessionOutcome = 'commit' | 'test_pass' | 'tool_error' | 'user_interrupt' | 'other';
+
+export interface OutcomeStats {
+  range: { from: string; to: string };
+  sessions: number;
+  classified: number;
+  outcomes: { outcome: SessionOutcome; sessions: number; share: number | null }[];
+  // Sessions with a successful git commit
+  shipped: { sessions: number; share: number | null };
+  by_project: {
+    project_id: string;
+    sessions: number;
+    shipped: number;
+    shipped_share: number | null;
+    outcomes: Record<SessionOutcome, number>;
+  }[];
+  classification: { classified: number; unchanged: number; missing_files: number } | null;
+}
+
+export interface McpServerUsage {
+  server: string;
+  calls: number;
+  failures: number;
+  // Percentage of calls with a logged result; null if none has one yet
+  failure_rate: number | null;
+  pending: number;
+  sessions: number;
+  message_tokens: number;
+  // Estimated from the tool result text
+  result_tokens: number;
+  tools: { tool: string; calls: number; failures: number; result_tokens: number }[];
+}
+
+export interface McpUsage {
+  range: { from: string; to: string };
+  totals: {
+    calls: number;
+    failures: number;
+    failure_rate: number | null;
+    sessions: number;
+    message_tokens: number;
+    result_tokens: number;
+  };
+  servers: McpServerUsage[];
+}
+
+export interface HookUsage {
+  hook_event: string;
+  command: string;
+  // Hook names seen for this hook, e.g. "PreToolUse:Bash"
+  matchers: string[];
+  executions: number;
+  failures: number;
+  blocked: number;
+  cancelled: number;
+  failure_rate: number | null;
+  timed_executions: number;
+  avg_duration_ms: number | null;
+  p95_duration_ms: number | null;
+  max_duration_ms: number | null;
+  total_duration_ms: number;
+  sessions: number;
+}
+
+export interface HookStats {
+  range: { from: string; to: string };
+  totals: {
+    executions: number;
+    failures: number;
+    blocked: number;
+    failure_rate: number | null;
+    total_duration_ms: number;
+  };
+  by_event: { hook_event: string; executions: number; failures: number; blocked: number; total_duration_ms: number }[];
+  hooks: HookUsage[];
+}
+
+export interface EffortSetting {
+  // thinkingMetadata level ("none", "medium", "high", ...) or "unknown"
+  effort: string;
+  requests: number;
+  thinking_requests: number;
+  thinking_share: number;
+  output_tokens: number;
+  total_tokens: number;
+  cost: number;
+  cost_share: number;
+  avg_cost: number;
+  avg_output_tokens: number;
+  avg_latency_ms: number | null;
+  p50_latency_ms: number | null;
+  p95_latency_ms: number | null;
+}
+
+export interface EffortBreakdown {
+  model: string | null;
+  range: { from: string; to: string };
+  settings: EffortSetting[];
+}
+
+export interface ConcurrencyPeriod {
+  start: string;
+  end: string;
+  minutes: number;
+  max_concurrency: number;
+  sessions: string[];
+  projects: string[];
+  tokens: number;
+  cost: number;
+}
+
+export interface ConcurrencyStats {
+  range: { from: string; to: string };
+  idle_gap_minutes: number;
+  max_concurrency: number;
+  peak: { start: string; end: string; sessions: string[] } | null;
+  levels: {
+    concurrency: number;
+    minutes: number;
+    messages: number;
+    tokens: number;
+    cost: number;
+    token_share: number | null;
+  }[];
+  concurrent: { minutes: number; tokens: number; cost: number; token_share: number | null };
+  daily: { date: string; max_concurrency: number; concurrent_minutes: number; concurrent_tokens: number }[];
+  periods: ConcurrencyPeriod[];
+  period_count: number;
+  limit_windows: {
+    occurred_at: string;
+    tokens: number;
+    concurrent_tokens: number;
+    concurrent_share: number | null;
+    max_concurrency: number;
+  }[];
+}
+
+export type CompactionTrigger = 'auto' | 'manual' | 'unknown';
+
+export interface SessionCompactions {
+  count: number;
+  auto: number;
+  manual: number;
+  tokens_freed: number;
+  events: {
+    timestamp_local: string;
+    trigger: CompactionTrigger;
+    pre_tokens: number | null;
+    post_tokens: number | null;
+    tokens_freed: number | null;
+  }[];
+}
+
+export interface CompactionStats {
+  range: { from: string; to: string };
+  sessions: number;
+  sessions_with_compactions: number;
+  compactions: number;
+  by_trigger: Record<CompactionTrigger, number>;
+  per_session: number | null;
+  per_compacted_session: number | null;
+  avg_pre_tokens: number | null;
+  avg_post_tokens: number | null;
+  tokens_freed: number;
+  avg_tokens_freed: number | null;
+  daily: ({ date: string } & Record<CompactionTrigger, number>)[];
+  top_sessions: { session_id: string; project_id: string; compactions: number; first_time: string }[];
+  by_project: { project_id: string; sessions: number; compactions: number; per_session: number }[];
+  scan: { scanned: number; unchanged: number; missing_files: number } | null;
+}
+
+export interface PresentationMode {
+  read_only: boolean;
+  blur_project_names: boolean;
+  demo_data: boolean;
+}
+
+export interface PrivacyMode {
+  enabled: boolean;
+}
+
+export interface DemoDataResult {
+  seed: number;
+  months: number;
+  date_from: string;
+  date_to: string;
+  projects: number;
+  sessions: number;
+  messages: number;
+  total_cost_usd: number;
+}
+
+export type SampleIntensity = 'light' | 'regular' | 'heavy';
+
+export interface SampleDataResult {
+  seed: number;
+  days: number;
+  intensity: SampleIntensity;
+  data_root: string;
+  date_from: string;
+  date_to: string;
+  projects: number;
+  sessions: number;
+  messages: number;
+  ingested_files: number;
+}
+
+export interface TelemetrySettings {
+  enabled: boolean;
+  opted_in: boolean;
+  kill_switch: boolean;
+  endpoint: string | null;
+}
+
+export interface CommandPerformance {
+  samples: number;
+  p50_ms: number | null;
+  p90_ms: number | null;
+  p99_ms: number | null;
+}
+
+export interface TelemetryPreview {
+  would_send: boolean;
+  endpoint: string | null;
+  payload: {
+    schema: number;
+    app_version: string;
+    os: string;
+    arch: string;
+    session_minutes: number;
+    features: Record<string, number>;
+    errors: Record<string, number>;
+    performance: Record<string, CommandPerformance>;
+  };
+}
+
+// In-app changelog (get_changelog)
+export interface ChangelogRelease {
+  version: string;
+  date: string | null;
+  sections: { title: string; items: string[] }[];
+}
+
+export interface Changelog {
+  current_version: string;
+  releases: ChangelogRelease[];
+}
+
+// Feature flags of experimental subsystems (get_feature_flags)
+export interface FeatureFlag {
+  name: string;
+  description: string;
+  enabled: boolean;
+  default: boolean;
+  source: 'local' | 'remote' | 'default';
+}
+
+export interface FeatureFlags {
+  flags: FeatureFlag[];
+  remote_url: string | null;
+  remote_fetched_at: string | null;
+}
+
+// API tokens of the local HTTP server (list_api_tokens)
+export type ApiTokenScope = 'read' | 'export' | 'admin';
+
+export interface ApiToken {
+  id: string;
+  name: string | null;
+  scopes: ApiTokenScope[];
+  prefix: string;  // first characters of the secret
+  created_at: string;
+  expires_at: string | null;
+  revoked_at: string | null;
+  last_used_at: string | null;
+  status: 'active' | 'expired' | 'revoked';
+}
+
+export interface ApiTokens {
+  tokens: ApiToken[];
+  scopes: ApiTokenScope[];
+}
+
+// create_api_token: the secret is only returned here
+export interface CreatedApiToken extends ApiToken {
+  token: string;
+}
+
+// Listener of the local HTTP server (get_http_server_settings); changes apply on restart
+export interface HttpServerSettings {
+  enabled: boolean;  // http_server feature flag
+  port: number;
+  bind_address: string | null;  // null: 127.0.0.1
+  allow_remote: boolean;
+  tls_cert: string | null;  // PEM paths; null: plain HTTP
+  tls_key: string | null;
+  tls_client_ca: string | null;  // set: client certificates required (mTLS)
+  url: string | null;
+  mutual_tls: boolean;
+  error: string | null;  // why the server would not start with these settings
+}
+
+// Malformed JSONL lines skipped during ingestion (get_quarantined_lines)
+export interface QuarantinedLine {
+  source_file: string;
+  line_number: number;
+  error: string;
+  content: string;
+  truncated: boolean;
+  quarantined_at: string;
+}
+
+export interface QuarantinedLines {
+  total: number;
+  files: number;
+  lines: QuarantinedLine[];
+}
+
+// 'refresh-progress' event, emitted while a dashboard refresh or partial refresh reindexes logs
+export interface RefreshProgress {
+  request_id: string;
+  mode: string;       // full, partial
+  state: 'running' | 'done' | 'failed';
+  files_done: number;
+  files_total: number | null;  // null until the changed files are known
+  percent: number;
+}
+
+// 'result-chunk' event: part of the list of a streamed call (list_sessions, get_session_details with stream)
+export interface ResultChunk {
+  request_id: string;
+  key: string;        // sessions, messages
+  seq: number;        // restarts at 0 when the call is retried
+  items: unknown[];
+}
+
+// 'result-done' event, after the last chunk of a streamed call
+export interface ResultDone {
+  request_id: string;
+  ok: boolean;
+  chunks: number;
+  error: CommandError | null;
+}
+
+// Summary in the result of a streamed call; its list (`key`) only holds items not sent as chunks
+export interface StreamedResponse {
+  streamed?: { key: string; chunks: number; items: number };
+}
+
+// 'backend-log' event: a Python stderr line, emitted while set_backend_log_events is on
+export interface BackendLogLine {
+  request_id: string;  // "worker" for worker output outside a request
+  backend: string;
+  level: 'error' | 'warn' | 'info' | 'debug';
+  message: string;
+}
+
+// Partial refresh result (refresh_today / refresh_since), also emitted as 'refresh-delta' event
+export interface RefreshDelta {
+  mode: 'partial';
+  since: string;
+  updated_files: number;
+  affected_dates: string[];
+  quarantined_lines: number;
+  delta: {
+    messages: number;
+    tokens: number;
+    cost: number;
+  };
+  refreshed_at: string;
+  request_id: string;
+}
+
+// Export queue (enqueue_export / list_exports / rerun_export), jobs are also emitted
+// as 'export-completed' and 'export-failed' events
+export type ExportKind = 'png_report' | 'cost_allocation' | 'raw_sessions';
+export type ExportStatus = 'queued' | 'running' | 'succeeded' | 'failed';
+
+export interface ExportJob {
+  id: string;
+  kind: ExportKind;
+  params: Record<string, unknown>;
+  output_path: string;
+  status: ExportStatus;
+  request_id: string;
+  created_at: string;
+  started_at: string | null;
+  finished_at: string | null;
+  size: number | null;
+  sha256: string | null;
+  error: string | null;
+  rerun_of: string | null;
+  result: Record<string, unknown> | null;
+}
+
+// Scheduled exports (get_schedule_preview / list_export_schedules / set_export_schedule);
+// rule times are wall-clock times in the rule's timezone
+export interface ScheduleRule {
+  frequency: 'daily' | 'weekly' | 'monthly';
+  weekday?: string;   // weekly: "monday"
+  day?: number;       // monthly: 1-31, the last day in shorter months
+  time: string;       // "HH:MM"
+  timezone?: string;  // "local", "UTC" or "+02:00"; default: the timezone setting
+}
+
+export interface ScheduleFire {
+  at: string;     // UTC
+  local: string;  // with the rule timezone's offset
+  weekday: string;
+  // Set on DST switch days: fired later (time in a gap) or at the first of two times
+  adjustment: 'shifted_forward' | 'first_of_two' | null;
+}
+
+export interface SchedulePreview {
+  timezone: string;
+  fires: ScheduleFire[];
+}
+
+export interface ExportSchedule {
+  id: string;
+  name: string | null;
+  kind: Exclude<ExportKind, 'raw_sessions'>;
+  params: Record<string, unknown>;  // export params with a `range` preset instead of dates
+  rule: ScheduleRule;
+  enabled: boolean;
+  created_at: string;
+  last_fired_at: string | null;
+  last_job_id: string | null;
+  next_fire?: ScheduleFire | null;  // list_export_schedules only
 }
 
 // Error response
 export interface ApiError {
   error: string;
   type?: string;
+  request_id?: string;
 }
 
 // Project management
@@ -240,10 +1299,43 @@ export interface Project {
   first_seen: string;  // ISO timestamp
   last_seen: string;   // ISO timestamp
   visible: boolean;
+  tags?: string[];
+  imported?: boolean;  // created by import_session_archive; excluded from overall totals
+  workspace_id?: string;
+  pinned?: boolean;
+  sort_order?: number;       // position in the manual order
+  cost_this_month?: number;  // only with sort 'cost_this_month'
+  color?: string;            // custom "#rrggbb"
+  icon?: string;             // emoji or short icon name
+  display_color?: string;    // color, or a stable palette color
 }
 
+// Order of get_projects; pinned projects always come first
+export type ProjectSort = 'manual' | 'last_activity' | 'cost_this_month' | 'name';
+
 export interface ProjectsResponse {
   projects: Project[];
+  sort?: ProjectSort;
+  source?: 'native';  // Read from the session logs without Python
+}
+
+// Workspace: optional group of projects; dashboards accept a workspaceId filter
+export interface Workspace {
+  workspace_id: string;
+  name: string;
+  description: string;
+  created_at: string;  // ISO timestamp
+  projects: string[];  // project IDs
+}
+
+export interface WorkspacesResponse {
+  workspaces: Workspace[];
+}
+
+export interface WorkspaceAssignment {
+  project_id: string;
+  workspace_id: string | null;
+  previous_workspace_id: string | null;
 }
 
 export interface UpdateProjectParams {
@@ -251,8 +1343,122 @@ export interface UpdateProjectParams {
   name?: string;
   description?: string;
   visible?: boolean;
+  tags?: string[];
+  color?: string;  // "#rrggbb"; empty string clears it
+  icon?: string;   // empty string clears it
 }
 
 export interface UpdateProjectResponse {
   project: Project;
 }
+
+// Candidate description from recent sessions (suggest_project_description); not applied
+export interface ProjectDescriptionSuggestion {
+  project_id: string;
+  suggestion: string | null;
+  source: 'local' | 'llm';
+  current_description: string;
+  keywords: string[];
+  languages: string[];
+  files: string[];
+  sessions_analyzed: number;
+  llm_error?: string;
+}
+
+// Lifetime timeline of a project (get_project_timeline), for the project profile
+export type ProjectTimelineGranularity = 'auto' | 'day' | 'week' | 'month';
+
+export interface ProjectTimelinePoint {
+  period: string;  // YYYY-MM-DD, YYYY-Www or YYYY-MM
+  messages: number;
+  sessions: number;  // sessions started in the period
+  tokens: number;
+  cost: number;
+  cumulative_cost: number;
+}
+
+export type ProjectMilestone =
+  | { type: 'first_session'; date: string; session_id: string; models: string[] }
+  | { type: 'new_model'; date: string; model: string }
+  | { type: 'cost_milestone'; date: string; threshold: number }
+  | { type: 'biggest_day'; date: string; tokens: number; cost: number }
+  | { type: 'last_session'; date: string; session_id: string };
+
+export interface ProjectTimeline {
+  project: Project;
+  project_id: string;
+  granularity: Exclude<ProjectTimelineGranularity, 'auto'>;
+  first_session: { session_id: string; timestamp: string } | null;
+  last_session: { session_id: string; timestamp: string } | null;
+  totals: {
+    messages: number;
+    sessions: number;
+    tokens: number;
+    cost: number;
+    active_days: number;
+    models: string[];
+  };
+  curve: ProjectTimelinePoint[];
+  milestones: ProjectMilestone[];
+}
+
+export interface FirstSeenBackfill {
+  updated: { project_id: string; previous: string | null; first_seen: string }[];
+  checked: number;
+}
+
+// Inactive projects with their storage footprint (get_stale_projects)
+export interface StaleProject {
+  project_id: string;
+  name: string;
+  visible: boolean;
+  pinned: boolean;
+  last_activity: string | null;
+  days_inactive: number;
+  messages: number;
+  sessions: number;
+  cost: number;
+  source_files: number;
+  source_bytes: number;
+  mirror_bytes: number;
+  suggestion: 'archive' | null;
+  reason: string;
+}
+
+export interface StaleProjectsResponse {
+  inactive_days: number;
+  projects: StaleProject[];
+  totals: {
+    projects: number;
+    suggested: number;
+    source_bytes: number;
+    mirror_bytes: number;
+  };
+}
+
+// LLM phrasing of description suggestions (get_description_llm_settings / set_description_llm)
+export interface ReportTemplate {
+  name: string;
+  path: string;
+  size_bytes: number;
+  modified: number | null;  // seconds since the Unix epoch
+  error: string | null;     // syntax error or size limit; the template cannot be rendered
+}
+
+export type RenderedReport =
+  | {
+      template: string;
+      filename: string;
+      html: string;
+      // Set when the report was saved
+      path?: string;
+      size?: number;
+      sha256?: string;
+    }
+  | { cancelled: true };
+
+export interface DescriptionLlmSettings {
+  enabled: boolean;
+  model: string | null;
+  api_key_set: boolean;
+}
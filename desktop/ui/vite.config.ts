@@ -22,7 +22,13 @@ function pythonApiPlugin() {
           const refreshParam = params.get('refresh');
           const refresh = refreshParam === 'true' || refreshParam === '1' ? '1' : '0';
           const granularity = params.get('granularity') || 'month';
+          const sections = params.get('sections');
           command = `uv run python -m command_center.tauri_api dashboard --from ${from} --to ${to} --refresh ${refresh} --granularity ${granularity}`;
+          if (sections && /^[a-z,]+$/.test(sections)) {
+            command += ` --sections ${sections}`;
+          }
+        } else if (path === '/refresh-status') {
+          command = 'uv run python -m command_center.tauri_api refresh-status';
         } else if (path === '/usage-accounts') {
           command = 'uv run python -m command_center.tauri_api usage-accounts';
         } else if (path === '/day') {
@@ -0,0 +1,10 @@
+"""
+Entry point of the frozen backend sidecar bundled with the desktop app.
+
+The binary takes the same arguments as ``python -m command_center.tauri_api``
+(including ``serve`` for the persistent worker); see build_sidecar.sh.
+"""
+from command_center.tauri_api import main
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,29 @@
+#!/bin/bash
+# Build the frozen Python backend as a Tauri sidecar.
+#
+# Produces desktop/src-tauri/binaries/command-center-backend-<target triple>[.exe],
+# the name Tauri expects for bundle.externalBin. Bundle it with:
+#   cd desktop/src-tauri && cargo tauri build --config tauri.sidecar.conf.json
+#
+# Usage: packaging/sidecar/build_sidecar.sh [target-triple]   (default: rustc host)
+set -euo pipefail
+
+ROOT="$(cd "$(dirname "$0")/../.." && pwd)"
+TRIPLE="${1:-$(rustc -vV | sed -n 's/^host: //p')}"
+OUT_DIR="$ROOT/desktop/src-tauri/binaries"
+NAME="command-center-backend-$TRIPLE"
+
+cd "$ROOT"
+# tauri_api imports most modules lazily, so collect the whole package
+uv run --with pyinstaller pyinstaller \
+  --onefile \
+  --name "$NAME" \
+  --paths src \
+  --collect-submodules command_center \
+  --distpath "$OUT_DIR" \
+  --workpath "$ROOT/build/sidecar" \
+  --specpath "$ROOT/build/sidecar" \
+  --noconfirm \
+  packaging/sidecar/backend_entry.py
+
+echo "Built $OUT_DIR/$NAME"
@@ -0,0 +1,145 @@
+#!/usr/bin/env python3
+"""
+Record the backend fixtures of the desktop command harness.
+
+Runs every subcommand the harness (desktop/src-tauri/src/harness.rs) answers
+from fixtures against a throwaway demo home filled with seeded demo data
+ending on a fixed date, and writes each response to
+desktop/src-tauri/fixtures/harness/<subcommand>.json. Re-record after
+changing a response shape in the Python API, then update the harness
+golden files (UPDATE_GOLDEN=1 cargo test harness).
+
+Usage: python scripts/record_harness_fixtures.py
+"""
+import json
+import os
+import re
+import subprocess
+import sys
+import tempfile
+from datetime import date, datetime, timezone
+from pathlib import Path
+
+REPO = Path(__file__).resolve().parent.parent
+FIXTURE_DIR = REPO / "desktop" / "src-tauri" / "fixtures" / "harness"
+
+END_DATE = date(2026, 3, 31)
+MONTHS = 3
+PROJECT = "-home-demo-projects-aurora-web"
+# Stands in for timestamps of the recording run
+RECORDED_AT = "2026-04-01T00:00:00+00:00"
+RANGE = ["--from", "2026-03-01", "--to", "2026-03-31"]
+
+# Subcommand arguments, in the order they run (writes set up later reads)
+CALLS = [
+    ["refresh-status"],
+    ["quarantined-lines"],
+    ["aggregate-history", "--date", "2026-03-16"],
+    ["day", "--date", "2026-03-16"],
+    ["model", "--model", "claude-sonnet-4-5", *RANGE],
+    ["limits", *RANGE],
+    ["limit-impact", *RANGE],
+    ["mcp-usage", *RANGE],
+    ["hook-stats", *RANGE],
+    ["effort-breakdown", "--model", "claude-sonnet-4-5", *RANGE],
+    ["concurrency-stats", *RANGE],
+    ["outcome-stats", *RANGE],
+    ["compaction-stats", *RANGE],
+    ["set-budget", "--amount", "200", "--effective-from", "2026-03"],
+    ["budget-history"],
+    ["set-hook", "--event", "refresh-completed", "--command", "echo refreshed", "--id", "on-refresh"],
+    ["hooks"],
+    ["test-hook", "--id", "on-refresh"],
+    ["delete-hook", "--id", "on-refresh"],
+    ["available-metrics"],
+    ["metric-series", *RANGE, "--metrics", "cost,messages", "--granularity", "week"],
+    ["set-derived-metric", "--name", "cost_per_message", "--expression", "cost / messages"],
+    ["derived-metrics"],
+    ["delete-derived-metric", "--name", "cost_per_message"],
+    ["set-calendar-overlay", "--periods", '[{"from": "2026-03-09", "to": "2026-03-13", "kind": "vacation"}]'],
+    ["calendar-overlay"],
+    ["set-working-hours", "--days", "1,2,3,4,5", "--start", "09:00", "--end", "17:00"],
+    ["working-hours"],
+    ["out-of-hours-usage", *RANGE],
+    ["set-cost-center", "--code", "ENG", "--name", "Engineering", f"--projects={PROJECT}"],
+    ["cost-centers"],
+    ["cost-allocation", "--month", "2026-03"],
+    ["delete-cost-center", "--code", "ENG"],
+    ["price-versions"],
+    ["set-price", "--model", "claude-sonnet-4-5", "--effective-from", "2026-03-15", "--input", "3", "--output", "15"],
+    ["reprice", *RANGE, "--dry-run", "1"],
+    ["create-snapshot", "--name", "march", *RANGE],
+    ["snapshots"],
+    ["snapshot", "--snapshot", "march"],
+    ["diff-snapshots", "--base", "march"],
+    ["delete-snapshot", "--snapshot", "march"],
+    ["projects"],
+    ["set-project-pinned", f"--project-id={PROJECT}", "--pinned", "1"],
+    ["set-project-order", f"--project-ids={PROJECT}"],
+    ["usage-accounts"],
+    ["compare-accounts", *RANGE],
+    ["claude-accounts"],
+    ["detect-plan"],
+    ["update-project", f"--project-id={PROJECT}", "--color", "#3b82f6"],
+    ["suggest-description", f"--project-id={PROJECT}"],
+    ["project-timeline", f"--project-id={PROJECT}"],
+    ["backfill-first-seen"],
+    ["stale-projects", "--inactive-days", "14"],
+    ["create-workspace", "--name", "Client work"],
+    ["workspaces"],
+    ["mirror-status"],
+    ["clear-mirror"],
+    ["schema-info"],
+    ["version"],
+    ["shift-timestamps", "--offset", "+1h", "--from", "2026-03-16", "--to", "2026-03-16", "--preview", "1"],
+    ["timestamp-shifts"],
+    ["archive-projects", "--project-ids=-home-demo-projects-ledger-api"],
+]
+
+
+def main() -> int:
+    home = Path(tempfile.mkdtemp(prefix="cc-harness-"))
+    env = {
+        **os.environ,
+        "COMMAND_CENTER_DEMO": "1",
+        "COMMAND_CENTER_DEMO_HOME": str(home),
+        "PYTHONPATH": os.pathsep.join(filter(None, [str(REPO / "src"), os.environ.get("PYTHONPATH")])),
+    }
+    seed = (
+        "from datetime import date\n"
+        "from command_center.database.connection import get_db_connection\n"
+        "from command_center.database.schema import init_database\n"
+        "from command_center.demo_data import generate_demo_data\n"
+        "with get_db_connection() as conn:\n"
+        "    init_database(conn)\n"
+        f"    generate_demo_data(conn, months={MONTHS}, end_date=date.fromisoformat('{END_DATE}'))\n"
+    )
+    subprocess.run([sys.executable, "-c", seed], env=env, check=True)
+
+    FIXTURE_DIR.mkdir(parents=True, exist_ok=True)
+    today = datetime.now(timezone.utc).date().isoformat()
+    failed = []
+    for args in CALLS:
+        run = subprocess.run(
+            [sys.executable, "-m", "command_center.tauri_api", *args],
+            env=env, capture_output=True, text=True,
+        )
+        lines = run.stdout.strip().splitlines()
+        if run.returncode != 0 or not lines:
+            failed.append(f"{args[0]}: exit {run.returncode}: {run.stderr.strip()[-300:]}")
+            continue
+        # Paths of the throwaway home and times of the recording would change
+        # on every recording
+        text = lines[-1].replace(json.dumps(str(home))[1:-1], "~")
+        text = re.sub(rf"{today}T[0-9:.]+(\+00:00|Z)?", RECORDED_AT, text)
+        response = json.loads(text)
+        (FIXTURE_DIR / f"{args[0]}.json").write_text(json.dumps(response, indent=2, ensure_ascii=False) + "\n")
+        print(f"recorded {args[0]}")
+
+    for failure in failed:
+        print(f"FAILED {failure}", file=sys.stderr)
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
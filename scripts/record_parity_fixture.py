@@ -0,0 +1,80 @@
+#!/usr/bin/env python3
+"""
+Record the Python dashboard of the native pricing parity fixture.
+
+Copies desktop/src-tauri/fixtures/parity/home (session logs, a LiteLLM price
+table, account plans and bindings, cc_usage snapshots and derived metrics) to
+a throwaway home, builds the dashboard for the week of the logs through the
+Python API and writes it to desktop/src-tauri/fixtures/parity/dashboard.json.
+The native store indexes the same home in `test_dashboard_matches_python`
+(desktop/src-tauri/src/aggregate.rs) and must build the same bundle;
+re-record after changing the Python dashboard.
+
+Bindings name config dirs as "~/..." and cc_usage snapshots are SQL
+(`cc_usage.sql`); both are turned into what the backend reads in the copy.
+
+Usage: python scripts/record_parity_fixture.py
+"""
+import json
+import os
+import shutil
+import sqlite3
+import subprocess
+import sys
+import tempfile
+from pathlib import Path
+
+REPO = Path(__file__).resolve().parent.parent
+FIXTURE_DIR = REPO / "desktop" / "src-tauri" / "fixtures" / "parity"
+
+ARGS = ["dashboard", "--from", "2025-06-08", "--to", "2025-06-14", "--refresh", "1", "--granularity", "day"]
+# Stands in for the time of the recording run
+RECORDED_AT = "2025-06-15T00:00:00"
+
+
+def prepare_home(home: Path):
+    """Expand the "~/" of bound config dirs and build cc_usage.db from its SQL."""
+    db = home / ".claude" / "db"
+    bindings_path = db / "command-center-claude-configs.json"
+    bindings = json.loads(bindings_path.read_text())
+    bindings_path.write_text(json.dumps({
+        str(home / config_dir[2:]) if config_dir.startswith("~/") else config_dir: binding
+        for config_dir, binding in bindings.items()
+    }))
+    with sqlite3.connect(db / "cc_usage.db") as conn:
+        conn.executescript((db / "cc_usage.sql").read_text())
+
+
+def main() -> int:
+    home = Path(tempfile.mkdtemp(prefix="cc-parity-")) / "home"
+    shutil.copytree(FIXTURE_DIR / "home", home)
+    prepare_home(home.resolve())
+    env = {
+        **os.environ,
+        "HOME": str(home),
+        "TZ": "UTC",
+        "PYTHONPATH": os.pathsep.join(filter(None, [str(REPO / "src"), os.environ.get("PYTHONPATH")])),
+    }
+    # Snapshots come from the copied home only
+    env.pop("CC_USAGE_DB_PATH", None)
+    run = subprocess.run(
+        [sys.executable, "-m", "command_center.tauri_api", *ARGS],
+        env=env, capture_output=True, text=True,
+    )
+    lines = run.stdout.strip().splitlines()
+    shutil.rmtree(home.parent, ignore_errors=True)
+    if run.returncode != 0 or not lines:
+        print(f"FAILED: exit {run.returncode}: {run.stderr.strip()[-300:]}", file=sys.stderr)
+        return 1
+
+    bundle = json.loads(lines[-1])
+    # Times of the recording run would change on every recording
+    bundle["meta"]["generated_at"] = RECORDED_AT
+    bundle["meta"]["ingest"] = None
+    (FIXTURE_DIR / "dashboard.json").write_text(json.dumps(bundle, indent=2, ensure_ascii=False) + "\n")
+    print(f"recorded {FIXTURE_DIR / 'dashboard.json'}")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -3,6 +3,12 @@ Claude Code Wrapped - Modern SQLite-based analytics
 
 A modular Python application for analyzing Claude Code usage with intelligent caching.
 """
+import os
+
+# Demo mode: resolve every home-relative path inside the demo home, before any
+# module computes its paths (see demo_data.py)
+if os.environ.get("COMMAND_CENTER_DEMO") == "1" and os.environ.get("COMMAND_CENTER_DEMO_HOME"):
+    os.environ["HOME"] = os.environ["USERPROFILE"] = os.environ["COMMAND_CENTER_DEMO_HOME"]
 
 try:
     from importlib.metadata import version
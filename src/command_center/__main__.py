@@ -15,10 +15,14 @@ from command_center.database.connection import get_db_connection
 from command_center.database.schema import init_database, check_integrity
 from command_center.database.queries import query_usage_stats
 from command_center.cache.incremental_update import perform_incremental_update
+from command_center.cache.ingest_lock import IngestLockedError, ingest_lock
 from command_center.visualization.png_generator import generate_usage_report_png
+from command_center.visualization.report_description import describe_usage_report
 from command_center.visualization.terminal_display import display_png_in_terminal
 from command_center.utils.console_output import show_db_stats
+from command_center.utils.project_metadata import load_projects_json
 from command_center.utils.pricing import update_pricing_cache
+from command_center.utils.atomic_write import write_bytes_atomic
 from command_center.cli.project_commands import list_projects_command, update_project_command
 
 
@@ -204,16 +208,21 @@ def main():
             show_db_stats(conn)
             return
 
-        # Perform incremental update
-        updated_count = perform_incremental_update(
-            conn,
-            force_rescan=args.force_rescan,
-            verbose=args.verbose
-        )
-
-        if updated_count > 0:
+        # Perform incremental update (unless another process is ingesting)
+        try:
+            with ingest_lock():
+                updated_count = perform_incremental_update(
+                    conn,
+                    force_rescan=args.force_rescan,
+                    verbose=args.verbose
+                )
+        except IngestLockedError as e:
+            console.print(f"[yellow]Skipping update: {e}[/yellow]\n")
+            updated_count = None
+
+        if updated_count:
             console.print(f"[green]✓ Processed {updated_count} new/modified files[/green]\n")
-        else:
+        elif updated_count == 0:
             console.print("[green]✓ All files up to date (using cached data)[/green]\n")
 
         # Query stats for date range
@@ -226,15 +235,17 @@ def main():
 
         # Generate PNG
         with Live(Spinner("dots", text="[bold blue]Generating PNG image...[/bold blue]"), console=console, refresh_per_second=10):
-            png_bytes = generate_usage_report_png(stats)
+            projects_metadata = load_projects_json()
+            png_bytes = generate_usage_report_png(
+                stats, projects_metadata, description=describe_usage_report(stats, projects_metadata)
+            )
 
         # Display in terminal
         display_png_in_terminal(png_bytes)
 
         # Save to file
         filename = f"cc-usage-report-{args.date_from}_{args.date_to}.png"
-        with open(filename, 'wb') as f:
-            f.write(png_bytes)
+        write_bytes_atomic(filename, png_bytes)
 
         console.print(f"\n[green]Saved to: {filename}[/green]")
 
@@ -2,19 +2,22 @@
 Streak calculation logic
 """
 from datetime import datetime, timedelta
-from typing import Tuple
+from typing import Optional, Tuple
 
 
-def calculate_streaks(daily_activity: dict[str, int]) -> Tuple[int, int]:
+def calculate_streaks(daily_activity: dict[str, int], excluded_days: Optional[set[str]] = None) -> Tuple[int, int]:
     """
     Calculate max streak and current streak from daily activity.
 
     Args:
         daily_activity: Dict mapping date (YYYY-MM-DD) → message_count
+        excluded_days: Days off (calendar overlay); they neither extend nor break a streak
 
     Returns:
         (max_streak, current_streak)
     """
+    excluded = excluded_days or set()
+    daily_activity = {day: count for day, count in daily_activity.items() if day not in excluded}
     if not daily_activity:
         return 0, 0
 
@@ -30,9 +33,13 @@ def calculate_streaks(daily_activity: dict[str, int]) -> Tuple[int, int]:
             temp_streak = 1
         else:
             delta = dates[i] - dates[i-1]
-            if delta.days == 1:
+            # Consecutive, or only days off in between
+            if all(
+                (dates[i-1] + timedelta(days=gap)).strftime("%Y-%m-%d") in excluded
+                for gap in range(1, delta.days)
+            ):
                 temp_streak += 1
-            elif delta.days > 1:
+            else:
                 temp_streak = 1
         max_streak = max(max_streak, temp_streak)
 
@@ -42,16 +49,25 @@ def calculate_streaks(daily_activity: dict[str, int]) -> Tuple[int, int]:
     curr = datetime.now()
     curr_str = curr.strftime("%Y-%m-%d")
 
+    # Being on a day off today does not end the current streak
+    while curr_str in excluded:
+        curr = curr - timedelta(days=1)
+        curr_str = curr.strftime("%Y-%m-%d")
+
     # If not active today, check yesterday
     if curr_str not in active_date_strs:
         curr = curr - timedelta(days=1)
         curr_str = curr.strftime("%Y-%m-%d")
+        while curr_str in excluded:
+            curr = curr - timedelta(days=1)
+            curr_str = curr.strftime("%Y-%m-%d")
         if curr_str not in active_date_strs:
             return max_streak, 0
 
-    # Count backwards
-    while curr_str in active_date_strs:
-        current_streak += 1
+    # Count backwards, skipping days off
+    while curr_str in active_date_strs or curr_str in excluded:
+        if curr_str in active_date_strs:
+            current_streak += 1
         curr = curr - timedelta(days=1)
         curr_str = curr.strftime("%Y-%m-%d")
 
@@ -0,0 +1,181 @@
+"""
+Persistent worker mode for the Tauri API (``tauri_api serve``).
+
+The desktop app keeps one worker process and sends it line-delimited JSON
+requests on stdin instead of starting an interpreter per command:
+
+    {"id": 1, "args": ["dashboard", "--from", "2025-01-01", ...], "request_id": "ui-1"}
+
+Each request gets exactly one response line on stdout with what a one-shot
+``python -m command_center.tauri_api <args>`` run would have produced:
+
+    {"id": 1, "exit_code": 0, "stdout": "{...}", "stderr": ""}
+
+While a refresh runs, progress events for the request may come first:
+
+    {"id": 1, "progress": {"mode": "full", "state": "running", "files_done": 40, ...}}
+
+A request with ``"stream": true`` gets the list of a streamed result
+(see result_stream) in chunk lines before the response:
+
+    {"id": 1, "chunk": {"key": "messages", "seq": 0, "items": [...]}}
+
+The first line the worker writes is ``{"ready": true, "pid": ..., "protocol": 3}``.
+
+One-shot runs can take a request of the same shape (without ``id``) as JSON on
+stdin with ``python -m command_center.tauri_api --stdin-json``, so long
+free-text parameters do not hit OS argv limits or quoting issues.
+Anything written to the process stdout outside a response (e.g. by a
+subprocess) is sent to stderr so it cannot corrupt the protocol stream.
+The worker exits when stdin is closed.
+"""
+from __future__ import annotations
+
+import io
+import json
+import os
+import sys
+import traceback
+from contextlib import redirect_stderr, redirect_stdout
+from typing import Any, Callable, Optional, TextIO
+
+from command_center.audit_log import REQUEST_ID_ENV
+from command_center.cache.refresh_status import ProgressSink, set_progress_sink
+from command_center.result_stream import ChunkSink, set_chunk_sink
+
+# 2: progress events before the response; 3: result chunks of streamed requests
+WORKER_PROTOCOL_VERSION = 3
+
+# Sole argument of a one-shot run whose request comes as JSON on stdin
+STDIN_JSON_FLAG = "--stdin-json"
+
+
+def _write(stream: TextIO, message: dict[str, Any]):
+    stream.write(json.dumps(message, ensure_ascii=False) + "\n")
+    stream.flush()
+
+
+def _error_output(error: str, kind: str) -> str:
+    return json.dumps({"error": error, "type": kind})
+
+
+def run_request(
+    handler: Callable[[list[str]], None],
+    args: list[str],
+    request_id: Optional[str],
+    progress: Optional[ProgressSink] = None,
+    chunks: Optional[ChunkSink] = None
+) -> dict[str, Any]:
+    """
+    Run one command in-process, capturing its output and exit code.
+
+    Args:
+        handler: CLI entry point taking an argument list (tauri_api.main)
+        args: Command-line arguments
+        request_id: Correlation ID, exposed as COMMAND_CENTER_REQUEST_ID during the call
+        progress: Receives refresh progress events during the call
+        chunks: Receives the chunks of a streamed result (None: not streamed)
+
+    Returns:
+        {"exit_code": 0, "stdout": "...", "stderr": "..."}
+    """
+    stdout, stderr = io.StringIO(), io.StringIO()
+    previous = os.environ.get(REQUEST_ID_ENV)
+    if request_id:
+        os.environ[REQUEST_ID_ENV] = request_id
+    else:
+        os.environ.pop(REQUEST_ID_ENV, None)
+
+    previous_sink = set_progress_sink(progress)
+    previous_chunk_sink = set_chunk_sink(chunks)
+
+    exit_code = 0
+    try:
+        with redirect_stdout(stdout), redirect_stderr(stderr):
+            handler(args)
+    except SystemExit as e:
+        exit_code = e.code if isinstance(e.code, int) else (0 if e.code is None else 1)
+    except Exception as e:
+        # The handler reports its own errors; this only catches crashes outside of it
+        traceback.print_exc(file=stderr)
+        stderr.write(_error_output(str(e), type(e).__name__) + "\n")
+        exit_code = 1
+    finally:
+        set_progress_sink(previous_sink)
+        set_chunk_sink(previous_chunk_sink)
+        if previous is None:
+            os.environ.pop(REQUEST_ID_ENV, None)
+        else:
+            os.environ[REQUEST_ID_ENV] = previous
+
+    return {"exit_code": exit_code, "stdout": stdout.getvalue(), "stderr": stderr.getvalue()}
+
+
+def read_stdin_request(stdin: TextIO = sys.stdin) -> list[str]:
+    """
+    Arguments of a one-shot request passed as JSON on stdin (``--stdin-json``).
+
+    Args:
+        stdin: Stream with one request: {"args": [...], "request_id": "..."}
+
+    Returns:
+        Command-line arguments, with ``--request-id`` first if the request has one
+
+    Raises:
+        ValueError: If the request is not valid JSON or has no argument list
+    """
+    try:
+        request = json.load(stdin)
+        args = [str(arg) for arg in request["args"]]
+    except (AttributeError, KeyError, TypeError) as e:
+        raise ValueError(f"expected {{\"args\": [...]}}: {e}") from e
+    if not args or args[0] == "serve":
+        raise ValueError("expected a command other than serve")
+    request_id = request.get("request_id")
+    return ["--request-id", str(request_id), *args] if request_id else args
+
+
+def serve(handler: Callable[[list[str]], None], stdin: TextIO = sys.stdin, stdout: Optional[TextIO] = None):
+    """
+    Answer line-delimited JSON requests until stdin is closed.
+
+    Args:
+        handler: CLI entry point taking an argument list (tauri_api.main)
+        stdin: Request stream
+        stdout: Response stream (default: the process stdout, which is then
+            redirected to stderr for everything else)
+    """
+    if stdout is None:
+        stdout = os.fdopen(os.dup(sys.stdout.fileno()), "w", encoding="utf-8")
+        sys.stdout.flush()
+        os.dup2(sys.stderr.fileno(), sys.stdout.fileno())
+
+    _write(stdout, {"ready": True, "pid": os.getpid(), "protocol": WORKER_PROTOCOL_VERSION})
+
+    for line in stdin:
+        if not line.strip():
+            continue
+        request_id_value = None
+        try:
+            request = json.loads(line)
+            request_id_value = request.get("id")
+            args = [str(arg) for arg in request["args"]]
+            if args[:1] == ["serve"]:
+                raise ValueError("serve cannot be run inside the worker")
+        except (json.JSONDecodeError, AttributeError, KeyError, TypeError, ValueError) as e:
+            _write(stdout, {
+                "id": request_id_value, "exit_code": 2, "stdout": "",
+                "stderr": _error_output(f"Invalid worker request: {e}", type(e).__name__),
+            })
+            continue
+
+        def progress(event: dict[str, Any]):
+            _write(stdout, {"id": request_id_value, "progress": event})
+
+        def chunk(event: dict[str, Any]):
+            _write(stdout, {"id": request_id_value, "chunk": event})
+
+        response = run_request(
+            handler, args, request.get("request_id"), progress, chunk if request.get("stream") else None
+        )
+        _write(stdout, {"id": request_id_value, **response})
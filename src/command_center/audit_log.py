@@ -0,0 +1,120 @@
+"""
+Audit log of state-changing API commands.
+
+Each line of the log is a JSON object describing one command invocation:
+when it ran, which request it belonged to, its parameters and whether it
+succeeded. The request ID is generated by the desktop app (or passed with
+--request-id) so a user report can be traced from the UI through the bridge
+logs into this file.
+
+The desktop app appends its own events for the local HTTP server: API token
+creation and revocation and every request made with a token, each with the
+token's `token_id`.
+"""
+from __future__ import annotations
+
+import json
+import os
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Optional
+from command_center.utils.atomic_write import write_text_atomic
+
+
+# Default location for the audit log (JSON lines)
+AUDIT_LOG_PATH = os.path.expanduser("~/.claude/db/command-center-audit.jsonl")
+
+# Environment variable used by the desktop bridge to pass the request ID
+REQUEST_ID_ENV = "COMMAND_CENTER_REQUEST_ID"
+
+# Keep the log bounded; the oldest half is dropped when it grows past this size
+MAX_AUDIT_LOG_BYTES = 5 * 1024 * 1024
+
+
+def current_request_id() -> Optional[str]:
+    """Return the request ID passed by the caller, if any."""
+    request_id = os.environ.get(REQUEST_ID_ENV, "").strip()
+    return request_id or None
+
+
+def _trim_log(path: Path):
+    lines = path.read_text(encoding="utf-8").splitlines(keepends=True)
+    write_text_atomic(path, "".join(lines[len(lines) // 2:]))
+
+
+def record_audit_event(
+    command: str,
+    params: dict[str, Any],
+    request_id: Optional[str] = None,
+    error: Optional[str] = None,
+    log_path: str = AUDIT_LOG_PATH
+) -> dict[str, Any]:
+    """
+    Append one command invocation to the audit log.
+
+    Args:
+        command: API subcommand name (e.g. "set-budget")
+        params: Command parameters (None values are dropped)
+        request_id: Correlation ID of the request
+        error: Error message if the command failed
+        log_path: Path to audit log file
+        token_id: Only return events of this API token (optional)
+
+    Returns:
+        The recorded event
+    """
+    event = {
+        "timestamp": datetime.now().astimezone().isoformat(),
+        "request_id": request_id,
+        "command": command,
+        "params": {k: v for k, v in params.items() if v is not None},
+        "status": "error" if error else "ok",
+    }
+    if error:
+        event["error"] = error
+
+    path = Path(log_path)
+    path.parent.mkdir(parents=True, exist_ok=True)
+    if path.exists() and path.stat().st_size > MAX_AUDIT_LOG_BYTES:
+        _trim_log(path)
+    with open(path, "a", encoding="utf-8") as f:
+        f.write(json.dumps(event, ensure_ascii=False) + "\n")
+    return event
+
+
+def read_audit_log(
+    limit: int = 100,
+    request_id: Optional[str] = None,
+    log_path: str = AUDIT_LOG_PATH,
+    token_id: Optional[str] = None
+) -> list[dict[str, Any]]:
+    """
+    Read the most recent audit events, newest first.
+
+    Args:
+        limit: Maximum number of events to return
+        request_id: Only return events of this request (optional)
+        log_path: Path to audit log file
+        token_id: Only return events of this API token (optional)
+
+    Returns:
+        List of audit events; malformed lines are skipped
+    """
+    path = Path(log_path)
+    if not path.exists():
+        return []
+
+    events = []
+    for line in reversed(path.read_text(encoding="utf-8").splitlines()):
+        try:
+            event = json.loads(line)
+        except json.JSONDecodeError:
+            continue
+        if request_id and event.get("request_id") != request_id:
+            continue
+        if token_id and event.get("token_id") != token_id:
+            continue
+        events.append(event)
+        if len(events) >= limit:
+            break
+    return events
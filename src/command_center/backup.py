@@ -0,0 +1,459 @@
+"""
+Signed backups of settings, project metadata and the database.
+
+A backup is a zip archive with one folder per component and a manifest
+listing every file with its SHA-256 and size. The manifest is signed with
+HMAC-SHA256. The signing key lives in the OS keychain when the optional
+``keyring`` package is installed (``command-center[keychain]``), otherwise in
+a key file next to the database that only the user can read.
+
+Backups are verified before anything is restored, so corruption in a synced
+backup folder is caught before it overwrites good data. A backup signed with
+another key (e.g. on another machine) has valid checksums but an unverifiable
+signature; restoring it requires ``force``.
+
+Layout:
+    manifest.json
+    settings/command-center-settings.json, ...
+    projects/command-center-projects.json
+    cache/command_center.db
+"""
+from __future__ import annotations
+
+import hashlib
+import hmac
+import json
+import os
+import secrets
+import sqlite3
+import tempfile
+import zipfile
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Optional
+
+from command_center import __version__ as package_version
+from command_center.cache.ingest_lock import ingest_lock
+from command_center.config import DB_PATH
+from command_center.utils.atomic_write import commit_temp_file, temp_path_for, write_bytes_atomic
+
+
+BACKUP_FORMAT = "command-center-backup"
+BACKUP_VERSION = 1
+MANIFEST_NAME = "manifest.json"
+
+DB_DIR = os.path.dirname(DB_PATH)
+DB_FILE_NAME = os.path.basename(DB_PATH)
+
+# Files of each component, relative to the database directory. Logs and
+# state that is rebuilt on its own (audit log, refresh status, quarantine,
+# export history, mirror) are not backed up.
+COMPONENTS: dict[str, list[str]] = {
+    "settings": [
+        "command-center-settings.json",
+        "command-center-features.json",
+        "command-center-budget.json",
+        "command-center-hooks.json",
+        "command-center-cost-centers.json",
+        "command-center-derived-metrics.json",
+        "command-center-calendar.json",
+        "command-center-working-hours.json",
+        "command-center-accounts.json",
+        "command-center-claude-configs.json",
+    ],
+    "projects": ["command-center-projects.json"],
+    "cache": [DB_FILE_NAME],
+}
+
+KEYCHAIN_SERVICE = "command-center"
+KEYCHAIN_ACCOUNT = "backup-signing-key"
+KEY_FILE_NAME = "command-center-backup.key"
+
+
+def _keyring():
+    try:
+        import keyring
+    except ImportError:
+        return None
+    return keyring
+
+
+def get_signing_key(db_dir: str = DB_DIR, create: bool = True) -> Optional[tuple[bytes, str]]:
+    """
+    Load (or create) the backup signing key.
+
+    Returns:
+        (key, store) with store "keychain" or "file", or None if no key exists and create is False
+    """
+    keyring = _keyring()
+    if keyring is not None:
+        try:
+            stored = keyring.get_password(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)
+            if stored:
+                return bytes.fromhex(stored), "keychain"
+            if create:
+                key = secrets.token_bytes(32)
+                keyring.set_password(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT, key.hex())
+                return key, "keychain"
+        except Exception:
+            pass  # No usable keychain backend (e.g. headless Linux): use the key file
+
+    key_path = Path(db_dir) / KEY_FILE_NAME
+    try:
+        return bytes.fromhex(key_path.read_text(encoding="ascii").strip()), "file"
+    except (OSError, ValueError):
+        if not create:
+            return None
+    key = secrets.token_bytes(32)
+    key_path.parent.mkdir(parents=True, exist_ok=True)
+    fd = os.open(key_path, os.O_WRONLY | os.O_CREAT | os.O_TRUNC, 0o600)
+    with os.fdopen(fd, "w", encoding="ascii") as f:
+        f.write(key.hex())
+    return key, "file"
+
+
+def key_id(key: bytes) -> str:
+    """Public identifier of a key (tells which key signed a backup)."""
+    return hashlib.sha256(key).hexdigest()[:16]
+
+
+def _canonical(manifest: dict[str, Any]) -> bytes:
+    unsigned = {k: v for k, v in manifest.items() if k != "signature"}
+    return json.dumps(unsigned, sort_keys=True, separators=(",", ":"), ensure_ascii=False).encode("utf-8")
+
+
+def sign_manifest(manifest: dict[str, Any], key: bytes) -> dict[str, str]:
+    """HMAC-SHA256 signature of a manifest (its "signature" field excluded)."""
+    return {
+        "algorithm": "hmac-sha256",
+        "key_id": key_id(key),
+        "value": hmac.new(key, _canonical(manifest), hashlib.sha256).hexdigest(),
+    }
+
+
+def _file_sha256(path: Path) -> str:
+    digest = hashlib.sha256()
+    with open(path, "rb") as f:
+        for chunk in iter(lambda: f.read(1024 * 1024), b""):
+            digest.update(chunk)
+    return digest.hexdigest()
+
+
+def _copy_database(db_path: Path, target: Path):
+    """Consistent copy of a live (WAL) database."""
+    source = sqlite3.connect(db_path)
+    copy = sqlite3.connect(target)
+    try:
+        source.backup(copy)
+    finally:
+        copy.close()
+        source.close()
+
+
+def create_backup(output_path: str, db_dir: str = DB_DIR) -> dict[str, Any]:
+    """
+    Write a signed backup archive.
+
+    Args:
+        output_path: Path of the zip file to write
+        db_dir: Directory holding the database and settings
+
+    Returns:
+        {"path", "files", "bytes", "components", "key_id", "key_store"}
+    """
+    key, key_store = get_signing_key(db_dir)
+    base = Path(db_dir)
+    manifest: dict[str, Any] = {
+        "format": BACKUP_FORMAT,
+        "version": BACKUP_VERSION,
+        "created_at": datetime.now().astimezone().isoformat(),
+        "app_version": package_version,
+        "files": [],
+    }
+
+    tmp_path = temp_path_for(output_path)
+    try:
+        with tempfile.TemporaryDirectory() as scratch, \
+                zipfile.ZipFile(tmp_path, "w", compression=zipfile.ZIP_DEFLATED) as archive:
+            for component, names in COMPONENTS.items():
+                for name in names:
+                    source = base / name
+                    if not source.is_file():
+                        continue
+                    if name == DB_FILE_NAME:
+                        copy = Path(scratch) / name
+                        _copy_database(source, copy)
+                        source = copy
+                    member = f"{component}/{name}"
+                    archive.write(source, member)
+                    manifest["files"].append({
+                        "component": component,
+                        "name": member,
+                        "target": name,
+                        "sha256": _file_sha256(source),
+                        "size_bytes": source.stat().st_size,
+                    })
+            manifest["signature"] = sign_manifest(manifest, key)
+            archive.writestr(MANIFEST_NAME, json.dumps(manifest, indent=2, ensure_ascii=False))
+    except Exception:
+        tmp_path.unlink(missing_ok=True)
+        raise
+    commit_temp_file(tmp_path, output_path)
+
+    return {
+        "path": output_path,
+        "files": len(manifest["files"]),
+        "bytes": os.path.getsize(output_path),
+        "components": sorted({f["component"] for f in manifest["files"]}),
+        "key_id": manifest["signature"]["key_id"],
+        "key_store": key_store,
+    }
+
+
+def _read_manifest(archive: zipfile.ZipFile) -> dict[str, Any]:
+    try:
+        manifest = json.loads(archive.read(MANIFEST_NAME))
+    except KeyError:
+        raise ValueError("Not a Command Center backup (no manifest)")
+    except json.JSONDecodeError as e:
+        raise ValueError(f"Corrupted backup manifest: {e}")
+    if not isinstance(manifest, dict) or manifest.get("format") != BACKUP_FORMAT:
+        raise ValueError("Not a Command Center backup")
+    if manifest.get("version", 0) > BACKUP_VERSION:
+        raise ValueError(f"Backup version {manifest.get('version')} is newer than supported ({BACKUP_VERSION})")
+    return manifest
+
+
+def _member_sha256(archive: zipfile.ZipFile, name: str) -> str:
+    digest = hashlib.sha256()
+    with archive.open(name) as f:
+        for chunk in iter(lambda: f.read(1024 * 1024), b""):
+            digest.update(chunk)
+    return digest.hexdigest()
+
+
+def verify_backup(path: str, db_dir: str = DB_DIR) -> dict[str, Any]:
+    """
+    Check a backup's file checksums and manifest signature.
+
+    Args:
+        path: Backup archive
+        db_dir: Directory holding the signing key file
+
+    Returns:
+        {"valid": bool, "signature": "valid" | "invalid" | "unknown_key" | "missing",
+         "files_checked": 3, "errors": [...], "created_at", "app_version", "components"}
+        valid means all checksums match and the signature is valid.
+    """
+    errors: list[str] = []
+    try:
+        archive = zipfile.ZipFile(path)
+    except (OSError, zipfile.BadZipFile) as e:
+        raise ValueError(f"Cannot open backup: {e}")
+
+    with archive:
+        manifest = _read_manifest(archive)
+        members = set(archive.namelist())
+        files = manifest.get("files") or []
+        for entry in files:
+            name = entry.get("name")
+            allowed = COMPONENTS.get(entry.get("component"), [])
+            if entry.get("target") not in allowed or name != f"{entry.get('component')}/{entry.get('target')}":
+                errors.append(f"{name}: unexpected file in manifest")
+            elif name not in members:
+                errors.append(f"{name}: missing from archive")
+            else:
+                try:
+                    actual = _member_sha256(archive, name)
+                except (zipfile.BadZipFile, OSError, EOFError) as e:
+                    errors.append(f"{name}: unreadable ({e})")
+                    continue
+                if actual != entry.get("sha256"):
+                    errors.append(f"{name}: checksum mismatch")
+        listed = {entry.get("name") for entry in files} | {MANIFEST_NAME}
+        errors.extend(f"{name}: not listed in manifest" for name in sorted(members - listed))
+
+    signature = manifest.get("signature")
+    if not isinstance(signature, dict) or not signature.get("value"):
+        signature_state = "missing"
+    else:
+        loaded = get_signing_key(db_dir, create=False)
+        if loaded is None or key_id(loaded[0]) != signature.get("key_id"):
+            signature_state = "unknown_key"
+        elif hmac.compare_digest(sign_manifest(manifest, loaded[0])["value"], signature["value"]):
+            signature_state = "valid"
+        else:
+            signature_state = "invalid"
+            errors.append("Manifest signature does not match (manifest was modified)")
+
+    return {
+        "valid": not errors and signature_state == "valid",
+        "signature": signature_state,
+        "files_checked": len(files),
+        "errors": errors,
+        "created_at": manifest.get("created_at"),
+        "app_version": manifest.get("app_version"),
+        "components": sorted({entry.get("component") for entry in files if entry.get("component")}),
+    }
+
+
+def _restore_database(archive: zipfile.ZipFile, name: str, db_path: Path):
+    """Copy the backed up database into the live one through SQLite (WAL-safe)."""
+    with tempfile.TemporaryDirectory(dir=db_path.parent) as scratch:
+        extracted = Path(archive.extract(name, scratch))
+        source = sqlite3.connect(extracted)
+        target = sqlite3.connect(db_path)
+        try:
+            source.backup(target)
+        finally:
+            target.close()
+            source.close()
+
+
+MAX_CHANGES_PER_FILE = 100
+
+
+def _json_changes(current: Any, incoming: Any, prefix: str = "") -> list[dict[str, str]]:
+    """Dotted paths that differ between two JSON documents (lists compare as a whole)."""
+    if isinstance(current, dict) and isinstance(incoming, dict):
+        changes = []
+        for key in sorted(set(current) | set(incoming), key=str):
+            path = f"{prefix}.{key}" if prefix else str(key)
+            if key not in current:
+                changes.append({"path": path, "change": "added"})
+            elif key not in incoming:
+                changes.append({"path": path, "change": "removed"})
+            else:
+                changes.extend(_json_changes(current[key], incoming[key], path))
+        return changes
+    if current != incoming:
+        return [{"path": prefix or "$", "change": "modified"}]
+    return []
+
+
+def _table_counts(db_path: Path) -> dict[str, int]:
+    conn = sqlite3.connect(f"file:{db_path}?mode=ro", uri=True)
+    try:
+        tables = [
+            row[0] for row in conn.execute(
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name"
+            )
+        ]
+        return {table: conn.execute(f'SELECT COUNT(*) FROM "{table}"').fetchone()[0] for table in tables}
+    finally:
+        conn.close()
+
+
+def _diff_entry(archive: zipfile.ZipFile, entry: dict[str, Any], base: Path) -> dict[str, Any]:
+    """What restoring one manifest entry would change."""
+    target = base / entry["target"]
+    result: dict[str, Any] = {
+        "name": entry["name"],
+        "component": entry["component"],
+        "target": entry["target"],
+    }
+    if not target.is_file():
+        result["status"] = "added"
+        return result
+    if _file_sha256(target) == entry["sha256"]:
+        result["status"] = "unchanged"
+        return result
+
+    result["status"] = "modified"
+    if entry["target"] == DB_FILE_NAME:
+        with tempfile.TemporaryDirectory() as scratch:
+            incoming = _table_counts(Path(archive.extract(entry["name"], scratch)))
+        current = _table_counts(target)
+        result["tables"] = [
+            {"table": table, "current_rows": current.get(table), "backup_rows": incoming.get(table)}
+            for table in sorted(set(current) | set(incoming))
+            if current.get(table) != incoming.get(table)
+        ]
+        return result
+    try:
+        changes = _json_changes(
+            json.loads(target.read_text(encoding="utf-8")),
+            json.loads(archive.read(entry["name"])),
+        )
+    except (OSError, ValueError):
+        return result  # Current file is not valid JSON: replaced as a whole
+    result["changes"] = changes[:MAX_CHANGES_PER_FILE]
+    result["changes_truncated"] = len(changes) > MAX_CHANGES_PER_FILE
+    return result
+
+
+def restore_backup(
+    path: str,
+    force: bool = False,
+    components: Optional[list[str]] = None,
+    dry_run: bool = False,
+    db_dir: str = DB_DIR,
+) -> dict[str, Any]:
+    """
+    Restore a verified backup, optionally only some components.
+
+    Args:
+        path: Backup archive
+        force: Also restore a backup whose signature cannot be checked (other or missing key);
+               checksum errors and invalid signatures are never accepted
+        components: COMPONENTS keys to restore (default: all in the backup)
+        dry_run: Only report what would change
+        db_dir: Directory holding the database and settings
+
+    Returns:
+        {"dry_run": false, "components": ["settings"],
+         "files": [{"name", "component", "target", "status": "added" | "modified" | "unchanged",
+                    "changes": [{"path": "timezone", "change": "modified"}],   # JSON files
+                    "tables": [{"table", "current_rows", "backup_rows"}]}],    # database
+         "restored": ["settings/command-center-settings.json", ...], "verification": {...}}
+        Unchanged files are not rewritten; restored is empty for a dry run.
+
+    Raises:
+        ValueError: If verification fails or a component is unknown or not in the backup
+    """
+    verification = verify_backup(path, db_dir)
+    if verification["errors"]:
+        raise ValueError("Backup failed verification: " + "; ".join(verification["errors"]))
+    if verification["signature"] != "valid" and not force:
+        raise ValueError(
+            f"Backup signature is {verification['signature'].replace('_', ' ')} "
+            "(signed on another machine or by another key); restore with force to accept it"
+        )
+
+    selected = list(dict.fromkeys(components)) if components else verification["components"]
+    unknown = [c for c in selected if c not in COMPONENTS]
+    if unknown:
+        raise ValueError(
+            f"Unknown backup component(s): {', '.join(unknown)} (expected {', '.join(COMPONENTS)})"
+        )
+    missing = [c for c in selected if c not in verification["components"]]
+    if missing:
+        raise ValueError(f"Backup does not contain: {', '.join(missing)}")
+
+    base = Path(db_dir)
+    base.mkdir(parents=True, exist_ok=True)
+    files = []
+    restored = []
+    with zipfile.ZipFile(path) as archive:
+        manifest = _read_manifest(archive)
+        entries = [entry for entry in manifest["files"] if entry["component"] in selected]
+        with ingest_lock(str(base / "command-center-ingest.lock")):
+            for entry in entries:
+                diff = _diff_entry(archive, entry, base)
+                files.append(diff)
+                if dry_run or diff["status"] == "unchanged":
+                    continue
+                target = base / entry["target"]
+                if entry["target"] == DB_FILE_NAME:
+                    _restore_database(archive, entry["name"], target)
+                else:
+                    write_bytes_atomic(target, archive.read(entry["name"]))
+                restored.append(entry["name"])
+
+    return {
+        "dry_run": dry_run,
+        "components": sorted(selected),
+        "files": files,
+        "restored": restored,
+        "verification": verification,
+    }
@@ -0,0 +1,230 @@
+"""
+Ingestion and aggregation benchmark on synthetic session logs.
+
+``run_benchmark`` measures this machine's numbers for performance reports:
+for each dataset size it writes synthetic Claude Code session logs into a
+throwaway home and runs the real pipeline there in a separate backend
+process (paths are resolved from ``HOME`` on import), timing
+
+- ingest: a full refresh parsing and storing every message,
+- aggregate: a dashboard bundle over the whole range (all sections, daily),
+- cached refresh: a second refresh that finds every file unchanged.
+
+Synthetic lines carry ``costUSD``, so no pricing dataset is fetched and the
+numbers do not depend on the network. Real data is never read or touched.
+"""
+from __future__ import annotations
+
+import json
+import os
+import platform
+import random
+import shutil
+import sqlite3
+import subprocess
+import sys
+import tempfile
+import time
+from datetime import datetime, timedelta, timezone
+from pathlib import Path
+from typing import Any, Optional
+
+BENCHMARK_HOME_ENV = "COMMAND_CENTER_BENCHMARK_HOME"
+BENCHMARK_SIZES = (10_000, 100_000, 1_000_000)
+DEFAULT_SIZES = (10_000,)
+DEFAULT_SEED = 7
+
+# Synthetic logs: messages spread over DAYS days ending on END, in sessions of
+# SESSION_MESSAGES messages across PROJECTS projects
+END = datetime(2025, 12, 31, 18, 0, tzinfo=timezone.utc)
+DAYS = 90
+SESSION_MESSAGES = 200
+PROJECTS = 8
+MODELS = ("claude-sonnet-4-5-20250929", "claude-opus-4-5-20251101", "claude-haiku-4-5-20251001")
+
+
+def write_session_logs(projects_dir: Path, messages: int, seed: int = DEFAULT_SEED) -> int:
+    """
+    Write ``messages`` synthetic assistant messages as session JSONL files.
+
+    Args:
+        projects_dir: ``<config dir>/projects`` to write into
+        messages: Number of messages
+        seed: Random seed (same seed, same logs)
+
+    Returns:
+        Number of files written
+    """
+    rng = random.Random(seed)
+    start = END - timedelta(days=DAYS)
+    step = timedelta(days=DAYS) / max(messages, 1)
+    files = 0
+    handle = None
+    try:
+        for i in range(messages):
+            if i % SESSION_MESSAGES == 0:
+                if handle is not None:
+                    handle.close()
+                project_dir = projects_dir / f"-home-bench-projects-p{(i // SESSION_MESSAGES) % PROJECTS}"
+                project_dir.mkdir(parents=True, exist_ok=True)
+                session_id = f"bench-{seed}-{i // SESSION_MESSAGES:06d}"
+                handle = open(project_dir / f"{session_id}.jsonl", "w", encoding="utf-8")
+                files += 1
+            timestamp = start + step * i
+            input_tokens = rng.randint(10, 4_000)
+            output_tokens = rng.randint(50, 2_000)
+            record = {
+                "type": "assistant",
+                "sessionId": session_id,
+                "requestId": f"req_{seed}_{i}",
+                "timestamp": timestamp.strftime("%Y-%m-%dT%H:%M:%S.%f")[:-3] + "Z",
+                "costUSD": round((input_tokens * 3 + output_tokens * 15) / 1_000_000, 6),
+                "message": {
+                    "id": f"msg_{seed}_{i}",
+                    "role": "assistant",
+                    "model": MODELS[rng.randrange(len(MODELS))],
+                    "content": [{"type": "text", "text": "Synthetic benchmark response"}],
+                    "usage": {
+                        "input_tokens": input_tokens,
+                        "output_tokens": output_tokens,
+                        "cache_read_input_tokens": rng.randint(0, 20_000),
+                        "cache_creation_input_tokens": rng.randint(0, 2_000),
+                    },
+                },
+            }
+            handle.write(json.dumps(record) + "\n")
+    finally:
+        if handle is not None:
+            handle.close()
+    return files
+
+
+def measure(messages: int, seed: int = DEFAULT_SEED) -> dict[str, Any]:
+    """
+    Run one benchmark in the current process.
+
+    Only runs in a process started by ``run_benchmark`` (``HOME`` must be the
+    throwaway ``COMMAND_CENTER_BENCHMARK_HOME``), so synthetic logs never end
+    up among real ones.
+
+    Returns:
+        {"messages", "files", "generate_seconds", "ingest_seconds",
+         "messages_per_second", "aggregate_seconds",
+         "cached_refresh_seconds", "db_bytes"}
+
+    Raises:
+        ValueError: Outside a benchmark process
+    """
+    from command_center.cache.incremental_update import perform_incremental_update
+    from command_center.config import DB_PATH, HOME
+    from command_center.database.connection import get_db_connection
+    from command_center.database.schema import init_database
+    from command_center.tauri_api import get_dashboard_bundle
+    from command_center.utils import pricing
+
+    home = os.environ.get(BENCHMARK_HOME_ENV)
+    if not home or os.path.realpath(HOME) != os.path.realpath(home):
+        raise ValueError(f"Benchmarks only run in a throwaway home ({BENCHMARK_HOME_ENV})")
+    # Lines carry costUSD; an empty dataset keeps pricing off the network
+    pricing._pricing_cache = {}
+
+    started = time.perf_counter()
+    files = write_session_logs(Path(HOME) / ".claude" / "projects", messages, seed)
+    generate_seconds = time.perf_counter() - started
+
+    with get_db_connection() as conn:
+        init_database(conn)
+        stats: dict = {}
+        started = time.perf_counter()
+        perform_incremental_update(conn, stats=stats)
+        ingest_seconds = time.perf_counter() - started
+
+        started = time.perf_counter()
+        perform_incremental_update(conn)
+        cached_refresh_seconds = time.perf_counter() - started
+
+    date_from = (END - timedelta(days=DAYS)).date().isoformat()
+    started = time.perf_counter()
+    get_dashboard_bundle(date_from, END.date().isoformat(), False, "day")
+    aggregate_seconds = time.perf_counter() - started
+
+    return {
+        "messages": messages,
+        "files": files,
+        "generate_seconds": round(generate_seconds, 3),
+        "ingest_seconds": round(ingest_seconds, 3),
+        "messages_per_second": round(messages / ingest_seconds) if ingest_seconds > 0 else None,
+        "aggregate_seconds": round(aggregate_seconds, 3),
+        "cached_refresh_seconds": round(cached_refresh_seconds, 3),
+        "db_bytes": os.path.getsize(DB_PATH),
+    }
+
+
+def machine_info() -> dict[str, Any]:
+    """Platform, CPU and library versions reported with benchmark numbers."""
+    from command_center.tauri_api import get_app_version
+
+    return {
+        "app_version": get_app_version(),
+        "platform": platform.platform(),
+        "machine": platform.machine(),
+        "cpu_count": os.cpu_count(),
+        "python_version": platform.python_version(),
+        "sqlite_version": sqlite3.sqlite_version,
+    }
+
+
+def _backend_command() -> list[str]:
+    """Command starting this backend (the frozen sidecar is its own entry point)."""
+    if getattr(sys, "frozen", False):
+        return [sys.executable]
+    return [sys.executable, "-m", "command_center.tauri_api"]
+
+
+def run_benchmark(sizes: Optional[list[int]] = None, seed: int = DEFAULT_SEED) -> dict[str, Any]:
+    """
+    Benchmark ingestion, aggregation and cached refreshes on synthetic logs.
+
+    Args:
+        sizes: Dataset sizes in messages, each one of BENCHMARK_SIZES
+            (default: DEFAULT_SIZES; 1M messages take minutes and ~500 MB of disk)
+        seed: Random seed of the synthetic logs
+
+    Returns:
+        {"machine": {...machine_info...}, "results": [...measure per size...],
+         "total_seconds": 12.3}
+
+    Raises:
+        ValueError: For an unsupported size or a failed run
+    """
+    sizes = list(sizes or DEFAULT_SIZES)
+    unsupported = [size for size in sizes if size not in BENCHMARK_SIZES]
+    if unsupported:
+        raise ValueError(
+            f"Unsupported benchmark size: {', '.join(map(str, unsupported))} "
+            f"(expected {', '.join(map(str, BENCHMARK_SIZES))})"
+        )
+
+    started = time.perf_counter()
+    results = []
+    for size in sizes:
+        home = tempfile.mkdtemp(prefix="cc-benchmark-")
+        try:
+            env = {key: value for key, value in os.environ.items()
+                   if key not in ("CLAUDE_CONFIG_DIR", "COMMAND_CENTER_DEMO")}
+            env.update({"HOME": home, "USERPROFILE": home, BENCHMARK_HOME_ENV: home})
+            run = subprocess.run(
+                [*_backend_command(), "benchmark", "--measure", str(size), "--seed", str(seed)],
+                env=env, capture_output=True, text=True,
+            )
+            if run.returncode != 0:
+                raise ValueError(f"Benchmark of {size} messages failed: {run.stderr.strip()[-500:]}")
+            results.append(json.loads(run.stdout.strip().splitlines()[-1]))
+        finally:
+            shutil.rmtree(home, ignore_errors=True)
+
+    return {
+        "machine": machine_info(),
+        "results": results,
+        "total_seconds": round(time.perf_counter() - started, 3),
+    }
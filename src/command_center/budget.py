@@ -0,0 +1,287 @@
+"""
+Monthly budget tracking with automatic month rollover snapshots.
+
+The budget configuration lives in a small JSON file next to the database.
+Each budget change records the month it takes effect from, so completed
+months are always compared against the budget that applied at the time.
+
+Budget months are billing cycles: with a billing anchor day configured in
+the shared settings (e.g. the 17th), period "2025-06" runs from June 17 to
+July 16. Without one they are calendar months.
+
+When a month rolls over, its actual usage is frozen into the
+``budget_periods`` table together with the budget in effect and its dates.
+"""
+from __future__ import annotations
+
+import json
+import os
+import sqlite3
+from datetime import date, datetime
+from pathlib import Path
+from typing import Any, Optional
+
+from command_center.settings import billing_anchor_day, settings_path_near
+from command_center.utils.atomic_write import write_json_atomic
+from command_center.utils.date_helpers import billing_cycle_bounds, billing_cycle_key, parse_month_key
+
+
+# Default location for budget configuration JSON
+BUDGET_JSON_PATH = os.path.expanduser("~/.claude/db/command-center-budget.json")
+
+
+def _next_month(month: str) -> str:
+    year, mon = (int(part) for part in month.split("-"))
+    if mon == 12:
+        return f"{year + 1:04d}-01"
+    return f"{year:04d}-{mon + 1:02d}"
+
+
+def load_budget_config(json_path: str = BUDGET_JSON_PATH) -> dict:
+    """
+    Load budget configuration from JSON file.
+
+    Returns:
+        {
+            "monthly": [
+                {"effective_from": "2025-01", "amount_usd": 100.0},
+                {"effective_from": "2025-06", "amount_usd": 200.0}
+            ]
+        }
+    """
+    path = Path(json_path)
+    if not path.exists():
+        return {"monthly": []}
+
+    try:
+        with open(path, "r") as f:
+            config = json.load(f)
+    except (json.JSONDecodeError, IOError):
+        # Corrupted file - behave as if no budget is configured
+        return {"monthly": []}
+
+    config.setdefault("monthly", [])
+    return config
+
+
+def save_budget_config(config: dict, json_path: str = BUDGET_JSON_PATH):
+    """Save budget configuration to JSON file."""
+    write_json_atomic(json_path, config)
+
+
+def get_budget_for_month(config: dict, month: str) -> Optional[float]:
+    """
+    Return the monthly budget in effect for a month, or None if unset.
+
+    Args:
+        config: Budget configuration from load_budget_config()
+        month: Month key (YYYY-MM)
+    """
+    amount = None
+    for change in sorted(config.get("monthly", []), key=lambda c: c["effective_from"]):
+        if change["effective_from"] <= month:
+            amount = change["amount_usd"]
+        else:
+            break
+    return amount
+
+
+def set_monthly_budget(
+    amount_usd: Optional[float],
+    effective_from: Optional[str] = None,
+    json_path: str = BUDGET_JSON_PATH
+) -> dict:
+    """
+    Set the monthly budget starting from a given month.
+
+    Args:
+        amount_usd: Budget in USD, or None to clear the budget from that month on
+        effective_from: First month (YYYY-MM) the budget applies to; defaults to current month
+        json_path: Path to budget JSON file
+
+    Returns:
+        Updated budget configuration
+
+    Raises:
+        ValueError: If amount is negative or month is malformed
+    """
+    if amount_usd is not None and amount_usd < 0:
+        raise ValueError("Budget amount cannot be negative")
+
+    month = parse_month_key(effective_from) if effective_from else datetime.now().strftime("%Y-%m")
+
+    config = load_budget_config(json_path)
+    changes = [c for c in config["monthly"] if c["effective_from"] != month]
+    changes.append({"effective_from": month, "amount_usd": amount_usd})
+    changes.sort(key=lambda c: c["effective_from"])
+    config["monthly"] = changes
+
+    save_budget_config(config, json_path)
+    return config
+
+
+def _anchor_day(json_path: str) -> int:
+    return billing_anchor_day(settings_path_near(json_path))
+
+
+def _query_month_actuals(conn: sqlite3.Connection, date_from: str, date_to: str) -> dict:
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT
+            COUNT(*) as messages,
+            SUM(total_tokens) as tokens,
+            SUM(COALESCE(cost_usd, 0)) as cost
+        FROM message_entries
+        WHERE date >= ? AND date <= ? AND origin = 'local'
+    """, (date_from, date_to))
+    row = cursor.fetchone()
+    return {
+        "messages": row[0] or 0,
+        "tokens": row[1] or 0,
+        "cost": round(row[2] or 0, 4),
+    }
+
+
+def _build_period(
+    month: str,
+    date_from: str,
+    date_to: str,
+    budget: Optional[float],
+    actuals: dict
+) -> dict[str, Any]:
+    over_under = None
+    percent_used = None
+    if budget is not None:
+        over_under = round(actuals["cost"] - budget, 4)
+        percent_used = round(actuals["cost"] / budget * 100, 1) if budget > 0 else None
+    return {
+        "period": month,
+        "from": date_from,
+        "to": date_to,
+        "budget": budget,
+        "actual": actuals["cost"],
+        "over_under": over_under,
+        "percent_used": percent_used,
+        "messages": actuals["messages"],
+        "tokens": actuals["tokens"],
+    }
+
+
+def roll_over_budget_periods(
+    conn: sqlite3.Connection,
+    today: Optional[date] = None,
+    json_path: str = BUDGET_JSON_PATH
+) -> int:
+    """
+    Snapshot every completed month that has a budget but no snapshot yet.
+
+    Snapshots are immutable: once a month is frozen it is not recomputed,
+    even if older data is re-ingested later.
+
+    Args:
+        conn: Database connection
+        today: Reference date (defaults to today)
+        json_path: Path to budget JSON file
+
+    Returns:
+        Number of new period snapshots written
+    """
+    config = load_budget_config(json_path)
+    if not config["monthly"]:
+        return 0
+
+    anchor_day = _anchor_day(json_path)
+    current_month = billing_cycle_key(today or date.today(), anchor_day)
+    month = min(c["effective_from"] for c in config["monthly"])
+
+    cursor = conn.cursor()
+    cursor.execute("SELECT period FROM budget_periods")
+    existing = {row[0] for row in cursor.fetchall()}
+
+    written = 0
+    while month < current_month:
+        budget = get_budget_for_month(config, month)
+        if month not in existing and budget is not None:
+            date_from, date_to = billing_cycle_bounds(month, anchor_day)
+            actuals = _query_month_actuals(conn, date_from, date_to)
+            cursor.execute("""
+                INSERT OR IGNORE INTO budget_periods
+                (period, date_from, date_to, budget_usd, actual_cost_usd,
+                 message_count, total_tokens, snapshot_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            """, (
+                month, date_from, date_to, budget, actuals["cost"],
+                actuals["messages"], actuals["tokens"],
+                datetime.now().astimezone().isoformat(),
+            ))
+            written += 1
+        month = _next_month(month)
+
+    conn.commit()
+    return written
+
+
+def query_budget_history(
+    conn: sqlite3.Connection,
+    today: Optional[date] = None,
+    json_path: str = BUDGET_JSON_PATH
+) -> dict:
+    """
+    Get past budget periods with over/under amounts plus the running month.
+
+    Args:
+        conn: Database connection
+        today: Reference date (defaults to today)
+        json_path: Path to budget JSON file
+
+    Returns:
+        {
+            "current": {period, from, to, budget, actual, over_under, percent_used, ...},
+            "periods": [ ...completed periods, newest first... ],
+            "summary": {"periods": N, "over_budget": N, "under_budget": N, "average_percent_used": X}
+        }
+    """
+    config = load_budget_config(json_path)
+    anchor_day = _anchor_day(json_path)
+    current_month = billing_cycle_key(today or date.today(), anchor_day)
+
+    # Frozen periods keep the dates they were snapshotted with
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT period, date_from, date_to, budget_usd, actual_cost_usd, message_count,
+               total_tokens, snapshot_at
+        FROM budget_periods
+        ORDER BY period DESC
+    """)
+    periods = []
+    for row in cursor.fetchall():
+        period = _build_period(
+            row[0], row[1], row[2], row[3],
+            {"cost": round(row[4] or 0, 4), "messages": row[5] or 0, "tokens": row[6] or 0},
+        )
+        period["snapshot_at"] = row[7]
+        periods.append(period)
+
+    date_from, date_to = billing_cycle_bounds(current_month, anchor_day)
+    current = _build_period(
+        current_month,
+        date_from,
+        date_to,
+        get_budget_for_month(config, current_month),
+        _query_month_actuals(conn, date_from, date_to),
+    )
+
+    percents = [p["percent_used"] for p in periods if p["percent_used"] is not None]
+    summary = {
+        "periods": len(periods),
+        "over_budget": sum(1 for p in periods if (p["over_under"] or 0) > 0),
+        "under_budget": sum(1 for p in periods if p["over_under"] is not None and p["over_under"] <= 0),
+        "average_percent_used": round(sum(percents) / len(percents), 1) if percents else None,
+    }
+
+    return {
+        "current": current,
+        "periods": periods,
+        "summary": summary,
+        "billing_anchor_day": anchor_day,
+    }
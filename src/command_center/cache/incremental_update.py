@@ -3,13 +3,23 @@ Incremental update orchestration
 """
 import os
 import sqlite3
+import time
+from dataclasses import dataclass, field
+from datetime import datetime, timedelta, timezone
+from pathlib import Path
+from typing import Any, Callable, Optional
+
 from rich.progress import Progress, BarColumn, TextColumn, TimeRemainingColumn
 
 from command_center.collectors.file_scanner import scan_jsonl_files
 from command_center.collectors.jsonl_parser import parse_jsonl_line
 from command_center.collectors.limit_parser import parse_limit_event, complete_limit_event
+from command_center.collectors.mcp_parser import extract_mcp_calls
+from command_center.collectors.hook_parser import extract_hook_executions
+from command_center.collectors.effort_parser import extract_request_settings
 from command_center.database.queries import (
-    get_file_tracks, insert_message_entries, insert_limit_events, update_file_track,
+    get_file_tracks, insert_message_entries, insert_limit_events, insert_mcp_calls,
+    insert_hook_executions, insert_request_settings, update_file_track,
     recompute_hourly_aggregates, recompute_model_aggregates
 )
 from command_center.cache.file_tracker import detect_file_changes
@@ -17,11 +27,19 @@ from command_center.utils.date_helpers import format_datetime_hour, parse_and_co
 from command_center.utils.project_metadata import (
     load_projects_json, save_projects_json, auto_discover_project
 )
+from command_center.pricing_history import sync_price_versions, load_price_book
+from command_center.project_timeline import backfill_first_seen
+from command_center.config import MIRROR_ENABLED
+from command_center.cache.mirror import read_mirror, write_mirror
+from command_center.cache.quarantine import quarantine_record, replace_quarantined_lines
 
 
 def perform_incremental_update(conn: sqlite3.Connection,
                                force_rescan: bool = False,
-                               verbose: bool = False) -> int:
+                               verbose: bool = False,
+                               stats: Optional[dict] = None,
+                               mirror: Optional[bool] = None,
+                               on_progress: Optional[Callable[[int, int], None]] = None) -> int:
     """
     Perform incremental update of database.
 
@@ -31,10 +49,17 @@ def perform_incremental_update(conn: sqlite3.Connection,
         conn: Database connection
         force_rescan: If True, reprocess all files regardless of tracking
         verbose: Show progress bars and details
+        stats: Optional dict filled with timing numbers
+            (files, entries, mirror_hits, quarantined_lines, parse_seconds, write_seconds,
+            total_seconds, files_per_second)
+        mirror: Read/write the zstd record mirror (default: MIRROR_ENABLED)
+        on_progress: Optional callback called with (files_done, files_total) after each file
 
     Returns:
         Number of files processed
     """
+    started = time.perf_counter()
+
     # Scan filesystem
     discovered_files = scan_jsonl_files()
 
@@ -59,11 +84,22 @@ def perform_incremental_update(conn: sqlite3.Connection,
     # Load project metadata at start
     projects = load_projects_json()
 
+    # Record price changes, then price messages by the rates in effect on their date
+    sync_price_versions(conn)
+    price_book = load_price_book(conn)
+
     # Track affected hours, years, and discovered projects
     affected_hours = set()
     affected_years = set()
     discovered_project_ids = set()
 
+    use_mirror = MIRROR_ENABLED if mirror is None else mirror
+    mirror_hits = 0
+    write_seconds = 0.0
+    entry_total = 0
+    stored_count = 0
+    quarantine_updates: dict[str, list] = {}
+
     # Process files with progress bar (always shown)
     with Progress(
         TextColumn("[bold blue]Processing files..."),
@@ -73,15 +109,32 @@ def perform_incremental_update(conn: sqlite3.Connection,
     ) as progress:
         task = progress.add_task("Processing", total=len(files_to_process))
 
-        for file_path in files_to_process:
-            entry_count = process_file(
-                conn, file_path, affected_hours, affected_years, discovered_project_ids
-            )
+        def store(parsed: "ParsedFile"):
+            nonlocal write_seconds, entry_total, mirror_hits, stored_count
+            write_started = time.perf_counter()
+            entry_count = store_parsed_file(conn, parsed, quarantine_updates)
+            write_seconds += time.perf_counter() - write_started
+            entry_total += entry_count
+            mirror_hits += parsed.from_mirror
+            stored_count += 1
+            if on_progress is not None:
+                on_progress(stored_count, len(files_to_process))
+            affected_hours.update(parsed.affected_hours)
+            affected_years.update(parsed.affected_years)
+            discovered_project_ids.update(parsed.project_ids)
             progress.update(task, advance=1)
 
             # Verbose: show details for each file
             if verbose and entry_count > 0:
-                progress.console.print(f"  [dim]Processed {entry_count} entries from {os.path.basename(file_path)}[/dim]")
+                progress.console.print(f"  [dim]Processed {entry_count} entries from {os.path.basename(parsed.file_path)}[/dim]")
+
+        for file_path in files_to_process:
+            store(parse_file(file_path, price_book, use_mirror=use_mirror))
+
+    quarantined_lines = _save_quarantine(quarantine_updates)
+    if verbose and quarantined_lines:
+        from rich.console import Console
+        Console().print(f"[yellow]Quarantined {quarantined_lines} malformed lines (see quarantined-lines)[/yellow]")
 
     # Recompute aggregates for affected hours/years
     if affected_hours:
@@ -102,17 +155,190 @@ def perform_incremental_update(conn: sqlite3.Connection,
         for project_id in discovered_project_ids:
             projects = auto_discover_project(projects, project_id)
         save_projects_json(projects)
+        # first_seen of a new project is its first session, not the scan time
+        backfill_first_seen(conn, list(discovered_project_ids))
 
         if verbose:
             from rich.console import Console
             Console().print(f"[dim]Discovered {len(discovered_project_ids)} projects[/dim]")
 
+    if stats is not None:
+        total_seconds = time.perf_counter() - started
+        stats.update({
+            "files": len(files_to_process),
+            "entries": entry_total,
+            "mirror_hits": mirror_hits,
+            "quarantined_lines": quarantined_lines,
+            "parse_seconds": round(total_seconds - write_seconds, 3),
+            "write_seconds": round(write_seconds, 3),
+            "total_seconds": round(total_seconds, 3),
+            "files_per_second": round(len(files_to_process) / total_seconds, 1) if total_seconds else None,
+        })
+
     return len(files_to_process)
 
 
+# Overlap applied to the stored watermark, covering lines written while the last scan ran
+WATERMARK_SLACK = timedelta(minutes=5)
+
+
+def get_refresh_watermark(conn: sqlite3.Connection) -> Optional[datetime]:
+    """
+    Return the time of the most recent file scan (UTC), or None if nothing was scanned.
+    """
+    row = conn.execute("SELECT MAX(last_scanned) FROM file_tracks").fetchone()
+    if not row or not row[0]:
+        return None
+    return datetime.strptime(row[0], "%Y-%m-%d %H:%M:%S").replace(tzinfo=timezone.utc)
+
+
+def perform_partial_update(conn: sqlite3.Connection,
+                           since: Optional[datetime] = None,
+                           on_progress: Optional[Callable[[int, int], None]] = None) -> dict[str, Any]:
+    """
+    Fast-path update that only ingests what changed since a point in time.
+
+    Only new or modified files with an mtime at or after ``since`` are read.
+    Lines of already tracked files with an older timestamp are skipped (they
+    were ingested by an earlier scan); new files are always read in full.
+    Hourly aggregates are patched for affected hours and model aggregates for
+    affected (model, year) pairs only. No progress output is printed.
+
+    Args:
+        conn: Database connection
+        since: Timezone-aware start of the refresh window (default: last scan
+            watermark minus WATERMARK_SLACK, or local midnight if nothing was scanned)
+        on_progress: Optional callback called with (files_done, files_total) after each file
+
+    Returns:
+        {
+            "mode": "partial",
+            "since": "2025-06-15T00:00:00+02:00",
+            "updated_files": 2,
+            "affected_dates": ["2025-06-15"],
+            "quarantined_lines": 0,
+            "delta": {"messages": 12, "tokens": 34000, "cost": 0.42}
+        }
+    """
+    if since is None:
+        watermark = get_refresh_watermark(conn)
+        if watermark is not None:
+            since = watermark - WATERMARK_SLACK
+        else:
+            since = datetime.now().astimezone().replace(hour=0, minute=0, second=0, microsecond=0)
+
+    since_utc = since.astimezone(timezone.utc)
+    since_ns = int(since_utc.timestamp() * 1_000_000_000)
+    since_iso = since_utc.strftime("%Y-%m-%dT%H:%M:%S")
+    since_date = since.astimezone().strftime("%Y-%m-%d")
+
+    tracked = get_file_tracks(conn)
+    candidates = [
+        fs for fs in detect_file_changes(scan_jsonl_files(), tracked)
+        if fs.status in ("new", "modified") and fs.mtime_ns >= since_ns
+    ]
+
+    before = _usage_since(conn, since_date)
+    affected_hours: set[str] = set()
+    affected_years: set[int] = set()
+    affected_models: set[str] = set()
+    discovered_project_ids: set[str] = set()
+    quarantine_updates: dict[str, list] = {}
+
+    if candidates:
+        sync_price_versions(conn)
+        price_book = load_price_book(conn)
+        for done, fs in enumerate(candidates, start=1):
+            process_file(
+                conn, fs.path, affected_hours, affected_years, discovered_project_ids,
+                price_book,
+                since=since_iso if fs.status == "modified" else None,
+                affected_models=affected_models,
+                quarantine_updates=quarantine_updates
+            )
+            if on_progress is not None:
+                on_progress(done, len(candidates))
+
+    quarantined_lines = _save_quarantine(quarantine_updates)
+
+    recompute_hourly_aggregates(conn, affected_hours)
+    for year in affected_years:
+        recompute_model_aggregates(conn, year, affected_models or None)
+
+    if discovered_project_ids:
+        projects = load_projects_json()
+        for project_id in discovered_project_ids:
+            projects = auto_discover_project(projects, project_id)
+        save_projects_json(projects)
+        backfill_first_seen(conn, list(discovered_project_ids))
+
+    after = _usage_since(conn, since_date)
+    return {
+        "mode": "partial",
+        "since": since.isoformat(),
+        "updated_files": len(candidates),
+        "affected_dates": sorted({hour[:10] for hour in affected_hours}),
+        "quarantined_lines": quarantined_lines,
+        "delta": {
+            "messages": after["messages"] - before["messages"],
+            "tokens": after["tokens"] - before["tokens"],
+            "cost": round(after["cost"] - before["cost"], 6),
+        },
+    }
+
+
+def _save_quarantine(quarantine_updates: dict[str, list]) -> int:
+    """Store quarantined lines; a failing write never fails the refresh."""
+    try:
+        return replace_quarantined_lines(quarantine_updates)
+    except OSError:
+        return sum(len(lines) for lines in quarantine_updates.values())
+
+
+def _usage_since(conn: sqlite3.Connection, date_from: str) -> dict[str, Any]:
+    row = conn.execute("""
+        SELECT COUNT(*), COALESCE(SUM(total_tokens), 0), COALESCE(SUM(cost_usd), 0)
+        FROM message_entries
+        WHERE date >= ?
+    """, (date_from,)).fetchone()
+    return {"messages": row[0], "tokens": row[1], "cost": row[2]}
+
+
+@dataclass
+class ParsedFile:
+    """Parsed content of one .jsonl file; built by parse_file, stored by the caller."""
+    file_path: str
+    readable: bool = True
+    entries: list = field(default_factory=list)
+    limit_events: list = field(default_factory=list)
+    mcp_calls: list = field(default_factory=list)
+    hook_executions: list = field(default_factory=list)
+    request_settings: list = field(default_factory=list)
+    affected_hours: set[str] = field(default_factory=set)
+    affected_years: set[int] = field(default_factory=set)
+    affected_models: set[str] = field(default_factory=set)
+    project_ids: set[str] = field(default_factory=set)
+    quarantined: list = field(default_factory=list)
+    from_mirror: bool = False
+
+    def track(self, entry):
+        """Record the aggregate keys touched by a message entry."""
+        dt_local = parse_and_convert_to_local(entry.timestamp)
+        if dt_local:
+            self.affected_hours.add(format_datetime_hour(dt_local))
+            self.affected_years.add(entry.year)
+        if entry.model:
+            self.affected_models.add(entry.model)
+        if entry.project_id and entry.project_id != 'unknown':
+            self.project_ids.add(entry.project_id)
+
+
 def process_file(conn: sqlite3.Connection, file_path: str,
                 affected_hours: set[str], affected_years: set[int],
-                discovered_project_ids: set[str]) -> int:
+                discovered_project_ids: set[str], price_book=None,
+                since: Optional[str] = None,
+                affected_models: Optional[set[str]] = None,
+                quarantine_updates: Optional[dict[str, list]] = None) -> int:
     """
     Process a single .jsonl file.
 
@@ -124,56 +350,102 @@ def process_file(conn: sqlite3.Connection, file_path: str,
         affected_hours: Set to collect affected datetime_hours
         affected_years: Set to collect affected years
         discovered_project_ids: Set to collect discovered project IDs
+        price_book: Optional PriceBook for per-date message pricing
+        since: Skip message entries with a UTC timestamp before this (YYYY-MM-DDTHH:MM:SS)
+        affected_models: Optional set to collect affected models
+        quarantine_updates: Optional dict collecting file path → quarantined lines
 
     Returns:
         Number of valid entries processed
     """
+    parsed = parse_file(file_path, price_book, since)
+    affected_hours.update(parsed.affected_hours)
+    affected_years.update(parsed.affected_years)
+    discovered_project_ids.update(parsed.project_ids)
+    if affected_models is not None:
+        affected_models.update(parsed.affected_models)
+    return store_parsed_file(conn, parsed, quarantine_updates)
+
+
+def parse_file(file_path: str, price_book=None, since: Optional[str] = None,
+               use_mirror: bool = False) -> ParsedFile:
+    """
+    Parse a single .jsonl file without touching the database.
+
+    Args:
+        file_path: Path to .jsonl file
+        price_book: Optional PriceBook for per-date message pricing
+        since: Skip message entries with a UTC timestamp before this (YYYY-MM-DDTHH:MM:SS)
+        use_mirror: Read records from an up-to-date zstd mirror instead of parsing,
+            and write the mirror after a full parse (ignored when since is set)
+
+    Returns:
+        ParsedFile with message entries, completed limit events, MCP calls, hook executions,
+        request settings and affected keys;
+        readable is False if the file could not be read
+    """
     import json
 
-    entries = []
+    parsed = ParsedFile(file_path=file_path)
+
+    stat = None
+    if use_mirror and not since:
+        try:
+            stat = os.stat(file_path)
+        except OSError:
+            return ParsedFile(file_path=file_path, readable=False)
+        mirrored = read_mirror(file_path, stat.st_mtime_ns, stat.st_size)
+        if mirrored is not None:
+            (parsed.entries, parsed.limit_events, parsed.mcp_calls, parsed.hook_executions,
+             parsed.request_settings) = mirrored
+            parsed.from_mirror = True
+            for entry in parsed.entries:
+                parsed.track(entry)
+            return parsed
     all_lines = []  # Store all parsed lines for limit processing
-    entry_count = 0
 
     try:
-        with open(file_path, 'r', encoding='utf-8') as f:
-            for line in f:
-                line_stripped = line.strip()
-                if not line_stripped:
-                    continue
-
+        with open(file_path, 'rb') as f:
+            for line_number, raw in enumerate(f, start=1):
+                # Malformed lines are quarantined instead of failing the file
                 try:
+                    line = raw.decode('utf-8')
+                    line_stripped = line.strip()
+                    if not line_stripped:
+                        continue
                     data = json.loads(line_stripped)
-                    all_lines.append(data)
-                except:
+                    if not isinstance(data, dict):
+                        raise ValueError(f"expected a JSON object, got {type(data).__name__}")
+                except (UnicodeDecodeError, ValueError) as e:
+                    if raw.endswith(b'\n'):
+                        parsed.quarantined.append(
+                            quarantine_record(file_path, line_number, f"{type(e).__name__}: {e}", raw)
+                        )
+                    # else: last line still being written; read again on the next refresh
+                    continue
+                all_lines.append(data)
+
+                if since and str(data.get('timestamp') or '') < since:
                     continue
 
                 # Try to parse as message entry
-                entry = parse_jsonl_line(line, file_path)
+                try:
+                    entry = parse_jsonl_line(line, file_path, price_book)
+                except Exception as e:
+                    parsed.quarantined.append(
+                        quarantine_record(file_path, line_number, f"{type(e).__name__}: {e}", raw)
+                    )
+                    continue
                 if entry:
-                    entries.append(entry)
-                    entry_count += 1
-
-                    # Track affected hour and year
-                    dt_local = parse_and_convert_to_local(entry.timestamp)
-                    if dt_local:
-                        datetime_hour = format_datetime_hour(dt_local)
-                        affected_hours.add(datetime_hour)
-                        affected_years.add(entry.year)
-
-                    # Track discovered project
-                    if entry.project_id and entry.project_id != 'unknown':
-                        discovered_project_ids.add(entry.project_id)
+                    parsed.entries.append(entry)
+
+                    # Track affected hour, year, model and discovered project
+                    parsed.track(entry)
     except Exception:
         # File read error - skip
-        return 0
-
-    # Insert message entries
-    if entries:
-        insert_message_entries(conn, entries)
+        return ParsedFile(file_path=file_path, readable=False)
 
     # Process limit events from summary entries OR assistant error messages
-    completed_limits = []
-
     for i, data in enumerate(all_lines):
         # Check if this is a limit event (old summary format OR new error format)
         is_old_format = data.get('type') == 'summary'
@@ -186,7 +458,7 @@ def process_file(conn: sqlite3.Connection, file_path: str,
                 # (timestamp is in the same entry)
                 if is_new_format and limit_event.occurred_at:
                     # Event already completed
-                    completed_limits.append(limit_event)
+                    parsed.limit_events.append(limit_event)
                     continue
 
                 # For old format, find the NEXT message entry with timestamp
@@ -215,18 +487,72 @@ def process_file(conn: sqlite3.Connection, file_path: str,
                             timestamp,
                             session_id
                         )
-                        completed_limits.append(completed)
+                        parsed.limit_events.append(completed)
                     except Exception as e:
                         # Skip invalid limit events
                         pass
 
-    if completed_limits:
-        insert_limit_events(conn, completed_limits)
+    # MCP server tool calls (tool names mcp__<server>__<tool>)
+    parsed.mcp_calls = extract_mcp_calls(all_lines, file_path)
+
+    # Claude Code hook executions (PreToolUse, PostToolUse, Stop, ...)
+    parsed.hook_executions = extract_hook_executions(all_lines, file_path)
+
+    # Effort/thinking setting and latency of each response
+    parsed.request_settings = extract_request_settings(all_lines)
+
+    if stat is not None:
+        try:
+            write_mirror(file_path, stat.st_mtime_ns, stat.st_size, parsed.entries, parsed.limit_events,
+                         parsed.mcp_calls, parsed.hook_executions, parsed.request_settings)
+        except OSError:
+            pass
+
+    return parsed
+
+
+def store_parsed_file(conn: sqlite3.Connection, parsed: ParsedFile,
+                      quarantine_updates: Optional[dict[str, list]] = None) -> int:
+    """
+    Insert a parsed file's entries and side records (limit events, MCP calls, hook executions,
+    request settings) and update its file track.
+
+    Args:
+        conn: Database connection
+        parsed: Parsed file
+        quarantine_updates: Optional dict collecting file path → quarantined lines
+            (files loaded from the mirror are not re-checked and left out)
+
+    Returns:
+        Number of message entries in the file (0 for unreadable files, which stay untracked)
+    """
+    if not parsed.readable:
+        return 0
+
+    if quarantine_updates is not None and not parsed.from_mirror:
+        quarantine_updates[parsed.file_path] = parsed.quarantined
+
+    # Insert message entries
+    if parsed.entries:
+        insert_message_entries(conn, parsed.entries)
+
+    if parsed.limit_events:
+        insert_limit_events(conn, parsed.limit_events)
+
+    if parsed.mcp_calls:
+        insert_mcp_calls(conn, parsed.mcp_calls)
+
+    if parsed.hook_executions:
+        insert_hook_executions(conn, parsed.hook_executions)
+
+    if parsed.request_settings:
+        insert_request_settings(conn, parsed.request_settings)
 
     # Update file tracking
+    entry_count = len(parsed.entries)
     try:
-        stat = os.stat(file_path)
-        update_file_track(conn, file_path, stat.st_mtime_ns, stat.st_size, entry_count)
+        stat = os.stat(parsed.file_path)
+        update_file_track(conn, parsed.file_path, stat.st_mtime_ns, stat.st_size, entry_count)
     except OSError:
         pass
 
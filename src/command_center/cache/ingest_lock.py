@@ -0,0 +1,205 @@
+"""
+Ingestion lock shared by all machines writing the same database.
+
+When ``~/.claude`` lives on a network share used by two machines, both may
+start a refresh at once. Ingestion holds a lock file next to the database
+that records the owner (host, pid) and a heartbeat refreshed every
+HEARTBEAT_INTERVAL_SECONDS by a background thread.
+
+A lock whose heartbeat is older than STALE_LOCK_AFTER_SECONDS (the owner
+crashed, or its machine went to sleep or lost the share) is stolen. On the
+owner's host a lock held by a dead process is stolen right away. Stealing
+renames the lock file first, so two machines never steal the same lock.
+"""
+from __future__ import annotations
+
+import json
+import os
+import socket
+import threading
+import time
+import uuid
+from contextlib import contextmanager
+from typing import Any, Iterator, Optional
+
+from command_center.config import DB_PATH
+
+
+# Default lock file location (next to the database)
+INGEST_LOCK_PATH = os.path.join(os.path.dirname(DB_PATH), "command-center-ingest.lock")
+
+HEARTBEAT_INTERVAL_SECONDS = 15
+
+# Generous compared to the heartbeat, to tolerate clock skew between machines
+STALE_LOCK_AFTER_SECONDS = 120
+
+
+class IngestLockedError(RuntimeError):
+    """Another process (possibly on another machine) is ingesting."""
+
+    def __init__(self, holder: dict[str, Any]):
+        self.holder = holder
+        age = holder.get("heartbeat_age_seconds")
+        super().__init__(
+            f"Another refresh is running on {holder.get('hostname', 'unknown host')} "
+            f"(pid {holder.get('pid')}, last heartbeat "
+            f"{f'{age:.0f}s ago' if age is not None else 'unknown'}); "
+            f"try again later. The lock is taken over if its heartbeat is older than "
+            f"{STALE_LOCK_AFTER_SECONDS}s."
+        )
+
+
+def _pid_alive(pid: int) -> bool:
+    if os.name == "nt":
+        return True  # os.kill would terminate the process on Windows; rely on the heartbeat
+    try:
+        os.kill(pid, 0)
+    except ProcessLookupError:
+        return False
+    except (PermissionError, OSError):
+        return True
+    return True
+
+
+def read_lock(lock_path: str = INGEST_LOCK_PATH, now: Optional[float] = None) -> Optional[dict[str, Any]]:
+    """
+    Read the current lock holder.
+
+    Returns:
+        {"token", "hostname", "pid", "acquired_at", "heartbeat_at",
+         "heartbeat_age_seconds", "stale"} or None if the lock is free
+    """
+    try:
+        with open(lock_path, "r", encoding="utf-8") as f:
+            holder = json.load(f)
+    except FileNotFoundError:
+        return None
+    except (OSError, json.JSONDecodeError):
+        # Half-written or unreadable: judge by file age
+        try:
+            holder = {"heartbeat_at": os.path.getmtime(lock_path)}
+        except OSError:
+            return None
+    if not isinstance(holder, dict):
+        holder = {}
+
+    heartbeat_at = holder.get("heartbeat_at")
+    age = (now or time.time()) - heartbeat_at if isinstance(heartbeat_at, (int, float)) else None
+    holder["heartbeat_age_seconds"] = round(age, 1) if age is not None else None
+    dead_owner = (
+        holder.get("hostname") == socket.gethostname()
+        and isinstance(holder.get("pid"), int)
+        and not _pid_alive(holder["pid"])
+    )
+    holder["stale"] = age is None or age > STALE_LOCK_AFTER_SECONDS or dead_owner
+    return holder
+
+
+def _write_lock(lock_path: str, owner: dict[str, Any]):
+    tmp_path = f"{lock_path}.{owner['token']}.tmp"
+    with open(tmp_path, "w", encoding="utf-8") as f:
+        json.dump(owner, f)
+    os.replace(tmp_path, lock_path)
+
+
+def _steal(lock_path: str, holder: dict[str, Any]) -> bool:
+    """Remove a stale lock; False if someone else replaced or removed it first."""
+    aside = f"{lock_path}.stale-{uuid.uuid4().hex}"
+    try:
+        os.rename(lock_path, aside)
+    except OSError:
+        return False
+    moved = read_lock(aside) or {}
+    if moved.get("token") != holder.get("token"):
+        # A fresh lock was written between reading and renaming; put it back
+        try:
+            os.rename(aside, lock_path)
+        except OSError:
+            pass
+        return False
+    try:
+        os.remove(aside)
+    except OSError:
+        pass
+    return True
+
+
+class IngestLock:
+    """A held ingestion lock with a heartbeat thread."""
+
+    def __init__(self, lock_path: str, owner: dict[str, Any]):
+        self.lock_path = lock_path
+        self.owner = owner
+        self.lost = False
+        self._stop = threading.Event()
+        self._thread = threading.Thread(target=self._heartbeat, daemon=True)
+        self._thread.start()
+
+    def _heartbeat(self):
+        while not self._stop.wait(HEARTBEAT_INTERVAL_SECONDS):
+            holder = read_lock(self.lock_path) or {}
+            if holder.get("token") != self.owner["token"]:
+                self.lost = True  # Stolen after a missed heartbeat (e.g. suspended machine)
+                return
+            self.owner["heartbeat_at"] = time.time()
+            try:
+                _write_lock(self.lock_path, self.owner)
+            except OSError:
+                pass
+
+    def release(self):
+        self._stop.set()
+        self._thread.join()
+        holder = read_lock(self.lock_path) or {}
+        if holder.get("token") == self.owner["token"]:
+            try:
+                os.remove(self.lock_path)
+            except OSError:
+                pass
+
+
+def acquire_ingest_lock(lock_path: str = INGEST_LOCK_PATH, timeout: float = 0) -> IngestLock:
+    """
+    Acquire the ingestion lock, stealing it if stale.
+
+    Args:
+        lock_path: Lock file path
+        timeout: Seconds to wait for a live holder to finish (0 = fail at once)
+
+    Raises:
+        IngestLockedError: If another live process holds the lock
+    """
+    os.makedirs(os.path.dirname(lock_path) or ".", exist_ok=True)
+    now = time.time()
+    owner = {
+        "token": uuid.uuid4().hex,
+        "hostname": socket.gethostname(),
+        "pid": os.getpid(),
+        "acquired_at": now,
+        "heartbeat_at": now,
+    }
+    deadline = time.monotonic() + timeout
+    while True:
+        try:
+            fd = os.open(lock_path, os.O_CREAT | os.O_EXCL | os.O_WRONLY)
+        except FileExistsError:
+            holder = read_lock(lock_path)
+            if holder is None or (holder["stale"] and _steal(lock_path, holder)):
+                continue
+            if time.monotonic() >= deadline:
+                raise IngestLockedError(holder)
+            time.sleep(1)
+            continue
+        with os.fdopen(fd, "w", encoding="utf-8") as f:
+            json.dump(owner, f)
+        return IngestLock(lock_path, owner)
+
+
+@contextmanager
+def ingest_lock(lock_path: str = INGEST_LOCK_PATH, timeout: float = 0) -> Iterator[IngestLock]:
+    """Hold the ingestion lock for the duration of a block (see acquire_ingest_lock)."""
+    lock = acquire_ingest_lock(lock_path, timeout)
+    try:
+        yield lock
+    finally:
+        lock.release()
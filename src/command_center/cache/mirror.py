@@ -0,0 +1,203 @@
+"""
+Compact mirror of parsed message records (zstd-framed binary).
+
+For every ingested .jsonl file the parsed message entries, limit events, MCP
+calls, hook executions and request settings are written to one zstd frame next to the database. A cold rebuild (new
+database, --rebuild-db or --force-rescan) reads the frame instead of
+re-parsing the verbose JSONL when the source file is unchanged.
+
+A mirror file is only used when the source file's mtime and size, the local
+timezone (local timestamps are precomputed) and the record layout all match
+the values stored in its header; otherwise the source is parsed again and
+the mirror rewritten. Costs are stored as computed at ingestion time; use
+reprice_history after price changes.
+
+Frame content (before compression):
+    b"CCM1" | u32 header length | header JSON | marshal((entries, limit_events, mcp_calls, hook_executions, request_settings))
+
+Compression uses the `zstandard` package (pip install command-center[mirror])
+or `compression.zstd` on Python 3.14+.
+"""
+from __future__ import annotations
+
+import hashlib
+import json
+import marshal
+import os
+import struct
+import time
+from dataclasses import astuple, fields
+from pathlib import Path
+from typing import Any, Callable, Optional
+
+from command_center.config import HOME
+from command_center.database.models import HookExecution, LimitEvent, McpCall, MessageEntry, RequestSetting
+from command_center.utils.atomic_write import write_bytes_atomic
+
+
+# Default mirror directory (one .ccm.zst file per source file)
+MIRROR_DIR = os.path.join(HOME, ".claude", "db", "mirror")
+
+MIRROR_MAGIC = b"CCM1"
+MIRROR_SUFFIX = ".ccm.zst"
+ZSTD_LEVEL = 3
+
+
+def _load_codec() -> Optional[tuple[str, Callable[[bytes], bytes], Callable[[bytes], bytes]]]:
+    try:
+        import zstandard
+        return (
+            "zstandard",
+            zstandard.ZstdCompressor(level=ZSTD_LEVEL).compress,
+            zstandard.ZstdDecompressor().decompress,
+        )
+    except ImportError:
+        pass
+    try:
+        from compression import zstd
+        return (
+            "compression.zstd",
+            lambda data: zstd.compress(data, level=ZSTD_LEVEL),
+            zstd.decompress,
+        )
+    except ImportError:
+        return None
+
+
+_CODEC = _load_codec()
+
+
+def mirror_available() -> bool:
+    """Return True if a zstd codec is installed."""
+    return _CODEC is not None
+
+
+def _layout() -> dict[str, Any]:
+    """Values a mirror file must match to be reused."""
+    return {
+        "entry_fields": [f.name for f in fields(MessageEntry)],
+        "limit_fields": [f.name for f in fields(LimitEvent)],
+        "mcp_fields": [f.name for f in fields(McpCall)],
+        "hook_fields": [f.name for f in fields(HookExecution)],
+        "request_setting_fields": [f.name for f in fields(RequestSetting)],
+        "marshal_version": marshal.version,
+        "timezone": [time.timezone, *time.tzname],
+    }
+
+
+def mirror_path(source_file: str, mirror_dir: str = MIRROR_DIR) -> Path:
+    """Return the mirror file path for a source .jsonl file."""
+    digest = hashlib.sha1(source_file.encode("utf-8")).hexdigest()
+    return Path(mirror_dir) / f"{digest}{MIRROR_SUFFIX}"
+
+
+def write_mirror(
+    source_file: str,
+    mtime_ns: int,
+    size_bytes: int,
+    entries: list[MessageEntry],
+    limit_events: list[LimitEvent],
+    mcp_calls: Optional[list[McpCall]] = None,
+    hook_executions: Optional[list[HookExecution]] = None,
+    request_settings: Optional[list[RequestSetting]] = None,
+    mirror_dir: str = MIRROR_DIR
+) -> Optional[Path]:
+    """
+    Write the parsed records of a source file to its mirror file.
+
+    Returns:
+        Path of the mirror file, or None if no zstd codec is installed
+    """
+    if _CODEC is None:
+        return None
+    _name, compress, _decompress = _CODEC
+
+    header = json.dumps({
+        "source_file": source_file,
+        "mtime_ns": mtime_ns,
+        "size_bytes": size_bytes,
+        **_layout(),
+    }).encode("utf-8")
+    body = marshal.dumps((
+        [astuple(entry) for entry in entries],
+        [astuple(event) for event in limit_events],
+        [astuple(call) for call in mcp_calls or []],
+        [astuple(execution) for execution in hook_executions or []],
+        [astuple(setting) for setting in request_settings or []],
+    ))
+    frame = compress(MIRROR_MAGIC + struct.pack("<I", len(header)) + header + body)
+
+    path = mirror_path(source_file, mirror_dir)
+    write_bytes_atomic(path, frame)
+    return path
+
+
+def read_mirror(
+    source_file: str,
+    mtime_ns: int,
+    size_bytes: int,
+    mirror_dir: str = MIRROR_DIR
+) -> Optional[tuple[list[MessageEntry], list[LimitEvent], list[McpCall], list[HookExecution],
+                    list[RequestSetting]]]:
+    """
+    Read the mirrored records of a source file.
+
+    Returns:
+        (entries, limit_events, mcp_calls, hook_executions, request_settings), or None if there is no usable mirror
+        (missing, stale, corrupt, or no zstd codec installed)
+    """
+    if _CODEC is None:
+        return None
+    _name, _compress, decompress = _CODEC
+
+    path = mirror_path(source_file, mirror_dir)
+    try:
+        data = decompress(path.read_bytes())
+        if data[:4] != MIRROR_MAGIC:
+            return None
+        (header_len,) = struct.unpack("<I", data[4:8])
+        header = json.loads(data[8:8 + header_len])
+        expected = {"source_file": source_file, "mtime_ns": mtime_ns, "size_bytes": size_bytes, **_layout()}
+        if any(header.get(key) != value for key, value in expected.items()):
+            return None
+        entry_rows, limit_rows, mcp_rows, hook_rows, setting_rows = marshal.loads(data[8 + header_len:])
+        return (
+            [MessageEntry(*row) for row in entry_rows],
+            [LimitEvent(*row) for row in limit_rows],
+            [McpCall(*row) for row in mcp_rows],
+            [HookExecution(*row) for row in hook_rows],
+            [RequestSetting(*row) for row in setting_rows],
+        )
+    except Exception:
+        # Missing or unreadable mirror - fall back to parsing the source
+        return None
+
+
+def mirror_status(mirror_dir: str = MIRROR_DIR) -> dict[str, Any]:
+    """
+    Summarize the mirror directory.
+
+    Returns:
+        {"available": True, "codec": "zstandard", "path": ..., "files": 120, "bytes": 3456789}
+    """
+    files = list(Path(mirror_dir).glob(f"*{MIRROR_SUFFIX}")) if os.path.isdir(mirror_dir) else []
+    return {
+        "available": _CODEC is not None,
+        "codec": _CODEC[0] if _CODEC else None,
+        "path": mirror_dir,
+        "files": len(files),
+        "bytes": sum(f.stat().st_size for f in files),
+    }
+
+
+def clear_mirror(mirror_dir: str = MIRROR_DIR) -> dict[str, Any]:
+    """
+    Delete all mirror files.
+
+    Returns:
+        {"deleted": 120, "bytes": 3456789}
+    """
+    status = mirror_status(mirror_dir)
+    for path in Path(mirror_dir).glob(f"*{MIRROR_SUFFIX}"):
+        path.unlink(missing_ok=True)
+    return {"deleted": status["files"], "bytes": status["bytes"]}
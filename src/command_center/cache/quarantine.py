@@ -0,0 +1,123 @@
+"""
+Quarantine of malformed JSONL lines.
+
+A line that cannot be decoded or parsed is skipped during ingestion instead
+of failing its whole file. It is recorded, with its file, line number, error
+and (truncated) content, in a JSON lines side file next to the database, so
+it can be inspected with ``quarantined-lines``.
+
+Records are kept per source file: whenever a file is parsed again its
+previous records are replaced, so fixed or deleted lines drop out. A final
+line without a newline is assumed to be still being written and is not
+quarantined.
+"""
+from __future__ import annotations
+
+import json
+import os
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Optional
+
+from command_center.config import HOME
+from command_center.utils.atomic_write import write_text_atomic
+
+
+# Default location for quarantined lines (JSON lines)
+QUARANTINE_PATH = os.path.join(HOME, ".claude", "db", "command-center-quarantine.jsonl")
+
+# Stored excerpt of a quarantined line
+MAX_QUARANTINED_LINE_CHARS = 2000
+
+# Records kept per source file (a completely broken file should not flood the side file)
+MAX_QUARANTINED_LINES_PER_FILE = 100
+
+
+def quarantine_record(source_file: str, line_number: int, error: str, raw: bytes) -> dict[str, Any]:
+    """Build the record for one malformed line."""
+    content = raw.decode("utf-8", errors="replace").rstrip("\r\n")
+    return {
+        "source_file": source_file,
+        "line_number": line_number,
+        "error": error,
+        "content": content[:MAX_QUARANTINED_LINE_CHARS],
+        "truncated": len(content) > MAX_QUARANTINED_LINE_CHARS,
+        "quarantined_at": datetime.now().astimezone().isoformat(),
+    }
+
+
+def _load(path: str) -> list[dict[str, Any]]:
+    try:
+        text = Path(path).read_text(encoding="utf-8")
+    except OSError:
+        return []
+    records = []
+    for line in text.splitlines():
+        try:
+            record = json.loads(line)
+        except json.JSONDecodeError:
+            continue
+        if isinstance(record, dict):
+            records.append(record)
+    return records
+
+
+def replace_quarantined_lines(
+    updates: dict[str, list[dict[str, Any]]],
+    path: str = QUARANTINE_PATH
+) -> int:
+    """
+    Replace the records of re-parsed files.
+
+    Args:
+        updates: Dict mapping source file → its quarantined lines ([] if clean)
+        path: Quarantine file path
+
+    Returns:
+        Number of records written for the updated files
+    """
+    existing = _load(path)
+    kept = [r for r in existing if r.get("source_file") not in updates]
+    added = [
+        record
+        for source_file in sorted(updates)
+        for record in updates[source_file][:MAX_QUARANTINED_LINES_PER_FILE]
+    ]
+    if not added and len(kept) == len(existing):
+        return 0
+
+    write_text_atomic(path, "".join(json.dumps(record, ensure_ascii=False) + "\n" for record in kept + added))
+    return len(added)
+
+
+def read_quarantined_lines(
+    limit: int = 100,
+    source_file: Optional[str] = None,
+    path: str = QUARANTINE_PATH
+) -> dict[str, Any]:
+    """
+    Read quarantined lines, newest first.
+
+    Args:
+        limit: Maximum number of lines to return
+        source_file: Only return lines of this file (optional)
+        path: Quarantine file path
+
+    Returns:
+        {"total": 12, "files": 3, "lines": [{"source_file", "line_number", "error",
+         "content", "truncated", "quarantined_at"}]}
+    """
+    records = _load(path)
+    matching = [r for r in records if not source_file or r.get("source_file") == source_file]
+    matching.sort(key=lambda r: (r.get("quarantined_at") or "", r.get("line_number") or 0), reverse=True)
+    return {
+        "total": len(matching),
+        "files": len({r.get("source_file") for r in matching}),
+        "lines": matching[:limit],
+    }
+
+
+def quarantine_summary(path: str = QUARANTINE_PATH) -> dict[str, int]:
+    """Count quarantined lines and the files they come from."""
+    records = _load(path)
+    return {"lines": len(records), "files": len({r.get("source_file") for r in records})}
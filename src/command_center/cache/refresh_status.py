@@ -0,0 +1,258 @@
+"""
+Refresh status tracking.
+
+Each refresh (full, partial or as part of a dashboard request) records its
+progress in a small JSON file next to the database, so another process (the
+desktop app polling ``refresh-status``) can show "data current as of 14:32"
+or "refreshing 120/480 files" without touching the running refresh.
+
+While a refresh runs it also reports progress events (mode, state, files
+done/total, percent) for the desktop app's progress bar: to the sink set with
+``set_progress_sink`` (the worker forwards them on its protocol stream), or
+as ``{"progress": {...}}`` lines on stderr when ``COMMAND_CENTER_PROGRESS=1``.
+
+Per data root (Claude config directory) the status also lists the files
+still pending ingestion and the watermarks stored in ``file_tracks``, and
+it counts the malformed lines set aside in quarantine.
+"""
+from __future__ import annotations
+
+import json
+import os
+import sqlite3
+import sys
+import time
+from contextlib import contextmanager
+from datetime import datetime, timedelta
+from typing import Any, Callable, Iterator, Optional
+
+from command_center.cache.file_tracker import detect_file_changes
+from command_center.cache.quarantine import quarantine_summary
+from command_center.claude_configs import get_claude_dirs
+from command_center.collectors.file_scanner import scan_root_jsonl_files
+from command_center.config import HOME
+from command_center.database.queries import get_file_tracks
+from command_center.utils.atomic_write import write_json_atomic
+
+
+# Default location for the refresh status JSON
+REFRESH_STATUS_JSON_PATH = os.path.join(HOME, ".claude", "db", "command-center-refresh-status.json")
+
+# Minimum interval between progress writes
+PROGRESS_WRITE_INTERVAL_SECONDS = 0.5
+
+# A running refresh without a progress write for this long is reported as stale
+STALE_REFRESH_AFTER = timedelta(minutes=10)
+
+# Set to 1 (by the desktop bridge) to write progress events to stderr
+PROGRESS_ENV = "COMMAND_CENTER_PROGRESS"
+
+ProgressSink = Callable[[dict[str, Any]], None]
+
+_progress_sink: Optional[ProgressSink] = None
+
+
+def set_progress_sink(sink: Optional[ProgressSink]) -> Optional[ProgressSink]:
+    """Send progress events to ``sink`` (None: stderr if enabled); returns the previous sink."""
+    global _progress_sink
+    previous, _progress_sink = _progress_sink, sink
+    return previous
+
+
+def emit_progress(event: dict[str, Any]):
+    """Report a progress event; never fails the refresh."""
+    try:
+        if _progress_sink is not None:
+            _progress_sink(event)
+        elif os.environ.get(PROGRESS_ENV) == "1":
+            sys.stderr.write(json.dumps({"progress": event}) + "\n")
+            sys.stderr.flush()
+    except (OSError, ValueError):
+        pass
+
+
+def load_refresh_status(json_path: str = REFRESH_STATUS_JSON_PATH) -> dict[str, Any]:
+    """Load the stored status; a missing or unreadable file yields {}."""
+    try:
+        with open(json_path, "r", encoding="utf-8") as f:
+            loaded = json.load(f)
+    except (OSError, json.JSONDecodeError):
+        return {}
+    return loaded if isinstance(loaded, dict) else {}
+
+
+def save_refresh_status(status: dict[str, Any], json_path: str = REFRESH_STATUS_JSON_PATH):
+    """Save the status (atomically, so readers never see half a file)."""
+    write_json_atomic(json_path, status)
+
+
+class RefreshTracker:
+    """Records the progress and outcome of one refresh."""
+
+    def __init__(self, mode: str, json_path: str = REFRESH_STATUS_JSON_PATH):
+        self.mode = mode
+        self.json_path = json_path
+        self.status = load_refresh_status(json_path)
+        self.updated_files = 0
+        self._last_write = 0.0
+
+    def _save(self, **fields):
+        self.status.update(fields, updated_at=datetime.now().isoformat())
+        try:
+            save_refresh_status(self.status, self.json_path)
+        except OSError:
+            pass  # Status is informational; never fail a refresh over it
+        self._last_write = time.monotonic()
+
+    def _emit(self, state: str, files_done: int = 0, files_total: Optional[int] = None):
+        percent = 100.0 if state == "done" else (
+            round(files_done * 100 / files_total, 1) if files_total else 0.0
+        )
+        emit_progress({
+            "mode": self.mode,
+            "state": state,
+            "files_done": files_done,
+            "files_total": files_total,
+            "percent": percent,
+        })
+
+    def start(self):
+        self._save(
+            state="running",
+            current={
+                "mode": self.mode,
+                "started_at": datetime.now().isoformat(),
+                "pid": os.getpid(),
+                "files_done": 0,
+                "files_total": None,
+            },
+        )
+        self._emit("running")
+
+    def progress(self, files_done: int, files_total: int):
+        """Record progress; writes are throttled except for the last file."""
+        current = self.status.get("current") or {}
+        current.update(files_done=files_done, files_total=files_total)
+        due = time.monotonic() - self._last_write >= PROGRESS_WRITE_INTERVAL_SECONDS
+        if due or files_done >= files_total:
+            self._save(current=current)
+            self._emit("running", files_done, files_total)
+
+    def finish(self):
+        current = self.status.get("current") or {}
+        self._save(
+            state="idle",
+            current=None,
+            last_success={
+                "mode": self.mode,
+                "started_at": current.get("started_at"),
+                "finished_at": datetime.now().isoformat(),
+                "updated_files": self.updated_files,
+            },
+        )
+        self._emit("done", current.get("files_done") or 0, current.get("files_total"))
+
+    def fail(self, error: str):
+        self._save(
+            state="failed",
+            current=None,
+            last_error={"mode": self.mode, "failed_at": datetime.now().isoformat(), "error": error},
+        )
+        self._emit("failed")
+
+
+@contextmanager
+def track_refresh(mode: str, json_path: str = REFRESH_STATUS_JSON_PATH) -> Iterator[RefreshTracker]:
+    """
+    Track a refresh: marks it running, then idle (set ``tracker.updated_files``)
+    or failed if the block raises.
+    """
+    tracker = RefreshTracker(mode, json_path)
+    tracker.start()
+    try:
+        yield tracker
+    except Exception as e:
+        tracker.fail(str(e))
+        raise
+    tracker.finish()
+
+
+def _root_watermarks(conn: sqlite3.Connection, root: str) -> dict[str, Any]:
+    """Tracked file count and scan watermarks for files under a data root."""
+    prefix = os.path.join(root, "projects", "")
+    row = conn.execute("""
+        SELECT COUNT(*), MAX(last_scanned), MAX(mtime_ns), COALESCE(SUM(entry_count), 0)
+        FROM file_tracks
+        WHERE file_path >= ? AND file_path < ?
+    """, (prefix, prefix[:-1] + chr(ord(prefix[-1]) + 1))).fetchone()
+    last_scanned = (
+        datetime.strptime(row[1], "%Y-%m-%d %H:%M:%S").isoformat() + "Z" if row[1] else None
+    )
+    newest_file = datetime.fromtimestamp(row[2] / 1_000_000_000).isoformat() if row[2] else None
+    return {
+        "tracked_files": row[0],
+        "entries": row[3],
+        "last_scanned_at": last_scanned,
+        "newest_file_at": newest_file,
+    }
+
+
+def get_refresh_status(
+    conn: sqlite3.Connection,
+    json_path: str = REFRESH_STATUS_JSON_PATH,
+    now: Optional[datetime] = None
+) -> dict[str, Any]:
+    """
+    Get refresh progress, the last successful refresh and per-root freshness.
+
+    Returns:
+        {
+            "state": "idle" | "running" | "failed" | "stale",
+            "current": {"mode", "started_at", "pid", "files_done", "files_total"} | None,
+            "last_success": {"mode", "started_at", "finished_at", "updated_files"} | None,
+            "last_error": {"mode", "failed_at", "error"} | None,
+            "data_current_as_of": finished_at of the last success,
+            "pending_files": 4,
+            "quarantined": {"lines": 2, "files": 1},
+            "roots": [{"path", "files", "pending_files", "tracked_files", "entries",
+                       "last_scanned_at", "newest_file_at"}]
+        }
+    """
+    status = load_refresh_status(json_path)
+    state = status.get("state") or "idle"
+    if state == "running":
+        # A refresh that stopped writing progress was killed or hung
+        updated_at = status.get("updated_at")
+        try:
+            stale = (now or datetime.now()) - datetime.fromisoformat(updated_at) > STALE_REFRESH_AFTER
+        except (TypeError, ValueError):
+            stale = True
+        if stale:
+            state = "stale"
+
+    tracked = get_file_tracks(conn)
+    roots = []
+    for root in get_claude_dirs():
+        files = scan_root_jsonl_files(root)
+        pending = [
+            fs.path for fs in detect_file_changes(files, tracked)
+            if fs.status in ("new", "modified")
+        ]
+        roots.append({
+            "path": root,
+            "files": len(files),
+            "pending_files": len(pending),
+            **_root_watermarks(conn, root),
+        })
+
+    last_success = status.get("last_success")
+    return {
+        "state": state,
+        "current": status.get("current") if state in ("running", "stale") else None,
+        "last_success": last_success,
+        "last_error": status.get("last_error"),
+        "data_current_as_of": last_success.get("finished_at") if last_success else None,
+        "pending_files": sum(root["pending_files"] for root in roots),
+        "quarantined": quarantine_summary(),
+        "roots": roots,
+    }
@@ -0,0 +1,170 @@
+"""
+Holiday and vacation calendar overlay.
+
+Marked date ranges are days off: they are left out of the daily averages
+of the dashboard totals, they neither extend nor break streaks, and they are
+returned with timeline and heatmap payloads so the UI can shade them.
+The overlay is stored in a small JSON file next to the database.
+"""
+from __future__ import annotations
+
+import json
+import os
+from datetime import date, datetime, timedelta
+from typing import Any, Optional
+
+from command_center.utils.atomic_write import write_json_atomic
+
+
+# Default location for the calendar overlay JSON
+CALENDAR_JSON_PATH = os.path.expanduser("~/.claude/db/command-center-calendar.json")
+
+PERIOD_KINDS = ("vacation", "holiday")
+
+MAX_PERIODS = 200
+MAX_LABEL_LENGTH = 100
+
+# A single period longer than this is almost certainly a typo in the year
+MAX_PERIOD_DAYS = 366
+
+
+def _parse_day(value: Any) -> date:
+    try:
+        return datetime.strptime(str(value), "%Y-%m-%d").date()
+    except ValueError:
+        raise ValueError(f"Invalid date: {value} (expected YYYY-MM-DD)")
+
+
+def load_calendar_overlay(json_path: str = CALENDAR_JSON_PATH) -> list[dict]:
+    """
+    Load the overlay periods.
+
+    Returns:
+        [{"from", "to", "kind", "label"}] sorted by start; unreadable files yield []
+    """
+    try:
+        with open(json_path, "r", encoding="utf-8") as f:
+            loaded = json.load(f)
+    except (OSError, json.JSONDecodeError):
+        return []
+    periods = loaded.get("periods") if isinstance(loaded, dict) else None
+    return periods if isinstance(periods, list) else []
+
+
+def set_calendar_overlay(periods: list[dict], json_path: str = CALENDAR_JSON_PATH) -> dict:
+    """
+    Replace the overlay with the given periods.
+
+    Args:
+        periods: [{"from": "YYYY-MM-DD", "to": "YYYY-MM-DD", "kind": "vacation" | "holiday",
+                   "label": optional}]; an empty list clears the overlay
+
+    Returns:
+        {"periods": [...]} as stored (normalized and sorted by start)
+
+    Raises:
+        ValueError: For invalid dates, kinds or labels, or too many periods
+    """
+    if not isinstance(periods, list):
+        raise ValueError("Periods must be a list")
+    if len(periods) > MAX_PERIODS:
+        raise ValueError(f"At most {MAX_PERIODS} calendar periods can be defined")
+
+    stored = []
+    for period in periods:
+        if not isinstance(period, dict):
+            raise ValueError("Each period must be an object with from, to and kind")
+        start = _parse_day(period.get("from"))
+        end = _parse_day(period.get("to"))
+        if end < start:
+            raise ValueError(f"Period ends before it starts: {start} - {end}")
+        if (end - start).days >= MAX_PERIOD_DAYS:
+            raise ValueError(f"Period is longer than {MAX_PERIOD_DAYS} days: {start} - {end}")
+        kind = period.get("kind", "vacation")
+        if kind not in PERIOD_KINDS:
+            raise ValueError(f"Unknown period kind: {kind} (expected {', '.join(PERIOD_KINDS)})")
+        label = period.get("label")
+        if label is not None:
+            label = str(label).strip()[:MAX_LABEL_LENGTH] or None
+        stored.append({"from": start.isoformat(), "to": end.isoformat(), "kind": kind, "label": label})
+
+    stored.sort(key=lambda p: (p["from"], p["to"]))
+    write_json_atomic(json_path, {"periods": stored})
+    return {"periods": stored}
+
+
+def excluded_days(
+    periods: list[dict],
+    date_from: Optional[str] = None,
+    date_to: Optional[str] = None
+) -> set[str]:
+    """Dates (YYYY-MM-DD) covered by the overlay, optionally limited to a range."""
+    days: set[str] = set()
+    for period in periods:
+        start, end = period.get("from"), period.get("to")
+        if not start or not end:
+            continue
+        if date_from and date_from > start:
+            start = date_from
+        if date_to and date_to < end:
+            end = date_to
+        days.update(_days(start, end))
+    return days
+
+
+def overlay_for_range(periods: list[dict], date_from: str, date_to: str) -> list[dict]:
+    """
+    Periods that overlap a range, clipped to it (shaded regions of a timeline).
+
+    Returns:
+        [{"from", "to", "kind", "label"}] with from/to inside the range
+    """
+    return [
+        {**period, "from": max(period["from"], date_from), "to": min(period["to"], date_to)}
+        for period in periods
+        if period.get("from") and period.get("to")
+        and period["from"] <= date_to and period["to"] >= date_from
+    ]
+
+
+def daily_average(daily_totals: list[dict], date_from: str, date_to: str, excluded: set[str]) -> dict:
+    """
+    Average usage per day of a range, leaving out overlay days and their usage.
+
+    Days after today are not counted, so the current month is averaged over
+    the days that have passed.
+
+    Args:
+        daily_totals: Per-day rows of query_timeline_data (period, messages, tokens, cost)
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        excluded: Overlay days (see excluded_days)
+
+    Returns:
+        {"days": 18, "excluded_days": 4, "messages": 120.5, "tokens": 804211, "cost": 3.12}
+    """
+    elapsed = _days(date_from, min(date_to, date.today().isoformat()))
+    days = len([day for day in elapsed if day not in excluded])
+    included = [row for row in daily_totals if row["period"] not in excluded]
+
+    def average(key: str) -> float:
+        return sum(row.get(key) or 0 for row in included) / days if days else 0
+
+    return {
+        "days": days,
+        "excluded_days": len(elapsed) - days,
+        "messages": round(average("messages"), 1),
+        "tokens": round(average("tokens")),
+        "cost": round(average("cost"), 4),
+    }
+
+
+def _days(date_from: str, date_to: str) -> list[str]:
+    if date_from > date_to:
+        return []
+    day, last = _parse_day(date_from), _parse_day(date_to)
+    days = []
+    while day <= last:
+        days.append(day.isoformat())
+        day += timedelta(days=1)
+    return days
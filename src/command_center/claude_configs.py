@@ -0,0 +1,338 @@
+"""
+Detection of multiple Claude Code config directories and account bindings.
+
+Claude Code keeps its data in ``~/.claude`` by default, but users juggling
+several logins commonly point ``CLAUDE_CONFIG_DIR`` at separate directories
+(e.g. ``~/.claude-work``). Every such directory has its own ``projects/``
+tree of session logs. Each message remembers the config dir it was read
+from, and a binding file maps config dirs to usage accounts so stats stay
+attributed correctly after switching logins.
+"""
+from __future__ import annotations
+
+import glob
+import json
+import os
+import sqlite3
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Optional
+
+from command_center.config import HOME, CLAUDE_DIRS
+from command_center.database.queries import ProjectScope, project_scope_param
+from command_center.utils.atomic_write import write_json_atomic
+
+
+# Default location for config dir → account bindings
+CLAUDE_CONFIGS_JSON_PATH = os.path.join(HOME, ".claude", "db", "command-center-claude-configs.json")
+
+# Glob patterns for conventional extra config dirs
+CONFIG_DIR_PATTERNS = [
+    os.path.join(HOME, ".claude-*"),
+    os.path.join(HOME, ".config", "claude-*"),
+]
+
+
+def _normalize(path: str) -> str:
+    return os.path.abspath(os.path.expanduser(path))
+
+
+def get_claude_dirs() -> list[str]:
+    """
+    Return all Claude config directories that may contain session logs.
+
+    Includes the default locations, ``CLAUDE_CONFIG_DIR`` if set,
+    conventional ``~/.claude-*`` variants, and any directory with a saved
+    binding. Only directories with a ``projects/`` subdirectory are
+    returned (plus the defaults, which are always listed).
+    """
+    dirs: list[str] = [_normalize(d) for d in CLAUDE_DIRS]
+
+    env_dir = os.environ.get("CLAUDE_CONFIG_DIR")
+    candidates: list[str] = [env_dir] if env_dir else []
+    for pattern in CONFIG_DIR_PATTERNS:
+        candidates.extend(sorted(glob.glob(pattern)))
+    candidates.extend(load_config_bindings().keys())
+
+    for candidate in candidates:
+        path = _normalize(candidate)
+        if path in dirs:
+            continue
+        if os.path.isdir(os.path.join(path, "projects")):
+            dirs.append(path)
+
+    return dirs
+
+
+def extract_config_dir(file_path: str | Path) -> Optional[str]:
+    """
+    Extract the config directory a session file belongs to.
+
+    Pattern: {config_dir}/projects/{project_id}/{session}.jsonl
+
+    Returns:
+        Absolute config dir path, or None if the file is not under a projects/ tree
+    """
+    parts = Path(file_path).resolve().parts
+    for i in range(len(parts) - 1, 0, -1):
+        if parts[i] == "projects":
+            return str(Path(*parts[:i]))
+    return None
+
+
+def load_config_bindings(json_path: str = CLAUDE_CONFIGS_JSON_PATH) -> dict[str, dict[str, Any]]:
+    """
+    Load config dir bindings.
+
+    Example:
+        {
+            "/home/xai/.claude-work": {"account_email": "me@work.com", "bound_at": "..."}
+        }
+    """
+    path = Path(json_path)
+    if not path.exists():
+        return {}
+    try:
+        with open(path, "r") as f:
+            return json.load(f)
+    except (json.JSONDecodeError, IOError):
+        return {}
+
+
+def save_config_bindings(bindings: dict[str, dict[str, Any]], json_path: str = CLAUDE_CONFIGS_JSON_PATH):
+    """Save config dir bindings."""
+    write_json_atomic(json_path, bindings)
+
+
+def bind_config_dir(
+    config_dir: str,
+    account_email: Optional[str],
+    json_path: str = CLAUDE_CONFIGS_JSON_PATH
+) -> dict[str, Any]:
+    """
+    Bind a config directory to a usage account (or unbind with empty email).
+
+    Args:
+        config_dir: Claude config directory path
+        account_email: Usage account email; None or empty removes the binding
+        json_path: Path to bindings JSON file
+
+    Returns:
+        {"config_dir": ..., "account_email": ...}
+
+    Raises:
+        ValueError: If the directory does not exist
+    """
+    path = _normalize(config_dir)
+    if not os.path.isdir(path):
+        raise ValueError(f"Config directory not found: {config_dir}")
+
+    bindings = load_config_bindings(json_path)
+    email = (account_email or "").strip()
+    if email:
+        bindings[path] = {
+            "account_email": email,
+            "bound_at": datetime.now().astimezone().isoformat(),
+        }
+    else:
+        bindings.pop(path, None)
+
+    save_config_bindings(bindings, json_path)
+    return {"config_dir": path, "account_email": email or None}
+
+
+def _read_logged_in_email(config_dir: str) -> Optional[str]:
+    """Read the OAuth account email Claude Code stored for a config dir, if any."""
+    candidates = [os.path.join(config_dir, ".claude.json")]
+    if config_dir == _normalize(os.path.join(HOME, ".claude")):
+        # Default config keeps its state file next to the directory
+        candidates.append(os.path.join(HOME, ".claude.json"))
+
+    for candidate in candidates:
+        try:
+            with open(candidate, "r") as f:
+                data = json.load(f)
+        except (OSError, json.JSONDecodeError):
+            continue
+        email = (data.get("oauthAccount") or {}).get("emailAddress")
+        if email:
+            return email
+    return None
+
+
+def detect_claude_accounts(conn: Optional[sqlite3.Connection] = None) -> list[dict[str, Any]]:
+    """
+    List detected Claude config directories with activity and bindings.
+
+    Args:
+        conn: Optional database connection for ingested message counts
+
+    Returns:
+        List of dicts with config_dir, is_default, from_env, session_files,
+        last_activity, detected_email, account_email, messages
+    """
+    bindings = load_config_bindings()
+    env_dir = os.environ.get("CLAUDE_CONFIG_DIR")
+    env_dir = _normalize(env_dir) if env_dir else None
+    default_dirs = {_normalize(d) for d in CLAUDE_DIRS}
+
+    message_counts: dict[str, int] = {}
+    if conn is not None:
+        cursor = conn.cursor()
+        cursor.execute("""
+            SELECT config_dir, COUNT(*)
+            FROM message_entries
+            WHERE config_dir IS NOT NULL
+            GROUP BY config_dir
+        """)
+        message_counts = {row[0]: row[1] for row in cursor.fetchall()}
+
+    accounts = []
+    for config_dir in get_claude_dirs():
+        projects_dir = os.path.join(config_dir, "projects")
+        session_files = 0
+        last_mtime = None
+        if os.path.isdir(projects_dir):
+            for root, _dirs, files in os.walk(projects_dir):
+                for filename in files:
+                    if not filename.endswith(".jsonl"):
+                        continue
+                    session_files += 1
+                    try:
+                        mtime = os.path.getmtime(os.path.join(root, filename))
+                    except OSError:
+                        continue
+                    if last_mtime is None or mtime > last_mtime:
+                        last_mtime = mtime
+
+        if session_files == 0 and config_dir not in bindings and config_dir != env_dir:
+            # Default locations that were never used are just noise
+            continue
+
+        accounts.append({
+            "config_dir": config_dir,
+            "is_default": config_dir in default_dirs,
+            "from_env": config_dir == env_dir,
+            "session_files": session_files,
+            "last_activity": (
+                datetime.fromtimestamp(last_mtime).astimezone().isoformat() if last_mtime else None
+            ),
+            "detected_email": _read_logged_in_email(config_dir),
+            "account_email": bindings.get(config_dir, {}).get("account_email"),
+            "messages": message_counts.get(config_dir, 0),
+        })
+
+    return accounts
+
+
+def query_account_message_totals(
+    conn: sqlite3.Connection,
+    date_from: str,
+    date_to: str,
+    project_id: ProjectScope = None
+) -> dict[str, dict[str, Any]]:
+    """
+    Aggregate message totals per bound usage account.
+
+    Messages from config dirs without a binding are not included.
+
+    Returns:
+        Dict mapping account email → {messages, sessions, tokens, cost, config_dirs}
+    """
+    bindings = load_config_bindings()
+    if not bindings:
+        return {}
+
+    cursor = conn.cursor()
+    if project_id:
+        cursor.execute("""
+            SELECT config_dir, COUNT(*), COUNT(DISTINCT session_id),
+                   SUM(total_tokens), SUM(COALESCE(cost_usd, 0))
+            FROM message_entries
+            WHERE date >= ? AND date <= ? AND project_id IN (SELECT value FROM json_each(?))
+                  AND config_dir IS NOT NULL
+            GROUP BY config_dir
+        """, (date_from, date_to, project_scope_param(project_id)))
+    else:
+        cursor.execute("""
+            SELECT config_dir, COUNT(*), COUNT(DISTINCT session_id),
+                   SUM(total_tokens), SUM(COALESCE(cost_usd, 0))
+            FROM message_entries
+            WHERE date >= ? AND date <= ? AND config_dir IS NOT NULL
+            GROUP BY config_dir
+        """, (date_from, date_to))
+
+    totals: dict[str, dict[str, Any]] = {}
+    for config_dir, messages, sessions, tokens, cost in cursor.fetchall():
+        email = bindings.get(config_dir, {}).get("account_email")
+        if not email:
+            continue
+        entry = totals.setdefault(email, {
+            "messages": 0, "sessions": 0, "tokens": 0, "cost": 0.0, "config_dirs": [],
+        })
+        entry["messages"] += messages or 0
+        entry["sessions"] += sessions or 0
+        entry["tokens"] += tokens or 0
+        entry["cost"] = round(entry["cost"] + (cost or 0), 4)
+        entry["config_dirs"].append(config_dir)
+
+    return totals
+
+
+def query_account_usage(
+    conn: sqlite3.Connection,
+    email: str,
+    date_from: str,
+    date_to: str,
+) -> dict[str, Any]:
+    """
+    Message totals and daily usage of one usage account (its bound config dirs).
+
+    Returns:
+        {
+            "config_dirs": [...],
+            "totals": {"messages", "sessions", "tokens", "cost"},
+            "daily": [{"date", "messages", "tokens", "cost"}]
+        }
+    """
+    config_dirs = sorted(
+        config_dir for config_dir, binding in load_config_bindings().items()
+        if binding.get("account_email") == email
+    )
+    totals: dict[str, Any] = {"messages": 0, "sessions": 0, "tokens": 0, "cost": 0.0}
+    daily: list[dict[str, Any]] = []
+    if not config_dirs:
+        return {"config_dirs": [], "totals": totals, "daily": daily}
+
+    params = (date_from, date_to, json.dumps(config_dirs))
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT COUNT(*), COUNT(DISTINCT session_id),
+               SUM(total_tokens), SUM(COALESCE(cost_usd, 0))
+        FROM message_entries
+        WHERE date >= ? AND date <= ? AND config_dir IN (SELECT value FROM json_each(?))
+    """, params)
+    messages, sessions, tokens, cost = cursor.fetchone()
+    totals = {
+        "messages": messages or 0,
+        "sessions": sessions or 0,
+        "tokens": tokens or 0,
+        "cost": round(cost or 0, 4),
+    }
+
+    cursor.execute("""
+        SELECT date, COUNT(*), SUM(total_tokens), SUM(COALESCE(cost_usd, 0))
+        FROM message_entries
+        WHERE date >= ? AND date <= ? AND config_dir IN (SELECT value FROM json_each(?))
+        GROUP BY date
+        ORDER BY date
+    """, params)
+    for day, day_messages, day_tokens, day_cost in cursor.fetchall():
+        daily.append({
+            "date": day,
+            "messages": day_messages or 0,
+            "tokens": day_tokens or 0,
+            "cost": round(day_cost or 0, 4),
+        })
+
+    return {"config_dirs": config_dirs, "totals": totals, "daily": daily}
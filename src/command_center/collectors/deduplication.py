@@ -1,7 +1,17 @@
 """
 Deduplication logic for message entries
 """
-from typing import Optional
+import hashlib
+import json
+from typing import Any, Optional, Sequence
+
+# Fields of a message entry covered by its content hash, in hashing order.
+# Local-only fields (source file, project, config dir, local time) are left
+# out so the same logged message hashes the same on every machine.
+CONTENT_HASH_FIELDS = (
+    "session_id", "message_id", "request_id", "timestamp", "model",
+    "input_tokens", "output_tokens", "cache_read_tokens", "cache_write_tokens",
+)
 
 
 def compute_entry_hash(entry: dict) -> Optional[str]:
@@ -24,3 +34,25 @@ def compute_entry_hash(entry: dict) -> Optional[str]:
         return None
 
     return f"{message_id}:{request_id}"
+
+
+def hash_content_fields(values: Sequence[Any]) -> str:
+    """
+    Content hash of the CONTENT_HASH_FIELDS values of a message entry.
+
+    Returns:
+        First 32 hex digits of the SHA-256 of the values as compact JSON
+    """
+    payload = json.dumps(list(values), separators=(",", ":"))
+    return hashlib.sha256(payload.encode("utf-8")).hexdigest()[:32]
+
+
+def compute_content_hash(entry: Any) -> str:
+    """
+    Stable content hash of a message entry (anything with the CONTENT_HASH_FIELDS attributes).
+
+    Unlike the entry hash it also covers the session, timestamp, model and
+    usage, so a re-ingested message with different usage gets a new hash,
+    while the same record hashes identically across machines and imports.
+    """
+    return hash_content_fields([getattr(entry, name) for name in CONTENT_HASH_FIELDS])
@@ -0,0 +1,92 @@
+"""
+Parser for per-request effort/thinking settings and response latency from JSONL entries
+
+User prompts carry the thinking settings they were sent with
+(``thinkingMetadata.level``, e.g. ``none``, ``medium``, ``high``, and
+``maxThinkingTokens``). Each assistant response is attributed the settings
+of the prompt that started its turn; its latency is the time from the
+preceding user line (prompt or tool result) to the first line of the
+response.
+"""
+from typing import Any, Optional
+
+from command_center.collectors.deduplication import compute_entry_hash
+from command_center.database.models import RequestSetting
+from command_center.utils.date_helpers import parse_iso_timestamp
+
+
+def _prompt_settings(data: dict[str, Any]) -> Optional[tuple[Optional[str], Optional[int]]]:
+    """(effort, thinking budget) of a user prompt, or None if the line is not a prompt."""
+    if data.get("type") != "user" or data.get("isMeta") or data.get("isCompactSummary"):
+        return None
+    message = data.get("message") if isinstance(data.get("message"), dict) else {}
+    content = message.get("content")
+    if isinstance(content, list) and any(
+        isinstance(block, dict) and block.get("type") == "tool_result" for block in content
+    ):
+        return None
+    metadata = data.get("thinkingMetadata") if isinstance(data.get("thinkingMetadata"), dict) else {}
+    level = metadata.get("level") or data.get("effort")
+    budget = metadata.get("maxThinkingTokens")
+    return (
+        str(level).lower() if level else None,
+        int(budget) if isinstance(budget, (int, float)) else None,
+    )
+
+
+def extract_request_settings(lines: list[dict[str, Any]]) -> list[RequestSetting]:
+    """
+    Collect the effort setting, thinking use and latency of each response in a session file.
+
+    Args:
+        lines: Parsed JSONL lines, in file order
+
+    Returns:
+        List of RequestSetting, one per message entry hash
+    """
+    # Main thread and subagent sidechains have their own prompts and timing
+    effort: dict[bool, tuple[Optional[str], Optional[int]]] = {}
+    last_user_at: dict[bool, Any] = {}
+    settings: dict[str, RequestSetting] = {}
+
+    for data in lines:
+        thread = bool(data.get("isSidechain"))
+        timestamp = parse_iso_timestamp(data["timestamp"]) if data.get("timestamp") else None
+
+        if data.get("type") == "user":
+            prompt = _prompt_settings(data)
+            if prompt is not None:
+                effort[thread] = prompt
+            if timestamp is not None:
+                last_user_at[thread] = timestamp
+            continue
+
+        if data.get("type") != "assistant":
+            continue
+        entry_hash = compute_entry_hash(data)
+        if not entry_hash:
+            continue
+        message = data.get("message") if isinstance(data.get("message"), dict) else {}
+        content = message.get("content") if isinstance(message.get("content"), list) else []
+        used_thinking = any(
+            isinstance(block, dict) and block.get("type") in ("thinking", "redacted_thinking") for block in content
+        )
+
+        setting = settings.get(entry_hash)
+        if setting is None:
+            level, budget = effort.get(thread, (None, None))
+            started = last_user_at.get(thread)
+            latency_ms = None
+            if started is not None and timestamp is not None and timestamp >= started:
+                latency_ms = int((timestamp - started).total_seconds() * 1000)
+            settings[entry_hash] = RequestSetting(
+                entry_hash=entry_hash,
+                effort=level,
+                thinking_budget=budget,
+                used_thinking=used_thinking,
+                latency_ms=latency_ms,
+            )
+        elif used_thinking:
+            setting.used_thinking = True
+
+    return list(settings.values())
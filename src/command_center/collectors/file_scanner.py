@@ -4,29 +4,45 @@ File scanning - discover .jsonl files
 import os
 from typing import List
 
-from command_center.config import CLAUDE_DIRS
+from command_center.claude_configs import get_claude_dirs
 
 
 def scan_jsonl_files() -> List[str]:
     """
     Scan for all .jsonl files in Claude project directories.
 
+    Covers every detected Claude config directory (default locations,
+    CLAUDE_CONFIG_DIR and ~/.claude-* variants).
+
     Returns:
         List of absolute file paths to .jsonl files
     """
     jsonl_files = []
 
-    for base_dir in CLAUDE_DIRS:
-        projects_dir = os.path.join(base_dir, "projects")
+    for base_dir in get_claude_dirs():
+        jsonl_files.extend(scan_root_jsonl_files(base_dir))
+
+    return jsonl_files
+
+
+def scan_root_jsonl_files(base_dir: str) -> List[str]:
+    """
+    Scan one Claude config directory for .jsonl files under its projects/ tree.
+
+    Returns:
+        List of absolute file paths (empty if there is no projects/ directory)
+    """
+    jsonl_files = []
+    projects_dir = os.path.join(base_dir, "projects")
 
-        if not os.path.isdir(projects_dir):
-            continue
+    if not os.path.isdir(projects_dir):
+        return jsonl_files
 
-        # Recursively walk project directories
-        for root, dirs, files in os.walk(projects_dir):
-            for filename in files:
-                if filename.endswith(".jsonl"):
-                    full_path = os.path.join(root, filename)
-                    jsonl_files.append(full_path)
+    # Recursively walk project directories
+    for root, dirs, files in os.walk(projects_dir):
+        for filename in files:
+            if filename.endswith(".jsonl"):
+                full_path = os.path.join(root, filename)
+                jsonl_files.append(full_path)
 
     return jsonl_files
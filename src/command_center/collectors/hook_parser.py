@@ -0,0 +1,122 @@
+"""
+Parser for Claude Code hook executions (PreToolUse, PostToolUse, Stop, ...) from JSONL entries
+
+Three log formats are recognized:
+
+1. Hook attachments (``type: "attachment"``, ``attachment.type: "hook_success"``,
+   ``"hook_non_blocking_error"``, ``"hook_blocking_error"``, ...) with the hook
+   name, command, exit code and duration
+2. Stop hook summaries (``type: "system"``, ``subtype: "stop_hook_summary"``)
+   listing the commands that ran and their errors
+3. Older system messages like ``PostToolUse:Edit [./fmt.sh] completed successfully``
+   (no duration); only used for files without hook attachments
+"""
+import re
+from typing import Any, Optional
+
+from command_center.database.models import HookExecution
+from command_center.utils.date_helpers import parse_and_convert_to_local, format_date_key
+from command_center.utils.project_helpers import extract_project_id
+
+# Attachment type → outcome
+HOOK_ATTACHMENT_OUTCOMES = {
+    "hook_success": "success",
+    "hook_non_blocking_error": "error",
+    "hook_error_during_execution": "error",
+    "hook_blocking_error": "blocked",
+    "hook_cancelled": "cancelled",
+}
+
+LEGACY_HOOK_PATTERN = re.compile(
+    r"^(?P<name>[A-Za-z]+(?::\S+)?) \[(?P<command>.+?)\] (?P<status>completed successfully|failed)"
+)
+
+
+def _execution(data: dict[str, Any], index: int, hook_name: str, hook_event: Optional[str],
+               command: str, outcome: str, duration_ms: Any, source_file: str,
+               project_id: str) -> Optional[HookExecution]:
+    timestamp = data.get("timestamp")
+    dt_local = parse_and_convert_to_local(timestamp) if timestamp else None
+    if dt_local is None:
+        return None
+    return HookExecution(
+        execution_id=f"{data.get('uuid') or timestamp}:{index}",
+        hook_event=hook_event or hook_name.split(":", 1)[0] or "unknown",
+        hook_name=hook_name or hook_event or "unknown",
+        command=command,
+        outcome=outcome,
+        duration_ms=int(duration_ms) if isinstance(duration_ms, (int, float)) else None,
+        timestamp=timestamp,
+        timestamp_local=dt_local.isoformat(),
+        year=dt_local.year,
+        date=format_date_key(dt_local),
+        session_id=data.get("sessionId"),
+        project_id=project_id,
+        source_file=source_file,
+    )
+
+
+def extract_hook_executions(lines: list[dict[str, Any]], source_file: str) -> list[HookExecution]:
+    """
+    Collect hook executions from the parsed lines of a session file.
+
+    Args:
+        lines: Parsed JSONL lines, in file order
+        source_file: Path to source .jsonl file
+
+    Returns:
+        List of HookExecution (outcome: success, error, blocked or cancelled)
+    """
+    project_id = extract_project_id(source_file)
+    executions: list[HookExecution] = []
+    legacy: list[HookExecution] = []
+    has_attachments = False
+
+    for data in lines:
+        attachment = data.get("attachment")
+        if data.get("type") == "attachment" and isinstance(attachment, dict):
+            outcome = HOOK_ATTACHMENT_OUTCOMES.get(attachment.get("type"))
+            if outcome is None:
+                continue
+            has_attachments = True
+            execution = _execution(
+                data, 0, str(attachment.get("hookName") or ""), attachment.get("hookEvent"),
+                str(attachment.get("command") or ""), outcome, attachment.get("durationMs"),
+                source_file, project_id,
+            )
+            if execution:
+                executions.append(execution)
+
+        elif data.get("type") == "system" and data.get("subtype") == "stop_hook_summary":
+            infos = data.get("hookInfos") if isinstance(data.get("hookInfos"), list) else []
+            errors = [str(e) for e in data.get("hookErrors") or []]
+            hook_event = data.get("hookEvent") or "Stop"
+            for index, info in enumerate(infos):
+                if not isinstance(info, dict):
+                    continue
+                command = str(info.get("command") or "")
+                if data.get("preventedContinuation") and len(infos) == 1:
+                    outcome = "blocked"
+                elif any(command and command in error for error in errors) or (errors and len(infos) == 1):
+                    outcome = "error"
+                else:
+                    outcome = "success"
+                execution = _execution(
+                    data, index, hook_event, hook_event, command, outcome, info.get("durationMs"),
+                    source_file, project_id,
+                )
+                if execution:
+                    executions.append(execution)
+
+        elif data.get("type") == "system" and isinstance(data.get("content"), str):
+            match = LEGACY_HOOK_PATTERN.match(data["content"])
+            if match:
+                outcome = "success" if match.group("status") == "completed successfully" else "error"
+                execution = _execution(
+                    data, 0, match.group("name"), None, match.group("command"), outcome, None,
+                    source_file, project_id,
+                )
+                if execution:
+                    legacy.append(execution)
+
+    return executions if has_attachments else executions + legacy
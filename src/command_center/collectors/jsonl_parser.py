@@ -6,12 +6,13 @@ from typing import Optional
 
 from command_center.database.models import MessageEntry
 from command_center.utils.date_helpers import parse_and_convert_to_local, format_datetime_hour, format_date_key
-from command_center.collectors.deduplication import compute_entry_hash
+from command_center.collectors.deduplication import compute_content_hash, compute_entry_hash
 from command_center.utils.pricing import get_model_pricing, calculate_cost_usd
 from command_center.utils.project_helpers import extract_project_id
+from command_center.claude_configs import extract_config_dir
 
 
-def parse_jsonl_line(line: str, source_file: str) -> Optional[MessageEntry]:
+def parse_jsonl_line(line: str, source_file: str, price_book=None) -> Optional[MessageEntry]:
     """
     Parse a single JSONL line into MessageEntry.
 
@@ -21,6 +22,8 @@ def parse_jsonl_line(line: str, source_file: str) -> Optional[MessageEntry]:
     Args:
         line: Raw JSONL line
         source_file: Path to source .jsonl file
+        price_book: Optional PriceBook; rates in effect on the message date
+            are preferred over the current pricing dataset
 
     Returns:
         MessageEntry or None if invalid/malformed
@@ -67,12 +70,16 @@ def parse_jsonl_line(line: str, source_file: str) -> Optional[MessageEntry]:
 
     # Calculate cost if not provided in JSONL
     cost_usd = entry.get('costUSD')
+    cost_source = 'jsonl' if cost_usd is not None else None
     if cost_usd is None:
         model = entry.get('message', {}).get('model')
         if model:
             try:
-                pricing = get_model_pricing(model)
+                pricing = price_book.pricing_for(model, date) if price_book else None
+                if pricing is None:
+                    pricing = get_model_pricing(model)
                 if pricing:
+                    cost_source = 'computed'
                     cost_usd = calculate_cost_usd(
                         input_tokens=input_tokens,
                         output_tokens=output_tokens,
@@ -85,7 +92,7 @@ def parse_jsonl_line(line: str, source_file: str) -> Optional[MessageEntry]:
                 pass
 
     # Build MessageEntry
-    return MessageEntry(
+    message_entry = MessageEntry(
         entry_hash=entry_hash,
         timestamp=timestamp,
         timestamp_local=timestamp_local,
@@ -102,5 +109,9 @@ def parse_jsonl_line(line: str, source_file: str) -> Optional[MessageEntry]:
         cache_write_tokens=cache_write,
         total_tokens=total_tokens,
         source_file=source_file,
-        project_id=project_id
+        project_id=project_id,
+        config_dir=extract_config_dir(source_file),
+        cost_source=cost_source
     )
+    message_entry.content_hash = compute_content_hash(message_entry)
+    return message_entry
@@ -0,0 +1,123 @@
+"""
+Parser for MCP server tool calls from JSONL assistant and tool result entries
+"""
+from typing import Any, Optional
+
+from command_center.database.models import McpCall
+from command_center.utils.date_helpers import parse_and_convert_to_local, format_date_key
+from command_center.utils.project_helpers import extract_project_id
+
+MCP_PREFIX = "mcp__"
+
+# Rough characters per token, used to estimate the size of tool results
+CHARS_PER_TOKEN = 4
+
+
+def parse_mcp_tool_name(name: str) -> Optional[tuple[str, str]]:
+    """
+    Split an MCP tool name into server and tool.
+
+    Args:
+        name: Tool name like "mcp__github__create_issue"
+
+    Returns:
+        ("github", "create_issue"), or None if it is not an MCP tool
+    """
+    if not name.startswith(MCP_PREFIX):
+        return None
+    server, sep, tool = name[len(MCP_PREFIX):].partition("__")
+    if not server or not sep or not tool:
+        return None
+    return server, tool
+
+
+def _usage_tokens(message: dict[str, Any]) -> int:
+    usage = message.get("usage") if isinstance(message.get("usage"), dict) else {}
+    return sum(
+        usage.get(key, 0) or 0
+        for key in ("input_tokens", "output_tokens", "cache_read_input_tokens", "cache_creation_input_tokens")
+    )
+
+
+def _result_chars(content: Any) -> int:
+    if isinstance(content, str):
+        return len(content)
+    if isinstance(content, list):
+        return sum(
+            len(block.get("text", "")) for block in content
+            if isinstance(block, dict) and block.get("type") == "text"
+        )
+    return 0
+
+
+def extract_mcp_calls(lines: list[dict[str, Any]], source_file: str) -> list[McpCall]:
+    """
+    Collect MCP tool calls from the parsed lines of a session file.
+
+    A response's tokens are split evenly between all tool calls it made (a
+    response is often logged as several lines, one per content block, with
+    the same message id and usage), and the size of each tool result is
+    estimated from its text length.
+
+    Args:
+        lines: Parsed JSONL lines, in file order
+        source_file: Path to source .jsonl file
+
+    Returns:
+        List of McpCall (is_error is None for calls without a logged result)
+    """
+    project_id = extract_project_id(source_file)
+    calls: dict[str, McpCall] = {}
+    message_tokens: dict[str, int] = {}
+    message_tool_uses: dict[str, list[str]] = {}
+
+    for data in lines:
+        message = data.get("message")
+        if not isinstance(message, dict) or not isinstance(message.get("content"), list):
+            continue
+
+        if data.get("type") == "assistant":
+            message_key = str(message.get("id") or data.get("uuid") or "")
+            tokens = _usage_tokens(message)
+            if tokens:
+                message_tokens[message_key] = tokens
+            for block in message["content"]:
+                if not isinstance(block, dict) or block.get("type") != "tool_use" or not block.get("id"):
+                    continue
+                tool_use_ids = message_tool_uses.setdefault(message_key, [])
+                if block["id"] not in tool_use_ids:
+                    tool_use_ids.append(block["id"])
+                parsed = parse_mcp_tool_name(str(block.get("name") or ""))
+                timestamp = data.get("timestamp")
+                dt_local = parse_and_convert_to_local(timestamp) if timestamp else None
+                if parsed is None or dt_local is None:
+                    continue
+                calls[block["id"]] = McpCall(
+                    tool_use_id=block["id"],
+                    server=parsed[0],
+                    tool=parsed[1],
+                    timestamp=timestamp,
+                    timestamp_local=dt_local.isoformat(),
+                    year=dt_local.year,
+                    date=format_date_key(dt_local),
+                    session_id=data.get("sessionId"),
+                    project_id=project_id,
+                    source_file=source_file,
+                )
+
+        elif data.get("type") == "user":
+            for block in message["content"]:
+                if not isinstance(block, dict) or block.get("type") != "tool_result":
+                    continue
+                call = calls.get(block.get("tool_use_id", ""))
+                if call is not None:
+                    call.is_error = bool(block.get("is_error"))
+                    call.result_tokens = _result_chars(block.get("content")) // CHARS_PER_TOKEN
+
+    for message_key, tool_use_ids in message_tool_uses.items():
+        share = message_tokens.get(message_key, 0) // len(tool_use_ids)
+        for tool_use_id in tool_use_ids:
+            if tool_use_id in calls:
+                calls[tool_use_id].message_tokens = share
+
+    return list(calls.values())
@@ -0,0 +1,300 @@
+"""
+Context compaction analytics.
+
+Claude Code writes a ``compact_boundary`` system entry whenever a session's
+context is compacted, either by the user (``/compact``, trigger ``manual``)
+or automatically near the context limit (trigger ``auto``), with the context
+size before compaction in ``compactMetadata.preTokens``. Older logs only
+contain the compact summary message (``isCompactSummary``); those events are
+recorded with trigger ``unknown``.
+
+A scan pass reads each session's JSONL files and stores the events in
+``compaction_events``, with the context size of the first response after
+the compaction as ``post_tokens``. Like the outcome classifier, the pass keeps
+a signature of each session's files in ``compaction_scans`` and only re-reads
+sessions whose files changed.
+"""
+from __future__ import annotations
+
+import json
+import sqlite3
+from datetime import datetime
+from typing import Any, Optional
+
+from command_center.session_outcomes import files_signature
+from command_center.utils.date_helpers import format_date_key, parse_and_convert_to_local
+
+TRIGGERS = ("auto", "manual", "unknown")
+
+# Sessions listed in the top_sessions breakdown
+TOP_SESSIONS = 10
+
+
+def _context_tokens(entry: dict[str, Any]) -> Optional[int]:
+    """Context size of an assistant response (input + cache read + cache write), if it has usage."""
+    message = entry.get("message")
+    if entry.get("type") != "assistant" or not isinstance(message, dict):
+        return None
+    usage = message.get("usage")
+    if not isinstance(usage, dict):
+        return None
+    return (
+        (usage.get("input_tokens") or 0)
+        + (usage.get("cache_read_input_tokens") or 0)
+        + (usage.get("cache_creation_input_tokens") or 0)
+    )
+
+
+def parse_compaction_events(paths: list[str], session_id: str) -> list[dict[str, Any]]:
+    """
+    Compaction events of a session's main thread, in file order.
+
+    Returns:
+        [{"event_id", "timestamp", "trigger", "pre_tokens", "post_tokens"}, ...]
+        (token counts are None when unknown)
+    """
+    events: list[dict[str, Any]] = []
+    last_context: Optional[int] = None
+    pending: Optional[dict[str, Any]] = None  # waiting for the first response after it
+    for path in paths:
+        with open(path, "r", encoding="utf-8", errors="replace") as f:
+            for line in f:
+                try:
+                    entry = json.loads(line)
+                except ValueError:
+                    continue
+                if not isinstance(entry, dict) or entry.get("isSidechain"):
+                    continue
+                if entry.get("sessionId", session_id) != session_id:
+                    continue
+
+                if entry.get("type") == "system" and entry.get("subtype") == "compact_boundary":
+                    metadata = entry.get("compactMetadata") if isinstance(entry.get("compactMetadata"), dict) else {}
+                    trigger = metadata.get("trigger")
+                    pending = {
+                        "event_id": str(entry.get("uuid") or entry.get("timestamp") or len(events)),
+                        "timestamp": entry.get("timestamp"),
+                        "trigger": trigger if trigger in TRIGGERS else "unknown",
+                        "pre_tokens": metadata.get("preTokens") or last_context,
+                        "post_tokens": None,
+                    }
+                    events.append(pending)
+                    continue
+
+                if entry.get("isCompactSummary"):
+                    # Older logs: a summary without a boundary entry before it
+                    if pending is None:
+                        pending = {
+                            "event_id": str(entry.get("uuid") or entry.get("timestamp") or len(events)),
+                            "timestamp": entry.get("timestamp"),
+                            "trigger": "unknown",
+                            "pre_tokens": last_context,
+                            "post_tokens": None,
+                        }
+                        events.append(pending)
+                    continue
+
+                context = _context_tokens(entry)
+                if context:
+                    last_context = context
+                    if pending is not None:
+                        pending["post_tokens"] = context
+                        pending = None
+    return [event for event in events if event["timestamp"]]
+
+
+def scan_compactions(
+    conn: sqlite3.Connection,
+    sessions: dict[str, list[str]],
+    force: bool = False,
+) -> dict[str, int]:
+    """
+    Store the compaction events of sessions whose files changed since the last scan.
+
+    Args:
+        conn: Database connection
+        sessions: Session ID → JSONL files (see ``session_outcomes.session_files``)
+        force: Re-read every session, even if its files are unchanged
+
+    Returns:
+        {"scanned": 12, "unchanged": 140, "missing_files": 3}
+    """
+    cursor = conn.cursor()
+    known = dict(conn.execute("SELECT session_id, files_signature FROM compaction_scans").fetchall())
+
+    stats = {"scanned": 0, "unchanged": 0, "missing_files": 0}
+    now = datetime.now().astimezone().isoformat()
+    for session_id, paths in sessions.items():
+        signature = files_signature(paths)
+        if signature is None:
+            stats["missing_files"] += 1
+            continue
+        if not force and known.get(session_id) == signature:
+            stats["unchanged"] += 1
+            continue
+        try:
+            events = parse_compaction_events(paths, session_id)
+        except OSError:
+            stats["missing_files"] += 1
+            continue
+
+        rows = []
+        for event in events:
+            dt_local = parse_and_convert_to_local(event["timestamp"])
+            if dt_local is None:
+                continue
+            rows.append((
+                session_id, event["event_id"], event["timestamp"], dt_local.isoformat(),
+                format_date_key(dt_local), event["trigger"], event["pre_tokens"], event["post_tokens"],
+            ))
+        cursor.execute("DELETE FROM compaction_events WHERE session_id = ?", (session_id,))
+        cursor.executemany("""
+            INSERT OR REPLACE INTO compaction_events
+            (session_id, event_id, timestamp, timestamp_local, date, trigger, pre_tokens, post_tokens)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        """, rows)
+        cursor.execute("""
+            INSERT OR REPLACE INTO compaction_scans (session_id, files_signature, scanned_at)
+            VALUES (?, ?, ?)
+        """, (session_id, signature, now))
+        stats["scanned"] += 1
+
+    conn.commit()
+    return stats
+
+
+def _freed(pre_tokens: Optional[int], post_tokens: Optional[int]) -> Optional[int]:
+    if pre_tokens is None or post_tokens is None:
+        return None
+    return max(pre_tokens - post_tokens, 0)
+
+
+def _average(values: list[Optional[int]]) -> Optional[int]:
+    known = [value for value in values if value is not None]
+    return round(sum(known) / len(known)) if known else None
+
+
+def query_session_compactions(conn: sqlite3.Connection, session_id: str) -> dict[str, Any]:
+    """
+    Compaction events of one session (for session details).
+
+    Returns:
+        {"count", "auto", "manual", "tokens_freed",
+         "events": [{"timestamp_local", "trigger", "pre_tokens", "post_tokens", "tokens_freed"}, ...]}
+    """
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT timestamp_local, trigger, pre_tokens, post_tokens
+        FROM compaction_events
+        WHERE session_id = ?
+        ORDER BY timestamp
+    """, (session_id,))
+    events = [
+        {
+            "timestamp_local": row[0],
+            "trigger": row[1],
+            "pre_tokens": row[2],
+            "post_tokens": row[3],
+            "tokens_freed": _freed(row[2], row[3]),
+        }
+        for row in cursor.fetchall()
+    ]
+    return {
+        "count": len(events),
+        "auto": sum(1 for e in events if e["trigger"] == "auto"),
+        "manual": sum(1 for e in events if e["trigger"] == "manual"),
+        "tokens_freed": sum(e["tokens_freed"] or 0 for e in events),
+        "events": events,
+    }
+
+
+def query_compaction_stats(
+    conn: sqlite3.Connection,
+    date_from: str,
+    date_to: str,
+    project_id: Optional[str] = None,
+) -> dict[str, Any]:
+    """
+    Frequency and token impact of compactions in a date range.
+
+    Args:
+        conn: Database connection
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Only sessions of this project
+
+    Returns:
+        {
+            "sessions": 150, "sessions_with_compactions": 31, "compactions": 58,
+            "by_trigger": {"auto": 49, "manual": 9, "unknown": 0},
+            "per_session": 0.387, "per_compacted_session": 1.87,
+            "avg_pre_tokens": 154000, "avg_post_tokens": 21000,
+            "tokens_freed": 7700000, "avg_tokens_freed": 133000,
+            "daily": [{"date", "auto", "manual", "unknown"}, ...],
+            "top_sessions": [{"session_id", "project_id", "compactions", "first_time"}, ...],
+            "by_project": [{"project_id", "sessions", "compactions", "per_session"}, ...]
+        }
+    """
+    project_filter = "AND project_id = ?" if project_id else ""
+    params: list = [date_from, date_to] + ([project_id] if project_id else [])
+    cursor = conn.cursor()
+    cursor.execute(f"""
+        SELECT session_id, MIN(project_id), MIN(timestamp_local)
+        FROM message_entries
+        WHERE date BETWEEN ? AND ? AND session_id IS NOT NULL AND origin = 'local' {project_filter}
+        GROUP BY session_id
+    """, params)
+    sessions = {row[0]: (row[1], row[2]) for row in cursor.fetchall()}
+
+    cursor.execute("""
+        SELECT session_id, date, trigger, pre_tokens, post_tokens
+        FROM compaction_events
+        WHERE date BETWEEN ? AND ?
+        ORDER BY timestamp
+    """, (date_from, date_to))
+    events = [row for row in cursor.fetchall() if row[0] in sessions]
+
+    by_trigger = {trigger: 0 for trigger in TRIGGERS}
+    daily: dict[str, dict[str, Any]] = {}
+    per_session: dict[str, int] = {}
+    for session_id, day, trigger, _, _ in events:
+        by_trigger[trigger] = by_trigger.get(trigger, 0) + 1
+        daily.setdefault(day, {"date": day, **{t: 0 for t in TRIGGERS}})[trigger] += 1
+        per_session[session_id] = per_session.get(session_id, 0) + 1
+
+    projects: dict[str, dict[str, Any]] = {}
+    for session_id, (project, _) in sessions.items():
+        entry = projects.setdefault(project, {"project_id": project, "sessions": 0, "compactions": 0})
+        entry["sessions"] += 1
+        entry["compactions"] += per_session.get(session_id, 0)
+    for entry in projects.values():
+        entry["per_session"] = round(entry["compactions"] / entry["sessions"], 3)
+
+    freed = [_freed(row[3], row[4]) for row in events]
+    top = sorted(per_session.items(), key=lambda item: item[1], reverse=True)[:TOP_SESSIONS]
+    return {
+        "sessions": len(sessions),
+        "sessions_with_compactions": len(per_session),
+        "compactions": len(events),
+        "by_trigger": by_trigger,
+        "per_session": round(len(events) / len(sessions), 3) if sessions else None,
+        "per_compacted_session": round(len(events) / len(per_session), 3) if per_session else None,
+        "avg_pre_tokens": _average([row[3] for row in events]),
+        "avg_post_tokens": _average([row[4] for row in events]),
+        "tokens_freed": sum(value for value in freed if value is not None),
+        "avg_tokens_freed": _average(freed),
+        "daily": [daily[day] for day in sorted(daily)],
+        "top_sessions": [
+            {
+                "session_id": session_id,
+                "project_id": sessions[session_id][0],
+                "compactions": count,
+                "first_time": sessions[session_id][1],
+            }
+            for session_id, count in top
+        ],
+        "by_project": sorted(
+            (entry for entry in projects.values() if entry["compactions"]),
+            key=lambda p: p["compactions"], reverse=True,
+        ),
+    }
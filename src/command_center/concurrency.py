@@ -0,0 +1,255 @@
+"""
+Concurrent session detection (multi-terminal workflows).
+
+Each session is split into activity segments: consecutive messages at most
+``idle_gap_minutes`` apart belong to the same segment, so a terminal left
+open over lunch does not count as running. A sweep over the segment
+boundaries gives the number of sessions active at every moment; each
+message is attributed the concurrency level at its timestamp.
+
+The report covers time and tokens per concurrency level, the periods where
+two or more sessions overlapped, and, for every 5-hour limit hit, how much
+of the usage in the five hours before it ran concurrently.
+"""
+from __future__ import annotations
+
+import sqlite3
+from bisect import bisect_left, bisect_right
+from datetime import date, datetime, timedelta
+from typing import Any, Optional
+
+from command_center.utils.date_helpers import parse_iso_timestamp
+
+DEFAULT_IDLE_GAP_MINUTES = 15
+
+# Overlap periods listed in the report (by tokens)
+TOP_PERIODS = 20
+
+LIMIT_WINDOW = timedelta(hours=5)
+
+
+def _segments(messages: list[dict[str, Any]], idle_gap: timedelta) -> list[dict[str, Any]]:
+    """Activity segments of all sessions; messages must be sorted by session and time."""
+    segments: list[dict[str, Any]] = []
+    for message in messages:
+        last = segments[-1] if segments else None
+        if (last is None or last["session_id"] != message["session_id"]
+                or message["at"] - last["end"] > idle_gap):
+            segments.append({
+                "session_id": message["session_id"], "project_id": message["project_id"],
+                "start": message["at"], "end": message["at"],
+            })
+        else:
+            last["end"] = message["at"]
+    return segments
+
+
+def _sweep(segments: list[dict[str, Any]]) -> list[tuple[datetime, datetime, int]]:
+    """(start, end, active sessions) intervals between segment boundaries, idle time left out."""
+    boundaries: list[tuple[datetime, int]] = []
+    for segment in segments:
+        if segment["end"] > segment["start"]:
+            boundaries.append((segment["start"], 1))
+            boundaries.append((segment["end"], -1))
+    # Ends before starts at the same instant: back-to-back segments do not overlap
+    boundaries.sort(key=lambda b: (b[0], b[1]))
+
+    intervals = []
+    level = 0
+    previous: Optional[datetime] = None
+    for at, delta in boundaries:
+        if previous is not None and level > 0 and at > previous:
+            intervals.append((previous, at, level))
+        level += delta
+        previous = at
+    return intervals
+
+
+def query_concurrency_stats(
+    conn: sqlite3.Connection,
+    date_from: str,
+    date_to: str,
+    idle_gap_minutes: int = DEFAULT_IDLE_GAP_MINUTES,
+) -> dict[str, Any]:
+    """
+    Detect periods where several sessions ran at the same time.
+
+    Args:
+        conn: Database connection
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        idle_gap_minutes: Longest pause between messages within one activity segment
+
+    Returns:
+        {
+            "range": {"from", "to"}, "idle_gap_minutes": 15,
+            "max_concurrency": 3, "peak": {"start", "end", "sessions": [...]} | None,
+            "levels": [{"concurrency", "minutes", "messages", "tokens", "cost", "token_share"}, ...],
+            "concurrent": {"minutes", "tokens", "cost", "token_share"},
+            "daily": [{"date", "max_concurrency", "concurrent_minutes", "concurrent_tokens"}, ...],
+            "periods": [{"start", "end", "minutes", "max_concurrency", "sessions", "projects",
+                         "tokens", "cost"}, ...],
+            "period_count": 42,
+            "limit_windows": [{"occurred_at", "tokens", "concurrent_tokens", "concurrent_share",
+                               "max_concurrency"}, ...]
+        }
+        Times are local ISO timestamps; shares are percentages.
+    """
+    if idle_gap_minutes < 1:
+        raise ValueError(f"Invalid idle gap: {idle_gap_minutes} (expected >= 1 minute)")
+    idle_gap = timedelta(minutes=idle_gap_minutes)
+
+    # One extra day before the range covers the 5-hour windows of early limit hits
+    margin_from = (date.fromisoformat(date_from) - timedelta(days=1)).isoformat()
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT session_id, project_id, timestamp, timestamp_local, date, total_tokens, COALESCE(cost_usd, 0)
+        FROM message_entries
+        WHERE date BETWEEN ? AND ? AND session_id IS NOT NULL AND origin = 'local'
+        ORDER BY session_id, timestamp
+    """, (margin_from, date_to))
+    messages = []
+    for session_id, project_id, timestamp, timestamp_local, day, tokens, cost in cursor.fetchall():
+        at = parse_iso_timestamp(timestamp)
+        if at is None:
+            continue
+        messages.append({
+            "session_id": session_id, "project_id": project_id, "at": at, "local": timestamp_local,
+            "date": day, "tokens": tokens or 0, "cost": cost,
+        })
+
+    segments = _segments(messages, idle_gap)
+    intervals = _sweep(segments)
+    local_offset = {m["at"]: m["local"] for m in messages}
+
+    def local(at: datetime) -> str:
+        return local_offset.get(at) or at.astimezone().isoformat()
+
+    # Concurrency of each message: segments with start <= t <= end
+    starts = sorted(s["start"] for s in segments)
+    ends = sorted(s["end"] for s in segments)
+    for message in messages:
+        message["level"] = bisect_right(starts, message["at"]) - bisect_left(ends, message["at"])
+
+    in_range = [m for m in messages if date_from <= m["date"] <= date_to]
+    range_start = min((m["at"] for m in in_range), default=None)
+
+    levels: dict[int, dict[str, Any]] = {}
+    for message in in_range:
+        entry = levels.setdefault(message["level"], {
+            "concurrency": message["level"], "minutes": 0.0, "messages": 0, "tokens": 0, "cost": 0.0,
+        })
+        entry["messages"] += 1
+        entry["tokens"] += message["tokens"]
+        entry["cost"] += message["cost"]
+
+    daily: dict[str, dict[str, Any]] = {}
+    for start, end, level in intervals:
+        if range_start is None or end <= range_start:
+            continue
+        minutes = (end - start).total_seconds() / 60
+        levels.setdefault(level, {"concurrency": level, "minutes": 0.0, "messages": 0, "tokens": 0, "cost": 0.0})
+        levels[level]["minutes"] += minutes
+        day = local(start)[:10]
+        entry = daily.setdefault(day, {
+            "date": day, "max_concurrency": 0, "concurrent_minutes": 0.0, "concurrent_tokens": 0,
+        })
+        entry["max_concurrency"] = max(entry["max_concurrency"], level)
+        if level >= 2:
+            entry["concurrent_minutes"] += minutes
+    for message in in_range:
+        if message["level"] >= 2 and message["date"] in daily:
+            daily[message["date"]]["concurrent_tokens"] += message["tokens"]
+
+    # Overlap periods: maximal runs of intervals with two or more active sessions
+    periods: list[dict[str, Any]] = []
+    for start, end, level in intervals:
+        if level < 2 or range_start is None or end <= range_start:
+            continue
+        if periods and periods[-1]["end_at"] == start:
+            periods[-1]["end_at"] = end
+            periods[-1]["max_concurrency"] = max(periods[-1]["max_concurrency"], level)
+        else:
+            periods.append({"start_at": start, "end_at": end, "max_concurrency": level})
+    by_time = sorted(in_range, key=lambda m: m["at"])
+    times = [m["at"] for m in by_time]
+    for period in periods:
+        active = [s for s in segments if s["start"] < period["end_at"] and s["end"] > period["start_at"]]
+        window = by_time[bisect_left(times, period["start_at"]):bisect_right(times, period["end_at"])]
+        period.update({
+            "start": local(period["start_at"]),
+            "end": local(period["end_at"]),
+            "minutes": round((period["end_at"] - period["start_at"]).total_seconds() / 60, 1),
+            "sessions": sorted({s["session_id"] for s in active}),
+            "projects": sorted({s["project_id"] for s in active}),
+            "tokens": sum(m["tokens"] for m in window),
+            "cost": round(sum(m["cost"] for m in window), 4),
+        })
+
+    peak = max(periods, key=lambda p: (p["max_concurrency"], p["tokens"]), default=None)
+
+    # Usage before each 5-hour limit hit
+    cursor.execute("""
+        SELECT occurred_at, occurred_at_local
+        FROM limit_events
+        WHERE limit_type = '5-hour' AND date BETWEEN ? AND ?
+        ORDER BY occurred_at
+    """, (date_from, date_to))
+    all_times = sorted(messages, key=lambda m: m["at"])
+    all_at = [m["at"] for m in all_times]
+    limit_windows = []
+    for occurred_at, occurred_at_local in cursor.fetchall():
+        hit = parse_iso_timestamp(occurred_at)
+        if hit is None:
+            continue
+        window = all_times[bisect_left(all_at, hit - LIMIT_WINDOW):bisect_right(all_at, hit)]
+        tokens = sum(m["tokens"] for m in window)
+        concurrent_tokens = sum(m["tokens"] for m in window if m["level"] >= 2)
+        limit_windows.append({
+            "occurred_at": occurred_at_local,
+            "tokens": tokens,
+            "concurrent_tokens": concurrent_tokens,
+            "concurrent_share": round(concurrent_tokens / tokens * 100, 1) if tokens else None,
+            "max_concurrency": max((m["level"] for m in window), default=0),
+        })
+
+    total_tokens = sum(m["tokens"] for m in in_range)
+
+    def share(tokens: int) -> Optional[float]:
+        return round(tokens / total_tokens * 100, 1) if total_tokens else None
+
+    level_list = []
+    for level in sorted(levels):
+        entry = levels[level]
+        level_list.append({
+            **entry,
+            "minutes": round(entry["minutes"], 1),
+            "cost": round(entry["cost"], 4),
+            "token_share": share(entry["tokens"]),
+        })
+    concurrent_levels = [entry for entry in level_list if entry["concurrency"] >= 2]
+    concurrent_tokens = sum(entry["tokens"] for entry in concurrent_levels)
+
+    return {
+        "range": {"from": date_from, "to": date_to},
+        "idle_gap_minutes": idle_gap_minutes,
+        "max_concurrency": max([m["level"] for m in in_range] + [p["max_concurrency"] for p in periods], default=0),
+        "peak": {"start": peak["start"], "end": peak["end"], "sessions": peak["sessions"]} if peak else None,
+        "levels": level_list,
+        "concurrent": {
+            "minutes": round(sum(entry["minutes"] for entry in concurrent_levels), 1),
+            "tokens": concurrent_tokens,
+            "cost": round(sum(entry["cost"] for entry in concurrent_levels), 4),
+            "token_share": share(concurrent_tokens),
+        },
+        "daily": [
+            {**daily[day], "concurrent_minutes": round(daily[day]["concurrent_minutes"], 1)}
+            for day in sorted(daily) if date_from <= day <= date_to
+        ],
+        "periods": [
+            {key: value for key, value in period.items() if key not in ("start_at", "end_at")}
+            for period in sorted(periods, key=lambda p: p["tokens"], reverse=True)[:TOP_PERIODS]
+        ],
+        "period_count": len(periods),
+        "limit_windows": limit_windows,
+    }
@@ -49,3 +49,6 @@ FONT_PATHS = [
 
 # Batch processing
 BATCH_INSERT_SIZE = 100  # Insert entries in batches of 100
+
+# Compact zstd mirror of parsed records for fast cold rebuilds (requires a zstd codec)
+MIRROR_ENABLED = os.environ.get("COMMAND_CENTER_MIRROR", "0") == "1"
@@ -0,0 +1,353 @@
+"""
+Cost allocation of monthly usage to finance cost centers.
+
+Cost centers are configured in a small JSON file next to the database.
+Each cost center lists the projects and project tags it owns. A project is
+allocated by the first matching rule:
+
+1. explicit project mapping
+2. first cost center (in configured order) sharing a project tag
+3. the default cost center
+
+Exports are finance-ready CSV or XLSX files with the methodology embedded,
+so the numbers can be audited without access to this tool.
+"""
+from __future__ import annotations
+
+import base64
+import csv
+import io
+import json
+import os
+import sqlite3
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Optional
+
+from command_center import __version__ as package_version
+from command_center.utils.date_helpers import month_bounds, parse_month_key
+from command_center.utils.atomic_write import write_json_atomic
+from command_center.utils.project_metadata import load_projects_json
+from command_center.utils.xlsx_writer import build_xlsx
+
+
+# Default location for cost center configuration JSON
+COST_CENTERS_JSON_PATH = os.path.expanduser("~/.claude/db/command-center-cost-centers.json")
+
+DEFAULT_COST_CENTER = {"code": "UNALLOCATED", "name": "Unallocated"}
+
+EXPORT_FORMATS = {
+    "csv": "text/csv",
+    "xlsx": "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+}
+
+
+def load_cost_center_config(json_path: str = COST_CENTERS_JSON_PATH) -> dict:
+    """
+    Load cost center configuration from JSON file.
+
+    Returns:
+        {
+            "default_cost_center": {"code": "UNALLOCATED", "name": "Unallocated"},
+            "cost_centers": [
+                {"code": "CC-100", "name": "Engineering", "projects": ["-home-xai-DEV-app"], "tags": ["client"]}
+            ]
+        }
+    """
+    path = Path(json_path)
+    config: dict = {}
+    if path.exists():
+        try:
+            with open(path, "r") as f:
+                config = json.load(f)
+        except (json.JSONDecodeError, IOError):
+            config = {}
+
+    config.setdefault("default_cost_center", dict(DEFAULT_COST_CENTER))
+    config.setdefault("cost_centers", [])
+    return config
+
+
+def save_cost_center_config(config: dict, json_path: str = COST_CENTERS_JSON_PATH):
+    """Save cost center configuration to JSON file."""
+    write_json_atomic(json_path, config)
+
+
+def _clean_list(values: Optional[list[str]]) -> list[str]:
+    cleaned = []
+    for value in values or []:
+        value = value.strip()
+        if value and value not in cleaned:
+            cleaned.append(value)
+    return cleaned
+
+
+def set_cost_center(
+    code: str,
+    name: Optional[str] = None,
+    projects: Optional[list[str]] = None,
+    tags: Optional[list[str]] = None,
+    json_path: str = COST_CENTERS_JSON_PATH
+) -> dict:
+    """
+    Create or update a cost center.
+
+    A project can only be mapped explicitly to one cost center; mapping it
+    here removes it from any other cost center.
+
+    Args:
+        code: Cost center code (e.g. "CC-100")
+        name: Display name (optional, defaults to code for new cost centers)
+        projects: Project IDs to map (optional, replaces the list)
+        tags: Project tags to map (optional, replaces the list)
+        json_path: Path to cost center JSON file
+
+    Returns:
+        Updated cost center configuration
+
+    Raises:
+        ValueError: If code or name is empty or too long
+    """
+    code = (code or "").strip()
+    if not code:
+        raise ValueError("Cost center code is required")
+    if len(code) > 30:
+        raise ValueError("Cost center code cannot exceed 30 characters")
+    if name is not None:
+        name = name.strip()
+        if len(name) > 100:
+            raise ValueError("Cost center name cannot exceed 100 characters")
+
+    config = load_cost_center_config(json_path)
+    entry = next((c for c in config["cost_centers"] if c["code"] == code), None)
+    if entry is None:
+        entry = {"code": code, "name": name or code, "projects": [], "tags": []}
+        config["cost_centers"].append(entry)
+    elif name:
+        entry["name"] = name
+
+    if projects is not None:
+        entry["projects"] = _clean_list(projects)
+        for other in config["cost_centers"]:
+            if other is not entry:
+                other["projects"] = [p for p in other.get("projects", []) if p not in entry["projects"]]
+    if tags is not None:
+        entry["tags"] = _clean_list(tags)
+
+    save_cost_center_config(config, json_path)
+    return config
+
+
+def delete_cost_center(code: str, json_path: str = COST_CENTERS_JSON_PATH) -> dict:
+    """
+    Delete a cost center; its projects fall back to tag rules or the default.
+
+    Raises:
+        ValueError: If the cost center does not exist
+    """
+    config = load_cost_center_config(json_path)
+    remaining = [c for c in config["cost_centers"] if c["code"] != code]
+    if len(remaining) == len(config["cost_centers"]):
+        raise ValueError(f"Cost center not found: {code}")
+    config["cost_centers"] = remaining
+    save_cost_center_config(config, json_path)
+    return config
+
+
+def resolve_cost_center(project_id: str, project_meta: dict, config: dict) -> dict[str, str]:
+    """
+    Resolve the cost center for a project.
+
+    Returns:
+        {"code": ..., "name": ..., "rule": "project" | "tag:<tag>" | "default"}
+    """
+    for center in config["cost_centers"]:
+        if project_id in center.get("projects", []):
+            return {"code": center["code"], "name": center["name"], "rule": "project"}
+
+    project_tags = project_meta.get("tags", [])
+    for center in config["cost_centers"]:
+        for tag in center.get("tags", []):
+            if tag in project_tags:
+                return {"code": center["code"], "name": center["name"], "rule": f"tag:{tag}"}
+
+    default = config["default_cost_center"]
+    return {"code": default["code"], "name": default["name"], "rule": "default"}
+
+
+def query_cost_allocation(
+    conn: sqlite3.Connection,
+    month: str,
+    json_path: str = COST_CENTERS_JSON_PATH,
+    projects: Optional[dict] = None
+) -> dict[str, Any]:
+    """
+    Allocate a month's usage to cost centers.
+
+    Args:
+        conn: Database connection
+        month: Month key (YYYY-MM)
+        json_path: Path to cost center JSON file
+        projects: Project metadata (defaults to the projects JSON)
+
+    Returns:
+        {
+            "month": "2025-06",
+            "range": {"from": ..., "to": ...},
+            "cost_centers": [
+                {"code", "name", "cost", "tokens", "messages", "share",
+                 "projects": [{"project_id", "name", "rule", "cost", "tokens", "messages", "share"}]}
+            ],
+            "totals": {"cost", "tokens", "messages", "unpriced_messages"},
+            "methodology": [...]
+        }
+    """
+    month = parse_month_key(month)
+    date_from, date_to = month_bounds(month)
+    config = load_cost_center_config(json_path)
+    if projects is None:
+        projects = load_projects_json()
+
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT project_id, COUNT(*), SUM(total_tokens), SUM(COALESCE(cost_usd, 0)),
+               SUM(CASE WHEN cost_usd IS NULL THEN 1 ELSE 0 END)
+        FROM message_entries
+        WHERE date >= ? AND date <= ? AND origin = 'local'
+        GROUP BY project_id
+    """, (date_from, date_to))
+    rows = cursor.fetchall()
+
+    total_cost = sum(row[3] or 0 for row in rows)
+    centers: dict[str, dict[str, Any]] = {}
+    unpriced = 0
+    for project_id, messages, tokens, cost, unpriced_messages in rows:
+        unpriced += unpriced_messages or 0
+        meta = projects.get(project_id, {})
+        center = resolve_cost_center(project_id, meta, config)
+        entry = centers.setdefault(center["code"], {
+            "code": center["code"], "name": center["name"],
+            "cost": 0.0, "tokens": 0, "messages": 0, "projects": [],
+        })
+        entry["cost"] += cost or 0
+        entry["tokens"] += tokens or 0
+        entry["messages"] += messages or 0
+        entry["projects"].append({
+            "project_id": project_id,
+            "name": meta.get("name") or project_id,
+            "absolute_path": meta.get("absolute_path"),
+            "rule": center["rule"],
+            "cost": round(cost or 0, 4),
+            "tokens": tokens or 0,
+            "messages": messages or 0,
+            "share": round((cost or 0) / total_cost * 100, 2) if total_cost else 0.0,
+        })
+
+    result_centers = []
+    for entry in sorted(centers.values(), key=lambda c: c["cost"], reverse=True):
+        entry["projects"].sort(key=lambda p: p["cost"], reverse=True)
+        entry["share"] = round(entry["cost"] / total_cost * 100, 2) if total_cost else 0.0
+        entry["cost"] = round(entry["cost"], 4)
+        result_centers.append(entry)
+
+    default = config["default_cost_center"]
+    methodology = [
+        f"Period: {date_from} to {date_to} (calendar month, local time of the machine that recorded usage).",
+        "Source: Claude Code session logs ingested by Command Center; one row per project.",
+        "Cost: costUSD from the logs when present, otherwise token counts priced with the "
+        "model rates in effect on each message date (API list prices, USD).",
+        "Allocation: explicit project mapping first, then the first cost center (in configured order) "
+        f"sharing a project tag, otherwise {default['code']} ({default['name']}).",
+        "Share: cost of the row divided by total cost of the month.",
+        f"Messages without pricing (counted, cost 0): {unpriced}.",
+        f"Generated: {datetime.now().astimezone().isoformat()} by Command Center {package_version}.",
+    ]
+
+    return {
+        "month": month,
+        "range": {"from": date_from, "to": date_to},
+        "cost_centers": result_centers,
+        "totals": {
+            "cost": round(total_cost, 4),
+            "tokens": sum(c["tokens"] for c in result_centers),
+            "messages": sum(c["messages"] for c in result_centers),
+            "unpriced_messages": unpriced,
+        },
+        "methodology": methodology,
+    }
+
+
+ALLOCATION_HEADER = [
+    "Month", "Cost Center Code", "Cost Center Name", "Project", "Project Path",
+    "Allocation Rule", "Messages", "Tokens", "Cost (USD)", "Share (%)",
+]
+
+
+def _allocation_rows(allocation: dict[str, Any]) -> list[list[Any]]:
+    month = allocation["month"]
+    rows: list[list[Any]] = [ALLOCATION_HEADER]
+    for center in allocation["cost_centers"]:
+        for project in center["projects"]:
+            rows.append([
+                month, center["code"], center["name"], project["name"],
+                project["absolute_path"] or "", project["rule"],
+                project["messages"], project["tokens"], project["cost"], project["share"],
+            ])
+        rows.append([
+            month, center["code"], center["name"], "Subtotal", "", "",
+            center["messages"], center["tokens"], center["cost"], center["share"],
+        ])
+    totals = allocation["totals"]
+    rows.append([
+        month, "", "", "Total", "", "",
+        totals["messages"], totals["tokens"], totals["cost"], 100.0 if totals["cost"] else 0.0,
+    ])
+    return rows
+
+
+def export_cost_allocation(
+    conn: sqlite3.Connection,
+    month: str,
+    fmt: str = "csv",
+    json_path: str = COST_CENTERS_JSON_PATH
+) -> dict[str, Any]:
+    """
+    Export a month's cost allocation as CSV or XLSX.
+
+    CSV files append the methodology after a blank row; XLSX files carry it
+    on a separate "Methodology" sheet.
+
+    Returns:
+        Dict with base64-encoded file data, filename, size, mime_type and summary totals
+
+    Raises:
+        ValueError: If the format is not supported
+    """
+    if fmt not in EXPORT_FORMATS:
+        raise ValueError(f"Unsupported format: {fmt}. Use one of: {', '.join(EXPORT_FORMATS)}")
+
+    allocation = query_cost_allocation(conn, month, json_path)
+    rows = _allocation_rows(allocation)
+
+    if fmt == "csv":
+        buffer = io.StringIO()
+        writer = csv.writer(buffer)
+        writer.writerows(rows)
+        writer.writerow([])
+        writer.writerow(["Methodology"])
+        for note in allocation["methodology"]:
+            writer.writerow([note])
+        data = buffer.getvalue().encode("utf-8-sig")  # BOM so Excel detects UTF-8
+    else:
+        data = build_xlsx([
+            ("Allocation", rows),
+            ("Methodology", [["Methodology"]] + [[note] for note in allocation["methodology"]]),
+        ])
+
+    return {
+        "filename": f"cc-cost-allocation-{allocation['month']}.{fmt}",
+        "data": base64.b64encode(data).decode("utf-8"),
+        "size": len(data),
+        "mime_type": EXPORT_FORMATS[fmt],
+        "totals": allocation["totals"],
+    }
@@ -0,0 +1,366 @@
+"""
+Merging usage from a second data source without double counting.
+
+A source is a backup archive (its database), a database file or a Claude
+data root (a config directory with a ``projects/`` tree, e.g. copied from
+another machine). Sources usually overlap with the local database in time:
+the same sessions were synced to both machines, or a backup was taken
+before the local data was collected.
+
+Messages are matched on their entry hash (``message.id:requestId``) and
+then on the Anthropic message ID alone, which catches the same message
+logged under another request ID (a naive union counts it twice). For each
+match the more complete record wins: more tokens, then a known cost, then
+a known model. A local row that loses keeps its key and source file and
+only takes over the usage columns (its content hash is recomputed).
+
+Only message entries are merged; settings, project metadata and limit
+events are left alone (use ``restore_backup`` for those).
+"""
+from __future__ import annotations
+
+import dataclasses
+import json
+import os
+import sqlite3
+import tempfile
+import zipfile
+from pathlib import Path
+from typing import Any, Iterable
+
+from command_center.collectors.deduplication import compute_content_hash
+from command_center.database.models import MessageEntry
+from command_center.database.queries import (
+    insert_message_entries,
+    recompute_hourly_aggregates,
+    recompute_model_aggregates,
+)
+from command_center.project_timeline import backfill_first_seen
+from command_center.utils.project_metadata import (
+    PROJECTS_JSON_PATH,
+    auto_discover_project,
+    load_projects_json,
+    save_projects_json,
+)
+
+ENTRY_FIELDS = [field.name for field in dataclasses.fields(MessageEntry)]
+REQUIRED_FIELDS = ("entry_hash", "timestamp", "timestamp_local", "year", "date")
+USAGE_FIELDS = ("model", "cost_usd", "input_tokens", "output_tokens", "cache_read_tokens",
+                "cache_write_tokens", "total_tokens", "cost_source")
+MAX_SESSION_DETAILS = 50
+
+
+def _completeness(entry: MessageEntry) -> tuple:
+    """Ranking of a record's completeness; the higher tuple wins."""
+    return (entry.total_tokens or 0, entry.output_tokens or 0,
+            entry.cost_usd is not None, entry.model is not None)
+
+
+def _usage(entry: MessageEntry) -> tuple:
+    return tuple(getattr(entry, name) for name in USAGE_FIELDS)
+
+
+def _merged_content_hash(local: MessageEntry, incoming: MessageEntry) -> str:
+    """Content hash of a local row after it takes over the incoming usage columns."""
+    return compute_content_hash(dataclasses.replace(local, **dict(zip(USAGE_FIELDS, _usage(incoming)))))
+
+
+def _match_key(entry: MessageEntry) -> str:
+    return f"msg:{entry.message_id}" if entry.message_id else f"hash:{entry.entry_hash}"
+
+
+def _datetime_hour(entry: MessageEntry) -> str:
+    return f"{entry.date} {entry.timestamp_local[11:13]}:00:00"
+
+
+def read_database_entries(db_path: str) -> list[MessageEntry]:
+    """
+    Read all message entries of another Command Center database.
+
+    Columns added by later schema versions default to the MessageEntry defaults.
+
+    Raises:
+        ValueError: If the file is not a database with a message_entries table
+    """
+    try:
+        conn = sqlite3.connect(f"file:{db_path}?mode=ro", uri=True)
+    except sqlite3.Error as e:
+        raise ValueError(f"Cannot open database {db_path}: {e}")
+    try:
+        try:
+            columns = {row[1] for row in conn.execute("PRAGMA table_info(message_entries)")}
+        except sqlite3.DatabaseError as e:
+            raise ValueError(f"Not a Command Center database: {db_path} ({e})")
+        if not set(REQUIRED_FIELDS) <= columns:
+            raise ValueError(f"Not a Command Center database: {db_path}")
+        selected = [name for name in ENTRY_FIELDS if name in columns]
+        rows = conn.execute(f"SELECT {', '.join(selected)} FROM message_entries").fetchall()
+    finally:
+        conn.close()
+    return [MessageEntry(**dict(zip(selected, row))) for row in rows]
+
+
+def read_backup_entries(path: str) -> tuple[list[MessageEntry], dict[str, Any]]:
+    """
+    Read the message entries of a backup's database after checking its checksums.
+
+    A backup from another machine has an unverifiable signature; that is
+    accepted here because nothing is overwritten, but checksum errors are not.
+
+    Returns:
+        (entries, verification)
+
+    Raises:
+        ValueError: If the backup fails verification or has no database
+    """
+    from command_center.backup import DB_FILE_NAME, verify_backup
+
+    verification = verify_backup(path)
+    if verification["errors"]:
+        raise ValueError("Backup failed verification: " + "; ".join(verification["errors"]))
+    if "cache" not in verification["components"]:
+        raise ValueError("Backup does not contain a database")
+
+    with tempfile.TemporaryDirectory() as scratch:
+        target = Path(scratch) / DB_FILE_NAME
+        with zipfile.ZipFile(path) as archive:
+            target.write_bytes(archive.read(f"cache/{DB_FILE_NAME}"))
+        return read_database_entries(str(target)), verification
+
+
+def read_data_root_entries(base_dir: str, price_book=None) -> tuple[list[MessageEntry], int]:
+    """
+    Parse every session file under a Claude data root.
+
+    Returns:
+        (entries, number of files read)
+
+    Raises:
+        ValueError: If the directory has no projects/ tree
+    """
+    from command_center.cache.incremental_update import parse_file
+    from command_center.collectors.file_scanner import scan_root_jsonl_files
+
+    if not os.path.isdir(os.path.join(base_dir, "projects")):
+        raise ValueError(f"Not a Claude data root (no projects/ directory): {base_dir}")
+    files = scan_root_jsonl_files(base_dir)
+    entries = []
+    for path in files:
+        entries.extend(parse_file(path, price_book).entries)
+    return entries, len(files)
+
+
+def _load_local(conn: sqlite3.Connection, entries: list[MessageEntry]) -> tuple[dict, dict]:
+    """Local rows sharing an entry hash or message ID with the incoming entries."""
+    hashes = json.dumps([e.entry_hash for e in entries])
+    message_ids = json.dumps([e.message_id for e in entries if e.message_id])
+    rows = conn.execute(f"""
+        SELECT {', '.join(ENTRY_FIELDS)} FROM message_entries
+        WHERE entry_hash IN (SELECT value FROM json_each(?))
+           OR message_id IN (SELECT value FROM json_each(?))
+    """, (hashes, message_ids)).fetchall()
+    by_hash: dict[str, MessageEntry] = {}
+    by_message: dict[str, MessageEntry] = {}
+    for row in rows:
+        entry = MessageEntry(**dict(zip(ENTRY_FIELDS, row)))
+        by_hash[entry.entry_hash] = entry
+        if entry.message_id:
+            current = by_message.get(entry.message_id)
+            if current is None or _completeness(entry) > _completeness(current):
+                by_message[entry.message_id] = entry
+    return by_hash, by_message
+
+
+def _session_counts(conn: sqlite3.Connection, session_ids: Iterable[str]) -> dict[str, int]:
+    rows = conn.execute("""
+        SELECT session_id, COUNT(*) FROM message_entries
+        WHERE session_id IN (SELECT value FROM json_each(?))
+        GROUP BY session_id
+    """, (json.dumps(sorted(session_ids)),)).fetchall()
+    return dict(rows)
+
+
+def merge_entries(
+    conn: sqlite3.Connection,
+    incoming: list[MessageEntry],
+    dry_run: bool = False
+) -> dict[str, Any]:
+    """
+    Merge message entries into the database, preferring the more complete record.
+
+    Args:
+        conn: Database connection
+        incoming: Entries of the other source
+        dry_run: Only report what would change
+
+    Returns:
+        {
+            "dry_run": false,
+            "incoming": 5200,               # rows read from the source
+            "duplicates_in_source": 3,      # collapsed before merging
+            "added": 1200, "identical": 3900, "replaced": 40, "kept": 57,
+            "double_counts_prevented": 12,  # same message under another entry hash
+            "tokens_added": 980000, "cost_added": 14.2,   # including replaced rows
+            "date_range": {"from": "2025-01-02", "to": "2025-03-30"} | None,
+            "sessions": {
+                "new": 14, "overlapping": 9,
+                "details": [{"session_id", "project_id", "local_messages", "incoming_messages",
+                             "added", "replaced", "kept"}, ...]   # overlapping, largest first
+            },
+            "projects": ["-home-user-app", ...]   # projects that gained messages
+        }
+    """
+    # Collapse the source first: it can hold the same message twice as well
+    best: dict[str, MessageEntry] = {}
+    for entry in incoming:
+        key = _match_key(entry)
+        current = best.get(key)
+        if current is None or _completeness(entry) > _completeness(current):
+            best[key] = entry
+    candidates = list(best.values())
+
+    by_hash, by_message = _load_local(conn, candidates)
+    local_sessions = _session_counts(conn, {e.session_id for e in candidates if e.session_id})
+
+    added: list[MessageEntry] = []
+    replaced: list[tuple[MessageEntry, MessageEntry]] = []
+    counts = {"identical": 0, "kept": 0, "double_counts_prevented": 0}
+    sessions: dict[str, dict[str, Any]] = {}
+    for entry in candidates:
+        local = by_hash.get(entry.entry_hash)
+        if local is None and entry.message_id:
+            local = by_message.get(entry.message_id)
+            if local is not None:
+                counts["double_counts_prevented"] += 1
+
+        if local is None:
+            outcome = "added"
+            added.append(entry)
+        elif _usage(local) == _usage(entry):
+            outcome = "identical"
+        elif _completeness(entry) > _completeness(local):
+            outcome = "replaced"
+            replaced.append((local, entry))
+        else:
+            outcome = "kept"
+        if outcome in ("identical", "kept"):
+            counts[outcome] += 1
+
+        if entry.session_id:
+            session = sessions.setdefault(entry.session_id, {
+                "session_id": entry.session_id,
+                "project_id": entry.project_id,
+                "local_messages": local_sessions.get(entry.session_id, 0),
+                "incoming_messages": 0,
+                "added": 0, "replaced": 0, "kept": 0,
+            })
+            session["incoming_messages"] += 1
+            if outcome in ("added", "replaced", "kept"):
+                session[outcome] += 1
+
+    tokens_added = sum(e.total_tokens or 0 for e in added) + sum(
+        (new.total_tokens or 0) - (old.total_tokens or 0) for old, new in replaced)
+    cost_added = sum((e.cost_usd or 0.0 for e in added), 0.0) + sum(
+        (new.cost_usd or 0.0) - (old.cost_usd or 0.0) for old, new in replaced)
+
+    if not dry_run and (added or replaced):
+        insert_message_entries(conn, added)
+        conn.executemany(f"""
+            UPDATE message_entries SET {', '.join(f'{name} = ?' for name in USAGE_FIELDS)}, content_hash = ?
+            WHERE entry_hash = ?
+        """, [(*_usage(new), _merged_content_hash(old, new), old.entry_hash) for old, new in replaced])
+        conn.commit()
+
+        changed = added + [old for old, _ in replaced]
+        recompute_hourly_aggregates(conn, {_datetime_hour(e) for e in changed if e.origin == "local"}, reason="merge")
+        for year in sorted({e.year for e in changed}):
+            recompute_model_aggregates(conn, year)
+
+    overlapping = sorted(
+        (s for s in sessions.values() if s["local_messages"] > 0),
+        key=lambda s: (-s["incoming_messages"], s["session_id"]),
+    )
+    dates = sorted(e.date for e in candidates)
+    return {
+        "dry_run": dry_run,
+        "incoming": len(incoming),
+        "duplicates_in_source": len(incoming) - len(candidates),
+        "added": len(added),
+        "identical": counts["identical"],
+        "replaced": len(replaced),
+        "kept": counts["kept"],
+        "double_counts_prevented": counts["double_counts_prevented"],
+        "tokens_added": tokens_added,
+        "cost_added": round(cost_added, 4),
+        "date_range": {"from": dates[0], "to": dates[-1]} if dates else None,
+        "sessions": {
+            "new": sum(1 for s in sessions.values() if s["local_messages"] == 0),
+            "overlapping": len(overlapping),
+            "details": overlapping[:MAX_SESSION_DETAILS],
+        },
+        "projects": sorted({e.project_id for e in added if e.project_id != "unknown"}),
+    }
+
+
+def merge_source(
+    conn: sqlite3.Connection,
+    path: str,
+    dry_run: bool = False,
+    price_book=None
+) -> dict[str, Any]:
+    """
+    Merge a backup archive, database file or Claude data root into the database.
+
+    Args:
+        conn: Database connection
+        path: Source to merge
+        dry_run: Only report what would change
+        price_book: PriceBook for pricing parsed session files (data roots only)
+
+    Returns:
+        merge_entries report plus {"source", "kind": "backup" | "database" | "data_root",
+        "files": 12 (data roots), "signature": "unknown_key" (backups)}
+
+    Raises:
+        ValueError: If the source is missing or not a supported kind
+    """
+    extra: dict[str, Any] = {}
+    if os.path.isdir(path):
+        kind = "data_root"
+        entries, extra["files"] = read_data_root_entries(path, price_book)
+    elif not os.path.exists(path):
+        raise ValueError(f"Merge source not found: {path}")
+    elif zipfile.is_zipfile(path):
+        kind = "backup"
+        entries, verification = read_backup_entries(path)
+        extra["signature"] = verification["signature"]
+    else:
+        kind = "database"
+        entries = read_database_entries(path)
+
+    report = merge_entries(conn, entries, dry_run)
+    return {"source": path, "kind": kind, **extra, **report}
+
+
+def register_merged_projects(
+    conn: sqlite3.Connection,
+    project_ids: list[str],
+    json_path: str = PROJECTS_JSON_PATH
+) -> list[str]:
+    """
+    Add projects that only the merged source knew to projects.json.
+
+    first_seen of every given project moves back to its earliest merged session.
+
+    Returns:
+        IDs of the projects that were created
+    """
+    projects = load_projects_json(json_path)
+    created = [project_id for project_id in project_ids if project_id not in projects]
+    if created:
+        for project_id in created:
+            projects = auto_discover_project(projects, project_id, json_path)
+        save_projects_json(projects, json_path)
+    if project_ids:
+        backfill_first_seen(conn, project_ids, json_path)
+    return created
@@ -1,7 +1,7 @@
 """
 Data models for database entities
 """
-from dataclasses import dataclass
+from dataclasses import dataclass, field
 from datetime import datetime
 from typing import Optional, Literal
 
@@ -36,6 +36,10 @@ class MessageEntry:
     total_tokens: int = 0
     source_file: str = ""
     project_id: str = "unknown"
+    config_dir: Optional[str] = None  # Claude config dir the file was read from
+    cost_source: Optional[str] = None  # 'jsonl' (costUSD in log) or 'computed' (from price table)
+    origin: str = "local"  # 'local' or 'imported' (session archive); imported rows are excluded from overall totals
+    content_hash: Optional[str] = None  # compute_content_hash(); filled in on insert when missing
 
 
 @dataclass
@@ -93,6 +97,52 @@ class LimitEvent:
     source_file: str = ""
 
 
+@dataclass
+class McpCall:
+    """Call of a tool provided by an MCP server (tool name mcp__<server>__<tool>)"""
+    tool_use_id: str  # Unique identifier for deduplication
+    server: str
+    tool: str
+    timestamp: str  # ISO 8601 UTC
+    timestamp_local: str  # ISO 8601 local time
+    year: int  # Local year
+    date: str  # YYYY-MM-DD local date
+    session_id: Optional[str] = None
+    project_id: str = "unknown"
+    is_error: Optional[bool] = None  # None until the tool result is logged
+    message_tokens: int = 0  # Share of the calling response's tokens
+    result_tokens: int = 0  # Estimated tokens of the tool result added to the context
+    source_file: str = ""
+
+
+@dataclass
+class HookExecution:
+    """Execution of a Claude Code hook (PreToolUse, PostToolUse, Stop, ...)"""
+    execution_id: str  # Unique identifier for deduplication (log line uuid:index)
+    hook_event: str  # e.g. 'PreToolUse'
+    hook_name: str  # Event with matcher, e.g. 'PreToolUse:Bash'
+    command: str
+    outcome: str  # 'success', 'error', 'blocked', 'cancelled'
+    duration_ms: Optional[int]
+    timestamp: str  # ISO 8601 UTC
+    timestamp_local: str  # ISO 8601 local time
+    year: int  # Local year
+    date: str  # YYYY-MM-DD local date
+    session_id: Optional[str] = None
+    project_id: str = "unknown"
+    source_file: str = ""
+
+
+@dataclass
+class RequestSetting:
+    """Effort/thinking setting and latency of one response (joined to message_entries by entry_hash)"""
+    entry_hash: str
+    effort: Optional[str] = None  # thinkingMetadata.level of the prompt, e.g. 'none', 'medium', 'high'
+    thinking_budget: Optional[int] = None  # maxThinkingTokens of the prompt
+    used_thinking: bool = False  # The response contains thinking blocks
+    latency_ms: Optional[int] = None  # Preceding user line to first line of the response
+
+
 @dataclass
 class UsageStats:
     """Statistics for usage report generation"""
@@ -107,3 +157,5 @@ class UsageStats:
     cache_read_tokens: int
     cache_write_tokens: int
     first_session_date: Optional[datetime] = None
+    top_projects: list[dict] = field(default_factory=list)  # [{project_id, tokens, cost}, ...]
+    project_count: int = 0
@@ -1,20 +1,34 @@
 """
 SQL query interface for database operations
 """
+import base64
+import hashlib
+import json
 import sqlite3
-from typing import Optional, Literal
+from typing import Optional, Literal, Union
 from datetime import datetime
 
-from command_center.database.models import MessageEntry, UsageStats, LimitEvent
+from command_center.database.models import MessageEntry, UsageStats, LimitEvent, McpCall, HookExecution, RequestSetting
 from command_center.config import BATCH_INSERT_SIZE
+from command_center.collectors.deduplication import compute_content_hash
 from command_center.utils.model_names import format_model_name
 
 
+# Project filter of dashboard queries: one project ID, or a list of IDs (e.g. a workspace)
+ProjectScope = Union[str, list[str], None]
+
+
+def project_scope_param(project_id: Union[str, list[str]]) -> str:
+    """JSON list of project IDs for a `project_id IN (SELECT value FROM json_each(?))` filter."""
+    return json.dumps([project_id] if isinstance(project_id, str) else list(project_id))
+
+
 def insert_message_entries(conn: sqlite3.Connection, entries: list[MessageEntry]):
     """
     Batch insert message entries into database.
 
-    Uses INSERT OR IGNORE for idempotent operation.
+    Uses INSERT OR IGNORE for idempotent operation. Entries without a
+    content hash (demo data, rows read from older databases) get one here.
     """
     if not entries:
         return
@@ -30,7 +44,8 @@ def insert_message_entries(conn: sqlite3.Connection, entries: list[MessageEntry]
                 e.entry_hash, e.timestamp, e.timestamp_local, e.year, e.date,
                 e.session_id, e.request_id, e.message_id, e.model, e.cost_usd,
                 e.input_tokens, e.output_tokens, e.cache_read_tokens,
-                e.cache_write_tokens, e.total_tokens, e.source_file, e.project_id
+                e.cache_write_tokens, e.total_tokens, e.source_file, e.project_id,
+                e.config_dir, e.cost_source, e.origin, e.content_hash or compute_content_hash(e)
             )
             for e in batch
         ]
@@ -39,8 +54,9 @@ def insert_message_entries(conn: sqlite3.Connection, entries: list[MessageEntry]
             INSERT OR IGNORE INTO message_entries
             (entry_hash, timestamp, timestamp_local, year, date, session_id,
              request_id, message_id, model, cost_usd, input_tokens, output_tokens,
-             cache_read_tokens, cache_write_tokens, total_tokens, source_file, project_id)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             cache_read_tokens, cache_write_tokens, total_tokens, source_file, project_id,
+             config_dir, cost_source, origin, content_hash)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         """, rows)
 
     conn.commit()
@@ -80,6 +96,104 @@ def insert_limit_events(conn: sqlite3.Connection, events: list[LimitEvent]):
     conn.commit()
 
 
+def insert_mcp_calls(conn: sqlite3.Connection, calls: list[McpCall]):
+    """
+    Batch insert MCP tool calls into database.
+
+    Uses INSERT OR REPLACE (by tool_use_id): a rescan of a growing session file
+    updates calls whose result was not logged yet.
+    """
+    if not calls:
+        return
+
+    cursor = conn.cursor()
+
+    # Process in batches
+    for i in range(0, len(calls), BATCH_INSERT_SIZE):
+        batch = calls[i:i + BATCH_INSERT_SIZE]
+
+        rows = [
+            (
+                c.tool_use_id, c.server, c.tool, c.timestamp, c.timestamp_local, c.year, c.date,
+                c.session_id, c.project_id, None if c.is_error is None else int(c.is_error),
+                c.message_tokens, c.result_tokens, c.source_file
+            )
+            for c in batch
+        ]
+
+        cursor.executemany("""
+            INSERT OR REPLACE INTO mcp_calls
+            (tool_use_id, server, tool, timestamp, timestamp_local, year, date,
+             session_id, project_id, is_error, message_tokens, result_tokens, source_file)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        """, rows)
+
+    conn.commit()
+
+
+def insert_hook_executions(conn: sqlite3.Connection, executions: list[HookExecution]):
+    """
+    Batch insert hook executions into database.
+
+    Uses INSERT OR IGNORE for idempotent operation (deduplication by execution_id).
+    """
+    if not executions:
+        return
+
+    cursor = conn.cursor()
+
+    # Process in batches
+    for i in range(0, len(executions), BATCH_INSERT_SIZE):
+        batch = executions[i:i + BATCH_INSERT_SIZE]
+
+        rows = [
+            (
+                e.execution_id, e.hook_event, e.hook_name, e.command, e.outcome, e.duration_ms,
+                e.timestamp, e.timestamp_local, e.year, e.date, e.session_id, e.project_id, e.source_file
+            )
+            for e in batch
+        ]
+
+        cursor.executemany("""
+            INSERT OR IGNORE INTO hook_executions
+            (execution_id, hook_event, hook_name, command, outcome, duration_ms,
+             timestamp, timestamp_local, year, date, session_id, project_id, source_file)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        """, rows)
+
+    conn.commit()
+
+
+def insert_request_settings(conn: sqlite3.Connection, settings: list[RequestSetting]):
+    """
+    Batch insert per-response effort settings into database.
+
+    Uses INSERT OR REPLACE (by entry_hash), so a rescan backfills responses
+    ingested before the table existed.
+    """
+    if not settings:
+        return
+
+    cursor = conn.cursor()
+
+    # Process in batches
+    for i in range(0, len(settings), BATCH_INSERT_SIZE):
+        batch = settings[i:i + BATCH_INSERT_SIZE]
+
+        rows = [
+            (s.entry_hash, s.effort, s.thinking_budget, int(s.used_thinking), s.latency_ms)
+            for s in batch
+        ]
+
+        cursor.executemany("""
+            INSERT OR REPLACE INTO request_settings
+            (entry_hash, effort, thinking_budget, used_thinking, latency_ms)
+            VALUES (?, ?, ?, ?, ?)
+        """, rows)
+
+    conn.commit()
+
+
 def update_file_track(conn: sqlite3.Connection, file_path: str, mtime_ns: int,
                       size_bytes: int, entry_count: int):
     """Update file tracking information"""
@@ -104,12 +218,16 @@ def get_file_tracks(conn: sqlite3.Connection) -> dict[str, tuple[int, int]]:
     return {row[0]: (row[1], row[2]) for row in cursor.fetchall()}
 
 
-def recompute_hourly_aggregates(conn: sqlite3.Connection, datetime_hours: set[str]):
+def recompute_hourly_aggregates(conn: sqlite3.Connection, datetime_hours: set[str], reason: str = "ingest"):
     """
     Recompute hourly aggregates for specific hours.
 
+    The new totals of the affected days are appended to
+    daily_aggregate_history when they changed (see record_daily_history).
+
     Args:
         datetime_hours: Set of datetime_hour strings (YYYY-MM-DD HH:00:00)
+        reason: Why the hours changed (ingest, reprice, merge, time-shift, ...)
     """
     if not datetime_hours:
         return
@@ -144,24 +262,118 @@ def recompute_hourly_aggregates(conn: sqlite3.Connection, datetime_hours: set[st
                 SUM(total_tokens) as total_tokens,
                 SUM(COALESCE(cost_usd, 0)) as total_cost
             FROM message_entries
-            WHERE date = ? AND SUBSTR(timestamp_local, 12, 2) = ?
+            WHERE date = ? AND SUBSTR(timestamp_local, 12, 2) = ? AND origin = 'local'
             HAVING COUNT(*) > 0
         """, (datetime_hour, datetime_hour, datetime_hour, hour_part, date_part, date_part, hour_part))
 
+    record_daily_history(conn, {datetime_hour[:10] for datetime_hour in datetime_hours}, reason)
     conn.commit()
 
 
-def recompute_model_aggregates(conn: sqlite3.Connection, year: int):
+def record_daily_history(conn: sqlite3.Connection, dates: set[str], reason: str) -> int:
+    """
+    Append the current totals of days to daily_aggregate_history.
+
+    A day gets a row only if its totals differ from its last recorded row
+    (days never recorded and without usage are skipped). Does not commit.
+
+    Returns:
+        Number of rows appended
+    """
+    cursor = conn.cursor()
+    recorded_at = datetime.now().astimezone().isoformat()
+    rows = []
+    for date in sorted(dates):
+        current = cursor.execute("""
+            SELECT COUNT(*), COUNT(DISTINCT session_id), COALESCE(SUM(total_tokens), 0),
+                   ROUND(COALESCE(SUM(COALESCE(cost_usd, 0)), 0), 6)
+            FROM message_entries
+            WHERE date = ? AND origin = 'local'
+        """, (date,)).fetchone()
+        last = cursor.execute("""
+            SELECT message_count, session_count, total_tokens, total_cost_usd
+            FROM daily_aggregate_history
+            WHERE date = ?
+            ORDER BY id DESC LIMIT 1
+        """, (date,)).fetchone()
+        if (last is None and current[0] == 0) or tuple(current) == last:
+            continue
+        rows.append((date, recorded_at, reason, *current))
+
+    cursor.executemany("""
+        INSERT INTO daily_aggregate_history
+        (date, recorded_at, reason, message_count, session_count, total_tokens, total_cost_usd)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+    """, rows)
+    return len(rows)
+
+
+def query_aggregate_history(conn: sqlite3.Connection, date: str) -> dict:
+    """
+    Get every recorded version of a day's totals, oldest first.
+
+    Args:
+        conn: Database connection
+        date: Local date (YYYY-MM-DD)
+
+    Returns:
+        {
+            "date": "2025-03-04",
+            "current": {"messages", "sessions", "tokens", "cost"} | None,   # latest version
+            "history": [{"recorded_at", "reason", "messages", "sessions", "tokens", "cost",
+                         "delta": {"messages", "sessions", "tokens", "cost"}}, ...]
+        }
+        The first version's delta is relative to zero.
+    """
+    rows = conn.execute("""
+        SELECT recorded_at, reason, message_count, session_count, total_tokens, total_cost_usd
+        FROM daily_aggregate_history
+        WHERE date = ?
+        ORDER BY id
+    """, (date,)).fetchall()
+
+    history = []
+    previous = {"messages": 0, "sessions": 0, "tokens": 0, "cost": 0.0}
+    for recorded_at, reason, messages, sessions, tokens, cost in rows:
+        values = {"messages": messages, "sessions": sessions, "tokens": tokens, "cost": round(cost, 4)}
+        history.append({
+            "recorded_at": recorded_at,
+            "reason": reason,
+            **values,
+            "delta": {key: round(values[key] - previous[key], 4) if key == "cost" else values[key] - previous[key]
+                      for key in values},
+        })
+        previous = values
+
+    return {
+        "date": date,
+        "current": {key: history[-1][key] for key in previous} if history else None,
+        "history": history,
+    }
+
+
+def recompute_model_aggregates(conn: sqlite3.Connection, year: int,
+                               models: Optional[set[str]] = None):
     """
     Recompute model aggregates for a specific year.
+
+    Args:
+        year: Year to recompute
+        models: Only recompute these models (default: all models of the year)
     """
     cursor = conn.cursor()
 
+    model_filter = ""
+    params: list = [year]
+    if models:
+        model_filter = f" AND model IN ({','.join('?' * len(models))})"
+        params.extend(sorted(models))
+
     # Delete existing aggregates for this year
-    cursor.execute("DELETE FROM model_aggregates WHERE year = ?", (year,))
+    cursor.execute(f"DELETE FROM model_aggregates WHERE year = ?{model_filter}", params)
 
     # Recompute from message_entries
-    cursor.execute("""
+    cursor.execute(f"""
         INSERT INTO model_aggregates
         (model, year, total_tokens, input_tokens, output_tokens,
          cache_read_tokens, cache_write_tokens, message_count, total_cost_usd)
@@ -176,21 +388,21 @@ def recompute_model_aggregates(conn: sqlite3.Connection, year: int):
             COUNT(*),
             SUM(COALESCE(cost_usd, 0))
         FROM message_entries
-        WHERE year = ? AND model IS NOT NULL
+        WHERE year = ? AND model IS NOT NULL{model_filter} AND origin = 'local'
         GROUP BY model, year
-    """, (year,))
+    """, params)
 
     conn.commit()
 
 
-def query_daily_stats(conn: sqlite3.Connection, date_from: str, date_to: str, project_id: Optional[str] = None) -> dict[str, int]:
+def query_daily_stats(conn: sqlite3.Connection, date_from: str, date_to: str, project_id: ProjectScope = None) -> dict[str, int]:
     """
     Query daily statistics from hourly aggregates.
 
     Args:
         date_from: Start date (YYYY-MM-DD)
         date_to: End date (YYYY-MM-DD)
-        project_id: Optional project filter
+        project_id: Optional project filter (a project ID or a list of IDs)
 
     Returns:
         Dict mapping date (YYYY-MM-DD) → message_count
@@ -202,10 +414,10 @@ def query_daily_stats(conn: sqlite3.Connection, date_from: str, date_to: str, pr
         cursor.execute("""
             SELECT date, COUNT(*) as message_count
             FROM message_entries
-            WHERE date >= ? AND date <= ? AND project_id = ?
+            WHERE date >= ? AND date <= ? AND project_id IN (SELECT value FROM json_each(?))
             GROUP BY date
             ORDER BY date
-        """, (date_from, date_to, project_id))
+        """, (date_from, date_to, project_scope_param(project_id)))
     else:
         # Use aggregates for all projects
         cursor.execute("""
@@ -243,7 +455,7 @@ def query_usage_stats(conn: sqlite3.Connection, date_from: str, date_to: str) ->
             COUNT(*) as message_count,
             SUM(COALESCE(cost_usd, 0)) as total_cost
         FROM message_entries
-        WHERE date >= ? AND date <= ? AND model IS NOT NULL
+        WHERE date >= ? AND date <= ? AND model IS NOT NULL AND origin = 'local'
         GROUP BY model
         ORDER BY total_tokens DESC
         LIMIT 3
@@ -253,6 +465,23 @@ def query_usage_stats(conn: sqlite3.Connection, date_from: str, date_to: str) ->
         for row in cursor.fetchall()
     ]
 
+    # Projects by tokens (top 5 are drawn in the report's project strip)
+    cursor.execute("""
+        SELECT
+            project_id,
+            SUM(total_tokens) as total_tokens,
+            SUM(COALESCE(cost_usd, 0)) as total_cost
+        FROM message_entries
+        WHERE date >= ? AND date <= ? AND project_id IS NOT NULL AND origin = 'local'
+        GROUP BY project_id
+        ORDER BY total_tokens DESC
+    """, (date_from, date_to))
+    project_rows = cursor.fetchall()
+    top_projects = [
+        {"project_id": row[0], "tokens": row[1] or 0, "cost": row[2]}
+        for row in project_rows[:5]
+    ]
+
     # Totals
     cursor.execute("""
         SELECT
@@ -264,7 +493,7 @@ def query_usage_stats(conn: sqlite3.Connection, date_from: str, date_to: str) ->
             SUM(cache_write_tokens) as cache_write,
             MIN(timestamp) as first_timestamp
         FROM message_entries
-        WHERE date >= ? AND date <= ?
+        WHERE date >= ? AND date <= ? AND origin = 'local'
     """, (date_from, date_to))
     row = cursor.fetchone()
 
@@ -286,7 +515,9 @@ def query_usage_stats(conn: sqlite3.Connection, date_from: str, date_to: str) ->
         total_cost=row[3] or 0.0,
         cache_read_tokens=row[4] or 0,
         cache_write_tokens=row[5] or 0,
-        first_session_date=first_timestamp
+        first_session_date=first_timestamp,
+        top_projects=top_projects,
+        project_count=len(project_rows)
     )
 
 
@@ -300,7 +531,7 @@ def query_timeline_data(
     date_from: str,
     date_to: str,
     granularity: Literal["month", "week", "day", "hour"],
-    project_id: Optional[str] = None
+    project_id: ProjectScope = None
 ) -> list[dict]:
     """
     Query timeline data grouped by month/week/day/hour.
@@ -310,7 +541,7 @@ def query_timeline_data(
         date_from: Start date (YYYY-MM-DD)
         date_to: End date (YYYY-MM-DD)
         granularity: Grouping level - 'month', 'week', 'day', or 'hour'
-        project_id: Optional project filter
+        project_id: Optional project filter (a project ID or a list of IDs)
 
     Returns:
         List of dicts with period, messages, tokens, input_tokens, output_tokens, cost
@@ -339,10 +570,10 @@ def query_timeline_data(
                 SUM(total_tokens) as tokens,
                 SUM(COALESCE(cost_usd, 0)) as cost
             FROM message_entries
-            WHERE date >= ? AND date <= ? AND project_id = ?
+            WHERE date >= ? AND date <= ? AND project_id IN (SELECT value FROM json_each(?))
             GROUP BY period
             ORDER BY period
-        """, (date_from, date_to, project_id))
+        """, (date_from, date_to, project_scope_param(project_id)))
     else:
         # Use aggregates for all projects
         cursor.execute(f"""
@@ -370,9 +601,9 @@ def query_timeline_data(
                 SUM(input_tokens) as input_tokens,
                 SUM(output_tokens) as output_tokens
             FROM message_entries
-            WHERE date >= ? AND date <= ? AND project_id = ?
+            WHERE date >= ? AND date <= ? AND project_id IN (SELECT value FROM json_each(?))
             GROUP BY period
-        """, (date_from, date_to, project_id))
+        """, (date_from, date_to, project_scope_param(project_id)))
     else:
         cursor.execute(f"""
             SELECT
@@ -380,7 +611,7 @@ def query_timeline_data(
                 SUM(input_tokens) as input_tokens,
                 SUM(output_tokens) as output_tokens
             FROM message_entries
-            WHERE date >= ? AND date <= ?
+            WHERE date >= ? AND date <= ? AND origin = 'local'
             GROUP BY period
         """, (date_from, date_to))
 
@@ -407,7 +638,7 @@ def query_model_distribution(
     conn: sqlite3.Connection,
     date_from: str,
     date_to: str,
-    project_id: Optional[str] = None
+    project_id: ProjectScope = None
 ) -> list[dict]:
     """
     Query model distribution with input/output breakdown.
@@ -416,7 +647,7 @@ def query_model_distribution(
         conn: Database connection
         date_from: Start date (YYYY-MM-DD)
         date_to: End date (YYYY-MM-DD)
-        project_id: Optional project filter
+        project_id: Optional project filter (a project ID or a list of IDs)
 
     Returns:
         List of dicts with model stats, sorted by tokens descending
@@ -433,10 +664,10 @@ def query_model_distribution(
                 COUNT(*) as messages,
                 SUM(COALESCE(cost_usd, 0)) as cost
             FROM message_entries
-            WHERE date >= ? AND date <= ? AND project_id = ? AND model IS NOT NULL
+            WHERE date >= ? AND date <= ? AND project_id IN (SELECT value FROM json_each(?)) AND model IS NOT NULL
             GROUP BY model
             ORDER BY tokens DESC
-        """, (date_from, date_to, project_id))
+        """, (date_from, date_to, project_scope_param(project_id)))
     else:
         cursor.execute("""
             SELECT
@@ -447,7 +678,7 @@ def query_model_distribution(
                 COUNT(*) as messages,
                 SUM(COALESCE(cost_usd, 0)) as cost
             FROM message_entries
-            WHERE date >= ? AND date <= ? AND model IS NOT NULL
+            WHERE date >= ? AND date <= ? AND model IS NOT NULL AND origin = 'local'
             GROUP BY model
             ORDER BY tokens DESC
         """, (date_from, date_to))
@@ -474,7 +705,7 @@ def query_hourly_profile(
     conn: sqlite3.Connection,
     date_from: str,
     date_to: str,
-    project_id: Optional[str] = None
+    project_id: ProjectScope = None
 ) -> list[dict]:
     """
     Query hourly activity profile (0-23).
@@ -483,7 +714,7 @@ def query_hourly_profile(
         conn: Database connection
         date_from: Start date (YYYY-MM-DD)
         date_to: End date (YYYY-MM-DD)
-        project_id: Optional project filter
+        project_id: Optional project filter (a project ID or a list of IDs)
 
     Returns:
         List of 24 dicts (one per hour) with hour, messages, tokens, input_tokens, output_tokens
@@ -498,10 +729,10 @@ def query_hourly_profile(
                 COUNT(*) as messages,
                 SUM(total_tokens) as tokens
             FROM message_entries
-            WHERE date >= ? AND date <= ? AND project_id = ?
+            WHERE date >= ? AND date <= ? AND project_id IN (SELECT value FROM json_each(?))
             GROUP BY hour
             ORDER BY hour
-        """, (date_from, date_to, project_id))
+        """, (date_from, date_to, project_scope_param(project_id)))
     else:
         # Get basic hourly stats from aggregates
         cursor.execute("""
@@ -526,9 +757,9 @@ def query_hourly_profile(
                 SUM(input_tokens) as input_tokens,
                 SUM(output_tokens) as output_tokens
             FROM message_entries
-            WHERE date >= ? AND date <= ? AND project_id = ?
+            WHERE date >= ? AND date <= ? AND project_id IN (SELECT value FROM json_each(?))
             GROUP BY hour
-        """, (date_from, date_to, project_id))
+        """, (date_from, date_to, project_scope_param(project_id)))
     else:
         cursor.execute("""
             SELECT
@@ -536,7 +767,7 @@ def query_hourly_profile(
                 SUM(input_tokens) as input_tokens,
                 SUM(output_tokens) as output_tokens
             FROM message_entries
-            WHERE date >= ? AND date <= ?
+            WHERE date >= ? AND date <= ? AND origin = 'local'
             GROUP BY hour
         """, (date_from, date_to))
 
@@ -559,13 +790,13 @@ def query_hourly_profile(
     return result
 
 
-def query_data_range(conn: sqlite3.Connection, project_id: Optional[str] = None) -> dict:
+def query_data_range(conn: sqlite3.Connection, project_id: ProjectScope = None) -> dict:
     """
     Query overall data range (min/max date) for all data or a specific project.
 
     Args:
         conn: Database connection
-        project_id: Optional project filter
+        project_id: Optional project filter (a project ID or a list of IDs)
 
     Returns:
         Dict with start/end date strings (YYYY-MM-DD), or None when empty.
@@ -576,12 +807,13 @@ def query_data_range(conn: sqlite3.Connection, project_id: Optional[str] = None)
         cursor.execute("""
             SELECT MIN(date), MAX(date)
             FROM message_entries
-            WHERE project_id = ?
-        """, (project_id,))
+            WHERE project_id IN (SELECT value FROM json_each(?))
+        """, (project_scope_param(project_id),))
     else:
         cursor.execute("""
             SELECT MIN(date), MAX(date)
             FROM message_entries
+            WHERE origin = 'local'
         """)
 
     row = cursor.fetchone()
@@ -591,12 +823,29 @@ def query_data_range(conn: sqlite3.Connection, project_id: Optional[str] = None)
     }
 
 
+def query_project_costs(conn: sqlite3.Connection, date_from: str, date_to: str) -> dict[str, float]:
+    """
+    Query cost per project for a date range.
+
+    Returns:
+        Dict mapping project_id → cost (projects without usage are absent)
+    """
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT project_id, SUM(COALESCE(cost_usd, 0))
+        FROM message_entries
+        WHERE date >= ? AND date <= ? AND project_id IS NOT NULL
+        GROUP BY project_id
+    """, (date_from, date_to))
+    return {project_id: round(cost, 4) for project_id, cost in cursor.fetchall()}
+
+
 def query_recent_sessions(
     conn: sqlite3.Connection,
     date_from: str,
     date_to: str,
     limit: int = 20,
-    project_id: Optional[str] = None
+    project_id: ProjectScope = None
 ) -> list[dict]:
     """
     Query top sessions with aggregated stats and per-model breakdowns.
@@ -606,7 +855,7 @@ def query_recent_sessions(
         date_from: Start date (YYYY-MM-DD)
         date_to: End date (YYYY-MM-DD)
         limit: Maximum number of sessions to return
-        project_id: Optional project filter
+        project_id: Optional project filter (a project ID or a list of IDs)
 
     Returns:
         List of session dicts, sorted by cost descending
@@ -625,11 +874,11 @@ def query_recent_sessions(
                 MIN(timestamp_local) as first_time,
                 MAX(timestamp_local) as last_time
             FROM message_entries
-            WHERE date >= ? AND date <= ? AND project_id = ? AND session_id IS NOT NULL
+            WHERE date >= ? AND date <= ? AND project_id IN (SELECT value FROM json_each(?)) AND session_id IS NOT NULL
             GROUP BY session_id
             ORDER BY cost DESC, tokens DESC, last_time DESC
             LIMIT ?
-        """, (date_from, date_to, project_id, limit))
+        """, (date_from, date_to, project_scope_param(project_id), limit))
     else:
         cursor.execute("""
             SELECT
@@ -642,16 +891,33 @@ def query_recent_sessions(
                 MIN(timestamp_local) as first_time,
                 MAX(timestamp_local) as last_time
             FROM message_entries
-            WHERE date >= ? AND date <= ? AND session_id IS NOT NULL
+            WHERE date >= ? AND date <= ? AND session_id IS NOT NULL AND origin = 'local'
             GROUP BY session_id
             ORDER BY cost DESC, tokens DESC, last_time DESC
             LIMIT ?
         """, (date_from, date_to, limit))
 
     summary_rows = cursor.fetchall()
+    return _sessions_with_models(conn, summary_rows, date_from, date_to, project_id)
+
+
+def _sessions_with_models(
+    conn: sqlite3.Connection,
+    summary_rows: list[tuple],
+    date_from: str,
+    date_to: str,
+    project_id: ProjectScope = None
+) -> list[dict]:
+    """
+    Build session dicts with per-model breakdowns from session summary rows.
+
+    Summary rows are (session_id, messages, tokens, input_tokens, output_tokens,
+    cost, first_time, last_time); result order follows the rows.
+    """
     if not summary_rows:
         return []
 
+    cursor = conn.cursor()
     session_ids = [row[0] for row in summary_rows]
     placeholders = ", ".join("?" for _ in session_ids)
 
@@ -668,9 +934,9 @@ def query_recent_sessions(
                 MIN(timestamp_local) as first_time,
                 MAX(timestamp_local) as last_time
             FROM message_entries
-            WHERE date >= ? AND date <= ? AND project_id = ? AND session_id IN ({placeholders})
+            WHERE date >= ? AND date <= ? AND project_id IN (SELECT value FROM json_each(?)) AND session_id IN ({placeholders})
             GROUP BY session_id, COALESCE(model, 'Unknown')
-        """, (date_from, date_to, project_id, *session_ids))
+        """, (date_from, date_to, project_scope_param(project_id), *session_ids))
     else:
         cursor.execute(f"""
             SELECT
@@ -684,7 +950,7 @@ def query_recent_sessions(
                 MIN(timestamp_local) as first_time,
                 MAX(timestamp_local) as last_time
             FROM message_entries
-            WHERE date >= ? AND date <= ? AND session_id IN ({placeholders})
+            WHERE date >= ? AND date <= ? AND session_id IN ({placeholders}) AND origin = 'local'
             GROUP BY session_id, COALESCE(model, 'Unknown')
         """, (date_from, date_to, *session_ids))
 
@@ -736,11 +1002,157 @@ def query_recent_sessions(
     return results
 
 
+# Sort orders for list_sessions: column and direction (session_id ASC breaks ties)
+SESSION_SORTS = {
+    "cost": ("cost", "DESC"),
+    "tokens": ("tokens", "DESC"),
+    "messages": ("messages", "DESC"),
+    "recent": ("last_time", "DESC"),
+    "oldest": ("first_time", "ASC"),
+}
+
+MAX_SESSION_PAGE_SIZE = 500
+
+
+def _session_filter_key(filters: dict, sort: str) -> str:
+    return hashlib.sha1(json.dumps([filters, sort], sort_keys=True).encode("utf-8")).hexdigest()[:12]
+
+
+def encode_session_cursor(filters: dict, sort: str, last_value, last_session_id: str) -> str:
+    """Encode the position after a session row as an opaque cursor string."""
+    payload = json.dumps({"f": _session_filter_key(filters, sort), "v": last_value, "s": last_session_id})
+    return base64.urlsafe_b64encode(payload.encode("utf-8")).decode("ascii").rstrip("=")
+
+
+def decode_session_cursor(cursor: str, filters: dict, sort: str) -> tuple:
+    """
+    Decode a cursor from encode_session_cursor.
+
+    Raises:
+        ValueError: If the cursor is malformed or was issued for another filter/sort
+    """
+    try:
+        padded = cursor + "=" * (-len(cursor) % 4)
+        payload = json.loads(base64.urlsafe_b64decode(padded.encode("ascii")))
+        key, value, session_id = payload["f"], payload["v"], payload["s"]
+    except (ValueError, KeyError, TypeError) as e:
+        raise ValueError(f"Invalid session cursor: {cursor}") from e
+    if key != _session_filter_key(filters, sort):
+        raise ValueError("Session cursor does not match the current filter or sort")
+    return value, session_id
+
+
+def query_sessions_page(
+    conn: sqlite3.Connection,
+    date_from: str,
+    date_to: str,
+    project_id: Optional[str] = None,
+    model: Optional[str] = None,
+    search: Optional[str] = None,
+    sort: str = "cost",
+    cursor: Optional[str] = None,
+    page_size: int = 100
+) -> dict:
+    """
+    Query one page of sessions for virtualized (infinite scroll) tables.
+
+    Pagination is keyset-based: the cursor encodes the sort value and
+    session_id of the last returned row, so pages stay stable while new
+    sessions are ingested (new rows only shift total_count).
+
+    Args:
+        conn: Database connection
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Optional project filter
+        model: Only sessions that used this model (optional)
+        search: Session ID substring (optional)
+        sort: One of SESSION_SORTS
+        cursor: next_cursor of the previous page (None for the first page)
+        page_size: Rows per page (1-500)
+
+    Returns:
+        {"sessions": [...], "next_cursor": str | None, "total_count": int,
+         "page_size": int, "sort": str}
+
+    Raises:
+        ValueError: For an unknown sort or invalid cursor
+    """
+    if sort not in SESSION_SORTS:
+        raise ValueError(f"Unknown sort: {sort} (expected one of {', '.join(SESSION_SORTS)})")
+    page_size = max(1, min(int(page_size), MAX_SESSION_PAGE_SIZE))
+    column, direction = SESSION_SORTS[sort]
+
+    filters = {"from": date_from, "to": date_to, "project_id": project_id, "model": model, "search": search}
+    where = ["date >= ?", "date <= ?", "session_id IS NOT NULL"]
+    params: list = [date_from, date_to]
+    if project_id:
+        where.append("project_id = ?")
+        params.append(project_id)
+    else:
+        where.append("origin = 'local'")
+    if model:
+        where.append("session_id IN (SELECT session_id FROM message_entries WHERE date >= ? AND date <= ? AND model = ?)")
+        params.extend([date_from, date_to, model])
+    if search:
+        where.append("session_id LIKE ?")
+        params.append(f"%{search}%")
+    where_sql = " AND ".join(where)
+
+    db_cursor = conn.cursor()
+    db_cursor.execute(f"SELECT COUNT(DISTINCT session_id) FROM message_entries WHERE {where_sql}", params)
+    total_count = db_cursor.fetchone()[0] or 0
+
+    after_sql = ""
+    after_params: list = []
+    if cursor:
+        last_value, last_session_id = decode_session_cursor(cursor, filters, sort)
+        op = "<" if direction == "DESC" else ">"
+        after_sql = f"WHERE {column} {op} ? OR ({column} = ? AND session_id > ?)"
+        after_params = [last_value, last_value, last_session_id]
+
+    db_cursor.execute(f"""
+        SELECT * FROM (
+            SELECT
+                session_id,
+                COUNT(*) as messages,
+                SUM(total_tokens) as tokens,
+                SUM(input_tokens) as input_tokens,
+                SUM(output_tokens) as output_tokens,
+                SUM(COALESCE(cost_usd, 0)) as cost,
+                MIN(timestamp_local) as first_time,
+                MAX(timestamp_local) as last_time
+            FROM message_entries
+            WHERE {where_sql}
+            GROUP BY session_id
+        )
+        {after_sql}
+        ORDER BY {column} {direction}, session_id ASC
+        LIMIT ?
+    """, (*params, *after_params, page_size + 1))
+    rows = db_cursor.fetchall()
+
+    has_more = len(rows) > page_size
+    rows = rows[:page_size]
+    next_cursor = None
+    if has_more:
+        column_index = {"messages": 1, "tokens": 2, "cost": 5, "first_time": 6, "last_time": 7}[column]
+        next_cursor = encode_session_cursor(filters, sort, rows[-1][column_index], rows[-1][0])
+
+    return {
+        "sessions": _sessions_with_models(conn, rows, date_from, date_to, project_id),
+        "next_cursor": next_cursor,
+        "total_count": total_count,
+        "page_size": page_size,
+        "sort": sort,
+    }
+
+
 def query_totals(
     conn: sqlite3.Connection,
     date_from: str,
     date_to: str,
-    project_id: Optional[str] = None
+    project_id: ProjectScope = None
 ) -> dict:
     """
     Query total statistics for a date range.
@@ -749,7 +1161,7 @@ def query_totals(
         conn: Database connection
         date_from: Start date (YYYY-MM-DD)
         date_to: End date (YYYY-MM-DD)
-        project_id: Optional project filter
+        project_id: Optional project filter (a project ID or a list of IDs)
 
     Returns:
         Dict with all totals including token breakdowns
@@ -769,8 +1181,8 @@ def query_totals(
                 SUM(cache_write_tokens) as cache_write,
                 MIN(timestamp_local) as first_timestamp
             FROM message_entries
-            WHERE date >= ? AND date <= ? AND project_id = ?
-        """, (date_from, date_to, project_id))
+            WHERE date >= ? AND date <= ? AND project_id IN (SELECT value FROM json_each(?))
+        """, (date_from, date_to, project_scope_param(project_id)))
     else:
         cursor.execute("""
             SELECT
@@ -784,7 +1196,7 @@ def query_totals(
                 SUM(cache_write_tokens) as cache_write,
                 MIN(timestamp_local) as first_timestamp
             FROM message_entries
-            WHERE date >= ? AND date <= ?
+            WHERE date >= ? AND date <= ? AND origin = 'local'
         """, (date_from, date_to))
     row = cursor.fetchone()
 
@@ -870,7 +1282,7 @@ def query_day_details(conn: sqlite3.Connection, date: str, project_id: Optional[
                 SUM(output_tokens) as output_tokens,
                 SUM(COALESCE(cost_usd, 0)) as cost
             FROM message_entries
-            WHERE date = ? AND model IS NOT NULL
+            WHERE date = ? AND model IS NOT NULL AND origin = 'local'
             GROUP BY model
             ORDER BY tokens DESC
         """, (date,))
@@ -916,7 +1328,7 @@ def query_day_details(conn: sqlite3.Connection, date: str, project_id: Optional[
                 MIN(timestamp_local) as first_time,
                 MAX(timestamp_local) as last_time
             FROM message_entries
-            WHERE date = ? AND session_id IS NOT NULL
+            WHERE date = ? AND session_id IS NOT NULL AND origin = 'local'
             GROUP BY session_id
             ORDER BY first_time
         """, (date,))
@@ -959,7 +1371,7 @@ def query_day_details(conn: sqlite3.Connection, date: str, project_id: Optional[
                 SUM(output_tokens) as output_tokens,
                 SUM(COALESCE(cost_usd, 0)) as cost
             FROM message_entries
-            WHERE date = ?
+            WHERE date = ? AND origin = 'local'
         """, (date,))
     totals_row = cursor.fetchone()
 
@@ -1014,7 +1426,7 @@ def query_model_details(
         cursor.execute("""
             SELECT date, COUNT(*) as messages, SUM(total_tokens) as tokens
             FROM message_entries
-            WHERE model = ? AND date >= ? AND date <= ?
+            WHERE model = ? AND date >= ? AND date <= ? AND origin = 'local'
             GROUP BY date
             ORDER BY date
         """, (model, date_from, date_to))
@@ -1050,7 +1462,7 @@ def query_model_details(
                 SUM(cache_write_tokens) as cache_write,
                 SUM(COALESCE(cost_usd, 0)) as cost
             FROM message_entries
-            WHERE model = ? AND date >= ? AND date <= ?
+            WHERE model = ? AND date >= ? AND date <= ? AND origin = 'local'
         """, (model, date_from, date_to))
     row = cursor.fetchone()
 
@@ -1080,7 +1492,7 @@ def query_model_details(
                 MIN(timestamp_local) as first_time,
                 MAX(timestamp_local) as last_time
             FROM message_entries
-            WHERE model = ? AND date >= ? AND date <= ? AND session_id IS NOT NULL
+            WHERE model = ? AND date >= ? AND date <= ? AND session_id IS NOT NULL AND origin = 'local'
             GROUP BY session_id
             ORDER BY tokens DESC
             LIMIT 10
@@ -1126,7 +1538,7 @@ def query_session_details(conn: sqlite3.Connection, session_id: str, project_id:
         project_id: Optional project filter (not typically used for sessions, but for consistency)
 
     Returns:
-        Dict with session totals, messages, and metadata
+        Dict with session totals, messages (each with its stable content_hash), and metadata
     """
     cursor = conn.cursor()
 
@@ -1135,7 +1547,7 @@ def query_session_details(conn: sqlite3.Connection, session_id: str, project_id:
         cursor.execute("""
             SELECT
                 timestamp_local, model, input_tokens, output_tokens,
-                cache_read_tokens, cache_write_tokens, cost_usd
+                cache_read_tokens, cache_write_tokens, cost_usd, content_hash
             FROM message_entries
             WHERE session_id = ? AND project_id = ?
             ORDER BY timestamp_local
@@ -1144,7 +1556,7 @@ def query_session_details(conn: sqlite3.Connection, session_id: str, project_id:
         cursor.execute("""
             SELECT
                 timestamp_local, model, input_tokens, output_tokens,
-                cache_read_tokens, cache_write_tokens, cost_usd
+                cache_read_tokens, cache_write_tokens, cost_usd, content_hash
             FROM message_entries
             WHERE session_id = ?
             ORDER BY timestamp_local
@@ -1158,7 +1570,8 @@ def query_session_details(conn: sqlite3.Connection, session_id: str, project_id:
             "output_tokens": r[3] or 0,
             "cache_read": r[4] or 0,
             "cache_write": r[5] or 0,
-            "cost": round(r[6] or 0, 6) if r[6] else 0
+            "cost": round(r[6] or 0, 6) if r[6] else 0,
+            "content_hash": r[7]
         }
         for r in cursor.fetchall()
     ]
@@ -1240,6 +1653,88 @@ def query_session_details(conn: sqlite3.Connection, session_id: str, project_id:
     }
 
 
+def query_session_source_files(conn: sqlite3.Connection, session_id: str) -> list[dict]:
+    """
+    Get the JSONL files that contain messages of a session.
+
+    Args:
+        conn: Database connection
+        session_id: Session identifier
+
+    Returns:
+        List of {"source_file", "project_id", "messages"} dicts, main session file first
+    """
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT source_file, project_id, COUNT(*) as messages, MIN(timestamp) as first_ts
+        FROM message_entries
+        WHERE session_id = ?
+        GROUP BY source_file
+        ORDER BY first_ts, source_file
+    """, (session_id,))
+    return [
+        {"source_file": row[0], "project_id": row[1], "messages": row[2]}
+        for row in cursor.fetchall()
+    ]
+
+
+def query_metric_records(
+    conn: sqlite3.Connection,
+    date_from: str,
+    date_to: str,
+    project_id: str | None = None
+) -> list[dict]:
+    """
+    Get normalized message records for metric plugins.
+
+    Without a project filter only local messages are returned (imported
+    sessions are excluded from personal totals).
+
+    Args:
+        conn: Database connection
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Optional project filter
+
+    Returns:
+        List of {"timestamp", "date", "hour", "session_id", "project_id", "model",
+        "input_tokens", "output_tokens", "cache_read_tokens", "cache_write_tokens",
+        "total_tokens", "cost_usd", "content_hash"} dicts in timestamp order
+    """
+    if project_id:
+        scope, params = "project_id = ?", (date_from, date_to, project_id)
+    else:
+        scope, params = "origin = 'local'", (date_from, date_to)
+
+    cursor = conn.cursor()
+    cursor.execute(f"""
+        SELECT timestamp_local, date, session_id, project_id, model,
+               input_tokens, output_tokens, cache_read_tokens, cache_write_tokens,
+               total_tokens, COALESCE(cost_usd, 0), content_hash
+        FROM message_entries
+        WHERE date >= ? AND date <= ? AND {scope}
+        ORDER BY timestamp, entry_hash
+    """, params)
+    return [
+        {
+            "timestamp": row[0],
+            "date": row[1],
+            "hour": int(row[0][11:13]) if len(row[0]) >= 13 else 0,
+            "session_id": row[2],
+            "project_id": row[3],
+            "model": row[4],
+            "input_tokens": row[5] or 0,
+            "output_tokens": row[6] or 0,
+            "cache_read_tokens": row[7] or 0,
+            "cache_write_tokens": row[8] or 0,
+            "total_tokens": row[9] or 0,
+            "cost_usd": row[10],
+            "content_hash": row[11],
+        }
+        for row in cursor.fetchall()
+    ]
+
+
 def get_limit_events(conn: sqlite3.Connection, date_from: str, date_to: str) -> list[dict]:
     """
     Get limit reset events for a date range.
@@ -1279,3 +1774,416 @@ def get_limit_events(conn: sqlite3.Connection, date_from: str, date_to: str) ->
         })
     
     return events
+
+
+def query_limit_impact(conn: sqlite3.Connection, date_from: str, date_to: str) -> dict:
+    """
+    Analyze how often limits were hit, how long they locked usage out,
+    and which projects/hours drive limit hits.
+
+    Lockout is reported two ways:
+    - nominal: from the limit hit until the announced reset
+    - effective: from the limit hit until the next recorded message
+      (capped at the reset), i.e. time actually spent waiting
+
+    Projects are credited with the tokens they consumed in the 5 hours
+    before each hit (the rolling window the limit is computed over).
+
+    Args:
+        conn: Database connection
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+
+    Returns:
+        Dict with totals, by_type, by_project, by_hour, by_weekday and events
+    """
+    from datetime import timedelta
+    from command_center.utils.project_helpers import extract_project_id
+
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT limit_type, occurred_at_local, reset_at_local, date, hour, session_id, source_file
+        FROM limit_events
+        WHERE date >= ? AND date <= ?
+        ORDER BY occurred_at_local
+    """, (date_from, date_to))
+    rows = cursor.fetchall()
+
+    by_type: dict[str, int] = {}
+    by_hour = [0] * 24
+    by_weekday = [0] * 7
+    projects: dict[str, dict] = {}
+    events = []
+    nominal_total = 0.0
+    effective_total = 0.0
+
+    for limit_type, occurred_at, reset_at, date, hour, session_id, source_file in rows:
+        try:
+            occurred_dt = datetime.fromisoformat(occurred_at)
+            reset_dt = datetime.fromisoformat(reset_at)
+        except (TypeError, ValueError):
+            continue
+
+        nominal_minutes = max((reset_dt - occurred_dt).total_seconds() / 60, 0)
+
+        # Next activity after the hit, capped at reset time
+        cursor.execute("""
+            SELECT MIN(timestamp_local) FROM message_entries
+            WHERE timestamp_local > ? AND date >= ? AND origin = 'local'
+        """, (occurred_at, date))
+        next_row = cursor.fetchone()
+        effective_minutes = nominal_minutes
+        if next_row and next_row[0]:
+            try:
+                next_dt = datetime.fromisoformat(next_row[0])
+                effective_minutes = min(
+                    max((next_dt - occurred_dt).total_seconds() / 60, 0),
+                    nominal_minutes
+                )
+            except ValueError:
+                pass
+
+        # Token consumption per project in the 5h window before the hit
+        window_start = (occurred_dt - timedelta(hours=5)).isoformat()
+        cursor.execute("""
+            SELECT project_id, SUM(total_tokens), SUM(COALESCE(cost_usd, 0))
+            FROM message_entries
+            WHERE timestamp_local >= ? AND timestamp_local <= ? AND origin = 'local'
+            GROUP BY project_id
+        """, (window_start, occurred_at))
+        for project_id, tokens, cost in cursor.fetchall():
+            entry = projects.setdefault(project_id, {"hits": 0, "window_tokens": 0, "window_cost": 0.0})
+            entry["window_tokens"] += tokens or 0
+            entry["window_cost"] += cost or 0
+
+        project_id = extract_project_id(source_file) if source_file else "unknown"
+        projects.setdefault(project_id, {"hits": 0, "window_tokens": 0, "window_cost": 0.0})["hits"] += 1
+
+        by_type[limit_type] = by_type.get(limit_type, 0) + 1
+        by_hour[hour] += 1
+        by_weekday[occurred_dt.weekday()] += 1
+        nominal_total += nominal_minutes
+        effective_total += effective_minutes
+
+        events.append({
+            "limit_type": limit_type,
+            "occurred_at": occurred_at,
+            "reset_at": reset_at,
+            "date": date,
+            "hour": hour,
+            "session_id": session_id,
+            "project_id": project_id,
+            "lockout_minutes": round(nominal_minutes, 1),
+            "effective_lockout_minutes": round(effective_minutes, 1),
+        })
+
+    hits = len(events)
+    total_window_tokens = sum(p["window_tokens"] for p in projects.values()) or 1
+
+    by_project = sorted(
+        (
+            {
+                "project_id": project_id,
+                "hits": data["hits"],
+                "window_tokens": data["window_tokens"],
+                "window_cost": round(data["window_cost"], 4),
+                "window_share": round(data["window_tokens"] / total_window_tokens * 100, 1),
+            }
+            for project_id, data in projects.items()
+        ),
+        key=lambda p: (-p["window_tokens"], -p["hits"])
+    )
+
+    return {
+        "range": {"from": date_from, "to": date_to},
+        "totals": {
+            "hits": hits,
+            "days_with_hits": len({e["date"] for e in events}),
+            "lockout_minutes": round(nominal_total, 1),
+            "effective_lockout_minutes": round(effective_total, 1),
+            "avg_lockout_minutes": round(nominal_total / hits, 1) if hits else 0,
+            "avg_effective_lockout_minutes": round(effective_total / hits, 1) if hits else 0,
+        },
+        "by_type": by_type,
+        "by_project": by_project,
+        "by_hour": [{"hour": h, "hits": by_hour[h]} for h in range(24)],
+        "by_weekday": [{"weekday": d, "hits": by_weekday[d]} for d in range(7)],
+        "events": events,
+    }
+
+
+def query_mcp_usage(conn: sqlite3.Connection, date_from: str, date_to: str,
+                    project_id: Optional[str] = None) -> dict:
+    """
+    Summarize MCP server tool calls for a date range.
+
+    Tokens are the calling responses' tokens (split between the tool calls of
+    each response) and the estimated size of the tool results, i.e. the
+    context an integration costs.
+
+    Args:
+        conn: Database connection
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Optional project filter
+
+    Returns:
+        {
+            "range": {"from", "to"},
+            "totals": {"calls", "failures", "failure_rate", "sessions", "message_tokens", "result_tokens"},
+            "servers": [{"server", "calls", "failures", "failure_rate", "pending", "sessions",
+                         "message_tokens", "result_tokens",
+                         "tools": [{"tool", "calls", "failures", "result_tokens"}, ...]}, ...]
+        }
+        failure_rate is a percentage of calls with a logged result (pending calls have none yet).
+    """
+    project_filter = "AND project_id = ?" if project_id else ""
+    params: list = [date_from, date_to] + ([project_id] if project_id else [])
+    cursor = conn.cursor()
+    cursor.execute(f"""
+        SELECT server, tool, COUNT(*), SUM(is_error = 1), SUM(is_error IS NULL),
+               SUM(message_tokens), SUM(result_tokens)
+        FROM mcp_calls
+        WHERE date BETWEEN ? AND ? {project_filter}
+        GROUP BY server, tool
+    """, params)
+    tool_rows = cursor.fetchall()
+    cursor.execute(f"""
+        SELECT server, COUNT(DISTINCT session_id)
+        FROM mcp_calls
+        WHERE date BETWEEN ? AND ? {project_filter}
+        GROUP BY server
+    """, params)
+    sessions_by_server = dict(cursor.fetchall())
+    cursor.execute(f"""
+        SELECT COUNT(DISTINCT session_id)
+        FROM mcp_calls
+        WHERE date BETWEEN ? AND ? {project_filter}
+    """, params)
+    total_sessions = cursor.fetchone()[0]
+
+    def failure_rate(failures: int, calls: int, pending: int) -> Optional[float]:
+        completed = calls - pending
+        return round(failures / completed * 100, 1) if completed else None
+
+    servers: dict[str, dict] = {}
+    for server, tool, calls, failures, pending, message_tokens, result_tokens in tool_rows:
+        entry = servers.setdefault(server, {
+            "server": server, "calls": 0, "failures": 0, "pending": 0,
+            "sessions": sessions_by_server.get(server, 0),
+            "message_tokens": 0, "result_tokens": 0, "tools": [],
+        })
+        entry["calls"] += calls
+        entry["failures"] += failures or 0
+        entry["pending"] += pending or 0
+        entry["message_tokens"] += message_tokens or 0
+        entry["result_tokens"] += result_tokens or 0
+        entry["tools"].append({
+            "tool": tool, "calls": calls, "failures": failures or 0, "result_tokens": result_tokens or 0,
+        })
+    for entry in servers.values():
+        entry["failure_rate"] = failure_rate(entry["failures"], entry["calls"], entry["pending"])
+        entry["tools"].sort(key=lambda t: (-t["calls"], t["tool"]))
+
+    calls = sum(s["calls"] for s in servers.values())
+    failures = sum(s["failures"] for s in servers.values())
+    pending = sum(s["pending"] for s in servers.values())
+    return {
+        "range": {"from": date_from, "to": date_to},
+        "totals": {
+            "calls": calls,
+            "failures": failures,
+            "failure_rate": failure_rate(failures, calls, pending),
+            "sessions": total_sessions,
+            "message_tokens": sum(s["message_tokens"] for s in servers.values()),
+            "result_tokens": sum(s["result_tokens"] for s in servers.values()),
+        },
+        "servers": sorted(
+            servers.values(),
+            key=lambda s: (-(s["message_tokens"] + s["result_tokens"]), s["server"])
+        ),
+    }
+
+
+def query_hook_stats(conn: sqlite3.Connection, date_from: str, date_to: str,
+                     project_id: Optional[str] = None) -> dict:
+    """
+    Summarize Claude Code hook executions for a date range.
+
+    A hook is identified by its event and command; blocked runs (the hook
+    stopped a tool call or the end of a turn on purpose) are counted apart
+    from failures.
+
+    Args:
+        conn: Database connection
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Optional project filter
+
+    Returns:
+        {
+            "range": {"from", "to"},
+            "totals": {"executions", "failures", "blocked", "failure_rate", "total_duration_ms"},
+            "by_event": [{"hook_event", "executions", "failures", "blocked", "total_duration_ms"}, ...],
+            "hooks": [{"hook_event", "command", "matchers", "executions", "failures", "blocked",
+                       "cancelled", "failure_rate", "timed_executions", "avg_duration_ms",
+                       "p95_duration_ms", "max_duration_ms", "total_duration_ms", "sessions"}, ...]
+        }
+        failure_rate is a percentage of executions; durations are null for hooks without timing.
+    """
+    project_filter = "AND project_id = ?" if project_id else ""
+    params: list = [date_from, date_to] + ([project_id] if project_id else [])
+    cursor = conn.cursor()
+    cursor.execute(f"""
+        SELECT hook_event, command, hook_name, outcome, duration_ms, session_id
+        FROM hook_executions
+        WHERE date BETWEEN ? AND ? {project_filter}
+    """, params)
+
+    hooks: dict[tuple[str, str], dict] = {}
+    for hook_event, command, hook_name, outcome, duration_ms, session_id in cursor.fetchall():
+        entry = hooks.setdefault((hook_event, command), {
+            "hook_event": hook_event, "command": command, "matchers": set(), "executions": 0,
+            "failures": 0, "blocked": 0, "cancelled": 0, "durations": [], "sessions": set(),
+        })
+        entry["matchers"].add(hook_name)
+        entry["executions"] += 1
+        entry["failures"] += outcome == "error"
+        entry["blocked"] += outcome == "blocked"
+        entry["cancelled"] += outcome == "cancelled"
+        if duration_ms is not None:
+            entry["durations"].append(duration_ms)
+        if session_id:
+            entry["sessions"].add(session_id)
+
+    def rate(count: int, total: int) -> Optional[float]:
+        return round(count / total * 100, 1) if total else None
+
+    results = []
+    for entry in hooks.values():
+        durations = sorted(entry.pop("durations"))
+        results.append({
+            **entry,
+            "matchers": sorted(entry["matchers"]),
+            "sessions": len(entry["sessions"]),
+            "failure_rate": rate(entry["failures"], entry["executions"]),
+            "timed_executions": len(durations),
+            "avg_duration_ms": round(sum(durations) / len(durations)) if durations else None,
+            "p95_duration_ms": durations[min(len(durations) - 1, int(len(durations) * 0.95))] if durations else None,
+            "max_duration_ms": durations[-1] if durations else None,
+            "total_duration_ms": sum(durations),
+        })
+    results.sort(key=lambda h: (-h["total_duration_ms"], -h["executions"], h["hook_event"], h["command"]))
+
+    by_event: dict[str, dict] = {}
+    for hook in results:
+        event = by_event.setdefault(hook["hook_event"], {
+            "hook_event": hook["hook_event"], "executions": 0, "failures": 0, "blocked": 0, "total_duration_ms": 0,
+        })
+        for key in ("executions", "failures", "blocked", "total_duration_ms"):
+            event[key] += hook[key]
+
+    executions = sum(h["executions"] for h in results)
+    failures = sum(h["failures"] for h in results)
+    return {
+        "range": {"from": date_from, "to": date_to},
+        "totals": {
+            "executions": executions,
+            "failures": failures,
+            "blocked": sum(h["blocked"] for h in results),
+            "failure_rate": rate(failures, executions),
+            "total_duration_ms": sum(h["total_duration_ms"] for h in results),
+        },
+        "by_event": sorted(by_event.values(), key=lambda e: -e["executions"]),
+        "hooks": results,
+    }
+
+
+# Effort levels in display order; other values follow alphabetically, unknown last
+EFFORT_ORDER = ("none", "low", "medium", "high", "max", "ultrathink")
+
+
+def query_effort_breakdown(conn: sqlite3.Connection, model: Optional[str], date_from: str, date_to: str,
+                           project_id: Optional[str] = None) -> dict:
+    """
+    Aggregate cost, tokens and latency of a model's responses by effort/thinking setting.
+
+    Responses without a recorded setting (older logs, or not rescanned since
+    schema v13) are grouped as "unknown".
+
+    Args:
+        conn: Database connection
+        model: Model name (None for all models)
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Optional project filter
+
+    Returns:
+        {
+            "model", "range": {"from", "to"},
+            "settings": [{"effort", "requests", "thinking_requests", "thinking_share", "output_tokens",
+                          "total_tokens", "cost", "cost_share", "avg_cost", "avg_output_tokens",
+                          "avg_latency_ms", "p50_latency_ms", "p95_latency_ms"}, ...]
+        }
+    """
+    filters = ["e.date BETWEEN ? AND ?", "e.origin = 'local'"]
+    params: list = [date_from, date_to]
+    if model:
+        filters.append("e.model = ?")
+        params.append(model)
+    if project_id:
+        filters.append("e.project_id = ?")
+        params.append(project_id)
+    cursor = conn.cursor()
+    cursor.execute(f"""
+        SELECT COALESCE(r.effort, 'unknown'), COALESCE(r.used_thinking, 0), r.latency_ms,
+               e.output_tokens, e.total_tokens, COALESCE(e.cost_usd, 0)
+        FROM message_entries e
+        LEFT JOIN request_settings r ON r.entry_hash = e.entry_hash
+        WHERE {" AND ".join(filters)}
+    """, params)
+
+    groups: dict[str, dict] = {}
+    for effort, used_thinking, latency_ms, output_tokens, total_tokens, cost in cursor.fetchall():
+        group = groups.setdefault(effort, {
+            "effort": effort, "requests": 0, "thinking_requests": 0,
+            "output_tokens": 0, "total_tokens": 0, "cost": 0.0, "latencies": [],
+        })
+        group["requests"] += 1
+        group["thinking_requests"] += used_thinking
+        group["output_tokens"] += output_tokens or 0
+        group["total_tokens"] += total_tokens or 0
+        group["cost"] += cost
+        if latency_ms is not None:
+            group["latencies"].append(latency_ms)
+
+    def percentile(values: list[int], fraction: float) -> Optional[int]:
+        return values[min(len(values) - 1, int(len(values) * fraction))] if values else None
+
+    total_cost = sum(g["cost"] for g in groups.values())
+    settings = []
+    for group in groups.values():
+        latencies = sorted(group.pop("latencies"))
+        requests = group["requests"]
+        settings.append({
+            **group,
+            "cost": round(group["cost"], 4),
+            "thinking_share": round(group["thinking_requests"] / requests * 100, 1),
+            "cost_share": round(group["cost"] / total_cost * 100, 1) if total_cost else 0,
+            "avg_cost": round(group["cost"] / requests, 6),
+            "avg_output_tokens": round(group["output_tokens"] / requests),
+            "avg_latency_ms": round(sum(latencies) / len(latencies)) if latencies else None,
+            "p50_latency_ms": percentile(latencies, 0.5),
+            "p95_latency_ms": percentile(latencies, 0.95),
+        })
+
+    def order(effort: str) -> tuple:
+        if effort in EFFORT_ORDER:
+            return (0, EFFORT_ORDER.index(effort), "")
+        return (2 if effort == "unknown" else 1, 0, effort)
+
+    return {
+        "model": model,
+        "range": {"from": date_from, "to": date_to},
+        "settings": sorted(settings, key=lambda s: order(s["effort"])),
+    }
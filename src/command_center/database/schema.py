@@ -4,8 +4,10 @@ Database schema definitions and migrations
 import sqlite3
 from typing import Optional
 
+from command_center.collectors.deduplication import CONTENT_HASH_FIELDS, hash_content_fields
 
-CURRENT_SCHEMA_VERSION = 3
+
+CURRENT_SCHEMA_VERSION = 16
 
 
 def get_schema_version(conn: sqlite3.Connection) -> int:
@@ -82,7 +84,11 @@ def create_message_entries_table(conn: sqlite3.Connection):
             cache_write_tokens INTEGER DEFAULT 0,
             total_tokens INTEGER DEFAULT 0,
             source_file TEXT NOT NULL,
-            project_id TEXT DEFAULT 'unknown'
+            project_id TEXT DEFAULT 'unknown',
+            config_dir TEXT,
+            cost_source TEXT,
+            origin TEXT NOT NULL DEFAULT 'local',
+            content_hash TEXT
         )
     """)
     cursor.execute("""
@@ -105,6 +111,18 @@ def create_message_entries_table(conn: sqlite3.Connection):
         CREATE INDEX IF NOT EXISTS idx_entries_project_id
         ON message_entries(project_id)
     """)
+    cursor.execute("""
+        CREATE INDEX IF NOT EXISTS idx_entries_config_dir
+        ON message_entries(config_dir)
+    """)
+    cursor.execute("""
+        CREATE INDEX IF NOT EXISTS idx_entries_origin
+        ON message_entries(origin)
+    """)
+    cursor.execute("""
+        CREATE INDEX IF NOT EXISTS idx_entries_content_hash
+        ON message_entries(content_hash)
+    """)
     conn.commit()
 
 
@@ -198,6 +216,236 @@ def create_limit_events_table(conn: sqlite3.Connection):
     conn.commit()
 
 
+def create_budget_periods_table(conn: sqlite3.Connection):
+    """Create budget_periods table for monthly budget snapshots"""
+    cursor = conn.cursor()
+    cursor.execute("""
+        CREATE TABLE IF NOT EXISTS budget_periods (
+            period TEXT PRIMARY KEY,
+            date_from TEXT NOT NULL,
+            date_to TEXT NOT NULL,
+            budget_usd REAL,
+            actual_cost_usd REAL DEFAULT 0,
+            message_count INTEGER DEFAULT 0,
+            total_tokens INTEGER DEFAULT 0,
+            snapshot_at TEXT NOT NULL
+        )
+    """)
+    conn.commit()
+
+
+def create_report_snapshots_table(conn: sqlite3.Connection):
+    """Create report_snapshots table for immutable named statistics snapshots"""
+    cursor = conn.cursor()
+    cursor.execute("""
+        CREATE TABLE IF NOT EXISTS report_snapshots (
+            snapshot_id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            date_from TEXT NOT NULL,
+            date_to TEXT NOT NULL,
+            project_id TEXT,
+            created_at TEXT NOT NULL,
+            app_version TEXT,
+            payload TEXT NOT NULL
+        )
+    """)
+    conn.commit()
+
+
+def create_price_versions_table(conn: sqlite3.Connection):
+    """Create price_versions table for effective-dated model pricing"""
+    cursor = conn.cursor()
+    cursor.execute("""
+        CREATE TABLE IF NOT EXISTS price_versions (
+            model TEXT NOT NULL,
+            effective_from TEXT NOT NULL,
+            effective_to TEXT,
+            input_cost_per_token REAL DEFAULT 0,
+            input_cost_per_token_above_200k REAL,
+            cache_creation_cost_per_token REAL DEFAULT 0,
+            cache_creation_cost_per_token_above_200k REAL,
+            cached_input_cost_per_token REAL DEFAULT 0,
+            cached_input_cost_per_token_above_200k REAL,
+            output_cost_per_token REAL DEFAULT 0,
+            output_cost_per_token_above_200k REAL,
+            source TEXT,
+            recorded_at TEXT NOT NULL,
+            PRIMARY KEY (model, effective_from)
+        )
+    """)
+    conn.commit()
+
+
+def create_session_outcomes_table(conn: sqlite3.Connection):
+    """Create session_outcomes table for classified session endings"""
+    cursor = conn.cursor()
+    cursor.execute("""
+        CREATE TABLE IF NOT EXISTS session_outcomes (
+            session_id TEXT PRIMARY KEY,
+            outcome TEXT NOT NULL,
+            committed INTEGER NOT NULL DEFAULT 0,
+            evidence TEXT,
+            files_signature TEXT NOT NULL,
+            classified_at TEXT NOT NULL
+        )
+    """)
+    conn.commit()
+
+
+def create_compaction_tables(conn: sqlite3.Connection):
+    """Create compaction_events and compaction_scans tables for context compaction tracking"""
+    cursor = conn.cursor()
+    cursor.execute("""
+        CREATE TABLE IF NOT EXISTS compaction_events (
+            session_id TEXT NOT NULL,
+            event_id TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            timestamp_local TEXT NOT NULL,
+            date TEXT NOT NULL,
+            trigger TEXT NOT NULL,
+            pre_tokens INTEGER,
+            post_tokens INTEGER,
+            PRIMARY KEY (session_id, event_id)
+        )
+    """)
+    cursor.execute("""
+        CREATE INDEX IF NOT EXISTS idx_compaction_events_date
+        ON compaction_events(date)
+    """)
+    cursor.execute("""
+        CREATE TABLE IF NOT EXISTS compaction_scans (
+            session_id TEXT PRIMARY KEY,
+            files_signature TEXT NOT NULL,
+            scanned_at TEXT NOT NULL
+        )
+    """)
+    conn.commit()
+
+
+def create_mcp_calls_table(conn: sqlite3.Connection):
+    """Create mcp_calls table for MCP server tool calls"""
+    cursor = conn.cursor()
+    cursor.execute("""
+        CREATE TABLE IF NOT EXISTS mcp_calls (
+            tool_use_id TEXT PRIMARY KEY,
+            server TEXT NOT NULL,
+            tool TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            timestamp_local TEXT NOT NULL,
+            year INTEGER NOT NULL,
+            date TEXT NOT NULL,
+            session_id TEXT,
+            project_id TEXT NOT NULL DEFAULT 'unknown',
+            is_error INTEGER,
+            message_tokens INTEGER NOT NULL DEFAULT 0,
+            result_tokens INTEGER NOT NULL DEFAULT 0,
+            source_file TEXT NOT NULL
+        )
+    """)
+    cursor.execute("""
+        CREATE INDEX IF NOT EXISTS idx_mcp_calls_date
+        ON mcp_calls(date)
+    """)
+    cursor.execute("""
+        CREATE INDEX IF NOT EXISTS idx_mcp_calls_server
+        ON mcp_calls(server, date)
+    """)
+    conn.commit()
+
+
+def create_hook_executions_table(conn: sqlite3.Connection):
+    """Create hook_executions table for Claude Code hook runs found in session logs"""
+    cursor = conn.cursor()
+    cursor.execute("""
+        CREATE TABLE IF NOT EXISTS hook_executions (
+            execution_id TEXT PRIMARY KEY,
+            hook_event TEXT NOT NULL,
+            hook_name TEXT NOT NULL,
+            command TEXT NOT NULL,
+            outcome TEXT NOT NULL,
+            duration_ms INTEGER,
+            timestamp TEXT NOT NULL,
+            timestamp_local TEXT NOT NULL,
+            year INTEGER NOT NULL,
+            date TEXT NOT NULL,
+            session_id TEXT,
+            project_id TEXT NOT NULL DEFAULT 'unknown',
+            source_file TEXT NOT NULL
+        )
+    """)
+    cursor.execute("""
+        CREATE INDEX IF NOT EXISTS idx_hook_executions_date
+        ON hook_executions(date)
+    """)
+    conn.commit()
+
+
+def create_request_settings_table(conn: sqlite3.Connection):
+    """Create request_settings table for per-response effort/thinking settings and latency"""
+    cursor = conn.cursor()
+    cursor.execute("""
+        CREATE TABLE IF NOT EXISTS request_settings (
+            entry_hash TEXT PRIMARY KEY,
+            effort TEXT,
+            thinking_budget INTEGER,
+            used_thinking INTEGER NOT NULL DEFAULT 0,
+            latency_ms INTEGER
+        )
+    """)
+    conn.commit()
+
+
+def create_daily_aggregate_history_table(conn: sqlite3.Connection):
+    """
+    Create the append-only daily_aggregate_history table.
+
+    A row is added whenever a day's totals come out different from its last
+    row (reason: ingest, reprice, merge, time-shift, time-shift-undo, demo).
+    Rows are never updated or deleted, and the table survives --rebuild-db.
+    """
+    cursor = conn.cursor()
+    cursor.execute("""
+        CREATE TABLE IF NOT EXISTS daily_aggregate_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            date TEXT NOT NULL,
+            recorded_at TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            message_count INTEGER NOT NULL,
+            session_count INTEGER NOT NULL,
+            total_tokens INTEGER NOT NULL,
+            total_cost_usd REAL NOT NULL
+        )
+    """)
+    cursor.execute("""
+        CREATE INDEX IF NOT EXISTS idx_daily_history_date
+        ON daily_aggregate_history(date, id)
+    """)
+    conn.commit()
+
+
+def create_timestamp_shift_tables(conn: sqlite3.Connection):
+    """Create timestamp_shifts and timestamp_shift_entries tables for clock corrections"""
+    cursor = conn.cursor()
+    cursor.execute("""
+        CREATE TABLE IF NOT EXISTS timestamp_shifts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            offset_seconds INTEGER NOT NULL,
+            filter TEXT NOT NULL,
+            messages INTEGER NOT NULL,
+            created_at TEXT NOT NULL,
+            undone_at TEXT
+        )
+    """)
+    cursor.execute("""
+        CREATE TABLE IF NOT EXISTS timestamp_shift_entries (
+            shift_id INTEGER NOT NULL,
+            entry_hash TEXT NOT NULL,
+            PRIMARY KEY (shift_id, entry_hash)
+        )
+    """)
+    conn.commit()
+
+
 def init_database(conn: sqlite3.Connection):
     """
     Initialize database schema.
@@ -216,6 +464,16 @@ def init_database(conn: sqlite3.Connection):
         create_hourly_aggregates_table(conn)
         create_model_aggregates_table(conn)
         create_limit_events_table(conn)
+        create_budget_periods_table(conn)
+        create_report_snapshots_table(conn)
+        create_price_versions_table(conn)
+        create_session_outcomes_table(conn)
+        create_compaction_tables(conn)
+        create_mcp_calls_table(conn)
+        create_hook_executions_table(conn)
+        create_request_settings_table(conn)
+        create_timestamp_shift_tables(conn)
+        create_daily_aggregate_history_table(conn)
         set_schema_version(conn, CURRENT_SCHEMA_VERSION)
     elif current_version < CURRENT_SCHEMA_VERSION:
         # Run migrations
@@ -251,6 +509,121 @@ def migrate_to_v3(conn: sqlite3.Connection):
         conn.commit()
 
 
+def migrate_to_v5(conn: sqlite3.Connection):
+    """
+    Migration to v5: Add config_dir column to message_entries.
+
+    Backfills the column from source_file (everything before '/projects/'),
+    so existing data is attributed to its Claude config dir without a rebuild.
+    """
+    cursor = conn.cursor()
+
+    cursor.execute("PRAGMA table_info(message_entries)")
+    columns = [row[1] for row in cursor.fetchall()]
+
+    if 'config_dir' not in columns:
+        cursor.execute("""
+            ALTER TABLE message_entries
+            ADD COLUMN config_dir TEXT
+        """)
+
+    cursor.execute("""
+        UPDATE message_entries
+        SET config_dir = SUBSTR(source_file, 1, INSTR(source_file, '/projects/') - 1)
+        WHERE config_dir IS NULL AND INSTR(source_file, '/projects/') > 0
+    """)
+
+    cursor.execute("""
+        CREATE INDEX IF NOT EXISTS idx_entries_config_dir
+        ON message_entries(config_dir)
+    """)
+
+    conn.commit()
+
+
+def migrate_to_v7(conn: sqlite3.Connection):
+    """
+    Migration to v7: Add price_versions table and message_entries.cost_source.
+
+    Existing rows keep cost_source NULL; repricing only leaves rows
+    explicitly marked 'jsonl' untouched.
+    """
+    cursor = conn.cursor()
+
+    cursor.execute("PRAGMA table_info(message_entries)")
+    columns = [row[1] for row in cursor.fetchall()]
+
+    if 'cost_source' not in columns:
+        cursor.execute("""
+            ALTER TABLE message_entries
+            ADD COLUMN cost_source TEXT
+        """)
+
+    conn.commit()
+    create_price_versions_table(conn)
+
+
+def migrate_to_v8(conn: sqlite3.Connection):
+    """
+    Migration to v8: Add message_entries.origin.
+
+    Existing rows are local; 'imported' marks rows from session archives.
+    """
+    cursor = conn.cursor()
+
+    cursor.execute("PRAGMA table_info(message_entries)")
+    columns = [row[1] for row in cursor.fetchall()]
+
+    if 'origin' not in columns:
+        cursor.execute("""
+            ALTER TABLE message_entries
+            ADD COLUMN origin TEXT NOT NULL DEFAULT 'local'
+        """)
+
+    cursor.execute("""
+        CREATE INDEX IF NOT EXISTS idx_entries_origin
+        ON message_entries(origin)
+    """)
+
+    conn.commit()
+
+
+def migrate_to_v15(conn: sqlite3.Connection):
+    """
+    Migration to v15: Add message_entries.content_hash.
+
+    Backfills the hash from the stored columns, so existing rows get the
+    same identifiers a fresh ingestion would give them.
+    """
+    cursor = conn.cursor()
+
+    cursor.execute("PRAGMA table_info(message_entries)")
+    columns = [row[1] for row in cursor.fetchall()]
+
+    if 'content_hash' not in columns:
+        cursor.execute("""
+            ALTER TABLE message_entries
+            ADD COLUMN content_hash TEXT
+        """)
+
+    rows = cursor.execute(f"""
+        SELECT entry_hash, {', '.join(CONTENT_HASH_FIELDS)}
+        FROM message_entries
+        WHERE content_hash IS NULL
+    """).fetchall()
+    cursor.executemany(
+        "UPDATE message_entries SET content_hash = ? WHERE entry_hash = ?",
+        [(hash_content_fields(row[1:]), row[0]) for row in rows],
+    )
+
+    cursor.execute("""
+        CREATE INDEX IF NOT EXISTS idx_entries_content_hash
+        ON message_entries(content_hash)
+    """)
+
+    conn.commit()
+
+
 def run_migrations(conn: sqlite3.Connection, from_version: int, to_version: int):
     """
     Run database migrations from one version to another.
@@ -270,6 +643,71 @@ def run_migrations(conn: sqlite3.Connection, from_version: int, to_version: int)
         migrate_to_v3(conn)
         set_schema_version(conn, 3)
 
+    # Migration to v4: Add budget_periods table
+    if from_version < 4 and to_version >= 4:
+        create_budget_periods_table(conn)
+        set_schema_version(conn, 4)
+
+    # Migration to v5: Add config_dir to message_entries
+    if from_version < 5 and to_version >= 5:
+        migrate_to_v5(conn)
+        set_schema_version(conn, 5)
+
+    # Migration to v6: Add report_snapshots table
+    if from_version < 6 and to_version >= 6:
+        create_report_snapshots_table(conn)
+        set_schema_version(conn, 6)
+
+    # Migration to v7: Add price_versions table and cost_source
+    if from_version < 7 and to_version >= 7:
+        migrate_to_v7(conn)
+        set_schema_version(conn, 7)
+
+    # Migration to v8: Add origin (local/imported) to message_entries
+    if from_version < 8 and to_version >= 8:
+        migrate_to_v8(conn)
+        set_schema_version(conn, 8)
+
+    # Migration to v9: Add session_outcomes table
+    if from_version < 9 and to_version >= 9:
+        create_session_outcomes_table(conn)
+        set_schema_version(conn, 9)
+
+    # Migration to v10: Add compaction_events and compaction_scans tables
+    if from_version < 10 and to_version >= 10:
+        create_compaction_tables(conn)
+        set_schema_version(conn, 10)
+
+    # Migration to v11: Add mcp_calls table
+    if from_version < 11 and to_version >= 11:
+        create_mcp_calls_table(conn)
+        set_schema_version(conn, 11)
+
+    # Migration to v12: Add hook_executions table
+    if from_version < 12 and to_version >= 12:
+        create_hook_executions_table(conn)
+        set_schema_version(conn, 12)
+
+    # Migration to v13: Add request_settings table
+    if from_version < 13 and to_version >= 13:
+        create_request_settings_table(conn)
+        set_schema_version(conn, 13)
+
+    # Migration to v14: Add timestamp_shifts and timestamp_shift_entries tables
+    if from_version < 14 and to_version >= 14:
+        create_timestamp_shift_tables(conn)
+        set_schema_version(conn, 14)
+
+    # Migration to v15: Add content_hash to message_entries
+    if from_version < 15 and to_version >= 15:
+        migrate_to_v15(conn)
+        set_schema_version(conn, 15)
+
+    # Migration to v16: Add daily_aggregate_history table
+    if from_version < 16 and to_version >= 16:
+        create_daily_aggregate_history_table(conn)
+        set_schema_version(conn, 16)
+
 
 def check_integrity(conn: sqlite3.Connection) -> bool:
     """
@@ -0,0 +1,229 @@
+"""
+Synthetic demo data for screenshots and conference demos.
+
+In demo mode (``COMMAND_CENTER_DEMO=1``) the package switches ``HOME`` to
+``COMMAND_CENTER_DEMO_HOME`` on import (``~/.claude/db/demo``, set by the
+desktop app), so every database and settings path resolves there and real
+session logs are never read. ``generate_demo_data`` fills that
+home's database with seeded, realistic usage: weekday-heavy activity over
+several months, working-hours sessions, a model mix that shifts to newer
+models over time and a handful of fictional projects.
+
+The same seed always produces the same data (relative to the end date).
+"""
+from __future__ import annotations
+
+import os
+import random
+import sqlite3
+import uuid
+from datetime import date, datetime, timedelta, timezone
+from typing import Any, Optional
+
+from command_center.database.models import MessageEntry
+from command_center.database.queries import (
+    insert_message_entries,
+    recompute_hourly_aggregates,
+    recompute_model_aggregates,
+)
+from command_center.utils.pricing import ModelPricing, calculate_cost_usd
+from command_center.utils.project_metadata import PROJECTS_JSON_PATH, save_projects_json
+
+DEMO_ENV = "COMMAND_CENTER_DEMO"
+DEMO_HOME_ENV = "COMMAND_CENTER_DEMO_HOME"
+DEFAULT_SEED = 42
+DEFAULT_MONTHS = 6
+
+# (slug, name, description, share of sessions, tags)
+DEMO_PROJECTS = [
+    ("aurora-web", "Aurora Web", "Customer portal", 0.30, ["frontend"]),
+    ("ledger-api", "Ledger API", "Billing service", 0.22, ["backend"]),
+    ("atlas-mobile", "Atlas Mobile", "iOS and Android app", 0.16, ["mobile"]),
+    ("data-pipeline", "Data Pipeline", "Nightly ETL jobs", 0.14, ["backend", "data"]),
+    ("docs-site", "Docs Site", "Product documentation", 0.10, ["docs"]),
+    ("infra", "Infra", "Terraform and CI", 0.08, ["ops"]),
+]
+
+# Model share at the start and at the end of the range (interpolated in between)
+DEMO_MODELS = {
+    "claude-sonnet-4-20250514": (0.55, 0.05),
+    "claude-sonnet-4-5-20250929": (0.15, 0.55),
+    "claude-opus-4-1-20250805": (0.20, 0.05),
+    "claude-opus-4-5-20251101": (0.00, 0.25),
+    "claude-haiku-4-5-20251001": (0.10, 0.10),
+}
+
+# List prices in USD per million tokens: input, output, cache write, cache read.
+# Fixed so demo costs never depend on the network or the local price cache.
+DEMO_PRICES = {
+    "claude-sonnet-4-20250514": (3.0, 15.0, 3.75, 0.30),
+    "claude-sonnet-4-5-20250929": (3.0, 15.0, 3.75, 0.30),
+    "claude-opus-4-1-20250805": (15.0, 75.0, 18.75, 1.50),
+    "claude-opus-4-5-20251101": (5.0, 25.0, 6.25, 0.50),
+    "claude-haiku-4-5-20251001": (1.0, 5.0, 1.25, 0.10),
+}
+
+# Relative likelihood of a session starting at each local hour
+HOUR_WEIGHTS = [
+    0, 0, 0, 0, 0, 0, 0, 1, 3, 8, 10, 10, 5, 6, 9, 10, 9, 7, 4, 3, 3, 2, 1, 0,
+]
+
+# Tables rebuilt by generate_demo_data
+DEMO_TABLES = (
+    "message_entries", "hourly_aggregates", "model_aggregates", "limit_events", "budget_periods",
+    "session_outcomes", "compaction_events", "compaction_scans", "mcp_calls",
+    "hook_executions", "request_settings", "daily_aggregate_history",
+)
+
+
+def demo_mode() -> bool:
+    """True if the backend runs against the demo home."""
+    return os.environ.get(DEMO_ENV) == "1" and bool(os.environ.get(DEMO_HOME_ENV))
+
+
+def _pricing(model: str) -> ModelPricing:
+    input_price, output_price, write_price, read_price = (p / 1_000_000 for p in DEMO_PRICES[model])
+    return ModelPricing(
+        input_cost_per_token=input_price,
+        input_cost_per_token_above_200k=None,
+        cache_creation_cost_per_token=write_price,
+        cache_creation_cost_per_token_above_200k=None,
+        cached_input_cost_per_token=read_price,
+        cached_input_cost_per_token_above_200k=None,
+        output_cost_per_token=output_price,
+        output_cost_per_token_above_200k=None,
+    )
+
+
+def _project_id(slug: str) -> str:
+    return f"-home-demo-projects-{slug}"
+
+
+def _pick_model(rng: random.Random, progress: float) -> str:
+    models = list(DEMO_MODELS)
+    weights = [start + (end - start) * progress for start, end in DEMO_MODELS.values()]
+    return rng.choices(models, weights)[0]
+
+
+def _session_entries(
+    rng: random.Random,
+    day: date,
+    project_id: str,
+    model: str,
+    pricing: ModelPricing,
+) -> list[MessageEntry]:
+    session_id = str(uuid.UUID(int=rng.getrandbits(128), version=4))
+    hour = rng.choices(range(24), HOUR_WEIGHTS)[0]
+    current = datetime(day.year, day.month, day.day, hour, rng.randrange(60), rng.randrange(60)).astimezone()
+    messages = min(300, max(3, int(rng.lognormvariate(3.0, 0.7))))
+
+    entries = []
+    for index in range(messages):
+        input_tokens = int(rng.lognormvariate(3.5, 1.0))
+        output_tokens = int(rng.lognormvariate(5.5, 1.0))
+        cache_read = int(rng.lognormvariate(10.3, 0.6))
+        cache_write = int(rng.lognormvariate(7.5, 1.2))
+        entries.append(MessageEntry(
+            entry_hash=f"demo:{session_id}:{index}",
+            timestamp=current.astimezone(timezone.utc).strftime("%Y-%m-%dT%H:%M:%S.%f")[:-3] + "Z",
+            timestamp_local=current.isoformat(),
+            year=current.year,
+            date=current.date().isoformat(),
+            session_id=session_id,
+            request_id=f"req_demo_{session_id[:8]}_{index}",
+            message_id=f"msg_demo_{session_id[:8]}_{index}",
+            model=model,
+            cost_usd=calculate_cost_usd(input_tokens, output_tokens, cache_write, cache_read, pricing),
+            input_tokens=input_tokens,
+            output_tokens=output_tokens,
+            cache_read_tokens=cache_read,
+            cache_write_tokens=cache_write,
+            total_tokens=input_tokens + output_tokens + cache_read + cache_write,
+            source_file=f"demo/{project_id}/{session_id}.jsonl",
+            project_id=project_id,
+            cost_source="computed",
+        ))
+        current += timedelta(seconds=5 + rng.expovariate(1 / 40))
+    return entries
+
+
+def generate_demo_data(
+    conn: sqlite3.Connection,
+    seed: int = DEFAULT_SEED,
+    months: int = DEFAULT_MONTHS,
+    end_date: Optional[date] = None,
+    projects_json_path: str = PROJECTS_JSON_PATH,
+) -> dict[str, Any]:
+    """
+    Replace the usage data in ``conn`` with synthetic sessions.
+
+    Only call this against the demo home's database (see ``demo_mode``): all
+    message entries, aggregates, limit events and budget periods are deleted.
+
+    Args:
+        conn: Database connection (schema initialized)
+        seed: Random seed
+        months: Number of months of history, ending at end_date (1-24)
+        end_date: Last day with data (default: today)
+        projects_json_path: Project metadata file to write the demo projects to
+
+    Returns:
+        {"seed", "months", "date_from", "date_to", "projects", "sessions", "messages", "total_cost_usd"}
+    """
+    if not 1 <= months <= 24:
+        raise ValueError(f"Invalid months: {months} (expected 1-24)")
+    rng = random.Random(seed)
+    end = end_date or date.today()
+    start = end - timedelta(days=round(months * 30.44) - 1)
+    span = max((end - start).days, 1)
+
+    projects = [(_project_id(slug), share) for slug, _, _, share, _ in DEMO_PROJECTS]
+    pricing = {model: _pricing(model) for model in DEMO_MODELS}
+    entries: list[MessageEntry] = []
+    sessions = 0
+    day = start
+    while day <= end:
+        progress = (day - start).days / span
+        active = rng.random() < (0.92 if day.weekday() < 5 else 0.3)
+        if active:
+            count = max(1, round(rng.gauss(4 + 3 * progress, 1.5)))
+            for _ in range(count):
+                project_id = rng.choices([p for p, _ in projects], [w for _, w in projects])[0]
+                model = _pick_model(rng, progress)
+                entries.extend(_session_entries(rng, day, project_id, model, pricing[model]))
+                sessions += 1
+        day += timedelta(days=1)
+
+    cursor = conn.cursor()
+    for table in DEMO_TABLES:
+        cursor.execute(f"DELETE FROM {table}")
+    conn.commit()
+    insert_message_entries(conn, entries)
+    recompute_hourly_aggregates(conn, {f"{e.timestamp_local[:10]} {e.timestamp_local[11:13]}:00:00" for e in entries}, reason="demo")
+    for year in sorted({e.year for e in entries}):
+        recompute_model_aggregates(conn, year)
+
+    now = datetime.now().astimezone().isoformat()
+    save_projects_json({
+        _project_id(slug): {
+            "name": name,
+            "description": description,
+            "absolute_path": f"/home/demo/projects/{slug}",
+            "first_seen": now,
+            "last_seen": now,
+            "visible": True,
+            "tags": tags,
+        }
+        for slug, name, description, _, tags in DEMO_PROJECTS
+    }, projects_json_path)
+
+    return {
+        "seed": seed,
+        "months": months,
+        "date_from": start.isoformat(),
+        "date_to": end.isoformat(),
+        "projects": len(DEMO_PROJECTS),
+        "sessions": sessions,
+        "messages": len(entries),
+        "total_cost_usd": round(sum(e.cost_usd or 0 for e in entries), 2),
+    }
@@ -0,0 +1,234 @@
+"""
+User-defined derived metrics.
+
+A derived metric is a named arithmetic formula over the per-period usage
+totals, e.g. ``cost_per_message = cost / messages`` or
+``cache_ratio = cache_read / input``. Definitions are stored in a small JSON
+file next to the database and evaluated for every timeline period and every
+day of the dashboard range.
+
+Formulas may use the registered metrics (metrics_registry), numbers, ``+ - * /``,
+parentheses and the functions ``min``, ``max``, ``abs`` and ``round``.
+Division by zero yields null instead of an error, so a period without
+messages simply has no value.
+"""
+from __future__ import annotations
+
+import ast
+import json
+import math
+import os
+import re
+import sqlite3
+from typing import Optional
+from command_center.database.queries import ProjectScope
+from command_center.metrics_registry import metric_names, query_metrics
+from command_center.utils.atomic_write import write_json_atomic
+
+
+# Default location for derived metric definitions
+DERIVED_METRICS_JSON_PATH = os.path.expanduser("~/.claude/db/command-center-derived-metrics.json")
+
+METRIC_FUNCTIONS = {"min": min, "max": max, "abs": abs, "round": round}
+
+MAX_DERIVED_METRICS = 20
+MAX_EXPRESSION_LENGTH = 200
+
+_NAME_RE = re.compile(r"^[a-z][a-z0-9_]{0,39}$")
+
+_BINARY_OPS = {
+    ast.Add: lambda a, b: a + b,
+    ast.Sub: lambda a, b: a - b,
+    ast.Mult: lambda a, b: a * b,
+    ast.Div: lambda a, b: a / b if b else None,
+}
+
+def parse_expression(expression: str) -> ast.Expression:
+    """
+    Parse and check a formula.
+
+    Raises:
+        ValueError: For syntax errors, unknown variables or unsupported operations
+    """
+    if not expression.strip():
+        raise ValueError("Formula must not be empty")
+    if len(expression) > MAX_EXPRESSION_LENGTH:
+        raise ValueError(f"Formula is longer than {MAX_EXPRESSION_LENGTH} characters")
+    try:
+        tree = ast.parse(expression, mode="eval")
+    except SyntaxError as e:
+        raise ValueError(f"Invalid formula: {e.msg}")
+
+    for node in ast.walk(tree):
+        if isinstance(node, (ast.Expression, ast.Load, ast.operator, ast.unaryop)):
+            if isinstance(node, ast.operator) and type(node) not in _BINARY_OPS:
+                raise ValueError(f"Unsupported operator in formula: {type(node).__name__}")
+            if isinstance(node, ast.unaryop) and not isinstance(node, (ast.UAdd, ast.USub)):
+                raise ValueError(f"Unsupported operator in formula: {type(node).__name__}")
+        elif isinstance(node, (ast.BinOp, ast.UnaryOp)):
+            continue
+        elif isinstance(node, ast.Constant):
+            if isinstance(node.value, bool) or not isinstance(node.value, (int, float)):
+                raise ValueError(f"Only numbers are allowed in formulas, got {node.value!r}")
+        elif isinstance(node, ast.Name):
+            if node.id not in metric_names() and node.id not in METRIC_FUNCTIONS:
+                raise ValueError(
+                    f"Unknown variable {node.id!r} (expected one of {', '.join(metric_names())})"
+                )
+        elif isinstance(node, ast.Call):
+            if not isinstance(node.func, ast.Name) or node.func.id not in METRIC_FUNCTIONS:
+                raise ValueError(f"Unknown function in formula (allowed: {', '.join(METRIC_FUNCTIONS)})")
+            if node.keywords:
+                raise ValueError("Keyword arguments are not allowed in formulas")
+        else:
+            raise ValueError(f"Unsupported syntax in formula: {type(node).__name__}")
+    return tree
+
+
+def evaluate(tree: ast.Expression, values: dict[str, float]) -> Optional[float]:
+    """Evaluate a parsed formula; None if any step divides by zero or overflows."""
+    def visit(node: ast.AST) -> Optional[float]:
+        if isinstance(node, ast.Constant):
+            return node.value
+        if isinstance(node, ast.Name):
+            return values.get(node.id, 0) or 0
+        if isinstance(node, ast.UnaryOp):
+            operand = visit(node.operand)
+            return None if operand is None else (-operand if isinstance(node.op, ast.USub) else operand)
+        if isinstance(node, ast.BinOp):
+            left, right = visit(node.left), visit(node.right)
+            if left is None or right is None:
+                return None
+            return _BINARY_OPS[type(node.op)](left, right)
+        if isinstance(node, ast.Call):
+            args = [visit(arg) for arg in node.args]
+            if any(arg is None for arg in args):
+                return None
+            if node.func.id == "round":
+                args = [args[0]] + [int(a) for a in args[1:]]
+            return METRIC_FUNCTIONS[node.func.id](*args)
+        raise ValueError(f"Unsupported syntax in formula: {type(node).__name__}")
+
+    try:
+        result = visit(tree.body)
+    except (TypeError, OverflowError):
+        return None
+    if result is None or not math.isfinite(result):
+        return None
+    return round(float(result), 6)
+
+
+def load_derived_metrics(json_path: str = DERIVED_METRICS_JSON_PATH) -> list[dict]:
+    """
+    Load derived metric definitions.
+
+    Returns:
+        [{"name", "expression", "description"}]; unreadable files yield []
+    """
+    try:
+        with open(json_path, "r", encoding="utf-8") as f:
+            loaded = json.load(f)
+    except (OSError, json.JSONDecodeError):
+        return []
+    metrics = loaded.get("metrics") if isinstance(loaded, dict) else None
+    return metrics if isinstance(metrics, list) else []
+
+
+def save_derived_metrics(metrics: list[dict], json_path: str = DERIVED_METRICS_JSON_PATH):
+    """Save derived metric definitions."""
+    write_json_atomic(json_path, {"metrics": metrics})
+
+
+def set_derived_metric(
+    name: str,
+    expression: str,
+    description: Optional[str] = None,
+    json_path: str = DERIVED_METRICS_JSON_PATH
+) -> dict:
+    """
+    Create or replace a derived metric.
+
+    Args:
+        name: Metric name (lowercase identifier, e.g. cost_per_message)
+        expression: Formula over the registered metrics
+        description: Optional label shown in the UI
+
+    Returns:
+        The stored metric
+
+    Raises:
+        ValueError: For an invalid name or formula, or too many metrics
+    """
+    if not _NAME_RE.match(name):
+        raise ValueError("Metric name must be a lowercase identifier (a-z, 0-9, _), max 40 characters")
+    if name in metric_names() or name in METRIC_FUNCTIONS:
+        raise ValueError(f"Metric name {name!r} is reserved")
+    parse_expression(expression)
+
+    metrics = load_derived_metrics(json_path)
+    metric = {"name": name, "expression": expression.strip(), "description": description}
+    for i, existing in enumerate(metrics):
+        if existing.get("name") == name:
+            metrics[i] = metric
+            break
+    else:
+        if len(metrics) >= MAX_DERIVED_METRICS:
+            raise ValueError(f"At most {MAX_DERIVED_METRICS} derived metrics can be defined")
+        metrics.append(metric)
+    save_derived_metrics(metrics, json_path)
+    return metric
+
+
+def delete_derived_metric(name: str, json_path: str = DERIVED_METRICS_JSON_PATH) -> dict:
+    """
+    Delete a derived metric.
+
+    Raises:
+        ValueError: If the metric does not exist
+    """
+    metrics = load_derived_metrics(json_path)
+    remaining = [m for m in metrics if m.get("name") != name]
+    if len(remaining) == len(metrics):
+        raise ValueError(f"Derived metric not found: {name}")
+    save_derived_metrics(remaining, json_path)
+    return {"deleted": name}
+
+
+def query_metric_inputs(
+    conn: sqlite3.Connection,
+    date_from: str,
+    date_to: str,
+    granularity: str,
+    project_id: ProjectScope = None
+) -> dict[str, dict[str, float]]:
+    """
+    Get the formula variables per period.
+
+    Returns:
+        Dict mapping period (as in query_timeline_data) → registered metric values
+    """
+    return query_metrics(conn, date_from, date_to, granularity, project_id)
+
+
+def compute_derived_metrics(
+    metrics: list[dict],
+    inputs: dict[str, dict[str, float]]
+) -> dict[str, dict[str, Optional[float]]]:
+    """
+    Evaluate metrics for every period.
+
+    Definitions with an invalid formula (e.g. edited by hand) are skipped.
+
+    Returns:
+        Dict mapping period → {metric name: value or None}
+    """
+    parsed = []
+    for metric in metrics:
+        try:
+            parsed.append((metric["name"], parse_expression(metric["expression"])))
+        except (KeyError, TypeError, ValueError):
+            continue
+    return {
+        period: {name: evaluate(tree, values) for name, tree in parsed}
+        for period, values in inputs.items()
+    }
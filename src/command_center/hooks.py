@@ -0,0 +1,505 @@
+"""
+User-configurable hooks fired on lifecycle events.
+
+Hooks are shell commands stored in a small JSON file next to the database.
+After each refresh the API checks for events and runs the matching hooks:
+
+    refresh-completed  after every refresh (with today's running totals)
+    day-rolled-over    once per finished day (with that day's totals)
+    month-completed    once per finished month (with that month's totals)
+    budget-threshold   once per month and threshold (default 80% and 100%)
+
+Commands are templates: ``{name}`` is replaced by the shell-quoted value of
+the event field ``name`` (e.g. ``echo {date},{cost} >> ~/ledger.csv``). The
+full event is also passed as JSON on stdin and the event name in the
+COMMAND_CENTER_EVENT environment variable. Each hook runs with a timeout;
+failures are recorded but never fail the refresh.
+
+Export hooks (action "export", month-completed only) run no command: they
+write the finished month's cost allocation CSV and usage report PDF into a
+folder, e.g. a bookkeeping folder that fills itself at month end.
+"""
+from __future__ import annotations
+
+import base64
+import json
+import os
+import shlex
+import signal
+import sqlite3
+import string
+import subprocess
+import time
+import uuid
+from datetime import date, datetime, timedelta
+from pathlib import Path
+from typing import Any, Optional
+
+from command_center.budget import query_budget_history
+from command_center.database.queries import query_totals
+from command_center.utils.atomic_write import write_bytes_atomic, write_json_atomic
+from command_center.utils.date_helpers import month_bounds
+
+
+# Default location for hook configuration JSON
+HOOKS_JSON_PATH = os.path.expanduser("~/.claude/db/command-center-hooks.json")
+
+# Fired-event bookkeeping and recent runs
+HOOKS_STATE_PATH = os.path.expanduser("~/.claude/db/command-center-hooks-state.json")
+
+HOOK_EVENTS = ("refresh-completed", "day-rolled-over", "month-completed", "budget-threshold")
+
+# "command" runs the shell command; "export" writes a finished month's files to a folder
+HOOK_ACTIONS = ("command", "export")
+
+# Files written by export hooks
+EXPORT_HOOK_FORMATS = ("csv", "pdf")
+
+DEFAULT_HOOK_TIMEOUT = 30
+MAX_HOOK_TIMEOUT = 600
+DEFAULT_BUDGET_THRESHOLDS = [80, 100]
+
+# day-rolled-over catches up at most this many missed days
+MAX_ROLLOVER_DAYS = 31
+
+# month-completed catches up at most this many missed months
+MAX_ROLLOVER_MONTHS = 12
+
+# Number of hook runs kept in the state file
+MAX_RECENT_RUNS = 50
+
+# Captured output kept per run
+MAX_OUTPUT_CHARS = 2000
+
+
+def load_hooks_config(json_path: str = HOOKS_JSON_PATH) -> dict:
+    """
+    Load hook configuration.
+
+    Returns:
+        {"hooks": [{"id", "event", "action", "command", "timeout", "enabled"}
+                   or {"id", "event", "action": "export", "folder", "formats", "enabled"}],
+         "budget_thresholds": [80, 100]}
+    """
+    path = Path(json_path)
+    config: dict[str, Any] = {"hooks": [], "budget_thresholds": list(DEFAULT_BUDGET_THRESHOLDS)}
+    if not path.exists():
+        return config
+    try:
+        with open(path, "r", encoding="utf-8") as f:
+            loaded = json.load(f)
+    except (json.JSONDecodeError, IOError):
+        return config
+    if isinstance(loaded.get("hooks"), list):
+        config["hooks"] = loaded["hooks"]
+    if isinstance(loaded.get("budget_thresholds"), list):
+        config["budget_thresholds"] = sorted(float(t) for t in loaded["budget_thresholds"])
+    return config
+
+
+def save_hooks_config(config: dict, json_path: str = HOOKS_JSON_PATH):
+    """Save hook configuration."""
+    write_json_atomic(json_path, config)
+
+
+def _load_state(state_path: str) -> dict:
+    try:
+        with open(state_path, "r", encoding="utf-8") as f:
+            return json.load(f)
+    except (OSError, json.JSONDecodeError):
+        return {}
+
+
+def _save_state(state: dict, state_path: str):
+    write_json_atomic(state_path, state)
+
+
+def template_fields(command: str) -> list[str]:
+    """Return the placeholder names used in a command template."""
+    return [name for _, name, _, _ in string.Formatter().parse(command) if name]
+
+
+def render_command(command: str, payload: dict[str, Any]) -> str:
+    """
+    Fill a command template with shell-quoted event fields.
+
+    Raises:
+        ValueError: If the template uses a field the event does not have
+    """
+    values = {}
+    for name in template_fields(command):
+        if name not in payload:
+            raise ValueError(f"Unknown placeholder {{{name}}} for event {payload.get('event')}")
+        value = payload[name]
+        values[name] = shlex.quote(value if isinstance(value, str) else json.dumps(value))
+    return command.format_map(values)
+
+
+def set_hook(
+    event: str,
+    command: str,
+    timeout: Optional[int] = None,
+    hook_id: Optional[str] = None,
+    enabled: bool = True,
+    action: str = "command",
+    folder: Optional[str] = None,
+    formats: Optional[list[str]] = None,
+    json_path: str = HOOKS_JSON_PATH
+) -> dict:
+    """
+    Create or update a hook.
+
+    Args:
+        event: One of HOOK_EVENTS
+        command: Shell command template (ignored by export hooks)
+        timeout: Seconds before the command is killed (default 30, max 600)
+        hook_id: Hook to create or replace (default: a new random id)
+        enabled: Whether the hook runs
+        action: One of HOOK_ACTIONS; "export" requires the month-completed event
+        folder: Absolute folder export hooks write to (~ is expanded)
+        formats: Files export hooks write (EXPORT_HOOK_FORMATS, default all)
+
+    Returns:
+        The stored hook
+
+    Raises:
+        ValueError: For an unknown event or action, empty command, invalid
+            timeout, or an export hook without an absolute folder
+    """
+    if event not in HOOK_EVENTS:
+        raise ValueError(f"Unknown event: {event} (expected one of {', '.join(HOOK_EVENTS)})")
+    if action not in HOOK_ACTIONS:
+        raise ValueError(f"Unknown action: {action} (expected one of {', '.join(HOOK_ACTIONS)})")
+    hook_id = hook_id or uuid.uuid4().hex[:8]
+
+    if action == "export":
+        if event != "month-completed":
+            raise ValueError("Export hooks run on the month-completed event")
+        folder = os.path.expanduser((folder or "").strip())
+        if not os.path.isabs(folder):
+            raise ValueError("Export hooks need an absolute folder")
+        formats = list(formats or EXPORT_HOOK_FORMATS)
+        unknown = [fmt for fmt in formats if fmt not in EXPORT_HOOK_FORMATS]
+        if unknown:
+            raise ValueError(f"Unknown export format: {unknown[0]} (expected {', '.join(EXPORT_HOOK_FORMATS)})")
+        hook = {"id": hook_id, "event": event, "action": action, "folder": folder,
+                "formats": [fmt for fmt in EXPORT_HOOK_FORMATS if fmt in formats], "enabled": enabled}
+    else:
+        if not command.strip():
+            raise ValueError("Hook command must not be empty")
+        timeout = DEFAULT_HOOK_TIMEOUT if timeout is None else int(timeout)
+        if not 1 <= timeout <= MAX_HOOK_TIMEOUT:
+            raise ValueError(f"Timeout must be between 1 and {MAX_HOOK_TIMEOUT} seconds")
+        hook = {"id": hook_id, "event": event, "action": action, "command": command,
+                "timeout": timeout, "enabled": enabled}
+
+    config = load_hooks_config(json_path)
+    for i, existing in enumerate(config["hooks"]):
+        if existing.get("id") == hook["id"]:
+            config["hooks"][i] = hook
+            break
+    else:
+        config["hooks"].append(hook)
+    save_hooks_config(config, json_path)
+    return hook
+
+
+def delete_hook(hook_id: str, json_path: str = HOOKS_JSON_PATH) -> dict:
+    """
+    Delete a hook.
+
+    Raises:
+        ValueError: If the hook does not exist
+    """
+    config = load_hooks_config(json_path)
+    remaining = [h for h in config["hooks"] if h.get("id") != hook_id]
+    if len(remaining) == len(config["hooks"]):
+        raise ValueError(f"Hook not found: {hook_id}")
+    config["hooks"] = remaining
+    save_hooks_config(config, json_path)
+    return {"deleted": hook_id}
+
+
+def run_hook(
+    hook: dict,
+    payload: dict[str, Any],
+    conn: Optional[sqlite3.Connection] = None
+) -> dict[str, Any]:
+    """
+    Run one hook for an event.
+
+    The command runs through the shell in its own process group, so a
+    timeout also stops anything it started. Export hooks go to
+    run_export_hook, which needs the database connection.
+
+    Returns:
+        {"hook_id", "event", "status": "ok" | "error" | "timeout", "exit_code",
+         "duration_ms", "stdout", "stderr", "ran_at"}
+    """
+    if hook.get("action", "command") == "export":
+        return run_export_hook(hook, payload, conn)
+    result: dict[str, Any] = {
+        "hook_id": hook.get("id"),
+        "event": payload.get("event"),
+        "ran_at": datetime.now().astimezone().isoformat(),
+        "exit_code": None,
+        "stdout": "",
+        "stderr": "",
+    }
+    started = time.monotonic()
+    try:
+        command = render_command(hook["command"], payload)
+    except (KeyError, ValueError) as e:
+        result.update(status="error", stderr=str(e), duration_ms=0)
+        return result
+
+    env = {**os.environ, "COMMAND_CENTER_EVENT": str(payload.get("event"))}
+    try:
+        process = subprocess.Popen(
+            command, shell=True, stdin=subprocess.PIPE, stdout=subprocess.PIPE, stderr=subprocess.PIPE,
+            text=True, env=env, start_new_session=(os.name == "posix"),
+        )
+    except OSError as e:
+        result.update(status="error", stderr=str(e), duration_ms=0)
+        return result
+    try:
+        stdout, stderr = process.communicate(json.dumps(payload), timeout=hook.get("timeout", DEFAULT_HOOK_TIMEOUT))
+        result["status"] = "ok" if process.returncode == 0 else "error"
+    except subprocess.TimeoutExpired:
+        if os.name == "posix":
+            os.killpg(process.pid, signal.SIGKILL)
+        else:
+            process.kill()
+        stdout, stderr = process.communicate()
+        result["status"] = "timeout"
+
+    result.update(
+        exit_code=process.returncode,
+        stdout=(stdout or "")[-MAX_OUTPUT_CHARS:],
+        stderr=(stderr or "")[-MAX_OUTPUT_CHARS:],
+        duration_ms=round((time.monotonic() - started) * 1000),
+    )
+    return result
+
+
+def run_export_hook(
+    hook: dict,
+    payload: dict[str, Any],
+    conn: Optional[sqlite3.Connection]
+) -> dict[str, Any]:
+    """
+    Write the exports of payload["month"] into the hook's folder.
+
+    Files are named like the app's exports (cc-cost-allocation-2025-06.csv,
+    cc-usage-report-2025-06.pdf) and replace earlier ones.
+
+    Returns:
+        Same fields as run_hook plus "files" (written paths, also listed in stdout)
+    """
+    from command_center.cost_allocation import export_cost_allocation
+    from command_center.database.queries import query_usage_stats
+    from command_center.utils.project_metadata import PROJECTS_JSON_PATH, load_projects_json
+    from command_center.visualization.png_generator import generate_usage_report_pdf
+
+    result: dict[str, Any] = {
+        "hook_id": hook.get("id"),
+        "event": payload.get("event"),
+        "ran_at": datetime.now().astimezone().isoformat(),
+        "exit_code": None,
+        "stderr": "",
+    }
+    started = time.monotonic()
+    written: list[str] = []
+    try:
+        if conn is None:
+            raise ValueError("Export hooks need a database connection")
+        month = payload["month"]
+        folder = Path(hook["folder"])
+        for fmt in hook.get("formats", EXPORT_HOOK_FORMATS):
+            if fmt == "csv":
+                export = export_cost_allocation(conn, month, "csv")
+                path, data = folder / export["filename"], base64.b64decode(export["data"])
+            else:
+                stats = query_usage_stats(conn, *month_bounds(month))
+                path = folder / f"cc-usage-report-{month}.pdf"
+                data = generate_usage_report_pdf(stats, load_projects_json(PROJECTS_JSON_PATH))
+            write_bytes_atomic(path, data)
+            written.append(str(path))
+        result["status"] = "ok"
+    except Exception as e:  # recorded like a failing command; never fails the refresh
+        result.update(status="error", stderr=str(e))
+    result.update(
+        stdout="\n".join(written),
+        files=written,
+        duration_ms=round((time.monotonic() - started) * 1000),
+    )
+    return result
+
+
+def fire_event(
+    payload: dict[str, Any],
+    config: Optional[dict] = None,
+    json_path: str = HOOKS_JSON_PATH,
+    conn: Optional[sqlite3.Connection] = None
+) -> list[dict]:
+    """Run every enabled hook registered for payload["event"]."""
+    config = config or load_hooks_config(json_path)
+    return [
+        run_hook(hook, payload, conn)
+        for hook in config["hooks"]
+        if hook.get("event") == payload["event"] and hook.get("enabled", True)
+    ]
+
+
+def _day_totals(conn: sqlite3.Connection, day: str) -> dict[str, Any]:
+    totals = query_totals(conn, day, day)
+    return {
+        "date": day,
+        "messages": totals["messages"],
+        "sessions": totals["sessions"],
+        "tokens": totals["tokens"],
+        "cost": totals["cost"],
+    }
+
+
+def _month_totals(conn: sqlite3.Connection, month: str) -> dict[str, Any]:
+    date_from, date_to = month_bounds(month)
+    totals = query_totals(conn, date_from, date_to)
+    return {
+        "month": month,
+        "from": date_from,
+        "to": date_to,
+        "messages": totals["messages"],
+        "sessions": totals["sessions"],
+        "tokens": totals["tokens"],
+        "cost": totals["cost"],
+    }
+
+
+def _months_between(first: str, end: str) -> list[str]:
+    """YYYY-MM months from first up to (excluding) end."""
+    months = []
+    month = first
+    while month < end:
+        months.append(month)
+        month = (date.fromisoformat(month_bounds(month)[1]) + timedelta(days=1)).strftime("%Y-%m")
+    return months
+
+
+def run_lifecycle_hooks(
+    conn: sqlite3.Connection,
+    refresh: dict[str, Any],
+    today: Optional[date] = None,
+    json_path: str = HOOKS_JSON_PATH,
+    state_path: str = HOOKS_STATE_PATH
+) -> list[dict]:
+    """
+    Detect lifecycle events after a refresh and run their hooks.
+
+    Args:
+        conn: Database connection
+        refresh: Refresh result fields included in the refresh-completed event
+            (e.g. {"mode": "full", "updated_files": 3})
+        today: Reference date (defaults to today)
+
+    Returns:
+        Hook run results (empty when no hooks are configured)
+    """
+    config = load_hooks_config(json_path)
+    if not any(h.get("enabled", True) for h in config["hooks"]):
+        return []
+
+    today = today or date.today()
+    state = _load_state(state_path)
+    results: list[dict] = []
+
+    results += fire_event({"event": "refresh-completed", **refresh, **_day_totals(conn, today.isoformat())}, config)
+
+    # First run only records the day; rollovers are fired from then on
+    last_day = state.get("last_day")
+    if last_day and last_day < today.isoformat():
+        day = max(date.fromisoformat(last_day), today - timedelta(days=MAX_ROLLOVER_DAYS))
+        while day < today:
+            results += fire_event({"event": "day-rolled-over", **_day_totals(conn, day.isoformat())}, config)
+            day += timedelta(days=1)
+    state["last_day"] = today.isoformat()
+
+    # Months likewise; state from before month-completed existed starts at last_day's month
+    this_month = today.strftime("%Y-%m")
+    last_month = state.get("last_month") or (last_day[:7] if last_day else None)
+    if last_month and last_month < this_month:
+        for month in _months_between(last_month, this_month)[-MAX_ROLLOVER_MONTHS:]:
+            results += fire_event({"event": "month-completed", **_month_totals(conn, month)}, config, conn=conn)
+    state["last_month"] = this_month
+
+    current = query_budget_history(conn, today)["current"]
+    fired = set(state.get("budget_thresholds_fired", []))
+    if current["percent_used"] is not None:
+        for threshold in config["budget_thresholds"]:
+            key = f"{current['period']}:{threshold:g}"
+            if current["percent_used"] >= threshold and key not in fired:
+                results += fire_event({
+                    "event": "budget-threshold",
+                    "month": current["period"],
+                    "threshold": threshold,
+                    "budget": current["budget"],
+                    "actual": current["actual"],
+                    "percent_used": current["percent_used"],
+                }, config)
+                fired.add(key)
+    # Keep only this month's keys
+    state["budget_thresholds_fired"] = sorted(k for k in fired if k.startswith(current["period"]))
+
+    if results:
+        state["recent_runs"] = (results + state.get("recent_runs", []))[:MAX_RECENT_RUNS]
+    _save_state(state, state_path)
+    return results
+
+
+def test_hook(
+    hook_id: str,
+    json_path: str = HOOKS_JSON_PATH,
+    conn: Optional[sqlite3.Connection] = None
+) -> dict:
+    """
+    Run a hook once with a sample event (disabled hooks included).
+
+    Export hooks write the real files of the previous month.
+
+    Raises:
+        ValueError: If the hook does not exist
+    """
+    hook = next((h for h in load_hooks_config(json_path)["hooks"] if h.get("id") == hook_id), None)
+    if hook is None:
+        raise ValueError(f"Hook not found: {hook_id}")
+
+    sample_day = {"date": date.today().isoformat(), "messages": 120, "sessions": 4,
+                  "tokens": 1_500_000, "cost": 12.5}
+    previous_month = (date.today().replace(day=1) - timedelta(days=1)).strftime("%Y-%m")
+    sample_month = {"month": previous_month, "from": month_bounds(previous_month)[0],
+                    "to": month_bounds(previous_month)[1], "messages": 2400, "sessions": 80,
+                    "tokens": 30_000_000, "cost": 250.0}
+    samples = {
+        "refresh-completed": {"mode": "test", "updated_files": 0, **sample_day},
+        "day-rolled-over": sample_day,
+        "month-completed": sample_month,
+        "budget-threshold": {"month": date.today().strftime("%Y-%m"), "threshold": 80.0,
+                             "budget": 200.0, "actual": 160.0, "percent_used": 80.0},
+    }
+    return run_hook(hook, {"event": hook["event"], "test": True, **samples[hook["event"]]}, conn)
+
+
+def list_hooks(json_path: str = HOOKS_JSON_PATH, state_path: str = HOOKS_STATE_PATH) -> dict:
+    """
+    Get configured hooks with recent runs.
+
+    Returns:
+        {"events": [...], "hooks": [...], "budget_thresholds": [...], "recent_runs": [...]}
+    """
+    config = load_hooks_config(json_path)
+    return {
+        "events": list(HOOK_EVENTS),
+        "hooks": config["hooks"],
+        "budget_thresholds": config["budget_thresholds"],
+        "recent_runs": _load_state(state_path).get("recent_runs", []),
+    }
@@ -0,0 +1,206 @@
+"""
+Registry of aggregated metrics.
+
+Every metric the app can aggregate per period (tokens by kind, cost,
+messages, tool calls, latency) is one Metric: a SQL aggregate over the rows
+of a source table. query_metrics groups all requested metrics of a source
+into one query per source, so adding a metric means registering it here
+(or with register_metric) instead of editing each aggregation query.
+Derived metric formulas use the registered names as variables, and
+list_available_metrics describes them for the frontend's metric pickers.
+"""
+from __future__ import annotations
+
+import re
+import sqlite3
+from dataclasses import asdict, dataclass
+from typing import Optional
+
+from command_center.database.queries import ProjectScope, project_scope_param
+
+
+@dataclass(frozen=True)
+class MetricSource:
+    """Rows a metric aggregates; `m` is the alias with date, timestamp_local and project_id."""
+    from_clause: str
+    local_filter: Optional[str] = None  # applied without a project filter
+
+
+@dataclass(frozen=True)
+class Metric:
+    name: str        # result key and derived formula variable
+    label: str
+    unit: str        # count, tokens, usd or ms
+    category: str    # activity, tokens, cost, tools or latency
+    source: str      # key of METRIC_SOURCES
+    expression: str  # SQL aggregate over the source rows
+    average: bool = False  # periods without rows have no value (None) instead of 0
+    description: str = ""
+
+
+METRIC_SOURCES = {
+    "messages": MetricSource("message_entries m", "m.origin = 'local'"),
+    "mcp_calls": MetricSource("mcp_calls m"),
+    "request_settings": MetricSource(
+        "message_entries m JOIN request_settings r ON r.entry_hash = m.entry_hash", "m.origin = 'local'"
+    ),
+}
+
+METRIC_UNITS = ("count", "tokens", "usd", "ms")
+
+# Timeline period expressions (see query_timeline_data)
+PERIOD_EXPRS = {
+    "month": "SUBSTR(m.date, 1, 7)",
+    "week": "STRFTIME('%Y-W%W', m.date)",
+    "hour": "m.date || ' ' || STRFTIME('%H', m.timestamp_local)",
+    "day": "m.date",
+}
+
+BUILTIN_METRICS = (
+    Metric("messages", "Messages", "count", "activity", "messages", "COUNT(*)"),
+    Metric("sessions", "Sessions", "count", "activity", "messages", "COUNT(DISTINCT m.session_id)"),
+    Metric("tokens", "Total tokens", "tokens", "tokens", "messages", "SUM(m.total_tokens)"),
+    Metric("input", "Input tokens", "tokens", "tokens", "messages", "SUM(m.input_tokens)"),
+    Metric("output", "Output tokens", "tokens", "tokens", "messages", "SUM(m.output_tokens)"),
+    Metric("cache_read", "Cache read tokens", "tokens", "tokens", "messages", "SUM(m.cache_read_tokens)"),
+    Metric("cache_write", "Cache write tokens", "tokens", "tokens", "messages", "SUM(m.cache_write_tokens)"),
+    Metric("cost", "Cost", "usd", "cost", "messages", "SUM(COALESCE(m.cost_usd, 0))"),
+    Metric(
+        "tool_calls", "MCP tool calls", "count", "tools", "mcp_calls", "COUNT(*)",
+        description="Calls of MCP server tools",
+    ),
+    Metric(
+        "tool_errors", "MCP tool errors", "count", "tools", "mcp_calls",
+        "SUM(CASE WHEN m.is_error THEN 1 ELSE 0 END)",
+        description="MCP tool calls whose result was an error",
+    ),
+    Metric(
+        "latency_ms", "Average latency", "ms", "latency", "request_settings",
+        "AVG(r.latency_ms)", average=True,
+        description="Average time from request to response of responses with a measured latency",
+    ),
+)
+
+_NAME_RE = re.compile(r"^[a-z][a-z0-9_]{0,39}$")
+
+_registry: dict[str, Metric] = {}
+
+
+def register_metric(metric: Metric) -> Metric:
+    """
+    Add a metric to the registry.
+
+    Raises:
+        ValueError: For an invalid or taken name, an unknown source or unit
+    """
+    if not _NAME_RE.match(metric.name):
+        raise ValueError("Metric name must be a lowercase identifier (a-z, 0-9, _), max 40 characters")
+    if metric.name in _registry:
+        raise ValueError(f"Metric {metric.name!r} is already registered")
+    if metric.source not in METRIC_SOURCES:
+        raise ValueError(f"Unknown metric source {metric.source!r} (expected one of {', '.join(METRIC_SOURCES)})")
+    if metric.unit not in METRIC_UNITS:
+        raise ValueError(f"Unknown metric unit {metric.unit!r} (expected one of {', '.join(METRIC_UNITS)})")
+    _registry[metric.name] = metric
+    return metric
+
+
+for _metric in BUILTIN_METRICS:
+    register_metric(_metric)
+
+
+def metric_names() -> tuple[str, ...]:
+    """Names of the registered metrics, in registration order."""
+    return tuple(_registry)
+
+
+def get_metric(name: str) -> Metric:
+    """
+    Raises:
+        ValueError: If no metric of that name is registered
+    """
+    try:
+        return _registry[name]
+    except KeyError:
+        raise ValueError(f"Unknown metric {name!r} (expected one of {', '.join(_registry)})")
+
+
+def list_available_metrics() -> list[dict]:
+    """
+    Describe the registered metrics for metric pickers.
+
+    Returns:
+        [{"name", "label", "unit", "category", "description"}]
+    """
+    return [
+        {key: value for key, value in asdict(metric).items() if key not in ("source", "expression", "average")}
+        for metric in _registry.values()
+    ]
+
+
+def query_metrics(
+    conn: sqlite3.Connection,
+    date_from: str,
+    date_to: str,
+    granularity: str,
+    project_id: ProjectScope = None,
+    names: Optional[list[str]] = None,
+) -> dict[str, dict[str, Optional[float]]]:
+    """
+    Aggregate metrics per period.
+
+    Args:
+        granularity: 'month', 'week', 'day' or 'hour' (periods as in query_timeline_data)
+        project_id: Optional project filter (a project ID or a list of IDs)
+        names: Metrics to compute (default: all registered)
+
+    Returns:
+        Dict mapping period → {metric name: value}; every period with a row in
+        any source has every metric (0, or None for averages)
+
+    Raises:
+        ValueError: For an unknown metric name
+    """
+    metrics = [get_metric(name) for name in (names if names is not None else metric_names())]
+    period_expr = PERIOD_EXPRS.get(granularity, PERIOD_EXPRS["day"])
+
+    by_source: dict[str, list[Metric]] = {}
+    for metric in metrics:
+        by_source.setdefault(metric.source, []).append(metric)
+
+    values: dict[str, dict[str, Optional[float]]] = {}
+    cursor = conn.cursor()
+    for source_name, source_metrics in by_source.items():
+        source = METRIC_SOURCES[source_name]
+        conditions = ["m.date >= ?", "m.date <= ?"]
+        params: list = [date_from, date_to]
+        if project_id:
+            conditions.append("m.project_id IN (SELECT value FROM json_each(?))")
+            params.append(project_scope_param(project_id))
+        elif source.local_filter:
+            conditions.append(source.local_filter)
+        columns = ", ".join(metric.expression for metric in source_metrics)
+        cursor.execute(f"""
+            SELECT {period_expr} as period, {columns}
+            FROM {source.from_clause}
+            WHERE {" AND ".join(conditions)}
+            GROUP BY period
+        """, params)
+        for row in cursor.fetchall():
+            period = values.setdefault(row[0], {})
+            for metric, value in zip(source_metrics, row[1:]):
+                period[metric.name] = value
+
+    return {
+        period: {
+            metric.name: _value(metric, found.get(metric.name))
+            for metric in metrics
+        }
+        for period, found in sorted(values.items())
+    }
+
+
+def _value(metric: Metric, value) -> Optional[float]:
+    if value is None:
+        return None if metric.average else 0
+    return round(value, 6) if isinstance(value, float) else value
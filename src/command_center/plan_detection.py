@@ -0,0 +1,226 @@
+"""
+Plan/limit auto-detection from local Claude Code files.
+
+Looks for plan hints in every detected Claude config directory:
+
+- ``.credentials.json``: ``subscriptionType`` / ``rateLimitTier`` of the OAuth login
+  (only these two fields are read; tokens are never touched)
+- ``.claude.json``: OAuth account vs. API key usage
+- ``statsig/`` caches: cached ``subscriptionType`` / ``rateLimitTier`` values
+- ``limit_events``: limit messages (Opus/weekly limits only exist on Max plans)
+
+The strongest hint wins. Detected plans are stored on the bound usage account
+as ``detected_plan_type``; a manually set ``plan_type`` always overrides them.
+"""
+from __future__ import annotations
+
+import json
+import os
+import re
+import sqlite3
+from typing import Any, Optional
+
+from command_center.claude_configs import get_claude_dirs, load_config_bindings
+from command_center.config import HOME
+from command_center.usage_accounts import load_account_metadata, save_account_metadata
+
+
+# Evidence strength, strongest first
+CONFIDENCE_ORDER = ("high", "medium", "low")
+
+_TIER_RE = re.compile(r"max[_-]?(5|20)x", re.IGNORECASE)
+
+
+def _plan_from_subscription(subscription: Optional[str], tier: Optional[str]) -> Optional[str]:
+    """Map subscriptionType / rateLimitTier values to a PLAN_TYPES entry."""
+    tier_match = _TIER_RE.search(tier or "")
+    if tier_match:
+        return f"max_{tier_match.group(1)}x"
+
+    value = (subscription or "").strip().lower()
+    if value == "max":
+        # Max without a tier hint: assume the entry tier
+        return "max_5x"
+    if value in ("pro", "team", "enterprise"):
+        return value
+    return None
+
+
+def _read_json(path: str) -> Optional[dict]:
+    try:
+        with open(path, "r") as f:
+            data = json.load(f)
+    except (OSError, json.JSONDecodeError):
+        return None
+    return data if isinstance(data, dict) else None
+
+
+def _hints_from_credentials(config_dir: str) -> list[dict[str, Any]]:
+    data = _read_json(os.path.join(config_dir, ".credentials.json"))
+    if not data:
+        return []
+    oauth = data.get("claudeAiOauth") or {}
+    subscription = oauth.get("subscriptionType")
+    tier = oauth.get("rateLimitTier")
+    plan = _plan_from_subscription(subscription, tier)
+    if not plan:
+        return []
+    return [{
+        "plan_type": plan,
+        "confidence": "high",
+        "source": "credentials",
+        "detail": f"subscriptionType={subscription}, rateLimitTier={tier}",
+    }]
+
+
+def _hints_from_state_file(config_dir: str) -> list[dict[str, Any]]:
+    candidates = [os.path.join(config_dir, ".claude.json")]
+    if config_dir == os.path.join(HOME, ".claude"):
+        candidates.append(os.path.join(HOME, ".claude.json"))
+
+    for candidate in candidates:
+        data = _read_json(candidate)
+        if not data:
+            continue
+        if data.get("oauthAccount"):
+            # Subscription login, tier unknown from this file alone
+            return []
+        if data.get("primaryApiKey") or data.get("customApiKeyResponses"):
+            return [{
+                "plan_type": "api",
+                "confidence": "medium",
+                "source": "state_file",
+                "detail": "API key login without OAuth account",
+            }]
+    return []
+
+
+def _hints_from_statsig(config_dir: str) -> list[dict[str, Any]]:
+    statsig_dir = os.path.join(config_dir, "statsig")
+    if not os.path.isdir(statsig_dir):
+        return []
+
+    pattern = re.compile(r'"(subscriptionType|rateLimitTier)"\s*:\s*"([^"]+)"')
+    hints = []
+    for filename in sorted(os.listdir(statsig_dir)):
+        try:
+            with open(os.path.join(statsig_dir, filename), "r", errors="ignore") as f:
+                content = f.read(1_000_000)
+        except OSError:
+            continue
+        values = dict(pattern.findall(content))
+        plan = _plan_from_subscription(values.get("subscriptionType"), values.get("rateLimitTier"))
+        if plan:
+            hints.append({
+                "plan_type": plan,
+                "confidence": "medium",
+                "source": "statsig",
+                "detail": filename,
+            })
+            break
+    return hints
+
+
+def _hints_from_limit_events(conn: sqlite3.Connection, config_dir: str) -> list[dict[str, Any]]:
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT summary_text, occurred_at_local
+        FROM limit_events
+        WHERE source_file LIKE ? AND summary_text IS NOT NULL
+        ORDER BY occurred_at_local DESC
+        LIMIT 200
+    """, (config_dir.rstrip(os.sep) + os.sep + "%",))
+    rows = cursor.fetchall()
+    if not rows:
+        return []
+
+    for text, occurred_at in rows:
+        lower = text.lower()
+        if "opus" in lower or "weekly limit" in lower:
+            return [{
+                "plan_type": "max_5x",
+                "confidence": "low",
+                "source": "limit_messages",
+                "detail": f"{text.strip()[:120]} ({occurred_at})",
+            }]
+
+    text, occurred_at = rows[0]
+    return [{
+        "plan_type": "pro",
+        "confidence": "low",
+        "source": "limit_messages",
+        "detail": f"{text.strip()[:120]} ({occurred_at})",
+    }]
+
+
+def detect_plan_for_config_dir(
+    config_dir: str,
+    conn: Optional[sqlite3.Connection] = None
+) -> dict[str, Any]:
+    """
+    Detect the plan for one Claude config directory.
+
+    Returns:
+        {"config_dir": ..., "plan_type": ... | None, "confidence": ... | None, "evidence": [...]}
+    """
+    evidence = (
+        _hints_from_credentials(config_dir)
+        + _hints_from_state_file(config_dir)
+        + _hints_from_statsig(config_dir)
+        + (_hints_from_limit_events(conn, config_dir) if conn is not None else [])
+    )
+    evidence.sort(key=lambda h: CONFIDENCE_ORDER.index(h["confidence"]))
+    best = evidence[0] if evidence else None
+    return {
+        "config_dir": config_dir,
+        "plan_type": best["plan_type"] if best else None,
+        "confidence": best["confidence"] if best else None,
+        "evidence": evidence,
+    }
+
+
+def detect_plans(conn: Optional[sqlite3.Connection] = None, apply: bool = True) -> dict[str, Any]:
+    """
+    Detect plans for all config directories and populate bound accounts.
+
+    Args:
+        conn: Optional database connection (enables limit-message hints)
+        apply: Store detected plans as detected_plan_type on bound accounts
+
+    Returns:
+        {
+            "configs": [...per config dir detections...],
+            "accounts": [{"email", "plan_type", "plan_source", "detected_plan_type", "override"}]
+        }
+    """
+    bindings = load_config_bindings()
+    detections = [detect_plan_for_config_dir(d, conn) for d in get_claude_dirs()]
+
+    metadata = load_account_metadata()
+    changed = False
+    for detection in detections:
+        email = bindings.get(detection["config_dir"], {}).get("account_email")
+        detection["account_email"] = email
+        if not apply or not email or not detection["plan_type"]:
+            continue
+        entry = metadata.setdefault(email, {})
+        if entry.get("detected_plan_type") != detection["plan_type"]:
+            entry["detected_plan_type"] = detection["plan_type"]
+            changed = True
+
+    if changed:
+        save_account_metadata(metadata)
+
+    accounts = []
+    for email, entry in sorted(metadata.items()):
+        override = entry.get("plan_type") or None
+        detected = entry.get("detected_plan_type") or None
+        accounts.append({
+            "email": email,
+            "plan_type": override or detected,
+            "plan_source": "manual" if override else ("detected" if detected else None),
+            "detected_plan_type": detected,
+            "override": override,
+        })
+
+    return {"configs": detections, "accounts": accounts}
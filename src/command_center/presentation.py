@@ -0,0 +1,75 @@
+"""
+Presentation (read-only) mode for demos and screen sharing.
+
+The desktop app passes its presentation settings through the environment:
+
+- ``COMMAND_CENTER_READ_ONLY=1`` refuses state-changing subcommands (the
+  audited ones: project edits, purges, restores, settings changes)
+- ``COMMAND_CENTER_BLUR_PROJECTS=1`` replaces project names with stable
+  aliases and drops descriptions and paths from responses
+
+Project IDs are kept so project filters keep working; every object that
+carries a ``project_id`` gets an alias ``name``, so the UI never falls back to
+deriving a name from the ID.
+"""
+from __future__ import annotations
+
+import hashlib
+import os
+from typing import Any
+
+READ_ONLY_ENV = "COMMAND_CENTER_READ_ONLY"
+BLUR_PROJECTS_ENV = "COMMAND_CENTER_BLUR_PROJECTS"
+
+# Paths reveal project names wherever they appear; cleared when blurring.
+PATH_FIELDS = ("absolute_path", "cwd")
+
+
+class ReadOnlyModeError(PermissionError):
+    """A state-changing command was called in read-only mode."""
+
+
+def _flag(name: str) -> bool:
+    return os.environ.get(name, "").strip().lower() in ("1", "true", "yes", "on")
+
+
+def read_only() -> bool:
+    """True if read-only mode is on."""
+    return _flag(READ_ONLY_ENV)
+
+
+def blur_projects_enabled() -> bool:
+    """True if project names are blurred in responses."""
+    return _flag(BLUR_PROJECTS_ENV)
+
+
+def project_alias(project_id: str) -> str:
+    """Stable display alias of a project (same ID, same alias across responses)."""
+    return "Project " + hashlib.sha256(project_id.encode("utf-8")).hexdigest()[:4].upper()
+
+
+def blur_projects(value: Any) -> Any:
+    """
+    Copy of a response with project names replaced by aliases.
+
+    Args:
+        value: JSON-compatible response
+
+    Returns:
+        The blurred copy; ``value`` is not modified
+    """
+    if isinstance(value, list):
+        return [blur_projects(item) for item in value]
+    if not isinstance(value, dict):
+        return value
+
+    blurred = {key: blur_projects(item) for key, item in value.items()}
+    for field in PATH_FIELDS:
+        if blurred.get(field) is not None:
+            blurred[field] = None
+    project_id = blurred.get("project_id")
+    if isinstance(project_id, str) and project_id:
+        blurred["name"] = project_alias(project_id)
+        if blurred.get("description") is not None:
+            blurred["description"] = None
+    return blurred
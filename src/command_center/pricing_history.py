@@ -0,0 +1,399 @@
+"""
+Effective-dated model pricing and history repricing.
+
+Model prices change over time, so every price is stored as a version with
+an effective date range in the ``price_versions`` table. Costs are computed
+with the version in effect on the message's local date.
+
+Versions are recorded either from the LiteLLM pricing dataset (a new
+version starts whenever the cached rates change) or manually. After the
+price table changes, ``reprice_history()`` recomputes stored costs and
+reports before/after deltas. Costs taken verbatim from ``costUSD`` in the
+logs (``cost_source = 'jsonl'``) are never repriced.
+"""
+from __future__ import annotations
+
+import sqlite3
+from dataclasses import asdict, fields
+from datetime import date, datetime
+from typing import Any, Optional
+
+from command_center.database.queries import (
+    ProjectScope,
+    project_scope_param,
+    recompute_hourly_aggregates,
+    recompute_model_aggregates,
+)
+from command_center.utils.pricing import (
+    ModelPricing,
+    calculate_cost_usd,
+    find_model_pricing,
+    load_pricing_dataset,
+)
+
+
+# Effective date of the first recorded version of a model (covers all history)
+FIRST_EFFECTIVE_DATE = "1970-01-01"
+
+RATE_FIELDS = [f.name for f in fields(ModelPricing)]
+
+
+class PriceBook:
+    """In-memory view of price_versions for per-date lookups."""
+
+    def __init__(self, versions: dict[str, list[tuple[str, Optional[str], ModelPricing]]]):
+        self._versions = versions
+
+    def pricing_for(self, model: Optional[str], day: str) -> Optional[ModelPricing]:
+        """Return the pricing in effect for a model on a local date (YYYY-MM-DD)."""
+        for effective_from, effective_to, pricing in self._versions.get(model or "", []):
+            if effective_from <= day and (effective_to is None or day < effective_to):
+                return pricing
+        return None
+
+
+def _validate_date(value: str) -> str:
+    try:
+        return datetime.strptime(value.strip(), "%Y-%m-%d").strftime("%Y-%m-%d")
+    except ValueError:
+        raise ValueError(f"Invalid date: {value}. Use YYYY-MM-DD format")
+
+
+def _select_versions(conn: sqlite3.Connection, model: Optional[str] = None) -> list[dict[str, Any]]:
+    cursor = conn.cursor()
+    columns = ", ".join(RATE_FIELDS)
+    query = f"""
+        SELECT model, effective_from, effective_to, {columns}, source, recorded_at
+        FROM price_versions
+    """
+    if model:
+        cursor.execute(query + " WHERE model = ? ORDER BY effective_from", (model,))
+    else:
+        cursor.execute(query + " ORDER BY model, effective_from")
+
+    versions = []
+    for row in cursor.fetchall():
+        rates = dict(zip(RATE_FIELDS, row[3:3 + len(RATE_FIELDS)]))
+        versions.append({
+            "model": row[0],
+            "effective_from": row[1],
+            "effective_to": row[2],
+            "rates": rates,
+            "source": row[-2],
+            "recorded_at": row[-1],
+        })
+    return versions
+
+
+def list_price_versions(conn: sqlite3.Connection, model: Optional[str] = None) -> list[dict[str, Any]]:
+    """
+    List price versions, ordered by model and effective date.
+
+    Rates are per token; effective_to is exclusive (None = still in effect).
+    """
+    return _select_versions(conn, model)
+
+
+def load_price_book(conn: sqlite3.Connection) -> PriceBook:
+    """Load all price versions into a PriceBook."""
+    versions: dict[str, list[tuple[str, Optional[str], ModelPricing]]] = {}
+    for version in _select_versions(conn):
+        versions.setdefault(version["model"], []).append((
+            version["effective_from"],
+            version["effective_to"],
+            ModelPricing(**version["rates"]),
+        ))
+    return PriceBook(versions)
+
+
+def record_price_version(
+    conn: sqlite3.Connection,
+    model: str,
+    pricing: ModelPricing,
+    effective_from: Optional[str] = None,
+    source: str = "manual"
+) -> dict[str, Any]:
+    """
+    Record a price version for a model starting at a date.
+
+    The version covering the date is split: it ends where the new one
+    starts, and the new one ends where the next existing version starts.
+    Recording rates identical to the version already in effect is a no-op.
+
+    Args:
+        conn: Database connection
+        model: Model name as stored in message_entries
+        pricing: Per-token rates
+        effective_from: First local date (YYYY-MM-DD) the rates apply to (default: today)
+        source: Where the rates came from ('litellm' or 'manual')
+
+    Returns:
+        {"recorded": bool, "model": ..., "effective_from": ...}
+    """
+    day = _validate_date(effective_from) if effective_from else date.today().isoformat()
+    rates = asdict(pricing)
+    existing = _select_versions(conn, model)
+
+    covering = next(
+        (v for v in existing
+         if v["effective_from"] <= day and (v["effective_to"] is None or day < v["effective_to"])),
+        None
+    )
+    if covering and covering["rates"] == rates:
+        return {"recorded": False, "model": model, "effective_from": covering["effective_from"]}
+
+    later = [v["effective_from"] for v in existing if v["effective_from"] > day]
+    effective_to = min(later) if later else None
+
+    cursor = conn.cursor()
+    if covering and covering["effective_from"] != day:
+        cursor.execute("""
+            UPDATE price_versions SET effective_to = ?
+            WHERE model = ? AND effective_from = ?
+        """, (day, model, covering["effective_from"]))
+
+    columns = ", ".join(RATE_FIELDS)
+    placeholders = ", ".join("?" for _ in RATE_FIELDS)
+    cursor.execute(f"""
+        INSERT OR REPLACE INTO price_versions
+        (model, effective_from, effective_to, {columns}, source, recorded_at)
+        VALUES (?, ?, ?, {placeholders}, ?, ?)
+    """, (
+        model, day, effective_to, *[rates[name] for name in RATE_FIELDS],
+        source, datetime.now().astimezone().isoformat(),
+    ))
+    conn.commit()
+
+    return {"recorded": True, "model": model, "effective_from": day}
+
+
+def sync_price_versions(
+    conn: sqlite3.Connection,
+    effective_from: Optional[str] = None
+) -> dict[str, Any]:
+    """
+    Record LiteLLM rates for every model seen in message_entries.
+
+    A model's first LiteLLM version covers all history up to any manual
+    version; later changes in the cached dataset start a new version at
+    effective_from (default: today).
+
+    Returns:
+        {"models": N, "new_versions": N, "unpriced": [...models without pricing...]}
+    """
+    dataset = load_pricing_dataset()
+    cursor = conn.cursor()
+    cursor.execute("SELECT DISTINCT model FROM message_entries WHERE model IS NOT NULL")
+    models = sorted(row[0] for row in cursor.fetchall())
+
+    # Latest LiteLLM-sourced rates per model (manual versions are left alone)
+    latest_litellm: dict[str, dict[str, Any]] = {}
+    for version in _select_versions(conn):
+        if version["source"] == "litellm":
+            latest_litellm[version["model"]] = version["rates"]
+
+    new_versions = 0
+    unpriced = []
+    for model in models:
+        pricing = find_model_pricing(model, dataset) if dataset else None
+        if pricing is None:
+            unpriced.append(model)
+            continue
+        if model not in latest_litellm:
+            start = FIRST_EFFECTIVE_DATE
+        elif latest_litellm[model] != asdict(pricing):
+            start = effective_from
+        else:
+            continue
+        if record_price_version(conn, model, pricing, start, source="litellm")["recorded"]:
+            new_versions += 1
+
+    return {"models": len(models), "new_versions": new_versions, "unpriced": unpriced}
+
+
+def _hour_bucket(timestamp_local: str) -> str:
+    return f"{timestamp_local[:10]} {timestamp_local[11:13]}:00:00"
+
+
+def reprice_history(
+    conn: sqlite3.Connection,
+    date_from: Optional[str] = None,
+    date_to: Optional[str] = None,
+    dry_run: bool = False
+) -> dict[str, Any]:
+    """
+    Recompute stored message costs from the price table.
+
+    Messages whose model has no price version for their date keep their
+    cost. Hourly and model aggregates are recomputed for affected data.
+
+    Args:
+        conn: Database connection
+        date_from: Optional start date (YYYY-MM-DD)
+        date_to: Optional end date (YYYY-MM-DD)
+        dry_run: Report deltas without writing
+
+    Returns:
+        {
+            "dry_run": bool,
+            "messages_checked": N,
+            "messages_changed": N,
+            "cost_before": X, "cost_after": X, "delta": X,
+            "by_model": [{"model", "messages_changed", "cost_before", "cost_after", "delta"}]
+        }
+    """
+    book = load_price_book(conn)
+
+    conditions = ["model IS NOT NULL", "(cost_source IS NULL OR cost_source != 'jsonl')"]
+    params: list[Any] = []
+    if date_from:
+        conditions.append("date >= ?")
+        params.append(_validate_date(date_from))
+    if date_to:
+        conditions.append("date <= ?")
+        params.append(_validate_date(date_to))
+
+    cursor = conn.cursor()
+    cursor.execute(f"""
+        SELECT entry_hash, model, date, timestamp_local, year, cost_usd,
+               input_tokens, output_tokens, cache_read_tokens, cache_write_tokens
+        FROM message_entries
+        WHERE {" AND ".join(conditions)}
+    """, params)
+
+    checked = 0
+    updates = []
+    by_model: dict[str, dict[str, Any]] = {}
+    affected_hours: set[str] = set()
+    affected_years: set[int] = set()
+
+    for row in cursor.fetchall():
+        entry_hash, model, day, timestamp_local, year, old_cost = row[:6]
+        pricing = book.pricing_for(model, day)
+        if pricing is None:
+            continue
+        checked += 1
+        new_cost = calculate_cost_usd(
+            input_tokens=row[6] or 0,
+            output_tokens=row[7] or 0,
+            cache_creation_tokens=row[9] or 0,
+            cache_read_tokens=row[8] or 0,
+            pricing=pricing
+        )
+        if old_cost is not None and abs(new_cost - old_cost) < 1e-9:
+            continue
+
+        updates.append((new_cost, entry_hash))
+        affected_hours.add(_hour_bucket(timestamp_local))
+        affected_years.add(year)
+
+        stats = by_model.setdefault(model, {
+            "model": model, "messages_changed": 0, "cost_before": 0.0, "cost_after": 0.0,
+        })
+        stats["messages_changed"] += 1
+        stats["cost_before"] += old_cost or 0
+        stats["cost_after"] += new_cost
+
+    if updates and not dry_run:
+        cursor.executemany("""
+            UPDATE message_entries SET cost_usd = ?, cost_source = 'computed'
+            WHERE entry_hash = ?
+        """, updates)
+        conn.commit()
+        recompute_hourly_aggregates(conn, affected_hours, reason="reprice")
+        for year in affected_years:
+            recompute_model_aggregates(conn, year)
+
+    models = []
+    for stats in sorted(by_model.values(), key=lambda s: abs(s["cost_after"] - s["cost_before"]), reverse=True):
+        stats["delta"] = round(stats["cost_after"] - stats["cost_before"], 4)
+        stats["cost_before"] = round(stats["cost_before"], 4)
+        stats["cost_after"] = round(stats["cost_after"], 4)
+        models.append(stats)
+
+    cost_before = sum(s["cost_before"] for s in models)
+    cost_after = sum(s["cost_after"] for s in models)
+    return {
+        "dry_run": dry_run,
+        "messages_checked": checked,
+        "messages_changed": len(updates),
+        "cost_before": round(cost_before, 4),
+        "cost_after": round(cost_after, 4),
+        "delta": round(cost_after - cost_before, 4),
+        "by_model": models,
+    }
+
+
+def query_api_equivalent_cost(
+    conn: sqlite3.Connection,
+    date_from: str,
+    date_to: str,
+    project_id: ProjectScope = None,
+    model: Optional[str] = None
+) -> dict[str, Any]:
+    """
+    API list-price equivalent of the usage in a range.
+
+    Computed costs already are token counts times the rates in effect on
+    the message date. Messages that carried ``costUSD`` in the logs are
+    recomputed from their token counts, so the total is comparable to a
+    subscription price regardless of where costs came from.
+
+    Returns:
+        {"total": X, "by_model": {model: X}, "unpriced_messages": N}
+    """
+    conditions = ["date >= ?", "date <= ?"]
+    params: list[Any] = [date_from, date_to]
+    if project_id:
+        conditions.append("project_id IN (SELECT value FROM json_each(?))")
+        params.append(project_scope_param(project_id))
+    else:
+        conditions.append("origin = 'local'")
+    if model:
+        conditions.append("model = ?")
+        params.append(model)
+    where = " AND ".join(conditions)
+
+    cursor = conn.cursor()
+    cursor.execute(f"""
+        SELECT model, SUM(COALESCE(cost_usd, 0)), SUM(CASE WHEN cost_usd IS NULL THEN 1 ELSE 0 END)
+        FROM message_entries
+        WHERE {where} AND (cost_source IS NULL OR cost_source != 'jsonl')
+        GROUP BY model
+    """, params)
+
+    by_model: dict[str, float] = {}
+    unpriced = 0
+    for row_model, cost, missing in cursor.fetchall():
+        by_model[row_model] = by_model.get(row_model, 0.0) + (cost or 0)
+        unpriced += missing or 0
+
+    cursor.execute(f"""
+        SELECT model, date, input_tokens, output_tokens, cache_read_tokens, cache_write_tokens
+        FROM message_entries
+        WHERE {where} AND cost_source = 'jsonl'
+    """, params)
+    jsonl_rows = cursor.fetchall()
+    if jsonl_rows:
+        book = load_price_book(conn)
+        dataset = load_pricing_dataset()
+        for row_model, day, input_tokens, output_tokens, cache_read, cache_write in jsonl_rows:
+            pricing = book.pricing_for(row_model, day)
+            if pricing is None and row_model and dataset:
+                pricing = find_model_pricing(row_model, dataset)
+            if pricing is None:
+                unpriced += 1
+                continue
+            by_model[row_model] = by_model.get(row_model, 0.0) + calculate_cost_usd(
+                input_tokens=input_tokens or 0,
+                output_tokens=output_tokens or 0,
+                cache_creation_tokens=cache_write or 0,
+                cache_read_tokens=cache_read or 0,
+                pricing=pricing
+            )
+
+    return {
+        "total": round(sum(by_model.values()), 4),
+        "by_model": {m: round(c, 4) for m, c in by_model.items()},
+        "unpriced_messages": unpriced,
+    }
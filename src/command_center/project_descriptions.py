@@ -0,0 +1,261 @@
+"""
+Suggested project descriptions from recent session content.
+
+The project's most recent session files are read for session summaries,
+user prompts and the files touched by tool calls. A local keyword
+extraction turns them into a one-line candidate such as
+``"Python and TypeScript project: dashboard, worker, export"``.
+
+When the ``description_llm`` setting is on and ``ANTHROPIC_API_KEY`` is
+set, the extracted summaries, keywords and file names (never full
+transcripts) are sent to the Messages API for a better phrased sentence;
+any failure falls back to the local suggestion. Suggestions are only
+returned, never stored: the user approves them through ``update_project``.
+"""
+from __future__ import annotations
+
+import json
+import os
+import re
+import sqlite3
+from collections import Counter
+from typing import Any, Iterator, Optional
+
+from command_center.settings import SETTINGS_JSON_PATH, load_settings
+
+# Session files read per suggestion (most recent first)
+MAX_SESSION_FILES = 20
+
+MAX_KEYWORDS = 6
+MAX_DESCRIPTION_LENGTH = 200
+
+API_KEY_ENV = "ANTHROPIC_API_KEY"
+MESSAGES_URL = "https://api.anthropic.com/v1/messages"
+DEFAULT_LLM_MODEL = "claude-haiku-4-5"
+
+# Session summaries describe the work better than prompts, so they weigh more
+SUMMARY_WEIGHT = 3
+
+WORD_PATTERN = re.compile(r"[A-Za-z][A-Za-z0-9_+#-]{2,}")
+
+STOPWORDS = frozenset("""
+about above after again against also and any are aren because been before being below between both but
+can cannot could did didn does doesn doing don down during each either else few for from further get
+got had has have having here how into isn its just let like make many may more most much must need
+new not now off once only other our out over own please same should some such than that the their
+them then there these they this those through too under until use used using very was way well were
+what when where which while who why will with within without would yes yet you your
+add added adding change changes check create fix fixed file files code run running look want try work
+working show make sure update implement implemented let thanks okay think still able done next
+""".split())
+
+LANGUAGES = {
+    ".py": "Python", ".rs": "Rust", ".ts": "TypeScript", ".tsx": "TypeScript", ".js": "JavaScript",
+    ".jsx": "JavaScript", ".go": "Go", ".java": "Java", ".kt": "Kotlin", ".swift": "Swift",
+    ".rb": "Ruby", ".php": "PHP", ".cs": "C#", ".cpp": "C++", ".cc": "C++", ".c": "C", ".h": "C",
+    ".scala": "Scala", ".ex": "Elixir", ".exs": "Elixir", ".dart": "Dart", ".lua": "Lua",
+    ".sql": "SQL", ".sh": "Shell", ".vue": "Vue", ".svelte": "Svelte",
+}
+
+FILE_PATH_KEYS = ("file_path", "notebook_path", "path")
+
+
+def _text(content: Any) -> str:
+    """Plain text of a message content (string or block list)."""
+    if isinstance(content, str):
+        return content
+    if isinstance(content, list):
+        return "\n".join(
+            block.get("text", "") for block in content
+            if isinstance(block, dict) and block.get("type") == "text"
+        )
+    return ""
+
+
+def _session_files(conn: sqlite3.Connection, project_id: str, limit: int) -> list[str]:
+    """Source files of the project's most recently active sessions."""
+    cursor = conn.execute("""
+        SELECT source_file
+        FROM message_entries
+        WHERE project_id = ? AND origin = 'local'
+        GROUP BY source_file
+        ORDER BY MAX(timestamp) DESC
+        LIMIT ?
+    """, (project_id, limit))
+    return [row[0] for row in cursor.fetchall()]
+
+
+def _session_content(paths: list[str]) -> Iterator[tuple[str, str]]:
+    """("summary" | "prompt" | "file", text) items of session files; unreadable files are skipped."""
+    for path in paths:
+        try:
+            f = open(path, "r", encoding="utf-8", errors="replace")
+        except OSError:
+            continue
+        with f:
+            for line in f:
+                try:
+                    entry = json.loads(line)
+                except ValueError:
+                    continue
+                if not isinstance(entry, dict) or entry.get("isSidechain"):
+                    continue
+                if entry.get("type") == "summary" and isinstance(entry.get("summary"), str):
+                    yield "summary", entry["summary"]
+                    continue
+                message = entry.get("message")
+                if not isinstance(message, dict):
+                    continue
+                content = message.get("content")
+                if entry.get("type") == "user":
+                    text = _text(content).strip()
+                    # Slash commands, hook output and interrupts are wrapped in tags or brackets
+                    if text and not text.startswith(("<", "[")):
+                        yield "prompt", text
+                elif entry.get("type") == "assistant" and isinstance(content, list):
+                    for block in content:
+                        if isinstance(block, dict) and block.get("type") == "tool_use":
+                            tool_input = block.get("input") if isinstance(block.get("input"), dict) else {}
+                            for key in FILE_PATH_KEYS:
+                                if isinstance(tool_input.get(key), str):
+                                    yield "file", tool_input[key]
+                                    break
+
+
+def extract_topics(paths: list[str]) -> dict[str, Any]:
+    """
+    Keywords, languages and file names of session files.
+
+    Returns:
+        {"keywords": [...], "languages": [...], "files": [...], "summaries": [...]}
+        (most frequent first)
+    """
+    words: Counter[str] = Counter()
+    languages: Counter[str] = Counter()
+    files: Counter[str] = Counter()
+    summaries: list[str] = []
+    for kind, text in _session_content(paths):
+        if kind == "file":
+            name = os.path.basename(text)
+            stem, ext = os.path.splitext(name)
+            files[name] += 1
+            if ext.lower() in LANGUAGES:
+                languages[LANGUAGES[ext.lower()]] += 1
+            # File stems are topics too ("png_generator" → png, generator)
+            for part in re.split(r"[_\-.]", stem.lower()):
+                if len(part) > 2 and part not in STOPWORDS:
+                    words[part] += 1
+            continue
+        if kind == "summary" and text not in summaries:
+            summaries.append(text)
+        weight = SUMMARY_WEIGHT if kind == "summary" else 1
+        for word in WORD_PATTERN.findall(text):
+            word = word.lower().strip("-")
+            if len(word) > 2 and word not in STOPWORDS:
+                words[word] += weight
+
+    return {
+        "keywords": [word for word, _ in words.most_common(MAX_KEYWORDS)],
+        "languages": [language for language, _ in languages.most_common(2)],
+        "files": [name for name, _ in files.most_common(10)],
+        "summaries": summaries[:10],
+    }
+
+
+def local_description(topics: dict[str, Any]) -> Optional[str]:
+    """One-line description from extracted topics (None if there is nothing to say)."""
+    languages = topics["languages"]
+    keywords = topics["keywords"]
+    if not languages and not keywords:
+        return None
+    subject = f"{' and '.join(languages)} project" if languages else "Project"
+    description = f"{subject}: {', '.join(keywords)}" if keywords else subject
+    return description[:MAX_DESCRIPTION_LENGTH]
+
+
+def llm_description(topics: dict[str, Any], project_name: str, model: str, api_key: str) -> str:
+    """
+    Ask the Messages API for a one-sentence description of the extracted topics.
+
+    Raises:
+        ValueError: If the request fails or the answer is empty
+    """
+    import requests
+
+    prompt = (
+        "Write a one-sentence description (max 20 words, no quotes) of a software project "
+        "based on what its recent coding sessions were about.\n"
+        f"Project name: {project_name}\n"
+        f"Languages: {', '.join(topics['languages']) or 'unknown'}\n"
+        f"Session summaries: {'; '.join(topics['summaries']) or 'none'}\n"
+        f"Keywords: {', '.join(topics['keywords'])}\n"
+        f"Files: {', '.join(topics['files'])}"
+    )
+    response = requests.post(
+        MESSAGES_URL,
+        headers={
+            "x-api-key": api_key,
+            "anthropic-version": "2023-06-01",
+            "content-type": "application/json",
+        },
+        json={"model": model, "max_tokens": 100, "messages": [{"role": "user", "content": prompt}]},
+        timeout=30,
+    )
+    if response.status_code >= 400:
+        raise ValueError(f"Messages API returned HTTP {response.status_code}")
+    try:
+        text = _text(response.json().get("content")).strip().strip('"')
+    except (ValueError, AttributeError) as e:
+        raise ValueError(f"Invalid Messages API response: {e}")
+    if not text:
+        raise ValueError("Messages API returned an empty description")
+    return text[:MAX_DESCRIPTION_LENGTH]
+
+
+def suggest_project_description(
+    conn: sqlite3.Connection,
+    project_id: str,
+    project: dict[str, Any],
+    settings_path: str = SETTINGS_JSON_PATH,
+) -> dict[str, Any]:
+    """
+    Suggest a description for a project from its recent sessions.
+
+    Args:
+        conn: Database connection
+        project_id: Project identifier
+        project: The project's metadata (name, description)
+        settings_path: Settings file with the ``description_llm`` opt-in
+
+    Returns:
+        {
+            "project_id", "suggestion": "Python project: ..." | None,
+            "source": "local" | "llm", "current_description": "...",
+            "keywords": [...], "languages": [...], "files": [...],
+            "sessions_analyzed": 12, "llm_error": "..." (only if the LLM call failed)
+        }
+    """
+    paths = _session_files(conn, project_id, MAX_SESSION_FILES)
+    topics = extract_topics(paths)
+    suggestion = local_description(topics)
+    result: dict[str, Any] = {
+        "project_id": project_id,
+        "suggestion": suggestion,
+        "source": "local",
+        "current_description": project.get("description", ""),
+        "keywords": topics["keywords"],
+        "languages": topics["languages"],
+        "files": topics["files"],
+        "sessions_analyzed": len(paths),
+    }
+
+    settings = load_settings(settings_path)
+    api_key = os.environ.get(API_KEY_ENV)
+    if suggestion and settings.get("description_llm") is True and api_key:
+        model = settings.get("description_llm_model") or DEFAULT_LLM_MODEL
+        try:
+            result["suggestion"] = llm_description(topics, project.get("name") or project_id, model, api_key)
+            result["source"] = "llm"
+        except Exception as e:
+            result["llm_error"] = str(e)
+    return result
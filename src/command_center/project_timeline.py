@@ -0,0 +1,238 @@
+"""
+Lifetime timeline of a single project (the project "profile").
+
+Covers the whole history of the project, independent of the dashboard date
+range: an activity curve with cumulative cost, the first and last session,
+and milestones such as the first use of each model, the biggest day and the
+points where cumulative cost crossed $10, $100, ...
+
+``backfill_first_seen`` corrects the ``first_seen`` field of projects.json,
+which records when a scan discovered the project rather than its first
+session, from the earliest message in the database.
+"""
+from __future__ import annotations
+
+import sqlite3
+from datetime import date
+from typing import Any, Optional
+
+from command_center.database.queries import project_scope_param
+from command_center.utils.date_helpers import parse_iso_timestamp
+from command_center.utils.project_metadata import PROJECTS_JSON_PATH, load_projects_json, save_projects_json
+
+GRANULARITIES = ("auto", "day", "week", "month")
+
+# Cumulative cost thresholds reported as milestones (USD)
+COST_MILESTONES = (10, 100, 1000, 10000)
+
+# Auto granularity: daily up to ~3 months of history, weekly up to two years
+AUTO_DAY_SPAN_DAYS = 92
+AUTO_WEEK_SPAN_DAYS = 730
+
+
+def _period(day: str, granularity: str) -> str:
+    if granularity == "month":
+        return day[:7]
+    if granularity == "week":
+        # Same keys as the dashboard timeline (SQLite %Y-W%W)
+        return date.fromisoformat(day).strftime("%Y-W%W")
+    return day
+
+
+def query_project_first_seen(
+    conn: sqlite3.Connection,
+    project_ids: Optional[list[str]] = None
+) -> dict[str, str]:
+    """
+    Local timestamp of each project's earliest message.
+
+    Args:
+        conn: Database connection
+        project_ids: Projects to look up (default: all)
+
+    Returns:
+        Dict mapping project_id → ISO timestamp
+    """
+    sql = "SELECT project_id, MIN(timestamp_local) FROM message_entries"
+    params: tuple = ()
+    if project_ids is not None:
+        sql += " WHERE project_id IN (SELECT value FROM json_each(?))"
+        params = (project_scope_param(project_ids),)
+    sql += " GROUP BY project_id"
+    return {row[0]: row[1] for row in conn.execute(sql, params).fetchall() if row[0] and row[1]}
+
+
+def backfill_first_seen(
+    conn: sqlite3.Connection,
+    project_ids: Optional[list[str]] = None,
+    json_path: str = PROJECTS_JSON_PATH
+) -> dict[str, Any]:
+    """
+    Move ``first_seen`` back to the first session where the database knows an earlier one.
+
+    Args:
+        conn: Database connection
+        project_ids: Projects to correct (default: all)
+        json_path: Path to projects JSON file
+
+    Returns:
+        {"updated": [{"project_id", "previous", "first_seen"}, ...], "checked": 12}
+    """
+    projects = load_projects_json(json_path)
+    earliest = query_project_first_seen(conn, project_ids)
+    updated = []
+    for project_id, first_message in earliest.items():
+        meta = projects.get(project_id)
+        if meta is None:
+            continue
+        previous = meta.get("first_seen")
+        previous_at = parse_iso_timestamp(previous) if isinstance(previous, str) else None
+        first_at = parse_iso_timestamp(first_message)
+        if first_at is None:
+            continue
+        if previous_at is None or first_at < previous_at:
+            meta["first_seen"] = first_message
+            updated.append({"project_id": project_id, "previous": previous, "first_seen": first_message})
+    if updated:
+        save_projects_json(projects, json_path)
+    return {"updated": updated, "checked": len(earliest)}
+
+
+def query_project_timeline(
+    conn: sqlite3.Connection,
+    project_id: str,
+    granularity: str = "auto"
+) -> dict[str, Any]:
+    """
+    Lifetime activity of a project.
+
+    Args:
+        conn: Database connection
+        project_id: Project identifier
+        granularity: Curve resolution: 'day', 'week', 'month' or 'auto' (by history length)
+
+    Returns:
+        {
+            "project_id", "granularity": "week",
+            "first_session": {"session_id", "timestamp"} | None,
+            "last_session": {"session_id", "timestamp"} | None,
+            "totals": {"messages", "sessions", "tokens", "cost", "active_days", "models"},
+            "curve": [{"period", "messages", "sessions", "tokens", "cost", "cumulative_cost"}, ...],
+            "milestones": [{"type", "date", ...}, ...]
+        }
+        Milestone types: first_session, new_model (model), biggest_day (tokens, cost),
+        cost_milestone (threshold), last_session; sorted by date.
+    """
+    if granularity not in GRANULARITIES:
+        raise ValueError(f"Invalid granularity: {granularity} (expected one of {', '.join(GRANULARITIES)})")
+
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT date, COUNT(*), SUM(total_tokens), SUM(COALESCE(cost_usd, 0))
+        FROM message_entries
+        WHERE project_id = ?
+        GROUP BY date
+        ORDER BY date
+    """, (project_id,))
+    days = [
+        {"date": row[0], "messages": row[1], "tokens": row[2] or 0, "cost": row[3] or 0.0}
+        for row in cursor.fetchall()
+    ]
+
+    empty = {
+        "project_id": project_id,
+        "granularity": "day" if granularity == "auto" else granularity,
+        "first_session": None,
+        "last_session": None,
+        "totals": {"messages": 0, "sessions": 0, "tokens": 0, "cost": 0.0, "active_days": 0, "models": []},
+        "curve": [],
+        "milestones": [],
+    }
+    if not days:
+        return empty
+
+    if granularity == "auto":
+        span = (date.fromisoformat(days[-1]["date"]) - date.fromisoformat(days[0]["date"])).days
+        granularity = "day" if span <= AUTO_DAY_SPAN_DAYS else "week" if span <= AUTO_WEEK_SPAN_DAYS else "month"
+
+    # Sessions per period are counted distinct across days, so they come from their own query
+    cursor.execute("""
+        SELECT session_id, MIN(date), MIN(timestamp_local), MAX(timestamp_local)
+        FROM message_entries
+        WHERE project_id = ? AND session_id IS NOT NULL
+        GROUP BY session_id
+    """, (project_id,))
+    sessions = cursor.fetchall()
+    sessions_per_period: dict[str, int] = {}
+    for _, first_day, _, _ in sessions:
+        key = _period(first_day, granularity)
+        sessions_per_period[key] = sessions_per_period.get(key, 0) + 1
+    first = min(sessions, key=lambda s: s[2], default=None)
+    last = max(sessions, key=lambda s: s[3], default=None)
+
+    curve: list[dict[str, Any]] = []
+    cumulative = 0.0
+    milestones: list[dict[str, Any]] = []
+    thresholds = list(COST_MILESTONES)
+    for day in days:
+        cumulative += day["cost"]
+        while thresholds and cumulative >= thresholds[0]:
+            milestones.append({"type": "cost_milestone", "date": day["date"], "threshold": thresholds.pop(0)})
+        key = _period(day["date"], granularity)
+        if not curve or curve[-1]["period"] != key:
+            curve.append({"period": key, "messages": 0, "sessions": sessions_per_period.get(key, 0),
+                          "tokens": 0, "cost": 0.0, "cumulative_cost": 0.0})
+        curve[-1]["messages"] += day["messages"]
+        curve[-1]["tokens"] += day["tokens"]
+        curve[-1]["cost"] += day["cost"]
+        curve[-1]["cumulative_cost"] = cumulative
+    for point in curve:
+        point["cost"] = round(point["cost"], 4)
+        point["cumulative_cost"] = round(point["cumulative_cost"], 4)
+
+    # First use of each model (models of the first day belong to the first_session milestone)
+    cursor.execute("""
+        SELECT model, MIN(date)
+        FROM message_entries
+        WHERE project_id = ? AND model IS NOT NULL
+        GROUP BY model
+        ORDER BY MIN(timestamp)
+    """, (project_id,))
+    models = cursor.fetchall()
+    first_day = days[0]["date"]
+    first_day_models = [model for model, day in models if day == first_day]
+    for model, day in models:
+        if day != first_day:
+            milestones.append({"type": "new_model", "date": day, "model": model})
+
+    biggest = max(days, key=lambda d: d["tokens"])
+    milestones.append({
+        "type": "biggest_day", "date": biggest["date"],
+        "tokens": biggest["tokens"], "cost": round(biggest["cost"], 4),
+    })
+    if first:
+        milestones.append({
+            "type": "first_session", "date": first[2][:10], "session_id": first[0], "models": first_day_models,
+        })
+    if last and len(sessions) > 1:
+        milestones.append({"type": "last_session", "date": last[3][:10], "session_id": last[0]})
+
+    order = {"first_session": 0, "new_model": 1, "cost_milestone": 2, "biggest_day": 3, "last_session": 4}
+    milestones.sort(key=lambda m: (m["date"], order[m["type"]]))
+
+    return {
+        **empty,
+        "granularity": granularity,
+        "first_session": {"session_id": first[0], "timestamp": first[2]} if first else None,
+        "last_session": {"session_id": last[0], "timestamp": last[3]} if last else None,
+        "totals": {
+            "messages": sum(d["messages"] for d in days),
+            "sessions": len(sessions),
+            "tokens": sum(d["tokens"] for d in days),
+            "cost": round(cumulative, 4),
+            "active_days": len(days),
+            "models": [model for model, _ in models],
+        },
+        "curve": curve,
+        "milestones": milestones,
+    }
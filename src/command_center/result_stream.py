@@ -0,0 +1,79 @@
+"""
+Streaming of large result lists as newline-delimited JSON.
+
+Some subcommands return one big list (STREAMED_RESULTS: the sessions of a
+page, the messages of a session). When the desktop bridge asks for a stream
+(``COMMAND_CENTER_STREAM=1`` for one-shot runs, ``"stream": true`` in a
+worker request) the list is sent in chunks before the result instead of
+inside it:
+
+    {"chunk": {"key": "messages", "seq": 0, "items": [...]}}    one per CHUNK_SIZE items
+    {...result, "messages": [], "streamed": {"key": "messages", "chunks": 3, "items": 512}}
+
+One-shot runs write the chunk lines to stdout ahead of the result line; the
+worker forwards them to the sink set with ``set_chunk_sink`` as
+``{"id", "chunk"}`` lines on its protocol stream, like progress events.
+Without a stream the result is printed unchanged.
+"""
+from __future__ import annotations
+
+import json
+import os
+import sys
+from typing import Any, Callable, Optional
+
+# Set to 1 (by the desktop bridge) to stream results of one-shot runs
+STREAM_ENV = "COMMAND_CENTER_STREAM"
+
+# Subcommand → key of the list that is streamed
+STREAMED_RESULTS = {
+    "sessions": "sessions",
+    "session": "messages",
+}
+
+# Items per chunk
+CHUNK_SIZE = 200
+
+ChunkSink = Callable[[dict[str, Any]], None]
+
+_chunk_sink: Optional[ChunkSink] = None
+
+
+def set_chunk_sink(sink: Optional[ChunkSink]) -> Optional[ChunkSink]:
+    """Send chunks to ``sink`` (None: stdout if enabled); returns the previous sink."""
+    global _chunk_sink
+    previous, _chunk_sink = _chunk_sink, sink
+    return previous
+
+
+def _write_chunk(chunk: dict[str, Any]):
+    sys.stdout.write(json.dumps({"chunk": chunk}, ensure_ascii=False) + "\n")
+    sys.stdout.flush()
+
+
+def stream_result(command: str, result: Any, chunk_size: int = CHUNK_SIZE) -> Any:
+    """
+    Send the streamed list of a result in chunks, if a stream was requested.
+
+    Args:
+        command: Subcommand that produced the result
+        result: Its result (after presentation blurring)
+        chunk_size: Items per chunk
+
+    Returns:
+        The result to print: with the list emptied and a "streamed" summary
+        when it was streamed, else unchanged
+    """
+    key = STREAMED_RESULTS.get(command)
+    sink = _chunk_sink
+    if sink is None and os.environ.get(STREAM_ENV) == "1":
+        sink = _write_chunk
+    if key is None or sink is None or not isinstance(result, dict) or not isinstance(result.get(key), list):
+        return result
+
+    items = result[key]
+    chunks = 0
+    for start in range(0, len(items), chunk_size):
+        sink({"key": key, "seq": chunks, "items": items[start:start + chunk_size]})
+        chunks += 1
+    return {**result, key: [], "streamed": {"key": key, "chunks": chunks, "items": len(items)}}
@@ -0,0 +1,337 @@
+"""
+Sample session logs for onboarding.
+
+Unlike demo data (rows written straight into the database), sample data are
+Claude Code session logs: ``generate_sample_data`` writes synthetic JSONL
+into the sandbox home's ``.claude/projects`` (the demo home, see
+``demo_data``) and ingests them with the regular incremental update, so a new
+user (or a test) exercises the whole pipeline before having real history.
+
+Besides usage, the logs contain what the other analytics read: thinking
+settings of prompts, Bash tool calls with commits, test runs and failures,
+``mcp__<server>__<tool>`` calls, hook attachments, compactions and
+interrupted requests. Every assistant line carries ``costUSD``, so no pricing
+dataset is needed.
+
+The same seed always produces the same logs (relative to the end date).
+"""
+from __future__ import annotations
+
+import json
+import os
+import random
+import shutil
+import sqlite3
+import uuid
+from contextlib import contextmanager
+from datetime import date, datetime, timedelta, timezone
+from pathlib import Path
+from typing import Any, Iterator, Optional
+
+from command_center.demo_data import (
+    DEFAULT_SEED,
+    DEMO_PROJECTS,
+    DEMO_TABLES,
+    HOUR_WEIGHTS,
+    _pick_model,
+    _pricing,
+    _project_id,
+)
+from command_center.utils.pricing import calculate_cost_usd
+from command_center.utils.project_metadata import PROJECTS_JSON_PATH, save_projects_json
+
+DEFAULT_DAYS = 30
+MAX_DAYS = 365
+DEFAULT_INTENSITY = "regular"
+
+# Mean sessions on an active weekday per intensity
+SAMPLE_INTENSITIES = {
+    "light": 2,
+    "regular": 5,
+    "heavy": 12,
+}
+
+THINKING_LEVELS = ("none", "none", "medium", "high")
+MCP_TOOLS = (
+    "mcp__github__create_pull_request",
+    "mcp__github__list_issues",
+    "mcp__postgres__query",
+    "mcp__linear__create_issue",
+)
+BASH_COMMANDS = (
+    ("npm test", "Tests: 42 passed, 42 total"),
+    ("pytest -q", "118 passed in 3.21s"),
+    ("cargo build", "Finished dev [unoptimized + debuginfo] target(s)"),
+    ("git status", "nothing to commit, working tree clean"),
+)
+PROMPTS = (
+    "Fix the failing test in the checkout flow",
+    "Add pagination to the invoices endpoint",
+    "Refactor the settings screen into smaller components",
+    "Why is the nightly job slower than last week?",
+    "Write a migration for the new audit table",
+    "Update the README with the new setup steps",
+)
+
+
+def _timestamp(moment: datetime) -> str:
+    return moment.astimezone(timezone.utc).strftime("%Y-%m-%dT%H:%M:%S.%f")[:-3] + "Z"
+
+
+class _Session:
+    """Writer of one session's lines, keeping the clock and line identifiers."""
+
+    def __init__(self, rng: random.Random, session_id: str, cwd: str, model: str, start: datetime):
+        self.rng = rng
+        self.session_id = session_id
+        self.cwd = cwd
+        self.model = model
+        self.pricing = _pricing(model)
+        self.clock = start
+        self.lines: list[dict[str, Any]] = []
+        self.messages = 0
+        self.context = 0
+
+    def _line(self, kind: str, **fields: Any) -> dict[str, Any]:
+        self.clock += timedelta(seconds=2 + self.rng.expovariate(1 / 20))
+        line = {
+            "type": kind,
+            "uuid": str(uuid.UUID(int=self.rng.getrandbits(128), version=4)),
+            "sessionId": self.session_id,
+            "timestamp": _timestamp(self.clock),
+            "cwd": self.cwd,
+            "version": "2.0.0",
+        }
+        line.update(fields)
+        self.lines.append(line)
+        return line
+
+    def prompt(self, text: str) -> None:
+        self._line(
+            "user",
+            thinkingMetadata={"level": self.rng.choice(THINKING_LEVELS)},
+            message={"role": "user", "content": text},
+        )
+
+    def tool_result(self, tool_use_id: str, output: str, is_error: bool = False) -> None:
+        self._line("user", message={"role": "user", "content": [
+            {"type": "tool_result", "tool_use_id": tool_use_id, "content": output, "is_error": is_error},
+        ]})
+
+    def response(self, content: list[dict[str, Any]]) -> None:
+        input_tokens = int(self.rng.lognormvariate(3.5, 1.0))
+        output_tokens = int(self.rng.lognormvariate(5.5, 1.0))
+        cache_read = self.context
+        cache_write = int(self.rng.lognormvariate(7.5, 1.2))
+        self.context += cache_write + output_tokens
+        index = self.messages
+        self.messages += 1
+        self._line(
+            "assistant",
+            requestId=f"req_sample_{self.session_id[:8]}_{index}",
+            costUSD=round(calculate_cost_usd(input_tokens, output_tokens, cache_write, cache_read, self.pricing), 6),
+            message={
+                "id": f"msg_sample_{self.session_id[:8]}_{index}",
+                "role": "assistant",
+                "model": self.model,
+                "content": content,
+                "usage": {
+                    "input_tokens": input_tokens,
+                    "output_tokens": output_tokens,
+                    "cache_read_input_tokens": cache_read,
+                    "cache_creation_input_tokens": cache_write,
+                },
+            },
+        )
+
+    def tool_use(self, name: str, tool_input: dict[str, Any]) -> str:
+        tool_use_id = f"toolu_sample_{self.session_id[:8]}_{len(self.lines)}"
+        self.response([{"type": "tool_use", "id": tool_use_id, "name": name, "input": tool_input}])
+        return tool_use_id
+
+    def hook(self, event: str, command: str, outcome: str) -> None:
+        self._line("attachment", attachment={
+            "type": outcome, "hookName": f"{event}:Bash", "hookEvent": event,
+            "command": command, "durationMs": int(self.rng.lognormvariate(5.0, 0.8)),
+        })
+
+    def interrupt(self) -> None:
+        self._line("user", message={"role": "user", "content": "[Request interrupted by user]"})
+
+    def compact(self, trigger: str) -> None:
+        self._line("system", subtype="compact_boundary", compactMetadata={"trigger": trigger, "preTokens": self.context})
+        self.context = int(self.context * 0.15)
+
+
+def _write_session(rng: random.Random, project_dir: Path, cwd: str, model: str, start: datetime) -> int:
+    """Write one session file; returns its number of assistant messages."""
+    session = _Session(rng, str(uuid.UUID(int=rng.getrandbits(128), version=4)), cwd, model, start)
+    for _ in range(max(1, int(rng.lognormvariate(1.2, 0.6)))):
+        session.prompt(rng.choice(PROMPTS))
+        for _ in range(max(1, int(rng.lognormvariate(1.5, 0.7)))):
+            roll = rng.random()
+            if roll < 0.35:
+                command, output = rng.choice(BASH_COMMANDS)
+                failed = rng.random() < 0.1
+                tool_use_id = session.tool_use("Bash", {"command": command})
+                session.tool_result(tool_use_id, "Error: command failed" if failed else output, failed)
+                if rng.random() < 0.3:
+                    outcome = "hook_non_blocking_error" if rng.random() < 0.15 else "hook_success"
+                    session.hook("PostToolUse", "./scripts/lint.sh", outcome)
+            elif roll < 0.5:
+                name = rng.choice(MCP_TOOLS)
+                tool_use_id = session.tool_use(name, {"query": "sample"})
+                session.tool_result(tool_use_id, "ok" * rng.randrange(1, 400), rng.random() < 0.05)
+            else:
+                session.response([{"type": "text", "text": "Sample response"}])
+            if session.context > 150_000:
+                session.compact("auto")
+        if rng.random() < 0.05:
+            session.interrupt()
+            break
+    else:
+        if rng.random() < 0.4:
+            tool_use_id = session.tool_use("Bash", {"command": 'git commit -m "Apply review feedback"'})
+            session.tool_result(tool_use_id, "[main 1a2b3c4] Apply review feedback")
+
+    project_dir.mkdir(parents=True, exist_ok=True)
+    with open(project_dir / f"{session.session_id}.jsonl", "w", encoding="utf-8") as f:
+        for line in session.lines:
+            f.write(json.dumps(line) + "\n")
+    return session.messages
+
+
+def write_sample_logs(
+    projects_dir: Path,
+    days: int = DEFAULT_DAYS,
+    intensity: str = DEFAULT_INTENSITY,
+    seed: int = DEFAULT_SEED,
+    end_date: Optional[date] = None,
+) -> dict[str, Any]:
+    """
+    Write synthetic session logs of the demo projects.
+
+    Args:
+        projects_dir: ``<config dir>/projects`` to write into
+        days: Days of history, ending at end_date (1-MAX_DAYS)
+        intensity: Key of SAMPLE_INTENSITIES
+        seed: Random seed (same seed, same logs)
+        end_date: Last day with sessions (default: today)
+
+    Returns:
+        {"date_from", "date_to", "sessions", "messages"} (one file per session)
+
+    Raises:
+        ValueError: For invalid days or an unknown intensity
+    """
+    if not 1 <= days <= MAX_DAYS:
+        raise ValueError(f"Invalid days: {days} (expected 1-{MAX_DAYS})")
+    if intensity not in SAMPLE_INTENSITIES:
+        raise ValueError(f"Unknown intensity: {intensity} (expected {', '.join(SAMPLE_INTENSITIES)})")
+    rng = random.Random(f"{seed}:{intensity}")
+    end = end_date or date.today()
+    start = end - timedelta(days=days - 1)
+    span = max(days - 1, 1)
+    projects = [(slug, share) for slug, _, _, share, _ in DEMO_PROJECTS]
+
+    sessions = messages = 0
+    day = start
+    while day <= end:
+        progress = (day - start).days / span
+        if rng.random() < (0.92 if day.weekday() < 5 else 0.3):
+            for _ in range(max(1, round(rng.gauss(SAMPLE_INTENSITIES[intensity], 1.5)))):
+                slug = rng.choices([p for p, _ in projects], [w for _, w in projects])[0]
+                hour = rng.choices(range(24), HOUR_WEIGHTS)[0]
+                started = datetime(day.year, day.month, day.day, hour, rng.randrange(60)).astimezone()
+                messages += _write_session(
+                    rng, projects_dir / _project_id(slug), f"/home/demo/projects/{slug}",
+                    _pick_model(rng, progress), started,
+                )
+                sessions += 1
+        day += timedelta(days=1)
+
+    return {
+        "date_from": start.isoformat(),
+        "date_to": end.isoformat(),
+        "sessions": sessions,
+        "messages": messages,
+    }
+
+
+@contextmanager
+def _only_home_config_dirs() -> Iterator[None]:
+    """Hide ``CLAUDE_CONFIG_DIR`` so a user's real config dir is not ingested with the samples."""
+    saved = os.environ.pop("CLAUDE_CONFIG_DIR", None)
+    try:
+        yield
+    finally:
+        if saved is not None:
+            os.environ["CLAUDE_CONFIG_DIR"] = saved
+
+
+def generate_sample_data(
+    conn: sqlite3.Connection,
+    projects_dir: Path,
+    days: int = DEFAULT_DAYS,
+    intensity: str = DEFAULT_INTENSITY,
+    seed: int = DEFAULT_SEED,
+    end_date: Optional[date] = None,
+    projects_json_path: str = PROJECTS_JSON_PATH,
+) -> dict[str, Any]:
+    """
+    Replace the sandbox's session logs and usage data with sample logs.
+
+    Only call this against the demo home (see ``demo_data.demo_mode``):
+    ``projects_dir`` is deleted and rewritten, and all usage tables and file
+    tracks of ``conn`` are cleared before the logs are ingested.
+
+    Args:
+        conn: Database connection (schema initialized)
+        projects_dir: The sandbox's ``.claude/projects``
+        days: Days of history ending at end_date (1-MAX_DAYS)
+        intensity: Key of SAMPLE_INTENSITIES
+        seed: Random seed
+        end_date: Last day with sessions (default: today)
+        projects_json_path: Project metadata file to write the sample projects to
+
+    Returns:
+        {"seed", "days", "intensity", "data_root", "date_from", "date_to", "projects",
+         "sessions", "messages", "ingested_files"}
+    """
+    from command_center.cache.incremental_update import perform_incremental_update
+
+    if os.path.isdir(projects_dir):
+        shutil.rmtree(projects_dir)
+    result = write_sample_logs(projects_dir, days, intensity, seed, end_date)
+
+    cursor = conn.cursor()
+    for table in DEMO_TABLES + ("file_tracks",):
+        cursor.execute(f"DELETE FROM {table}")
+    conn.commit()
+
+    now = datetime.now().astimezone().isoformat()
+    save_projects_json({
+        _project_id(slug): {
+            "name": name,
+            "description": description,
+            "absolute_path": f"/home/demo/projects/{slug}",
+            "first_seen": now,
+            "last_seen": now,
+            "visible": True,
+            "tags": tags,
+        }
+        for slug, name, description, _, tags in DEMO_PROJECTS
+    }, projects_json_path)
+
+    with _only_home_config_dirs():
+        ingested = perform_incremental_update(conn, force_rescan=True)
+
+    return {
+        "seed": seed,
+        "days": days,
+        "intensity": intensity,
+        "data_root": str(Path(projects_dir).parent),
+        "projects": len(DEMO_PROJECTS),
+        **result,
+        "ingested_files": ingested,
+    }
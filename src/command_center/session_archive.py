@@ -0,0 +1,306 @@
+"""
+Zip archives of raw session files.
+
+An archive bundles the JSONL files of selected sessions (main session file
+plus subagent files) with a manifest, so a reproduction case can be shared
+with teammates. With redaction, conversation content (prompts, responses,
+tool input/output, working directories) is replaced by a placeholder while
+ids, timestamps, models and token usage are kept, so usage statistics
+computed from the archive stay the same.
+
+Imported archives are extracted below IMPORTS_DIR and their messages stored
+with origin 'imported' in the chosen project: they show up when that project
+is selected but are excluded from overall (personal) totals, budgets and
+cost allocation. Messages already in the database are skipped.
+
+Layout:
+    manifest.json
+    <project_id>/<file name>.jsonl
+"""
+from __future__ import annotations
+
+import hashlib
+import json
+import os
+import sqlite3
+import zipfile
+from datetime import datetime
+from pathlib import Path
+from typing import Any
+
+from command_center import __version__ as package_version
+from command_center.config import HOME
+from command_center.database.queries import insert_message_entries, query_session_source_files
+from command_center.utils.atomic_write import commit_temp_file, temp_path_for
+from command_center.utils.project_metadata import (
+    PROJECTS_JSON_PATH,
+    auto_discover_project,
+    load_projects_json,
+    save_projects_json,
+)
+
+
+ARCHIVE_FORMAT = "command-center-session-archive"
+ARCHIVE_VERSION = 1
+MANIFEST_NAME = "manifest.json"
+
+REDACTED = "[redacted]"
+
+# Extracted archives (one directory per archive checksum)
+IMPORTS_DIR = os.path.join(HOME, ".claude", "db", "imports")
+
+# Refuse archives that would expand beyond this size
+MAX_IMPORT_BYTES = 1024 * 1024 * 1024
+
+# Top-level record fields that carry conversation content or local paths
+REDACTED_RECORD_KEYS = ("cwd", "gitBranch", "summary", "toolUseResult")
+
+
+def redact_record(record: dict[str, Any]) -> dict[str, Any]:
+    """
+    Strip conversation content from one JSONL record.
+
+    Message content blocks keep their type (and tool name/id) so the
+    conversation shape stays visible; text, thinking, tool input and tool
+    results are replaced.
+    """
+    redacted = dict(record)
+    for key in REDACTED_RECORD_KEYS:
+        if key in redacted:
+            redacted[key] = REDACTED
+
+    message = redacted.get("message")
+    if isinstance(message, dict) and "content" in message:
+        message = dict(message)
+        content = message["content"]
+        if isinstance(content, list):
+            message["content"] = [_redact_block(block) for block in content]
+        else:
+            message["content"] = REDACTED
+        redacted["message"] = message
+    return redacted
+
+
+def _redact_block(block: Any) -> Any:
+    if not isinstance(block, dict):
+        return REDACTED
+    kept = {k: block[k] for k in ("type", "id", "name", "tool_use_id") if k in block}
+    for key in ("text", "thinking", "input", "content"):
+        if key in block:
+            kept[key] = REDACTED
+    return kept
+
+
+def _redact_jsonl(data: bytes) -> bytes:
+    lines = []
+    for line in data.decode("utf-8", errors="replace").splitlines():
+        if not line.strip():
+            continue
+        try:
+            record = json.loads(line)
+        except json.JSONDecodeError:
+            # Unparseable lines may contain anything - drop them
+            continue
+        if isinstance(record, dict):
+            record = redact_record(record)
+        lines.append(json.dumps(record, ensure_ascii=False))
+    return ("\n".join(lines) + "\n").encode("utf-8") if lines else b""
+
+
+def export_session_archive(
+    conn: sqlite3.Connection,
+    session_ids: list[str],
+    output_path: str,
+    redact: bool = False
+) -> dict[str, Any]:
+    """
+    Write the raw files of sessions to a zip archive.
+
+    Args:
+        conn: Database connection
+        session_ids: Sessions to export
+        output_path: Path of the zip file to write
+        redact: Strip conversation content (see redact_record)
+
+    Returns:
+        {"path", "sessions", "files", "bytes", "redacted", "missing_files"}
+
+    Raises:
+        ValueError: If no session ids are given or a session is unknown
+    """
+    if not session_ids:
+        raise ValueError("No sessions selected for export")
+
+    manifest: dict[str, Any] = {
+        "format": ARCHIVE_FORMAT,
+        "version": ARCHIVE_VERSION,
+        "exported_at": datetime.now().astimezone().isoformat(),
+        "app_version": package_version,
+        "redacted": redact,
+        "sessions": [],
+    }
+    missing_files = []
+    written: set[str] = set()
+
+    tmp_path = temp_path_for(output_path)
+    try:
+        with zipfile.ZipFile(tmp_path, "w", compression=zipfile.ZIP_DEFLATED) as archive:
+            for session_id in dict.fromkeys(session_ids):
+                rows = query_session_source_files(conn, session_id)
+                if not rows:
+                    raise ValueError(f"Unknown session: {session_id}")
+
+                session = {"session_id": session_id, "project_id": rows[0]["project_id"], "files": []}
+                for row in rows:
+                    source = Path(row["source_file"])
+                    if not source.is_file():
+                        missing_files.append(str(source))
+                        continue
+                    name = f"{row['project_id'] or 'unknown'}/{source.name}"
+                    if name in written:
+                        # Subagent file shared by several selected sessions
+                        continue
+
+                    data = source.read_bytes()
+                    if redact:
+                        data = _redact_jsonl(data)
+                    archive.writestr(name, data)
+                    written.add(name)
+                    session["files"].append({
+                        "name": name,
+                        "sha256": hashlib.sha256(data).hexdigest(),
+                        "size_bytes": len(data),
+                    })
+                manifest["sessions"].append(session)
+
+            archive.writestr(MANIFEST_NAME, json.dumps(manifest, indent=2, ensure_ascii=False))
+    except Exception:
+        tmp_path.unlink(missing_ok=True)
+        raise
+    commit_temp_file(tmp_path, output_path)
+
+    return {
+        "path": output_path,
+        "sessions": len(manifest["sessions"]),
+        "files": len(written),
+        "bytes": os.path.getsize(output_path),
+        "redacted": redact,
+        "missing_files": missing_files,
+    }
+
+
+def _validate_archive(archive: zipfile.ZipFile) -> dict[str, Any]:
+    """Check the manifest, member names and checksums; return the manifest."""
+    try:
+        manifest = json.loads(archive.read(MANIFEST_NAME))
+    except KeyError:
+        raise ValueError(f"Not a session archive: {MANIFEST_NAME} is missing")
+    except json.JSONDecodeError as e:
+        raise ValueError(f"Invalid {MANIFEST_NAME}: {e}")
+
+    if manifest.get("format") != ARCHIVE_FORMAT:
+        raise ValueError(f"Not a session archive: unexpected format {manifest.get('format')!r}")
+    if not isinstance(manifest.get("version"), int) or manifest["version"] > ARCHIVE_VERSION:
+        raise ValueError(f"Unsupported session archive version: {manifest.get('version')}")
+
+    if sum(info.file_size for info in archive.infolist()) > MAX_IMPORT_BYTES:
+        raise ValueError("Session archive is too large to import")
+
+    for session in manifest.get("sessions", []):
+        for file in session.get("files", []):
+            name = file.get("name", "")
+            parts = Path(name).parts
+            if not name.endswith(".jsonl") or Path(name).is_absolute() or ".." in parts:
+                raise ValueError(f"Unsafe file name in session archive: {name!r}")
+            try:
+                data = archive.read(name)
+            except KeyError:
+                raise ValueError(f"Session archive is missing {name}")
+            if hashlib.sha256(data).hexdigest() != file.get("sha256"):
+                raise ValueError(f"Checksum mismatch for {name}")
+    return manifest
+
+
+def import_session_archive(
+    conn: sqlite3.Connection,
+    archive_path: str,
+    target_project: str | None = None,
+    imports_dir: str = IMPORTS_DIR,
+    projects_json_path: str = PROJECTS_JSON_PATH
+) -> dict[str, Any]:
+    """
+    Validate, deduplicate and ingest a session archive.
+
+    Args:
+        conn: Database connection
+        archive_path: Path of the zip archive (see export_session_archive)
+        target_project: Project ID to import into; created if it does not
+            exist (default: a new "imported-<archive name>" project)
+        imports_dir: Directory the archive's files are extracted to
+        projects_json_path: Path to projects JSON file
+
+    Returns:
+        {"project_id", "created_project", "sessions", "files",
+         "messages", "duplicates", "redacted"}
+
+    Raises:
+        ValueError: If the archive is invalid, tampered with or too large
+    """
+    from command_center.cache.incremental_update import parse_file
+    from command_center.pricing_history import load_price_book
+
+    try:
+        archive = zipfile.ZipFile(archive_path)
+    except (OSError, zipfile.BadZipFile) as e:
+        raise ValueError(f"Cannot open session archive {archive_path}: {e}")
+
+    with archive:
+        manifest = _validate_archive(archive)
+        digest = hashlib.sha256(Path(archive_path).read_bytes()).hexdigest()[:16]
+        project_id = target_project or f"imported-{Path(archive_path).stem}"
+        if project_id == "unknown":
+            raise ValueError("Choose a project to import into")
+
+        extract_dir = Path(imports_dir) / digest
+        extracted = []
+        for session in manifest.get("sessions", []):
+            for file in session.get("files", []):
+                destination = extract_dir / file["name"]
+                if not destination.exists():
+                    destination.parent.mkdir(parents=True, exist_ok=True)
+                    destination.write_bytes(archive.read(file["name"]))
+                extracted.append(str(destination))
+
+    price_book = load_price_book(conn)
+    entries = []
+    for path in dict.fromkeys(extracted):
+        parsed = parse_file(path, price_book)
+        for entry in parsed.entries:
+            entry.project_id = project_id
+            entry.config_dir = None
+            entry.origin = "imported"
+        entries.extend(parsed.entries)
+
+    before = conn.execute("SELECT COUNT(*) FROM message_entries").fetchone()[0]
+    insert_message_entries(conn, entries)
+    inserted = conn.execute("SELECT COUNT(*) FROM message_entries").fetchone()[0] - before
+
+    projects = load_projects_json(projects_json_path)
+    # Nothing new (e.g. re-importing own sessions): don't create an empty project
+    created = inserted > 0 and project_id not in projects
+    if created:
+        projects = auto_discover_project(projects, project_id, projects_json_path)
+        projects[project_id]["name"] = f"Imported: {Path(archive_path).stem}"
+        projects[project_id]["absolute_path"] = str(extract_dir)
+        projects[project_id]["imported"] = True
+        save_projects_json(projects, projects_json_path)
+
+    return {
+        "project_id": project_id,
+        "created_project": created,
+        "sessions": len(manifest.get("sessions", [])),
+        "files": len(set(extracted)),
+        "messages": inserted,
+        "duplicates": len(entries) - inserted,
+        "redacted": bool(manifest.get("redacted")),
+    }
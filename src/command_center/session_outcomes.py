@@ -0,0 +1,288 @@
+"""
+Rule-based session outcome classification.
+
+An optional pass reads each session's JSONL files and labels the session by
+how it ended, walking back from the last event:
+
+- ``user_interrupt``: the user interrupted the final request
+- ``tool_error``: the last tool call failed and nothing succeeded after it
+- ``commit``: a successful ``git commit``
+- ``test_pass``: a successful test run (pytest, cargo test, npm test, ...)
+- ``other``: anything else (e.g. the session ended with a plain answer)
+
+A successful tool call after an error counts as recovery, so the walk keeps
+looking for a commit or test run. Independently of the outcome, a session
+is counted as *shipped* if it made any successful commit.
+
+Labels are stored in ``session_outcomes`` with a signature of the session's
+files, so later passes only re-read sessions whose files changed.
+"""
+from __future__ import annotations
+
+import json
+import os
+import re
+import sqlite3
+from datetime import datetime
+from typing import Any, Iterator, Optional
+
+OUTCOMES = ("commit", "test_pass", "tool_error", "user_interrupt", "other")
+
+INTERRUPT_MARKER = "[Request interrupted by user"
+
+COMMIT_PATTERN = re.compile(r"\bgit\s+(?:-C\s+\S+\s+)?commit\b")
+TEST_COMMAND_PATTERN = re.compile(
+    r"\b(?:pytest|cargo\s+(?:test|nextest)|(?:npm|pnpm|yarn|bun)\s+(?:run\s+)?test|go\s+test"
+    r"|jest|vitest|mvn\s+(?:\S+\s+)*test|gradlew?\s+(?:\S+\s+)*test|make\s+test|tox|unittest)\b"
+)
+TEST_FAILURE_PATTERN = re.compile(r"\b[1-9]\d*\s+(?:failed|failing|failures?)\b|\bFAILED\b|\bFAIL\b")
+
+# Characters of the deciding command kept as evidence
+MAX_EVIDENCE_CHARS = 200
+
+
+def _text(content: Any) -> str:
+    """Plain text of a message or tool result content (string or block list)."""
+    if isinstance(content, str):
+        return content
+    if isinstance(content, list):
+        return "\n".join(
+            block.get("text", "") for block in content
+            if isinstance(block, dict) and block.get("type") == "text"
+        )
+    return ""
+
+
+def _session_events(paths: list[str], session_id: str) -> Iterator[dict[str, Any]]:
+    """
+    Interrupts and tool results of a session's main thread, in file order.
+
+    Yields {"kind": "interrupt"} or {"kind": "tool_result", "error": bool, "command": str, "output": str}.
+    """
+    commands: dict[str, str] = {}
+    for path in paths:
+        with open(path, "r", encoding="utf-8", errors="replace") as f:
+            for line in f:
+                try:
+                    entry = json.loads(line)
+                except ValueError:
+                    continue
+                if not isinstance(entry, dict) or entry.get("isSidechain"):
+                    continue
+                if entry.get("sessionId", session_id) != session_id:
+                    continue
+                message = entry.get("message")
+                if not isinstance(message, dict):
+                    continue
+                content = message.get("content")
+                if entry.get("type") == "assistant" and isinstance(content, list):
+                    for block in content:
+                        if isinstance(block, dict) and block.get("type") == "tool_use":
+                            tool_input = block.get("input") if isinstance(block.get("input"), dict) else {}
+                            commands[block.get("id", "")] = str(tool_input.get("command") or block.get("name", ""))
+                elif entry.get("type") == "user":
+                    if INTERRUPT_MARKER in _text(content):
+                        yield {"kind": "interrupt"}
+                    if isinstance(content, list):
+                        for block in content:
+                            if isinstance(block, dict) and block.get("type") == "tool_result":
+                                yield {
+                                    "kind": "tool_result",
+                                    "error": bool(block.get("is_error")),
+                                    "command": commands.get(block.get("tool_use_id", ""), ""),
+                                    "output": _text(block.get("content")),
+                                }
+
+
+def _is_commit(event: dict[str, Any]) -> bool:
+    return not event["error"] and bool(COMMIT_PATTERN.search(event["command"]))
+
+
+def _is_test_pass(event: dict[str, Any]) -> bool:
+    return (
+        not event["error"]
+        and bool(TEST_COMMAND_PATTERN.search(event["command"]))
+        and not TEST_FAILURE_PATTERN.search(event["output"])
+    )
+
+
+def classify_events(events: list[dict[str, Any]]) -> dict[str, Any]:
+    """
+    Outcome of a session from its events (see module docstring).
+
+    Returns:
+        {"outcome": "commit", "committed": True, "evidence": "git commit -m ..."}
+    """
+    committed = any(e["kind"] == "tool_result" and _is_commit(e) for e in events)
+    recovered = False
+    for event in reversed(events):
+        if event["kind"] == "interrupt":
+            if not recovered:
+                return {"outcome": "user_interrupt", "committed": committed, "evidence": INTERRUPT_MARKER + "]"}
+            continue
+        if event["error"]:
+            if not recovered:
+                return {"outcome": "tool_error", "committed": committed,
+                        "evidence": event["command"][:MAX_EVIDENCE_CHARS]}
+            continue
+        if _is_commit(event):
+            return {"outcome": "commit", "committed": committed, "evidence": event["command"][:MAX_EVIDENCE_CHARS]}
+        if _is_test_pass(event):
+            return {"outcome": "test_pass", "committed": committed, "evidence": event["command"][:MAX_EVIDENCE_CHARS]}
+        recovered = True
+    return {"outcome": "other", "committed": committed, "evidence": None}
+
+
+def files_signature(paths: list[str]) -> Optional[str]:
+    """size:mtime of each file, or None if one is missing."""
+    parts = []
+    for path in sorted(paths):
+        try:
+            stat = os.stat(path)
+        except OSError:
+            return None
+        parts.append(f"{stat.st_size}:{stat.st_mtime_ns}")
+    return "|".join(parts)
+
+
+def session_files(conn: sqlite3.Connection, date_from: str, date_to: str) -> dict[str, list[str]]:
+    """JSONL files of the local sessions active in a date range, in first-message order."""
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT session_id, source_file
+        FROM message_entries
+        WHERE date BETWEEN ? AND ? AND session_id IS NOT NULL AND origin = 'local'
+        GROUP BY session_id, source_file
+        ORDER BY session_id, MIN(timestamp)
+    """, (date_from, date_to))
+    sessions: dict[str, list[str]] = {}
+    for session_id, source_file in cursor.fetchall():
+        sessions.setdefault(session_id, []).append(source_file)
+    return sessions
+
+
+def classify_sessions(
+    conn: sqlite3.Connection,
+    date_from: str,
+    date_to: str,
+    force: bool = False,
+) -> dict[str, int]:
+    """
+    Classify sessions active in a date range whose files changed since the last pass.
+
+    Args:
+        conn: Database connection
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        force: Re-read every session, even if its files are unchanged
+
+    Returns:
+        {"classified": 12, "unchanged": 140, "missing_files": 3}
+    """
+    cursor = conn.cursor()
+    sessions = session_files(conn, date_from, date_to)
+    known = dict(conn.execute("SELECT session_id, files_signature FROM session_outcomes").fetchall())
+
+    stats = {"classified": 0, "unchanged": 0, "missing_files": 0}
+    now = datetime.now().astimezone().isoformat()
+    rows = []
+    for session_id, paths in sessions.items():
+        signature = files_signature(paths)
+        if signature is None:
+            stats["missing_files"] += 1
+            continue
+        if not force and known.get(session_id) == signature:
+            stats["unchanged"] += 1
+            continue
+        try:
+            result = classify_events(list(_session_events(paths, session_id)))
+        except OSError:
+            stats["missing_files"] += 1
+            continue
+        rows.append((session_id, result["outcome"], int(result["committed"]), result["evidence"], signature, now))
+        stats["classified"] += 1
+
+    cursor.executemany("""
+        INSERT OR REPLACE INTO session_outcomes
+        (session_id, outcome, committed, evidence, files_signature, classified_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+    """, rows)
+    conn.commit()
+    return stats
+
+
+def query_outcome_stats(
+    conn: sqlite3.Connection,
+    date_from: str,
+    date_to: str,
+    project_id: Optional[str] = None,
+) -> dict[str, Any]:
+    """
+    Aggregate classified outcomes of sessions started in a date range.
+
+    Args:
+        conn: Database connection
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Only sessions of this project
+
+    Returns:
+        {
+            "sessions": 150, "classified": 147,
+            "outcomes": [{"outcome": "commit", "sessions": 60, "share": 0.408}, ...],
+            "shipped": {"sessions": 72, "share": 0.49},
+            "by_project": [{"project_id", "sessions", "shipped", "shipped_share",
+                            "outcomes": {"commit": 20, ...}}, ...]
+        }
+        Shares are fractions of classified sessions (null if none are classified).
+    """
+    project_filter = "AND project_id = ?" if project_id else ""
+    params: list = ([project_id] if project_id else []) + [date_from, date_to]
+    cursor = conn.cursor()
+    cursor.execute(f"""
+        WITH sessions AS (
+            SELECT session_id, MIN(project_id) AS project_id
+            FROM message_entries
+            WHERE session_id IS NOT NULL AND origin = 'local' {project_filter}
+            GROUP BY session_id
+            HAVING MIN(date) BETWEEN ? AND ?
+        )
+        SELECT s.project_id, o.outcome, o.committed
+        FROM sessions s
+        LEFT JOIN session_outcomes o ON o.session_id = s.session_id
+    """, params)
+    rows = cursor.fetchall()
+
+    def share(count: int, total: int) -> Optional[float]:
+        return round(count / total, 3) if total else None
+
+    classified_rows = [row for row in rows if row[1] is not None]
+    classified = len(classified_rows)
+    shipped = sum(row[2] for row in classified_rows)
+
+    projects: dict[str, dict[str, Any]] = {}
+    for project, outcome, committed in classified_rows:
+        entry = projects.setdefault(project, {
+            "project_id": project, "sessions": 0, "shipped": 0,
+            "outcomes": {name: 0 for name in OUTCOMES},
+        })
+        entry["sessions"] += 1
+        entry["shipped"] += committed
+        entry["outcomes"][outcome] = entry["outcomes"].get(outcome, 0) + 1
+    for entry in projects.values():
+        entry["shipped_share"] = share(entry["shipped"], entry["sessions"])
+
+    return {
+        "sessions": len(rows),
+        "classified": classified,
+        "outcomes": [
+            {
+                "outcome": name,
+                "sessions": sum(1 for row in classified_rows if row[1] == name),
+                "share": share(sum(1 for row in classified_rows if row[1] == name), classified),
+            }
+            for name in OUTCOMES
+        ],
+        "shipped": {"sessions": shipped, "share": share(shipped, classified)},
+        "by_project": sorted(projects.values(), key=lambda p: p["sessions"], reverse=True),
+    }
@@ -0,0 +1,49 @@
+"""
+App settings shared with the desktop app.
+
+The desktop app writes ``command-center-settings.json`` next to the database
+(timezone, week start and billing anchor day); the backend only reads it.
+Settings are looked up in the directory of the config file a feature uses,
+so budgets and accounts stored elsewhere (e.g. in tests) get defaults.
+"""
+from __future__ import annotations
+
+import json
+import os
+from typing import Any
+
+
+SETTINGS_FILE_NAME = "command-center-settings.json"
+
+# Default location for the shared settings JSON
+SETTINGS_JSON_PATH = os.path.expanduser(f"~/.claude/db/{SETTINGS_FILE_NAME}")
+
+# Billing cycles start on this day of the month unless configured
+DEFAULT_BILLING_ANCHOR_DAY = 1
+
+
+def load_settings(json_path: str = SETTINGS_JSON_PATH) -> dict[str, Any]:
+    """Load settings; a missing or unreadable file yields {}."""
+    try:
+        with open(json_path, "r", encoding="utf-8") as f:
+            loaded = json.load(f)
+    except (OSError, json.JSONDecodeError):
+        return {}
+    return loaded if isinstance(loaded, dict) else {}
+
+
+def settings_path_near(config_path: str) -> str:
+    """Settings file in the same directory as another config file."""
+    return os.path.join(os.path.dirname(config_path), SETTINGS_FILE_NAME)
+
+
+def billing_anchor_day(json_path: str = SETTINGS_JSON_PATH) -> int:
+    """
+    Day of the month the subscription renews (1-31).
+
+    Returns DEFAULT_BILLING_ANCHOR_DAY (calendar months) if unset or invalid.
+    """
+    value = load_settings(json_path).get("billing_anchor_day")
+    if isinstance(value, int) and not isinstance(value, bool) and 1 <= value <= 31:
+        return value
+    return DEFAULT_BILLING_ANCHOR_DAY
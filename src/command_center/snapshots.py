@@ -0,0 +1,237 @@
+"""
+Immutable report snapshots.
+
+A snapshot freezes the computed statistics for a date range (totals, daily
+activity, timeline, models, hourly profile) so reported numbers stay stable
+even when old data is later re-ingested or re-priced. Snapshots can be
+listed, read back, and diffed against each other or against live data.
+"""
+from __future__ import annotations
+
+import json
+import sqlite3
+from datetime import datetime
+from typing import Any, Optional
+
+from command_center import __version__ as package_version
+from command_center.database.queries import (
+    query_totals,
+    query_daily_stats,
+    query_timeline_data,
+    query_model_distribution,
+    query_hourly_profile,
+)
+
+
+def compute_snapshot_payload(
+    conn: sqlite3.Connection,
+    date_from: str,
+    date_to: str,
+    project_id: Optional[str] = None
+) -> dict[str, Any]:
+    """Compute the statistics that get frozen into a snapshot."""
+    return {
+        "totals": query_totals(conn, date_from, date_to, project_id),
+        "daily_activity": query_daily_stats(conn, date_from, date_to, project_id),
+        "timeline": query_timeline_data(conn, date_from, date_to, "month", project_id),
+        "model_distribution": query_model_distribution(conn, date_from, date_to, project_id),
+        "hourly_profile": query_hourly_profile(conn, date_from, date_to, project_id),
+    }
+
+
+def _row_to_snapshot(row: tuple, include_payload: bool) -> dict[str, Any]:
+    snapshot = {
+        "snapshot_id": row[0],
+        "name": row[1],
+        "range": {"from": row[2], "to": row[3]},
+        "project_id": row[4],
+        "created_at": row[5],
+        "app_version": row[6],
+    }
+    payload = json.loads(row[7])
+    if include_payload:
+        snapshot["data"] = payload
+    else:
+        snapshot["totals"] = payload.get("totals", {})
+    return snapshot
+
+
+def create_snapshot(
+    conn: sqlite3.Connection,
+    name: str,
+    date_from: str,
+    date_to: str,
+    project_id: Optional[str] = None
+) -> dict[str, Any]:
+    """
+    Freeze computed statistics for a range into a named snapshot.
+
+    Raises:
+        ValueError: If the name is empty, too long, or already used
+    """
+    name = (name or "").strip()
+    if not name:
+        raise ValueError("Snapshot name is required")
+    if len(name) > 100:
+        raise ValueError("Snapshot name cannot exceed 100 characters")
+
+    payload = compute_snapshot_payload(conn, date_from, date_to, project_id)
+    cursor = conn.cursor()
+    try:
+        cursor.execute("""
+            INSERT INTO report_snapshots
+            (name, date_from, date_to, project_id, created_at, app_version, payload)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+        """, (
+            name, date_from, date_to, project_id,
+            datetime.now().astimezone().isoformat(), package_version,
+            json.dumps(payload, ensure_ascii=False),
+        ))
+    except sqlite3.IntegrityError:
+        raise ValueError(f"Snapshot already exists: {name}")
+    conn.commit()
+
+    return get_snapshot(conn, str(cursor.lastrowid))
+
+
+def list_snapshots(conn: sqlite3.Connection) -> list[dict[str, Any]]:
+    """List snapshots (newest first) with their frozen totals."""
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT snapshot_id, name, date_from, date_to, project_id, created_at, app_version, payload
+        FROM report_snapshots
+        ORDER BY created_at DESC
+    """)
+    return [_row_to_snapshot(row, include_payload=False) for row in cursor.fetchall()]
+
+
+def get_snapshot(conn: sqlite3.Connection, snapshot: str) -> dict[str, Any]:
+    """
+    Get a snapshot with its full frozen data.
+
+    Args:
+        snapshot: Snapshot id or name
+
+    Raises:
+        ValueError: If snapshot not found
+    """
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT snapshot_id, name, date_from, date_to, project_id, created_at, app_version, payload
+        FROM report_snapshots
+        WHERE name = ? OR CAST(snapshot_id AS TEXT) = ?
+        ORDER BY name = ? DESC
+        LIMIT 1
+    """, (snapshot, snapshot, snapshot))
+    row = cursor.fetchone()
+    if not row:
+        raise ValueError(f"Snapshot not found: {snapshot}")
+    return _row_to_snapshot(row, include_payload=True)
+
+
+def delete_snapshot(conn: sqlite3.Connection, snapshot: str) -> dict[str, Any]:
+    """Delete a snapshot by id or name."""
+    existing = get_snapshot(conn, snapshot)
+    conn.execute("DELETE FROM report_snapshots WHERE snapshot_id = ?", (existing["snapshot_id"],))
+    conn.commit()
+    return {"deleted": existing["snapshot_id"], "name": existing["name"]}
+
+
+def _delta(before: Any, after: Any) -> dict[str, Any]:
+    before = before or 0
+    after = after or 0
+    change = after - before
+    percent = round(change / before * 100, 2) if before else None
+    if isinstance(change, float):
+        change = round(change, 4)
+    return {"before": before, "after": after, "change": change, "percent": percent}
+
+
+def diff_payloads(base: dict[str, Any], target: dict[str, Any]) -> dict[str, Any]:
+    """
+    Diff two snapshot payloads.
+
+    Returns:
+        {
+            "totals": {metric: {before, after, change, percent}},
+            "models": [{model, tokens: {...}, cost: {...}, messages: {...}}],
+            "daily_activity": [{date, before, after, change}]  # only changed days
+            "changed": bool
+        }
+    """
+    totals = {}
+    for key, before in base.get("totals", {}).items():
+        after = target.get("totals", {}).get(key)
+        if isinstance(before, (int, float)) or isinstance(after, (int, float)):
+            totals[key] = _delta(before, after)
+
+    base_models = {m["model"]: m for m in base.get("model_distribution", [])}
+    target_models = {m["model"]: m for m in target.get("model_distribution", [])}
+    models = []
+    for model in sorted(set(base_models) | set(target_models)):
+        b = base_models.get(model, {})
+        t = target_models.get(model, {})
+        entry = {
+            "model": model,
+            "tokens": _delta(b.get("tokens"), t.get("tokens")),
+            "cost": _delta(b.get("cost"), t.get("cost")),
+            "messages": _delta(b.get("messages"), t.get("messages")),
+        }
+        if any(entry[k]["change"] for k in ("tokens", "cost", "messages")):
+            models.append(entry)
+
+    base_daily = base.get("daily_activity", {})
+    target_daily = target.get("daily_activity", {})
+    daily = []
+    for day in sorted(set(base_daily) | set(target_daily)):
+        before = base_daily.get(day, 0)
+        after = target_daily.get(day, 0)
+        if before != after:
+            daily.append({"date": day, "before": before, "after": after, "change": after - before})
+
+    changed = bool(models or daily or any(t["change"] for t in totals.values()))
+    return {"totals": totals, "models": models, "daily_activity": daily, "changed": changed}
+
+
+def diff_snapshots(
+    conn: sqlite3.Connection,
+    base: str,
+    target: Optional[str] = None
+) -> dict[str, Any]:
+    """
+    Diff a snapshot against another snapshot, or against live data.
+
+    Args:
+        base: Snapshot id or name
+        target: Snapshot id or name; None recomputes the base range from live data
+
+    Returns:
+        {"base": {...meta...}, "target": {...meta...}, "diff": {...}}
+    """
+    base_snapshot = get_snapshot(conn, base)
+    base_data = base_snapshot.pop("data")
+
+    if target:
+        target_snapshot = get_snapshot(conn, target)
+        target_data = target_snapshot.pop("data")
+    else:
+        target_data = compute_snapshot_payload(
+            conn,
+            base_snapshot["range"]["from"],
+            base_snapshot["range"]["to"],
+            base_snapshot["project_id"],
+        )
+        target_snapshot = {
+            "snapshot_id": None,
+            "name": "live",
+            "range": base_snapshot["range"],
+            "project_id": base_snapshot["project_id"],
+            "created_at": datetime.now().astimezone().isoformat(),
+            "app_version": package_version,
+        }
+
+    return {
+        "base": base_snapshot,
+        "target": target_snapshot,
+        "diff": diff_payloads(base_data, target_data),
+    }
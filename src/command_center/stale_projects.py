@@ -0,0 +1,140 @@
+"""
+Inactive-project detection and cleanup suggestions.
+
+A project is stale when its last message (or, for projects without usage
+in the database, its ``last_seen`` metadata) is older than the inactivity
+threshold. Each stale project is listed with its footprint: database rows,
+the raw session files under ``~/.claude/projects`` and the parsed-record
+mirror files kept next to the database.
+
+Visible, unpinned stale projects get an ``archive`` suggestion; archiving
+(``archive_projects``) only hides them from the project selector, so their
+usage stays in every total and nothing is deleted.
+"""
+from __future__ import annotations
+
+import os
+import sqlite3
+from datetime import datetime
+from typing import Any, Optional
+
+from command_center.cache.mirror import MIRROR_DIR, mirror_path
+from command_center.utils.date_helpers import parse_iso_timestamp
+
+DEFAULT_INACTIVE_DAYS = 90
+
+
+def _file_size(path: Any) -> int:
+    try:
+        return os.path.getsize(path)
+    except OSError:
+        return 0
+
+
+def query_stale_projects(
+    conn: sqlite3.Connection,
+    projects: dict[str, dict],
+    inactive_days: int = DEFAULT_INACTIVE_DAYS,
+    now: Optional[datetime] = None,
+    mirror_dir: str = MIRROR_DIR
+) -> dict[str, Any]:
+    """
+    Projects without activity for at least ``inactive_days`` days.
+
+    Args:
+        conn: Database connection
+        projects: Project metadata (projects.json content)
+        inactive_days: Inactivity threshold in days
+        now: Reference time (default: now, local timezone)
+        mirror_dir: Mirror directory measured for the footprint
+
+    Returns:
+        {
+            "inactive_days": 90,
+            "projects": [
+                {
+                    "project_id", "name", "visible", "pinned",
+                    "last_activity": "2025-01-05T11:00:00+01:00" | None,
+                    "days_inactive": 214,
+                    "messages", "sessions", "cost",
+                    "source_files", "source_bytes", "mirror_bytes",
+                    "suggestion": "archive" | None, "reason": "No activity for 214 days"
+                },
+                ...
+            ],
+            "totals": {"projects", "suggested", "source_bytes", "mirror_bytes"}
+        }
+        Projects are sorted by inactivity, longest first.
+
+    Raises:
+        ValueError: If inactive_days is negative
+    """
+    if inactive_days < 0:
+        raise ValueError("inactive_days must not be negative")
+    now = now or datetime.now().astimezone()
+
+    cursor = conn.cursor()
+    cursor.execute("""
+        SELECT project_id, MAX(timestamp_local), COUNT(*), COUNT(DISTINCT session_id),
+               SUM(COALESCE(cost_usd, 0))
+        FROM message_entries
+        WHERE project_id IS NOT NULL
+        GROUP BY project_id
+    """)
+    usage = {row[0]: row[1:] for row in cursor.fetchall()}
+
+    cursor.execute("""
+        SELECT DISTINCT project_id, source_file
+        FROM message_entries
+        WHERE project_id IS NOT NULL AND origin = 'local'
+    """)
+    source_files: dict[str, list[str]] = {}
+    for project_id, source_file in cursor.fetchall():
+        source_files.setdefault(project_id, []).append(source_file)
+
+    stale = []
+    for project_id in sorted(set(projects) | set(usage)):
+        meta = projects.get(project_id, {})
+        last_activity, messages, sessions, cost = usage.get(project_id, (None, 0, 0, 0.0))
+        last_activity = last_activity or meta.get("last_seen")
+        last_at = parse_iso_timestamp(last_activity) if isinstance(last_activity, str) else None
+        if last_at is None:
+            continue
+        if last_at.tzinfo is None:
+            last_at = last_at.astimezone()
+        days_inactive = (now - last_at).days
+        if days_inactive < inactive_days:
+            continue
+
+        files = source_files.get(project_id, [])
+        visible = meta.get("visible", True)
+        pinned = bool(meta.get("pinned", False))
+        suggest = project_id in projects and visible and not pinned
+        stale.append({
+            "project_id": project_id,
+            "name": meta.get("name", project_id),
+            "visible": visible,
+            "pinned": pinned,
+            "last_activity": last_activity,
+            "days_inactive": days_inactive,
+            "messages": messages,
+            "sessions": sessions,
+            "cost": round(cost or 0.0, 4),
+            "source_files": len(files),
+            "source_bytes": sum(_file_size(path) for path in files),
+            "mirror_bytes": sum(_file_size(mirror_path(path, mirror_dir)) for path in files),
+            "suggestion": "archive" if suggest else None,
+            "reason": f"No activity for {days_inactive} days",
+        })
+
+    stale.sort(key=lambda p: p["days_inactive"], reverse=True)
+    return {
+        "inactive_days": inactive_days,
+        "projects": stale,
+        "totals": {
+            "projects": len(stale),
+            "suggested": sum(1 for p in stale if p["suggestion"]),
+            "source_bytes": sum(p["source_bytes"] for p in stale),
+            "mirror_bytes": sum(p["mirror_bytes"] for p in stale),
+        },
+    }
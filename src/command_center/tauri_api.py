@@ -9,9 +9,14 @@ Usage:
     python -m command_center.tauri_api day --date 2025-06-15
     python -m command_center.tauri_api model --model claude-sonnet-4-20250514 --from 2025-01-01 --to 2025-12-31
     python -m command_center.tauri_api session --id SESSION_UUID
+    python -m command_center.tauri_api sessions --from 2025-01-01 --to 2025-12-31 --page-size 100
+    python -m command_center.tauri_api serve   # persistent worker, see api_worker
+    echo '{"args": ["day", "--date", "2025-06-15"]}' | python -m command_center.tauri_api --stdin-json
 """
 import argparse
 import json
+import os
+import platform
 import sys
 from datetime import datetime, timedelta
 from pathlib import Path
@@ -19,8 +24,10 @@ from typing import Literal
 
 from command_center import __version__ as package_version
 from command_center.database.connection import get_db_connection
-from command_center.database.schema import init_database
+from command_center.database.schema import init_database, get_schema_version, CURRENT_SCHEMA_VERSION
+from command_center.utils.date_helpers import month_bounds
 from command_center.database.queries import (
+    query_project_costs,
     query_daily_stats,
     query_timeline_data,
     query_model_distribution,
@@ -29,15 +36,139 @@ from command_center.database.queries import (
     query_data_range,
     query_totals,
     query_day_details,
+    query_aggregate_history,
     query_model_details,
     query_session_details,
+    query_sessions_page,
+    query_session_source_files,
+    query_metric_records,
     get_limit_events,
+    query_limit_impact,
+    query_mcp_usage,
+    query_hook_stats,
+    query_effort_breakdown,
     query_usage_stats,
 )
-from command_center.cache.incremental_update import perform_incremental_update
+from command_center.cache.incremental_update import perform_incremental_update, perform_partial_update
+from command_center.cache.ingest_lock import IngestLockedError, ingest_lock, read_lock
+from command_center.cache.quarantine import read_quarantined_lines
+from command_center.cache.refresh_status import track_refresh, get_refresh_status as query_refresh_status
 from command_center.aggregators.streak_calculator import calculate_streaks
-from command_center.visualization.png_generator import generate_usage_report_png
-from command_center.usage_accounts import fetch_latest_usage_accounts
+from command_center.visualization.png_generator import (
+    EXPORT_SCALES,
+    SOCIAL_FORMATS,
+    generate_social_card_png,
+    generate_usage_report_png,
+)
+from command_center.visualization.report_description import describe_usage_report
+from command_center.utils.atomic_write import write_bytes_atomic
+from command_center.usage_accounts import (
+    fetch_latest_usage_accounts,
+    load_account_metadata,
+    query_usage_account_totals,
+    update_usage_account_fields,
+    compare_usage_accounts,
+    subscription_cost_for_range,
+    usage_account_totals,
+)
+from command_center.claude_configs import (
+    detect_claude_accounts,
+    bind_config_dir,
+    query_account_message_totals,
+    query_account_usage,
+)
+from command_center.plan_detection import detect_plans
+from command_center.budget import roll_over_budget_periods, query_budget_history, set_monthly_budget
+from command_center.cost_allocation import (
+    load_cost_center_config,
+    set_cost_center as set_cost_center_config,
+    delete_cost_center as delete_cost_center_config,
+    query_cost_allocation,
+    export_cost_allocation as export_cost_allocation_file,
+)
+from command_center.pricing_history import (
+    list_price_versions,
+    record_price_version,
+    sync_price_versions,
+    reprice_history as reprice_message_history,
+    query_api_equivalent_cost,
+)
+from command_center.utils.pricing import ModelPricing
+from command_center.cache.mirror import mirror_status, clear_mirror
+from command_center import backup
+from command_center.config import CANVAS_HEIGHT, CANVAS_WIDTH, CLAUDE_DIRS, DB_PATH, MIRROR_ENABLED
+from command_center.hooks import (
+    run_lifecycle_hooks,
+    list_hooks,
+    set_hook as set_hook_config,
+    delete_hook as delete_hook_config,
+    test_hook as run_test_hook,
+)
+from command_center.data_merge import merge_source, register_merged_projects
+from command_center.calendar_overlay import (
+    daily_average,
+    excluded_days,
+    load_calendar_overlay,
+    overlay_for_range,
+    set_calendar_overlay as set_calendar_overlay_config,
+)
+from command_center.working_hours import (
+    load_working_hours,
+    query_out_of_hours_usage,
+    set_working_hours as set_working_hours_config,
+)
+from command_center.time_shift import (
+    list_timestamp_shifts,
+    parse_offset,
+    shift_timestamps,
+    undo_timestamp_shift,
+)
+from command_center.derived_metrics import (
+    METRIC_FUNCTIONS,
+    compute_derived_metrics,
+    load_derived_metrics,
+    query_metric_inputs,
+    set_derived_metric as set_derived_metric_config,
+    delete_derived_metric as delete_derived_metric_config,
+)
+from command_center.metrics_registry import list_available_metrics as list_registered_metrics, metric_names, query_metrics
+from command_center.session_outcomes import classify_sessions, query_outcome_stats, session_files
+from command_center.compactions import query_compaction_stats, query_session_compactions, scan_compactions
+from command_center.concurrency import DEFAULT_IDLE_GAP_MINUTES, query_concurrency_stats
+from command_center.workspaces import (
+    list_workspaces,
+    create_workspace as create_workspace_config,
+    delete_workspace as delete_workspace_config,
+    assign_project_to_workspace as assign_project_workspace,
+    workspace_project_ids,
+)
+from command_center.session_archive import export_session_archive, import_session_archive
+from command_center.audit_log import current_request_id, record_audit_event, read_audit_log
+from command_center.api_worker import STDIN_JSON_FLAG, read_stdin_request, serve as serve_worker
+from command_center.demo_data import (
+    DEFAULT_MONTHS as DEFAULT_DEMO_MONTHS,
+    DEFAULT_SEED as DEFAULT_DEMO_SEED,
+    DEMO_ENV,
+    DEMO_HOME_ENV,
+    demo_mode,
+    generate_demo_data as build_demo_data,
+)
+from command_center.sample_data import (
+    DEFAULT_DAYS as DEFAULT_SAMPLE_DAYS,
+    DEFAULT_INTENSITY as DEFAULT_SAMPLE_INTENSITY,
+    SAMPLE_INTENSITIES,
+    generate_sample_data as build_sample_data,
+)
+from command_center.result_stream import stream_result
+from command_center.benchmark import DEFAULT_SEED as DEFAULT_BENCHMARK_SEED, measure as measure_benchmark, run_benchmark
+from command_center.presentation import ReadOnlyModeError, blur_projects, blur_projects_enabled, read_only
+from command_center.snapshots import (
+    create_snapshot as create_report_snapshot,
+    list_snapshots,
+    get_snapshot as get_report_snapshot,
+    delete_snapshot as delete_report_snapshot,
+    diff_snapshots as diff_report_snapshots,
+)
 import base64
 
 
@@ -72,6 +203,21 @@ def get_app_version() -> str:
     return version
 
 
+def merge_account_totals(snapshot_totals: list[dict], message_totals: dict[str, dict]) -> list[dict]:
+    """
+    Merge per-account message totals (from bound config dirs) into
+    per-account cc_usage snapshot totals.
+
+    Accounts that only have one of the two sources are still included.
+    """
+    merged = {a["email"]: {**a} for a in snapshot_totals}
+    for email, totals in message_totals.items():
+        merged.setdefault(email, {"email": email})["usage"] = totals
+    for account in merged.values():
+        account.setdefault("usage", None)
+    return sorted(merged.values(), key=lambda a: a["email"])
+
+
 def get_previous_period(date_from: str, date_to: str) -> tuple[str, str]:
     """
     Calculate the previous period with same duration as current period.
@@ -99,12 +245,25 @@ def get_previous_period(date_from: str, date_to: str) -> tuple[str, str]:
     )
 
 
+# Version of the JSON API (subcommands, arguments, response shapes) that the
+# desktop app checks in its startup handshake (`version`). Bump it on changes
+# an app or package of the previous version cannot handle (2: `export-png
+# --output`), together with EXPECTED_API_VERSION in
+# desktop/src-tauri/src/handshake.rs.
+API_VERSION = 2
+
+# Sections of the dashboard bundle that can be requested separately
+DASHBOARD_SECTIONS = ("totals", "timeline", "models", "hourly", "sessions")
+
+
 def get_dashboard_bundle(
     date_from: str,
     date_to: str,
     refresh: bool,
     granularity: Literal["month", "week", "day", "hour"],
-    project_id: str | None = None
+    project_id: str | None = None,
+    sections: list[str] | None = None,
+    workspace_id: str | None = None
 ) -> dict:
     """
     Generate complete dashboard JSON bundle.
@@ -112,401 +271,3094 @@ def get_dashboard_bundle(
     Args:
         date_from: Start date (YYYY-MM-DD)
         date_to: End date (YYYY-MM-DD)
-        refresh: If True, perform incremental update before querying
+        refresh: If True, perform incremental update before querying (skipped with
+            meta.refresh_blocked set if another process holds the ingestion lock)
         granularity: Timeline grouping - 'month', 'week', 'day', or 'hour'
         project_id: Optional project filter
+        sections: DASHBOARD_SECTIONS to compute (default: all); keys of
+            sections that were not requested are left out of the bundle
+        workspace_id: Optional workspace filter (all projects of the workspace;
+            combined with project_id, the project must belong to the workspace)
 
     Returns:
         Complete dashboard data bundle as dict
     """
+    wanted = set(sections) if sections else set(DASHBOARD_SECTIONS)
+    unknown = wanted - set(DASHBOARD_SECTIONS)
+    if unknown:
+        raise ValueError(
+            f"Unknown dashboard section: {', '.join(sorted(unknown))} "
+            f"(expected {', '.join(DASHBOARD_SECTIONS)})"
+        )
+
+    # Project filter: one project, or every project of the workspace
+    scope: str | list[str] | None = project_id
+    if workspace_id:
+        members = workspace_project_ids(workspace_id)
+        if project_id and project_id not in members:
+            raise ValueError(f"Project {project_id} is not in workspace {workspace_id}")
+        scope = project_id or members
+
     with get_db_connection() as conn:
         init_database(conn)
 
         # Optionally refresh data
         updated_files = 0
+        ingest_stats: dict = {}
+        hook_runs: list[dict] = []
+        refresh_blocked = None
         if refresh:
-            updated_files = perform_incremental_update(conn, force_rescan=False, verbose=False)
-
-        # Query all data for current period
-        totals = query_totals(conn, date_from, date_to, project_id)
-        daily_activity = query_daily_stats(conn, date_from, date_to, project_id)
-        timeline_data = query_timeline_data(conn, date_from, date_to, granularity, project_id)
-        model_distribution = query_model_distribution(conn, date_from, date_to, project_id)
-        hourly_profile = query_hourly_profile(conn, date_from, date_to, project_id)
-        recent_sessions = query_recent_sessions(conn, date_from, date_to, limit=50, project_id=project_id)
-
-        # Calculate streaks
-        max_streak, current_streak = calculate_streaks(daily_activity)
-
-        # Query previous period for trend calculation
-        prev_from, prev_to = get_previous_period(date_from, date_to)
-        prev_totals = query_totals(conn, prev_from, prev_to, project_id)
-
-        # Calculate trends
-        trends = {
-            "messages": calculate_trend(totals["messages"], prev_totals["messages"]),
-            "sessions": calculate_trend(totals["sessions"], prev_totals["sessions"]),
-            "tokens": calculate_trend(totals["tokens"], prev_totals["tokens"]),
-            "cost": calculate_trend(totals["cost"], prev_totals["cost"]),
-        }
-
-        data_range = query_data_range(conn, project_id)
-
-        heatmap_to = datetime.now().date()
-        heatmap_from = heatmap_to - timedelta(days=364)
-        heatmap_from_str = heatmap_from.strftime('%Y-%m-%d')
-        heatmap_to_str = heatmap_to.strftime('%Y-%m-%d')
-        heatmap_activity = query_daily_stats(conn, heatmap_from_str, heatmap_to_str, project_id)
-
-        # Build response
-        return {
+            # Another writer (e.g. a second machine on a shared data dir) is
+            # ingesting: serve the data as it is and report why it is not refreshed
+            try:
+                with ingest_lock(), track_refresh("full") as tracker:
+                    updated_files = perform_incremental_update(
+                        conn, force_rescan=False, verbose=False, stats=ingest_stats,
+                        on_progress=tracker.progress
+                    )
+                    tracker.updated_files = updated_files
+            except IngestLockedError as e:
+                refresh_blocked = str(e)
+
+        # Freeze any budget months that rolled over since the last call
+        roll_over_budget_periods(conn)
+
+        if refresh and refresh_blocked is None:
+            hook_runs = _run_lifecycle_hooks(conn, {"mode": "full", "updated_files": updated_files})
+
+        bundle: dict = {
             "range": {
                 "from": date_from,
                 "to": date_to
             },
-            "totals": {
+        }
+
+        daily_activity = {}
+        if wanted & {"totals", "timeline"}:
+            # Also needed for streaks
+            daily_activity = query_daily_stats(conn, date_from, date_to, scope)
+
+        # Vacation/holiday days: left out of averages and streaks, shaded in timelines
+        calendar = load_calendar_overlay()
+
+        # API list-price equivalent vs subscription (not attributable per project)
+        api_equivalent = None
+        if wanted & {"totals", "models"}:
+            api_equivalent = query_api_equivalent_cost(conn, date_from, date_to, scope)
+
+        if "totals" in wanted:
+            totals = query_totals(conn, date_from, date_to, scope)
+            max_streak, current_streak = calculate_streaks(daily_activity, excluded_days(calendar))
+
+            # Query previous period for trend calculation
+            prev_from, prev_to = get_previous_period(date_from, date_to)
+            prev_totals = query_totals(conn, prev_from, prev_to, scope)
+
+            subscription_cost = None if scope else subscription_cost_for_range(date_from, date_to)["cost"]
+            subscription_savings = (
+                round(api_equivalent["total"] - subscription_cost, 2)
+                if subscription_cost is not None else None
+            )
+
+            bundle["totals"] = {
                 "messages": totals["messages"],
                 "sessions": totals["sessions"],
                 "tokens": totals["tokens"],
                 "input_tokens": totals["input_tokens"],
                 "output_tokens": totals["output_tokens"],
                 "cost": totals["cost"],
+                "api_equivalent_cost": api_equivalent["total"],
+                "subscription_cost": subscription_cost,
+                "subscription_savings": subscription_savings,
                 "cache_read": totals["cache_read"],
                 "cache_write": totals["cache_write"],
                 "current_streak": current_streak,
                 "max_streak": max_streak,
-                "first_session_date": totals["first_session_date"]
-            },
-            "trends": trends,
-            "daily_activity": daily_activity,
-            "timeline": {
+                "first_session_date": totals["first_session_date"],
+                "daily_average": daily_average(
+                    query_timeline_data(conn, date_from, date_to, "day", scope),
+                    date_from, date_to, excluded_days(calendar, date_from, date_to)
+                ),
+            }
+            bundle["trends"] = {
+                "messages": calculate_trend(totals["messages"], prev_totals["messages"]),
+                "sessions": calculate_trend(totals["sessions"], prev_totals["sessions"]),
+                "tokens": calculate_trend(totals["tokens"], prev_totals["tokens"]),
+                "cost": calculate_trend(totals["cost"], prev_totals["cost"]),
+            }
+            bundle["usage_accounts"] = merge_account_totals(
+                query_usage_account_totals(date_from, date_to),
+                query_account_message_totals(conn, date_from, date_to, scope),
+            )
+
+        if "timeline" in wanted:
+            timeline_data = query_timeline_data(conn, date_from, date_to, granularity, scope)
+
+            # User-defined formulas, evaluated per timeline period and per day
+            derived_metrics = load_derived_metrics()
+            daily_derived: dict = {}
+            if derived_metrics:
+                timeline_derived = compute_derived_metrics(
+                    derived_metrics, query_metric_inputs(conn, date_from, date_to, granularity, scope)
+                )
+                for item in timeline_data:
+                    item["derived"] = timeline_derived.get(item["period"], {})
+                daily_derived = compute_derived_metrics(
+                    derived_metrics, query_metric_inputs(conn, date_from, date_to, "day", scope)
+                )
+
+            heatmap_to = datetime.now().date()
+            heatmap_from = heatmap_to - timedelta(days=364)
+            heatmap_from_str = heatmap_from.strftime('%Y-%m-%d')
+            heatmap_to_str = heatmap_to.strftime('%Y-%m-%d')
+            heatmap_activity = query_daily_stats(conn, heatmap_from_str, heatmap_to_str, scope)
+
+            bundle["daily_activity"] = daily_activity
+            bundle["daily_derived"] = daily_derived
+            bundle["derived_metrics"] = derived_metrics
+            bundle["timeline"] = {
                 "granularity": granularity,
-                "data": timeline_data
-            },
-            "model_distribution": model_distribution,
-            "hourly_profile": hourly_profile,
-            "recent_sessions": recent_sessions,
-            "heatmap": {
+                "data": timeline_data,
+                "overlay": overlay_for_range(calendar, date_from, date_to),
+            }
+            bundle["heatmap"] = {
                 "range": {"from": heatmap_from_str, "to": heatmap_to_str},
                 "daily_activity": heatmap_activity,
-            },
-            "meta": {
-                "updated_files": updated_files,
-                "generated_at": datetime.now().isoformat(),
-                "data_range": data_range,
-                "app_version": get_app_version(),
+                "overlay": overlay_for_range(calendar, heatmap_from_str, heatmap_to_str),
             }
+
+        if "models" in wanted:
+            model_distribution = query_model_distribution(conn, date_from, date_to, scope)
+            for item in model_distribution:
+                item["api_equivalent_cost"] = api_equivalent["by_model"].get(item["model"], 0.0)
+            bundle["model_distribution"] = model_distribution
+
+        if "hourly" in wanted:
+            bundle["hourly_profile"] = query_hourly_profile(conn, date_from, date_to, scope)
+
+        if "sessions" in wanted:
+            bundle["recent_sessions"] = query_recent_sessions(
+                conn, date_from, date_to, limit=50, project_id=scope
+            )
+
+        bundle["meta"] = {
+            "updated_files": updated_files,
+            "ingest": ingest_stats or None,
+            "refresh_blocked": refresh_blocked,
+            "hooks": hook_runs,
+            "generated_at": datetime.now().isoformat(),
+            "data_range": query_data_range(conn, scope),
+            "app_version": get_app_version(),
+            "sections": [section for section in DASHBOARD_SECTIONS if section in wanted],
+            "workspace_id": workspace_id,
         }
+        return bundle
 
 
-def get_day_details(date: str, project_id: str | None = None) -> dict:
+def _run_lifecycle_hooks(conn, refresh: dict) -> list[dict]:
+    """Run lifecycle hooks after a refresh; hook problems never fail the refresh."""
+    try:
+        return run_lifecycle_hooks(conn, refresh)
+    except Exception as e:
+        return [{"hook_id": None, "event": None, "status": "error", "stderr": str(e)}]
+
+
+def refresh_data() -> dict:
     """
-    Get detailed stats for a specific day.
+    Run an incremental update without querying any range.
 
-    Args:
-        date: Date (YYYY-MM-DD)
-        project_id: Optional project filter
+    Used before fetching several dashboard ranges concurrently, so the
+    refresh happens once instead of once per range.
 
     Returns:
-        Day details with hourly breakdown, models, and sessions
+        {"updated_files": 3, "refreshed_at": "2025-06-15T10:00:00",
+         "ingest": {"files", "entries", "parse_seconds", "write_seconds",
+                    "total_seconds", "files_per_second"} or None if nothing changed}
+
+    Raises:
+        IngestLockedError: If another process (possibly on another machine) is ingesting
     """
     with get_db_connection() as conn:
         init_database(conn)
-        return query_day_details(conn, date, project_id)
+        ingest_stats: dict = {}
+        with ingest_lock(), track_refresh("full") as tracker:
+            updated_files = perform_incremental_update(
+                conn, force_rescan=False, verbose=False, stats=ingest_stats,
+                on_progress=tracker.progress
+            )
+            tracker.updated_files = updated_files
+        roll_over_budget_periods(conn)
+        return {
+            "updated_files": updated_files,
+            "refreshed_at": datetime.now().isoformat(),
+            "ingest": ingest_stats or None,
+            "hooks": _run_lifecycle_hooks(conn, {"mode": "full", "updated_files": updated_files}),
+        }
 
 
-def get_model_details(model: str, date_from: str, date_to: str, project_id: str | None = None) -> dict:
+def refresh_partial(since: str | None = None, today: bool = False) -> dict:
     """
-    Get detailed stats for a specific model.
+    Fast-path refresh of recent changes only.
 
     Args:
-        model: Model identifier
-        date_from: Start date (YYYY-MM-DD)
-        date_to: End date (YYYY-MM-DD)
-        project_id: Optional project filter
+        since: ISO timestamp to refresh from (naive values are local time);
+            default is the last scan watermark
+        today: Refresh from local midnight (overrides since)
 
     Returns:
-        Model details with daily activity and top sessions
+        {"mode": "partial", "since": ..., "updated_files": 1, "affected_dates": [...],
+         "delta": {"messages", "tokens", "cost"}, "refreshed_at": ...}
+
+    Raises:
+        ValueError: If since is not a valid ISO timestamp
+        IngestLockedError: If another process is ingesting
     """
+    since_dt = None
+    if today:
+        since_dt = datetime.now().astimezone().replace(hour=0, minute=0, second=0, microsecond=0)
+    elif since:
+        try:
+            since_dt = datetime.fromisoformat(since.replace("Z", "+00:00")).astimezone()
+        except ValueError:
+            raise ValueError(f"Invalid timestamp: {since}. Use ISO format, e.g. 2025-06-15T08:00:00")
+
     with get_db_connection() as conn:
         init_database(conn)
-        return query_model_details(conn, model, date_from, date_to, project_id)
+        with ingest_lock(), track_refresh("partial") as tracker:
+            result = perform_partial_update(conn, since_dt, on_progress=tracker.progress)
+            tracker.updated_files = result["updated_files"]
+        result["refreshed_at"] = datetime.now().isoformat()
+        result["hooks"] = _run_lifecycle_hooks(
+            conn, {"mode": "partial", "updated_files": result["updated_files"]}
+        )
+        return result
 
 
-def get_session_details(session_id: str, project_id: str | None = None) -> dict:
+def get_refresh_status() -> dict:
     """
-    Get detailed stats for a specific session.
-
-    Args:
-        session_id: Session identifier
-        project_id: Optional project filter
+    Get data freshness: last successful refresh, the running refresh's
+    progress, and pending files and scan watermarks per data root.
 
     Returns:
-        Session details with messages and totals
+        {"state", "current", "last_success", "last_error", "data_current_as_of",
+         "pending_files", "roots": [...], "lock": holder or None}
+        (see cache.refresh_status.get_refresh_status and cache.ingest_lock.read_lock)
     """
     with get_db_connection() as conn:
         init_database(conn)
-        return query_session_details(conn, session_id, project_id)
+        status = query_refresh_status(conn)
+    status["lock"] = read_lock()
+    return status
 
 
-def get_limit_resets(date_from: str, date_to: str) -> list[dict]:
+def get_quarantined_lines(limit: int = 100, source_file: str | None = None) -> dict:
     """
-    Get limit reset events for a date range.
+    Get malformed JSONL lines skipped during ingestion, newest first.
 
     Args:
-        date_from: Start date (YYYY-MM-DD)
-        date_to: End date (YYYY-MM-DD)
+        limit: Maximum number of lines to return
+        source_file: Only return lines of this session file
 
     Returns:
-        List of limit reset events with timestamps
+        {"total", "files", "lines": [{"source_file", "line_number", "error", "content",
+         "truncated", "quarantined_at"}]}
+    """
+    return read_quarantined_lines(limit, source_file)
+
+
+def get_schema_info() -> dict:
+    """
+    Get the database schema version (pending migrations run on connect).
+
+    Returns:
+        {"path": ".../command_center.db", "version": 8, "latest": 8, "app_version": "..."}
     """
     with get_db_connection() as conn:
         init_database(conn)
-        return get_limit_events(conn, date_from, date_to)
+        version = get_schema_version(conn)
+    return {
+        "path": DB_PATH,
+        "version": version,
+        "latest": CURRENT_SCHEMA_VERSION,
+        "app_version": get_app_version(),
+    }
 
 
-def get_usage_accounts() -> dict:
+def get_backend_version() -> dict:
     """
-    Get latest cc_usage events per email account.
+    Report the versions the desktop app compares in its startup handshake.
+
+    Does not open the database, so the handshake works before migrations.
+
+    Returns:
+        {"api_version": 2, "package_version": "...", "schema_version": 16,
+         "python_version": "3.12.3"}
+    """
+    return {
+        "api_version": API_VERSION,
+        "package_version": get_app_version(),
+        "schema_version": CURRENT_SCHEMA_VERSION,
+        "python_version": platform.python_version(),
+    }
+
+
+def get_diagnostics() -> dict:
+    """
+    Describe the backend installation for troubleshooting.
+
+    Does not open or create the database, so it works when the database is
+    missing, locked or unreadable.
 
     Returns:
         {
-            "accounts": [
-                {
-                    "email": "...",
-                    "captured_at_local": "...",
-                    "current_session_used_raw": "...",
-                    "current_session_resets_local": "...",
-                    "current_session_resets_raw": "...",
-                    "current_week_used_raw": "...",
-                    "current_week_resets_local": "...",
-                    "current_week_resets_raw": "...",
-                    ...
-                }
-            ]
+            "app_version": "...",
+            "python": {"executable": "/usr/bin/python3", "version": "3.12.3"},
+            "module_path": ".../command_center",
+            "data_dir": "~/.claude/db",
+            "db": {"path", "exists", "size", "wal_size"}   # sizes in bytes
         }
     """
-    return {"accounts": fetch_latest_usage_accounts()}
+    def size(path: str) -> int | None:
+        return os.path.getsize(path) if os.path.isfile(path) else None
+
+    return {
+        "app_version": get_app_version(),
+        "python": {"executable": sys.executable, "version": platform.python_version()},
+        "module_path": str(Path(__file__).resolve().parent),
+        "data_dir": os.path.dirname(DB_PATH),
+        "db": {
+            "path": DB_PATH,
+            "exists": os.path.isfile(DB_PATH),
+            "size": size(DB_PATH),
+            "wal_size": size(DB_PATH + "-wal"),
+        },
+    }
+
+
+def generate_demo_data(seed: int = DEFAULT_DEMO_SEED, months: int = DEFAULT_DEMO_MONTHS) -> dict:
+    """
+    Fill the demo home's database with synthetic usage (demo mode only).
+
+    Args:
+        seed: Random seed (same seed, same data)
+        months: Months of history ending today (1-24)
+
+    Returns:
+        Dict with seed, months, date range and generated project/session/message counts
+
+    Raises:
+        ValueError: Outside demo mode, so real data is never replaced
+    """
+    if not demo_mode():
+        raise ValueError(f"Demo data is only generated in demo mode ({DEMO_ENV}=1 and {DEMO_HOME_ENV})")
+    with get_db_connection() as conn:
+        init_database(conn)
+        return build_demo_data(conn, seed, months)
 
 
-def export_png_report(date_from: str, date_to: str) -> dict:
+def generate_sample_data(
+    days: int = DEFAULT_SAMPLE_DAYS,
+    intensity: str = DEFAULT_SAMPLE_INTENSITY,
+    seed: int = DEFAULT_DEMO_SEED,
+) -> dict:
     """
-    Generate PNG usage report and return as base64-encoded string.
+    Write sample session logs into the demo home and ingest them (demo mode only).
 
     Args:
-        date_from: Start date (YYYY-MM-DD)
-        date_to: End date (YYYY-MM-DD)
+        days: Days of history ending today (1-365)
+        intensity: Sessions per day: light, regular or heavy
+        seed: Random seed (same seed, same logs)
 
     Returns:
-        Dict with base64-encoded PNG data and filename
+        Dict with the sandbox data root, date range and generated session/message counts
+
+    Raises:
+        ValueError: Outside demo mode, so real logs and data are never replaced
     """
+    if not demo_mode():
+        raise ValueError(f"Sample data is only generated in demo mode ({DEMO_ENV}=1 and {DEMO_HOME_ENV})")
     with get_db_connection() as conn:
         init_database(conn)
+        with ingest_lock():
+            return build_sample_data(conn, Path(CLAUDE_DIRS[0]) / "projects", days, intensity, seed)
 
-        # Query usage stats
-        stats = query_usage_stats(conn, date_from, date_to)
 
-        # Generate PNG
-        png_bytes = generate_usage_report_png(stats)
+def send_telemetry(endpoint: str, payload: str) -> dict:
+    """
+    POST an anonymous telemetry payload built by the desktop app.
 
-        # Encode to base64
-        png_base64 = base64.b64encode(png_bytes).decode('utf-8')
+    Args:
+        endpoint: https URL configured by the user
+        payload: JSON object (see preview_telemetry_payload in the desktop app)
 
-        # Generate filename
-        filename = f"cc-usage-report-{date_from}_{date_to}.png"
+    Returns:
+        {"sent": True, "status": 204, "bytes": 512}
 
-        return {
-            "filename": filename,
-            "data": png_base64,
-            "size": len(png_bytes),
-            "mime_type": "image/png"
-        }
+    Raises:
+        ValueError: For a non-https endpoint, an invalid payload or an HTTP error status
+    """
+    import requests
+
+    if not endpoint.startswith("https://"):
+        raise ValueError("Telemetry endpoint must be an https:// URL")
+    try:
+        body = json.loads(payload)
+    except json.JSONDecodeError as e:
+        raise ValueError(f"Invalid telemetry payload: {e}")
+    if not isinstance(body, dict):
+        raise ValueError("Telemetry payload must be a JSON object")
+
+    data = json.dumps(body).encode("utf-8")
+    response = requests.post(
+        endpoint, data=data, headers={"Content-Type": "application/json"}, timeout=10
+    )
+    if response.status_code >= 400:
+        raise ValueError(f"Telemetry endpoint returned HTTP {response.status_code}")
+    return {"sent": True, "status": response.status_code, "bytes": len(data)}
 
 
-def get_projects() -> dict:
+def fetch_feature_flags(url: str) -> dict:
     """
-    Get all projects with metadata.
+    Fetch a remote feature flag document for the desktop app.
+
+    Args:
+        url: https URL of a JSON object {"flags": {"name": true}} (or the flags object alone)
 
     Returns:
-        {
-            "projects": [
-                {
-                    "project_id": "-home-xai-DEV-command-center",
-                    "name": "Command Center",
-                    "description": "Analytics tool",
-                    "absolute_path": "/home/xai/DEV/command-center",
-                    "first_seen": "2024-12-27T10:00:00+01:00",
-                    "last_seen": "2024-12-28T01:44:00+01:00",
-                    "visible": true
-                },
-                ...
-            ]
-        }
+        {"flags": {"native_ingestion": False, ...}} (non-boolean values are dropped)
+
+    Raises:
+        ValueError: For a non-https URL, an HTTP error status or an invalid document
     """
-    from command_center.utils.project_metadata import (
-        list_all_projects,
-        ensure_visible_field,
-        PROJECTS_JSON_PATH
-    )
+    import requests
 
-    # Backward compatibility: ensure all projects have visible field
-    ensure_visible_field(PROJECTS_JSON_PATH)
+    if not url.startswith("https://"):
+        raise ValueError("Feature flag URL must be an https:// URL")
+    response = requests.get(url, headers={"Accept": "application/json"}, timeout=10)
+    if response.status_code >= 400:
+        raise ValueError(f"Feature flag URL returned HTTP {response.status_code}")
+    try:
+        document = response.json()
+    except ValueError as e:
+        raise ValueError(f"Invalid feature flag document: {e}")
+    flags = document.get("flags", document) if isinstance(document, dict) else None
+    if not isinstance(flags, dict):
+        raise ValueError("Feature flag document must be a JSON object")
+    return {"flags": {name: value for name, value in flags.items() if isinstance(value, bool)}}
 
-    # Get all projects
-    projects = list_all_projects(PROJECTS_JSON_PATH)
 
-    return {"projects": projects}
+def get_day_details(date: str, project_id: str | None = None) -> dict:
+    """
+    Get detailed stats for a specific day.
 
+    Args:
+        date: Date (YYYY-MM-DD)
+        project_id: Optional project filter
 
-def update_project(
-    project_id: str,
-    name: str | None = None,
-    description: str | None = None,
-    visible: bool | None = None
-) -> dict:
+    Returns:
+        Day details with hourly breakdown, models, and sessions
     """
-    Update project metadata fields.
+    with get_db_connection() as conn:
+        init_database(conn)
+        return query_day_details(conn, date, project_id)
+
+
+def get_aggregate_history(date: str) -> dict:
+    """
+    Get every recorded version of a day's totals with the reason it changed.
 
     Args:
-        project_id: Project identifier
-        name: New display name (optional)
-        description: New description (optional)
-        visible: Visibility flag (optional)
+        date: Local date (YYYY-MM-DD)
 
     Returns:
-        {
-            "project": {
-                "project_id": "...",
-                "name": "...",
-                "description": "...",
-                "visible": true,
-                ...
-            }
-        }
+        {"date", "current", "history": [...]} (see query_aggregate_history)
 
     Raises:
-        ValueError: If project not found or validation fails
+        ValueError: If the date is malformed
     """
-    from command_center.utils.project_metadata import (
-        update_project_fields,
-        PROJECTS_JSON_PATH
-    )
+    try:
+        datetime.strptime(date, "%Y-%m-%d")
+    except ValueError:
+        raise ValueError(f"Invalid date: {date}")
+    with get_db_connection() as conn:
+        init_database(conn)
+        return query_aggregate_history(conn, date)
 
-    # Call validation and update function
-    updated_project = update_project_fields(
-        project_id=project_id,
-        name=name,
-        description=description,
-        visible=visible,
-        json_path=PROJECTS_JSON_PATH
-    )
 
-    return {"project": updated_project}
+def get_model_details(model: str, date_from: str, date_to: str, project_id: str | None = None) -> dict:
+    """
+    Get detailed stats for a specific model.
 
+    Args:
+        model: Model identifier
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Optional project filter
 
-def main():
-    """CLI entry point for Tauri API."""
-    parser = argparse.ArgumentParser(
-        prog="command_center.tauri_api",
-        description="JSON API for Tauri desktop dashboard"
-    )
-    subparsers = parser.add_subparsers(dest="command", required=True)
+    Returns:
+        Model details with daily activity and top sessions; totals include
+        api_equivalent_cost (API list-price equivalent of the model usage)
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        details = query_model_details(conn, model, date_from, date_to, project_id)
+        api_equivalent = query_api_equivalent_cost(conn, date_from, date_to, project_id, model)
+        details["totals"]["api_equivalent_cost"] = api_equivalent["total"]
+        return details
 
-    # dashboard subcommand
-    dash_parser = subparsers.add_parser(
-        "dashboard",
-        help="Get complete dashboard bundle"
-    )
-    dash_parser.add_argument(
-        "--from", dest="date_from", required=True,
-        help="Start date (YYYY-MM-DD)"
-    )
-    dash_parser.add_argument(
-        "--to", dest="date_to", required=True,
-        help="End date (YYYY-MM-DD)"
-    )
+
+def get_session_details(session_id: str, project_id: str | None = None) -> dict:
+    """
+    Get detailed stats for a specific session.
+
+    Args:
+        session_id: Session identifier
+        project_id: Optional project filter
+
+    Returns:
+        Session details with messages, totals and compaction events
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        details = query_session_details(conn, session_id, project_id)
+        files = [row["source_file"] for row in query_session_source_files(conn, session_id)]
+        if files:
+            scan_compactions(conn, {session_id: files})
+        details["compactions"] = query_session_compactions(conn, session_id)
+        return details
+
+
+def get_session_files(session_id: str) -> dict:
+    """
+    Locate the raw JSONL files of a session.
+
+    Args:
+        session_id: Session identifier
+
+    Returns:
+        {"session_id", "files": [{"path", "project_id", "messages", "size_bytes"}]};
+        files that no longer exist on disk are omitted
+
+    Raises:
+        ValueError: If no messages of the session are in the database
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        rows = query_session_source_files(conn, session_id)
+    if not rows:
+        raise ValueError(f"Unknown session: {session_id}")
+
+    files = []
+    for row in rows:
+        path = Path(row["source_file"])
+        if path.is_file():
+            files.append({
+                "path": str(path),
+                "project_id": row["project_id"],
+                "messages": row["messages"],
+                "size_bytes": path.stat().st_size,
+            })
+    return {"session_id": session_id, "files": files}
+
+
+def get_plugin_records(date_from: str, date_to: str, project_id: str | None = None) -> dict:
+    """
+    Get normalized message records fed to metric plugins.
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Optional project filter
+
+    Returns:
+        {"range": {"from", "to", "project_id"}, "records": [...]}
+        (see query_metric_records)
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        records = query_metric_records(conn, date_from, date_to, project_id)
+    return {
+        "range": {"from": date_from, "to": date_to, "project_id": project_id},
+        "records": records,
+    }
+
+
+def export_raw_sessions(session_ids: list[str], output_path: str, redact: bool = False) -> dict:
+    """
+    Bundle the raw JSONL files of sessions into a zip archive.
+
+    Args:
+        session_ids: Sessions to export
+        output_path: Path of the zip file to write
+        redact: Strip conversation content, keeping ids, models and usage
+
+    Returns:
+        Dict with path, session/file counts, archive size and missing files
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return export_session_archive(conn, session_ids, output_path, redact)
+
+
+def create_backup(output_path: str) -> dict:
+    """
+    Write a signed backup of settings, project metadata and the database.
+
+    Args:
+        output_path: Path of the zip file to write
+
+    Returns:
+        Dict with path, file count, size, components and signing key id/store
+    """
+    return backup.create_backup(output_path)
+
+
+def verify_backup(path: str) -> dict:
+    """
+    Check a backup's checksums and signature without restoring it.
+
+    Args:
+        path: Backup archive
+
+    Returns:
+        Dict with valid, signature state, files_checked and errors
+    """
+    return backup.verify_backup(path)
+
+
+def restore_backup(
+    path: str,
+    force: bool = False,
+    components: list[str] | None = None,
+    dry_run: bool = False,
+) -> dict:
+    """
+    Restore a backup (or some of its components) after verifying it.
+
+    Args:
+        path: Backup archive
+        force: Accept a backup signed by another key (checksums must still match)
+        components: Components to restore: settings, projects, cache (default: all)
+        dry_run: Only report what would change
+
+    Returns:
+        Dict with per-file changes, restored files and the verification result
+    """
+    return backup.restore_backup(path, force, components, dry_run)
+
+
+def merge_data_source(path: str, dry_run: bool = False) -> dict:
+    """
+    Merge a backup, database file or Claude data root from another machine.
+
+    Overlapping messages are matched instead of counted twice and the more
+    complete record is kept (see data_merge).
+
+    Args:
+        path: Backup archive, command_center.db file or config directory with projects/
+        dry_run: Only report what would change
+
+    Returns:
+        Merge report: added/identical/replaced/kept counts, double counts prevented,
+        overlapping sessions and created_projects
+    """
+    from command_center.pricing_history import load_price_book
+
+    with get_db_connection() as conn:
+        init_database(conn)
+        if dry_run:
+            return merge_source(conn, path, True, load_price_book(conn))
+        with ingest_lock():
+            report = merge_source(conn, path, False, load_price_book(conn))
+            report["created_projects"] = register_merged_projects(conn, report["projects"])
+        return report
+
+
+def shift_session_timestamps(
+    offset: str,
+    date_from: str | None = None,
+    date_to: str | None = None,
+    session_ids: list[str] | None = None,
+    project_id: str | None = None,
+    config_dir: str | None = None,
+    preview: bool = False
+) -> dict:
+    """
+    Move the timestamps of messages logged with a wrong clock (see time_shift).
+
+    Args:
+        offset: Offset like -1d, +2h30m or seconds
+        date_from: First local date of the selection (YYYY-MM-DD)
+        date_to: Last local date of the selection
+        session_ids: Only these sessions
+        project_id: Only this project
+        config_dir: Only messages from this Claude config directory
+        preview: Only report what would move
+
+    Returns:
+        Dict with shift_id, message/session counts, date ranges before and after and samples
+    """
+    offset_seconds = parse_offset(offset)
+    with get_db_connection() as conn:
+        init_database(conn)
+        if preview:
+            return shift_timestamps(
+                conn, offset_seconds, date_from, date_to, session_ids, project_id, config_dir, True
+            )
+        with ingest_lock():
+            return shift_timestamps(
+                conn, offset_seconds, date_from, date_to, session_ids, project_id, config_dir
+            )
+
+
+def undo_session_timestamp_shift(shift_id: int, preview: bool = False) -> dict:
+    """
+    Undo a timestamp shift and rebuild the affected aggregates.
+
+    Args:
+        shift_id: ID returned by shift_session_timestamps
+        preview: Only report what would move back
+
+    Returns:
+        Same summary as shift_session_timestamps
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        if preview:
+            return undo_timestamp_shift(conn, shift_id, True)
+        with ingest_lock():
+            return undo_timestamp_shift(conn, shift_id)
+
+
+def get_timestamp_shifts() -> dict:
+    """
+    List recorded timestamp shifts, newest first.
+
+    Returns:
+        Dict with shifts (offset, filter, message count, created/undone times)
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return list_timestamp_shifts(conn)
+
+
+def import_raw_sessions(archive_path: str, target_project: str | None = None) -> dict:
+    """
+    Import a session archive exported by export_raw_sessions.
+
+    Args:
+        archive_path: Path of the zip archive
+        target_project: Project to import into (created if missing; default: new project)
+
+    Returns:
+        Dict with project_id, created_project and imported/duplicate message counts
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return import_session_archive(conn, archive_path, target_project)
+
+
+def list_sessions(
+    date_from: str,
+    date_to: str,
+    project_id: str | None = None,
+    model: str | None = None,
+    search: str | None = None,
+    sort: str = "cost",
+    cursor: str | None = None,
+    page_size: int = 100
+) -> dict:
+    """
+    Get one page of sessions for infinite-scroll tables.
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Optional project filter
+        model: Only sessions that used this model (optional)
+        search: Session ID substring (optional)
+        sort: cost, tokens, messages, recent or oldest
+        cursor: next_cursor of the previous page
+        page_size: Rows per page
+
+    Returns:
+        Page with sessions, next_cursor (None on the last page) and total_count
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return query_sessions_page(
+            conn, date_from, date_to, project_id, model, search, sort, cursor, page_size
+        )
+
+
+def get_limit_resets(date_from: str, date_to: str) -> list[dict]:
+    """
+    Get limit reset events for a date range.
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+
+    Returns:
+        List of limit reset events with timestamps
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return get_limit_events(conn, date_from, date_to)
+
+
+def get_limit_impact(date_from: str, date_to: str) -> dict:
+    """
+    Analyze limit hits for a date range.
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+
+    Returns:
+        Limit hit counts, lockout durations, and per project/hour/weekday breakdowns
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return query_limit_impact(conn, date_from, date_to)
+
+
+def get_mcp_usage(date_from: str, date_to: str, project_id: str | None = None) -> dict:
+    """
+    Calls, tokens and failures per MCP server for a date range.
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Filter by project (optional)
+
+    Returns:
+        Totals and per-server calls, failures, sessions, tokens and per-tool breakdown
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return query_mcp_usage(conn, date_from, date_to, project_id)
+
+
+def get_hook_stats(date_from: str, date_to: str, project_id: str | None = None) -> dict:
+    """
+    Executions, durations and failure rates per Claude Code hook for a date range.
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Filter by project (optional)
+
+    Returns:
+        Totals, per-event counts and per-hook (event + command) stats
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return query_hook_stats(conn, date_from, date_to, project_id)
+
+
+def get_effort_breakdown(
+    model: str | None,
+    date_from: str,
+    date_to: str,
+    project_id: str | None = None
+) -> dict:
+    """
+    Cost, tokens and latency of a model's responses by effort/thinking setting.
+
+    Args:
+        model: Model name (None for all models)
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Filter by project (optional)
+
+    Returns:
+        Per setting requests, thinking share, tokens, cost (total, share, per request) and latency
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return query_effort_breakdown(conn, model, date_from, date_to, project_id)
+
+
+def get_concurrency_stats(
+    date_from: str,
+    date_to: str,
+    idle_gap_minutes: int = DEFAULT_IDLE_GAP_MINUTES
+) -> dict:
+    """
+    Periods where several sessions ran at the same time, and their token impact.
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        idle_gap_minutes: Longest pause between messages within one activity segment
+
+    Returns:
+        Max concurrency, time/tokens per concurrency level, overlap periods, daily maxima
+        and concurrent usage before each 5-hour limit hit
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return query_concurrency_stats(conn, date_from, date_to, idle_gap_minutes)
+
+
+def get_outcome_stats(
+    date_from: str,
+    date_to: str,
+    project_id: str | None = None,
+    classify: bool = True,
+    reclassify: bool = False
+) -> dict:
+    """
+    Share of sessions by outcome (commit, test pass, tool error, user interrupt).
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Filter by project (optional)
+        classify: Classify new and changed sessions of the range first (reads their JSONL files)
+        reclassify: Re-read every session of the range
+
+    Returns:
+        Outcome counts and shares, shipped (committed) sessions, per-project breakdown,
+        and the classification pass counts (null if it was skipped)
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        classification = classify_sessions(conn, date_from, date_to, reclassify) if classify or reclassify else None
+        result = query_outcome_stats(conn, date_from, date_to, project_id)
+    result["range"] = {"from": date_from, "to": date_to}
+    result["classification"] = classification
+    return result
+
+
+def get_compaction_stats(
+    date_from: str,
+    date_to: str,
+    project_id: str | None = None,
+    scan: bool = True,
+    rescan: bool = False
+) -> dict:
+    """
+    Frequency and token impact of context compactions (/compact and auto-compaction).
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Filter by project (optional)
+        scan: Scan new and changed sessions of the range first (reads their JSONL files)
+        rescan: Re-read every session of the range
+
+    Returns:
+        Compaction counts by trigger, per-session rates, average context before and after,
+        tokens freed, daily counts, top sessions, per-project breakdown,
+        and the scan pass counts (null if it was skipped)
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        scanned = scan_compactions(conn, session_files(conn, date_from, date_to), rescan) if scan or rescan else None
+        result = query_compaction_stats(conn, date_from, date_to, project_id)
+    result["range"] = {"from": date_from, "to": date_to}
+    result["scan"] = scanned
+    return result
+
+
+def get_usage_accounts() -> dict:
+    """
+    Get latest cc_usage events per email account.
+
+    Returns:
+        {
+            "accounts": [
+                {
+                    "email": "...",
+                    "captured_at_local": "...",
+                    "current_session_used_raw": "...",
+                    "current_session_resets_local": "...",
+                    "current_session_resets_raw": "...",
+                    "current_week_used_raw": "...",
+                    "current_week_resets_local": "...",
+                    "current_week_resets_raw": "...",
+                    ...
+                }
+            ]
+        }
+    """
+    return {"accounts": fetch_latest_usage_accounts()}
+
+
+def update_usage_account(
+    email: str,
+    label: str | None = None,
+    color: str | None = None,
+    plan_type: str | None = None
+) -> dict:
+    """
+    Update usage account metadata fields.
+
+    Args:
+        email: Account email
+        label: Display label (optional, empty string clears)
+        color: Hex color #RRGGBB (optional, empty string clears)
+        plan_type: Plan type (optional, empty string clears)
+
+    Returns:
+        {"account": {"email": "...", "label": "...", "color": "...", "plan_type": "..."}}
+
+    Raises:
+        ValueError: If validation fails
+    """
+    return {"account": update_usage_account_fields(email, label, color, plan_type)}
+
+
+def get_account_comparison(date_from: str, date_to: str, emails: list[str] | None = None) -> dict:
+    """
+    Compare usage accounts side by side for a date range.
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        emails: Optional subset of account emails
+
+    Returns:
+        Comparison payload with per-account totals and a metric matrix
+    """
+    return compare_usage_accounts(date_from, date_to, emails)
+
+
+def get_account_dashboard(date_from: str, date_to: str, email: str) -> dict:
+    """
+    Usage of one account for a date range (one column of the desktop's
+    combined multi-account dashboard, which queries accounts concurrently).
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        email: Usage account email
+
+    Returns:
+        {"account": {...snapshot totals and metadata}, "config_dirs": [...],
+         "totals": {messages, sessions, tokens, cost}, "daily": [...]}
+
+    Raises:
+        ValueError: If the account has neither metadata, snapshots nor bound config dirs
+    """
+    account = usage_account_totals(date_from, date_to, email)
+    with get_db_connection() as conn:
+        init_database(conn)
+        usage = query_account_usage(conn, email, date_from, date_to)
+    if not (account["snapshots"] or usage["config_dirs"] or email in load_account_metadata()):
+        raise ValueError(f"Unknown usage account: {email}")
+    return {"account": account, **usage}
+
+
+def get_claude_accounts() -> dict:
+    """
+    Detect Claude config directories (default, CLAUDE_CONFIG_DIR, ~/.claude-*).
+
+    Returns:
+        {
+            "configs": [
+                {
+                    "config_dir": "/home/xai/.claude-work",
+                    "is_default": false,
+                    "from_env": false,
+                    "session_files": 42,
+                    "last_activity": "...",
+                    "detected_email": "me@work.com",
+                    "account_email": "me@work.com",
+                    "messages": 1234
+                }
+            ]
+        }
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return {"configs": detect_claude_accounts(conn)}
+
+
+def bind_claude_account(config_dir: str, email: str | None) -> dict:
+    """
+    Bind a Claude config directory to a usage account.
+
+    Args:
+        config_dir: Claude config directory path
+        email: Usage account email (None or empty removes the binding)
+
+    Returns:
+        {"binding": {"config_dir": "...", "account_email": "..."}}
+    """
+    return {"binding": bind_config_dir(config_dir, email)}
+
+
+def detect_plan(apply: bool = True) -> dict:
+    """
+    Detect subscription plans from local Claude files.
+
+    Args:
+        apply: Store detected plans on bound usage accounts
+
+    Returns:
+        {
+            "configs": [{"config_dir": "...", "plan_type": "max_20x", "confidence": "high", "evidence": [...]}],
+            "accounts": [{"email": "...", "plan_type": "...", "plan_source": "manual|detected", ...}]
+        }
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return detect_plans(conn, apply=apply)
+
+
+def get_budget_history() -> dict:
+    """
+    Get monthly budget history with actual vs budget per period.
+
+    Completed months are snapshotted on first access after rollover.
+
+    Returns:
+        {
+            "current": {"period": "2025-06", "budget": 200.0, "actual": 143.2, ...},
+            "periods": [{"period": "2025-05", "budget": 200.0, "actual": 231.7, "over_under": 31.7, ...}],
+            "summary": {"periods": 5, "over_budget": 2, "under_budget": 3, ...}
+        }
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        roll_over_budget_periods(conn)
+        return query_budget_history(conn)
+
+
+def set_budget(amount_usd: float | None, effective_from: str | None = None) -> dict:
+    """
+    Set the monthly budget.
+
+    Args:
+        amount_usd: Budget in USD (None clears the budget)
+        effective_from: First month the budget applies to (YYYY-MM, default: current month)
+
+    Returns:
+        {"budget": {"monthly": [...]}}
+    """
+    return {"budget": set_monthly_budget(amount_usd, effective_from)}
+
+
+def get_hooks() -> dict:
+    """
+    Get lifecycle hooks with their recent runs.
+
+    Returns:
+        {"events": [...], "hooks": [{"id", "event", "action", "command", "timeout", "enabled"}
+                                    or {"id", "event", "action": "export", "folder", "formats", "enabled"}],
+         "budget_thresholds": [80.0, 100.0], "recent_runs": [...]}
+    """
+    return list_hooks()
+
+
+def set_hook(
+    event: str,
+    command: str,
+    timeout: int | None = None,
+    hook_id: str | None = None,
+    enabled: bool = True,
+    action: str = "command",
+    folder: str | None = None,
+    formats: list[str] | None = None
+) -> dict:
+    """
+    Create or update a lifecycle hook.
+
+    Args:
+        event: refresh-completed, day-rolled-over, month-completed or budget-threshold
+        command: Shell command template ({field} is replaced by the quoted event field)
+        timeout: Seconds before the command is killed (default 30)
+        hook_id: Hook to create or replace (default: a new random id)
+        enabled: Whether the hook runs
+        action: "command" or "export" (month-completed: write the month's files to folder)
+        folder: Absolute folder of an export hook
+        formats: Files of an export hook ("csv", "pdf"; default both)
+
+    Returns:
+        {"hook": {...}}
+    """
+    return {"hook": set_hook_config(event, command, timeout, hook_id, enabled, action, folder, formats)}
+
+
+def test_hook(hook_id: str) -> dict:
+    """
+    Run a hook once with a sample event; export hooks write the previous month's files.
+
+    Returns:
+        The run result (see hooks.run_hook)
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return run_test_hook(hook_id, conn=conn)
+
+
+def get_derived_metrics() -> dict:
+    """
+    Get user-defined derived metrics.
+
+    Returns:
+        {"variables": [...], "functions": [...],
+         "metrics": [{"name", "expression", "description"}]}
+    """
+    return {
+        "variables": list(metric_names()),
+        "functions": list(METRIC_FUNCTIONS),
+        "metrics": load_derived_metrics(),
+    }
+
+
+def list_available_metrics() -> dict:
+    """
+    List the metrics that can be charted, for metric pickers.
+
+    Returns:
+        {"metrics": [{"name", "label", "unit", "category", "description"}]};
+        registered metrics first, then derived metrics (category "derived",
+        unit null, label from the description)
+    """
+    derived = [
+        {
+            "name": metric.get("name"),
+            "label": metric.get("description") or metric.get("name"),
+            "unit": None,
+            "category": "derived",
+            "description": metric.get("expression"),
+        }
+        for metric in load_derived_metrics()
+    ]
+    return {"metrics": list_registered_metrics() + derived}
+
+
+def get_metric_series(
+    date_from: str,
+    date_to: str,
+    granularity: str,
+    metrics: list[str],
+    project_id: str | None = None
+) -> dict:
+    """
+    Get chosen metrics per period.
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        granularity: 'month', 'week', 'day' or 'hour'
+        metrics: Registered or derived metric names
+        project_id: Filter by project (optional)
+
+    Returns:
+        {"granularity", "metrics": [...], "data": [{"period", <metric>: value}]}
+
+    Raises:
+        ValueError: For an unknown metric
+    """
+    if not metrics:
+        raise ValueError("No metrics requested")
+    derived = {metric.get("name"): metric for metric in load_derived_metrics()}
+    with get_db_connection() as conn:
+        init_database(conn)
+        registered = [name for name in metrics if name not in derived]
+        wanted_derived = [derived[name] for name in metrics if name in derived]
+        # Derived formulas need every registered metric as input
+        values = query_metrics(
+            conn, date_from, date_to, granularity, project_id,
+            None if wanted_derived else registered,
+        )
+    computed = compute_derived_metrics(wanted_derived, values) if wanted_derived else {}
+    return {
+        "granularity": granularity,
+        "metrics": metrics,
+        "data": [
+            {"period": period, **{
+                name: computed[period].get(name) if name in derived else period_values[name]
+                for name in metrics
+            }}
+            for period, period_values in values.items()
+        ],
+    }
+
+
+def set_derived_metric(name: str, expression: str, description: str | None = None) -> dict:
+    """
+    Create or replace a derived metric.
+
+    Args:
+        name: Metric name (lowercase identifier)
+        expression: Formula, e.g. "cost / messages"
+        description: Optional label
+
+    Returns:
+        {"metric": {...}}
+    """
+    return {"metric": set_derived_metric_config(name, expression, description)}
+
+
+def get_calendar_overlay() -> dict:
+    """
+    Get the vacation/holiday calendar overlay.
+
+    Returns:
+        {"periods": [{"from", "to", "kind", "label"}]}
+    """
+    return {"periods": load_calendar_overlay()}
+
+
+def set_calendar_overlay(periods: list[dict]) -> dict:
+    """
+    Replace the vacation/holiday calendar overlay.
+
+    Args:
+        periods: [{"from", "to", "kind": "vacation" | "holiday", "label"}]; [] clears it
+
+    Returns:
+        {"periods": [...]} as stored
+    """
+    return set_calendar_overlay_config(periods)
+
+
+def get_working_hours() -> dict:
+    """
+    Get the working hours definition.
+
+    Returns:
+        {"days": [1, ..., 5], "start": "09:00", "end": "17:00"} (ISO weekdays, local time)
+    """
+    return load_working_hours()
+
+
+def set_working_hours(days: list[int], start: str, end: str) -> dict:
+    """
+    Replace the working hours definition.
+
+    Args:
+        days: ISO weekdays counted as work days (1 = Monday ... 7 = Sunday)
+        start: Start of the work window (HH:MM)
+        end: End of the work window (HH:MM, exclusive)
+
+    Returns:
+        {"days", "start", "end"} as stored
+    """
+    return set_working_hours_config(days, start, end)
+
+
+def get_out_of_hours_usage(date_from: str, date_to: str, project_id: str | None = None) -> dict:
+    """
+    Split usage of a date range into work and personal time.
+
+    Calendar overlay days count as personal time.
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Filter by project (optional)
+
+    Returns:
+        Work and personal totals, the work share of cost and a per-day split
+    """
+    excluded = excluded_days(load_calendar_overlay(), date_from, date_to)
+    with get_db_connection() as conn:
+        init_database(conn)
+        return query_out_of_hours_usage(conn, date_from, date_to, load_working_hours(), excluded, project_id)
+
+
+def get_price_versions(model: str | None = None) -> dict:
+    """
+    Get effective-dated price versions.
+
+    Args:
+        model: Optional model filter
+
+    Returns:
+        {
+            "versions": [
+                {
+                    "model": "claude-opus-4-5-20251101",
+                    "effective_from": "1970-01-01",
+                    "effective_to": "2025-11-24",
+                    "rates": {"input_cost_per_token": 1.5e-05, ...},
+                    "source": "litellm",
+                    "recorded_at": "..."
+                },
+                ...
+            ]
+        }
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return {"versions": list_price_versions(conn, model)}
+
+
+def sync_prices(effective_from: str | None = None) -> dict:
+    """
+    Record current LiteLLM rates as price versions for all seen models.
+
+    Args:
+        effective_from: Start date for changed rates (YYYY-MM-DD, default: today)
+
+    Returns:
+        {"models": 12, "new_versions": 1, "unpriced": [...]}
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return sync_price_versions(conn, effective_from)
+
+
+def set_price(
+    model: str,
+    effective_from: str,
+    input_per_mtok: float,
+    output_per_mtok: float,
+    cache_write_per_mtok: float | None = None,
+    cache_read_per_mtok: float | None = None
+) -> dict:
+    """
+    Record a manual price version for a model.
+
+    Args:
+        model: Model name as stored in messages
+        effective_from: First date the rates apply to (YYYY-MM-DD)
+        input_per_mtok: Input price in USD per million tokens
+        output_per_mtok: Output price in USD per million tokens
+        cache_write_per_mtok: Cache write price per million tokens (default: 1.25x input)
+        cache_read_per_mtok: Cache read price per million tokens (default: 0.1x input)
+
+    Returns:
+        {"recorded": true, "model": "...", "effective_from": "..."}
+    """
+    if cache_write_per_mtok is None:
+        cache_write_per_mtok = input_per_mtok * 1.25
+    if cache_read_per_mtok is None:
+        cache_read_per_mtok = input_per_mtok * 0.1
+    if min(input_per_mtok, output_per_mtok, cache_write_per_mtok, cache_read_per_mtok) < 0:
+        raise ValueError("Prices cannot be negative")
+
+    pricing = ModelPricing(
+        input_cost_per_token=input_per_mtok / 1_000_000,
+        input_cost_per_token_above_200k=None,
+        cache_creation_cost_per_token=cache_write_per_mtok / 1_000_000,
+        cache_creation_cost_per_token_above_200k=None,
+        cached_input_cost_per_token=cache_read_per_mtok / 1_000_000,
+        cached_input_cost_per_token_above_200k=None,
+        output_cost_per_token=output_per_mtok / 1_000_000,
+        output_cost_per_token_above_200k=None,
+    )
+    with get_db_connection() as conn:
+        init_database(conn)
+        return record_price_version(conn, model, pricing, effective_from, source="manual")
+
+
+def reprice_history(
+    date_from: str | None = None,
+    date_to: str | None = None,
+    dry_run: bool = False
+) -> dict:
+    """
+    Recompute stored costs with the rates in effect on each message date.
+
+    Args:
+        date_from: Optional start date (YYYY-MM-DD)
+        date_to: Optional end date (YYYY-MM-DD)
+        dry_run: Report deltas without writing
+
+    Returns:
+        {
+            "dry_run": false,
+            "messages_checked": 52000,
+            "messages_changed": 1800,
+            "cost_before": 812.4, "cost_after": 640.1, "delta": -172.3,
+            "by_model": [{"model": "...", "messages_changed": 1800, "delta": -172.3, ...}]
+        }
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return reprice_message_history(conn, date_from, date_to, dry_run)
+
+
+def get_cost_centers() -> dict:
+    """
+    Get cost center configuration.
+
+    Returns:
+        {
+            "default_cost_center": {"code": "UNALLOCATED", "name": "Unallocated"},
+            "cost_centers": [{"code": "CC-100", "name": "Engineering", "projects": [...], "tags": [...]}]
+        }
+    """
+    return load_cost_center_config()
+
+
+def set_cost_center(
+    code: str,
+    name: str | None = None,
+    projects: list[str] | None = None,
+    tags: list[str] | None = None
+) -> dict:
+    """
+    Create or update a cost center mapping.
+
+    Args:
+        code: Cost center code
+        name: Display name (optional)
+        projects: Project IDs mapped explicitly (optional, replaces the list)
+        tags: Project tags mapped (optional, replaces the list)
+
+    Returns:
+        Updated cost center configuration
+    """
+    return set_cost_center_config(code, name, projects, tags)
+
+
+def delete_cost_center(code: str) -> dict:
+    """
+    Delete a cost center mapping.
+
+    Args:
+        code: Cost center code
+
+    Returns:
+        Updated cost center configuration
+    """
+    return delete_cost_center_config(code)
+
+
+def get_cost_allocation(month: str) -> dict:
+    """
+    Allocate a month's usage to cost centers.
+
+    Args:
+        month: Month (YYYY-MM)
+
+    Returns:
+        {
+            "month": "2025-06",
+            "range": {"from": "2025-06-01", "to": "2025-06-30"},
+            "cost_centers": [{"code", "name", "cost", "share", "projects": [...]}, ...],
+            "totals": {"cost", "tokens", "messages", "unpriced_messages"},
+            "methodology": ["...", ...]
+        }
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return query_cost_allocation(conn, month)
+
+
+def export_cost_allocation(month: str, fmt: str = "csv") -> dict:
+    """
+    Export a month's cost allocation as CSV or XLSX.
+
+    Args:
+        month: Month (YYYY-MM)
+        fmt: "csv" or "xlsx"
+
+    Returns:
+        Dict with base64-encoded file data, filename, size, mime_type and totals
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return export_cost_allocation_file(conn, month, fmt)
+
+
+def create_snapshot(
+    name: str,
+    date_from: str,
+    date_to: str,
+    project_id: str | None = None
+) -> dict:
+    """
+    Freeze the computed statistics for a range into a named snapshot.
+
+    Args:
+        name: Unique snapshot name (e.g. "2025-Q2")
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        project_id: Optional project filter
+
+    Returns:
+        {"snapshot": {"snapshot_id": 1, "name": "...", "range": {...}, "data": {...}}}
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return {"snapshot": create_report_snapshot(conn, name, date_from, date_to, project_id)}
+
+
+def get_snapshots() -> dict:
+    """
+    List saved snapshots with their frozen totals.
+
+    Returns:
+        {"snapshots": [{"snapshot_id": 1, "name": "...", "range": {...}, "totals": {...}}, ...]}
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return {"snapshots": list_snapshots(conn)}
+
+
+def get_snapshot(snapshot: str) -> dict:
+    """
+    Get a snapshot with its full frozen data.
+
+    Args:
+        snapshot: Snapshot id or name
+
+    Returns:
+        {"snapshot": {..., "data": {"totals", "daily_activity", "timeline", ...}}}
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return {"snapshot": get_report_snapshot(conn, snapshot)}
+
+
+def delete_snapshot(snapshot: str) -> dict:
+    """
+    Delete a snapshot.
+
+    Args:
+        snapshot: Snapshot id or name
+
+    Returns:
+        {"deleted": 1, "name": "..."}
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return delete_report_snapshot(conn, snapshot)
+
+
+def diff_snapshots(base: str, target: str | None = None) -> dict:
+    """
+    Diff a snapshot against another snapshot or against live data.
+
+    Args:
+        base: Snapshot id or name
+        target: Snapshot id or name (None compares with live data for the base range)
+
+    Returns:
+        {"base": {...}, "target": {...}, "diff": {"totals", "models", "daily_activity", "changed"}}
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+        return diff_report_snapshots(conn, base, target)
+
+
+def export_png_report(
+    date_from: str,
+    date_to: str,
+    scale: int = 1,
+    social_format: str | None = None,
+    output_path: str | None = None
+) -> dict:
+    """
+    Generate PNG usage report and return it as base64-encoded string or write it to a file.
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        scale: Pixel density, 1-3 (2 = @2x for retina displays)
+        social_format: Render a social card instead ("twitter" 1200x675, "linkedin" 1200x627);
+            scale is ignored
+        output_path: Write the PNG to this file (atomically) instead of returning its data;
+            large reports then never pass through JSON
+
+    Returns:
+        Dict with filename, size, pixel width/height and scale, plus the base64-encoded
+        PNG data, or the written path when output_path is given. alt_text (one sentence)
+        and long_description (one line per report panel) describe the image for screen
+        readers; both are also embedded in the PNG as its Title and Description.
+    """
+    with get_db_connection() as conn:
+        init_database(conn)
+
+        # Query usage stats
+        stats = query_usage_stats(conn, date_from, date_to)
+
+        # Generate PNG (project names and colors come from project metadata)
+        from command_center.utils.project_metadata import load_projects_json, PROJECTS_JSON_PATH
+        projects_metadata = load_projects_json(PROJECTS_JSON_PATH)
+        description = describe_usage_report(stats, projects_metadata)
+        if social_format:
+            png_bytes = generate_social_card_png(stats, projects_metadata, social_format, description)
+            width, height = SOCIAL_FORMATS[social_format]
+            filename = f"cc-usage-{social_format}-{date_from}_{date_to}.png"
+        else:
+            png_bytes = generate_usage_report_png(stats, projects_metadata, scale, description)
+            width, height = CANVAS_WIDTH * scale, CANVAS_HEIGHT * scale
+            suffix = f"@{scale}x" if scale > 1 else ""
+            filename = f"cc-usage-report-{date_from}_{date_to}{suffix}.png"
+
+        if output_path:
+            write_bytes_atomic(output_path, png_bytes)
+            payload = {"path": output_path}
+        else:
+            payload = {"data": base64.b64encode(png_bytes).decode('utf-8')}
+
+        return {
+            "filename": filename,
+            **payload,
+            "size": len(png_bytes),
+            "width": width,
+            "height": height,
+            "scale": 1 if social_format else scale,
+            "mime_type": "image/png",
+            **description
+        }
+
+
+def get_projects(sort: str = "manual") -> dict:
+    """
+    Get all projects with metadata.
+
+    Args:
+        sort: One of PROJECT_SORTS (manual, last_activity, cost_this_month, name);
+            pinned projects always come first
+
+    Returns:
+        {
+            "projects": [
+                {
+                    "project_id": "-home-xai-DEV-command-center",
+                    "name": "Command Center",
+                    "description": "Analytics tool",
+                    "absolute_path": "/home/xai/DEV/command-center",
+                    "first_seen": "2024-12-27T10:00:00+01:00",
+                    "last_seen": "2024-12-28T01:44:00+01:00",
+                    "visible": true,
+                    "pinned": false,
+                    "sort_order": 0,
+                    "color": "#d97757",
+                    "icon": "📊",
+                    "display_color": "#d97757"
+                },
+                ...
+            ],
+            "sort": "manual"
+        }
+        color and icon are only present when set; display_color falls back to
+        a stable palette color. With sort=cost_this_month each project also
+        has "cost_this_month".
+    """
+    from command_center.utils.project_metadata import (
+        list_all_projects,
+        ensure_visible_field,
+        project_color,
+        sort_projects,
+        PROJECTS_JSON_PATH
+    )
+
+    # Backward compatibility: ensure all projects have visible field
+    ensure_visible_field(PROJECTS_JSON_PATH)
+
+    # Get all projects
+    projects = list_all_projects(PROJECTS_JSON_PATH)
+    for project in projects:
+        project["display_color"] = project_color(project["project_id"], project)
+
+    if sort == "cost_this_month":
+        month_from, month_to = month_bounds(datetime.now().strftime("%Y-%m"))
+        with get_db_connection() as conn:
+            init_database(conn)
+            costs = query_project_costs(conn, month_from, month_to)
+        for project in projects:
+            project["cost_this_month"] = costs.get(project["project_id"], 0.0)
+
+    return {"projects": sort_projects(projects, sort), "sort": sort}
+
+
+def set_project_pinned(project_id: str, pinned: bool) -> dict:
+    """
+    Pin or unpin a project (pinned projects are listed first).
+
+    Returns:
+        {"project": {...}}
+
+    Raises:
+        ValueError: If project not found
+    """
+    from command_center.utils.project_metadata import set_project_pinned as set_pinned
+
+    return {"project": set_pinned(project_id, pinned)}
+
+
+def set_project_order(project_ids: list[str]) -> dict:
+    """
+    Persist the manual project order used by get_projects(sort="manual").
+
+    Args:
+        project_ids: Project IDs in display order (projects left out go last)
+
+    Returns:
+        {"order": [...]}
+
+    Raises:
+        ValueError: If a project is unknown or listed twice
+    """
+    from command_center.utils.project_metadata import set_project_order as store_order
+
+    return {"order": store_order(project_ids)}
+
+
+def update_project(
+    project_id: str,
+    name: str | None = None,
+    description: str | None = None,
+    visible: bool | None = None,
+    tags: list[str] | None = None,
+    color: str | None = None,
+    icon: str | None = None
+) -> dict:
+    """
+    Update project metadata fields.
+
+    Args:
+        project_id: Project identifier
+        name: New display name (optional)
+        description: New description (optional)
+        visible: Visibility flag (optional)
+        tags: Tag list (optional, empty list clears tags)
+        color: Hex color "#rrggbb" used in charts and exports (optional, empty clears)
+        icon: Emoji or short icon name (optional, empty clears)
+
+    Returns:
+        {
+            "project": {
+                "project_id": "...",
+                "name": "...",
+                "description": "...",
+                "visible": true,
+                ...
+            }
+        }
+
+    Raises:
+        ValueError: If project not found or validation fails
+    """
+    from command_center.utils.project_metadata import (
+        update_project_fields,
+        PROJECTS_JSON_PATH
+    )
+
+    # Call validation and update function
+    updated_project = update_project_fields(
+        project_id=project_id,
+        name=name,
+        description=description,
+        visible=visible,
+        tags=tags,
+        color=color,
+        icon=icon,
+        json_path=PROJECTS_JSON_PATH
+    )
+
+    return {"project": updated_project}
+
+
+def suggest_project_description(project_id: str) -> dict:
+    """
+    Suggest a project description from the project's recent sessions.
+
+    The suggestion is not applied; the user approves it via update_project.
+
+    Args:
+        project_id: Project identifier
+
+    Returns:
+        {
+            "project_id", "suggestion": "Python project: ..." | null,
+            "source": "local" | "llm", "current_description": "...",
+            "keywords": [...], "languages": [...], "files": [...],
+            "sessions_analyzed": 12, "llm_error": "..." (only if the LLM call failed)
+        }
+
+    Raises:
+        ValueError: If the project does not exist or project names are blurred
+    """
+    from command_center.project_descriptions import suggest_project_description as suggest
+    from command_center.utils.project_metadata import load_projects_json, PROJECTS_JSON_PATH
+
+    if blur_projects_enabled():
+        raise ValueError("Description suggestions are not available while project names are blurred")
+    projects = load_projects_json(PROJECTS_JSON_PATH)
+    if project_id not in projects:
+        raise ValueError(f"Project not found: {project_id}. Run a scan first to discover projects.")
+
+    with get_db_connection() as conn:
+        init_database(conn)
+        return suggest(conn, project_id, projects[project_id])
+
+
+def get_project_timeline(project_id: str, granularity: str = "auto") -> dict:
+    """
+    Lifetime timeline of a project for its profile page.
+
+    Args:
+        project_id: Project identifier
+        granularity: Curve resolution: 'day', 'week', 'month' or 'auto'
+
+    Returns:
+        {
+            "project": {project metadata, "display_color"},
+            "project_id", "granularity",
+            "first_session", "last_session", "totals",
+            "curve": [{"period", "messages", "sessions", "tokens", "cost", "cumulative_cost"}, ...],
+            "milestones": [{"type", "date", ...}, ...]
+        }
+
+    Raises:
+        ValueError: If the project does not exist or the granularity is invalid
+    """
+    from command_center.project_timeline import query_project_timeline
+    from command_center.utils.project_metadata import load_projects_json, project_color, PROJECTS_JSON_PATH
+
+    projects = load_projects_json(PROJECTS_JSON_PATH)
+    if project_id not in projects:
+        raise ValueError(f"Project not found: {project_id}. Run a scan first to discover projects.")
+    project = {"project_id": project_id, **projects[project_id]}
+    project["display_color"] = project_color(project_id, project)
+
+    with get_db_connection() as conn:
+        init_database(conn)
+        return {"project": project, **query_project_timeline(conn, project_id, granularity)}
+
+
+def backfill_project_first_seen() -> dict:
+    """
+    Correct first_seen of all projects to their first session in the database.
+
+    first_seen used to record when a scan discovered a project; scans now
+    backfill new projects, this fixes projects discovered before that.
+
+    Returns:
+        {"updated": [{"project_id", "previous", "first_seen"}, ...], "checked": 12}
+    """
+    from command_center.project_timeline import backfill_first_seen
+    from command_center.utils.project_metadata import PROJECTS_JSON_PATH
+
+    with get_db_connection() as conn:
+        init_database(conn)
+        return backfill_first_seen(conn, json_path=PROJECTS_JSON_PATH)
+
+
+def get_stale_projects(inactive_days: int = 90) -> dict:
+    """
+    List projects without activity for N days, with their storage footprint.
+
+    Args:
+        inactive_days: Inactivity threshold in days
+
+    Returns:
+        {
+            "inactive_days": 90,
+            "projects": [{"project_id", "name", "last_activity", "days_inactive",
+                          "messages", "sessions", "cost", "source_files", "source_bytes",
+                          "mirror_bytes", "suggestion": "archive" | None, "reason", ...}, ...],
+            "totals": {"projects", "suggested", "source_bytes", "mirror_bytes"}
+        }
+
+    Raises:
+        ValueError: If inactive_days is negative
+    """
+    from command_center.stale_projects import query_stale_projects
+    from command_center.utils.project_metadata import load_projects_json, PROJECTS_JSON_PATH
+
+    projects = load_projects_json(PROJECTS_JSON_PATH)
+    with get_db_connection() as conn:
+        init_database(conn)
+        return query_stale_projects(conn, projects, inactive_days)
+
+
+def archive_projects(project_ids: list[str]) -> dict:
+    """
+    Archive projects: hide them from the project selector and unpin them.
+
+    Usage data is kept; set visible again with update_project to restore.
+
+    Returns:
+        {"projects": [{...}, ...]}
+
+    Raises:
+        ValueError: If a project is unknown
+    """
+    from command_center.utils.project_metadata import archive_projects as archive, PROJECTS_JSON_PATH
+
+    return {"projects": archive(project_ids, PROJECTS_JSON_PATH)}
+
+
+def get_workspaces() -> dict:
+    """
+    Get all workspaces with their projects.
+
+    Returns:
+        {"workspaces": [{"workspace_id", "name", "description", "created_at", "projects": [...]}, ...]}
+    """
+    return {"workspaces": list_workspaces()}
+
+
+def create_workspace(name: str, description: str | None = None) -> dict:
+    """
+    Create a workspace (a group of projects).
+
+    Args:
+        name: Display name (unique)
+        description: Optional description
+
+    Returns:
+        {"workspace": {"workspace_id", "name", "description", "created_at", "projects": []}}
+
+    Raises:
+        ValueError: If the name is empty or already used
+    """
+    return {"workspace": create_workspace_config(name, description or "")}
+
+
+def delete_workspace(workspace_id: str) -> dict:
+    """
+    Delete a workspace; its projects become unassigned.
+
+    Returns:
+        {"deleted": "...", "unassigned_projects": 3}
+    """
+    return delete_workspace_config(workspace_id)
+
+
+def assign_project_to_workspace(project_id: str, workspace_id: str | None) -> dict:
+    """
+    Move a project into a workspace (None removes it from its workspace).
+
+    Returns:
+        {"project_id", "workspace_id", "previous_workspace_id"}
+
+    Raises:
+        ValueError: If the project or workspace does not exist
+    """
+    return assign_project_workspace(project_id, workspace_id)
+
+# Subcommands that change stored data or configuration; recorded in the audit log
+AUDITED_COMMANDS = {
+    "update-usage-account", "bind-claude-account", "detect-plan", "set-budget",
+    "set-cost-center", "delete-cost-center", "sync-prices", "set-price", "reprice",
+    "create-snapshot", "delete-snapshot", "update-project", "clear-mirror",
+    "import-session-archive", "set-hook", "delete-hook", "test-hook",
+    "set-derived-metric", "delete-derived-metric", "restore-backup",
+    "create-workspace", "delete-workspace", "assign-project-workspace",
+    "set-project-pinned", "set-project-order", "backfill-first-seen",
+    "archive-projects", "merge-data", "shift-timestamps", "undo-timestamp-shift",
+    "set-calendar-overlay", "set-working-hours",
+}
+
+
+def _is_audited(args: argparse.Namespace) -> bool:
+    if args.command in ("reprice", "restore-backup", "merge-data"):
+        return not args.dry_run
+    if args.command in ("shift-timestamps", "undo-timestamp-shift"):
+        return not args.preview
+    if args.command == "detect-plan":
+        return bool(args.apply)
+    return args.command in AUDITED_COMMANDS
+
+
+def _audit(args: argparse.Namespace, request_id: str | None, error: str | None = None):
+    """Record an audited command; a failing audit log never fails the command."""
+    if not _is_audited(args):
+        return
+    params = {k: v for k, v in vars(args).items() if k not in ("command", "request_id")}
+    try:
+        record_audit_event(args.command, params, request_id, error)
+    except OSError:
+        pass
+
+
+def main(argv: list[str] | None = None):
+    """
+    CLI entry point for Tauri API.
+
+    Args:
+        argv: Command-line arguments (default: sys.argv[1:]); ``--stdin-json``
+            alone reads them as a JSON request from stdin (see api_worker)
+    """
+    if argv is None:
+        argv = sys.argv[1:]
+    if argv == [STDIN_JSON_FLAG]:
+        try:
+            argv = read_stdin_request(sys.stdin)
+        except ValueError as e:
+            print(json.dumps({"error": f"Invalid stdin request: {e}", "type": "ValueError"}), file=sys.stderr)
+            sys.exit(2)
+
+    parser = argparse.ArgumentParser(
+        prog="command_center.tauri_api",
+        description="JSON API for Tauri desktop dashboard"
+    )
+    parser.add_argument(
+        "--request-id", dest="request_id", required=False,
+        help="Correlation ID for logs and errors (default: $COMMAND_CENTER_REQUEST_ID)"
+    )
+    subparsers = parser.add_subparsers(dest="command", required=True)
+
+    # dashboard subcommand
+    dash_parser = subparsers.add_parser(
+        "dashboard",
+        help="Get complete dashboard bundle"
+    )
+    dash_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    dash_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
     dash_parser.add_argument(
         "--refresh", type=int, default=0,
         help="Perform incremental update (0 or 1)"
     )
-    dash_parser.add_argument(
-        "--granularity", default="month",
+    dash_parser.add_argument(
+        "--granularity", default="month",
+        choices=["month", "week", "day", "hour"],
+        help="Timeline granularity"
+    )
+    dash_parser.add_argument(
+        "--project-id", dest="project_id", required=False, default=None,
+        help="Filter by project (optional)"
+    )
+    dash_parser.add_argument(
+        "--workspace-id", dest="workspace_id", required=False, default=None,
+        help="Filter by workspace (optional)"
+    )
+    dash_parser.add_argument(
+        "--sections", required=False, default=None,
+        help="Comma-separated sections to compute: totals,timeline,models,hourly,sessions (default: all)"
+    )
+
+    # refresh subcommand
+    refresh_parser = subparsers.add_parser(
+        "refresh",
+        help="Run incremental update only"
+    )
+    refresh_parser.add_argument(
+        "--since", required=False,
+        help="Fast path: only ingest changes since this ISO timestamp"
+    )
+    refresh_parser.add_argument(
+        "--since-watermark", type=int, default=0,
+        help="Fast path: only ingest changes since the last scan (0 or 1)"
+    )
+    refresh_parser.add_argument(
+        "--today", type=int, default=0,
+        help="Fast path: only ingest changes since local midnight (0 or 1)"
+    )
+
+    # refresh-status subcommand
+    subparsers.add_parser(
+        "refresh-status",
+        help="Get last refresh, refresh progress and pending files per data root"
+    )
+
+    # quarantined-lines subcommand
+    quarantine_parser = subparsers.add_parser(
+        "quarantined-lines",
+        help="List malformed JSONL lines skipped during ingestion"
+    )
+    quarantine_parser.add_argument(
+        "--limit", type=int, default=100,
+        help="Maximum number of lines (default: 100)"
+    )
+    quarantine_parser.add_argument(
+        "--file", dest="source_file", required=False,
+        help="Only lines of this session file"
+    )
+
+    # day subcommand
+    day_parser = subparsers.add_parser(
+        "day",
+        help="Get day details"
+    )
+    day_parser.add_argument(
+        "--date", required=True,
+        help="Date (YYYY-MM-DD)"
+    )
+    day_parser.add_argument(
+        "--project-id", dest="project_id", required=False, default=None,
+        help="Filter by project (optional)"
+    )
+
+    # aggregate-history subcommand
+    aggregate_history_parser = subparsers.add_parser(
+        "aggregate-history",
+        help="Recorded versions of a day's totals (after ingests, reprices, merges, time shifts)"
+    )
+    aggregate_history_parser.add_argument(
+        "--date", required=True,
+        help="Date (YYYY-MM-DD)"
+    )
+
+    # model subcommand
+    model_parser = subparsers.add_parser(
+        "model",
+        help="Get model details"
+    )
+    model_parser.add_argument(
+        "--model", required=True,
+        help="Model identifier"
+    )
+    model_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    model_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+    model_parser.add_argument(
+        "--project-id", dest="project_id", required=False, default=None,
+        help="Filter by project (optional)"
+    )
+
+    # session subcommand
+    session_parser = subparsers.add_parser(
+        "session",
+        help="Get session details"
+    )
+    session_parser.add_argument(
+        "--id", dest="session_id", required=True,
+        help="Session identifier"
+    )
+    session_parser.add_argument(
+        "--project-id", dest="project_id", required=False, default=None,
+        help="Filter by project (optional)"
+    )
+
+    # session-files subcommand
+    session_files_parser = subparsers.add_parser(
+        "session-files",
+        help="Locate the raw JSONL files of a session"
+    )
+    session_files_parser.add_argument(
+        "--id", dest="session_id", required=True,
+        help="Session identifier"
+    )
+
+    # plugin-records subcommand
+    plugin_records_parser = subparsers.add_parser(
+        "plugin-records",
+        help="Get normalized message records for metric plugins"
+    )
+    plugin_records_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    plugin_records_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+    plugin_records_parser.add_argument(
+        "--project-id", dest="project_id",
+        help="Filter by project (optional)"
+    )
+
+    # export-raw-sessions subcommand
+    export_raw_parser = subparsers.add_parser(
+        "export-raw-sessions",
+        help="Bundle the raw JSONL files of sessions into a zip archive"
+    )
+    export_raw_parser.add_argument(
+        "--id", dest="session_ids", action="append", required=True,
+        help="Session identifier (repeat for several sessions)"
+    )
+    export_raw_parser.add_argument(
+        "--output", required=True,
+        help="Path of the zip file to write"
+    )
+    export_raw_parser.add_argument(
+        "--redact", action="store_true",
+        help="Strip conversation content (prompts, responses, tool I/O, paths)"
+    )
+
+    # create-backup subcommand
+    create_backup_parser = subparsers.add_parser(
+        "create-backup",
+        help="Write a signed backup of settings, project metadata and the database"
+    )
+    create_backup_parser.add_argument("--output", required=True, help="Path of the zip file to write")
+
+    # verify-backup subcommand
+    verify_backup_parser = subparsers.add_parser(
+        "verify-backup",
+        help="Check a backup's checksums and signature"
+    )
+    verify_backup_parser.add_argument("--path", dest="backup_path", required=True, help="Backup archive")
+
+    # restore-backup subcommand
+    restore_backup_parser = subparsers.add_parser(
+        "restore-backup",
+        help="Restore a verified backup"
+    )
+    restore_backup_parser.add_argument("--path", dest="backup_path", required=True, help="Backup archive")
+    restore_backup_parser.add_argument(
+        "--force", action="store_true",
+        help="Accept a backup signed by another key (checksums must still match)"
+    )
+    restore_backup_parser.add_argument(
+        "--components", required=False, default=None,
+        help="Comma-separated components to restore: settings,projects,cache (default: all)"
+    )
+    restore_backup_parser.add_argument(
+        "--dry-run", dest="dry_run", type=int, choices=[0, 1], default=0,
+        help="Only report what would change (0 or 1)"
+    )
+
+    # merge-data subcommand
+    merge_data_parser = subparsers.add_parser(
+        "merge-data",
+        help="Merge a backup, database or data root from another machine without double counting"
+    )
+    merge_data_parser.add_argument(
+        "--path", required=True,
+        help="Backup archive, command_center.db file or Claude config directory"
+    )
+    merge_data_parser.add_argument(
+        "--dry-run", dest="dry_run", type=int, choices=[0, 1], default=0,
+        help="Only report what would change (0 or 1)"
+    )
+
+    # shift-timestamps subcommand
+    shift_parser = subparsers.add_parser(
+        "shift-timestamps",
+        help="Move message timestamps logged with a wrong clock and rebuild aggregates"
+    )
+    shift_parser.add_argument(
+        "--offset", required=True,
+        help="Offset like -1d, +2h30m or seconds"
+    )
+    shift_parser.add_argument("--from", dest="date_from", help="First local date (YYYY-MM-DD)")
+    shift_parser.add_argument("--to", dest="date_to", help="Last local date (YYYY-MM-DD)")
+    shift_parser.add_argument(
+        "--session-ids", dest="session_ids",
+        help="Comma-separated session IDs"
+    )
+    shift_parser.add_argument("--project-id", dest="project_id", help="Only this project")
+    shift_parser.add_argument("--config-dir", dest="config_dir", help="Only this Claude config directory")
+    shift_parser.add_argument(
+        "--preview", type=int, choices=[0, 1], default=0,
+        help="Only report what would move (0 or 1)"
+    )
+
+    # undo-timestamp-shift subcommand
+    undo_shift_parser = subparsers.add_parser(
+        "undo-timestamp-shift",
+        help="Undo a timestamp shift"
+    )
+    undo_shift_parser.add_argument("--shift-id", dest="shift_id", type=int, required=True)
+    undo_shift_parser.add_argument(
+        "--preview", type=int, choices=[0, 1], default=0,
+        help="Only report what would move back (0 or 1)"
+    )
+
+    # timestamp-shifts subcommand
+    subparsers.add_parser("timestamp-shifts", help="List recorded timestamp shifts")
+
+    # import-session-archive subcommand
+    import_archive_parser = subparsers.add_parser(
+        "import-session-archive",
+        help="Import a session archive into a project (excluded from overall totals)"
+    )
+    import_archive_parser.add_argument(
+        "--path", dest="archive_path", required=True,
+        help="Path of the zip archive"
+    )
+    import_archive_parser.add_argument(
+        "--project", dest="target_project", required=False, default=None,
+        help="Project ID to import into (default: new imported-<archive name> project)"
+    )
+
+    # sessions subcommand (cursor-paginated session list)
+    sessions_parser = subparsers.add_parser(
+        "sessions",
+        help="List sessions page by page"
+    )
+    sessions_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    sessions_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+    sessions_parser.add_argument(
+        "--project-id", dest="project_id", required=False, default=None,
+        help="Filter by project (optional)"
+    )
+    sessions_parser.add_argument(
+        "--model", required=False, default=None,
+        help="Only sessions that used this model (optional)"
+    )
+    sessions_parser.add_argument(
+        "--search", required=False, default=None,
+        help="Session ID substring (optional)"
+    )
+    sessions_parser.add_argument(
+        "--sort", choices=["cost", "tokens", "messages", "recent", "oldest"], default="cost",
+        help="Sort order (default: cost)"
+    )
+    sessions_parser.add_argument(
+        "--cursor", required=False, default=None,
+        help="next_cursor of the previous page"
+    )
+    sessions_parser.add_argument(
+        "--page-size", dest="page_size", type=int, default=100,
+        help="Rows per page (default: 100, max: 500)"
+    )
+
+    # limits subcommand
+    limits_parser = subparsers.add_parser(
+        "limits",
+        help="Get limit reset events"
+    )
+    limits_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    limits_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+
+    # limit-impact subcommand
+    limit_impact_parser = subparsers.add_parser(
+        "limit-impact",
+        help="Analyze limit hits and lockout time"
+    )
+    limit_impact_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    limit_impact_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+
+    # mcp-usage subcommand
+    mcp_usage_parser = subparsers.add_parser(
+        "mcp-usage",
+        help="Calls, tokens and failures per MCP server for a date range"
+    )
+    mcp_usage_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    mcp_usage_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+    mcp_usage_parser.add_argument(
+        "--project-id", dest="project_id", required=False, default=None,
+        help="Filter by project (optional)"
+    )
+
+    # hook-stats subcommand
+    hook_stats_parser = subparsers.add_parser(
+        "hook-stats",
+        help="Claude Code hook executions, durations and failures for a date range"
+    )
+    hook_stats_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    hook_stats_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+    hook_stats_parser.add_argument(
+        "--project-id", dest="project_id", required=False, default=None,
+        help="Filter by project (optional)"
+    )
+
+    # effort-breakdown subcommand
+    effort_breakdown_parser = subparsers.add_parser(
+        "effort-breakdown",
+        help="Cost and latency by effort/thinking setting for a model and date range"
+    )
+    effort_breakdown_parser.add_argument(
+        "--model", required=False, default=None,
+        help="Model name (default: all models)"
+    )
+    effort_breakdown_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    effort_breakdown_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+    effort_breakdown_parser.add_argument(
+        "--project-id", dest="project_id", required=False, default=None,
+        help="Filter by project (optional)"
+    )
+
+    # concurrency-stats subcommand
+    concurrency_parser = subparsers.add_parser(
+        "concurrency-stats",
+        help="Concurrent sessions and their token impact for a date range"
+    )
+    concurrency_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    concurrency_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+    concurrency_parser.add_argument(
+        "--idle-gap", dest="idle_gap", type=int, default=DEFAULT_IDLE_GAP_MINUTES,
+        help=f"Minutes without messages that end an activity segment (default: {DEFAULT_IDLE_GAP_MINUTES})"
+    )
+
+    # outcome-stats subcommand
+    outcome_parser = subparsers.add_parser(
+        "outcome-stats",
+        help="Session outcomes (commit, test pass, tool error, interrupt) for a date range"
+    )
+    outcome_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    outcome_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+    outcome_parser.add_argument(
+        "--project-id", dest="project_id", required=False, default=None,
+        help="Filter by project (optional)"
+    )
+    outcome_parser.add_argument(
+        "--classify", type=int, choices=[0, 1], default=1,
+        help="Classify new and changed sessions first (0 or 1)"
+    )
+    outcome_parser.add_argument(
+        "--reclassify", action="store_true",
+        help="Re-read every session of the range"
+    )
+
+    # compaction-stats subcommand
+    compaction_parser = subparsers.add_parser(
+        "compaction-stats",
+        help="Context compaction frequency and token impact for a date range"
+    )
+    compaction_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    compaction_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+    compaction_parser.add_argument(
+        "--project-id", dest="project_id", required=False, default=None,
+        help="Filter by project (optional)"
+    )
+    compaction_parser.add_argument(
+        "--scan", type=int, choices=[0, 1], default=1,
+        help="Scan new and changed sessions first (0 or 1)"
+    )
+    compaction_parser.add_argument(
+        "--rescan", action="store_true",
+        help="Re-read every session of the range"
+    )
+
+    # usage-accounts subcommand
+    usage_accounts_parser = subparsers.add_parser(
+        "usage-accounts",
+        help="Get latest cc_usage accounts"
+    )
+
+    # update-usage-account subcommand
+    update_account_parser = subparsers.add_parser(
+        "update-usage-account",
+        help="Update usage account metadata"
+    )
+    update_account_parser.add_argument(
+        "--email", required=True,
+        help="Account email"
+    )
+    update_account_parser.add_argument(
+        "--label", required=False,
+        help="Display label"
+    )
+    update_account_parser.add_argument(
+        "--color", required=False,
+        help="Hex color (#RRGGBB)"
+    )
+    update_account_parser.add_argument(
+        "--plan-type", dest="plan_type", required=False,
+        help="Plan type (pro, max_5x, max_20x, team, enterprise, api)"
+    )
+
+    # compare-accounts subcommand
+    compare_accounts_parser = subparsers.add_parser(
+        "compare-accounts",
+        help="Compare usage accounts for a date range"
+    )
+    compare_accounts_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    compare_accounts_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+    compare_accounts_parser.add_argument(
+        "--email", dest="emails", action="append", default=None,
+        help="Account email to include (repeatable, default: all)"
+    )
+
+    # account-dashboard subcommand
+    account_dashboard_parser = subparsers.add_parser(
+        "account-dashboard",
+        help="Usage of one account for a date range"
+    )
+    account_dashboard_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    account_dashboard_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+    account_dashboard_parser.add_argument(
+        "--email", required=True,
+        help="Usage account email"
+    )
+
+    # claude-accounts subcommand
+    claude_accounts_parser = subparsers.add_parser(
+        "claude-accounts",
+        help="Detect Claude config directories and account bindings"
+    )
+
+    # bind-claude-account subcommand
+    bind_account_parser = subparsers.add_parser(
+        "bind-claude-account",
+        help="Bind a Claude config directory to a usage account"
+    )
+    bind_account_parser.add_argument(
+        "--config-dir", dest="config_dir", required=True,
+        help="Claude config directory path"
+    )
+    bind_account_parser.add_argument(
+        "--email", required=False, default=None,
+        help="Usage account email (omit to unbind)"
+    )
+
+    # detect-plan subcommand
+    detect_plan_parser = subparsers.add_parser(
+        "detect-plan",
+        help="Detect subscription plans from local Claude files"
+    )
+    detect_plan_parser.add_argument(
+        "--apply", type=int, choices=[0, 1], default=1,
+        help="Store detected plans on bound accounts (0 or 1)"
+    )
+
+    # budget-history subcommand
+    budget_history_parser = subparsers.add_parser(
+        "budget-history",
+        help="Get monthly budget history"
+    )
+
+    # set-budget subcommand
+    set_budget_parser = subparsers.add_parser(
+        "set-budget",
+        help="Set monthly budget"
+    )
+    set_budget_parser.add_argument(
+        "--amount", type=float, required=False, default=None,
+        help="Monthly budget in USD (omit to clear)"
+    )
+    set_budget_parser.add_argument(
+        "--effective-from", dest="effective_from", required=False, default=None,
+        help="First month the budget applies to (YYYY-MM)"
+    )
+
+    # hooks subcommand
+    subparsers.add_parser(
+        "hooks",
+        help="List lifecycle hooks and recent runs"
+    )
+
+    # set-hook subcommand
+    set_hook_parser = subparsers.add_parser(
+        "set-hook",
+        help="Create or update a lifecycle hook"
+    )
+    set_hook_parser.add_argument(
+        "--event", required=True,
+        choices=["refresh-completed", "day-rolled-over", "month-completed", "budget-threshold"],
+        help="Event that fires the hook"
+    )
+    set_hook_parser.add_argument(
+        "--command", dest="hook_command", required=False, default="",
+        help="Shell command template, e.g. 'echo {date},{cost} >> ~/ledger.csv'"
+    )
+    set_hook_parser.add_argument(
+        "--action", required=False, default="command", choices=["command", "export"],
+        help="export: write the finished month's files to --folder (month-completed only)"
+    )
+    set_hook_parser.add_argument(
+        "--folder", required=False, default=None,
+        help="Folder an export hook writes to"
+    )
+    set_hook_parser.add_argument(
+        "--format", dest="formats", action="append", choices=["csv", "pdf"], default=None,
+        help="File an export hook writes (repeatable; default: csv and pdf)"
+    )
+    set_hook_parser.add_argument(
+        "--timeout", type=int, required=False, default=None,
+        help="Seconds before the command is killed (default: 30)"
+    )
+    set_hook_parser.add_argument(
+        "--id", dest="hook_id", required=False, default=None,
+        help="Hook to create or replace (default: a new random id)"
+    )
+    set_hook_parser.add_argument(
+        "--disabled", action="store_true",
+        help="Store the hook without running it"
+    )
+
+    # delete-hook subcommand
+    delete_hook_parser = subparsers.add_parser(
+        "delete-hook",
+        help="Delete a lifecycle hook"
+    )
+    delete_hook_parser.add_argument(
+        "--id", dest="hook_id", required=True,
+        help="Hook identifier"
+    )
+
+    # test-hook subcommand
+    test_hook_parser = subparsers.add_parser(
+        "test-hook",
+        help="Run a lifecycle hook once with a sample event"
+    )
+    test_hook_parser.add_argument(
+        "--id", dest="hook_id", required=True,
+        help="Hook identifier"
+    )
+
+    # derived-metrics subcommand
+    subparsers.add_parser(
+        "derived-metrics",
+        help="List user-defined derived metrics"
+    )
+
+    # available-metrics subcommand
+    subparsers.add_parser(
+        "available-metrics",
+        help="List registered and derived metrics for metric pickers"
+    )
+
+    # metric-series subcommand
+    metric_series_parser = subparsers.add_parser(
+        "metric-series",
+        help="Get chosen metrics per period"
+    )
+    metric_series_parser.add_argument(
+        "--from", dest="date_from", required=True,
+        help="Start date (YYYY-MM-DD)"
+    )
+    metric_series_parser.add_argument(
+        "--to", dest="date_to", required=True,
+        help="End date (YYYY-MM-DD)"
+    )
+    metric_series_parser.add_argument(
+        "--granularity", default="day",
         choices=["month", "week", "day", "hour"],
-        help="Timeline granularity"
+        help="Period length"
     )
-    dash_parser.add_argument(
+    metric_series_parser.add_argument(
+        "--metrics", required=True,
+        help="Comma-separated metric names, e.g. cost,tool_calls"
+    )
+    metric_series_parser.add_argument(
         "--project-id", dest="project_id", required=False, default=None,
         help="Filter by project (optional)"
     )
 
-    # day subcommand
-    day_parser = subparsers.add_parser(
-        "day",
-        help="Get day details"
+    # set-derived-metric subcommand
+    set_derived_parser = subparsers.add_parser(
+        "set-derived-metric",
+        help="Create or replace a derived metric"
     )
-    day_parser.add_argument(
-        "--date", required=True,
-        help="Date (YYYY-MM-DD)"
+    set_derived_parser.add_argument(
+        "--name", required=True,
+        help="Metric name, e.g. cost_per_message"
     )
-    day_parser.add_argument(
-        "--project-id", dest="project_id", required=False, default=None,
-        help="Filter by project (optional)"
+    set_derived_parser.add_argument(
+        "--expression", required=True,
+        help="Formula, e.g. 'cost / messages'"
+    )
+    set_derived_parser.add_argument(
+        "--description", required=False, default=None,
+        help="Label shown in the UI (optional)"
     )
 
-    # model subcommand
-    model_parser = subparsers.add_parser(
-        "model",
-        help="Get model details"
+    # delete-derived-metric subcommand
+    delete_derived_parser = subparsers.add_parser(
+        "delete-derived-metric",
+        help="Delete a derived metric"
     )
-    model_parser.add_argument(
-        "--model", required=True,
-        help="Model identifier"
+    delete_derived_parser.add_argument(
+        "--name", required=True,
+        help="Metric name"
     )
-    model_parser.add_argument(
+
+    # calendar-overlay subcommand
+    subparsers.add_parser(
+        "calendar-overlay",
+        help="Get vacation/holiday periods excluded from averages and streaks"
+    )
+
+    # set-calendar-overlay subcommand
+    set_calendar_parser = subparsers.add_parser(
+        "set-calendar-overlay",
+        help="Replace vacation/holiday periods excluded from averages and streaks"
+    )
+    set_calendar_parser.add_argument(
+        "--periods", required=True,
+        help='JSON list, e.g. \'[{"from": "2025-07-01", "to": "2025-07-14", "kind": "vacation"}]\''
+    )
+
+    # working-hours subcommand
+    subparsers.add_parser(
+        "working-hours",
+        help="Get the weekdays and hours counted as work time"
+    )
+
+    # set-working-hours subcommand
+    set_working_hours_parser = subparsers.add_parser(
+        "set-working-hours",
+        help="Set the weekdays and hours counted as work time"
+    )
+    set_working_hours_parser.add_argument(
+        "--days", required=True,
+        help="Comma-separated ISO weekdays, e.g. 1,2,3,4,5 (1 = Monday); empty for none"
+    )
+    set_working_hours_parser.add_argument(
+        "--start", required=True,
+        help="Start of the work window (HH:MM)"
+    )
+    set_working_hours_parser.add_argument(
+        "--end", required=True,
+        help="End of the work window (HH:MM, exclusive)"
+    )
+
+    # out-of-hours-usage subcommand
+    out_of_hours_parser = subparsers.add_parser(
+        "out-of-hours-usage",
+        help="Split usage of a date range into work and personal time"
+    )
+    out_of_hours_parser.add_argument(
         "--from", dest="date_from", required=True,
         help="Start date (YYYY-MM-DD)"
     )
-    model_parser.add_argument(
+    out_of_hours_parser.add_argument(
         "--to", dest="date_to", required=True,
         help="End date (YYYY-MM-DD)"
     )
-    model_parser.add_argument(
+    out_of_hours_parser.add_argument(
         "--project-id", dest="project_id", required=False, default=None,
         help="Filter by project (optional)"
     )
 
-    # session subcommand
-    session_parser = subparsers.add_parser(
-        "session",
-        help="Get session details"
+    # cost-centers subcommand
+    cost_centers_parser = subparsers.add_parser(
+        "cost-centers",
+        help="Get cost center configuration"
     )
-    session_parser.add_argument(
-        "--id", dest="session_id", required=True,
-        help="Session identifier"
+
+    # set-cost-center subcommand
+    set_cost_center_parser = subparsers.add_parser(
+        "set-cost-center",
+        help="Create or update a cost center mapping"
     )
-    session_parser.add_argument(
-        "--project-id", dest="project_id", required=False, default=None,
-        help="Filter by project (optional)"
+    set_cost_center_parser.add_argument(
+        "--code", required=True,
+        help="Cost center code"
+    )
+    set_cost_center_parser.add_argument(
+        "--name", required=False, default=None,
+        help="Display name"
+    )
+    set_cost_center_parser.add_argument(
+        "--projects", required=False, default=None,
+        help="Comma-separated project IDs (empty string clears)"
+    )
+    set_cost_center_parser.add_argument(
+        "--tags", required=False, default=None,
+        help="Comma-separated project tags (empty string clears)"
     )
 
-    # limits subcommand
-    limits_parser = subparsers.add_parser(
-        "limits",
-        help="Get limit reset events"
+    # delete-cost-center subcommand
+    delete_cost_center_parser = subparsers.add_parser(
+        "delete-cost-center",
+        help="Delete a cost center mapping"
     )
-    limits_parser.add_argument(
+    delete_cost_center_parser.add_argument(
+        "--code", required=True,
+        help="Cost center code"
+    )
+
+    # cost-allocation subcommand
+    cost_allocation_parser = subparsers.add_parser(
+        "cost-allocation",
+        help="Allocate a month's usage to cost centers"
+    )
+    cost_allocation_parser.add_argument(
+        "--month", required=True,
+        help="Month (YYYY-MM)"
+    )
+
+    # export-cost-allocation subcommand
+    export_allocation_parser = subparsers.add_parser(
+        "export-cost-allocation",
+        help="Export a month's cost allocation as CSV or XLSX"
+    )
+    export_allocation_parser.add_argument(
+        "--month", required=True,
+        help="Month (YYYY-MM)"
+    )
+    export_allocation_parser.add_argument(
+        "--format", dest="fmt", choices=["csv", "xlsx"], default="csv",
+        help="Export format"
+    )
+
+    # price-versions subcommand
+    price_versions_parser = subparsers.add_parser(
+        "price-versions",
+        help="List effective-dated model price versions"
+    )
+    price_versions_parser.add_argument(
+        "--model", required=False, default=None,
+        help="Filter by model (optional)"
+    )
+
+    # sync-prices subcommand
+    sync_prices_parser = subparsers.add_parser(
+        "sync-prices",
+        help="Record current LiteLLM rates as price versions"
+    )
+    sync_prices_parser.add_argument(
+        "--effective-from", dest="effective_from", required=False, default=None,
+        help="Start date for changed rates (YYYY-MM-DD, default: today)"
+    )
+
+    # set-price subcommand
+    set_price_parser = subparsers.add_parser(
+        "set-price",
+        help="Record a manual price version for a model"
+    )
+    set_price_parser.add_argument(
+        "--model", required=True,
+        help="Model name"
+    )
+    set_price_parser.add_argument(
+        "--effective-from", dest="effective_from", required=True,
+        help="First date the rates apply to (YYYY-MM-DD)"
+    )
+    set_price_parser.add_argument(
+        "--input", dest="input_per_mtok", type=float, required=True,
+        help="Input price in USD per million tokens"
+    )
+    set_price_parser.add_argument(
+        "--output", dest="output_per_mtok", type=float, required=True,
+        help="Output price in USD per million tokens"
+    )
+    set_price_parser.add_argument(
+        "--cache-write", dest="cache_write_per_mtok", type=float, required=False, default=None,
+        help="Cache write price in USD per million tokens (default: 1.25x input)"
+    )
+    set_price_parser.add_argument(
+        "--cache-read", dest="cache_read_per_mtok", type=float, required=False, default=None,
+        help="Cache read price in USD per million tokens (default: 0.1x input)"
+    )
+
+    # reprice subcommand
+    reprice_parser = subparsers.add_parser(
+        "reprice",
+        help="Recompute stored costs from price versions"
+    )
+    reprice_parser.add_argument(
+        "--from", dest="date_from", required=False, default=None,
+        help="Start date (YYYY-MM-DD, optional)"
+    )
+    reprice_parser.add_argument(
+        "--to", dest="date_to", required=False, default=None,
+        help="End date (YYYY-MM-DD, optional)"
+    )
+    reprice_parser.add_argument(
+        "--dry-run", dest="dry_run", type=int, choices=[0, 1], default=0,
+        help="Report deltas without writing (0 or 1)"
+    )
+
+    # create-snapshot subcommand
+    create_snapshot_parser = subparsers.add_parser(
+        "create-snapshot",
+        help="Freeze statistics for a range into a named snapshot"
+    )
+    create_snapshot_parser.add_argument(
+        "--name", required=True,
+        help="Unique snapshot name"
+    )
+    create_snapshot_parser.add_argument(
         "--from", dest="date_from", required=True,
         help="Start date (YYYY-MM-DD)"
     )
-    limits_parser.add_argument(
+    create_snapshot_parser.add_argument(
         "--to", dest="date_to", required=True,
         help="End date (YYYY-MM-DD)"
     )
+    create_snapshot_parser.add_argument(
+        "--project-id", dest="project_id", required=False, default=None,
+        help="Filter by project ID (optional)"
+    )
 
-    # usage-accounts subcommand
-    usage_accounts_parser = subparsers.add_parser(
-        "usage-accounts",
-        help="Get latest cc_usage accounts"
+    # snapshots subcommand
+    snapshots_parser = subparsers.add_parser(
+        "snapshots",
+        help="List saved snapshots"
+    )
+
+    # snapshot subcommand
+    snapshot_parser = subparsers.add_parser(
+        "snapshot",
+        help="Get a snapshot with its frozen data"
+    )
+    snapshot_parser.add_argument(
+        "--snapshot", required=True,
+        help="Snapshot id or name"
+    )
+
+    # delete-snapshot subcommand
+    delete_snapshot_parser = subparsers.add_parser(
+        "delete-snapshot",
+        help="Delete a snapshot"
+    )
+    delete_snapshot_parser.add_argument(
+        "--snapshot", required=True,
+        help="Snapshot id or name"
+    )
+
+    # diff-snapshots subcommand
+    diff_snapshots_parser = subparsers.add_parser(
+        "diff-snapshots",
+        help="Diff a snapshot against another snapshot or live data"
+    )
+    diff_snapshots_parser.add_argument(
+        "--base", required=True,
+        help="Base snapshot id or name"
+    )
+    diff_snapshots_parser.add_argument(
+        "--target", required=False, default=None,
+        help="Target snapshot id or name (omit to compare with live data)"
     )
 
     # export-png subcommand
@@ -522,12 +3374,53 @@ def main():
         "--to", dest="date_to", required=True,
         help="End date (YYYY-MM-DD)"
     )
+    png_parser.add_argument(
+        "--scale", type=int, default=1, choices=EXPORT_SCALES,
+        help="Pixel density (2 = @2x, 3 = @3x)"
+    )
+    png_parser.add_argument(
+        "--social", dest="social_format", default=None, choices=sorted(SOCIAL_FORMATS),
+        help="Render a social card (twitter 1200x675, linkedin 1200x627)"
+    )
+    png_parser.add_argument(
+        "--output", default=None,
+        help="Write the PNG to this file instead of returning it base64-encoded"
+    )
 
     # projects subcommand
     projects_parser = subparsers.add_parser(
         "projects",
         help="Get all projects with metadata"
     )
+    projects_parser.add_argument(
+        "--sort", default="manual",
+        choices=["manual", "last_activity", "cost_this_month", "name"],
+        help="Project order (pinned projects first)"
+    )
+
+    # set-project-pinned subcommand
+    set_pinned_parser = subparsers.add_parser(
+        "set-project-pinned",
+        help="Pin or unpin a project"
+    )
+    set_pinned_parser.add_argument(
+        "--project-id", required=True,
+        help="Project identifier"
+    )
+    set_pinned_parser.add_argument(
+        "--pinned", type=int, choices=[0, 1], required=True,
+        help="Pinned flag (0 or 1)"
+    )
+
+    # set-project-order subcommand
+    set_order_parser = subparsers.add_parser(
+        "set-project-order",
+        help="Persist the manual project order"
+    )
+    set_order_parser.add_argument(
+        "--project-ids", required=True,
+        help="Comma-separated project IDs in display order"
+    )
 
     # update-project subcommand
     update_project_parser = subparsers.add_parser(
@@ -550,53 +3443,509 @@ def main():
         "--visible", type=int, choices=[0, 1], required=False,
         help="Visibility flag (0 or 1)"
     )
+    update_project_parser.add_argument(
+        "--tags", required=False,
+        help="Comma-separated tags (empty string clears tags)"
+    )
+    update_project_parser.add_argument(
+        "--color", required=False,
+        help="Hex color #rrggbb (empty string clears it)"
+    )
+    update_project_parser.add_argument(
+        "--icon", required=False,
+        help="Emoji or short icon name (empty string clears it)"
+    )
+
+    # suggest-description subcommand
+    suggest_description_parser = subparsers.add_parser(
+        "suggest-description",
+        help="Suggest a project description from recent sessions (not applied)"
+    )
+    suggest_description_parser.add_argument(
+        "--project-id", required=True,
+        help="Project identifier"
+    )
+
+    # project-timeline subcommand
+    project_timeline_parser = subparsers.add_parser(
+        "project-timeline",
+        help="Lifetime activity curve, first/last session and milestones of a project"
+    )
+    project_timeline_parser.add_argument(
+        "--project-id", required=True,
+        help="Project identifier"
+    )
+    project_timeline_parser.add_argument(
+        "--granularity", choices=["auto", "day", "week", "month"], default="auto",
+        help="Curve resolution (default: auto, by history length)"
+    )
+
+    # backfill-first-seen subcommand
+    subparsers.add_parser(
+        "backfill-first-seen",
+        help="Set project first_seen to the first session in the database"
+    )
+
+    # stale-projects subcommand
+    stale_projects_parser = subparsers.add_parser(
+        "stale-projects",
+        help="Projects without activity for N days, with storage footprint and archive suggestions"
+    )
+    stale_projects_parser.add_argument(
+        "--inactive-days", type=int, default=90,
+        help="Inactivity threshold in days (default: 90)"
+    )
+
+    # archive-projects subcommand
+    archive_projects_parser = subparsers.add_parser(
+        "archive-projects",
+        help="Hide projects from the project selector and unpin them (usage is kept)"
+    )
+    archive_projects_parser.add_argument(
+        "--project-ids", required=True,
+        help="Comma-separated project IDs"
+    )
+
+    # workspaces subcommand
+    subparsers.add_parser(
+        "workspaces",
+        help="List workspaces with their projects"
+    )
+
+    # create-workspace subcommand
+    create_workspace_parser = subparsers.add_parser(
+        "create-workspace",
+        help="Create a workspace (group of projects)"
+    )
+    create_workspace_parser.add_argument(
+        "--name", required=True,
+        help="Display name"
+    )
+    create_workspace_parser.add_argument(
+        "--description", required=False, default=None,
+        help="Description"
+    )
+
+    # delete-workspace subcommand
+    delete_workspace_parser = subparsers.add_parser(
+        "delete-workspace",
+        help="Delete a workspace (its projects become unassigned)"
+    )
+    delete_workspace_parser.add_argument(
+        "--workspace-id", required=True,
+        help="Workspace identifier"
+    )
+
+    # assign-project-workspace subcommand
+    assign_workspace_parser = subparsers.add_parser(
+        "assign-project-workspace",
+        help="Move a project into a workspace"
+    )
+    assign_workspace_parser.add_argument(
+        "--project-id", required=True,
+        help="Project identifier"
+    )
+    assign_workspace_parser.add_argument(
+        "--workspace-id", required=False, default=None,
+        help="Workspace identifier (omit to remove the project from its workspace)"
+    )
+
+    # mirror-status subcommand
+    subparsers.add_parser(
+        "mirror-status",
+        help="Show zstd record mirror status"
+    )
+
+    # clear-mirror subcommand
+    subparsers.add_parser(
+        "clear-mirror",
+        help="Delete all zstd record mirror files"
+    )
+
+    # send-telemetry subcommand
+    telemetry_parser = subparsers.add_parser(
+        "send-telemetry",
+        help="POST an opt-in telemetry payload built by the desktop app"
+    )
+    telemetry_parser.add_argument("--endpoint", required=True, help="https URL")
+    telemetry_parser.add_argument("--payload", required=True, help="Payload JSON object")
+
+    # fetch-feature-flags subcommand
+    flags_parser = subparsers.add_parser(
+        "fetch-feature-flags",
+        help="Fetch a remote feature flag document for the desktop app"
+    )
+    flags_parser.add_argument("--url", required=True, help="https URL")
+
+    # generate-demo-data subcommand
+    demo_parser = subparsers.add_parser(
+        "generate-demo-data",
+        help="Replace the demo database with seeded synthetic usage (demo mode only)"
+    )
+    demo_parser.add_argument("--seed", type=int, default=DEFAULT_DEMO_SEED, help="Random seed")
+    demo_parser.add_argument(
+        "--months", type=int, default=DEFAULT_DEMO_MONTHS,
+        help="Months of history ending today (1-24)"
+    )
+
+    # generate-sample-data subcommand
+    sample_parser = subparsers.add_parser(
+        "generate-sample-data",
+        help="Write sample session logs into the demo home and ingest them (demo mode only)"
+    )
+    sample_parser.add_argument(
+        "--days", type=int, default=DEFAULT_SAMPLE_DAYS, help="Days of history ending today (1-365)"
+    )
+    sample_parser.add_argument(
+        "--intensity", choices=list(SAMPLE_INTENSITIES), default=DEFAULT_SAMPLE_INTENSITY,
+        help="Sessions per day"
+    )
+    sample_parser.add_argument("--seed", type=int, default=DEFAULT_DEMO_SEED, help="Random seed")
+
+    # benchmark subcommand
+    benchmark_parser = subparsers.add_parser(
+        "benchmark",
+        help="Time ingestion, aggregation and cached refreshes on synthetic logs in a throwaway home"
+    )
+    benchmark_parser.add_argument(
+        "--sizes", required=False,
+        help="Comma-separated dataset sizes in messages: 10000, 100000, 1000000 (default: 10000)"
+    )
+    benchmark_parser.add_argument("--seed", type=int, default=DEFAULT_BENCHMARK_SEED, help="Random seed")
+    # One run inside the throwaway home (started by the benchmark itself)
+    benchmark_parser.add_argument("--measure", type=int, required=False, help=argparse.SUPPRESS)
+
+    # version subcommand
+    subparsers.add_parser(
+        "version",
+        help="Report the API, package and schema versions (startup handshake)"
+    )
 
-    args = parser.parse_args()
+    # schema-info subcommand
+    subparsers.add_parser(
+        "schema-info",
+        help="Get the database schema version"
+    )
+
+    # diagnostics subcommand
+    subparsers.add_parser(
+        "diagnostics",
+        help="Describe the Python, package and data directory of the backend"
+    )
+
+    # audit-log subcommand
+    audit_log_parser = subparsers.add_parser(
+        "audit-log",
+        help="List recent state-changing commands"
+    )
+    audit_log_parser.add_argument(
+        "--limit", type=int, default=100,
+        help="Maximum number of events (newest first)"
+    )
+    audit_log_parser.add_argument(
+        "--request", dest="audit_request_id", required=False,
+        help="Only show events of this request ID"
+    )
+    audit_log_parser.add_argument(
+        "--token", dest="audit_token_id", required=False,
+        help="Only show events of this API token ID (local HTTP server)"
+    )
+
+    # serve subcommand
+    subparsers.add_parser(
+        "serve",
+        help="Run as a persistent worker answering line-delimited JSON requests on stdin"
+    )
+
+    args = parser.parse_args(argv)
+    if args.command == "serve":
+        serve_worker(main)
+        return
+
+    request_id = args.request_id or current_request_id()
 
     try:
+        if read_only() and _is_audited(args):
+            raise ReadOnlyModeError(f"Read-only mode is on: {args.command} is disabled")
+
         if args.command == "dashboard":
             result = get_dashboard_bundle(
                 args.date_from,
                 args.date_to,
                 bool(args.refresh),
                 args.granularity,
-                args.project_id
+                args.project_id,
+                args.sections.split(",") if args.sections else None,
+                args.workspace_id
             )
+        elif args.command == "refresh":
+            if args.since or args.since_watermark or args.today:
+                result = refresh_partial(args.since, bool(args.today))
+            else:
+                result = refresh_data()
+        elif args.command == "refresh-status":
+            result = get_refresh_status()
+        elif args.command == "quarantined-lines":
+            result = get_quarantined_lines(args.limit, args.source_file)
         elif args.command == "day":
             result = get_day_details(args.date, args.project_id)
+        elif args.command == "aggregate-history":
+            result = get_aggregate_history(args.date)
         elif args.command == "model":
             result = get_model_details(args.model, args.date_from, args.date_to, args.project_id)
         elif args.command == "session":
             result = get_session_details(args.session_id, args.project_id)
+        elif args.command == "session-files":
+            result = get_session_files(args.session_id)
+        elif args.command == "plugin-records":
+            result = get_plugin_records(args.date_from, args.date_to, args.project_id)
+        elif args.command == "export-raw-sessions":
+            result = export_raw_sessions(args.session_ids, args.output, bool(args.redact))
+        elif args.command == "create-backup":
+            result = create_backup(args.output)
+        elif args.command == "verify-backup":
+            result = verify_backup(args.backup_path)
+        elif args.command == "restore-backup":
+            result = restore_backup(
+                args.backup_path,
+                bool(args.force),
+                args.components.split(",") if args.components else None,
+                bool(args.dry_run),
+            )
+        elif args.command == "merge-data":
+            result = merge_data_source(args.path, bool(args.dry_run))
+        elif args.command == "shift-timestamps":
+            result = shift_session_timestamps(
+                args.offset, args.date_from, args.date_to,
+                [sid for sid in args.session_ids.split(",") if sid] if args.session_ids else None,
+                args.project_id, args.config_dir, bool(args.preview)
+            )
+        elif args.command == "undo-timestamp-shift":
+            result = undo_session_timestamp_shift(args.shift_id, bool(args.preview))
+        elif args.command == "timestamp-shifts":
+            result = get_timestamp_shifts()
+        elif args.command == "import-session-archive":
+            result = import_raw_sessions(args.archive_path, args.target_project)
+        elif args.command == "sessions":
+            result = list_sessions(
+                args.date_from,
+                args.date_to,
+                args.project_id,
+                args.model,
+                args.search,
+                args.sort,
+                args.cursor,
+                args.page_size
+            )
         elif args.command == "limits":
             result = get_limit_resets(args.date_from, args.date_to)
+        elif args.command == "limit-impact":
+            result = get_limit_impact(args.date_from, args.date_to)
+        elif args.command == "mcp-usage":
+            result = get_mcp_usage(args.date_from, args.date_to, args.project_id)
+        elif args.command == "hook-stats":
+            result = get_hook_stats(args.date_from, args.date_to, args.project_id)
+        elif args.command == "effort-breakdown":
+            result = get_effort_breakdown(args.model, args.date_from, args.date_to, args.project_id)
+        elif args.command == "concurrency-stats":
+            result = get_concurrency_stats(args.date_from, args.date_to, args.idle_gap)
+        elif args.command == "outcome-stats":
+            result = get_outcome_stats(
+                args.date_from, args.date_to, args.project_id, bool(args.classify), bool(args.reclassify)
+            )
+        elif args.command == "compaction-stats":
+            result = get_compaction_stats(
+                args.date_from, args.date_to, args.project_id, bool(args.scan), bool(args.rescan)
+            )
         elif args.command == "usage-accounts":
             result = get_usage_accounts()
+        elif args.command == "update-usage-account":
+            result = update_usage_account(args.email, args.label, args.color, args.plan_type)
+        elif args.command == "compare-accounts":
+            result = get_account_comparison(args.date_from, args.date_to, args.emails)
+        elif args.command == "account-dashboard":
+            result = get_account_dashboard(args.date_from, args.date_to, args.email)
+        elif args.command == "claude-accounts":
+            result = get_claude_accounts()
+        elif args.command == "bind-claude-account":
+            result = bind_claude_account(args.config_dir, args.email)
+        elif args.command == "detect-plan":
+            result = detect_plan(bool(args.apply))
+        elif args.command == "budget-history":
+            result = get_budget_history()
+        elif args.command == "set-budget":
+            result = set_budget(args.amount, args.effective_from)
+        elif args.command == "hooks":
+            result = get_hooks()
+        elif args.command == "set-hook":
+            result = set_hook(
+                args.event, args.hook_command, args.timeout, args.hook_id, not args.disabled,
+                args.action, args.folder, args.formats,
+            )
+        elif args.command == "delete-hook":
+            result = delete_hook_config(args.hook_id)
+        elif args.command == "test-hook":
+            result = test_hook(args.hook_id)
+        elif args.command == "derived-metrics":
+            result = get_derived_metrics()
+        elif args.command == "available-metrics":
+            result = list_available_metrics()
+        elif args.command == "metric-series":
+            result = get_metric_series(
+                args.date_from, args.date_to, args.granularity,
+                [name.strip() for name in args.metrics.split(",") if name.strip()],
+                args.project_id,
+            )
+        elif args.command == "calendar-overlay":
+            result = get_calendar_overlay()
+        elif args.command == "set-calendar-overlay":
+            try:
+                periods = json.loads(args.periods)
+            except json.JSONDecodeError as e:
+                raise ValueError(f"Invalid periods JSON: {e}")
+            result = set_calendar_overlay(periods)
+        elif args.command == "working-hours":
+            result = get_working_hours()
+        elif args.command == "set-working-hours":
+            try:
+                days = [int(day) for day in args.days.split(",") if day.strip()]
+            except ValueError:
+                raise ValueError(f"Invalid days: {args.days} (expected e.g. 1,2,3,4,5)")
+            result = set_working_hours(days, args.start, args.end)
+        elif args.command == "out-of-hours-usage":
+            result = get_out_of_hours_usage(args.date_from, args.date_to, args.project_id)
+        elif args.command == "set-derived-metric":
+            result = set_derived_metric(args.name, args.expression, args.description)
+        elif args.command == "delete-derived-metric":
+            result = delete_derived_metric_config(args.name)
+        elif args.command == "cost-centers":
+            result = get_cost_centers()
+        elif args.command == "set-cost-center":
+            projects = args.projects.split(",") if args.projects is not None else None
+            tags = args.tags.split(",") if args.tags is not None else None
+            result = set_cost_center(args.code, args.name, projects, tags)
+        elif args.command == "delete-cost-center":
+            result = delete_cost_center(args.code)
+        elif args.command == "cost-allocation":
+            result = get_cost_allocation(args.month)
+        elif args.command == "export-cost-allocation":
+            result = export_cost_allocation(args.month, args.fmt)
+        elif args.command == "price-versions":
+            result = get_price_versions(args.model)
+        elif args.command == "sync-prices":
+            result = sync_prices(args.effective_from)
+        elif args.command == "set-price":
+            result = set_price(
+                args.model,
+                args.effective_from,
+                args.input_per_mtok,
+                args.output_per_mtok,
+                args.cache_write_per_mtok,
+                args.cache_read_per_mtok
+            )
+        elif args.command == "reprice":
+            result = reprice_history(args.date_from, args.date_to, bool(args.dry_run))
+        elif args.command == "create-snapshot":
+            result = create_snapshot(args.name, args.date_from, args.date_to, args.project_id)
+        elif args.command == "snapshots":
+            result = get_snapshots()
+        elif args.command == "snapshot":
+            result = get_snapshot(args.snapshot)
+        elif args.command == "delete-snapshot":
+            result = delete_snapshot(args.snapshot)
+        elif args.command == "diff-snapshots":
+            result = diff_snapshots(args.base, args.target)
         elif args.command == "export-png":
-            result = export_png_report(args.date_from, args.date_to)
+            result = export_png_report(args.date_from, args.date_to, args.scale, args.social_format, args.output)
         elif args.command == "projects":
-            result = get_projects()
+            result = get_projects(args.sort)
+        elif args.command == "set-project-pinned":
+            result = set_project_pinned(args.project_id, bool(args.pinned))
+        elif args.command == "set-project-order":
+            result = set_project_order([pid for pid in args.project_ids.split(",") if pid])
         elif args.command == "update-project":
             # Convert visible from int (0/1) to bool if provided
             visible = bool(args.visible) if args.visible is not None else None
+            tags = args.tags.split(",") if args.tags is not None else None
             result = update_project(
                 args.project_id,
                 args.name,
                 args.description,
-                visible
+                visible,
+                tags,
+                args.color,
+                args.icon
             )
+        elif args.command == "suggest-description":
+            result = suggest_project_description(args.project_id)
+        elif args.command == "project-timeline":
+            result = get_project_timeline(args.project_id, args.granularity)
+        elif args.command == "backfill-first-seen":
+            result = backfill_project_first_seen()
+        elif args.command == "stale-projects":
+            result = get_stale_projects(args.inactive_days)
+        elif args.command == "archive-projects":
+            result = archive_projects([pid for pid in args.project_ids.split(",") if pid])
+        elif args.command == "workspaces":
+            result = get_workspaces()
+        elif args.command == "create-workspace":
+            result = create_workspace(args.name, args.description)
+        elif args.command == "delete-workspace":
+            result = delete_workspace(args.workspace_id)
+        elif args.command == "assign-project-workspace":
+            result = assign_project_to_workspace(args.project_id, args.workspace_id)
+        elif args.command == "mirror-status":
+            result = {"enabled": MIRROR_ENABLED, **mirror_status()}
+        elif args.command == "clear-mirror":
+            result = clear_mirror()
+        elif args.command == "send-telemetry":
+            result = send_telemetry(args.endpoint, args.payload)
+        elif args.command == "fetch-feature-flags":
+            result = fetch_feature_flags(args.url)
+        elif args.command == "generate-demo-data":
+            result = generate_demo_data(args.seed, args.months)
+        elif args.command == "generate-sample-data":
+            result = generate_sample_data(args.days, args.intensity, args.seed)
+        elif args.command == "diagnostics":
+            result = get_diagnostics()
+        elif args.command == "benchmark":
+            if args.measure is not None:
+                result = measure_benchmark(args.measure, args.seed)
+            else:
+                sizes = [int(size) for size in args.sizes.split(",") if size.strip()] if args.sizes else None
+                result = run_benchmark(sizes, args.seed)
+        elif args.command == "version":
+            result = get_backend_version()
+        elif args.command == "schema-info":
+            result = get_schema_info()
+        elif args.command == "audit-log":
+            result = {"events": read_audit_log(
+                args.limit, args.audit_request_id, token_id=args.audit_token_id
+            )}
         else:
             result = {"error": f"Unknown command: {args.command}"}
 
+        _audit(args, request_id)
+
+        if blur_projects_enabled():
+            result = blur_projects(result)
+
+        # Large lists go out in chunks first if the bridge asked for a stream
+        result = stream_result(args.command, result)
+
         # Output JSON to stdout
         print(json.dumps(result, ensure_ascii=False, indent=None))
 
     except Exception as e:
+        _audit(args, request_id, str(e))
+
         # Output error as JSON to stderr
         error_response = {
             "error": str(e),
             "type": type(e).__name__
         }
+        if request_id:
+            error_response["request_id"] = request_id
         print(json.dumps(error_response), file=sys.stderr)
         sys.exit(1)
 
@@ -0,0 +1,286 @@
+"""
+Clock corrections for messages logged with a wrong system time.
+
+A machine whose clock was off writes sessions with impossible timestamps,
+so they land on the wrong days and hours. A shift moves the timestamps of
+the selected messages by a fixed offset, recomputes their local time, date
+and year, and rebuilds the hourly and model aggregates of the old and new
+hours.
+
+Every shift is recorded with the entry hashes it moved, so it can be
+previewed before and undone after applying. Undo moves the same messages
+back by the offset, which keeps later shifts of overlapping messages intact.
+Shifted rows are kept on re-ingest (message entries are inserted with
+INSERT OR IGNORE); ``--rebuild-db`` starts from the raw files again and
+drops all shifts.
+
+Only message entries are shifted; limit events, MCP calls and hook
+executions keep their logged times.
+"""
+from __future__ import annotations
+
+import json
+import re
+import sqlite3
+from datetime import datetime, timedelta, timezone
+from typing import Any, Optional
+
+from command_center.database.queries import recompute_hourly_aggregates, recompute_model_aggregates
+from command_center.utils.date_helpers import (
+    convert_to_local,
+    format_date_key,
+    format_datetime_hour,
+    parse_iso_timestamp,
+)
+
+# Largest accepted offset; covers clocks reset to a default date years off
+MAX_OFFSET_SECONDS = 30 * 366 * 24 * 3600
+MAX_SAMPLES = 10
+
+OFFSET_PATTERN = re.compile(r"^([+-]?)((?:\d+[dhms])+)$")
+OFFSET_PART = re.compile(r"(\d+)([dhms])")
+UNIT_SECONDS = {"d": 86400, "h": 3600, "m": 60, "s": 1}
+
+
+def parse_offset(value: str) -> int:
+    """
+    Parse an offset like ``-1d``, ``+2h30m`` or ``3600`` (seconds).
+
+    Raises:
+        ValueError: If the offset is malformed, zero or larger than 30 years
+    """
+    text = value.strip().replace(" ", "")
+    if re.fullmatch(r"[+-]?\d+", text):
+        seconds = int(text)
+    else:
+        match = OFFSET_PATTERN.match(text)
+        if not match:
+            raise ValueError(f"Invalid offset: {value} (expected e.g. -1d, +2h30m or seconds)")
+        seconds = sum(int(n) * UNIT_SECONDS[unit] for n, unit in OFFSET_PART.findall(match.group(2)))
+        if match.group(1) == "-":
+            seconds = -seconds
+    if seconds == 0:
+        raise ValueError("Offset must not be zero")
+    if abs(seconds) > MAX_OFFSET_SECONDS:
+        raise ValueError("Offset must not be larger than 30 years")
+    return seconds
+
+
+def _shifted(timestamp: str, offset_seconds: int) -> Optional[tuple[str, str, int, str]]:
+    """(timestamp, timestamp_local, year, date) of a UTC timestamp moved by the offset."""
+    parsed = parse_iso_timestamp(timestamp)
+    if parsed is None:
+        return None
+    if parsed.tzinfo is None:
+        parsed = parsed.replace(tzinfo=timezone.utc)
+    moved = (parsed + timedelta(seconds=offset_seconds)).astimezone(timezone.utc)
+    text = moved.isoformat(timespec="milliseconds" if parsed.microsecond else "seconds")
+    local = convert_to_local(moved)
+    return text.replace("+00:00", "Z"), local.isoformat(), local.year, format_date_key(local)
+
+
+def _hour_key(date: str, timestamp_local: str) -> str:
+    return f"{date} {timestamp_local[11:13]}:00:00"
+
+
+def _move(conn: sqlite3.Connection, rows: list[tuple], offset_seconds: int, write: bool,
+          reason: str = "time-shift") -> dict[str, Any]:
+    """
+    Move rows of (entry_hash, session_id, timestamp, timestamp_local, year, date) by the offset.
+
+    Returns the summary shared by shifts and undos; writes and recomputes aggregates if write.
+    """
+    updates = []
+    samples = []
+    old_dates, new_dates = [], []
+    hours: set[str] = set()
+    years: set[int] = set()
+    for entry_hash, session_id, timestamp, timestamp_local, year, date in rows:
+        moved = _shifted(timestamp, offset_seconds)
+        if moved is None:
+            continue
+        new_timestamp, new_local, new_year, new_date = moved
+        updates.append((new_timestamp, new_local, new_year, new_date, entry_hash))
+        old_dates.append(date)
+        new_dates.append(new_date)
+        hours.update({_hour_key(date, timestamp_local), format_datetime_hour(datetime.fromisoformat(new_local))})
+        years.update({year, new_year})
+        if len(samples) < MAX_SAMPLES:
+            samples.append({
+                "entry_hash": entry_hash,
+                "session_id": session_id,
+                "before": timestamp_local,
+                "after": new_local,
+            })
+
+    if write and updates:
+        conn.executemany("""
+            UPDATE message_entries SET timestamp = ?, timestamp_local = ?, year = ?, date = ?
+            WHERE entry_hash = ?
+        """, updates)
+        conn.commit()
+        recompute_hourly_aggregates(conn, hours, reason=reason)
+        for year in sorted(years):
+            recompute_model_aggregates(conn, year)
+
+    return {
+        "offset_seconds": offset_seconds,
+        "messages": len(updates),
+        "sessions": len({row[1] for row in rows if row[1]}),
+        "before": {"from": min(old_dates), "to": max(old_dates)} if old_dates else None,
+        "after": {"from": min(new_dates), "to": max(new_dates)} if new_dates else None,
+        "samples": samples,
+        "moved": [update[-1] for update in updates],
+    }
+
+
+def shift_timestamps(
+    conn: sqlite3.Connection,
+    offset_seconds: int,
+    date_from: Optional[str] = None,
+    date_to: Optional[str] = None,
+    session_ids: Optional[list[str]] = None,
+    project_id: Optional[str] = None,
+    config_dir: Optional[str] = None,
+    preview: bool = False
+) -> dict[str, Any]:
+    """
+    Move the timestamps of the selected messages by an offset.
+
+    Args:
+        conn: Database connection
+        offset_seconds: Offset to add (negative moves messages back in time)
+        date_from: First local date of the selection (YYYY-MM-DD, as currently stored)
+        date_to: Last local date of the selection (inclusive)
+        session_ids: Only these sessions
+        project_id: Only this project
+        config_dir: Only messages read from this Claude config directory (one machine)
+        preview: Only report what would move
+
+    Returns:
+        {
+            "preview": false, "shift_id": 3 | None (preview),
+            "offset_seconds": -86400, "messages": 412, "sessions": 9,
+            "before": {"from": "2031-03-02", "to": "2031-03-08"},
+            "after": {"from": "2025-03-01", "to": "2025-03-07"},
+            "samples": [{"entry_hash", "session_id", "before", "after"}, ...]   # local times
+        }
+
+    Raises:
+        ValueError: If the selection is empty of criteria or the offset is invalid
+    """
+    if offset_seconds == 0 or abs(offset_seconds) > MAX_OFFSET_SECONDS:
+        raise ValueError("Offset must be non-zero and at most 30 years")
+    if not (date_from or date_to or session_ids):
+        raise ValueError("Select messages by date range or sessions")
+
+    conditions = []
+    params: list[Any] = []
+    if date_from:
+        conditions.append("date >= ?")
+        params.append(date_from)
+    if date_to:
+        conditions.append("date <= ?")
+        params.append(date_to)
+    if session_ids:
+        conditions.append("session_id IN (SELECT value FROM json_each(?))")
+        params.append(json.dumps(session_ids))
+    if project_id:
+        conditions.append("project_id = ?")
+        params.append(project_id)
+    if config_dir:
+        conditions.append("config_dir = ?")
+        params.append(config_dir)
+    rows = conn.execute(f"""
+        SELECT entry_hash, session_id, timestamp, timestamp_local, year, date
+        FROM message_entries
+        WHERE {' AND '.join(conditions)}
+        ORDER BY timestamp
+    """, params).fetchall()
+
+    summary = _move(conn, rows, offset_seconds, write=not preview)
+    moved = summary.pop("moved")
+    shift_id = None
+    if not preview and moved:
+        selection = {
+            "date_from": date_from, "date_to": date_to, "session_ids": session_ids,
+            "project_id": project_id, "config_dir": config_dir,
+        }
+        cursor = conn.execute("""
+            INSERT INTO timestamp_shifts (offset_seconds, filter, messages, created_at)
+            VALUES (?, ?, ?, ?)
+        """, (offset_seconds, json.dumps({k: v for k, v in selection.items() if v}), len(moved),
+              datetime.now().astimezone().isoformat()))
+        shift_id = cursor.lastrowid
+        conn.executemany(
+            "INSERT INTO timestamp_shift_entries (shift_id, entry_hash) VALUES (?, ?)",
+            [(shift_id, entry_hash) for entry_hash in moved],
+        )
+        conn.commit()
+    return {"preview": preview, "shift_id": shift_id, **summary}
+
+
+def undo_timestamp_shift(conn: sqlite3.Connection, shift_id: int, preview: bool = False) -> dict[str, Any]:
+    """
+    Move the messages of a shift back by its offset.
+
+    Messages deleted since (e.g. by a rebuild) are skipped.
+
+    Returns:
+        Same summary as shift_timestamps, with the shift_id undone
+
+    Raises:
+        ValueError: If the shift does not exist or was already undone
+    """
+    shift = conn.execute(
+        "SELECT offset_seconds, undone_at FROM timestamp_shifts WHERE id = ?", (shift_id,)
+    ).fetchone()
+    if shift is None:
+        raise ValueError(f"Unknown timestamp shift: {shift_id}")
+    if shift[1]:
+        raise ValueError(f"Timestamp shift {shift_id} was already undone at {shift[1]}")
+
+    rows = conn.execute("""
+        SELECT m.entry_hash, m.session_id, m.timestamp, m.timestamp_local, m.year, m.date
+        FROM timestamp_shift_entries s
+        JOIN message_entries m ON m.entry_hash = s.entry_hash
+        WHERE s.shift_id = ?
+        ORDER BY m.timestamp
+    """, (shift_id,)).fetchall()
+    summary = _move(conn, rows, -shift[0], write=not preview, reason="time-shift-undo")
+    summary.pop("moved")
+    if not preview:
+        conn.execute(
+            "UPDATE timestamp_shifts SET undone_at = ? WHERE id = ?",
+            (datetime.now().astimezone().isoformat(), shift_id),
+        )
+        conn.commit()
+    return {"preview": preview, "shift_id": shift_id, **summary}
+
+
+def list_timestamp_shifts(conn: sqlite3.Connection) -> dict[str, Any]:
+    """
+    All recorded shifts, newest first.
+
+    Returns:
+        {"shifts": [{"shift_id", "offset_seconds", "filter": {...}, "messages",
+                     "created_at", "undone_at"}, ...]}
+    """
+    rows = conn.execute("""
+        SELECT id, offset_seconds, filter, messages, created_at, undone_at
+        FROM timestamp_shifts
+        ORDER BY id DESC
+    """).fetchall()
+    return {
+        "shifts": [
+            {
+                "shift_id": shift_id,
+                "offset_seconds": offset_seconds,
+                "filter": json.loads(selection),
+                "messages": messages,
+                "created_at": created_at,
+                "undone_at": undone_at,
+            }
+            for shift_id, offset_seconds, selection, messages, created_at, undone_at in rows
+        ]
+    }
@@ -1,15 +1,24 @@
 """
 Read latest cc_usage_events per account from the cc_usage SQLite database.
+
+User-editable account metadata (label, color, plan type) is kept in a JSON
+file next to the database and merged into every account payload.
 """
 from __future__ import annotations
 
+import json
 import os
 import re
 import sqlite3
-from datetime import datetime, timedelta
+from datetime import date, datetime, timedelta
+from pathlib import Path
 from typing import Any
 from zoneinfo import ZoneInfo
 
+from command_center.settings import billing_anchor_day, settings_path_near
+from command_center.utils.atomic_write import write_json_atomic
+from command_center.utils.date_helpers import billing_cycle_bounds, billing_cycle_key
+
 
 DEFAULT_CC_USAGE_DB_PATH = os.path.join(
     os.path.expanduser("~"),
@@ -18,6 +27,28 @@ DEFAULT_CC_USAGE_DB_PATH = os.path.join(
     "cc_usage.db",
 )
 
+# Default location for account metadata JSON
+ACCOUNTS_JSON_PATH = os.path.join(
+    os.path.expanduser("~"),
+    ".claude",
+    "db",
+    "command-center-accounts.json",
+)
+
+PLAN_TYPES = ("pro", "max_5x", "max_20x", "team", "enterprise", "api")
+
+# Monthly list price per seat (USD); None = negotiated, API has no subscription
+PLAN_MONTHLY_PRICES_USD = {
+    "pro": 20.0,
+    "max_5x": 100.0,
+    "max_20x": 200.0,
+    "team": 30.0,
+    "enterprise": None,
+    "api": 0.0,
+}
+
+_HEX_COLOR_RE = re.compile(r"^#[0-9a-fA-F]{6}$")
+
 
 def _table_exists(conn: sqlite3.Connection, table_name: str) -> bool:
     cursor = conn.execute(
@@ -239,4 +270,330 @@ def fetch_latest_usage_accounts(
             if incoming_ts and (not existing_ts or incoming_ts > existing_ts):
                 accounts_by_email[email] = account
 
-    return sorted(accounts_by_email.values(), key=lambda item: item.get("email", ""))
+    metadata = load_account_metadata()
+    return sorted(
+        (_with_metadata(account, metadata) for account in accounts_by_email.values()),
+        key=lambda item: item.get("email", ""),
+    )
+
+
+def load_account_metadata(json_path: str = ACCOUNTS_JSON_PATH) -> dict[str, dict[str, Any]]:
+    """
+    Load account metadata keyed by email.
+
+    Example:
+        {
+            "me@work.com": {"label": "Work", "color": "#D97757", "plan_type": "max_5x"}
+        }
+    """
+    path = Path(json_path)
+    if not path.exists():
+        return {}
+    try:
+        with open(path, "r") as f:
+            return json.load(f)
+    except (json.JSONDecodeError, IOError):
+        return {}
+
+
+def save_account_metadata(metadata: dict[str, dict[str, Any]], json_path: str = ACCOUNTS_JSON_PATH):
+    """Save account metadata keyed by email."""
+    write_json_atomic(json_path, metadata)
+
+
+def _with_metadata(account: dict[str, Any], metadata: dict[str, dict[str, Any]]) -> dict[str, Any]:
+    meta = metadata.get(account.get("email", ""), {})
+    # A manually set plan overrides the auto-detected one
+    override = meta.get("plan_type") or None
+    detected = meta.get("detected_plan_type") or None
+    return {
+        **account,
+        "label": meta.get("label") or None,
+        "color": meta.get("color") or None,
+        "plan_type": override or detected,
+        "plan_source": "manual" if override else ("detected" if detected else None),
+    }
+
+
+def update_usage_account_fields(
+    email: str,
+    label: str | None = None,
+    color: str | None = None,
+    plan_type: str | None = None,
+    json_path: str = ACCOUNTS_JSON_PATH,
+) -> dict[str, Any]:
+    """
+    Update account metadata fields. Empty strings clear a field.
+
+    Args:
+        email: Account email (as recorded by cc_usage)
+        label: Display label (max 50 characters)
+        color: Hex color (#RRGGBB)
+        plan_type: One of PLAN_TYPES (manual override of the detected plan)
+        json_path: Path to accounts JSON file
+
+    Returns:
+        Updated metadata dict with email included
+
+    Raises:
+        ValueError: If validation fails
+    """
+    email = (email or "").strip()
+    if not email:
+        raise ValueError("Account email is required")
+
+    metadata = load_account_metadata(json_path)
+    entry = metadata.setdefault(email, {})
+
+    if label is not None:
+        label = label.strip()
+        if len(label) > 50:
+            raise ValueError("Account label cannot exceed 50 characters")
+        entry["label"] = label
+
+    if color is not None:
+        color = color.strip()
+        if color and not _HEX_COLOR_RE.match(color):
+            raise ValueError(f"Invalid color: {color}. Use #RRGGBB format")
+        entry["color"] = color
+
+    if plan_type is not None:
+        plan_type = plan_type.strip()
+        if plan_type and plan_type not in PLAN_TYPES:
+            raise ValueError(
+                f"Invalid plan type: {plan_type}. Use one of: {', '.join(PLAN_TYPES)}"
+            )
+        entry["plan_type"] = plan_type
+
+    save_account_metadata(metadata, json_path)
+    return {"email": email, **entry}
+
+
+def _fetch_range_rows_from_path(db_path: str, date_from: str, date_to: str) -> list[dict[str, Any]]:
+    try:
+        with sqlite3.connect(db_path) as conn:
+            conn.row_factory = sqlite3.Row
+            if not _table_exists(conn, "cc_usage_events"):
+                return []
+            columns = _get_columns(conn, "cc_usage_events")
+
+            def select_column(name: str) -> str:
+                return name if name in columns else f"NULL AS {name}"
+
+            rows = conn.execute(
+                f"""
+                SELECT
+                    email,
+                    captured_at_local,
+                    {select_column("current_session_used_pct")},
+                    {select_column("current_week_used_pct")}
+                FROM cc_usage_events
+                WHERE email IS NOT NULL AND email != ''
+                AND SUBSTR(captured_at_local, 1, 10) >= ?
+                AND SUBSTR(captured_at_local, 1, 10) <= ?
+                """,
+                (date_from, date_to),
+            ).fetchall()
+            return [dict(row) for row in rows]
+    except sqlite3.Error:
+        return []
+
+
+def query_usage_account_totals(
+    date_from: str,
+    date_to: str,
+    db_path: str | None = None,
+    json_path: str = ACCOUNTS_JSON_PATH,
+) -> list[dict[str, Any]]:
+    """
+    Summarize cc_usage snapshots per account for a date range.
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        db_path: Optional explicit path to cc_usage SQLite DB
+        json_path: Path to accounts JSON file
+
+    Returns:
+        List of per-account dicts sorted by email:
+        email, label, color, plan_type, snapshots, active_days,
+        peak/avg session and week usage percentages, and
+        session_near_limit (snapshots with session usage >= 90%).
+    """
+    candidate_paths = [db_path] if db_path else _get_candidate_paths()
+    seen: set[tuple[str, str]] = set()
+    rows_by_email: dict[str, list[dict[str, Any]]] = {}
+
+    for path in candidate_paths:
+        if not path or not os.path.exists(path):
+            continue
+        for row in _fetch_range_rows_from_path(path, date_from, date_to):
+            key = (row["email"], row["captured_at_local"])
+            if key in seen:
+                continue
+            seen.add(key)
+            rows_by_email.setdefault(row["email"], []).append(row)
+
+    def _peak(values: list[int]) -> int | None:
+        return max(values) if values else None
+
+    def _avg(values: list[int]) -> float | None:
+        return round(sum(values) / len(values), 1) if values else None
+
+    metadata = load_account_metadata(json_path)
+    totals = []
+    for email in sorted(rows_by_email):
+        rows = rows_by_email[email]
+        session_pcts = [r["current_session_used_pct"] for r in rows if r["current_session_used_pct"] is not None]
+        week_pcts = [r["current_week_used_pct"] for r in rows if r["current_week_used_pct"] is not None]
+        totals.append(_with_metadata({
+            "email": email,
+            "snapshots": len(rows),
+            "active_days": len({(r["captured_at_local"] or "")[:10] for r in rows}),
+            "peak_session_used_pct": _peak(session_pcts),
+            "avg_session_used_pct": _avg(session_pcts),
+            "peak_week_used_pct": _peak(week_pcts),
+            "avg_week_used_pct": _avg(week_pcts),
+            "session_near_limit": sum(1 for pct in session_pcts if pct >= 90),
+        }, metadata))
+
+    return totals
+
+
+def usage_account_totals(
+    date_from: str,
+    date_to: str,
+    email: str,
+    json_path: str = ACCOUNTS_JSON_PATH,
+) -> dict[str, Any]:
+    """
+    Snapshot totals of one account for a date range, shaped like the rows of
+    query_usage_account_totals (empty totals without snapshots in the range).
+    """
+    for account in query_usage_account_totals(date_from, date_to, json_path=json_path):
+        if account["email"] == email:
+            return account
+    return _with_metadata({
+        "email": email,
+        "snapshots": 0,
+        "active_days": 0,
+        "peak_session_used_pct": None,
+        "avg_session_used_pct": None,
+        "peak_week_used_pct": None,
+        "avg_week_used_pct": None,
+        "session_near_limit": 0,
+    }, load_account_metadata(json_path))
+
+
+COMPARISON_METRICS = (
+    ("snapshots", "Snapshots"),
+    ("active_days", "Active days"),
+    ("peak_session_used_pct", "Peak session usage %"),
+    ("avg_session_used_pct", "Average session usage %"),
+    ("peak_week_used_pct", "Peak weekly usage %"),
+    ("avg_week_used_pct", "Average weekly usage %"),
+    ("session_near_limit", "Snapshots near session limit"),
+)
+
+
+def compare_usage_accounts(
+    date_from: str,
+    date_to: str,
+    emails: list[str] | None = None,
+) -> dict[str, Any]:
+    """
+    Build a side-by-side comparison of usage accounts for a date range.
+
+    Args:
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        emails: Optional subset of accounts to compare
+
+    Returns:
+        {
+            "range": {"from": ..., "to": ...},
+            "accounts": [...per-account totals...],
+            "metrics": [{"key": ..., "label": ..., "values": {email: value}, "max_email": ...}]
+        }
+    """
+    accounts = query_usage_account_totals(date_from, date_to)
+    if emails:
+        wanted = set(emails)
+        accounts = [a for a in accounts if a["email"] in wanted]
+
+    metrics = []
+    for key, label in COMPARISON_METRICS:
+        values = {a["email"]: a[key] for a in accounts}
+        present = {email: v for email, v in values.items() if v is not None}
+        metrics.append({
+            "key": key,
+            "label": label,
+            "values": values,
+            "max_email": max(present, key=present.get) if present else None,
+        })
+
+    return {
+        "range": {"from": date_from, "to": date_to},
+        "accounts": accounts,
+        "metrics": metrics,
+    }
+
+
+def _prorated_months(date_from: str, date_to: str, anchor_day: int = 1) -> float:
+    """Number of (fractional) billing cycles covered by an inclusive date range."""
+    start = date.fromisoformat(date_from)
+    end = date.fromisoformat(date_to)
+    months = 0.0
+    year, month = (int(part) for part in billing_cycle_key(start, anchor_day).split("-"))
+    while f"{year:04d}-{month:02d}" <= billing_cycle_key(end, anchor_day):
+        first, last = (
+            date.fromisoformat(d) for d in billing_cycle_bounds(f"{year:04d}-{month:02d}", anchor_day)
+        )
+        overlap = (min(last, end) - max(first, start)).days + 1
+        months += overlap / ((last - first).days + 1)
+        year, month = (year + 1, 1) if month == 12 else (year, month + 1)
+    return months
+
+
+def subscription_cost_for_range(
+    date_from: str,
+    date_to: str,
+    json_path: str = ACCOUNTS_JSON_PATH,
+) -> dict[str, Any]:
+    """
+    Subscription cost of all accounts with a known plan, prorated to a range.
+
+    Plans without a list price (enterprise) are listed but not counted.
+    Months are billing cycles starting on the configured billing anchor day.
+
+    Returns:
+        {
+            "cost": X | None,   # None when no account has a priced plan
+            "accounts": [{"email", "plan_type", "monthly_price", "cost"}]
+        }
+    """
+    anchor_day = billing_anchor_day(settings_path_near(json_path))
+    months = _prorated_months(date_from, date_to, anchor_day)
+    metadata = load_account_metadata(json_path)
+
+    accounts = []
+    total = None
+    for email in sorted(metadata):
+        plan_type = _with_metadata({"email": email}, metadata)["plan_type"]
+        if not plan_type:
+            continue
+        monthly_price = PLAN_MONTHLY_PRICES_USD.get(plan_type)
+        cost = round(monthly_price * months, 2) if monthly_price is not None else None
+        if cost is not None:
+            total = (total or 0.0) + cost
+        accounts.append({
+            "email": email,
+            "plan_type": plan_type,
+            "monthly_price": monthly_price,
+            "cost": cost,
+        })
+
+    return {
+        "cost": round(total, 2) if total is not None else None,
+        "accounts": accounts,
+    }
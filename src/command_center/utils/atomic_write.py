@@ -0,0 +1,83 @@
+"""
+Atomic file writes.
+
+Data is written to a temporary file in the target's directory, flushed to
+disk (fsync) and renamed over the target. Readers and a crash mid-write only
+ever see the old or the new file, never a truncated one.
+"""
+from __future__ import annotations
+
+import json
+import os
+import uuid
+from pathlib import Path
+from typing import Any, Optional, Union
+
+PathLike = Union[str, "os.PathLike[str]"]
+
+
+def temp_path_for(path: PathLike) -> Path:
+    """Unique temporary file next to the target (same filesystem, so rename is atomic)."""
+    target = Path(path)
+    return target.with_name(f".{target.name}.{os.getpid()}.{uuid.uuid4().hex[:8]}.tmp")
+
+
+def _fsync_dir(directory: Path):
+    """Persist the rename itself (not possible on Windows)."""
+    if os.name == "nt":
+        return
+    try:
+        fd = os.open(directory, os.O_RDONLY)
+    except OSError:
+        return
+    try:
+        os.fsync(fd)
+    except OSError:
+        pass
+    finally:
+        os.close(fd)
+
+
+def commit_temp_file(tmp_path: PathLike, path: PathLike):
+    """
+    Flush a fully written temporary file to disk and rename it over the target.
+
+    For writers that need a file name (e.g. zipfile); on failure the
+    temporary file is removed.
+    """
+    target = Path(path)
+    try:
+        with open(tmp_path, "rb+") as f:
+            os.fsync(f.fileno())
+        os.replace(tmp_path, target)
+    except BaseException:
+        Path(tmp_path).unlink(missing_ok=True)
+        raise
+    _fsync_dir(target.parent)
+
+
+def write_bytes_atomic(path: PathLike, data: bytes):
+    """Atomically replace ``path`` with ``data`` (parent directories are created)."""
+    target = Path(path)
+    target.parent.mkdir(parents=True, exist_ok=True)
+    tmp_path = temp_path_for(target)
+    try:
+        with open(tmp_path, "xb") as f:
+            f.write(data)
+            f.flush()
+            os.fsync(f.fileno())
+        os.replace(tmp_path, target)
+    except BaseException:
+        tmp_path.unlink(missing_ok=True)
+        raise
+    _fsync_dir(target.parent)
+
+
+def write_text_atomic(path: PathLike, text: str, encoding: str = "utf-8"):
+    """Atomically replace ``path`` with ``text``."""
+    write_bytes_atomic(path, text.encode(encoding))
+
+
+def write_json_atomic(path: PathLike, data: Any, indent: Optional[int] = 2):
+    """Atomically replace ``path`` with ``data`` as JSON (UTF-8, non-ASCII kept)."""
+    write_text_atomic(path, json.dumps(data, indent=indent, ensure_ascii=False))
@@ -3,7 +3,7 @@ Date and time utility functions
 
 Handles UTC to local time conversion for hourly aggregation.
 """
-from datetime import datetime
+from datetime import date, datetime
 from typing import Optional
 
 
@@ -87,3 +87,64 @@ def parse_and_convert_to_local(timestamp_str: str) -> Optional[datetime]:
     if dt_utc is None:
         return None
     return convert_to_local(dt_utc)
+
+
+def parse_month_key(value: str) -> str:
+    """
+    Validate a YYYY-MM month key and return it normalized.
+
+    Raises:
+        ValueError: If the value is not a valid month
+    """
+    try:
+        return datetime.strptime(value.strip(), "%Y-%m").strftime("%Y-%m")
+    except ValueError:
+        raise ValueError(f"Invalid month: {value}. Use YYYY-MM format")
+
+
+def month_bounds(month: str) -> tuple[str, str]:
+    """
+    Return (first_day, last_day) for a YYYY-MM month key.
+
+    Returns:
+        Tuple like ("2025-02-01", "2025-02-28")
+    """
+    year, mon = (int(part) for part in month.split("-"))
+    first = date(year, mon, 1)
+    if mon == 12:
+        next_first = date(year + 1, 1, 1)
+    else:
+        next_first = date(year, mon + 1, 1)
+    last = date.fromordinal(next_first.toordinal() - 1)
+    return first.isoformat(), last.isoformat()
+
+
+def _anchor_date(year: int, mon: int, anchor_day: int) -> date:
+    """Anchor day in a month, clamped to the month's last day."""
+    _, last = month_bounds(f"{year:04d}-{mon:02d}")
+    return date(year, mon, min(anchor_day, int(last[-2:])))
+
+
+def billing_cycle_bounds(month: str, anchor_day: int = 1) -> tuple[str, str]:
+    """
+    Return (first_day, last_day) of the billing cycle starting in a month.
+
+    Cycles start on ``anchor_day`` (clamped to short months) and end the day
+    before the next cycle; anchor day 1 gives calendar months.
+
+    Returns:
+        Tuple like ("2025-02-17", "2025-03-16") for anchor day 17
+    """
+    year, mon = (int(part) for part in month.split("-"))
+    next_year, next_mon = (year + 1, 1) if mon == 12 else (year, mon + 1)
+    first = _anchor_date(year, mon, anchor_day)
+    next_first = _anchor_date(next_year, next_mon, anchor_day)
+    return first.isoformat(), date.fromordinal(next_first.toordinal() - 1).isoformat()
+
+
+def billing_cycle_key(day: date, anchor_day: int = 1) -> str:
+    """Month key (YYYY-MM) of the billing cycle containing a day."""
+    if day >= _anchor_date(day.year, day.month, anchor_day):
+        return day.strftime("%Y-%m")
+    year, mon = (day.year - 1, 12) if day.month == 1 else (day.year, day.month - 1)
+    return f"{year:04d}-{mon:02d}"
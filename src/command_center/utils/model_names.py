@@ -3,14 +3,29 @@ Model name formatting utilities.
 
 Shared between PNG generator and Tauri API.
 """
+import re
+
+# Release date of a model identifier, e.g. "-20250929"
+_DATE_SUFFIX_RE = re.compile(r"-[0-9]{8}\Z")
+
+# Model family with its version before or after it, e.g. "sonnet-4-5" or "3-5-haiku"
+_FAMILY_VERSION_RE = re.compile(
+    r"(?:(?P<before>[0-9]+(?:-[0-9]+)*)-)?(?P<family>[a-z]+)(?:-(?P<after>[0-9]+(?:-[0-9]+)*))?"
+)
 
 
 def format_model_name(model: str | None) -> str:
     """
     Format model name for display.
 
-    Converts internal model identifiers to human-readable names.
-    E.g., "claude-sonnet-4-20250514" -> "Sonnet 4"
+    Converts internal model identifiers to human-readable names: the "claude-"
+    prefix and a release date suffix are dropped, and a family with its
+    version (before or after it) becomes e.g. "Sonnet 4.5". Identifiers of
+    another form are shown without prefix and date.
+    E.g., "claude-sonnet-4-20250514" -> "Sonnet 4", "claude-3-5-haiku-20241022" -> "Haiku 3.5"
+
+    The desktop app's `display_name` (desktop/src-tauri/src/aggregate.rs)
+    formats names the same way.
 
     Args:
         model: Internal model identifier (e.g., "claude-sonnet-4-5-20251101")
@@ -21,32 +36,12 @@ def format_model_name(model: str | None) -> str:
     if not model:
         return "Unknown"
 
-    display_name = model.replace("claude-", "")
-
-    # Remove date suffixes
-    date_suffixes = ["-20250514", "-20250929", "-20250805", "-20251101", "-20241022", "-20251001"]
-    for suffix in date_suffixes:
-        display_name = display_name.replace(suffix, "")
-
-    # Format model variants
-    replacements = [
-        ("sonnet-4-5", "Sonnet 4.5"),
-        ("sonnet-4", "Sonnet 4"),
-        ("opus-4-5", "Opus 4.5"),
-        ("opus-4-1", "Opus 4.1"),
-        ("opus-4", "Opus 4"),
-        ("haiku-4-5", "Haiku 4.5"),
-        ("haiku-4", "Haiku 4"),
-        ("haiku-3-5", "Haiku 3.5"),
-        ("3-5-sonnet", "Sonnet 3.5"),
-        ("3-5-haiku", "Haiku 3.5"),
-        ("3-opus", "Opus 3"),
-    ]
-
-    for old, new in replacements:
-        display_name = display_name.replace(old, new)
-
-    return display_name
+    display_name = _DATE_SUFFIX_RE.sub("", model.replace("claude-", ""))
+    match = _FAMILY_VERSION_RE.fullmatch(display_name)
+    if not match or bool(match["before"]) == bool(match["after"]):
+        return display_name
+    version = (match["before"] or match["after"]).replace("-", ".")
+    return f"{match['family'].capitalize()} {version}"
 
 
 def format_tokens(count: int) -> str:
@@ -9,6 +9,7 @@ import requests
 from pathlib import Path
 from typing import Optional, Dict
 from dataclasses import dataclass
+from command_center.utils.atomic_write import write_json_atomic
 
 PRICING_URL = "https://raw.githubusercontent.com/BerriAI/litellm/main/model_prices_and_context_window.json"
 DEFAULT_TIERED_THRESHOLD = 200_000
@@ -70,9 +71,7 @@ def load_from_disk() -> Optional[Dict]:
 def save_to_disk(data: Dict) -> None:
     """Save pricing cache to disk."""
     try:
-        PRICING_CACHE_FILE.parent.mkdir(parents=True, exist_ok=True)
-        with open(PRICING_CACHE_FILE, 'w') as f:
-            json.dump(data, f)
+        write_json_atomic(PRICING_CACHE_FILE, data, indent=None)
     except Exception:
         pass  # Fail silently if can't write
 
@@ -168,6 +167,24 @@ def normalize_pricing(record: Dict) -> ModelPricing:
     )
 
 
+def find_model_pricing(model: str, data: Dict) -> Optional[ModelPricing]:
+    """Find a model in a pricing dataset (no remote fetch)."""
+    # Try exact match and aliases
+    candidates = create_candidates(model)
+    for candidate in candidates:
+        if candidate in data:
+            return normalize_pricing(data[candidate])
+
+    # Fallback: substring match (best-effort)
+    model_lower = model.lower()
+    for key, value in data.items():
+        key_lower = key.lower()
+        if model_lower in key_lower or key_lower in model_lower:
+            return normalize_pricing(value)
+
+    return None
+
+
 def get_model_pricing(model: str) -> Optional[ModelPricing]:
     """
     Get pricing for a model.
@@ -184,33 +201,16 @@ def get_model_pricing(model: str) -> Optional[ModelPricing]:
     Returns:
         ModelPricing or None if not found
     """
-    def find_in_dataset(data: Dict) -> Optional[ModelPricing]:
-        """Try to find model in pricing dataset."""
-        # Try exact match and aliases
-        candidates = create_candidates(model)
-        for candidate in candidates:
-            if candidate in data:
-                return normalize_pricing(data[candidate])
-
-        # Fallback: substring match (best-effort)
-        model_lower = model.lower()
-        for key, value in data.items():
-            key_lower = key.lower()
-            if model_lower in key_lower or key_lower in model_lower:
-                return normalize_pricing(value)
-
-        return None
-
     # 1. Try current pricing dataset
     pricing_data = load_pricing_dataset()
-    result = find_in_dataset(pricing_data)
+    result = find_model_pricing(model, pricing_data)
     if result:
         return result
 
     # 2. Model not found - try to update pricing (might be new model)
     print(f"Model '{model}' not found in pricing cache, attempting to update...")
     pricing_data = load_pricing_dataset(force_update=True)
-    result = find_in_dataset(pricing_data)
+    result = find_model_pricing(model, pricing_data)
     if result:
         return result
 
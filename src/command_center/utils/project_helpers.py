@@ -5,12 +5,24 @@ from pathlib import Path
 from typing import Optional
 
 
+def _is_config_dir_name(parts: tuple[str, ...], index: int) -> bool:
+    """Check whether parts[index] names a Claude config directory."""
+    part = parts[index]
+    if part == '.claude' or part.startswith('.claude-'):
+        return True
+    # ~/.config/claude and ~/.config/claude-* variants
+    return index > 0 and parts[index - 1] == '.config' and (part == 'claude' or part.startswith('claude-'))
+
+
 def extract_project_id(file_path: str | Path) -> str:
     """
     Extract project_id from file path in ~/.claude/projects/ structure.
 
     Pattern: ~/.claude/projects/{project_id}/{session}.jsonl
 
+    The config directory may be ~/.claude, ~/.config/claude or a
+    ~/.claude-* variant used with CLAUDE_CONFIG_DIR.
+
     The project_id is derived from the absolute path by replacing '/' with '-'.
     For example:
         /home/xai/DEV/command-center → -home-xai-DEV-command-center
@@ -35,10 +47,10 @@ def extract_project_id(file_path: str | Path) -> str:
     parts = path.parts
 
     try:
-        # Find '.claude' directory in path
+        # Find Claude config directory in path
         claude_idx = None
         for i, part in enumerate(parts):
-            if part == '.claude':
+            if _is_config_dir_name(parts, i):
                 claude_idx = i
                 break
 
@@ -1,18 +1,33 @@
 """
 Project metadata management with JSON persistence
 """
+import hashlib
 import json
 import os
+import re
 from datetime import datetime
 from pathlib import Path
 from typing import Optional
 
 from command_center.utils.project_helpers import reconstruct_absolute_path
+from command_center.utils.atomic_write import write_json_atomic
 
 
 # Default location for projects metadata JSON
 PROJECTS_JSON_PATH = os.path.expanduser("~/.claude/db/command-center-projects.json")
 
+# Project list orders; pinned projects always come first
+PROJECT_SORTS = ("manual", "last_activity", "cost_this_month", "name")
+
+# Colors of projects without a custom color (picked by project ID, so stable across views)
+PROJECT_PALETTE = (
+    "#d97757", "#6a9bcc", "#788c5d", "#c46686",
+    "#b8860b", "#5e7ce2", "#4c9f8f", "#8f6bb3",
+)
+
+PROJECT_COLOR_PATTERN = re.compile(r"^#[0-9a-fA-F]{6}$")
+MAX_PROJECT_ICON_LENGTH = 16
+
 
 def _get_local_now_iso() -> str:
     """Get current time in local timezone as ISO 8601 string"""
@@ -49,8 +64,7 @@ def load_projects_json(json_path: str = PROJECTS_JSON_PATH) -> dict:
 
     # Create empty file if it doesn't exist
     if not json_path.exists():
-        with open(json_path, 'w') as f:
-            json.dump({}, f, indent=2)
+        write_json_atomic(json_path, {})
         return {}
 
     # Load existing file
@@ -70,11 +84,7 @@ def save_projects_json(projects: dict, json_path: str = PROJECTS_JSON_PATH):
         projects: Dictionary mapping project_id → metadata
         json_path: Path to projects JSON file
     """
-    json_path = Path(json_path)
-    json_path.parent.mkdir(parents=True, exist_ok=True)
-
-    with open(json_path, 'w') as f:
-        json.dump(projects, f, indent=2, ensure_ascii=False)
+    write_json_atomic(json_path, projects)
 
 
 def auto_discover_project(
@@ -186,21 +196,41 @@ def list_all_projects(json_path: str = PROJECTS_JSON_PATH) -> list[dict]:
     return projects_list
 
 
+def project_color(project_id: str, metadata: Optional[dict] = None) -> str:
+    """
+    Display color of a project: its custom color, else a stable palette color.
+
+    Returns:
+        Hex color like "#d97757"
+    """
+    color = (metadata or {}).get('color')
+    if color:
+        return color
+    digest = hashlib.sha256(project_id.encode('utf-8')).digest()
+    return PROJECT_PALETTE[digest[0] % len(PROJECT_PALETTE)]
+
+
 def update_project_fields(
     project_id: str,
     name: Optional[str] = None,
     description: Optional[str] = None,
     visible: Optional[bool] = None,
+    tags: Optional[list[str]] = None,
+    color: Optional[str] = None,
+    icon: Optional[str] = None,
     json_path: str = PROJECTS_JSON_PATH
 ) -> dict:
     """
-    Update project fields (name, description, visible) via API.
+    Update project fields (name, description, visible, tags, color, icon) via API.
 
     Args:
         project_id: Project identifier
         name: New display name (optional)
         description: New description (optional)
         visible: Visibility flag (optional)
+        tags: Tag list (optional, empty list clears tags)
+        color: Hex color "#rrggbb" (optional, empty string clears it)
+        icon: Emoji or short icon name (optional, empty string clears it)
         json_path: Path to projects JSON file
 
     Returns:
@@ -234,12 +264,153 @@ def update_project_fields(
             raise ValueError("Visible must be a boolean value")
         projects[project_id]['visible'] = visible
 
+    # Tags: trimmed, unique, max 20 tags of max 30 characters
+    if tags is not None:
+        cleaned = []
+        for tag in tags:
+            tag = tag.strip()
+            if not tag or tag in cleaned:
+                continue
+            if len(tag) > 30:
+                raise ValueError(f"Tag cannot exceed 30 characters: {tag}")
+            cleaned.append(tag)
+        if len(cleaned) > 20:
+            raise ValueError("A project cannot have more than 20 tags")
+        projects[project_id]['tags'] = cleaned
+
+    # Color: #rrggbb, stored lowercase
+    if color is not None:
+        color = color.strip()
+        if not color:
+            projects[project_id].pop('color', None)
+        elif not PROJECT_COLOR_PATTERN.match(color):
+            raise ValueError(f"Invalid color: {color} (expected #rrggbb)")
+        else:
+            projects[project_id]['color'] = color.lower()
+
+    # Icon: an emoji or short icon name
+    if icon is not None:
+        icon = icon.strip()
+        if not icon:
+            projects[project_id].pop('icon', None)
+        elif len(icon) > MAX_PROJECT_ICON_LENGTH:
+            raise ValueError(f"Project icon cannot exceed {MAX_PROJECT_ICON_LENGTH} characters")
+        else:
+            projects[project_id]['icon'] = icon
+
     save_projects_json(projects, json_path)
 
     # Zwróć zaktualizowany projekt z project_id
     return {'project_id': project_id, **projects[project_id]}
 
 
+def set_project_pinned(project_id: str, pinned: bool, json_path: str = PROJECTS_JSON_PATH) -> dict:
+    """
+    Pin or unpin a project; pinned projects are listed first.
+
+    Returns:
+        Updated project data dict with project_id included
+
+    Raises:
+        ValueError: If project_id not found
+    """
+    projects = load_projects_json(json_path)
+    if project_id not in projects:
+        raise ValueError(f"Project not found: {project_id}")
+
+    projects[project_id]['pinned'] = bool(pinned)
+    save_projects_json(projects, json_path)
+    return {'project_id': project_id, **projects[project_id]}
+
+
+def archive_projects(project_ids: list[str], json_path: str = PROJECTS_JSON_PATH) -> list[dict]:
+    """
+    Archive projects: hide them from the project selector and unpin them.
+
+    Their usage stays in the database and in overall totals; archiving is
+    undone by making the project visible again.
+
+    Returns:
+        Updated project data dicts with project_id included
+
+    Raises:
+        ValueError: If a project is unknown
+    """
+    projects = load_projects_json(json_path)
+    unknown = [pid for pid in project_ids if pid not in projects]
+    if unknown:
+        raise ValueError(f"Project not found: {', '.join(unknown)}")
+
+    for project_id in project_ids:
+        projects[project_id]['visible'] = False
+        projects[project_id]['pinned'] = False
+    save_projects_json(projects, json_path)
+    return [{'project_id': pid, **projects[pid]} for pid in dict.fromkeys(project_ids)]
+
+
+def set_project_order(project_ids: list[str], json_path: str = PROJECTS_JSON_PATH) -> list[str]:
+    """
+    Persist a manual project order.
+
+    Listed projects get their position as ``sort_order``; projects left out
+    lose it and are listed after them, most recently seen first.
+
+    Args:
+        project_ids: Project IDs in display order
+
+    Returns:
+        The stored order
+
+    Raises:
+        ValueError: If a project is unknown or listed twice
+    """
+    projects = load_projects_json(json_path)
+    unknown = [pid for pid in project_ids if pid not in projects]
+    if unknown:
+        raise ValueError(f"Project not found: {', '.join(unknown)}")
+    if len(set(project_ids)) != len(project_ids):
+        raise ValueError("Project order lists a project more than once")
+
+    positions = {pid: index for index, pid in enumerate(project_ids)}
+    for project_id, metadata in projects.items():
+        if project_id in positions:
+            metadata['sort_order'] = positions[project_id]
+        else:
+            metadata.pop('sort_order', None)
+
+    save_projects_json(projects, json_path)
+    return list(project_ids)
+
+
+def sort_projects(projects: list[dict], sort: str = "manual") -> list[dict]:
+    """
+    Order a project list (as returned by list_all_projects).
+
+    Pinned projects come first, then by ``sort``:
+
+    - manual: stored sort_order, then unordered projects by last_seen
+    - last_activity: last_seen, most recent first
+    - cost_this_month: ``cost_this_month`` field, highest first
+    - name: display name (falls back to project_id), case-insensitive
+
+    Raises:
+        ValueError: If sort is not one of PROJECT_SORTS
+    """
+    if sort not in PROJECT_SORTS:
+        raise ValueError(f"Invalid sort: {sort} (expected {', '.join(PROJECT_SORTS)})")
+
+    # Stable sorts: last_seen first, then the requested key, then pinned
+    ordered = sorted(projects, key=lambda p: p.get('last_seen') or '', reverse=True)
+    if sort == "manual":
+        ordered.sort(key=lambda p: (p.get('sort_order') is None, p.get('sort_order') or 0))
+    elif sort == "cost_this_month":
+        ordered.sort(key=lambda p: p.get('cost_this_month') or 0.0, reverse=True)
+    elif sort == "name":
+        ordered.sort(key=lambda p: (p.get('name') or p['project_id']).lower())
+    ordered.sort(key=lambda p: not p.get('pinned', False))
+    return ordered
+
+
 def ensure_visible_field(json_path: str = PROJECTS_JSON_PATH) -> int:
     """
     Backward compatibility: Add 'visible: true' to projects missing this field.
@@ -0,0 +1,110 @@
+"""
+Minimal XLSX writer (stdlib only).
+
+Writes plain worksheets with string and number cells, enough for tabular
+exports that open cleanly in Excel, LibreOffice and Google Sheets.
+"""
+import io
+import zipfile
+from typing import Any, Sequence
+from xml.sax.saxutils import escape
+
+
+_CONTENT_TYPES = """<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>
+{sheets}
+</Types>"""
+
+_ROOT_RELS = """<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>
+</Relationships>"""
+
+_WORKBOOK = """<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<sheets>{sheets}</sheets>
+</workbook>"""
+
+_WORKBOOK_RELS = """<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+{rels}
+</Relationships>"""
+
+_SHEET = """<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<sheetData>{rows}</sheetData>
+</worksheet>"""
+
+
+def _column_letter(index: int) -> str:
+    """Convert a 0-based column index to A, B, ..., Z, AA, ..."""
+    letters = ""
+    index += 1
+    while index:
+        index, remainder = divmod(index - 1, 26)
+        letters = chr(65 + remainder) + letters
+    return letters
+
+
+def _cell(ref: str, value: Any) -> str:
+    if value is None or value == "":
+        return ""
+    if isinstance(value, bool):
+        value = str(value).lower()
+    if isinstance(value, (int, float)):
+        return f'<c r="{ref}"><v>{value}</v></c>'
+    return f'<c r="{ref}" t="inlineStr"><is><t xml:space="preserve">{escape(str(value))}</t></is></c>'
+
+
+def _sheet_xml(rows: Sequence[Sequence[Any]]) -> str:
+    row_xml = []
+    for row_index, row in enumerate(rows, start=1):
+        cells = "".join(
+            _cell(f"{_column_letter(col)}{row_index}", value)
+            for col, value in enumerate(row)
+        )
+        row_xml.append(f'<row r="{row_index}">{cells}</row>')
+    return _SHEET.format(rows="".join(row_xml))
+
+
+def build_xlsx(sheets: Sequence[tuple[str, Sequence[Sequence[Any]]]]) -> bytes:
+    """
+    Build an XLSX workbook.
+
+    Args:
+        sheets: List of (sheet_name, rows) tuples; sheet names are cut to 31 chars
+
+    Returns:
+        XLSX file bytes
+    """
+    buffer = io.BytesIO()
+    with zipfile.ZipFile(buffer, "w", zipfile.ZIP_DEFLATED) as archive:
+        overrides = "\n".join(
+            f'<Override PartName="/xl/worksheets/sheet{i}.xml" '
+            f'ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>'
+            for i in range(1, len(sheets) + 1)
+        )
+        archive.writestr("[Content_Types].xml", _CONTENT_TYPES.format(sheets=overrides))
+        archive.writestr("_rels/.rels", _ROOT_RELS)
+
+        sheet_entries = "".join(
+            f'<sheet name="{escape(name[:31])}" sheetId="{i}" r:id="rId{i}"/>'
+            for i, (name, _rows) in enumerate(sheets, start=1)
+        )
+        archive.writestr("xl/workbook.xml", _WORKBOOK.format(sheets=sheet_entries))
+
+        rels = "\n".join(
+            f'<Relationship Id="rId{i}" '
+            f'Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" '
+            f'Target="worksheets/sheet{i}.xml"/>'
+            for i in range(1, len(sheets) + 1)
+        )
+        archive.writestr("xl/_rels/workbook.xml.rels", _WORKBOOK_RELS.format(rels=rels))
+
+        for i, (_name, rows) in enumerate(sheets, start=1):
+            archive.writestr(f"xl/worksheets/sheet{i}.xml", _sheet_xml(rows))
+
+    return buffer.getvalue()
@@ -11,6 +11,7 @@ from typing import Optional
 
 try:
     from PIL import Image, ImageDraw, ImageFont
+    from PIL.PngImagePlugin import PngInfo
 except ImportError:
     print("Error: Pillow not installed. Run: pip install pillow")
     raise
@@ -18,7 +19,9 @@ except ImportError:
 from command_center.config import COLORS, CANVAS_WIDTH, CANVAS_HEIGHT, FONT_PATHS
 from command_center.database.models import UsageStats
 from command_center.aggregators.streak_calculator import calculate_streaks
+from command_center.calendar_overlay import excluded_days, load_calendar_overlay
 from command_center.utils.model_names import format_model_name
+from command_center.utils.project_metadata import project_color
 
 
 def load_font(size: int):
@@ -57,26 +60,159 @@ def format_large_num(num: int) -> str:
         return f"{num:,}"
 
 
-def generate_usage_report_png(stats: UsageStats) -> bytes:
+def hex_to_rgb(color: str) -> tuple[int, int, int]:
+    """Convert "#rrggbb" to an RGB tuple"""
+    return tuple(int(color[i:i + 2], 16) for i in (1, 3, 5))
+
+
+# Pixel density variants of the report (1 = 1500x1400, 2 = @2x, 3 = @3x)
+EXPORT_SCALES = (1, 2, 3)
+
+# Social card presets: format -> (width, height) in pixels
+SOCIAL_FORMATS = {
+    "twitter": (1200, 675),    # summary_large_image card
+    "linkedin": (1200, 627),   # shared link image
+}
+
+
+class ScaledDraw:
+    """
+    ImageDraw wrapper drawing a layout given in report pixels at a higher density.
+
+    Coordinates, radii and line widths are multiplied by the scale and text
+    bounding boxes are returned in report pixels, so the layout code is the
+    same for every scale.
+    """
+
+    def __init__(self, img, scale: int):
+        self._draw = ImageDraw.Draw(img)
+        self._scale = scale
+
+    def _xy(self, xy):
+        scaled = [round(value * self._scale) for value in xy]
+        return scaled if isinstance(xy, list) else tuple(scaled)
+
+    def text(self, xy, text, **kwargs):
+        self._draw.text(self._xy(xy), text, **kwargs)
+
+    def textbbox(self, xy, text, **kwargs):
+        return tuple(value / self._scale for value in self._draw.textbbox(self._xy(xy), text, **kwargs))
+
+    def rectangle(self, xy, width=1, **kwargs):
+        self._draw.rectangle(self._xy(xy), width=width * self._scale, **kwargs)
+
+    def rounded_rectangle(self, xy, radius=0, width=1, **kwargs):
+        self._draw.rounded_rectangle(self._xy(xy), radius=radius * self._scale, width=width * self._scale, **kwargs)
+
+
+def png_text_chunks(description: Optional[dict]) -> Optional[PngInfo]:
+    """PNG text chunks carrying the report's text alternative (see describe_usage_report)"""
+    if not description:
+        return None
+    info = PngInfo()
+    info.add_itxt("Title", description["alt_text"])
+    info.add_itxt("Description", description["long_description"])
+    return info
+
+
+def generate_usage_report_png(
+    stats: UsageStats,
+    projects_metadata: Optional[dict] = None,
+    scale: int = 1,
+    description: Optional[dict] = None
+) -> bytes:
     """
     Generate PNG image of the usage report.
 
     Args:
         stats: UsageStats object with all data
+        projects_metadata: Project metadata (project_id → {name, color, ...}) used
+            for project names and colors in the project strip
+        scale: Pixel density (see EXPORT_SCALES); 2 renders a 3000x2800 @2x image
+        description: Text alternative from describe_usage_report, embedded as the
+            PNG's Title and Description
 
     Returns:
         PNG bytes
     """
+    buffer = BytesIO()
+    render_usage_report(stats, projects_metadata, scale).save(
+        buffer, format='PNG', pnginfo=png_text_chunks(description)
+    )
+    return buffer.getvalue()
+
+
+def generate_social_card_png(
+    stats: UsageStats,
+    projects_metadata: Optional[dict],
+    fmt: str,
+    description: Optional[dict] = None
+) -> bytes:
+    """
+    Generate the usage report as a social card of a fixed size.
+
+    The report is rendered @2x, scaled down to the card height and centered
+    on the report background.
+
+    Args:
+        fmt: Key of SOCIAL_FORMATS
+        description: Text alternative embedded as in generate_usage_report_png
+
+    Returns:
+        PNG bytes
+
+    Raises:
+        ValueError: For an unknown format
+    """
+    if fmt not in SOCIAL_FORMATS:
+        raise ValueError(f"Unknown social format: {fmt} (expected {', '.join(SOCIAL_FORMATS)})")
+    width, height = SOCIAL_FORMATS[fmt]
+    report = render_usage_report(stats, projects_metadata, 2)
+    fit = min(width / report.width, height / report.height)
+    report = report.resize((round(report.width * fit), round(report.height * fit)), Image.LANCZOS)
+
+    card = Image.new('RGB', (width, height), COLORS['background'])
+    card.paste(report, ((width - report.width) // 2, (height - report.height) // 2))
+    buffer = BytesIO()
+    card.save(buffer, format='PNG', pnginfo=png_text_chunks(description))
+    return buffer.getvalue()
+
+
+def generate_usage_report_pdf(stats: UsageStats, projects_metadata: Optional[dict] = None) -> bytes:
+    """
+    Generate the usage report as a one-page PDF.
+
+    The page is the @2x report at 192 dpi, i.e. the size of the 1x report
+    on a 96 dpi screen.
+
+    Returns:
+        PDF bytes
+    """
+    buffer = BytesIO()
+    render_usage_report(stats, projects_metadata, 2).save(buffer, format='PDF', resolution=192.0)
+    return buffer.getvalue()
+
+
+def render_usage_report(stats: UsageStats, projects_metadata: Optional[dict] = None, scale: int = 1):
+    """
+    Render the usage report image (CANVAS_WIDTH x CANVAS_HEIGHT times scale).
+
+    Raises:
+        ValueError: If scale is not one of EXPORT_SCALES
+    """
+    if scale not in EXPORT_SCALES:
+        raise ValueError(f"Invalid scale: {scale} (expected {', '.join(map(str, EXPORT_SCALES))})")
+
     # Create canvas with background color
-    img = Image.new('RGB', (CANVAS_WIDTH, CANVAS_HEIGHT), COLORS['background'])
-    draw = ImageDraw.Draw(img)
+    img = Image.new('RGB', (CANVAS_WIDTH * scale, CANVAS_HEIGHT * scale), COLORS['background'])
+    draw = ScaledDraw(img, scale)
 
     # Fonts
-    font_large = load_font(48)
-    font_title = load_font(48)
-    font_medium = load_font(32)
-    font_small = load_font(24)
-    font_tiny = load_font(18)
+    font_large = load_font(48 * scale)
+    font_title = load_font(48 * scale)
+    font_medium = load_font(32 * scale)
+    font_small = load_font(24 * scale)
+    font_tiny = load_font(18 * scale)
 
     y_offset = 80
 
@@ -299,7 +435,7 @@ def generate_usage_report_png(stats: UsageStats) -> bytes:
     y_offset = panel_y + panel_height + 60
 
     # === STATS GRID ===
-    max_streak, curr_streak = calculate_streaks(stats.daily_activity)
+    max_streak, curr_streak = calculate_streaks(stats.daily_activity, excluded_days(load_calendar_overlay()))
 
     grid_panel_height = 200
     grid_panel_width = panel_width * 2 + panel_gap  # Same total width as two panels above
@@ -319,7 +455,7 @@ def generate_usage_report_png(stats: UsageStats) -> bytes:
         ("SESSIONS", f"{stats.total_sessions:,}", stat_x1),
         ("MESSAGES", f"{stats.total_messages:,}", stat_x2),
         ("TOTAL TOKENS", format_large_num(stats.total_tokens), stat_x3),
-        ("PROJECTS", "N/A", stat_x1),  # Projects not tracked in new version
+        ("PROJECTS", f"{stats.project_count:,}" if stats.project_count else "N/A", stat_x1),
         ("STREAK", f"{max_streak}d", stat_x2),
         ("USAGE COST", f"${stats.total_cost:,.2f}" if stats.total_cost > 0 else "N/A", stat_x3),
     ]
@@ -329,6 +465,36 @@ def generate_usage_report_png(stats: UsageStats) -> bytes:
         draw.text((x_pos, y_pos), label, fill=COLORS['text_muted'], font=font_tiny)
         draw.text((x_pos, y_pos + 30), value, fill=COLORS['accent_primary'], font=font_medium)
 
+    # === PROJECT STRIP === (token share of the top projects, in project colors)
+    project_tokens = sum(p['tokens'] for p in stats.top_projects)
+    if project_tokens > 0:
+        projects_metadata = projects_metadata or {}
+        strip_y = y_offset + grid_panel_height + 30
+        strip_x = panel_x1
+        for project in stats.top_projects:
+            meta = projects_metadata.get(project['project_id'], {})
+            color = hex_to_rgb(project_color(project['project_id'], meta))
+            width = round(grid_panel_width * project['tokens'] / project_tokens)
+            draw.rectangle([strip_x, strip_y, min(strip_x + width, panel_x1 + grid_panel_width), strip_y + 16],
+                           fill=color)
+            strip_x += width
+
+        legend_x = panel_x1
+        legend_y = strip_y + 30
+        for project in stats.top_projects:
+            meta = projects_metadata.get(project['project_id'], {})
+            color = hex_to_rgb(project_color(project['project_id'], meta))
+            name = meta.get('name') or project['project_id']
+            if len(name) > 18:
+                name = name[:17] + "…"
+            label = f"{name} {project['tokens'] / project_tokens * 100:.0f}%"
+            label_width = draw.textbbox((0, 0), label, font=font_tiny)[2]
+            if legend_x + 22 + label_width > panel_x1 + grid_panel_width:
+                break
+            draw.rectangle([legend_x, legend_y + 4, legend_x + 14, legend_y + 18], fill=color)
+            draw.text((legend_x + 22, legend_y), label, fill=COLORS['text_secondary'], font=font_tiny)
+            legend_x += 22 + label_width + 30
+
     # Footer
     footer_text = "claude.ai/code"
     bbox = draw.textbbox((0, 0), footer_text, font=font_small)
@@ -336,7 +502,4 @@ def generate_usage_report_png(stats: UsageStats) -> bytes:
     x_center = (CANVAS_WIDTH - text_width) // 2
     draw.text((x_center, CANVAS_HEIGHT - 60), footer_text, fill=COLORS['text_muted'], font=font_small)
 
-    # Convert to bytes
-    buffer = BytesIO()
-    img.save(buffer, format='PNG')
-    return buffer.getvalue()
+    return img
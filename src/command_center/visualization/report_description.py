@@ -0,0 +1,115 @@
+"""
+Text alternatives for the PNG usage report
+
+The report is an image; these descriptions say what it shows so reports
+shared with screen-reader users carry the same information. The short alt
+text fits an `alt` attribute; the long description walks through every panel
+(one line each) in the order they are drawn.
+"""
+import datetime
+from typing import Optional
+
+from command_center.database.models import UsageStats
+from command_center.aggregators.streak_calculator import calculate_streaks
+from command_center.calendar_overlay import excluded_days, load_calendar_overlay
+from command_center.utils.model_names import format_model_name
+
+# Models and projects named in the long description
+MAX_LISTED = 5
+
+
+def _tokens(count: int) -> str:
+    """Token count in words a screen reader reads well ("12.3 million")"""
+    if count >= 1e9:
+        return f"{count / 1e9:.1f} billion"
+    if count >= 1e6:
+        return f"{count / 1e6:.1f} million"
+    return f"{count:,}"
+
+
+def _share(part: float, total: float) -> str:
+    return f"{part / total * 100:.0f}%" if total > 0 else "0%"
+
+
+def _plural(count: int, noun: str) -> str:
+    return f"{count:,} {noun}" if count == 1 else f"{count:,} {noun}s"
+
+
+def describe_usage_report(
+    stats: UsageStats,
+    projects_metadata: Optional[dict] = None,
+    excluded: Optional[set[str]] = None,
+) -> dict:
+    """
+    Describe the usage report image in text.
+
+    Args:
+        stats: Data the report was rendered from
+        projects_metadata: Project metadata (project_id → {name, ...}) for project names
+        excluded: Days off for the streaks (default: the calendar overlay, as in the image)
+
+    Returns:
+        {"alt_text": one sentence, "long_description": one line per report panel}
+    """
+    if excluded is None:
+        excluded = excluded_days(load_calendar_overlay())
+    projects_metadata = projects_metadata or {}
+    period = f"{stats.date_from} to {stats.date_to}"
+    cost = f"${stats.total_cost:,.2f} usage cost" if stats.total_cost > 0 else "no usage cost"
+    top_model = format_model_name(stats.top_models[0]['model']) if stats.top_models else None
+
+    alt_text = (
+        f"Claude Code usage report for {period}: {_plural(stats.total_messages, 'message')} in "
+        f"{_plural(stats.total_sessions, 'session')}, {_tokens(stats.total_tokens)} tokens, {cost}"
+    )
+    alt_text += f"; most used model {top_model}." if top_model else "."
+
+    lines = [f"Claude Code usage report, {period}."]
+    if stats.first_session_date:
+        lines.append(f"Started: first session on {stats.first_session_date.strftime('%Y-%m-%d')}.")
+
+    days = (datetime.date.fromisoformat(stats.date_to) - datetime.date.fromisoformat(stats.date_from)).days + 1
+    active = {day: count for day, count in stats.daily_activity.items() if count > 0}
+    if active:
+        best_day, best_count = max(active.items(), key=lambda item: item[1])
+        lines.append(f"Most active day: {best_day} with {_plural(best_count, 'message')}.")
+        lines.append(
+            f"Activity heatmap: messages on {len(active):,} of {_plural(days, 'day')}, "
+            f"{sum(active.values()) / len(active):,.0f} per active day on average."
+        )
+    else:
+        lines.append(f"Activity heatmap: no messages in the {_plural(days, 'day')}.")
+
+    max_streak, current_streak = calculate_streaks(stats.daily_activity, excluded)
+    lines.append(
+        f"Totals: {_plural(stats.total_sessions, 'session')}, {_plural(stats.total_messages, 'message')}, "
+        f"{_tokens(stats.total_tokens)} tokens, {_plural(stats.project_count, 'project')}, "
+        f"longest streak {_plural(max_streak, 'day')} (current {_plural(current_streak, 'day')}), {cost}."
+    )
+
+    if stats.top_models:
+        model_tokens = sum(model['tokens'] for model in stats.top_models)
+        models = ", ".join(
+            f"{i}. {format_model_name(model['model'])} {_tokens(model['tokens'])} tokens "
+            f"({_share(model['tokens'], model_tokens)})"
+            for i, model in enumerate(stats.top_models[:MAX_LISTED], 1)
+        )
+        lines.append(f"Top models by tokens: {models}.")
+
+    cache_total = stats.cache_read_tokens + stats.cache_write_tokens
+    lines.append(
+        f"Cache efficiency: {_tokens(stats.cache_read_tokens)} tokens read, "
+        f"{_tokens(stats.cache_write_tokens)} written, "
+        f"hit rate {stats.cache_read_tokens / cache_total * 100 if cache_total else 0:.1f}%."
+    )
+
+    project_tokens = sum(project['tokens'] for project in stats.top_projects)
+    if project_tokens > 0:
+        projects = ", ".join(
+            f"{projects_metadata.get(project['project_id'], {}).get('name') or project['project_id']} "
+            f"{_share(project['tokens'], project_tokens)}"
+            for project in stats.top_projects[:MAX_LISTED]
+        )
+        lines.append(f"Token share of the top projects: {projects}.")
+
+    return {"alt_text": alt_text, "long_description": "\n".join(lines)}
@@ -0,0 +1,173 @@
+"""
+Working hours and out-of-hours usage.
+
+Working hours are the weekdays and the daily time window counted as work
+time. Usage is split by the local time of each message into work and
+personal time, e.g. to expense only the work share of a subscription.
+Calendar overlay days (vacations, holidays) are always personal time.
+The definition is stored in a small JSON file next to the database.
+"""
+from __future__ import annotations
+
+import json
+import os
+import re
+import sqlite3
+from datetime import datetime
+from typing import Any, Optional
+
+from command_center.utils.atomic_write import write_json_atomic
+
+
+# Default location for the working hours JSON
+WORKING_HOURS_JSON_PATH = os.path.expanduser("~/.claude/db/command-center-working-hours.json")
+
+# ISO weekdays (1 = Monday ... 7 = Sunday)
+DEFAULT_WORKING_HOURS = {"days": [1, 2, 3, 4, 5], "start": "09:00", "end": "17:00"}
+
+TIME_PATTERN = re.compile(r"^([01]\d|2[0-3]):([0-5]\d)$")
+
+
+def _parse_time(value: Any) -> str:
+    text = str(value).strip()
+    if len(text) == 4 and text[1] == ":":
+        text = "0" + text
+    if not TIME_PATTERN.match(text):
+        raise ValueError(f"Invalid time: {value} (expected HH:MM)")
+    return text
+
+
+def load_working_hours(json_path: str = WORKING_HOURS_JSON_PATH) -> dict:
+    """
+    Load the working hours definition.
+
+    Returns:
+        {"days": [1, ..., 5], "start": "09:00", "end": "17:00"}; defaults when unset or unreadable
+    """
+    try:
+        with open(json_path, "r", encoding="utf-8") as f:
+            loaded = json.load(f)
+    except (OSError, json.JSONDecodeError):
+        return dict(DEFAULT_WORKING_HOURS)
+    if not isinstance(loaded, dict):
+        return dict(DEFAULT_WORKING_HOURS)
+    return {key: loaded.get(key, default) for key, default in DEFAULT_WORKING_HOURS.items()}
+
+
+def set_working_hours(
+    days: list[int],
+    start: str,
+    end: str,
+    json_path: str = WORKING_HOURS_JSON_PATH
+) -> dict:
+    """
+    Replace the working hours definition.
+
+    Args:
+        days: ISO weekdays counted as work days (1 = Monday ... 7 = Sunday); may be empty
+        start: Start of the work window (HH:MM, local time)
+        end: End of the work window (HH:MM, exclusive, after start)
+
+    Returns:
+        {"days", "start", "end"} as stored
+
+    Raises:
+        ValueError: For unknown weekdays, malformed times or an empty window
+    """
+    try:
+        weekdays = sorted({int(day) for day in days})
+    except (TypeError, ValueError):
+        raise ValueError("Days must be ISO weekday numbers (1 = Monday ... 7 = Sunday)")
+    if any(day < 1 or day > 7 for day in weekdays):
+        raise ValueError("Days must be ISO weekday numbers (1 = Monday ... 7 = Sunday)")
+    start, end = _parse_time(start), _parse_time(end)
+    if end <= start:
+        raise ValueError(f"Working hours must end after they start: {start} - {end}")
+
+    stored = {"days": weekdays, "start": start, "end": end}
+    write_json_atomic(json_path, stored)
+    return stored
+
+
+def is_work_time(date: str, time: str, working_hours: dict, excluded: set[str]) -> bool:
+    """
+    Whether a local date (YYYY-MM-DD) and time (HH:MM) fall in working hours.
+
+    Args:
+        excluded: Calendar overlay days, which are never work time
+    """
+    if date in excluded:
+        return False
+    weekday = datetime.strptime(date, "%Y-%m-%d").isoweekday()
+    return weekday in working_hours["days"] and working_hours["start"] <= time < working_hours["end"]
+
+
+def query_out_of_hours_usage(
+    conn: sqlite3.Connection,
+    date_from: str,
+    date_to: str,
+    working_hours: dict,
+    excluded: set[str],
+    project_id: Optional[str] = None
+) -> dict:
+    """
+    Split usage of a date range into work and personal time.
+
+    Args:
+        conn: Database connection
+        date_from: Start date (YYYY-MM-DD)
+        date_to: End date (YYYY-MM-DD)
+        working_hours: Definition from load_working_hours
+        excluded: Calendar overlay days (see calendar_overlay.excluded_days)
+        project_id: Optional project filter
+
+    Returns:
+        {
+            "range": {"from", "to"},
+            "working_hours": {"days", "start", "end"},
+            "work": {"messages", "tokens", "cost", "sessions"},
+            "personal": {...},
+            "work_share": 72.4,   # percentage of cost in work time, None without cost
+            "days": [{"date", "work": {"messages", "tokens", "cost"}, "personal": {...}}, ...]
+        }
+        A session counts towards each side it has messages in.
+    """
+    project_filter = "AND project_id = ?" if project_id else ""
+    params: list = [date_from, date_to] + ([project_id] if project_id else [])
+    rows = conn.execute(f"""
+        SELECT date, SUBSTR(timestamp_local, 12, 5) AS minute, session_id,
+               COUNT(*), SUM(total_tokens), SUM(cost_usd)
+        FROM message_entries
+        WHERE date BETWEEN ? AND ? {project_filter}
+        GROUP BY date, minute, session_id
+    """, params).fetchall()
+
+    def empty() -> dict:
+        return {"messages": 0, "tokens": 0, "cost": 0.0}
+
+    totals = {"work": empty(), "personal": empty()}
+    sessions: dict[str, set[str]] = {"work": set(), "personal": set()}
+    days: dict[str, dict] = {}
+    for date, minute, session_id, messages, tokens, cost in rows:
+        side = "work" if is_work_time(date, minute or "00:00", working_hours, excluded) else "personal"
+        day = days.setdefault(date, {"date": date, "work": empty(), "personal": empty()})
+        for target in (totals[side], day[side]):
+            target["messages"] += messages
+            target["tokens"] += tokens or 0
+            target["cost"] += cost or 0
+        if session_id:
+            sessions[side].add(session_id)
+
+    for side in ("work", "personal"):
+        totals[side]["sessions"] = len(sessions[side])
+    for entry in [totals["work"], totals["personal"]] + [d[s] for d in days.values() for s in ("work", "personal")]:
+        entry["cost"] = round(entry["cost"], 4)
+    total_cost = totals["work"]["cost"] + totals["personal"]["cost"]
+
+    return {
+        "range": {"from": date_from, "to": date_to},
+        "working_hours": working_hours,
+        **totals,
+        "work_share": round(totals["work"]["cost"] / total_cost * 100, 1) if total_cost else None,
+        "days": [days[date] for date in sorted(days)],
+    }
@@ -0,0 +1,192 @@
+"""
+Workspaces: optional groups of projects (e.g. a client or a product area).
+
+Workspaces are stored in a small JSON file next to the database; the
+assignment of a project is the ``workspace_id`` field of its entry in the
+projects metadata file. A project belongs to at most one workspace, and
+dashboards can be filtered to all projects of a workspace.
+"""
+from __future__ import annotations
+
+import json
+import os
+import uuid
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Optional
+
+from command_center.utils.atomic_write import write_json_atomic
+from command_center.utils.project_metadata import PROJECTS_JSON_PATH, load_projects_json, save_projects_json
+
+
+# Default location for workspace configuration JSON
+WORKSPACES_JSON_PATH = os.path.expanduser("~/.claude/db/command-center-workspaces.json")
+
+MAX_WORKSPACE_NAME_LENGTH = 100
+
+
+def load_workspaces(json_path: str = WORKSPACES_JSON_PATH) -> dict[str, dict[str, Any]]:
+    """
+    Load workspaces.
+
+    Returns:
+        Dict mapping workspace_id → {"name", "description", "created_at"}
+    """
+    path = Path(json_path)
+    if not path.exists():
+        return {}
+    try:
+        with open(path, "r", encoding="utf-8") as f:
+            loaded = json.load(f)
+    except (json.JSONDecodeError, IOError):
+        return {}
+    return loaded if isinstance(loaded, dict) else {}
+
+
+def save_workspaces(workspaces: dict[str, dict[str, Any]], json_path: str = WORKSPACES_JSON_PATH):
+    """Save workspaces."""
+    write_json_atomic(json_path, workspaces)
+
+
+def list_workspaces(
+    json_path: str = WORKSPACES_JSON_PATH,
+    projects_json_path: str = PROJECTS_JSON_PATH
+) -> list[dict[str, Any]]:
+    """
+    Get all workspaces sorted by name, with their project IDs.
+
+    Returns:
+        [{"workspace_id", "name", "description", "created_at", "projects": [...]}, ...]
+    """
+    projects = load_projects_json(projects_json_path)
+    result = []
+    for workspace_id, workspace in load_workspaces(json_path).items():
+        members = sorted(pid for pid, meta in projects.items() if meta.get("workspace_id") == workspace_id)
+        result.append({"workspace_id": workspace_id, **workspace, "projects": members})
+    return sorted(result, key=lambda w: w["name"].lower())
+
+
+def create_workspace(
+    name: str,
+    description: str = "",
+    json_path: str = WORKSPACES_JSON_PATH
+) -> dict[str, Any]:
+    """
+    Create a workspace.
+
+    Args:
+        name: Display name (unique, case-insensitive)
+        description: Optional description
+
+    Returns:
+        The new workspace ({"workspace_id", "name", "description", "created_at", "projects": []})
+
+    Raises:
+        ValueError: If the name is empty, too long or already used
+    """
+    name = name.strip()
+    if not name:
+        raise ValueError("Workspace name cannot be empty")
+    if len(name) > MAX_WORKSPACE_NAME_LENGTH:
+        raise ValueError(f"Workspace name too long (max {MAX_WORKSPACE_NAME_LENGTH} characters)")
+
+    workspaces = load_workspaces(json_path)
+    if any(w.get("name", "").lower() == name.lower() for w in workspaces.values()):
+        raise ValueError(f"Workspace already exists: {name}")
+
+    workspace_id = uuid.uuid4().hex[:12]
+    workspaces[workspace_id] = {
+        "name": name,
+        "description": description.strip(),
+        "created_at": datetime.now().astimezone().isoformat(),
+    }
+    save_workspaces(workspaces, json_path)
+    return {"workspace_id": workspace_id, **workspaces[workspace_id], "projects": []}
+
+
+def delete_workspace(
+    workspace_id: str,
+    json_path: str = WORKSPACES_JSON_PATH,
+    projects_json_path: str = PROJECTS_JSON_PATH
+) -> dict[str, Any]:
+    """
+    Delete a workspace; its projects become unassigned.
+
+    Returns:
+        {"deleted": workspace_id, "unassigned_projects": 3}
+
+    Raises:
+        ValueError: If the workspace does not exist
+    """
+    workspaces = load_workspaces(json_path)
+    if workspace_id not in workspaces:
+        raise ValueError(f"Workspace not found: {workspace_id}")
+
+    projects = load_projects_json(projects_json_path)
+    unassigned = 0
+    for meta in projects.values():
+        if meta.get("workspace_id") == workspace_id:
+            meta.pop("workspace_id")
+            unassigned += 1
+    if unassigned:
+        save_projects_json(projects, projects_json_path)
+
+    del workspaces[workspace_id]
+    save_workspaces(workspaces, json_path)
+    return {"deleted": workspace_id, "unassigned_projects": unassigned}
+
+
+def assign_project_to_workspace(
+    project_id: str,
+    workspace_id: Optional[str],
+    json_path: str = WORKSPACES_JSON_PATH,
+    projects_json_path: str = PROJECTS_JSON_PATH
+) -> dict[str, Any]:
+    """
+    Move a project into a workspace, or out of its workspace.
+
+    Args:
+        project_id: Project identifier
+        workspace_id: Target workspace (None removes the project from its workspace)
+
+    Returns:
+        {"project_id", "workspace_id", "previous_workspace_id"}
+
+    Raises:
+        ValueError: If the project or workspace does not exist
+    """
+    projects = load_projects_json(projects_json_path)
+    if project_id not in projects:
+        raise ValueError(f"Project not found: {project_id}. Run a scan first to discover projects.")
+    if workspace_id is not None and workspace_id not in load_workspaces(json_path):
+        raise ValueError(f"Workspace not found: {workspace_id}")
+
+    previous = projects[project_id].get("workspace_id")
+    if workspace_id is None:
+        projects[project_id].pop("workspace_id", None)
+    else:
+        projects[project_id]["workspace_id"] = workspace_id
+    save_projects_json(projects, projects_json_path)
+    return {"project_id": project_id, "workspace_id": workspace_id, "previous_workspace_id": previous}
+
+
+def workspace_project_ids(
+    workspace_id: str,
+    json_path: str = WORKSPACES_JSON_PATH,
+    projects_json_path: str = PROJECTS_JSON_PATH
+) -> list[str]:
+    """
+    Project IDs of a workspace, used as a dashboard project filter.
+
+    Raises:
+        ValueError: If the workspace does not exist or has no projects
+    """
+    if workspace_id not in load_workspaces(json_path):
+        raise ValueError(f"Workspace not found: {workspace_id}")
+    members = sorted(
+        pid for pid, meta in load_projects_json(projects_json_path).items()
+        if meta.get("workspace_id") == workspace_id
+    )
+    if not members:
+        raise ValueError(f"Workspace has no projects: {workspace_id}")
+    return members
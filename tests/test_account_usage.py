@@ -0,0 +1,59 @@
+"""
+Unit tests for per-account usage of the combined account dashboard
+"""
+import sqlite3
+
+from command_center import claude_configs
+from command_center.claude_configs import query_account_usage
+from command_center.database.schema import init_database
+
+
+def _insert(conn, entry_hash, date, config_dir, session_id="s1", tokens=100, cost=0.5):
+    conn.execute("""
+        INSERT INTO message_entries
+        (entry_hash, timestamp, timestamp_local, year, date, session_id, total_tokens, cost_usd,
+         source_file, config_dir)
+        VALUES (?, ?, ?, 2025, ?, ?, ?, ?, 's.jsonl', ?)
+    """, (entry_hash, f"{date}T10:00:00Z", f"{date}T10:00:00+00:00", date, session_id, tokens, cost, config_dir))
+
+
+class TestQueryAccountUsage:
+    """Tests for query_account_usage"""
+
+    def test_totals_and_daily_of_bound_config_dirs(self, monkeypatch):
+        """Only messages from the account's config dirs in the range are counted"""
+        monkeypatch.setattr(claude_configs, "load_config_bindings", lambda: {
+            "/home/u/.claude-work": {"account_email": "work@x.com"},
+            "/home/u/.claude-work2": {"account_email": "work@x.com"},
+            "/home/u/.claude": {"account_email": "home@x.com"},
+        })
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        _insert(conn, "a", "2025-03-01", "/home/u/.claude-work")
+        _insert(conn, "b", "2025-03-01", "/home/u/.claude-work2", session_id="s2")
+        _insert(conn, "c", "2025-03-02", "/home/u/.claude-work", cost=0.25)
+        _insert(conn, "d", "2025-03-02", "/home/u/.claude")
+        _insert(conn, "e", "2025-04-01", "/home/u/.claude-work")
+
+        usage = query_account_usage(conn, "work@x.com", "2025-03-01", "2025-03-31")
+
+        assert usage["config_dirs"] == ["/home/u/.claude-work", "/home/u/.claude-work2"]
+        assert usage["totals"] == {"messages": 3, "sessions": 2, "tokens": 300, "cost": 1.25}
+        assert usage["daily"] == [
+            {"date": "2025-03-01", "messages": 2, "tokens": 200, "cost": 1.0},
+            {"date": "2025-03-02", "messages": 1, "tokens": 100, "cost": 0.25},
+        ]
+
+    def test_unbound_account_is_empty(self, monkeypatch):
+        """An account without config dirs has zero totals"""
+        monkeypatch.setattr(claude_configs, "load_config_bindings", lambda: {})
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+
+        usage = query_account_usage(conn, "work@x.com", "2025-03-01", "2025-03-31")
+
+        assert usage == {
+            "config_dirs": [],
+            "totals": {"messages": 0, "sessions": 0, "tokens": 0, "cost": 0.0},
+            "daily": [],
+        }
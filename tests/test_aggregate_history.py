@@ -0,0 +1,62 @@
+"""
+Unit tests for the daily aggregate history
+"""
+import sqlite3
+
+from command_center.database.models import MessageEntry
+from command_center.database.queries import (
+    insert_message_entries,
+    query_aggregate_history,
+    recompute_hourly_aggregates,
+)
+from command_center.database.schema import init_database
+
+HOUR = "2025-03-01 11:00:00"
+
+
+def _entry(entry_hash, session_id="s1", tokens=100, cost=0.5):
+    return MessageEntry(
+        entry_hash=entry_hash, timestamp="2025-03-01T10:00:00Z",
+        timestamp_local="2025-03-01T11:00:00+01:00", year=2025, date="2025-03-01",
+        session_id=session_id, message_id=entry_hash, model="claude-sonnet-4", cost_usd=cost,
+        total_tokens=tokens, source_file="/a/projects/p/s1.jsonl", project_id="p",
+    )
+
+
+class TestAggregateHistory:
+    """Tests for record_daily_history and query_aggregate_history"""
+
+    def test_versions_are_appended_when_totals_change(self):
+        """Ingest and reprice each add a version; an unchanged recompute adds none"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        assert query_aggregate_history(conn, "2025-03-01") == {"date": "2025-03-01", "current": None, "history": []}
+
+        insert_message_entries(conn, [_entry("m1"), _entry("m2", session_id="s2")])
+        recompute_hourly_aggregates(conn, {HOUR})
+        recompute_hourly_aggregates(conn, {HOUR})
+        conn.execute("UPDATE message_entries SET cost_usd = 0.75")
+        recompute_hourly_aggregates(conn, {HOUR}, reason="reprice")
+
+        history = query_aggregate_history(conn, "2025-03-01")
+        assert [(v["reason"], v["messages"], v["sessions"], v["cost"]) for v in history["history"]] == [
+            ("ingest", 2, 2, 1.0),
+            ("reprice", 2, 2, 1.5),
+        ]
+        assert history["history"][1]["delta"] == {"messages": 0, "sessions": 0, "tokens": 0, "cost": 0.5}
+        assert history["current"] == {"messages": 2, "sessions": 2, "tokens": 200, "cost": 1.5}
+
+    def test_emptied_day_is_recorded(self):
+        """A day whose messages moved away gets a zero version"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        insert_message_entries(conn, [_entry("m1")])
+        recompute_hourly_aggregates(conn, {HOUR})
+        conn.execute("DELETE FROM message_entries")
+        recompute_hourly_aggregates(conn, {HOUR}, reason="time-shift")
+
+        history = query_aggregate_history(conn, "2025-03-01")["history"]
+        assert [(v["reason"], v["messages"], v["delta"]["tokens"]) for v in history] == [
+            ("ingest", 1, 100),
+            ("time-shift", 0, -100),
+        ]
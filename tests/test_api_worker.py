@@ -0,0 +1,107 @@
+"""
+Unit tests for api_worker module
+"""
+import io
+import json
+import os
+import sys
+
+import pytest
+
+from command_center.api_worker import read_stdin_request, serve
+from command_center.audit_log import REQUEST_ID_ENV
+from command_center.cache.refresh_status import track_refresh
+from command_center.result_stream import stream_result
+
+
+def _handler(args):
+    """Stand-in for tauri_api.main: echo, fail or crash"""
+    if args[0] == "echo":
+        print(json.dumps({"args": args[1:], "request_id": os.environ.get(REQUEST_ID_ENV)}))
+    elif args[0] == "refresh":
+        with track_refresh("full", args[1]) as tracker:
+            tracker.progress(2, 2)
+        print(json.dumps({"updated_files": 2}))
+    elif args[0] == "session":
+        print(json.dumps(stream_result("session", {"session_id": "s1", "messages": [1, 2, 3]}, chunk_size=2)))
+    elif args[0] == "fail":
+        print(json.dumps({"error": "bad", "type": "ValueError"}), file=sys.stderr)
+        sys.exit(1)
+    else:
+        raise RuntimeError("boom")
+
+
+def _serve(*requests):
+    stdin = io.StringIO("".join(
+        (r if isinstance(r, str) else json.dumps(r)) + "\n" for r in requests
+    ))
+    stdout = io.StringIO()
+    serve(_handler, stdin=stdin, stdout=stdout)
+    return [json.loads(line) for line in stdout.getvalue().splitlines()]
+
+
+class TestServe:
+    """Tests for the worker request loop"""
+
+    def test_responses_match_one_shot_runs(self):
+        """Each request gets its id back with exit code, stdout and stderr"""
+        ready, echo, fail, crash, invalid = _serve(
+            {"id": 1, "args": ["echo", "x"], "request_id": "ui-1"},
+            {"id": 2, "args": ["fail"]},
+            {"id": 3, "args": ["crash"]},
+            "not json",
+        )
+
+        assert ready["ready"] is True
+        assert echo["id"] == 1 and echo["exit_code"] == 0
+        assert json.loads(echo["stdout"]) == {"args": ["x"], "request_id": "ui-1"}
+        assert fail["exit_code"] == 1 and json.loads(fail["stderr"])["type"] == "ValueError"
+        assert crash["exit_code"] == 1 and "RuntimeError" in crash["stderr"]
+        assert invalid["id"] is None and invalid["exit_code"] == 2
+        assert os.environ.get(REQUEST_ID_ENV) is None
+
+    def test_progress_events_precede_the_response(self, tmp_path):
+        """Refresh progress is forwarded on the protocol stream with the request id"""
+        status_path = str(tmp_path / "status.json")
+        ready, *progress, response = _serve({"id": 7, "args": ["refresh", status_path]})
+
+        assert ready["protocol"] == 3
+        assert [event["id"] for event in progress] == [7, 7, 7]
+        assert [event["progress"]["state"] for event in progress] == ["running", "running", "done"]
+        assert progress[1]["progress"]["percent"] == 100.0
+        assert response["id"] == 7 and json.loads(response["stdout"]) == {"updated_files": 2}
+
+
+class TestReadStdinRequest:
+    """Tests for one-shot requests passed as JSON on stdin"""
+
+    def test_long_arguments_and_request_id(self):
+        """Arguments come through unchanged; the request ID becomes --request-id"""
+        description = "line 1\n\"quoted\" $HOME " + "x" * 200_000
+        stdin = io.StringIO(json.dumps({
+            "args": ["update-project", "--project-id=-home-u-app", f"--description={description}"],
+            "request_id": "rq-1",
+        }))
+
+        assert read_stdin_request(stdin) == [
+            "--request-id", "rq-1", "update-project", "--project-id=-home-u-app", f"--description={description}",
+        ]
+
+    def test_rejects_invalid_requests(self):
+        """Malformed JSON, a missing argument list and serve are refused"""
+        for raw in ("nope", '{"arg": []}', '{"args": []}', '{"args": ["serve"]}', "[1]"):
+            with pytest.raises(ValueError):
+                read_stdin_request(io.StringIO(raw))
+
+    def test_streamed_result_chunks_precede_the_response(self):
+        """Only requests asking for a stream get their list in chunk lines"""
+        ready, *chunks, streamed, plain = _serve(
+            {"id": 1, "args": ["session"], "stream": True},
+            {"id": 2, "args": ["session"]},
+        )
+
+        assert [(c["id"], c["chunk"]["seq"], c["chunk"]["items"]) for c in chunks] == [(1, 0, [1, 2]), (1, 1, [3])]
+        assert json.loads(streamed["stdout"]) == {
+            "session_id": "s1", "messages": [], "streamed": {"key": "messages", "chunks": 2, "items": 3},
+        }
+        assert json.loads(plain["stdout"]) == {"session_id": "s1", "messages": [1, 2, 3]}
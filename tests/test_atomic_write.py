@@ -0,0 +1,37 @@
+"""
+Unit tests for atomic_write module
+"""
+import json
+
+import pytest
+
+from command_center.utils import atomic_write
+from command_center.utils.atomic_write import write_json_atomic
+
+
+class TestWriteJsonAtomic:
+    """Tests for write_json_atomic function"""
+
+    def test_replaces_file_without_leftovers(self, tmp_path):
+        """The target is replaced and no temporary file stays behind"""
+        path = tmp_path / "nested" / "settings.json"
+        write_json_atomic(path, {"a": 1})
+        write_json_atomic(path, {"name": "żółw"})
+
+        assert json.loads(path.read_text(encoding="utf-8")) == {"name": "żółw"}
+        assert [p.name for p in path.parent.iterdir()] == ["settings.json"]
+
+    def test_failed_write_keeps_old_content(self, tmp_path, monkeypatch):
+        """A crash before the rename leaves the previous file intact"""
+        path = tmp_path / "budget.json"
+        write_json_atomic(path, {"amount": 100})
+
+        def fail_replace(src, dst):
+            raise OSError("disk full")
+
+        monkeypatch.setattr(atomic_write.os, "replace", fail_replace)
+        with pytest.raises(OSError):
+            write_json_atomic(path, {"amount": 200})
+
+        assert json.loads(path.read_text(encoding="utf-8")) == {"amount": 100}
+        assert [p.name for p in tmp_path.iterdir()] == ["budget.json"]
@@ -0,0 +1,33 @@
+"""
+Unit tests for audit_log module
+"""
+from command_center.audit_log import read_audit_log, record_audit_event
+
+
+class TestAuditLog:
+    """Tests for record_audit_event and read_audit_log"""
+
+    def test_events_are_filtered_by_request_id(self, tmp_path):
+        """Events keep their request ID and are returned newest first"""
+        log_path = str(tmp_path / "audit.jsonl")
+        record_audit_event("set-budget", {"amount": 200, "effective_from": None}, "ui-1", log_path=log_path)
+        record_audit_event("delete-snapshot", {"snapshot": "q"}, "ui-2", error="not found", log_path=log_path)
+
+        events = read_audit_log(log_path=log_path)
+        assert [e["command"] for e in events] == ["delete-snapshot", "set-budget"]
+        assert events[0]["status"] == "error"
+
+        only_first = read_audit_log(request_id="ui-1", log_path=log_path)
+        assert len(only_first) == 1
+        assert only_first[0]["params"] == {"amount": 200}
+
+    def test_events_are_filtered_by_token_id(self, tmp_path):
+        """Events the desktop app records for API tokens can be listed per token"""
+        log_path = tmp_path / "audit.jsonl"
+        record_audit_event("set-budget", {"amount": 200}, "ui-1", log_path=str(log_path))
+        with open(log_path, "a", encoding="utf-8") as f:
+            f.write('{"command": "api-request", "request_id": "rq-1", "token_id": "tok_a", "status": "ok"}\n')
+            f.write('{"command": "api-request", "request_id": "rq-2", "token_id": "tok_b", "status": "ok"}\n')
+
+        events = read_audit_log(log_path=str(log_path), token_id="tok_a")
+        assert [e["request_id"] for e in events] == ["rq-1"]
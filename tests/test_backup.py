@@ -0,0 +1,135 @@
+"""
+Unit tests for backup module
+"""
+import json
+import sqlite3
+import zipfile
+
+import pytest
+
+from command_center import backup
+from command_center.backup import KEY_FILE_NAME, create_backup, restore_backup, verify_backup
+
+
+def _make_db_dir(path):
+    path.mkdir()
+    (path / "command-center-settings.json").write_text('{"week_start": "sunday"}')
+    (path / "command-center-projects.json").write_text('{"p1": {"name": "One"}}')
+    conn = sqlite3.connect(path / backup.DB_FILE_NAME)
+    conn.execute("CREATE TABLE t (v INTEGER)")
+    conn.execute("INSERT INTO t VALUES (1)")
+    conn.commit()
+    conn.close()
+    return path
+
+
+def _rewrite(archive_path, name, transform):
+    with zipfile.ZipFile(archive_path) as archive:
+        members = {info.filename: archive.read(info.filename) for info in archive.infolist()}
+    members[name] = transform(members[name])
+    with zipfile.ZipFile(archive_path, "w") as archive:
+        for member, data in members.items():
+            archive.writestr(member, data)
+
+
+@pytest.fixture
+def db_dir(tmp_path, monkeypatch):
+    monkeypatch.setattr(backup, "_keyring", lambda: None)
+    return _make_db_dir(tmp_path / "db")
+
+
+class TestBackupVerification:
+    """Tests for create_backup / verify_backup"""
+
+    def test_roundtrip_is_valid_and_restores_files(self, db_dir, tmp_path):
+        """A fresh backup verifies and restores settings and the database"""
+        archive = str(tmp_path / "backup.zip")
+        result = create_backup(archive, str(db_dir))
+        assert result["components"] == ["cache", "projects", "settings"]
+        assert verify_backup(archive, str(db_dir))["valid"]
+
+        (db_dir / "command-center-settings.json").write_text("{}")
+        conn = sqlite3.connect(db_dir / backup.DB_FILE_NAME)
+        conn.execute("DELETE FROM t")
+        conn.commit()
+        conn.close()
+
+        restore_backup(archive, db_dir=str(db_dir))
+        assert json.loads((db_dir / "command-center-settings.json").read_text()) == {"week_start": "sunday"}
+        conn = sqlite3.connect(db_dir / backup.DB_FILE_NAME)
+        assert conn.execute("SELECT COUNT(*) FROM t").fetchone()[0] == 1
+        conn.close()
+
+    def test_corruption_and_tampering_are_detected(self, db_dir, tmp_path):
+        """Changed file content fails its checksum; a changed manifest fails the signature"""
+        archive = str(tmp_path / "backup.zip")
+        create_backup(archive, str(db_dir))
+        _rewrite(archive, "projects/command-center-projects.json", lambda data: data.replace(b"One", b"Two"))
+
+        result = verify_backup(archive, str(db_dir))
+        assert not result["valid"]
+        assert result["errors"] == ["projects/command-center-projects.json: checksum mismatch"]
+        with pytest.raises(ValueError):
+            restore_backup(archive, force=True, db_dir=str(db_dir))
+
+        create_backup(archive, str(db_dir))
+
+        def tamper(data):
+            manifest = json.loads(data)
+            manifest["app_version"] = "9.9.9"
+            return json.dumps(manifest).encode()
+
+        _rewrite(archive, "manifest.json", tamper)
+        assert verify_backup(archive, str(db_dir))["signature"] == "invalid"
+
+    def test_other_key_requires_force(self, db_dir, tmp_path):
+        """A backup signed by another key restores only with force"""
+        archive = str(tmp_path / "backup.zip")
+        create_backup(archive, str(db_dir))
+        (db_dir / KEY_FILE_NAME).unlink()
+        backup.get_signing_key(str(db_dir))
+
+        assert verify_backup(archive, str(db_dir))["signature"] == "unknown_key"
+        with pytest.raises(ValueError):
+            restore_backup(archive, db_dir=str(db_dir))
+        (db_dir / "command-center-settings.json").write_text("{}")
+        result = restore_backup(archive, force=True, db_dir=str(db_dir))
+        assert "settings/command-center-settings.json" in result["restored"]
+
+
+class TestSelectiveRestore:
+    """Tests for restore_backup components / dry_run"""
+
+    def test_dry_run_reports_changes_without_writing(self, db_dir, tmp_path):
+        """A dry run lists changed JSON keys and table row counts but writes nothing"""
+        archive = str(tmp_path / "backup.zip")
+        create_backup(archive, str(db_dir))
+        (db_dir / "command-center-settings.json").write_text('{"week_start": "monday", "timezone": "UTC"}')
+        conn = sqlite3.connect(db_dir / backup.DB_FILE_NAME)
+        conn.execute("INSERT INTO t VALUES (2)")
+        conn.commit()
+        conn.close()
+
+        result = restore_backup(archive, dry_run=True, db_dir=str(db_dir))
+        files = {f["component"]: f for f in result["files"]}
+        assert result["restored"] == []
+        assert files["projects"]["status"] == "unchanged"
+        assert files["settings"]["changes"] == [
+            {"path": "timezone", "change": "removed"},
+            {"path": "week_start", "change": "modified"},
+        ]
+        assert files["cache"]["tables"] == [{"table": "t", "current_rows": 2, "backup_rows": 1}]
+        assert "monday" in (db_dir / "command-center-settings.json").read_text()
+
+    def test_only_selected_components_are_restored(self, db_dir, tmp_path):
+        """Restoring settings leaves project metadata alone; unknown components are rejected"""
+        archive = str(tmp_path / "backup.zip")
+        create_backup(archive, str(db_dir))
+        (db_dir / "command-center-settings.json").write_text("{}")
+        (db_dir / "command-center-projects.json").write_text("{}")
+
+        result = restore_backup(archive, components=["settings"], db_dir=str(db_dir))
+        assert result["restored"] == ["settings/command-center-settings.json"]
+        assert json.loads((db_dir / "command-center-projects.json").read_text()) == {}
+        with pytest.raises(ValueError, match="Unknown backup component"):
+            restore_backup(archive, components=["annotations"], db_dir=str(db_dir))
@@ -0,0 +1,46 @@
+"""
+Unit tests for benchmark module
+"""
+import pytest
+
+from command_center.benchmark import (
+    BENCHMARK_HOME_ENV, SESSION_MESSAGES, measure, run_benchmark, write_session_logs
+)
+from command_center.collectors.jsonl_parser import parse_jsonl_line
+
+
+class TestWriteSessionLogs:
+    """Tests for write_session_logs"""
+
+    def test_writes_parseable_sessions(self, tmp_path):
+        """Every synthetic line is a message the parser accepts, with a cost"""
+        files = write_session_logs(tmp_path, SESSION_MESSAGES + 5, seed=1)
+        paths = sorted(tmp_path.glob("*/*.jsonl"))
+
+        assert files == len(paths) == 2
+        entries = [parse_jsonl_line(line, str(path)) for path in paths for line in path.read_text().splitlines()]
+        assert len(entries) == SESSION_MESSAGES + 5
+        assert all(entry is not None and entry.cost_usd > 0 for entry in entries)
+        assert len({entry.entry_hash for entry in entries}) == len(entries)
+
+    def test_same_seed_same_logs(self, tmp_path):
+        """A seed reproduces the logs"""
+        write_session_logs(tmp_path / "a", 50, seed=3)
+        write_session_logs(tmp_path / "b", 50, seed=3)
+        read = lambda root: [p.read_text() for p in sorted(root.glob("*/*.jsonl"))]
+        assert read(tmp_path / "a") == read(tmp_path / "b")
+
+
+class TestRunBenchmark:
+    """Tests for run_benchmark and measure"""
+
+    def test_rejects_unsupported_sizes(self):
+        """Only the documented dataset sizes run"""
+        with pytest.raises(ValueError, match="Unsupported benchmark size: 123"):
+            run_benchmark([10_000, 123])
+
+    def test_measure_refuses_real_home(self, monkeypatch):
+        """Synthetic logs are never written outside a benchmark home"""
+        monkeypatch.delenv(BENCHMARK_HOME_ENV, raising=False)
+        with pytest.raises(ValueError, match="throwaway home"):
+            measure(10)
@@ -0,0 +1,103 @@
+"""
+Unit tests for budget module
+"""
+import sqlite3
+from datetime import date
+
+import pytest
+
+from command_center.budget import (
+    get_budget_for_month,
+    set_monthly_budget,
+    roll_over_budget_periods,
+    query_budget_history,
+)
+from command_center.database.schema import init_database
+
+
+def _insert_entry(conn, entry_hash, day, cost):
+    conn.execute("""
+        INSERT INTO message_entries
+        (entry_hash, timestamp, timestamp_local, year, date, cost_usd, total_tokens, source_file)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+    """, (entry_hash, f"{day}T12:00:00Z", f"{day}T12:00:00", int(day[:4]), day, cost, 100, "test.jsonl"))
+    conn.commit()
+
+
+class TestGetBudgetForMonth:
+    """Tests for get_budget_for_month function"""
+
+    def test_returns_none_without_changes(self):
+        """No budget configured means no budget for any month"""
+        assert get_budget_for_month({"monthly": []}, "2025-03") is None
+
+    def test_uses_latest_effective_change(self):
+        """The most recent change at or before the month applies"""
+        config = {"monthly": [
+            {"effective_from": "2025-01", "amount_usd": 100.0},
+            {"effective_from": "2025-06", "amount_usd": 200.0},
+        ]}
+        assert get_budget_for_month(config, "2024-12") is None
+        assert get_budget_for_month(config, "2025-05") == 100.0
+        assert get_budget_for_month(config, "2025-06") == 200.0
+
+
+class TestSetMonthlyBudget:
+    """Tests for set_monthly_budget function"""
+
+    def test_replaces_change_for_same_month(self, tmp_path):
+        """Setting a budget twice for one month keeps a single entry"""
+        json_path = str(tmp_path / "budget.json")
+        set_monthly_budget(100.0, "2025-01", json_path=json_path)
+        config = set_monthly_budget(150.0, "2025-01", json_path=json_path)
+        assert config["monthly"] == [{"effective_from": "2025-01", "amount_usd": 150.0}]
+
+    def test_rejects_negative_amount(self, tmp_path):
+        """Negative budgets are rejected"""
+        with pytest.raises(ValueError):
+            set_monthly_budget(-1.0, "2025-01", json_path=str(tmp_path / "budget.json"))
+
+
+class TestRollover:
+    """Tests for roll_over_budget_periods and query_budget_history"""
+
+    def test_snapshots_completed_months_once(self, tmp_path):
+        """Completed months are frozen and not recomputed on later rollovers"""
+        json_path = str(tmp_path / "budget.json")
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        set_monthly_budget(10.0, "2025-01", json_path=json_path)
+        _insert_entry(conn, "a", "2025-01-10", 12.5)
+
+        written = roll_over_budget_periods(conn, today=date(2025, 3, 5), json_path=json_path)
+        assert written == 2
+
+        # Late data for January must not change the frozen snapshot
+        _insert_entry(conn, "b", "2025-01-11", 5.0)
+        assert roll_over_budget_periods(conn, today=date(2025, 3, 5), json_path=json_path) == 0
+
+        history = query_budget_history(conn, today=date(2025, 3, 5), json_path=json_path)
+        january = next(p for p in history["periods"] if p["period"] == "2025-01")
+        assert january["actual"] == 12.5
+        assert january["over_under"] == 2.5
+        assert history["current"]["period"] == "2025-03"
+        assert history["summary"]["over_budget"] == 1
+
+    def test_periods_follow_billing_anchor_day(self, tmp_path):
+        """With a billing anchor day, periods run from the anchor to the day before the next one"""
+        json_path = str(tmp_path / "budget.json")
+        (tmp_path / "command-center-settings.json").write_text('{"billing_anchor_day": 17}')
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        set_monthly_budget(10.0, "2025-01", json_path=json_path)
+        _insert_entry(conn, "a", "2025-01-20", 4.0)
+        _insert_entry(conn, "b", "2025-02-16", 3.0)
+        _insert_entry(conn, "c", "2025-02-17", 8.0)
+
+        roll_over_budget_periods(conn, today=date(2025, 2, 20), json_path=json_path)
+        history = query_budget_history(conn, today=date(2025, 2, 20), json_path=json_path)
+        january = next(p for p in history["periods"] if p["period"] == "2025-01")
+        assert (january["from"], january["to"]) == ("2025-01-17", "2025-02-16")
+        assert january["actual"] == 7.0
+        assert history["current"]["from"] == "2025-02-17"
+        assert history["current"]["actual"] == 8.0
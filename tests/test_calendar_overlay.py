@@ -0,0 +1,81 @@
+"""
+Unit tests for calendar_overlay module and overlay-aware streaks
+"""
+import json
+from datetime import date, timedelta
+
+import pytest
+
+from command_center.aggregators.streak_calculator import calculate_streaks
+from command_center.calendar_overlay import (
+    daily_average,
+    excluded_days,
+    load_calendar_overlay,
+    overlay_for_range,
+    set_calendar_overlay,
+)
+
+
+class TestSetCalendarOverlay:
+    """Tests for set_calendar_overlay"""
+
+    def test_stores_sorted_periods(self, tmp_path):
+        path = str(tmp_path / "calendar.json")
+        result = set_calendar_overlay([
+            {"from": "2025-12-24", "to": "2025-12-26", "kind": "holiday", "label": " Christmas "},
+            {"from": "2025-07-01", "to": "2025-07-14"},
+        ], path)
+        assert result["periods"] == [
+            {"from": "2025-07-01", "to": "2025-07-14", "kind": "vacation", "label": None},
+            {"from": "2025-12-24", "to": "2025-12-26", "kind": "holiday", "label": "Christmas"},
+        ]
+        assert load_calendar_overlay(path) == result["periods"]
+        with open(path, encoding="utf-8") as f:
+            assert len(json.load(f)["periods"]) == 2
+
+    def test_rejects_invalid_periods(self, tmp_path):
+        path = str(tmp_path / "calendar.json")
+        for periods in [
+            [{"from": "2025-07-14", "to": "2025-07-01"}],
+            [{"from": "2025-13-01", "to": "2025-13-02"}],
+            [{"from": "2025-07-01", "to": "2025-07-02", "kind": "sick"}],
+            [{"from": "2024-01-01", "to": "2025-07-02"}],
+        ]:
+            with pytest.raises(ValueError):
+                set_calendar_overlay(periods, path)
+        assert load_calendar_overlay(path) == []
+
+
+class TestOverlayEffects:
+    """Tests for averages, streaks and timeline regions"""
+
+    def test_daily_average_skips_days_off(self):
+        """A two-week holiday does not lower the average daily usage"""
+        periods = [{"from": "2025-03-03", "to": "2025-03-16", "kind": "vacation", "label": None}]
+        rows = [
+            {"period": f"2025-03-{day:02d}", "messages": 10, "tokens": 1000, "cost": 1.0}
+            for day in [1, 2] + list(range(17, 32))
+        ] + [{"period": "2025-03-05", "messages": 4, "tokens": 400, "cost": 0.4}]
+        excluded = excluded_days(periods, "2025-03-01", "2025-03-31")
+
+        average = daily_average(rows, "2025-03-01", "2025-03-31", excluded)
+        assert average == {"days": 17, "excluded_days": 14, "messages": 10.0, "tokens": 1000, "cost": 1.0}
+
+    def test_streak_bridges_days_off(self):
+        """Days off neither break nor extend a streak"""
+        today = date.today()
+        days = [(today - timedelta(days=n)).isoformat() for n in range(10)]
+        activity = {day: 5 for i, day in enumerate(days) if not 3 <= i <= 6}
+        off = set(days[3:7])
+
+        assert calculate_streaks(activity) == (3, 3)
+        assert calculate_streaks(activity, off) == (6, 6)
+
+    def test_overlay_clipped_to_range(self):
+        periods = [
+            {"from": "2025-02-20", "to": "2025-03-05", "kind": "vacation", "label": "Ski"},
+            {"from": "2025-05-01", "to": "2025-05-01", "kind": "holiday", "label": None},
+        ]
+        assert overlay_for_range(periods, "2025-03-01", "2025-03-31") == [
+            {"from": "2025-03-01", "to": "2025-03-05", "kind": "vacation", "label": "Ski"},
+        ]
@@ -0,0 +1,96 @@
+"""
+Unit tests for compactions module
+"""
+import json
+import sqlite3
+
+from command_center.compactions import (
+    parse_compaction_events,
+    query_compaction_stats,
+    query_session_compactions,
+    scan_compactions,
+)
+from command_center.database.schema import init_database
+
+
+def _assistant(session_id, timestamp, context):
+    return {"type": "assistant", "sessionId": session_id, "timestamp": timestamp,
+            "message": {"usage": {"input_tokens": 10, "cache_read_input_tokens": context - 10}}}
+
+
+def _boundary(session_id, timestamp, trigger, pre_tokens, uuid):
+    return {"type": "system", "subtype": "compact_boundary", "sessionId": session_id, "timestamp": timestamp,
+            "uuid": uuid, "compactMetadata": {"trigger": trigger, "preTokens": pre_tokens}}
+
+
+def _write(path, lines):
+    path.write_text("\n".join(json.dumps(line) for line in lines) + "\n")
+
+
+class TestParseCompactionEvents:
+    """Tests for parse_compaction_events"""
+
+    def test_boundaries_and_legacy_summaries(self, tmp_path):
+        """Boundary entries carry trigger and pre tokens; old summaries fall back to the last context"""
+        path = tmp_path / "s1.jsonl"
+        _write(path, [
+            _assistant("s1", "2025-03-01T10:00:00Z", 150000),
+            _boundary("s1", "2025-03-01T10:01:00Z", "auto", 155000, "b1"),
+            {"type": "user", "sessionId": "s1", "timestamp": "2025-03-01T10:01:00Z", "isCompactSummary": True},
+            _assistant("s1", "2025-03-01T10:02:00Z", 20000),
+            {"type": "assistant", "isSidechain": True, "sessionId": "s1", "timestamp": "2025-03-01T10:02:30Z",
+             "message": {"usage": {"input_tokens": 5}}},
+            {"type": "user", "sessionId": "s1", "timestamp": "2025-03-01T10:03:00Z", "uuid": "u2",
+             "isCompactSummary": True},
+            _assistant("s1", "2025-03-01T10:04:00Z", 12000),
+        ])
+
+        events = parse_compaction_events([str(path)], "s1")
+
+        assert [(e["trigger"], e["pre_tokens"], e["post_tokens"]) for e in events] == [
+            ("auto", 155000, 20000),
+            ("unknown", 20000, 12000),
+        ]
+
+
+class TestCompactionStats:
+    """Tests for scan_compactions / query_compaction_stats"""
+
+    def test_scan_once_and_aggregate(self, tmp_path):
+        """Unchanged files are skipped; stats count triggers, rates and freed tokens"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        sessions = {}
+        for session_id, lines in (
+            ("s1", [
+                _assistant("s1", "2025-03-01T10:00:00Z", 150000),
+                _boundary("s1", "2025-03-01T10:01:00Z", "auto", 160000, "b1"),
+                _assistant("s1", "2025-03-01T10:02:00Z", 20000),
+                _boundary("s1", "2025-03-01T11:01:00Z", "manual", 80000, "b2"),
+                _assistant("s1", "2025-03-01T11:02:00Z", 10000),
+            ]),
+            ("s2", [_assistant("s2", "2025-03-02T10:00:00Z", 5000)]),
+        ):
+            path = tmp_path / f"{session_id}.jsonl"
+            _write(path, lines)
+            sessions[session_id] = [str(path)]
+            conn.execute("""
+                INSERT INTO message_entries
+                (entry_hash, timestamp, timestamp_local, year, date, session_id, source_file, project_id)
+                VALUES (?, '2025-03-01T10:00:00Z', '2025-03-01T11:00:00+01:00', 2025, '2025-03-01', ?, ?, 'p1')
+            """, (f"h-{session_id}", session_id, str(path)))
+        conn.commit()
+
+        assert scan_compactions(conn, sessions)["scanned"] == 2
+        assert scan_compactions(conn, sessions)["unchanged"] == 2
+
+        stats = query_compaction_stats(conn, "2025-03-01", "2025-03-31")
+        assert stats["compactions"] == 2
+        assert stats["sessions"] == 2 and stats["sessions_with_compactions"] == 1
+        assert stats["by_trigger"] == {"auto": 1, "manual": 1, "unknown": 0}
+        assert stats["per_session"] == 1.0
+        assert stats["tokens_freed"] == 140000 + 70000
+        assert stats["top_sessions"][0]["session_id"] == "s1"
+
+        details = query_session_compactions(conn, "s1")
+        assert details["count"] == 2 and details["auto"] == 1 and details["manual"] == 1
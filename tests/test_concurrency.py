@@ -0,0 +1,58 @@
+"""
+Unit tests for concurrency module
+"""
+import sqlite3
+
+import pytest
+
+from command_center.concurrency import query_concurrency_stats
+from command_center.database.schema import init_database
+
+
+def _insert(conn, session_id, minutes, tokens=100, project_id="p1"):
+    for minute in minutes:
+        timestamp = f"2025-03-01T{10 + minute // 60:02d}:{minute % 60:02d}:00Z"
+        conn.execute("""
+            INSERT INTO message_entries
+            (entry_hash, timestamp, timestamp_local, year, date, session_id, total_tokens, source_file, project_id)
+            VALUES (?, ?, ?, 2025, '2025-03-01', ?, ?, 's.jsonl', ?)
+        """, (f"{session_id}-{minute}", timestamp, timestamp.replace("Z", "+00:00"), session_id, tokens, project_id))
+
+
+class TestConcurrencyStats:
+    """Tests for query_concurrency_stats"""
+
+    def test_overlapping_sessions_and_idle_gaps(self):
+        """Overlap is measured on activity segments; idle gaps end a segment"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        _insert(conn, "a", [0, 10, 20, 30])
+        _insert(conn, "b", [15, 25, 28], project_id="p2")
+        # Two segments: 0-5 and 120-130 (the gap is longer than 15 minutes)
+        _insert(conn, "c", [0, 5, 120, 130])
+        conn.execute("""
+            INSERT INTO limit_events
+            (leaf_uuid, limit_type, occurred_at, occurred_at_local, year, date, hour, reset_at_local, source_file)
+            VALUES ('l1', '5-hour', '2025-03-01T12:30:00Z', '2025-03-01T12:30:00+00:00', 2025, '2025-03-01', 12,
+                    '2025-03-01T15:00:00+00:00', 's.jsonl')
+        """)
+
+        stats = query_concurrency_stats(conn, "2025-03-01", "2025-03-01")
+
+        assert stats["max_concurrency"] == 2
+        assert stats["period_count"] == 2
+        periods = {p["start"]: p for p in stats["periods"]}
+        assert periods["2025-03-01T10:15:00+00:00"]["minutes"] == 13.0
+        assert periods["2025-03-01T10:15:00+00:00"]["sessions"] == ["a", "b"]
+        levels = {entry["concurrency"]: entry for entry in stats["levels"]}
+        assert levels[2]["minutes"] == 18.0
+        assert levels[1]["minutes"] == 22.0
+        assert stats["limit_windows"][0]["tokens"] == 1100
+        assert stats["limit_windows"][0]["max_concurrency"] == 2
+
+    def test_rejects_invalid_gap(self):
+        """The idle gap must be positive"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        with pytest.raises(ValueError):
+            query_concurrency_stats(conn, "2025-03-01", "2025-03-01", idle_gap_minutes=0)
@@ -0,0 +1,50 @@
+"""
+Unit tests for message content hashes
+"""
+import json
+import sqlite3
+
+from command_center.collectors.deduplication import compute_content_hash
+from command_center.collectors.jsonl_parser import parse_jsonl_line
+from command_center.database.queries import insert_message_entries, query_session_details
+from command_center.database.schema import init_database, migrate_to_v15
+
+
+def _line(output_tokens=20):
+    return json.dumps({
+        "sessionId": "s1",
+        "requestId": "r1",
+        "timestamp": "2025-03-01T10:00:00.000Z",
+        "message": {
+            "id": "m1",
+            "model": "claude-sonnet-4",
+            "usage": {"input_tokens": 10, "output_tokens": output_tokens},
+        },
+    })
+
+
+class TestContentHash:
+    """Tests for content hash computation and storage"""
+
+    def test_same_record_on_other_machines(self):
+        """Local fields don't change the hash; usage does"""
+        here = parse_jsonl_line(_line(), "/home/a/.claude/projects/-home-a-app/s1.jsonl")
+        there = parse_jsonl_line(_line(), "/Users/b/.claude-work/projects/-Users-b-app/s1.jsonl")
+        changed = parse_jsonl_line(_line(output_tokens=21), "/home/a/.claude/projects/-home-a-app/s1.jsonl")
+
+        assert len(here.content_hash) == 32
+        assert here.content_hash == there.content_hash == compute_content_hash(here)
+        assert changed.content_hash != here.content_hash
+
+    def test_migration_backfills_stored_rows(self):
+        """Rows stored before v15 get the hash a fresh ingestion gives them"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        entry = parse_jsonl_line(_line(), "/home/a/.claude/projects/p/s1.jsonl")
+        insert_message_entries(conn, [entry])
+        conn.execute("UPDATE message_entries SET content_hash = NULL")
+
+        migrate_to_v15(conn)
+
+        details = query_session_details(conn, "s1")
+        assert details["messages"][0]["content_hash"] == entry.content_hash
@@ -0,0 +1,43 @@
+"""
+Unit tests for cost_allocation module
+"""
+from command_center.cost_allocation import resolve_cost_center, set_cost_center
+
+
+class TestResolveCostCenter:
+    """Tests for resolve_cost_center function"""
+
+    CONFIG = {
+        "default_cost_center": {"code": "UNALLOCATED", "name": "Unallocated"},
+        "cost_centers": [
+            {"code": "CC-100", "name": "Engineering", "projects": ["-home-xai-app"], "tags": ["client"]},
+            {"code": "CC-200", "name": "Research", "projects": [], "tags": ["research", "client"]},
+        ],
+    }
+
+    def test_explicit_project_mapping_wins(self):
+        """A mapped project ignores tag rules"""
+        center = resolve_cost_center("-home-xai-app", {"tags": ["research"]}, self.CONFIG)
+        assert (center["code"], center["rule"]) == ("CC-100", "project")
+
+    def test_first_matching_tag_in_configured_order(self):
+        """Tag rules are evaluated in cost center order"""
+        center = resolve_cost_center("-home-xai-lab", {"tags": ["research", "client"]}, self.CONFIG)
+        assert (center["code"], center["rule"]) == ("CC-100", "tag:client")
+
+    def test_falls_back_to_default(self):
+        """Projects without a matching rule go to the default cost center"""
+        center = resolve_cost_center("-home-xai-misc", {}, self.CONFIG)
+        assert (center["code"], center["rule"]) == ("UNALLOCATED", "default")
+
+
+class TestSetCostCenter:
+    """Tests for set_cost_center function"""
+
+    def test_project_moves_between_cost_centers(self, tmp_path):
+        """Mapping a project explicitly removes it from other cost centers"""
+        json_path = str(tmp_path / "cost-centers.json")
+        set_cost_center("CC-100", projects=["-home-xai-app"], json_path=json_path)
+        config = set_cost_center("CC-200", projects=["-home-xai-app"], json_path=json_path)
+        projects = {c["code"]: c["projects"] for c in config["cost_centers"]}
+        assert projects == {"CC-100": [], "CC-200": ["-home-xai-app"]}
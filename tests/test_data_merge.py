@@ -0,0 +1,95 @@
+"""
+Unit tests for data_merge module
+"""
+import sqlite3
+
+import pytest
+
+from command_center.data_merge import merge_entries, merge_source
+from command_center.database.models import MessageEntry
+from command_center.database.queries import insert_message_entries
+from command_center.database.schema import init_database
+
+
+def _entry(entry_hash, message_id, session_id="s1", tokens=100, cost=0.5, model="claude-sonnet-4"):
+    return MessageEntry(
+        entry_hash=entry_hash, timestamp="2025-03-01T10:00:00Z",
+        timestamp_local="2025-03-01T11:00:00+01:00", year=2025, date="2025-03-01",
+        session_id=session_id, message_id=message_id, model=model, cost_usd=cost,
+        output_tokens=tokens // 2, total_tokens=tokens, source_file="/a/projects/p/s1.jsonl",
+        project_id="p",
+    )
+
+
+def _database(entries):
+    conn = sqlite3.connect(":memory:")
+    init_database(conn)
+    insert_message_entries(conn, entries)
+    return conn
+
+
+class TestMergeEntries:
+    """Tests for merge_entries"""
+
+    def test_overlap_prefers_complete_records(self):
+        """Matches by hash and message ID, keeps the fuller record and reports the overlap"""
+        conn = _database([
+            _entry("m1:r1", "m1"),
+            _entry("m2:r2", "m2", tokens=40, cost=None),
+            _entry("m3:r3", "m3", tokens=300),
+        ])
+        incoming = [
+            _entry("m1:r1", "m1"),                     # identical
+            _entry("m2:r2", "m2", tokens=90),          # more complete: replaces
+            _entry("m3:rX", "m3", tokens=100),         # same message, other request ID: kept
+            _entry("m4:r4", "m4", session_id="s2"),    # new
+            _entry("m4:r4", "m4", session_id="s2"),    # duplicate within the source
+        ]
+
+        report = merge_entries(conn, incoming)
+        assert (report["added"], report["identical"], report["replaced"], report["kept"]) == (1, 1, 1, 1)
+        assert report["double_counts_prevented"] == 1
+        assert report["duplicates_in_source"] == 1
+        assert report["tokens_added"] == 150
+        assert report["sessions"]["new"] == 1
+        assert report["sessions"]["details"] == [{
+            "session_id": "s1", "project_id": "p", "local_messages": 3, "incoming_messages": 3,
+            "added": 0, "replaced": 1, "kept": 1,
+        }]
+
+        rows = dict(conn.execute("SELECT entry_hash, total_tokens FROM message_entries").fetchall())
+        assert rows == {"m1:r1": 100, "m2:r2": 90, "m3:r3": 300, "m4:r4": 100}
+        assert conn.execute(
+            "SELECT message_count, total_tokens FROM hourly_aggregates WHERE datetime_hour = '2025-03-01 11:00:00'"
+        ).fetchone() == (4, 590)
+
+        again = merge_entries(conn, incoming)
+        assert (again["added"], again["replaced"]) == (0, 0)
+
+    def test_dry_run_writes_nothing(self):
+        """A dry run reports the merge but leaves the database untouched"""
+        conn = _database([_entry("m1:r1", "m1")])
+        report = merge_entries(conn, [_entry("m2:r2", "m2")], dry_run=True)
+        assert report["added"] == 1
+        assert conn.execute("SELECT COUNT(*) FROM message_entries").fetchone()[0] == 1
+
+
+class TestMergeSource:
+    """Tests for merge_source"""
+
+    def test_database_file(self, tmp_path):
+        """Another machine's database file is merged"""
+        source_path = tmp_path / "other.db"
+        source = sqlite3.connect(source_path)
+        init_database(source)
+        insert_message_entries(source, [_entry("m1:r1", "m1"), _entry("m2:r2", "m2")])
+        source.close()
+
+        conn = _database([_entry("m1:r1", "m1")])
+        report = merge_source(conn, str(source_path))
+        assert report["kind"] == "database"
+        assert (report["added"], report["identical"]) == (1, 1)
+        assert report["projects"] == ["p"]
+
+        with pytest.raises(ValueError):
+            merge_source(conn, str(tmp_path / "missing.db"))
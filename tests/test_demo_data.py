@@ -0,0 +1,48 @@
+"""
+Unit tests for demo_data module
+"""
+import json
+import sqlite3
+from datetime import date
+
+import pytest
+
+from command_center.database.schema import init_database
+from command_center.demo_data import DEMO_PROJECTS, generate_demo_data
+
+
+def _generate(tmp_path, seed, name="db"):
+    conn = sqlite3.connect(":memory:")
+    init_database(conn)
+    projects_path = tmp_path / f"{name}-projects.json"
+    result = generate_demo_data(conn, seed=seed, months=2, end_date=date(2025, 6, 30),
+                                projects_json_path=str(projects_path))
+    rows = conn.execute(
+        "SELECT entry_hash, timestamp, model, total_tokens FROM message_entries ORDER BY entry_hash"
+    ).fetchall()
+    hourly = conn.execute("SELECT SUM(message_count) FROM hourly_aggregates").fetchone()[0]
+    conn.close()
+    return result, rows, hourly, json.loads(projects_path.read_text())
+
+
+class TestGenerateDemoData:
+    """Tests for generate_demo_data"""
+
+    def test_same_seed_same_data(self, tmp_path):
+        """A seed reproduces the data; aggregates and demo projects are written"""
+        result, rows, hourly, projects = _generate(tmp_path, 7, "a")
+        again = _generate(tmp_path, 7, "b")
+        other = _generate(tmp_path, 8, "c")
+
+        assert result["date_to"] == "2025-06-30"
+        assert result["messages"] == len(rows) == hourly > 0
+        assert rows == again[1]
+        assert rows != other[1]
+        assert len(projects) == len(DEMO_PROJECTS)
+
+    def test_rejects_invalid_months(self):
+        """History length is bounded"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        with pytest.raises(ValueError):
+            generate_demo_data(conn, months=0)
@@ -0,0 +1,60 @@
+"""
+Unit tests for derived_metrics module
+"""
+import sqlite3
+
+import pytest
+
+from command_center.database.schema import init_database
+from command_center.derived_metrics import (
+    compute_derived_metrics,
+    evaluate,
+    parse_expression,
+    query_metric_inputs,
+    set_derived_metric,
+)
+
+
+class TestParseExpression:
+    """Tests for parse_expression function"""
+
+    def test_rejects_anything_but_arithmetic(self):
+        """Attribute access, calls of unknown functions and unknown names are refused"""
+        for expression in ["__import__('os')", "cost.real", "cost ** 99999", "foo / messages", "'a'"]:
+            with pytest.raises(ValueError):
+                parse_expression(expression)
+
+    def test_division_by_zero_is_null(self):
+        """A period without messages has no value instead of an error"""
+        tree = parse_expression("round(cost / messages, 2) + 1")
+        assert evaluate(tree, {"cost": 3.0, "messages": 4}) == 1.75
+        assert evaluate(tree, {"cost": 3.0, "messages": 0}) is None
+
+
+class TestComputeDerivedMetrics:
+    """Tests for query_metric_inputs / compute_derived_metrics"""
+
+    def test_values_per_period(self, tmp_path):
+        """Formulas are evaluated on the totals of each period"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        for i, (day, cost, cache_read) in enumerate([("2025-06-15", 2.0, 50), ("2025-06-15", 1.0, 0),
+                                                     ("2025-07-01", 4.0, 100)]):
+            conn.execute("""
+                INSERT INTO message_entries
+                (entry_hash, timestamp, timestamp_local, year, date, session_id, model, cost_usd,
+                 input_tokens, cache_read_tokens, total_tokens, source_file)
+                VALUES (?, ?, ?, ?, ?, 's1', 'claude-sonnet-4-20250514', ?, 100, ?, 100, 'test.jsonl')
+            """, (f"h{i}", f"{day}T12:00:00Z", f"{day}T12:00:00", 2025, day, cost, cache_read))
+        json_path = str(tmp_path / "derived.json")
+        metrics = [
+            set_derived_metric("cost_per_message", "cost / messages", json_path=json_path),
+            set_derived_metric("cache_ratio", "cache_read / input", json_path=json_path),
+        ]
+
+        inputs = query_metric_inputs(conn, "2025-06-01", "2025-07-31", "month")
+        derived = compute_derived_metrics(metrics, inputs)
+        assert derived == {
+            "2025-06": {"cost_per_message": 1.5, "cache_ratio": 0.25},
+            "2025-07": {"cost_per_message": 4.0, "cache_ratio": 1.0},
+        }
@@ -0,0 +1,61 @@
+"""
+Unit tests for effort_parser module and effort breakdown query
+"""
+import sqlite3
+
+from command_center.collectors.effort_parser import extract_request_settings
+from command_center.database.queries import insert_request_settings, query_effort_breakdown
+from command_center.database.schema import init_database
+
+MODEL = "claude-opus-4-5-20251101"
+
+
+def _prompt(timestamp, level=None, tool_result=False):
+    content = [{"type": "tool_result", "tool_use_id": "t1", "content": "ok"}] if tool_result else "do it"
+    line = {"type": "user", "timestamp": timestamp, "message": {"content": content}}
+    if level:
+        line["thinkingMetadata"] = {"level": level, "maxThinkingTokens": 31999}
+    return line
+
+
+def _response(message_id, timestamp, block_type="text"):
+    return {"type": "assistant", "timestamp": timestamp, "requestId": f"req-{message_id}",
+            "message": {"id": message_id, "content": [{"type": block_type}]}}
+
+
+class TestExtractRequestSettings:
+    """Tests for extract_request_settings and query_effort_breakdown"""
+
+    def test_settings_follow_prompts_and_latency(self):
+        """Responses inherit the prompt's level through tool results; latency is from the last user line"""
+        settings = {s.entry_hash: s for s in extract_request_settings([
+            _prompt("2025-03-01T10:00:00.000Z", "high"),
+            _response("m1", "2025-03-01T10:00:04.000Z", "thinking"),
+            _response("m1", "2025-03-01T10:00:05.000Z"),
+            _prompt("2025-03-01T10:00:06.000Z", tool_result=True),
+            _response("m2", "2025-03-01T10:00:06.500Z"),
+            _prompt("2025-03-01T10:01:00.000Z"),
+            _response("m3", "2025-03-01T10:01:02.000Z"),
+        ])}
+
+        assert settings["m1:req-m1"].effort == "high" and settings["m1:req-m1"].used_thinking
+        assert settings["m1:req-m1"].latency_ms == 4000 and settings["m1:req-m1"].thinking_budget == 31999
+        assert settings["m2:req-m2"].effort == "high" and settings["m2:req-m2"].latency_ms == 500
+        assert settings["m3:req-m3"].effort is None
+
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        for entry_hash, cost in (("m1:req-m1", 0.3), ("m2:req-m2", 0.1), ("m3:req-m3", 0.05), ("m4:req-m4", 1.0)):
+            conn.execute("""
+                INSERT INTO message_entries
+                (entry_hash, timestamp, timestamp_local, year, date, model, cost_usd, source_file)
+                VALUES (?, '2025-03-01T10:00:00Z', '2025-03-01T11:00:00+01:00', 2025, '2025-03-01', ?, ?, 's.jsonl')
+            """, (entry_hash, MODEL if entry_hash != "m4:req-m4" else "other-model", cost))
+        insert_request_settings(conn, list(settings.values()))
+
+        breakdown = query_effort_breakdown(conn, MODEL, "2025-03-01", "2025-03-31")
+
+        assert [s["effort"] for s in breakdown["settings"]] == ["high", "unknown"]
+        high = breakdown["settings"][0]
+        assert high["requests"] == 2 and high["cost"] == 0.4 and high["avg_cost"] == 0.2
+        assert high["thinking_share"] == 50.0 and high["avg_latency_ms"] == 2250
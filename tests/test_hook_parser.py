@@ -0,0 +1,73 @@
+"""
+Unit tests for hook_parser module and hook stats query
+"""
+import sqlite3
+
+from command_center.collectors.hook_parser import extract_hook_executions
+from command_center.database.queries import insert_hook_executions, query_hook_stats
+from command_center.database.schema import init_database
+
+SOURCE = "/home/u/.claude/projects/-home-u-app/s1.jsonl"
+
+
+def _attachment(uuid, kind, hook_name, command, duration_ms):
+    return {"type": "attachment", "uuid": uuid, "sessionId": "s1", "timestamp": "2025-03-01T10:00:00Z",
+            "attachment": {"type": kind, "hookName": hook_name, "hookEvent": hook_name.split(":")[0],
+                           "command": command, "durationMs": duration_ms}}
+
+
+def _legacy(uuid, content):
+    return {"type": "system", "uuid": uuid, "sessionId": "s1", "timestamp": "2025-03-01T10:00:00Z",
+            "content": content}
+
+
+class TestExtractHookExecutions:
+    """Tests for extract_hook_executions and query_hook_stats"""
+
+    def test_attachments_and_stop_summaries(self):
+        """Attachments and stop hook summaries become executions; per-hook stats are aggregated"""
+        lines = [
+            _attachment("a1", "hook_success", "PreToolUse:Bash", "./guard.sh", 40),
+            _attachment("a2", "hook_blocking_error", "PreToolUse:Bash", "./guard.sh", 60),
+            _attachment("a3", "hook_non_blocking_error", "PostToolUse:Edit", "./fmt.sh", 900),
+            _attachment("a4", "hook_additional_context", "SessionStart", "", None),
+            {"type": "system", "subtype": "stop_hook_summary", "uuid": "s1", "sessionId": "s1",
+             "timestamp": "2025-03-01T10:05:00Z", "hookInfos": [{"command": "./notify.sh"}, {"command": "./log.sh"}],
+             "hookErrors": ["./log.sh: exit 1"], "preventedContinuation": False},
+            # Ignored: the file has hook attachments
+            _legacy("l1", "PostToolUse:Edit [./fmt.sh] completed successfully"),
+        ]
+
+        executions = extract_hook_executions(lines, SOURCE)
+        assert [(e.hook_event, e.command, e.outcome) for e in executions] == [
+            ("PreToolUse", "./guard.sh", "success"),
+            ("PreToolUse", "./guard.sh", "blocked"),
+            ("PostToolUse", "./fmt.sh", "error"),
+            ("Stop", "./notify.sh", "success"),
+            ("Stop", "./log.sh", "error"),
+        ]
+
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        insert_hook_executions(conn, executions)
+        insert_hook_executions(conn, executions)
+        stats = query_hook_stats(conn, "2025-03-01", "2025-03-31")
+
+        assert stats["totals"]["executions"] == 5 and stats["totals"]["failures"] == 2
+        guard = next(h for h in stats["hooks"] if h["command"] == "./guard.sh")
+        assert guard["blocked"] == 1 and guard["failure_rate"] == 0.0
+        assert guard["avg_duration_ms"] == 50 and guard["matchers"] == ["PreToolUse:Bash"]
+        assert stats["hooks"][0]["command"] == "./fmt.sh"
+
+    def test_legacy_system_messages(self):
+        """Older logs without attachments are parsed from the hook status messages"""
+        executions = extract_hook_executions([
+            _legacy("l1", "PostToolUse:Edit [./fmt.sh] completed successfully"),
+            _legacy("l2", "PreToolUse:Bash [./guard.sh] failed with non-blocking status code 1: boom"),
+            _legacy("l3", "Compacted conversation"),
+        ], SOURCE)
+
+        assert [(e.hook_name, e.outcome, e.duration_ms) for e in executions] == [
+            ("PostToolUse:Edit", "success", None),
+            ("PreToolUse:Bash", "error", None),
+        ]
@@ -0,0 +1,83 @@
+"""
+Unit tests for hooks module
+"""
+import sqlite3
+import sys
+from datetime import date
+
+import pytest
+
+from command_center.database.schema import init_database
+from command_center.hooks import render_command, run_hook, run_lifecycle_hooks, set_hook
+
+
+class TestSetHook:
+    """Tests for set_hook function"""
+
+    def test_export_hooks_need_month_event_and_folder(self, tmp_path):
+        """Export hooks only run on month-completed and write to an absolute folder"""
+        hooks_path = str(tmp_path / "hooks.json")
+        hook = set_hook("month-completed", "", action="export", folder=str(tmp_path), formats=["pdf", "csv"],
+                        json_path=hooks_path)
+        assert hook["formats"] == ["csv", "pdf"] and "command" not in hook
+        for event, folder in [("day-rolled-over", str(tmp_path)), ("month-completed", "relative/books")]:
+            with pytest.raises(ValueError):
+                set_hook(event, "", action="export", folder=folder, json_path=hooks_path)
+
+
+class TestRenderCommand:
+    """Tests for render_command function"""
+
+    def test_values_are_shell_quoted(self):
+        """Event fields cannot inject shell syntax"""
+        command = render_command("echo {date} {note}", {"event": "x", "date": "2025-06-15", "note": "a; rm -rf ~"})
+        assert command == "echo 2025-06-15 'a; rm -rf ~'"
+
+
+class TestRunHook:
+    """Tests for run_hook function"""
+
+    def test_timeout_kills_command(self):
+        """Commands running past their timeout are stopped"""
+        hook = {"id": "slow", "command": f"{sys.executable} -c 'import time; time.sleep(5)'", "timeout": 1}
+        assert run_hook(hook, {"event": "refresh-completed"})["status"] == "timeout"
+
+
+class TestRunLifecycleHooks:
+    """Tests for run_lifecycle_hooks function"""
+
+    def test_day_rollover_fires_once_per_day(self, tmp_path):
+        """Missed days fire once each; the first run only records the day"""
+        hooks_path = str(tmp_path / "hooks.json")
+        state_path = str(tmp_path / "state.json")
+        ledger = tmp_path / "ledger.csv"
+        set_hook("day-rolled-over", f"echo {{date}},{{cost}} >> {ledger}", json_path=hooks_path)
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+
+        def run(day):
+            return run_lifecycle_hooks(conn, {"mode": "full"}, today=day, json_path=hooks_path, state_path=state_path)
+
+        assert run(date(2025, 6, 13)) == []
+        assert len(run(date(2025, 6, 15))) == 2
+        assert run(date(2025, 6, 15)) == []
+        assert ledger.read_text().split() == ["2025-06-13,0", "2025-06-14,0"]
+
+    def test_month_completed_exports_once_per_month(self, tmp_path):
+        """Each finished month writes its files once; the first run only records the month"""
+        hooks_path = str(tmp_path / "hooks.json")
+        state_path = str(tmp_path / "state.json")
+        folder = tmp_path / "books"
+        set_hook("month-completed", "", action="export", folder=str(folder), formats=["csv"], json_path=hooks_path)
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+
+        def run(day):
+            return run_lifecycle_hooks(conn, {"mode": "full"}, today=day, json_path=hooks_path, state_path=state_path)
+
+        assert run(date(2025, 5, 20)) == []
+        assert [r["status"] for r in run(date(2025, 7, 2))] == ["ok", "ok"]
+        assert run(date(2025, 7, 3)) == []
+        assert sorted(path.name for path in folder.iterdir()) == [
+            "cc-cost-allocation-2025-05.csv", "cc-cost-allocation-2025-06.csv",
+        ]
@@ -0,0 +1,56 @@
+"""
+Unit tests for incremental_update module
+"""
+import json
+import os
+import sqlite3
+from datetime import datetime, timezone
+
+from command_center.cache import incremental_update
+from command_center.cache.incremental_update import perform_partial_update
+from command_center.database.queries import get_file_tracks
+from command_center.database.schema import init_database
+
+
+def _line(message_id, timestamp):
+    return json.dumps({
+        "type": "assistant", "timestamp": timestamp, "sessionId": "s1",
+        "requestId": f"req-{message_id}",
+        "message": {"id": message_id, "model": "claude-sonnet-4-20250514",
+                    "usage": {"input_tokens": 100, "output_tokens": 10}},
+    }) + "\n"
+
+
+class TestPartialUpdate:
+    """Tests for perform_partial_update function"""
+
+    def test_only_lines_since_watermark_are_ingested(self, tmp_path, monkeypatch):
+        """Tracked files skip older lines; the delta covers new messages only"""
+        log = tmp_path / "-tmp-proj" / "s1.jsonl"
+        log.parent.mkdir()
+        log.write_text(_line("old", "2025-06-10T12:00:00.000Z") + _line("new", "2025-06-15T09:00:00.000Z"))
+
+        monkeypatch.setattr(incremental_update, "scan_jsonl_files", lambda: [str(log)])
+        monkeypatch.setattr(incremental_update, "sync_price_versions", lambda conn: None)
+        monkeypatch.setattr(incremental_update, "load_projects_json", lambda: {})
+        monkeypatch.setattr(incremental_update, "save_projects_json", lambda projects: None)
+
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        # Pretend an earlier scan saw the file with different size
+        conn.execute("""
+            INSERT INTO file_tracks (file_path, mtime_ns, size_bytes, last_scanned, entry_count)
+            VALUES (?, 0, 0, '2025-06-15 08:00:00', 1)
+        """, (str(log),))
+        conn.commit()
+
+        since = datetime(2025, 6, 15, 8, 0, tzinfo=timezone.utc)
+        os.utime(log, (since.timestamp() + 60, since.timestamp() + 60))
+        result = perform_partial_update(conn, since)
+
+        assert result["updated_files"] == 1
+        assert result["delta"]["messages"] == 1
+        message_ids = [row[0] for row in conn.execute("SELECT message_id FROM message_entries")]
+        assert message_ids == ["new"]
+        assert get_file_tracks(conn)[str(log)][1] == log.stat().st_size
+
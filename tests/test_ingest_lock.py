@@ -0,0 +1,40 @@
+"""
+Unit tests for ingest_lock module
+"""
+import json
+import time
+
+import pytest
+
+from command_center.cache.ingest_lock import (
+    STALE_LOCK_AFTER_SECONDS,
+    IngestLockedError,
+    ingest_lock,
+    read_lock,
+)
+
+
+class TestIngestLock:
+    """Tests for ingest_lock / read_lock"""
+
+    def test_live_holder_blocks_and_release_frees(self, tmp_path):
+        """A second writer gets a clear error while the lock is held; release removes the file"""
+        lock_path = str(tmp_path / "ingest.lock")
+        with ingest_lock(lock_path):
+            with pytest.raises(IngestLockedError, match="Another refresh is running"):
+                with ingest_lock(lock_path):
+                    pass
+        assert read_lock(lock_path) is None
+
+    def test_stale_lock_is_stolen(self, tmp_path):
+        """A lock from another host without a recent heartbeat is taken over"""
+        lock_path = tmp_path / "ingest.lock"
+        old = time.time() - STALE_LOCK_AFTER_SECONDS - 1
+        lock_path.write_text(json.dumps({
+            "token": "other", "hostname": "other-machine", "pid": 1,
+            "acquired_at": old, "heartbeat_at": old,
+        }))
+        assert read_lock(str(lock_path))["stale"] is True
+
+        with ingest_lock(str(lock_path)) as lock:
+            assert read_lock(str(lock_path))["token"] == lock.owner["token"]
@@ -0,0 +1,67 @@
+"""
+Unit tests for mcp_parser module and MCP usage query
+"""
+import sqlite3
+
+from command_center.collectors.mcp_parser import extract_mcp_calls, parse_mcp_tool_name
+from command_center.database.queries import insert_mcp_calls, query_mcp_usage
+from command_center.database.schema import init_database
+
+SOURCE = "/home/u/.claude/projects/-home-u-app/s1.jsonl"
+
+
+def _tool_use(message_id, tool_use_id, name, timestamp="2025-03-01T10:00:00Z", output_tokens=0):
+    return {"type": "assistant", "sessionId": "s1", "timestamp": timestamp, "message": {
+        "id": message_id, "usage": {"input_tokens": 100, "output_tokens": output_tokens},
+        "content": [{"type": "tool_use", "id": tool_use_id, "name": name, "input": {}}]}}
+
+
+def _tool_result(tool_use_id, content, is_error=False):
+    return {"type": "user", "sessionId": "s1", "timestamp": "2025-03-01T10:00:05Z", "message": {
+        "content": [{"type": "tool_result", "tool_use_id": tool_use_id, "is_error": is_error, "content": content}]}}
+
+
+class TestParseMcpToolName:
+    """Tests for parse_mcp_tool_name"""
+
+    def test_namespaces(self):
+        """Only mcp__<server>__<tool> names are MCP tools"""
+        assert parse_mcp_tool_name("mcp__github__create_issue") == ("github", "create_issue")
+        assert parse_mcp_tool_name("mcp__claude_ai_Linear__list__issues") == ("claude_ai_Linear", "list__issues")
+        assert parse_mcp_tool_name("Bash") is None
+        assert parse_mcp_tool_name("mcp__github") is None
+
+
+class TestExtractMcpCalls:
+    """Tests for extract_mcp_calls and query_mcp_usage"""
+
+    def test_calls_results_and_token_split(self):
+        """Response tokens are split between its tool calls; results set errors and sizes"""
+        lines = [
+            # One response logged as two lines (same message id and usage), two tool calls
+            _tool_use("m1", "t1", "mcp__github__get_issue", output_tokens=100),
+            _tool_use("m1", "t2", "Bash", output_tokens=100),
+            _tool_result("t1", "x" * 400),
+            _tool_result("t2", "ok"),
+            _tool_use("m2", "t3", "mcp__github__create_issue"),
+            _tool_result("t3", [{"type": "text", "text": "denied"}], is_error=True),
+            _tool_use("m3", "t4", "mcp__sentry__search"),
+        ]
+
+        calls = {c.tool_use_id: c for c in extract_mcp_calls(lines, SOURCE)}
+
+        assert set(calls) == {"t1", "t3", "t4"}
+        assert calls["t1"].message_tokens == 100 and calls["t1"].result_tokens == 100
+        assert calls["t1"].is_error is False and calls["t3"].is_error is True
+        assert calls["t4"].is_error is None
+        assert calls["t1"].project_id == "-home-u-app"
+
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        insert_mcp_calls(conn, list(calls.values()))
+        usage = query_mcp_usage(conn, "2025-03-01", "2025-03-31")
+
+        github = next(s for s in usage["servers"] if s["server"] == "github")
+        assert github["calls"] == 2 and github["failures"] == 1 and github["failure_rate"] == 50.0
+        assert [t["tool"] for t in github["tools"]] == ["create_issue", "get_issue"]
+        assert usage["totals"]["calls"] == 3 and usage["totals"]["sessions"] == 1
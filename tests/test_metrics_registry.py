@@ -0,0 +1,67 @@
+"""
+Unit tests for metrics_registry module
+"""
+import sqlite3
+
+import pytest
+
+from command_center import metrics_registry
+from command_center.database.schema import init_database
+from command_center.metrics_registry import (
+    Metric,
+    list_available_metrics,
+    query_metrics,
+    register_metric,
+)
+
+
+def _db():
+    conn = sqlite3.connect(":memory:")
+    init_database(conn)
+    for i, (day, latency) in enumerate([("2025-06-15", 800), ("2025-06-15", 1200), ("2025-06-16", None)]):
+        conn.execute("""
+            INSERT INTO message_entries
+            (entry_hash, timestamp, timestamp_local, year, date, session_id, model, cost_usd,
+             input_tokens, total_tokens, source_file, project_id)
+            VALUES (?, ?, ?, 2025, ?, 's1', 'claude-sonnet-4', 0.5, 10, 10, 'test.jsonl', 'p')
+        """, (f"h{i}", f"{day}T12:00:00Z", f"{day}T12:00:00", day))
+        if latency is not None:
+            conn.execute("INSERT INTO request_settings (entry_hash, latency_ms) VALUES (?, ?)", (f"h{i}", latency))
+    conn.execute("""
+        INSERT INTO mcp_calls
+        (tool_use_id, server, tool, timestamp, timestamp_local, year, date, project_id, is_error, source_file)
+        VALUES ('t1', 'github', 'search', '2025-06-17T09:00:00Z', '2025-06-17T09:00:00', 2025,
+                '2025-06-17', 'p', 1, 'test.jsonl')
+    """)
+    return conn
+
+
+class TestQueryMetrics:
+    """Tests for query_metrics"""
+
+    def test_metrics_of_all_sources_per_period(self):
+        """Each source is aggregated once; periods missing from a source get 0 or None"""
+        values = query_metrics(_db(), "2025-06-01", "2025-06-30", "day",
+                               names=["messages", "cost", "tool_errors", "latency_ms"])
+        assert values == {
+            "2025-06-15": {"messages": 2, "cost": 1.0, "tool_errors": 0, "latency_ms": 1000.0},
+            "2025-06-16": {"messages": 1, "cost": 0.5, "tool_errors": 0, "latency_ms": None},
+            "2025-06-17": {"messages": 0, "cost": 0, "tool_errors": 1, "latency_ms": None},
+        }
+        assert query_metrics(_db(), "2025-06-01", "2025-06-30", "month", project_id="other") == {}
+
+    def test_registered_metric_is_aggregated(self, monkeypatch):
+        """A new metric only needs registering"""
+        monkeypatch.setattr(metrics_registry, "_registry", dict(metrics_registry._registry))
+        register_metric(Metric("cache_share", "Cache share", "count", "tokens", "messages",
+                               "SUM(m.cache_read_tokens) * 1.0 / SUM(m.input_tokens)"))
+        assert query_metrics(_db(), "2025-06-01", "2025-06-30", "month",
+                             names=["cache_share"]) == {"2025-06": {"cache_share": 0}}
+        assert "cache_share" in [m["name"] for m in list_available_metrics()]
+
+        with pytest.raises(ValueError):
+            register_metric(Metric("cost", "Cost", "usd", "cost", "messages", "SUM(m.cost_usd)"))
+        with pytest.raises(ValueError):
+            register_metric(Metric("bad_source", "Bad", "count", "tools", "nowhere", "COUNT(*)"))
+        with pytest.raises(ValueError):
+            query_metrics(_db(), "2025-06-01", "2025-06-30", "day", names=["unknown"])
@@ -0,0 +1,37 @@
+"""
+Unit tests for mirror module
+"""
+from command_center.cache import mirror
+from command_center.cache.mirror import read_mirror, write_mirror
+from command_center.database.models import MessageEntry
+
+
+def _entry():
+    return MessageEntry(
+        entry_hash="m1:r1", timestamp="2025-06-10T12:00:00Z", timestamp_local="2025-06-10T14:00:00",
+        year=2025, date="2025-06-10", model="claude-sonnet-4-20250514", cost_usd=0.5,
+        input_tokens=100, total_tokens=100, source_file="/p/s1.jsonl", cost_source="computed",
+    )
+
+
+class TestMirror:
+    """Tests for write_mirror and read_mirror"""
+
+    def test_roundtrip_and_staleness(self, tmp_path, monkeypatch):
+        """Records round-trip; a changed mtime or size makes the mirror stale"""
+        monkeypatch.setattr(mirror, "_CODEC", ("identity", lambda data: data, lambda data: data))
+        mirror_dir = str(tmp_path)
+
+        write_mirror("/p/s1.jsonl", 10, 200, [_entry()], [], mirror_dir=mirror_dir)
+
+        entries, *side_records = read_mirror("/p/s1.jsonl", 10, 200, mirror_dir=mirror_dir)
+        assert entries == [_entry()]
+        assert side_records == [[], [], [], []]
+        assert read_mirror("/p/s1.jsonl", 11, 200, mirror_dir=mirror_dir) is None
+        assert read_mirror("/p/s1.jsonl", 10, 201, mirror_dir=mirror_dir) is None
+
+    def test_no_codec_disables_mirror(self, tmp_path, monkeypatch):
+        """Without a zstd codec nothing is written or read"""
+        monkeypatch.setattr(mirror, "_CODEC", None)
+        assert write_mirror("/p/s1.jsonl", 10, 200, [_entry()], [], mirror_dir=str(tmp_path)) is None
+        assert read_mirror("/p/s1.jsonl", 10, 200, mirror_dir=str(tmp_path)) is None
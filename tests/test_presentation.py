@@ -0,0 +1,40 @@
+"""
+Unit tests for presentation module
+"""
+from command_center.presentation import blur_projects, project_alias
+
+
+class TestBlurProjects:
+    """Tests for blur_projects"""
+
+    def test_names_descriptions_and_paths_are_hidden(self):
+        """Objects with a project_id get an alias name; paths are cleared anywhere"""
+        response = {
+            "projects": [{
+                "project_id": "-home-me-acme-portal",
+                "name": "ACME Portal",
+                "description": "Client work",
+                "absolute_path": "/home/me/acme-portal",
+            }],
+            "sessions": [{"session_id": "s1", "project_id": "-home-me-acme-portal", "cwd": "/home/me/acme-portal"}],
+            "metrics": [{"name": "cache_ratio", "description": "Cache reads per input token"}],
+        }
+
+        blurred = blur_projects(response)
+
+        alias = project_alias("-home-me-acme-portal")
+        assert blurred["projects"][0] == {
+            "project_id": "-home-me-acme-portal",
+            "name": alias,
+            "description": None,
+            "absolute_path": None,
+        }
+        assert blurred["sessions"][0]["name"] == alias
+        assert blurred["sessions"][0]["cwd"] is None
+        assert blurred["metrics"] == response["metrics"]
+        assert response["projects"][0]["name"] == "ACME Portal"
+
+    def test_alias_is_stable(self):
+        """The same project always gets the same alias"""
+        assert project_alias("-a") == project_alias("-a")
+        assert project_alias("-a") != project_alias("-b")
@@ -0,0 +1,106 @@
+"""
+Unit tests for pricing_history module
+"""
+import sqlite3
+
+from command_center.database.schema import init_database
+from command_center.pricing_history import (
+    list_price_versions,
+    load_price_book,
+    query_api_equivalent_cost,
+    record_price_version,
+    reprice_history,
+)
+from command_center.utils.pricing import ModelPricing
+
+MODEL = "claude-sonnet-4-20250514"
+
+
+def _pricing(input_per_mtok, output_per_mtok):
+    return ModelPricing(
+        input_cost_per_token=input_per_mtok / 1_000_000,
+        input_cost_per_token_above_200k=None,
+        cache_creation_cost_per_token=0.0,
+        cache_creation_cost_per_token_above_200k=None,
+        cached_input_cost_per_token=0.0,
+        cached_input_cost_per_token_above_200k=None,
+        output_cost_per_token=output_per_mtok / 1_000_000,
+        output_cost_per_token_above_200k=None,
+    )
+
+
+def _insert_entry(conn, entry_hash, day, cost, cost_source=None):
+    conn.execute("""
+        INSERT INTO message_entries
+        (entry_hash, timestamp, timestamp_local, year, date, model, cost_usd,
+         input_tokens, total_tokens, source_file, cost_source)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+    """, (entry_hash, f"{day}T12:00:00Z", f"{day}T12:00:00", int(day[:4]), day, MODEL,
+          cost, 1_000_000, 1_000_000, "test.jsonl", cost_source))
+    conn.commit()
+
+
+class TestRecordPriceVersion:
+    """Tests for record_price_version and PriceBook lookups"""
+
+    def test_new_version_splits_covering_range(self):
+        """A later version closes the earlier one; lookups follow the message date"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        record_price_version(conn, MODEL, _pricing(3, 15), "1970-01-01", source="litellm")
+        record_price_version(conn, MODEL, _pricing(1, 5), "2025-05-01")
+
+        versions = list_price_versions(conn, MODEL)
+        assert [(v["effective_from"], v["effective_to"]) for v in versions] == [
+            ("1970-01-01", "2025-05-01"),
+            ("2025-05-01", None),
+        ]
+
+        book = load_price_book(conn)
+        assert book.pricing_for(MODEL, "2025-04-30").input_cost_per_token == 3e-06
+        assert book.pricing_for(MODEL, "2025-05-01").input_cost_per_token == 1e-06
+
+    def test_identical_rates_are_not_recorded(self):
+        """Recording the rates already in effect is a no-op"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        record_price_version(conn, MODEL, _pricing(3, 15), "1970-01-01")
+        assert record_price_version(conn, MODEL, _pricing(3, 15), "2025-01-01")["recorded"] is False
+
+
+class TestRepriceHistory:
+    """Tests for reprice_history function"""
+
+    def test_reprices_computed_costs_only(self):
+        """Computed costs follow the price table; costUSD from logs is kept"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        record_price_version(conn, MODEL, _pricing(3, 15), "1970-01-01")
+        record_price_version(conn, MODEL, _pricing(1, 5), "2025-05-01")
+        _insert_entry(conn, "a", "2025-04-10", 3.0)
+        _insert_entry(conn, "b", "2025-05-10", 3.0)
+        _insert_entry(conn, "c", "2025-05-11", 3.0, cost_source="jsonl")
+
+        preview = reprice_history(conn, dry_run=True)
+        assert preview["messages_changed"] == 1
+        assert preview["delta"] == -2.0
+
+        reprice_history(conn)
+        costs = dict(conn.execute("SELECT entry_hash, cost_usd FROM message_entries").fetchall())
+        assert costs == {"a": 3.0, "b": 1.0, "c": 3.0}
+
+
+class TestApiEquivalentCost:
+    """Tests for query_api_equivalent_cost function"""
+
+    def test_recomputes_costs_from_logs(self):
+        """costUSD rows are priced from tokens; computed rows are used as stored"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        record_price_version(conn, MODEL, _pricing(3, 15), "1970-01-01")
+        _insert_entry(conn, "a", "2025-04-10", 3.0, cost_source="computed")
+        _insert_entry(conn, "b", "2025-04-11", 0.5, cost_source="jsonl")
+
+        result = query_api_equivalent_cost(conn, "2025-04-01", "2025-04-30")
+        assert result["total"] == 6.0
+        assert result["by_model"] == {MODEL: 6.0}
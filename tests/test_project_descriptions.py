@@ -0,0 +1,60 @@
+"""
+Unit tests for project_descriptions module (local keyword extraction)
+"""
+import json
+import sqlite3
+
+from command_center.database.schema import init_database
+from command_center.project_descriptions import suggest_project_description
+
+
+def _write_session(path, lines):
+    path.write_text("\n".join(json.dumps(line) for line in lines) + "\n", encoding="utf-8")
+
+
+class TestSuggestProjectDescription:
+    """Tests for suggest_project_description without the LLM setting"""
+
+    def test_keywords_languages_and_files(self, tmp_path):
+        """Summaries, prompts and touched files feed the suggestion; tagged prompts are skipped"""
+        session = tmp_path / "s1.jsonl"
+        _write_session(session, [
+            {"type": "summary", "summary": "Invoice parser rewrite"},
+            {"type": "user", "message": {"role": "user", "content": "Make the invoice parser handle refunds"}},
+            {"type": "user", "message": {"role": "user", "content": "<command-name>/clear</command-name>"}},
+            {"type": "assistant", "message": {"role": "assistant", "content": [
+                {"type": "tool_use", "id": "t1", "name": "Edit", "input": {"file_path": "/src/invoice_parser.py"}},
+                {"type": "tool_use", "id": "t2", "name": "Read", "input": {"file_path": "/src/refunds.py"}},
+            ]}},
+        ])
+
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        conn.execute("""
+            INSERT INTO message_entries
+            (entry_hash, timestamp, timestamp_local, year, date, session_id, source_file, project_id, total_tokens)
+            VALUES ('h1', '2025-03-01T10:00:00Z', '2025-03-01T11:00:00+01:00', 2025, '2025-03-01', 's1', ?, 'p1', 10)
+        """, (str(session),))
+        conn.commit()
+
+        result = suggest_project_description(
+            conn, "p1", {"name": "Billing", "description": ""},
+            settings_path=str(tmp_path / "settings.json"),
+        )
+        assert result["source"] == "local"
+        assert result["sessions_analyzed"] == 1
+        assert result["languages"] == ["Python"]
+        assert result["keywords"][:2] == ["invoice", "parser"]
+        assert "command" not in result["keywords"]
+        assert result["files"] == ["invoice_parser.py", "refunds.py"]
+        assert result["suggestion"].startswith("Python project: invoice, parser")
+
+    def test_no_sessions(self, tmp_path):
+        """A project without readable sessions gets no suggestion"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        result = suggest_project_description(
+            conn, "p1", {"description": "Old"}, settings_path=str(tmp_path / "settings.json"),
+        )
+        assert result["suggestion"] is None
+        assert result["current_description"] == "Old"
@@ -52,6 +52,16 @@ class TestExtractProjectId:
         path = "/home/xai/.claude/projects/-home-xai--local-bin/session.jsonl"
         assert extract_project_id(path) == "-home-xai--local-bin"
 
+    def test_extract_from_alternate_config_dir(self):
+        """Extract project_id from a ~/.claude-* config dir (CLAUDE_CONFIG_DIR)"""
+        path = "/home/xai/.claude-work/projects/-home-xai-DEV-client/session.jsonl"
+        assert extract_project_id(path) == "-home-xai-DEV-client"
+
+    def test_extract_from_xdg_config_dir(self):
+        """Extract project_id from ~/.config/claude"""
+        path = "/home/xai/.config/claude/projects/-home-xai-DEV-client/session.jsonl"
+        assert extract_project_id(path) == "-home-xai-DEV-client"
+
 
 class TestReconstructAbsolutePath:
     """Tests for reconstruct_absolute_path function"""
@@ -0,0 +1,81 @@
+"""
+Unit tests for project pinning, ordering and colors in project_metadata module
+"""
+import pytest
+
+from command_center.utils.project_metadata import (
+    PROJECT_PALETTE,
+    list_all_projects,
+    project_color,
+    save_projects_json,
+    set_project_order,
+    set_project_pinned,
+    sort_projects,
+    update_project_fields,
+)
+
+
+def _ids(projects):
+    return [p["project_id"] for p in projects]
+
+
+class TestSortProjects:
+    """Tests for set_project_pinned / set_project_order / sort_projects"""
+
+    def test_pinned_first_then_requested_order(self, tmp_path):
+        """Pinned projects lead every order; manual order falls back to last_seen"""
+        json_path = str(tmp_path / "projects.json")
+        save_projects_json({
+            "-a": {"name": "Zed", "last_seen": "2025-01-01"},
+            "-b": {"name": "alpha", "last_seen": "2025-03-01"},
+            "-c": {"name": "Mid", "last_seen": "2025-02-01"},
+            "-d": {"name": "", "last_seen": "2025-04-01"},
+        }, json_path)
+
+        set_project_order(["-c", "-a"], json_path)
+        assert _ids(sort_projects(list_all_projects(json_path))) == ["-c", "-a", "-d", "-b"]
+
+        set_project_pinned("-b", True, json_path)
+        projects = list_all_projects(json_path)
+        assert _ids(sort_projects(projects, "manual")) == ["-b", "-c", "-a", "-d"]
+        assert _ids(sort_projects(projects, "last_activity")) == ["-b", "-d", "-c", "-a"]
+        assert _ids(sort_projects(projects, "name")) == ["-b", "-d", "-c", "-a"]
+
+        costs = {"-a": 5.0, "-b": 1.0, "-c": 9.0, "-d": 0.0}
+        for project in projects:
+            project["cost_this_month"] = costs[project["project_id"]]
+        assert _ids(sort_projects(projects, "cost_this_month")) == ["-b", "-c", "-a", "-d"]
+
+    def test_invalid_input(self, tmp_path):
+        """Unknown projects, duplicates and sorts are rejected"""
+        json_path = str(tmp_path / "projects.json")
+        save_projects_json({"-a": {"name": "A"}}, json_path)
+        with pytest.raises(ValueError):
+            set_project_order(["-a", "-a"], json_path)
+        with pytest.raises(ValueError):
+            set_project_pinned("-x", True, json_path)
+        with pytest.raises(ValueError):
+            sort_projects([], "size")
+
+
+class TestProjectColors:
+    """Tests for color/icon fields and project_color"""
+
+    def test_set_clear_and_fallback(self, tmp_path):
+        """Custom colors are stored lowercase; cleared colors fall back to a stable palette color"""
+        json_path = str(tmp_path / "projects.json")
+        save_projects_json({"-a": {"name": "A"}}, json_path)
+
+        updated = update_project_fields("-a", color="#3366CC", icon="🚀", json_path=json_path)
+        assert (updated["color"], updated["icon"]) == ("#3366cc", "🚀")
+        assert project_color("-a", updated) == "#3366cc"
+
+        updated = update_project_fields("-a", color="", icon="", json_path=json_path)
+        assert "color" not in updated and "icon" not in updated
+        assert project_color("-a", updated) in PROJECT_PALETTE
+        assert project_color("-a", updated) == project_color("-a")
+
+        with pytest.raises(ValueError):
+            update_project_fields("-a", color="blue", json_path=json_path)
+        with pytest.raises(ValueError):
+            update_project_fields("-a", icon="x" * 17, json_path=json_path)
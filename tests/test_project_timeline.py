@@ -0,0 +1,83 @@
+"""
+Unit tests for project_timeline module
+"""
+import sqlite3
+
+import pytest
+
+from command_center.database.schema import init_database
+from command_center.project_timeline import backfill_first_seen, query_project_timeline
+from command_center.utils.project_metadata import load_projects_json, save_projects_json
+
+
+def _insert(conn, index, project_id, session_id, day, model, cost, tokens=100):
+    conn.execute("""
+        INSERT INTO message_entries
+        (entry_hash, timestamp, timestamp_local, year, date, session_id, source_file, project_id,
+         model, total_tokens, cost_usd)
+        VALUES (?, ?, ?, 2025, ?, ?, 'f', ?, ?, ?, ?)
+    """, (f"h{index}", f"{day}T10:00:00Z", f"{day}T11:00:00+01:00", day, session_id, project_id,
+          model, tokens, cost))
+
+
+@pytest.fixture
+def conn():
+    conn = sqlite3.connect(":memory:")
+    init_database(conn)
+    _insert(conn, 1, "p1", "s1", "2025-01-05", "claude-sonnet-4-5", 4.0)
+    _insert(conn, 2, "p1", "s1", "2025-01-05", "claude-sonnet-4-5", 4.0)
+    _insert(conn, 3, "p1", "s2", "2025-02-10", "claude-opus-4-5", 5.0, tokens=900)
+    _insert(conn, 4, "p1", "s3", "2025-02-12", "claude-sonnet-4-5", 1.0)
+    _insert(conn, 5, "p2", "s4", "2024-12-01", "claude-sonnet-4-5", 1.0)
+    conn.commit()
+    return conn
+
+
+class TestProjectTimeline:
+    """Tests for query_project_timeline"""
+
+    def test_curve_and_milestones(self, conn):
+        """Daily curve with cumulative cost; new model, cost and biggest-day milestones"""
+        timeline = query_project_timeline(conn, "p1")
+        assert timeline["granularity"] == "day"
+        assert timeline["first_session"]["session_id"] == "s1"
+        assert timeline["last_session"]["session_id"] == "s3"
+        assert timeline["totals"]["sessions"] == 3
+        assert timeline["totals"]["cost"] == 14.0
+        assert [p["cumulative_cost"] for p in timeline["curve"]] == [8.0, 13.0, 14.0]
+
+        milestones = [(m["type"], m["date"]) for m in timeline["milestones"]]
+        assert milestones == [
+            ("first_session", "2025-01-05"),
+            ("new_model", "2025-02-10"),
+            ("cost_milestone", "2025-02-10"),
+            ("biggest_day", "2025-02-10"),
+            ("last_session", "2025-02-12"),
+        ]
+
+        monthly = query_project_timeline(conn, "p1", "month")
+        assert [(p["period"], p["sessions"]) for p in monthly["curve"]] == [("2025-01", 1), ("2025-02", 2)]
+
+    def test_unknown_project_and_granularity(self, conn):
+        """An empty project has no curve; invalid granularities are rejected"""
+        assert query_project_timeline(conn, "missing")["curve"] == []
+        with pytest.raises(ValueError):
+            query_project_timeline(conn, "p1", "year")
+
+
+class TestBackfillFirstSeen:
+    """Tests for backfill_first_seen"""
+
+    def test_only_moves_first_seen_back(self, conn, tmp_path):
+        """first_seen becomes the first message unless it is already earlier"""
+        json_path = str(tmp_path / "projects.json")
+        save_projects_json({
+            "p1": {"first_seen": "2025-06-01T09:00:00+02:00"},
+            "p2": {"first_seen": "2024-01-01T00:00:00+01:00"},
+        }, json_path)
+
+        result = backfill_first_seen(conn, json_path=json_path)
+        assert [u["project_id"] for u in result["updated"]] == ["p1"]
+        projects = load_projects_json(json_path)
+        assert projects["p1"]["first_seen"] == "2025-01-05T11:00:00+01:00"
+        assert projects["p2"]["first_seen"] == "2024-01-01T00:00:00+01:00"
@@ -0,0 +1,57 @@
+"""
+Unit tests for quarantine module and malformed-line handling in parse_file
+"""
+import json
+
+from command_center.cache.incremental_update import parse_file
+from command_center.cache.quarantine import read_quarantined_lines, replace_quarantined_lines
+
+
+def _message(message_id: str) -> str:
+    return json.dumps({
+        "type": "assistant", "timestamp": "2025-06-15T09:00:00.000Z", "sessionId": "s1",
+        "requestId": f"req-{message_id}",
+        "message": {"id": message_id, "model": "claude-sonnet-4-20250514",
+                    "usage": {"input_tokens": 100, "output_tokens": 10}},
+    }) + "\n"
+
+
+class TestParseFile:
+    """Tests for malformed lines in parse_file"""
+
+    def test_bad_lines_are_quarantined_not_fatal(self, tmp_path):
+        """Broken JSON and invalid UTF-8 are set aside; a partial last line is left for later"""
+        log = tmp_path / "s1.jsonl"
+        log.write_bytes(
+            _message("a").encode()
+            + b'{"type": "assistant", "timest\n'
+            + b'\xff\xfe not utf-8\n'
+            + _message("b").encode()
+            + b'{"type": "assist'
+        )
+
+        parsed = parse_file(str(log))
+
+        assert parsed.readable
+        assert [entry.message_id for entry in parsed.entries] == ["a", "b"]
+        assert [(q["line_number"], q["error"].split(":")[0]) for q in parsed.quarantined] == [
+            (2, "JSONDecodeError"), (3, "UnicodeDecodeError"),
+        ]
+
+
+class TestQuarantineFile:
+    """Tests for replace_quarantined_lines / read_quarantined_lines"""
+
+    def test_reparsed_file_replaces_its_records(self, tmp_path):
+        """Records of a re-parsed file are replaced; other files keep theirs"""
+        path = str(tmp_path / "quarantine.jsonl")
+        record = {"source_file": "a.jsonl", "line_number": 2, "error": "x", "content": "{",
+                  "truncated": False, "quarantined_at": "2025-06-15T10:00:00+00:00"}
+        replace_quarantined_lines({"a.jsonl": [record], "b.jsonl": [dict(record, source_file="b.jsonl")]}, path)
+        assert read_quarantined_lines(path=path)["total"] == 2
+
+        # a.jsonl was fixed
+        assert replace_quarantined_lines({"a.jsonl": []}, path) == 0
+        result = read_quarantined_lines(path=path)
+        assert (result["total"], result["files"]) == (1, 1)
+        assert result["lines"][0]["source_file"] == "b.jsonl"
@@ -0,0 +1,62 @@
+"""
+Unit tests for refresh_status module
+"""
+import sqlite3
+from datetime import datetime, timedelta
+
+import pytest
+
+from command_center.cache import refresh_status
+from command_center.cache.refresh_status import get_refresh_status, load_refresh_status, track_refresh
+from command_center.database.queries import update_file_track
+from command_center.database.schema import init_database
+
+
+class TestTrackRefresh:
+    """Tests for track_refresh context manager"""
+
+    def test_records_success_and_failure(self, tmp_path):
+        """A finished refresh becomes last_success; a failing one keeps it and adds last_error"""
+        json_path = str(tmp_path / "status.json")
+        with track_refresh("full", json_path) as tracker:
+            assert load_refresh_status(json_path)["state"] == "running"
+            tracker.progress(2, 2)
+            assert load_refresh_status(json_path)["current"]["files_done"] == 2
+            tracker.updated_files = 2
+
+        with pytest.raises(RuntimeError):
+            with track_refresh("partial", json_path):
+                raise RuntimeError("database is locked")
+
+        status = load_refresh_status(json_path)
+        assert status["state"] == "failed"
+        assert status["last_success"]["updated_files"] == 2
+        assert status["last_error"]["error"] == "database is locked"
+
+
+class TestGetRefreshStatus:
+    """Tests for get_refresh_status function"""
+
+    def test_pending_files_and_stale_refresh(self, tmp_path, monkeypatch):
+        """Untracked files are pending per root; a running refresh without progress is stale"""
+        root = tmp_path / "claude"
+        (root / "projects" / "p1").mkdir(parents=True)
+        ingested, pending = root / "projects" / "p1" / "a.jsonl", root / "projects" / "p1" / "b.jsonl"
+        ingested.write_text("{}\n")
+        pending.write_text("{}\n")
+        monkeypatch.setattr(refresh_status, "get_claude_dirs", lambda: [str(root)])
+
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        stat = ingested.stat()
+        update_file_track(conn, str(ingested), stat.st_mtime_ns, stat.st_size, 1)
+
+        json_path = str(tmp_path / "status.json")
+        with track_refresh("full", json_path):
+            later = datetime.now() + timedelta(minutes=11)
+            status = get_refresh_status(conn, json_path, now=later)
+
+        assert status["state"] == "stale"
+        assert status["pending_files"] == 1
+        assert status["roots"][0]["tracked_files"] == 1
+        assert status["roots"][0]["last_scanned_at"] is not None
@@ -0,0 +1,56 @@
+"""
+Unit tests for report_description module
+"""
+import datetime
+
+from command_center.database.models import UsageStats
+from command_center.visualization.report_description import describe_usage_report
+
+
+def _stats(**overrides):
+    values = dict(
+        date_from="2026-01-01", date_to="2026-01-10",
+        daily_activity={"2026-01-02": 10, "2026-01-03": 30, "2026-01-04": 20},
+        top_models=[
+            {"model": "claude-sonnet-4-20250514", "tokens": 3_000_000, "messages": 50, "cost": 9.0},
+            {"model": "claude-opus-4-20250514", "tokens": 1_000_000, "messages": 10, "cost": 6.0},
+        ],
+        total_messages=60, total_sessions=4, total_tokens=4_000_000, total_cost=15.0,
+        cache_read_tokens=900, cache_write_tokens=100,
+        first_session_date=datetime.datetime(2025, 12, 1, 9, 0),
+        top_projects=[{"project_id": "-home-me-app", "tokens": 3_000, "cost": 1.0},
+                      {"project_id": "-home-me-lib", "tokens": 1_000, "cost": 0.5}],
+        project_count=2,
+    )
+    values.update(overrides)
+    return UsageStats(**values)
+
+
+class TestDescribeUsageReport:
+    """Tests for describe_usage_report"""
+
+    def test_alt_text_and_panels(self):
+        description = describe_usage_report(_stats(), {"-home-me-app": {"name": "App"}}, excluded=set())
+        assert description["alt_text"] == (
+            "Claude Code usage report for 2026-01-01 to 2026-01-10: 60 messages in 4 sessions, "
+            "4.0 million tokens, $15.00 usage cost; most used model Sonnet 4."
+        )
+        lines = description["long_description"].split("\n")
+        assert lines[0] == "Claude Code usage report, 2026-01-01 to 2026-01-10."
+        assert "Started: first session on 2025-12-01." in lines
+        assert "Most active day: 2026-01-03 with 30 messages." in lines
+        assert "Activity heatmap: messages on 3 of 10 days, 20 per active day on average." in lines
+        assert any("longest streak 3 days (current" in line for line in lines)
+        assert "Cache efficiency: 900 tokens read, 100 written, hit rate 90.0%." in lines
+        assert lines[-1] == "Token share of the top projects: App 75%, -home-me-lib 25%."
+
+    def test_empty_range(self):
+        description = describe_usage_report(
+            _stats(daily_activity={}, top_models=[], top_projects=[], total_messages=0, total_sessions=0,
+                   total_tokens=0, total_cost=0.0, cache_read_tokens=0, cache_write_tokens=0,
+                   first_session_date=None, project_count=0),
+            excluded=set(),
+        )
+        assert description["alt_text"].endswith("0 messages in 0 sessions, 0 tokens, no usage cost.")
+        assert "Activity heatmap: no messages in the 10 days." in description["long_description"]
+        assert "hit rate 0.0%" in description["long_description"]
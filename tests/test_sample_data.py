@@ -0,0 +1,88 @@
+"""
+Unit tests for sample_data module
+"""
+import sqlite3
+from datetime import date
+
+import pytest
+
+from command_center.cache import incremental_update
+from command_center.collectors.jsonl_parser import parse_jsonl_line
+from command_center.database.schema import init_database
+from command_center.demo_data import DEMO_PROJECTS
+from command_center.sample_data import generate_sample_data, write_sample_logs
+
+
+def _read(root):
+    return [p.read_text() for p in sorted(root.glob("*/*.jsonl"))]
+
+
+class TestWriteSampleLogs:
+    """Tests for write_sample_logs"""
+
+    def test_writes_parseable_sessions(self, tmp_path):
+        """Assistant lines are messages the parser accepts, with a cost, in the requested range"""
+        result = write_sample_logs(tmp_path, days=14, intensity="light", seed=1, end_date=date(2025, 6, 30))
+        paths = sorted(tmp_path.glob("*/*.jsonl"))
+        entries = [
+            entry for path in paths for line in path.read_text().splitlines()
+            if (entry := parse_jsonl_line(line, str(path))) is not None
+        ]
+
+        assert result["date_from"] == "2025-06-17"
+        assert result["sessions"] == len(paths) > 0
+        assert result["messages"] == len(entries)
+        assert all(entry.cost_usd > 0 for entry in entries)
+        assert len({entry.entry_hash for entry in entries}) == len(entries)
+        assert all("2025-06-16" <= entry.date <= "2025-07-01" for entry in entries)
+
+    def test_same_seed_same_logs(self, tmp_path):
+        """A seed and intensity reproduce the logs; heavier intensity writes more sessions"""
+        light = write_sample_logs(tmp_path / "a", 10, "light", seed=3, end_date=date(2025, 6, 30))
+        write_sample_logs(tmp_path / "b", 10, "light", seed=3, end_date=date(2025, 6, 30))
+        heavy = write_sample_logs(tmp_path / "c", 10, "heavy", seed=3, end_date=date(2025, 6, 30))
+
+        assert _read(tmp_path / "a") == _read(tmp_path / "b")
+        assert heavy["sessions"] > light["sessions"]
+
+    def test_rejects_invalid_arguments(self, tmp_path):
+        """History length and intensity are checked"""
+        with pytest.raises(ValueError):
+            write_sample_logs(tmp_path, days=0)
+        with pytest.raises(ValueError, match="Unknown intensity"):
+            write_sample_logs(tmp_path, intensity="extreme")
+
+
+class TestGenerateSampleData:
+    """Tests for generate_sample_data"""
+
+    def test_ingests_logs_and_replaces_previous_data(self, tmp_path, monkeypatch):
+        """Logs are ingested with the regular update; MCP calls and hooks are recorded too"""
+        projects_dir = tmp_path / ".claude" / "projects"
+        monkeypatch.setattr(
+            incremental_update, "scan_jsonl_files", lambda: [str(p) for p in projects_dir.glob("*/*.jsonl")]
+        )
+        monkeypatch.setattr(incremental_update, "sync_price_versions", lambda conn: None)
+        monkeypatch.setattr(incremental_update, "load_projects_json", lambda: {})
+        monkeypatch.setattr(incremental_update, "save_projects_json", lambda projects: None)
+        monkeypatch.setenv("CLAUDE_CONFIG_DIR", str(tmp_path / "real"))
+
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        stale = projects_dir / "-home-old" / "old.jsonl"
+        stale.parent.mkdir(parents=True)
+        stale.write_text("")
+        kwargs = {"end_date": date(2025, 6, 30), "projects_json_path": str(tmp_path / "projects.json")}
+
+        generate_sample_data(conn, projects_dir, days=20, intensity="heavy", seed=5, **kwargs)
+        result = generate_sample_data(conn, projects_dir, days=20, intensity="regular", seed=5, **kwargs)
+
+        messages = conn.execute("SELECT COUNT(*) FROM message_entries").fetchone()[0]
+        hourly = conn.execute("SELECT SUM(message_count) FROM hourly_aggregates").fetchone()[0]
+        assert not stale.exists()
+        assert result["data_root"] == str(tmp_path / ".claude")
+        assert result["ingested_files"] == result["sessions"]
+        assert result["messages"] == messages == hourly > 0
+        assert result["projects"] == len(DEMO_PROJECTS)
+        assert conn.execute("SELECT COUNT(*) FROM mcp_calls").fetchone()[0] > 0
+        assert conn.execute("SELECT COUNT(*) FROM hook_executions").fetchone()[0] > 0
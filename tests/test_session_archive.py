@@ -0,0 +1,107 @@
+"""
+Unit tests for session_archive module
+"""
+import json
+import sqlite3
+import zipfile
+
+from command_center.database.schema import init_database
+import pytest
+
+from command_center.session_archive import (
+    MANIFEST_NAME,
+    REDACTED,
+    export_session_archive,
+    import_session_archive,
+    redact_record,
+)
+
+
+def _record(session_id, message_id, text):
+    return {
+        "type": "assistant", "sessionId": session_id, "requestId": f"req-{message_id}",
+        "cwd": "/home/me/secret-project", "timestamp": "2025-06-15T12:00:00Z",
+        "message": {"id": message_id, "model": "claude-sonnet-4-20250514",
+                    "content": [{"type": "text", "text": text},
+                                {"type": "tool_use", "id": "t1", "name": "Bash", "input": {"command": "ls"}}],
+                    "usage": {"input_tokens": 100, "output_tokens": 10}},
+    }
+
+
+class TestRedactRecord:
+    """Tests for redact_record function"""
+
+    def test_content_is_replaced_and_usage_kept(self):
+        """Text, tool input and paths are redacted; ids, model and usage stay"""
+        redacted = redact_record(_record("s1", "m1", "my api key is 123"))
+        assert redacted["cwd"] == REDACTED
+        assert redacted["message"]["usage"] == {"input_tokens": 100, "output_tokens": 10}
+        assert redacted["message"]["content"] == [
+            {"type": "text", "text": REDACTED},
+            {"type": "tool_use", "id": "t1", "name": "Bash", "input": REDACTED},
+        ]
+        assert "123" not in json.dumps(redacted)
+
+
+def _db_with_session(tmp_path):
+    source = tmp_path / "s1.jsonl"
+    source.write_text(json.dumps(_record("s1", "m1", "secret")) + "\n")
+    conn = sqlite3.connect(":memory:")
+    init_database(conn)
+    conn.execute("""
+        INSERT INTO message_entries
+        (entry_hash, timestamp, timestamp_local, year, date, session_id, source_file, project_id)
+        VALUES ('h1', '2025-06-15T12:00:00Z', '2025-06-15T12:00:00', 2025, '2025-06-15', 's1', ?, 'proj')
+    """, (str(source),))
+    return conn
+
+
+class TestExportSessionArchive:
+    """Tests for export_session_archive function"""
+
+    def test_archive_contains_files_and_manifest(self, tmp_path):
+        """Each session file is stored once under its project with a checksum"""
+        conn = _db_with_session(tmp_path)
+
+        output = tmp_path / "out.zip"
+        result = export_session_archive(conn, ["s1"], str(output), redact=True)
+        assert result["files"] == 1
+
+        with zipfile.ZipFile(output) as archive:
+            manifest = json.loads(archive.read(MANIFEST_NAME))
+            assert manifest["redacted"] is True
+            assert manifest["sessions"][0]["files"][0]["name"] == "proj/s1.jsonl"
+            assert b"secret" not in archive.read("proj/s1.jsonl")
+
+
+class TestImportSessionArchive:
+    """Tests for import_session_archive function"""
+
+    def test_import_marks_rows_and_skips_duplicates(self, tmp_path):
+        """Imported messages land in the target project; a second import adds nothing"""
+        archive = tmp_path / "share.zip"
+        export_session_archive(_db_with_session(tmp_path), ["s1"], str(archive))
+
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        kwargs = {"imports_dir": str(tmp_path / "imports"), "projects_json_path": str(tmp_path / "projects.json")}
+        first = import_session_archive(conn, str(archive), "teammate", **kwargs)
+        second = import_session_archive(conn, str(archive), "teammate", **kwargs)
+
+        assert (first["messages"], first["created_project"]) == (1, True)
+        assert (second["messages"], second["duplicates"]) == (0, 1)
+        assert conn.execute("SELECT project_id, origin FROM message_entries").fetchall() == [("teammate", "imported")]
+
+    def test_tampered_archive_is_rejected(self, tmp_path):
+        """A file whose checksum does not match the manifest aborts the import"""
+        archive = tmp_path / "share.zip"
+        export_session_archive(_db_with_session(tmp_path), ["s1"], str(archive))
+        with zipfile.ZipFile(archive, "a") as zf:
+            zf.writestr("proj/s1.jsonl", "{}\n")
+
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        with pytest.raises(ValueError):
+            import_session_archive(conn, str(archive), "teammate", imports_dir=str(tmp_path / "imports"),
+                                   projects_json_path=str(tmp_path / "projects.json"))
+        assert conn.execute("SELECT COUNT(*) FROM message_entries").fetchone()[0] == 0
@@ -0,0 +1,71 @@
+"""
+Unit tests for session_outcomes module
+"""
+import json
+import sqlite3
+
+from command_center.database.schema import init_database
+from command_center.session_outcomes import classify_events, classify_sessions, query_outcome_stats
+
+
+def _result(command, error=False, output=""):
+    return {"kind": "tool_result", "error": error, "command": command, "output": output}
+
+
+def _write_session(path, session_id, command, is_error=False, output="ok"):
+    lines = [
+        {"type": "assistant", "sessionId": session_id, "message": {"content": [
+            {"type": "tool_use", "id": "t1", "name": "Bash", "input": {"command": command}}]}},
+        {"type": "user", "sessionId": session_id, "message": {"content": [
+            {"type": "tool_result", "tool_use_id": "t1", "is_error": is_error, "content": output}]}},
+    ]
+    path.write_text("\n".join(json.dumps(line) for line in lines) + "\n")
+
+
+class TestClassifyEvents:
+    """Tests for classify_events"""
+
+    def test_last_decisive_event_wins(self):
+        """Commits, passing tests, errors and interrupts are recognized from the end"""
+        assert classify_events([_result("git commit -m x")])["outcome"] == "commit"
+        assert classify_events([_result("uv run pytest -q", output="12 passed")])["outcome"] == "test_pass"
+        assert classify_events([_result("pytest", output="1 failed, 3 passed")])["outcome"] == "other"
+        assert classify_events([_result("git commit -m x"), _result("ls", error=True)])["outcome"] == "tool_error"
+        assert classify_events([_result("ls"), {"kind": "interrupt"}])["outcome"] == "user_interrupt"
+        assert classify_events([])["outcome"] == "other"
+
+    def test_recovered_error_and_shipped_flag(self):
+        """A success after an error is recovery; any commit marks the session shipped"""
+        result = classify_events([
+            _result("git commit -m x"),
+            _result("cargo build", error=True),
+            _result("cargo build"),
+        ])
+        assert result == {"outcome": "commit", "committed": True, "evidence": "git commit -m x"}
+
+
+class TestOutcomeStats:
+    """Tests for classify_sessions / query_outcome_stats"""
+
+    def test_sessions_are_classified_once_and_aggregated(self, tmp_path):
+        """Unchanged files are skipped on the next pass; shares are per classified session"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        for session_id, command, is_error in (("s1", "git commit -m a", False), ("s2", "npm test", True)):
+            path = tmp_path / f"{session_id}.jsonl"
+            _write_session(path, session_id, command, is_error)
+            conn.execute("""
+                INSERT INTO message_entries
+                (entry_hash, timestamp, timestamp_local, year, date, session_id, source_file, project_id)
+                VALUES (?, '2025-03-01T10:00:00Z', '2025-03-01T11:00:00+01:00', 2025, '2025-03-01', ?, ?, 'p1')
+            """, (f"h-{session_id}", session_id, str(path)))
+        conn.commit()
+
+        assert classify_sessions(conn, "2025-03-01", "2025-03-31")["classified"] == 2
+        assert classify_sessions(conn, "2025-03-01", "2025-03-31")["unchanged"] == 2
+
+        stats = query_outcome_stats(conn, "2025-03-01", "2025-03-31")
+        outcomes = {o["outcome"]: o["sessions"] for o in stats["outcomes"]}
+        assert outcomes["commit"] == 1 and outcomes["tool_error"] == 1
+        assert stats["shipped"] == {"sessions": 1, "share": 0.5}
+        assert stats["by_project"][0]["project_id"] == "p1"
@@ -0,0 +1,81 @@
+"""
+Unit tests for cursor-paginated session listing and session file lookup
+"""
+import sqlite3
+
+import pytest
+
+from command_center.database.queries import query_session_source_files, query_sessions_page
+from command_center.database.schema import init_database
+
+
+def _insert(conn, entry_hash, session_id, cost, day="2025-06-15", model="claude-sonnet-4-20250514",
+            source_file="test.jsonl"):
+    conn.execute("""
+        INSERT INTO message_entries
+        (entry_hash, timestamp, timestamp_local, year, date, session_id, model, cost_usd,
+         input_tokens, total_tokens, source_file)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+    """, (entry_hash, f"{day}T12:00:00Z", f"{day}T12:00:00", int(day[:4]), day, session_id,
+          model, cost, 100, 100, source_file))
+    conn.commit()
+
+
+def _db():
+    conn = sqlite3.connect(":memory:")
+    init_database(conn)
+    # s3 and s4 tie on cost; session_id breaks the tie
+    for i, (session_id, cost) in enumerate([("s1", 5.0), ("s2", 4.0), ("s3", 2.0), ("s4", 2.0), ("s5", 1.0)]):
+        _insert(conn, f"h{i}", session_id, cost)
+    return conn
+
+
+class TestQuerySessionsPage:
+    """Tests for query_sessions_page function"""
+
+    def test_pages_cover_all_sessions_once(self):
+        """Following next_cursor visits every session in sort order"""
+        conn = _db()
+        seen = []
+        cursor = None
+        while True:
+            page = query_sessions_page(conn, "2025-06-01", "2025-06-30", cursor=cursor, page_size=2)
+            assert page["total_count"] == 5
+            seen.extend(s["session_id"] for s in page["sessions"])
+            cursor = page["next_cursor"]
+            if cursor is None:
+                break
+        assert seen == ["s1", "s2", "s3", "s4", "s5"]
+
+    def test_cursor_is_stable_when_sessions_are_added(self):
+        """A session ingested between pages does not shift the next page"""
+        conn = _db()
+        first = query_sessions_page(conn, "2025-06-01", "2025-06-30", page_size=2)
+        _insert(conn, "h-new", "s0", 9.0)
+
+        second = query_sessions_page(conn, "2025-06-01", "2025-06-30", cursor=first["next_cursor"], page_size=2)
+        assert [s["session_id"] for s in second["sessions"]] == ["s3", "s4"]
+        assert second["total_count"] == 6
+
+    def test_cursor_rejected_for_other_filter(self):
+        """Cursors are bound to the filter and sort they were issued for"""
+        conn = _db()
+        page = query_sessions_page(conn, "2025-06-01", "2025-06-30", page_size=2)
+        with pytest.raises(ValueError):
+            query_sessions_page(conn, "2025-06-01", "2025-06-30", sort="recent", cursor=page["next_cursor"])
+
+
+class TestQuerySessionSourceFiles:
+    """Tests for query_session_source_files function"""
+
+    def test_lists_each_file_once_in_message_order(self):
+        """Subagent files follow the main session file"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        _insert(conn, "a", "s1", 1.0, day="2025-06-15", source_file="s1.jsonl")
+        _insert(conn, "b", "s1", 1.0, day="2025-06-16", source_file="agent-1.jsonl")
+        _insert(conn, "c", "s1", 1.0, day="2025-06-17", source_file="s1.jsonl")
+        _insert(conn, "d", "s2", 1.0, source_file="s2.jsonl")
+
+        files = query_session_source_files(conn, "s1")
+        assert [(f["source_file"], f["messages"]) for f in files] == [("s1.jsonl", 2), ("agent-1.jsonl", 1)]
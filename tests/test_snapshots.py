@@ -0,0 +1,50 @@
+"""
+Unit tests for snapshots module
+"""
+import sqlite3
+
+import pytest
+
+from command_center.database.schema import init_database
+from command_center.snapshots import create_snapshot, diff_snapshots, list_snapshots
+
+
+def _insert_entry(conn, entry_hash, day, cost):
+    conn.execute("""
+        INSERT INTO message_entries
+        (entry_hash, timestamp, timestamp_local, year, date, model, cost_usd, total_tokens, source_file)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+    """, (entry_hash, f"{day}T12:00:00Z", f"{day}T12:00:00", int(day[:4]), day,
+          "claude-sonnet-4-20250514", cost, 100, "test.jsonl"))
+    conn.commit()
+
+
+class TestSnapshots:
+    """Tests for create_snapshot and diff_snapshots"""
+
+    def test_snapshot_is_frozen_and_diffable_against_live(self):
+        """Later data changes show up in the live diff, not in the snapshot"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        _insert_entry(conn, "a", "2025-04-10", 2.0)
+
+        snapshot = create_snapshot(conn, "2025-Q2", "2025-04-01", "2025-06-30")
+        frozen_cost = snapshot["data"]["totals"]["cost"]
+
+        _insert_entry(conn, "b", "2025-04-11", 3.0)
+
+        listed = list_snapshots(conn)
+        assert listed[0]["totals"]["cost"] == frozen_cost
+
+        result = diff_snapshots(conn, "2025-Q2")
+        assert result["target"]["name"] == "live"
+        assert result["diff"]["changed"] is True
+        assert result["diff"]["totals"]["cost"]["before"] == frozen_cost
+
+    def test_duplicate_name_rejected(self):
+        """Snapshot names are unique"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        create_snapshot(conn, "q", "2025-01-01", "2025-03-31")
+        with pytest.raises(ValueError):
+            create_snapshot(conn, "q", "2025-01-01", "2025-03-31")
@@ -0,0 +1,81 @@
+"""
+Unit tests for stale_projects module and archive_projects
+"""
+import sqlite3
+from datetime import datetime, timezone
+
+import pytest
+
+from command_center.cache.mirror import mirror_path
+from command_center.database.schema import init_database
+from command_center.stale_projects import query_stale_projects
+from command_center.utils.project_metadata import archive_projects, load_projects_json, save_projects_json
+
+NOW = datetime(2025, 10, 1, 12, 0, tzinfo=timezone.utc)
+
+
+def _insert(conn, index, project_id, day, source_file, cost=1.0):
+    conn.execute("""
+        INSERT INTO message_entries
+        (entry_hash, timestamp, timestamp_local, year, date, session_id, source_file, project_id,
+         total_tokens, cost_usd)
+        VALUES (?, ?, ?, 2025, ?, ?, ?, ?, 100, ?)
+    """, (f"h{index}", f"{day}T10:00:00Z", f"{day}T10:00:00+00:00", day, f"s{index}", source_file,
+          project_id, cost))
+
+
+class TestStaleProjects:
+    """Tests for query_stale_projects"""
+
+    def test_threshold_footprint_and_suggestions(self, tmp_path):
+        """Old projects are listed with file sizes; pinned and hidden ones get no suggestion"""
+        source = tmp_path / "old.jsonl"
+        source.write_bytes(b"x" * 50)
+        mirror_dir = tmp_path / "mirror"
+        mirror_dir.mkdir()
+        mirror_path(str(source), str(mirror_dir)).write_bytes(b"y" * 7)
+
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        _insert(conn, 1, "old", "2025-03-01", str(source))
+        _insert(conn, 2, "old", "2025-02-01", str(source))
+        _insert(conn, 3, "recent", "2025-09-20", str(tmp_path / "recent.jsonl"))
+        _insert(conn, 4, "pinned", "2025-01-01", str(tmp_path / "missing.jsonl"))
+        conn.commit()
+        projects = {
+            "old": {"name": "Old", "visible": True},
+            "recent": {"name": "Recent", "visible": True},
+            "pinned": {"name": "Pinned", "visible": True, "pinned": True},
+            "hidden": {"name": "Hidden", "visible": False, "last_seen": "2024-10-01T09:00:00+02:00"},
+        }
+
+        result = query_stale_projects(conn, projects, 90, now=NOW, mirror_dir=str(mirror_dir))
+        stale = {p["project_id"]: p for p in result["projects"]}
+        assert [p["project_id"] for p in result["projects"]] == ["hidden", "pinned", "old"]
+        assert stale["old"]["days_inactive"] == 214
+        assert stale["old"]["messages"] == 2
+        assert stale["old"]["source_files"] == 1
+        assert (stale["old"]["source_bytes"], stale["old"]["mirror_bytes"]) == (50, 7)
+        assert stale["old"]["suggestion"] == "archive"
+        assert stale["pinned"]["suggestion"] is None
+        assert stale["hidden"]["suggestion"] is None
+        assert result["totals"] == {"projects": 3, "suggested": 1, "source_bytes": 50, "mirror_bytes": 7}
+
+        with pytest.raises(ValueError):
+            query_stale_projects(conn, projects, -1)
+
+
+class TestArchiveProjects:
+    """Tests for archive_projects"""
+
+    def test_hides_and_unpins(self, tmp_path):
+        """Archived projects are hidden and unpinned; unknown IDs are rejected"""
+        json_path = str(tmp_path / "projects.json")
+        save_projects_json({"a": {"visible": True, "pinned": True}, "b": {"visible": True}}, json_path)
+
+        archived = archive_projects(["a"], json_path)
+        assert archived == [{"project_id": "a", "visible": False, "pinned": False}]
+        assert load_projects_json(json_path)["b"]["visible"] is True
+
+        with pytest.raises(ValueError):
+            archive_projects(["a", "missing"], json_path)
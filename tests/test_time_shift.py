@@ -0,0 +1,103 @@
+"""
+Unit tests for time_shift module
+"""
+import sqlite3
+
+import pytest
+
+from command_center.database.models import MessageEntry
+from command_center.database.queries import insert_message_entries
+from command_center.database.schema import init_database
+from command_center.time_shift import (
+    list_timestamp_shifts,
+    parse_offset,
+    shift_timestamps,
+    undo_timestamp_shift,
+)
+from command_center.utils.date_helpers import parse_and_convert_to_local
+
+
+def _entry(entry_hash, timestamp, session_id="s1", tokens=100):
+    local = parse_and_convert_to_local(timestamp)
+    return MessageEntry(
+        entry_hash=entry_hash, timestamp=timestamp, timestamp_local=local.isoformat(),
+        year=local.year, date=local.strftime("%Y-%m-%d"), session_id=session_id,
+        message_id=entry_hash, model="claude-sonnet-4", cost_usd=0.5, output_tokens=tokens // 2,
+        total_tokens=tokens, source_file="/a/projects/p/s1.jsonl", project_id="p",
+    )
+
+
+def _database():
+    conn = sqlite3.connect(":memory:")
+    init_database(conn)
+    insert_message_entries(conn, [
+        _entry("a", "2031-06-10T12:00:00.500Z"),
+        _entry("b", "2031-06-11T12:00:00.000Z"),
+        _entry("c", "2025-06-10T12:00:00.000Z", session_id="s2"),
+    ])
+    return conn
+
+
+def _yearly_tokens(conn):
+    return dict(conn.execute(
+        "SELECT year, SUM(total_tokens) FROM model_aggregates GROUP BY year"
+    ).fetchall())
+
+
+class TestParseOffset:
+    """Tests for parse_offset"""
+
+    def test_units_and_seconds(self):
+        assert parse_offset("-1d2h30m") == -(86400 + 2 * 3600 + 30 * 60)
+        assert parse_offset("+90s") == 90
+        assert parse_offset("3600") == 3600
+
+    def test_rejected(self):
+        for value in ["", "1x", "0", "12000d", "1d-2h"]:
+            with pytest.raises(ValueError):
+                parse_offset(value)
+
+
+class TestShiftTimestamps:
+    """Tests for shift_timestamps and undo_timestamp_shift"""
+
+    def test_preview_shift_and_undo(self):
+        """A preview writes nothing; a shift moves dates and aggregates; undo restores them"""
+        conn = _database()
+        offset = parse_offset("-2190d")   # 2031-06-10 -> 2025-06-11
+
+        preview = shift_timestamps(conn, offset, date_from="2031-01-01", preview=True)
+        assert (preview["messages"], preview["sessions"], preview["shift_id"]) == (2, 1, None)
+        assert conn.execute("SELECT COUNT(*) FROM message_entries WHERE year = 2031").fetchone()[0] == 2
+
+        result = shift_timestamps(conn, offset, date_from="2031-01-01")
+        assert result["messages"] == 2
+        assert result["after"] == {"from": "2025-06-11", "to": "2025-06-12"}
+        assert conn.execute(
+            "SELECT timestamp FROM message_entries WHERE entry_hash = 'a'"
+        ).fetchone()[0] == "2025-06-11T12:00:00.500Z"
+        assert _yearly_tokens(conn) == {2025: 300}
+        assert conn.execute(
+            "SELECT COUNT(*) FROM hourly_aggregates WHERE datetime_hour LIKE '2031-%'"
+        ).fetchone()[0] == 0
+
+        shifts = list_timestamp_shifts(conn)["shifts"]
+        assert shifts[0]["filter"] == {"date_from": "2031-01-01"}
+        assert shifts[0]["undone_at"] is None
+
+        undone = undo_timestamp_shift(conn, result["shift_id"])
+        assert undone["messages"] == 2
+        assert conn.execute(
+            "SELECT timestamp FROM message_entries WHERE entry_hash = 'a'"
+        ).fetchone()[0] == "2031-06-10T12:00:00.500Z"
+        assert _yearly_tokens(conn) == {2025: 100, 2031: 200}
+        with pytest.raises(ValueError):
+            undo_timestamp_shift(conn, result["shift_id"])
+
+    def test_selection_required(self):
+        """A shift without a date range or sessions is refused"""
+        conn = _database()
+        with pytest.raises(ValueError):
+            shift_timestamps(conn, 3600, project_id="p")
+        result = shift_timestamps(conn, 3600, session_ids=["s2"])
+        assert result["messages"] == 1
@@ -0,0 +1,75 @@
+"""
+Unit tests for working_hours module
+"""
+import sqlite3
+
+import pytest
+
+from command_center.database.models import MessageEntry
+from command_center.database.queries import insert_message_entries
+from command_center.database.schema import init_database
+from command_center.working_hours import (
+    is_work_time,
+    load_working_hours,
+    query_out_of_hours_usage,
+    set_working_hours,
+)
+
+
+def _entry(entry_hash, timestamp_local, session_id="s1", cost=1.0):
+    return MessageEntry(
+        entry_hash=entry_hash, timestamp=timestamp_local + "Z", timestamp_local=timestamp_local,
+        year=int(timestamp_local[:4]), date=timestamp_local[:10], session_id=session_id,
+        message_id=entry_hash, model="claude-sonnet-4", cost_usd=cost, output_tokens=50,
+        total_tokens=100, source_file="/a/projects/p/s1.jsonl", project_id="p",
+    )
+
+
+class TestSetWorkingHours:
+    """Tests for set_working_hours and load_working_hours"""
+
+    def test_defaults_and_stored(self, tmp_path):
+        path = str(tmp_path / "working-hours.json")
+        assert load_working_hours(path) == {"days": [1, 2, 3, 4, 5], "start": "09:00", "end": "17:00"}
+
+        stored = set_working_hours([5, 1, 1, 3], "8:30", "16:00", path)
+        assert stored == {"days": [1, 3, 5], "start": "08:30", "end": "16:00"}
+        assert load_working_hours(path) == stored
+
+    def test_rejects_invalid(self, tmp_path):
+        path = str(tmp_path / "working-hours.json")
+        for days, start, end in [([0], "09:00", "17:00"), ([1], "9", "17:00"), ([1], "17:00", "09:00")]:
+            with pytest.raises(ValueError):
+                set_working_hours(days, start, end, path)
+
+
+class TestOutOfHoursUsage:
+    """Tests for the work/personal split"""
+
+    def test_window_weekends_and_days_off(self):
+        hours = {"days": [1, 2, 3, 4, 5], "start": "09:00", "end": "17:00"}
+        # 2025-03-03 is a Monday, 2025-03-08 a Saturday
+        assert is_work_time("2025-03-03", "09:00", hours, set())
+        assert not is_work_time("2025-03-03", "17:00", hours, set())
+        assert not is_work_time("2025-03-08", "10:00", hours, set())
+        assert not is_work_time("2025-03-04", "10:00", hours, {"2025-03-04"})
+
+    def test_split(self):
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        insert_message_entries(conn, [
+            _entry("a", "2025-03-03T10:15:00", cost=3.0),
+            _entry("b", "2025-03-03T21:40:00"),
+            _entry("c", "2025-03-08T11:00:00", session_id="s2"),
+        ])
+        hours = {"days": [1, 2, 3, 4, 5], "start": "09:00", "end": "17:00"}
+
+        result = query_out_of_hours_usage(conn, "2025-03-01", "2025-03-31", hours, set())
+        assert result["work"] == {"messages": 1, "tokens": 100, "cost": 3.0, "sessions": 1}
+        assert result["personal"] == {"messages": 2, "tokens": 200, "cost": 2.0, "sessions": 2}
+        assert result["work_share"] == 60.0
+        assert [day["date"] for day in result["days"]] == ["2025-03-03", "2025-03-08"]
+
+        on_vacation = query_out_of_hours_usage(conn, "2025-03-01", "2025-03-31", hours, {"2025-03-03"})
+        assert on_vacation["work"]["messages"] == 0
+        assert on_vacation["work_share"] == 0.0
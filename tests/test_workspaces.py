@@ -0,0 +1,71 @@
+"""
+Unit tests for workspaces module and workspace-scoped dashboard queries
+"""
+import sqlite3
+
+import pytest
+
+from command_center.database.queries import query_totals
+from command_center.database.schema import init_database
+from command_center.utils.project_metadata import load_projects_json, save_projects_json
+from command_center.workspaces import (
+    assign_project_to_workspace,
+    create_workspace,
+    delete_workspace,
+    list_workspaces,
+    workspace_project_ids,
+)
+
+
+class TestWorkspaces:
+    """Tests for workspace creation and project assignment"""
+
+    def test_assign_list_and_delete(self, tmp_path):
+        """Projects join one workspace at a time; deleting a workspace unassigns them"""
+        json_path = str(tmp_path / "workspaces.json")
+        projects_path = str(tmp_path / "projects.json")
+        save_projects_json({"p1": {"name": "A"}, "p2": {"name": "B"}, "p3": {"name": "C"}}, projects_path)
+        paths = {"json_path": json_path, "projects_json_path": projects_path}
+
+        client = create_workspace("Client X", json_path=json_path)["workspace_id"]
+        internal = create_workspace("Internal", json_path=json_path)["workspace_id"]
+        with pytest.raises(ValueError):
+            create_workspace("client x", json_path=json_path)
+
+        assign_project_to_workspace("p1", client, **paths)
+        assign_project_to_workspace("p2", client, **paths)
+        moved = assign_project_to_workspace("p2", internal, **paths)
+        assert moved["previous_workspace_id"] == client
+        with pytest.raises(ValueError):
+            assign_project_to_workspace("p3", "missing", **paths)
+
+        assert [(w["name"], w["projects"]) for w in list_workspaces(**paths)] == [
+            ("Client X", ["p1"]), ("Internal", ["p2"]),
+        ]
+        assert workspace_project_ids(client, **paths) == ["p1"]
+
+        assert delete_workspace(client, **paths)["unassigned_projects"] == 1
+        assert "workspace_id" not in load_projects_json(projects_path)["p1"]
+        with pytest.raises(ValueError):
+            workspace_project_ids(client, **paths)
+
+
+class TestProjectScope:
+    """Dashboard queries accept a list of project IDs"""
+
+    def test_totals_for_project_list(self):
+        """A list filter sums its projects; a single ID still works"""
+        conn = sqlite3.connect(":memory:")
+        init_database(conn)
+        for index, project_id in enumerate(["p1", "p2", "p3"]):
+            conn.execute("""
+                INSERT INTO message_entries
+                (entry_hash, timestamp, timestamp_local, year, date, session_id, source_file, project_id,
+                 total_tokens, cost_usd)
+                VALUES (?, '2025-03-01T10:00:00Z', '2025-03-01T11:00:00+01:00', 2025, '2025-03-01', ?, 'f', ?,
+                        100, 1.0)
+            """, (f"h{index}", f"s{index}", project_id))
+        conn.commit()
+
+        assert query_totals(conn, "2025-03-01", "2025-03-31", ["p1", "p2"])["sessions"] == 2
+        assert query_totals(conn, "2025-03-01", "2025-03-31", "p3")["sessions"] == 1